@@ -128,4 +128,10 @@ mp.add_key_binding("F3", function() vol(150) end)
 mp.add_key_binding("F4", function() vol(200) end)
 mp.add_key_binding("F5", function() vol(300) end)
 
+-- Sent by the plugin manager when the script is disabled
+mp.register_script_message("framex-unload", function()
+    overlay:remove()
+    mp.keep_running = false
+end)
+
 mp.msg.info("FrameX v6 Loaded")
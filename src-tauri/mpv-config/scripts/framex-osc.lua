@@ -409,4 +409,10 @@ mp.register_event("file-loaded", function()
     mp.add_timeout(3, hide_osc)
 end)
 
+-- Sent by the plugin manager when the script is disabled
+mp.register_script_message("framex-unload", function()
+    mp.set_osd_ass(0, 0, "")
+    mp.keep_running = false
+end)
+
 msg.info("FrameX Custom OSC loaded")
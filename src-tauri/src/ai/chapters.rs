@@ -0,0 +1,221 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+
+use crate::mpv_handler::{MpvCommand, MpvState};
+use crate::subtitle_format::Cue;
+
+// A scene change this close to a pause in speech counts as one stronger break
+const BREAK_MATCH_WINDOW: f64 = 5.0;
+const MAX_TITLE_LEN: usize = 48;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Chapter {
+    pub title: String,
+    pub start: f64,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct ChapterOptions {
+    pub scene_threshold: Option<f64>, // ffmpeg scene score, 0.0 - 1.0
+    pub min_chapter_length: Option<f64>, // seconds
+    pub min_speech_gap: Option<f64>,  // silence between cues treated as a topic break
+    pub load_into_player: bool,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ChapterResult {
+    pub path: String,
+    pub chapters: Vec<Chapter>,
+}
+
+struct Candidate {
+    time: f64,
+    score: u32,
+}
+
+/// Chapters are stored next to the video as FFMETADATA, which mpv reads via `chapters-file`.
+pub fn chapters_sidecar_path(video_path: &Path) -> PathBuf {
+    video_path.with_extension("chapters.txt")
+}
+
+/// Timestamps of scene cuts, from ffmpeg's `select` filter on a downscaled stream.
+fn detect_scene_changes(video_path: &Path, threshold: f64) -> Result<Vec<f64>, String> {
+    let output = crate::ffmpeg::hidden("ffmpeg")
+        .arg("-hide_banner")
+        .arg("-i").arg(video_path)
+        .arg("-an").arg("-sn")
+        .arg("-vf").arg(format!("scale=320:-2,select='gt(scene,{})',showinfo", threshold))
+        .arg("-f").arg("null")
+        .arg("-")
+        .output()
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!("Scene detection failed: {}", String::from_utf8_lossy(&output.stderr)));
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let times = stderr
+        .lines()
+        .filter(|l| l.contains("Parsed_showinfo"))
+        .filter_map(|l| l.split("pts_time:").nth(1))
+        .filter_map(|rest| rest.split_whitespace().next())
+        .filter_map(|t| t.parse::<f64>().ok())
+        .collect();
+    Ok(times)
+}
+
+/// Start times of cues that follow a long pause in speech.
+fn transcript_breaks(cues: &[Cue], min_gap: f64) -> Vec<f64> {
+    cues.windows(2)
+        .filter(|w| w[1].start - w[0].end >= min_gap)
+        .map(|w| w[1].start)
+        .collect()
+}
+
+/// Score every candidate break; scene cuts that coincide with a speech pause are preferred.
+fn merge_candidates(scenes: &[f64], speech: &[f64]) -> Vec<Candidate> {
+    let near = |t: f64, list: &[f64]| list.iter().any(|o| (o - t).abs() <= BREAK_MATCH_WINDOW);
+
+    let mut candidates: Vec<Candidate> = speech
+        .iter()
+        .map(|&t| Candidate { time: t, score: if near(t, scenes) { 3 } else { 2 } })
+        .chain(scenes.iter().filter(|&&t| !near(t, speech)).map(|&t| Candidate { time: t, score: 1 }))
+        .collect();
+    candidates.sort_by(|a, b| a.time.total_cmp(&b.time));
+    candidates
+}
+
+/// Greedily pick the strongest break in each window so chapters are at least `min_len` apart.
+fn select_breaks(candidates: &[Candidate], min_len: f64, duration: f64) -> Vec<f64> {
+    let mut breaks = vec![0.0];
+    let mut last = 0.0;
+    loop {
+        let window: Vec<&Candidate> = candidates
+            .iter()
+            .filter(|c| c.time >= last + min_len && c.time < last + 2.0 * min_len)
+            .collect();
+        let next = match window.iter().max_by(|a, b| a.score.cmp(&b.score).then(b.time.total_cmp(&a.time))) {
+            Some(best) => best.time,
+            None => match candidates.iter().find(|c| c.time >= last + min_len) {
+                Some(c) => c.time,
+                None => break,
+            },
+        };
+        // Skip a tiny trailing chapter
+        if duration > 0.0 && duration - next < min_len / 2.0 {
+            break;
+        }
+        breaks.push(next);
+        last = next;
+    }
+    breaks
+}
+
+/// Title from the first words spoken at the chapter start.
+fn chapter_title(cues: &[Cue], start: f64, number: usize) -> String {
+    let text = cues
+        .iter()
+        .find(|c| c.end > start)
+        .map(|c| c.text.split_whitespace().collect::<Vec<_>>().join(" "))
+        .unwrap_or_default();
+    if text.is_empty() {
+        return format!("Chapter {}", number);
+    }
+
+    let mut title = String::new();
+    for word in text.split(' ') {
+        if title.len() + word.len() + 1 > MAX_TITLE_LEN {
+            title.push('…');
+            break;
+        }
+        if !title.is_empty() {
+            title.push(' ');
+        }
+        title.push_str(word);
+    }
+    title
+}
+
+fn escape_ffmetadata(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn write_ffmetadata(chapters: &[Chapter], duration: f64, path: &Path) -> Result<(), String> {
+    let mut out = String::from(";FFMETADATA1\n");
+    for (i, chapter) in chapters.iter().enumerate() {
+        let end = chapters.get(i + 1).map(|c| c.start).unwrap_or(duration.max(chapter.start));
+        out.push_str(&format!(
+            "\n[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={}\n",
+            (chapter.start * 1000.0).round() as u64,
+            (end * 1000.0).round() as u64,
+            escape_ffmetadata(&chapter.title)
+        ));
+    }
+    fs::write(path, out).map_err(|e| format!("Failed to write chapters: {}", e))
+}
+
+/// Build chapter markers for an unchaptered video from scene cuts and pauses in its transcript.
+#[tauri::command]
+pub async fn generate_chapters(
+    app: AppHandle,
+    state: State<'_, MpvState>,
+    video_path: String,
+    options: Option<ChapterOptions>,
+) -> Result<ChapterResult, String> {
+    let options = options.unwrap_or_default();
+    let path = PathBuf::from(&video_path);
+    if !path.exists() {
+        return Err("Video file does not exist".to_string());
+    }
+
+    let threshold = options.scene_threshold.unwrap_or(0.4).clamp(0.05, 1.0);
+    let min_len = options.min_chapter_length.unwrap_or(120.0).max(10.0);
+    let min_gap = options.min_speech_gap.unwrap_or(3.0).max(0.5);
+
+    // Transcript is optional; without one chapters come from scene cuts only
+    let cues = crate::ai::transcript::load_transcript(&app, &video_path)
+        .map(|t| t.cues)
+        .unwrap_or_default();
+
+    let worker_path = path.clone();
+    let worker_cues = cues.clone();
+    let (chapters, duration) = tauri::async_runtime::spawn_blocking(move || -> Result<(Vec<Chapter>, f64), String> {
+        let duration = crate::file_scanner::get_video_duration(worker_path.to_string_lossy().to_string()).unwrap_or(0.0);
+        let scenes = detect_scene_changes(&worker_path, threshold)?;
+        let speech = transcript_breaks(&worker_cues, min_gap);
+        tracing::info!("Chapters: {} scene cuts, {} speech breaks", scenes.len(), speech.len());
+
+        let candidates = merge_candidates(&scenes, &speech);
+        let chapters = select_breaks(&candidates, min_len, duration)
+            .into_iter()
+            .enumerate()
+            .map(|(i, start)| Chapter { title: chapter_title(&worker_cues, start, i + 1), start })
+            .collect();
+        Ok((chapters, duration))
+    })
+    .await
+    .map_err(|e| format!("Chapter generation failed: {}", e))??;
+
+    let sidecar = chapters_sidecar_path(&path);
+    write_ffmetadata(&chapters, duration, &sidecar)?;
+    let sidecar = sidecar.to_string_lossy().to_string();
+
+    if options.load_into_player {
+        if let Some(tx) = state.tx.lock().unwrap().as_ref() {
+            let _ = tx.send(MpvCommand::LoadChapters(sidecar.clone()));
+        }
+    }
+
+    Ok(ChapterResult { path: sidecar, chapters })
+}
@@ -1,2 +1,5 @@
+pub mod chapters;
+pub mod summary;
+pub mod transcript;
+pub mod translate;
 pub mod whisper;
-
@@ -0,0 +1,152 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::subtitle_format::{self, Cue};
+
+// Keep the prompt within the context window of small local models
+const MAX_TRANSCRIPT_CHARS: usize = 24_000;
+const MAX_KEY_MOMENTS: usize = 10;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct KeyMoment {
+    pub time: f64,
+    pub title: String,
+}
+
+/// Cached next to the video as `<stem>.summary.json`, like the poster.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct VideoSummary {
+    pub summary: String,
+    pub key_moments: Vec<KeyMoment>,
+    pub model: String,
+    pub generated_at: u64,
+}
+
+#[derive(Deserialize)]
+struct OllamaResponse {
+    response: String,
+}
+
+#[derive(Deserialize)]
+struct LlmSummary {
+    summary: String,
+    #[serde(default)]
+    key_moments: Vec<LlmMoment>,
+}
+
+#[derive(Deserialize)]
+struct LlmMoment {
+    time: serde_json::Value, // models return either seconds or "HH:MM:SS"
+    title: String,
+}
+
+fn summary_path(video_path: &Path) -> PathBuf {
+    video_path.with_extension("summary.json")
+}
+
+fn format_time(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    format!("{:02}:{:02}:{:02}", total / 3600, (total % 3600) / 60, total % 60)
+}
+
+/// "[HH:MM:SS] text" lines, thinned evenly if the whole transcript is too long.
+fn transcript_text(cues: &[Cue]) -> String {
+    let lines: Vec<String> = cues
+        .iter()
+        .map(|c| format!("[{}] {}", format_time(c.start), c.text.replace('\n', " ")))
+        .collect();
+    let total: usize = lines.iter().map(|l| l.len() + 1).sum();
+    let step = total.div_ceil(MAX_TRANSCRIPT_CHARS).max(1);
+    lines.into_iter().step_by(step).collect::<Vec<_>>().join("\n")
+}
+
+fn moment_time(value: &serde_json::Value) -> Option<f64> {
+    match value {
+        serde_json::Value::Number(n) => n.as_f64(),
+        serde_json::Value::String(s) => s.parse::<f64>().ok().or_else(|| subtitle_format::parse_timestamp(s)),
+        _ => None,
+    }
+}
+
+#[tauri::command]
+pub fn get_video_summary(video_path: String) -> Option<VideoSummary> {
+    let content = fs::read_to_string(summary_path(Path::new(&video_path))).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Summarize a video's transcript with the configured local LLM and list its key moments.
+#[tauri::command]
+pub async fn summarize_video(app: AppHandle, video_path: String, force: Option<bool>) -> Result<VideoSummary, String> {
+    if !force.unwrap_or(false) {
+        if let Some(cached) = get_video_summary(video_path.clone()) {
+            return Ok(cached);
+        }
+    }
+
+    let transcript = crate::ai::transcript::load_transcript(&app, &video_path)
+        .ok_or("No transcript for this video. Generate subtitles first.")?;
+    if transcript.cues.is_empty() {
+        return Err("Transcript is empty".to_string());
+    }
+    let duration = transcript.cues.last().map(|c| c.end).unwrap_or(0.0);
+
+    let settings = crate::config::load_config(&app).ai;
+    let prompt = format!(
+        "Below is a timestamped transcript of a video. Respond with JSON of the form \
+         {{\"summary\": \"...\", \"key_moments\": [{{\"time\": \"HH:MM:SS\", \"title\": \"...\"}}]}}. \
+         The summary is 2-4 sentences. List up to {} key moments in order, using timestamps from the transcript.\n\n{}",
+        MAX_KEY_MOMENTS,
+        transcript_text(&transcript.cues)
+    );
+
+    tracing::info!("Summarizing {} with {}", video_path, settings.llm_model);
+    let resp: OllamaResponse = reqwest::Client::new()
+        .post(format!("{}/api/generate", settings.llm_endpoint))
+        .json(&serde_json::json!({
+            "model": settings.llm_model,
+            "prompt": prompt,
+            "format": "json",
+            "stream": false
+        }))
+        .send()
+        .await
+        .map_err(|e| format!("LLM request failed (is Ollama running at {}?): {}", settings.llm_endpoint, e))?
+        .error_for_status()
+        .map_err(|e| format!("LLM error: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Parse error: {}", e))?;
+
+    let parsed: LlmSummary = serde_json::from_str(resp.response.trim())
+        .map_err(|e| format!("LLM returned invalid JSON: {}", e))?;
+
+    let mut key_moments: Vec<KeyMoment> = parsed
+        .key_moments
+        .into_iter()
+        .filter_map(|m| {
+            let time = moment_time(&m.time)?;
+            (time >= 0.0 && time <= duration && !m.title.trim().is_empty())
+                .then(|| KeyMoment { time, title: m.title.trim().to_string() })
+        })
+        .collect();
+    key_moments.sort_by(|a, b| a.time.total_cmp(&b.time));
+    key_moments.truncate(MAX_KEY_MOMENTS);
+
+    let summary = VideoSummary {
+        summary: parsed.summary.trim().to_string(),
+        key_moments,
+        model: settings.llm_model,
+        generated_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+    };
+
+    let json = serde_json::to_string_pretty(&summary).map_err(|e| e.to_string())?;
+    if let Err(e) = fs::write(summary_path(Path::new(&video_path)), json) {
+        tracing::error!("Failed to cache summary: {}", e);
+    }
+    Ok(summary)
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{command, AppHandle, Manager};
+
+use crate::subtitle_format::{self, Cue};
+
+/// Timestamped transcript of a video, stored in `app_data/transcripts/<hash>.json`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Transcript {
+    pub video_path: String,
+    pub language: Option<String>,
+    pub source: String,
+    pub cues: Vec<Cue>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TranscriptMatch {
+    pub index: usize,
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+}
+
+fn get_transcript_path(app: &AppHandle, video_path: &str) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?.join("transcripts");
+    Ok(crate::util::hashed_file(&dir, video_path, "json"))
+}
+
+pub fn save_transcript(app: &AppHandle, transcript: &Transcript) -> Result<(), String> {
+    let path = get_transcript_path(app, &transcript.video_path)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string(transcript).map_err(|e| e.to_string())?;
+    fs::write(path, json).map_err(|e| e.to_string())
+}
+
+pub fn load_transcript(app: &AppHandle, video_path: &str) -> Option<Transcript> {
+    let path = get_transcript_path(app, video_path).ok()?;
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Lowercase and collapse whitespace/punctuation so queries match regardless of line breaks.
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '\'' { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parse an existing VTT/SRT file (e.g. a Whisper output) into the video's transcript.
+#[command]
+pub fn index_transcript(app: AppHandle, video_path: String, subtitle_path: String) -> Result<usize, String> {
+    let content = subtitle_format::read_subtitle_file(Path::new(&subtitle_path))?;
+    let cues = subtitle_format::parse_vtt_or_srt(&content);
+    if cues.is_empty() {
+        return Err("No cues found in subtitle file".to_string());
+    }
+
+    // `<stem>.<lang>.<ext>` naming carries the language
+    let language = Path::new(&subtitle_path)
+        .file_stem()
+        .and_then(|s| Path::new(s).extension())
+        .map(|l| l.to_string_lossy().to_string())
+        .filter(|l| l.len() <= 3);
+
+    let count = cues.len();
+    save_transcript(&app, &Transcript { video_path, language, source: subtitle_path, cues })?;
+    Ok(count)
+}
+
+#[command]
+pub fn get_transcript(app: AppHandle, video_path: String) -> Result<Option<Transcript>, String> {
+    Ok(load_transcript(&app, &video_path))
+}
+
+/// Find cues containing every word of `query`, so the player can seek to `start`.
+#[command]
+pub fn search_transcript(app: AppHandle, video_path: String, query: String) -> Result<Vec<TranscriptMatch>, String> {
+    let transcript = load_transcript(&app, &video_path).ok_or("No transcript for this video")?;
+    let query = normalize(&query);
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let matches = transcript
+        .cues
+        .iter()
+        .enumerate()
+        .filter(|(_, cue)| {
+            let text = normalize(&cue.text);
+            query.split(' ').all(|word| text.contains(word))
+        })
+        .map(|(index, cue)| TranscriptMatch {
+            index,
+            start: cue.start,
+            end: cue.end,
+            text: cue.text.clone(),
+        })
+        .collect();
+
+    Ok(matches)
+}
@@ -0,0 +1,207 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+use crate::subtitle_format::{self, Cue, SubtitleFormat};
+
+// Cues per request; keeps prompts short enough for small local models
+const BATCH_SIZE: usize = 20;
+
+/// Where translations come from. Add a variant here to support another service.
+enum TranslationBackend {
+    /// Local model served by Ollama (or any server speaking its /api/generate API)
+    Ollama { endpoint: String, model: String },
+    /// LibreTranslate instance, self-hosted or remote
+    LibreTranslate { url: String, api_key: Option<String> },
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct TranslateOptions {
+    pub backend: Option<String>, // "ollama" | "libretranslate"; defaults to the AI settings
+    pub source_language: Option<String>,
+    pub video_path: Option<String>, // names the output `<video>.<lang>.srt`
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TranslateProgress {
+    pub subtitle_path: String,
+    pub progress: f32,
+}
+
+#[derive(Deserialize)]
+struct OllamaResponse {
+    response: String,
+}
+
+#[derive(Deserialize)]
+struct LibreTranslateResponse {
+    #[serde(rename = "translatedText")]
+    translated_text: Vec<String>,
+}
+
+impl TranslationBackend {
+    fn from_settings(app: &AppHandle, name: Option<&str>) -> Result<Self, String> {
+        let config = crate::config::load_config(app);
+        let name = name.unwrap_or(&config.ai.translation_backend).to_lowercase();
+        match name.as_str() {
+            "ollama" => Ok(Self::Ollama {
+                endpoint: config.ai.llm_endpoint,
+                model: config.ai.llm_model,
+            }),
+            "libretranslate" => Ok(Self::LibreTranslate {
+                url: config.ai.libretranslate_url,
+                api_key: config.credentials.libretranslate_api_key,
+            }),
+            other => Err(format!("Unknown translation backend '{}'", other)),
+        }
+    }
+
+    async fn translate_batch(&self, client: &reqwest::Client, lines: &[String], source: Option<&str>, target: &str) -> Result<Vec<String>, String> {
+        match self {
+            Self::Ollama { endpoint, model } => {
+                let numbered: Vec<String> = lines.iter().enumerate().map(|(i, l)| format!("{}. {}", i + 1, l)).collect();
+                let prompt = format!(
+                    "Translate these numbered subtitle lines {}to the language with code '{}'. \
+                     Reply with only the translated lines, keeping the same numbering, one per line.\n\n{}",
+                    source.map(|s| format!("from '{}' ", s)).unwrap_or_default(),
+                    target,
+                    numbered.join("\n")
+                );
+                let resp: OllamaResponse = client
+                    .post(format!("{}/api/generate", endpoint))
+                    .json(&serde_json::json!({
+                        "model": model,
+                        "prompt": prompt,
+                        "stream": false,
+                        "options": { "temperature": 0 }
+                    }))
+                    .send()
+                    .await
+                    .map_err(|e| format!("Ollama request failed: {}", e))?
+                    .error_for_status()
+                    .map_err(|e| format!("Ollama error: {}", e))?
+                    .json()
+                    .await
+                    .map_err(|e| format!("Parse error: {}", e))?;
+                parse_numbered(&resp.response, lines.len())
+            }
+            Self::LibreTranslate { url, api_key } => {
+                let resp: LibreTranslateResponse = client
+                    .post(format!("{}/translate", url))
+                    .json(&serde_json::json!({
+                        "q": lines,
+                        "source": source.unwrap_or("auto"),
+                        "target": target,
+                        "format": "text",
+                        "api_key": api_key.clone().unwrap_or_default()
+                    }))
+                    .send()
+                    .await
+                    .map_err(|e| format!("LibreTranslate request failed: {}", e))?
+                    .error_for_status()
+                    .map_err(|e| format!("LibreTranslate error: {}", e))?
+                    .json()
+                    .await
+                    .map_err(|e| format!("Parse error: {}", e))?;
+                if resp.translated_text.len() != lines.len() {
+                    return Err("LibreTranslate returned a different number of lines".to_string());
+                }
+                Ok(resp.translated_text)
+            }
+        }
+    }
+}
+
+/// Pull "N. text" lines back out of a model reply, in order.
+fn parse_numbered(reply: &str, expected: usize) -> Result<Vec<String>, String> {
+    let mut out = vec![None; expected];
+    for line in reply.lines() {
+        let Some((num, text)) = line.trim().split_once('.') else {
+            continue;
+        };
+        if let Some(slot) = num.trim().parse::<usize>().ok().and_then(|n| n.checked_sub(1)).and_then(|i| out.get_mut(i)) {
+            *slot = Some(text.trim().to_string());
+        }
+    }
+    // Single lines often come back without the number
+    if expected == 1 && out[0].is_none() && !reply.trim().is_empty() {
+        out[0] = Some(reply.trim().to_string());
+    }
+    out.into_iter()
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| "Model reply was missing lines".to_string())
+}
+
+/// `<video stem>.<lang>.srt`, or the subtitle's own name with its language tag swapped.
+fn output_path(subtitle_path: &Path, video_path: Option<&str>, target: &str) -> PathBuf {
+    let source = video_path.map_or(subtitle_path, Path::new);
+    let dir = source.parent().unwrap_or(Path::new(""));
+    let mut stem = source.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    if video_path.is_none() {
+        // Drop an existing language tag like `.en` or `.fre`, but not `.S01` or `.2020`
+        if let Some((rest, tag)) = stem.rsplit_once('.') {
+            if (2..=3).contains(&tag.len()) && tag.chars().all(|c| c.is_ascii_alphabetic()) && !rest.is_empty() {
+                stem = rest.to_string();
+            }
+        }
+    }
+    dir.join(format!("{}.{}.srt", stem, target))
+}
+
+/// Translate a VTT/SRT file cue by cue, keeping its timing, and save it as SRT.
+#[tauri::command]
+pub async fn translate_subtitles(
+    app: AppHandle,
+    subtitle_path: String,
+    target_language: String,
+    options: Option<TranslateOptions>,
+) -> Result<String, String> {
+    let options = options.unwrap_or_default();
+    let target = target_language.trim().to_lowercase();
+    if target.is_empty() {
+        return Err("Target language is required".to_string());
+    }
+    if !crate::subtitle_downloader::is_valid_language_tag(&target) {
+        return Err(format!("Invalid target language '{}'", target));
+    }
+
+    let content = subtitle_format::read_subtitle_file(Path::new(&subtitle_path))?;
+    let cues = subtitle_format::parse_vtt_or_srt(&content);
+    if cues.is_empty() {
+        return Err("No cues found in subtitle file".to_string());
+    }
+
+    let backend = TranslationBackend::from_settings(&app, options.backend.as_deref())?;
+    let source = options.source_language.as_deref().filter(|s| !s.is_empty() && *s != "auto");
+    let client = reqwest::Client::new();
+
+    let mut translated: Vec<Cue> = Vec::with_capacity(cues.len());
+    for batch in cues.chunks(BATCH_SIZE) {
+        // Models handle one line per cue more reliably; the player re-wraps anyway
+        let lines: Vec<String> = batch.iter().map(|c| c.text.replace('\n', " ")).collect();
+        let texts = match backend.translate_batch(&client, &lines, source, &target).await {
+            Ok(texts) => texts,
+            Err(e) => {
+                // Retry line by line before giving up on the batch
+                tracing::error!("Batch translation failed, retrying per line: {}", e);
+                let mut texts = Vec::with_capacity(lines.len());
+                for line in &lines {
+                    let mut one = backend.translate_batch(&client, std::slice::from_ref(line), source, &target).await?;
+                    texts.push(one.pop().unwrap_or_default());
+                }
+                texts
+            }
+        };
+
+        translated.extend(batch.iter().zip(texts).map(|(cue, text)| Cue { start: cue.start, end: cue.end, text }));
+        let _ = app.emit("translate-progress", TranslateProgress {
+            subtitle_path: subtitle_path.clone(),
+            progress: translated.len() as f32 / cues.len() as f32 * 100.0,
+        });
+    }
+
+    let out = output_path(Path::new(&subtitle_path), options.video_path.as_deref(), &target);
+    subtitle_format::write_subtitles(&translated, SubtitleFormat::Srt, &out)?;
+    Ok(out.to_string_lossy().to_string())
+}
@@ -1,133 +1,609 @@
-use tauri::{AppHandle, Emitter};
-use std::process::Command;
-use std::path::PathBuf;
+use tauri::{AppHandle, Emitter, Manager, State};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+use crate::subtitle_format::{self, Cue, SubtitleFormat};
+
+// ggml models published by the whisper.cpp project
+const MODEL_BASE_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+const KNOWN_MODELS: &[&str] = &[
+    "tiny", "tiny.en", "base", "base.en", "small", "small.en",
+    "medium", "medium.en", "large-v1", "large-v2", "large-v3", "large-v3-turbo",
+];
+
+// tinydiarize fine-tune that marks speaker turns; English only
+const DIARIZE_MODEL: &str = "small.en-tdrz";
+const DIARIZE_MODEL_BASE_URL: &str = "https://huggingface.co/akashmjn/tinydiarize-whisper.cpp/resolve/main";
+
+// Whisper expects 16kHz mono f32 samples
+const SAMPLE_RATE: u32 = 16000;
 
 #[derive(serde::Serialize, Clone)]
 pub struct WhisperProgress {
+    pub job_id: String,
     pub status: String,
     pub progress: f32,
 }
 
+/// A running transcription, shared between the worker and `cancel_whisper`.
+pub struct WhisperJob {
+    id: String,
+    cancelled: AtomicBool,
+    child: Mutex<Option<Child>>,
+    temp_dir: PathBuf,
+}
+
+impl WhisperJob {
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+pub struct WhisperJobs {
+    pub jobs: Mutex<HashMap<String, Arc<WhisperJob>>>,
+}
+
+impl WhisperJobs {
+    pub fn new() -> Self {
+        Self {
+            jobs: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+/// Optional knobs for `run_whisper`; every field falls back to a sensible default.
+#[derive(serde::Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct WhisperOptions {
+    pub model: Option<String>,
+    pub language: Option<String>, // code, or "auto" / None to detect
+    pub task: Option<String>,     // "transcribe" | "translate"
+    pub audio_track: Option<u32>,
+    pub output_format: Option<String>, // "vtt" (default) | "srt" | "ass"
+    pub device: Option<String>,        // "auto" (default) | "cpu" | "gpu"
+    pub gpu_index: Option<i32>,
+    pub threads: Option<u32>,
+    pub diarize: bool, // prefix cues with SPEAKER 1/2 labels when possible
+    pub job_id: Option<String>,
+}
+
+/// Which inference backends this build can use on this machine.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct WhisperBackends {
+    pub compiled_gpu_backend: Option<String>, // "cuda" | "metal" | "vulkan"
+    pub gpu_available: bool,
+    pub gpu_devices: Vec<String>,
+    pub cpu_threads: u32,
+}
+
+/// What to decode and how to label it.
+#[derive(Clone, Debug)]
+struct DecodeSettings {
+    language: Option<String>,
+    task: WhisperTask,
+    diarize: bool,
+}
+
+/// The backend actually used for a run.
+#[derive(Clone, Debug)]
+struct InferenceDevice {
+    use_gpu: bool,
+    gpu_index: i32,
+    threads: u32,
+}
+
+fn compiled_gpu_backend() -> Option<&'static str> {
+    if cfg!(feature = "whisper-cuda") {
+        Some("cuda")
+    } else if cfg!(feature = "whisper-metal") {
+        Some("metal")
+    } else if cfg!(feature = "whisper-vulkan") {
+        Some("vulkan")
+    } else {
+        None
+    }
+}
+
+/// Names of usable GPUs for the compiled backend (empty when none or CPU-only build).
+fn detect_gpu_devices() -> Vec<String> {
+    match compiled_gpu_backend() {
+        Some("cuda") => {
+            // nvidia-smi ships with the driver; if it runs, CUDA is usable
+            crate::ffmpeg::hidden("nvidia-smi").arg("--query-gpu=name").arg("--format=csv,noheader").output()
+                .ok()
+                .filter(|o| o.status.success())
+                .map(|o| String::from_utf8_lossy(&o.stdout).lines().map(|l| l.trim().to_string()).filter(|l| !l.is_empty()).collect())
+                .unwrap_or_default()
+        }
+        Some("metal") if cfg!(target_os = "macos") => vec!["Apple GPU (Metal)".to_string()],
+        #[cfg(feature = "whisper-vulkan")]
+        Some("vulkan") => whisper_rs::vulkan::list_devices().into_iter().map(|d| d.name).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn default_threads() -> u32 {
+    std::thread::available_parallelism().map(|n| n.get() as u32).unwrap_or(4)
+}
+
+fn resolve_device(device: Option<&str>, gpu_index: Option<i32>, threads: Option<u32>) -> Result<InferenceDevice, String> {
+    let threads = threads.filter(|t| *t > 0).unwrap_or_else(default_threads).min(default_threads().max(1));
+    let use_gpu = match device.unwrap_or("auto") {
+        "cpu" => false,
+        "gpu" => {
+            if compiled_gpu_backend().is_none() {
+                return Err("This build of FrameX has no GPU backend for Whisper".to_string());
+            }
+            if detect_gpu_devices().is_empty() {
+                return Err("No compatible GPU found for Whisper".to_string());
+            }
+            true
+        }
+        "auto" => compiled_gpu_backend().is_some() && !detect_gpu_devices().is_empty(),
+        other => return Err(format!("Unknown Whisper device '{}'", other)),
+    };
+    Ok(InferenceDevice { use_gpu, gpu_index: gpu_index.unwrap_or(0), threads })
+}
+
+/// Returned to the frontend once a subtitle file has been written.
+#[derive(serde::Serialize, Clone)]
+pub struct WhisperResult {
+    pub path: String,
+    pub language: String,
+    pub source_language: String,
+    pub task: String,
+    pub backend: String,
+    pub threads: u32,
+    pub diarized: bool,
+}
+
+/// "transcribe" keeps the spoken language, "translate" produces English text.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum WhisperTask {
+    Transcribe,
+    Translate,
+}
+
+impl WhisperTask {
+    fn parse(task: Option<&str>) -> Result<Self, String> {
+        match task.unwrap_or("transcribe") {
+            "transcribe" => Ok(Self::Transcribe),
+            "translate" => Ok(Self::Translate),
+            other => Err(format!("Unknown Whisper task '{}'", other)),
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Transcribe => "transcribe",
+            Self::Translate => "translate",
+        }
+    }
+}
+
+/// An audio stream inside the video, as reported by ffprobe.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct AudioTrack {
+    pub index: u32, // position among audio streams, used with `-map 0:a:N`
+    pub stream_index: u32,
+    pub codec: String,
+    pub channels: u32,
+    pub language: Option<String>,
+    pub title: Option<String>,
+    pub default: bool,
+}
+
+fn emit_progress(app: &AppHandle, job_id: &str, status: &str, progress: f32) {
+    let _ = app.emit("whisper-progress", WhisperProgress {
+        job_id: job_id.to_string(),
+        status: status.to_string(),
+        progress,
+    });
+}
+
+fn models_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("whisper_models");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create models dir: {}", e))?;
+    Ok(dir)
+}
+
+/// Return the local path of a ggml model, downloading it on first use.
+async fn ensure_model(app: &AppHandle, job_id: &str, model_name: &str) -> Result<PathBuf, String> {
+    let base_url = if model_name == DIARIZE_MODEL {
+        DIARIZE_MODEL_BASE_URL
+    } else if KNOWN_MODELS.contains(&model_name) {
+        MODEL_BASE_URL
+    } else {
+        return Err(format!("Unknown Whisper model '{}'", model_name));
+    };
+
+    let model_path = models_dir(app)?.join(format!("ggml-{}.bin", model_name));
+    if model_path.exists() {
+        return Ok(model_path);
+    }
+
+    let url = format!("{}/ggml-{}.bin", base_url, model_name);
+    tracing::info!("Downloading Whisper model: {}", url);
+    emit_progress(app, job_id, &format!("Downloading '{}' model...", model_name), 0.0);
+
+    let mut resp = reqwest::Client::new()
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Model download failed: {}", e))?;
+    if !resp.status().is_success() {
+        return Err(format!("Model download failed: {}", resp.status()));
+    }
+
+    // Download to a .part file so an interrupted download is never mistaken for a model
+    let part_path = model_path.with_extension("part");
+    let mut file = fs::File::create(&part_path).map_err(|e| e.to_string())?;
+    let total = resp.content_length().unwrap_or(0);
+    let mut received: u64 = 0;
+    let mut last_reported = 0;
+
+    while let Some(chunk) = resp.chunk().await.map_err(|e| format!("Model download failed: {}", e))? {
+        file.write_all(&chunk).map_err(|e| e.to_string())?;
+        received += chunk.len() as u64;
+        if let Some(percent) = (received * 100).checked_div(total).map(|p| p as u32) {
+            if percent != last_reported {
+                last_reported = percent;
+                emit_progress(app, job_id, &format!("Downloading '{}' model... {}%", model_name, percent), percent as f32 * 0.05);
+            }
+        }
+    }
+    drop(file);
+
+    fs::rename(&part_path, &model_path).map_err(|e| format!("Failed to save model: {}", e))?;
+    Ok(model_path)
+}
+
+/// Decode one audio track of the video into a 16kHz mono WAV using ffmpeg,
+/// parsing its progress output from stderr to report extraction percent.
+fn extract_audio(app: &AppHandle, job: &WhisperJob, video_path: &Path, audio_track: u32, wav_path: &Path) -> Result<(), String> {
+    let duration = crate::file_scanner::get_video_duration(video_path.to_string_lossy().to_string()).unwrap_or(0.0);
+
+    let mut child = crate::ffmpeg::command()
+        .arg("-i").arg(video_path)
+        .arg("-map").arg(format!("0:a:{}", audio_track))
+        .arg("-vn")
+        .arg("-ac").arg("1")
+        .arg("-ar").arg(SAMPLE_RATE.to_string())
+        .arg("-acodec").arg("pcm_s16le")
+        .arg("-f").arg("wav")
+        .arg(wav_path)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("FFmpeg error: {}. Make sure ffmpeg is installed and in PATH.", e))?;
+
+    let stderr = child.stderr.take();
+    *job.child.lock().unwrap() = Some(child);
+
+    // Non-progress lines are ffmpeg errors; keep them for the failure message
+    let mut errors = Vec::new();
+    if let Some(stderr) = stderr {
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+            if let Some(us) = line.strip_prefix("out_time_us=") {
+                let secs = us.trim().parse::<f64>().unwrap_or(0.0) / 1_000_000.0;
+                if duration > 0.0 {
+                    let percent = (secs / duration * 100.0).clamp(0.0, 100.0);
+                    emit_progress(app, &job.id, &format!("Extracting audio... {:.0}%", percent), 5.0 + percent as f32 * 0.05);
+                }
+            } else if !line.contains('=') {
+                errors.push(line);
+            }
+        }
+    }
+
+    let status = job.child.lock().unwrap().take().map(|mut c| c.wait());
+    if job.is_cancelled() {
+        return Err("Transcription cancelled".to_string());
+    }
+    match status {
+        Some(Ok(s)) if s.success() => Ok(()),
+        Some(Err(e)) => Err(format!("Audio extraction failed: {}", e)),
+        _ => Err(format!("Audio extraction failed: {}", errors.join("\n"))),
+    }
+}
+
+/// Read the 16-bit PCM samples out of a WAV file written by ffmpeg.
+fn load_wav(wav_path: &Path) -> Result<Vec<f32>, String> {
+    let bytes = fs::read(wav_path).map_err(|e| format!("Failed to read audio: {}", e))?;
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err("Extracted audio is not a valid WAV file".to_string());
+    }
+
+    // Walk the RIFF chunks to find "data"
+    let mut pos = 12;
+    while pos + 8 <= bytes.len() {
+        let id = &bytes[pos..pos + 4];
+        let size = u32::from_le_bytes([bytes[pos + 4], bytes[pos + 5], bytes[pos + 6], bytes[pos + 7]]) as usize;
+        let body = pos + 8;
+        if id == b"data" {
+            let end = (body + size).min(bytes.len());
+            return Ok(bytes[body..end]
+                .chunks_exact(2)
+                .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0)
+                .collect());
+        }
+        // Chunks are word-aligned
+        pos = body + size + (size & 1);
+    }
+    Err("Extracted audio has no data chunk".to_string())
+}
+
+/// Run whisper.cpp inference in-process, reporting progress from its callback.
+/// `language: None` lets Whisper detect the spoken language; the resolved code is returned.
+fn transcribe(app: &AppHandle, job: &Arc<WhisperJob>, model_path: &Path, samples: &[f32], settings: &DecodeSettings, device: &InferenceDevice) -> Result<(Vec<Cue>, String), String> {
+    let model_str = model_path.to_str().ok_or("Invalid model path")?;
+    let mut ctx_params = WhisperContextParameters::default();
+    ctx_params.use_gpu(device.use_gpu).gpu_device(device.gpu_index);
+    let ctx = WhisperContext::new_with_params(model_str, ctx_params)
+        .map_err(|e| format!("Failed to load Whisper model: {}", e))?;
+    let mut state = ctx.create_state().map_err(|e| format!("Failed to create Whisper state: {}", e))?;
+
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    let language = settings.language.as_deref();
+    params.set_language(language);
+    params.set_translate(settings.task == WhisperTask::Translate);
+    params.set_tdrz_enable(settings.diarize);
+    params.set_n_threads(device.threads as i32);
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_special(false);
+    params.set_print_timestamps(false);
+
+    let progress_app = app.clone();
+    let progress_job = Arc::clone(job);
+    params.set_progress_callback_safe(move |percent: i32| {
+        emit_progress(&progress_app, &progress_job.id, &format!("Transcribing... {}%", percent), 10.0 + percent as f32 * 0.9);
+    });
+
+    // whisper.cpp polls this between decoder steps
+    let abort_job = Arc::clone(job);
+    params.set_abort_callback_safe(move || abort_job.is_cancelled());
+
+    let result = state.full(params, samples);
+    if job.is_cancelled() {
+        return Err("Transcription cancelled".to_string());
+    }
+    result.map_err(|e| format!("Whisper failed: {}", e))?;
+
+    let mut cues = Vec::new();
+    // tinydiarize only flags turns, so alternate between two speakers
+    let mut speaker = 1;
+    for seg in state.as_iter() {
+        let text = seg.to_str_lossy().map(|t| t.trim().to_string()).unwrap_or_default();
+        if !text.is_empty() {
+            cues.push(Cue {
+                // whisper.cpp timestamps are in centiseconds
+                start: seg.start_timestamp() as f64 / 100.0,
+                end: seg.end_timestamp() as f64 / 100.0,
+                text: if settings.diarize { format!("SPEAKER {}: {}", speaker, text) } else { text },
+            });
+        }
+        if settings.diarize && seg.next_segment_speaker_turn() {
+            speaker = if speaker == 1 { 2 } else { 1 };
+        }
+    }
+
+    let detected = whisper_rs::get_lang_str(state.full_lang_id_from_state())
+        .map(|l| l.to_string())
+        .or_else(|| language.map(|l| l.to_string()))
+        .unwrap_or_else(|| "und".to_string());
+    Ok((cues, detected))
+}
+
+/// Normalize the requested language: `None`/"auto" means detect, otherwise it must be a Whisper code.
+fn resolve_language(model_name: &str, language: Option<String>) -> Result<Option<String>, String> {
+    // English-only models can't detect or transcribe anything else
+    if model_name.ends_with(".en") {
+        return Ok(Some("en".to_string()));
+    }
+    match language.map(|l| l.trim().to_lowercase()) {
+        None => Ok(None),
+        Some(l) if l.is_empty() || l == "auto" => Ok(None),
+        Some(l) if whisper_rs::get_lang_id(&l).is_some() => Ok(Some(l)),
+        Some(l) => Err(format!("Unsupported Whisper language '{}'", l)),
+    }
+}
+
 #[tauri::command]
 pub async fn run_whisper(
     app: AppHandle,
+    jobs: State<'_, WhisperJobs>,
     video_path: String,
-    model: Option<String>,
-    _language: Option<String>,
-) -> Result<String, String> {
+    options: Option<WhisperOptions>,
+) -> Result<WhisperResult, String> {
+    let options = options.unwrap_or_default();
     let video_path = PathBuf::from(&video_path);
-    
+
     if !video_path.exists() {
         return Err("Video file does not exist".to_string());
     }
 
-    // Output .vtt file will be saved next to the video
-    let output_path = video_path.with_extension("vtt");
-    
-    // Default to base model and force English as requested
-    let model_name = model.unwrap_or_else(|| "base".to_string());
-    // Force English
-    let lang = "en".to_string(); 
+    // Default to base model with language auto-detection
+    let model_name = options.model.unwrap_or_else(|| "base".to_string());
+    let lang = resolve_language(&model_name, options.language)?;
+    let task = WhisperTask::parse(options.task.as_deref())?;
+    let audio_track = options.audio_track.unwrap_or(0);
+    let output_format = SubtitleFormat::parse(options.output_format.as_deref().unwrap_or("vtt"))?;
+    let device = resolve_device(options.device.as_deref(), options.gpu_index, options.threads)?;
+    let backend = if device.use_gpu { compiled_gpu_backend().unwrap_or("cpu") } else { "cpu" }.to_string();
+    tracing::info!("Whisper backend: {} ({} threads)", backend, device.threads);
+    let lang_label = match task {
+        WhisperTask::Translate => "translating to English".to_string(),
+        WhisperTask::Transcribe => lang.clone().unwrap_or_else(|| "auto-detect".to_string()),
+    };
 
-    // Emit progress event
-    let _ = app.emit("whisper-progress", WhisperProgress {
-        status: "Starting transcription (English)...".to_string(),
-        progress: 0.0,
+    // Use system temp directory for intermediate audio to avoid path/permission issues with OneDrive/spaces
+    let job_id = options.job_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let temp_output_dir = std::env::temp_dir().join(format!("framex_whisper_{}", job_id));
+    let job = Arc::new(WhisperJob {
+        id: job_id.clone(),
+        cancelled: AtomicBool::new(false),
+        child: Mutex::new(None),
+        temp_dir: temp_output_dir.clone(),
     });
+    jobs.jobs.lock().map_err(|e| e.to_string())?.insert(job_id.clone(), Arc::clone(&job));
 
-    // Run whisper command
-    // User needs to have `whisper` in PATH or we can configure a specific path
-    // Use system temp directory for Whisper output to avoid path/permission issues with OneDrive/spaces
-    let temp_dir = std::env::temp_dir();
-    let unique_id = uuid::Uuid::new_v4().to_string(); // Use uuid to be safe if multiple run
-    let temp_output_dir = temp_dir.join(format!("framex_whisper_{}", unique_id));
-    std::fs::create_dir_all(&temp_output_dir).map_err(|e| format!("Failed to create temp dir: {}", e))?;
-    
-    // Canonicalize temp dir to ensure Whisper gets a clean absolute path
-    let temp_output_dir = temp_output_dir.canonicalize().unwrap_or(temp_output_dir);
-
-    let mut cmd = Command::new("whisper");
-    cmd.arg(&video_path)
-        .arg("--model")
-        .arg(&model_name)
-        .arg("--output_format")
-        .arg("vtt")
-        .arg("--output_dir")
-        .arg(&temp_output_dir)
-        .arg("--language")
-        .arg(&lang)
-        .arg("--verbose")
-        .arg("True");
-
-    // Debug: Print command
-    println!("Running Whisper command: {:?}", cmd);
-
-    // Create the command with creation_flags to hide window on Windows if needed (Optional, but good for UX)
-    // For now, standard spawn
-    let output = cmd.output().map_err(|e| {
-        format!("Failed to execute whisper: {}. Make sure whisper is installed and in PATH.", e)
-    })?;
-
-    println!("Whisper Output Status: {}", output.status);
-    // println!("Whisper Stdout: {}", String::from_utf8_lossy(&output.stdout)); // Too noisy?
-    println!("Whisper Stderr: {}", String::from_utf8_lossy(&output.stderr));
-
-    if output.status.success() {
-        // Find the generated .vtt file in the temp dir
-        let mut found_temp_file = None;
-        println!("Scanning temp dir: {:?}", temp_output_dir);
-        
-        if let Ok(entries) = std::fs::read_dir(&temp_output_dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                println!("Found file in temp: {:?}", path);
-                if path.extension().map_or(false, |ext| ext == "vtt") {
-                    found_temp_file = Some(path);
-                    // Don't break immediately, let's see all files for debug if needed, 
-                    // but functionally we take the first VTT
-                    // break; 
-                }
+    emit_progress(&app, &job_id, &format!("Starting transcription ({})...", lang_label), 0.0);
+
+    // Speaker turns need the English tinydiarize model; anything else falls back to plain cues
+    let mut diarize = options.diarize;
+    if diarize && (task == WhisperTask::Translate || lang.as_deref().is_some_and(|l| l != "en")) {
+        tracing::info!("Speaker labels require English transcription, continuing without them");
+        diarize = false;
+    }
+    let model = if diarize {
+        match ensure_model(&app, &job_id, DIARIZE_MODEL).await {
+            Ok(path) => Ok(path),
+            Err(e) => {
+                tracing::warn!("Diarization model unavailable: {}", e);
+                emit_progress(&app, &job_id, "Speaker labels unavailable, continuing without them...", 0.0);
+                diarize = false;
+                ensure_model(&app, &job_id, &model_name).await
             }
         }
-        
-        // Recover output path from loop
-        // We need to re-scan or just take the one referencing found_temp_file
-        // Ideally there's only one.
-        
-        if let Some(temp_file_path) = found_temp_file {
-             // ... existing copy logic ...
-             println!("Found temp file: {}", temp_file_path.display());
-             // Move/Copy to final destination
-             // We rename if possible, otherwise copy and delete
-             // Trying rename first
-             // Note: rename might fail across filesystems (unlikely here but possible), so we fallback to copy
-             if std::fs::rename(&temp_file_path, &output_path).is_err() {
-                  std::fs::copy(&temp_file_path, &output_path).map_err(|e| format!("Failed to copy file to destination: {}", e))?;
-                  let _ = std::fs::remove_file(&temp_file_path);
-             }
-             
-             // Clean up temp dir
-             let _ = std::fs::remove_dir_all(&temp_output_dir);
- 
-             let _ = app.emit("whisper-progress", WhisperProgress {
-                 status: "Transcription complete!".to_string(),
-                 progress: 100.0,
-             });
-             Ok(output_path.to_string_lossy().to_string())
-        } else {
-             // Debugging: List what WAS there before deleting
-             let files_str = if let Ok(entries) = std::fs::read_dir(&temp_output_dir) {
-                 entries.filter_map(|e| e.ok()).map(|e| e.path().display().to_string()).collect::<Vec<_>>().join(", ")
-             } else {
-                 "Could not read dir".to_string()
-             };
-             
-             let _ = std::fs::remove_dir_all(&temp_output_dir); // Cleanup
-             Err(format!("Whisper finished but no .vtt file found in {}. Files present: [{}]", temp_output_dir.display(), files_str))
-        }
     } else {
-        let _ = std::fs::remove_dir_all(&temp_output_dir); // Cleanup
-        let error = String::from_utf8_lossy(&output.stderr);
-        Err(format!("Whisper failed: {}", error))
+        ensure_model(&app, &job_id, &model_name).await
+    };
+    let settings = DecodeSettings {
+        language: if diarize { Some("en".to_string()) } else { lang },
+        task,
+        diarize,
+    };
+
+    let result = match model {
+        Ok(model_path) if !job.is_cancelled() => {
+            let worker_app = app.clone();
+            let worker_job = Arc::clone(&job);
+            let worker_video = video_path.clone();
+            let worker_device = device.clone();
+            tauri::async_runtime::spawn_blocking(move || -> Result<(Vec<Cue>, String), String> {
+                fs::create_dir_all(&worker_job.temp_dir).map_err(|e| format!("Failed to create temp dir: {}", e))?;
+
+                emit_progress(&worker_app, &worker_job.id, "Extracting audio...", 5.0);
+                let wav_path = worker_job.temp_dir.join("audio.wav");
+                extract_audio(&worker_app, &worker_job, &worker_video, audio_track, &wav_path)?;
+                let samples = load_wav(&wav_path)?;
+                if samples.is_empty() {
+                    return Err("No audio found in video".to_string());
+                }
+
+                emit_progress(&worker_app, &worker_job.id, "Transcribing...", 10.0);
+                transcribe(&worker_app, &worker_job, &model_path, &samples, &settings, &worker_device)
+            })
+            .await
+            .map_err(|e| format!("Transcription task failed: {}", e))
+            .and_then(|r| r)
+        }
+        Ok(_) => Err("Transcription cancelled".to_string()),
+        Err(e) => Err(e),
+    };
+
+    // Clean up temp dir and job registration
+    let _ = fs::remove_dir_all(&temp_output_dir);
+    if let Ok(mut map) = jobs.jobs.lock() {
+        map.remove(&job_id);
     }
+
+    let (cues, detected_lang) = result?;
+    let output_lang = match task {
+        WhisperTask::Translate => "en".to_string(),
+        WhisperTask::Transcribe => detected_lang.clone(),
+    };
+
+    // Saved next to the video as `<stem>.<lang>.<ext>` so players pick up the language
+    let output_path = video_path.with_extension(format!("{}.{}", output_lang, output_format.extension()));
+    subtitle_format::write_subtitles(&cues, output_format, &output_path)?;
+
+    // Index the cues so the transcript is searchable from the player
+    let transcript = crate::ai::transcript::Transcript {
+        video_path: video_path.to_string_lossy().to_string(),
+        language: Some(output_lang.clone()),
+        source: output_path.to_string_lossy().to_string(),
+        cues,
+    };
+    if let Err(e) = crate::ai::transcript::save_transcript(&app, &transcript) {
+        tracing::error!("Failed to save transcript: {}", e);
+    }
+
+    emit_progress(&app, &job_id, "Transcription complete!", 100.0);
+    Ok(WhisperResult {
+        path: output_path.to_string_lossy().to_string(),
+        language: output_lang,
+        source_language: detected_lang,
+        task: task.as_str().to_string(),
+        backend,
+        threads: device.threads,
+        diarized: diarize,
+    })
+}
+
+/// Report the GPU backend compiled into this build and whether it's usable here.
+#[tauri::command]
+pub fn get_whisper_backends() -> WhisperBackends {
+    let gpu_devices = detect_gpu_devices();
+    WhisperBackends {
+        compiled_gpu_backend: compiled_gpu_backend().map(|b| b.to_string()),
+        gpu_available: !gpu_devices.is_empty(),
+        gpu_devices,
+        cpu_threads: default_threads(),
+    }
+}
+
+/// List the audio streams of a video so the caller can choose which one to transcribe.
+#[tauri::command]
+pub fn list_audio_tracks(video_path: String) -> Result<Vec<AudioTrack>, String> {
+    let output = crate::ffmpeg::ffprobe()
+        .arg("-select_streams").arg("a")
+        .arg("-show_entries").arg("stream=index,codec_name,channels:stream_tags=language,title:stream_disposition=default")
+        .arg("-of").arg("json")
+        .arg(&video_path)
+        .output()
+        .map_err(|e| format!("FFprobe error: {}", e))?;
+
+    if !output.status.success() {
+        return Err("Failed to read audio tracks".to_string());
+    }
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).map_err(|e| e.to_string())?;
+    let streams = json["streams"].as_array().cloned().unwrap_or_default();
+
+    Ok(streams.iter().enumerate().map(|(i, st)| AudioTrack {
+        index: i as u32,
+        stream_index: st["index"].as_u64().unwrap_or(0) as u32,
+        codec: st["codec_name"].as_str().unwrap_or("unknown").to_string(),
+        channels: st["channels"].as_u64().unwrap_or(0) as u32,
+        language: st["tags"]["language"].as_str().map(|s| s.to_string()),
+        title: st["tags"]["title"].as_str().map(|s| s.to_string()),
+        default: st["disposition"]["default"].as_u64() == Some(1),
+    }).collect())
+}
+
+/// Cancel a running transcription: kills ffmpeg, aborts inference and removes temp files.
+#[tauri::command]
+pub fn cancel_whisper(app: AppHandle, jobs: State<'_, WhisperJobs>, job_id: String) -> Result<bool, String> {
+    let job = jobs.jobs.lock().map_err(|e| e.to_string())?.get(&job_id).cloned();
+    let Some(job) = job else {
+        return Ok(false);
+    };
+
+    job.cancelled.store(true, Ordering::SeqCst);
+    if let Some(child) = job.child.lock().map_err(|e| e.to_string())?.as_mut() {
+        let _ = child.kill();
+    }
+    let _ = fs::remove_dir_all(&job.temp_dir);
+
+    emit_progress(&app, &job_id, "Transcription cancelled", 0.0);
+    Ok(true)
 }
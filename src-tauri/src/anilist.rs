@@ -0,0 +1,279 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+
+use crate::metadata_cache::MetadataCache;
+
+const ANILIST_URL: &str = "https://graphql.anilist.co";
+const ANILIST_CACHE_TTL: u64 = 7 * 24 * 60 * 60;
+// Sequel hops followed when mapping an absolute episode number onto later seasons
+const MAX_SEQUEL_HOPS: usize = 6;
+
+const MEDIA_FIELDS: &str = "id idMal episodes format status averageScore genres description(asHtml: false) \
+    title { romaji english native } coverImage { extraLarge large } startDate { year } \
+    relations { edges { relationType node { id type format } } }";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AnimeInfo {
+    pub anilist_id: u64,
+    pub title: String,
+    pub title_english: Option<String>,
+    pub title_native: Option<String>,
+    pub cover_url: Option<String>,
+    pub episodes: Option<u32>,
+    pub overview: String,
+    pub average_score: Option<u32>,
+    pub genres: Vec<String>,
+    pub year: Option<u32>,
+    pub format: Option<String>,
+    pub status: Option<String>,
+    pub episode: Option<EpisodeMapping>,
+}
+
+/// Where an episode number from the filename lands once sequels are taken into account.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EpisodeMapping {
+    pub absolute: u32,
+    pub anilist_id: u64,
+    pub title: String,
+    pub episode: u32,
+    #[serde(default)]
+    pub season_episodes: Option<u32>,
+    #[serde(default)]
+    pub mal_id: Option<u64>, // the same season on MyAnimeList
+}
+
+#[derive(Deserialize)]
+struct GqlResponse {
+    data: Option<GqlData>,
+}
+
+#[derive(Deserialize)]
+struct GqlData {
+    #[serde(rename = "Media")]
+    media: Option<AlMedia>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct AlMedia {
+    id: u64,
+    id_mal: Option<u64>,
+    episodes: Option<u32>,
+    format: Option<String>,
+    status: Option<String>,
+    average_score: Option<u32>,
+    #[serde(default)]
+    genres: Vec<String>,
+    description: Option<String>,
+    title: AlTitle,
+    cover_image: Option<AlCover>,
+    start_date: Option<AlDate>,
+    relations: Option<AlRelations>,
+}
+
+#[derive(Deserialize, Clone)]
+struct AlTitle {
+    romaji: Option<String>,
+    english: Option<String>,
+    native: Option<String>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct AlCover {
+    extra_large: Option<String>,
+    large: Option<String>,
+}
+
+#[derive(Deserialize, Clone)]
+struct AlDate {
+    year: Option<u32>,
+}
+
+#[derive(Deserialize, Clone)]
+struct AlRelations {
+    edges: Vec<AlEdge>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct AlEdge {
+    relation_type: String,
+    node: AlNode,
+}
+
+#[derive(Deserialize, Clone)]
+struct AlNode {
+    id: u64,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    format: Option<String>,
+}
+
+impl AlMedia {
+    fn display_title(&self) -> String {
+        self.title.english.clone()
+            .or_else(|| self.title.romaji.clone())
+            .unwrap_or_default()
+    }
+
+    fn sequel(&self) -> Option<u64> {
+        self.relations.as_ref()?.edges.iter()
+            .find(|e| {
+                e.relation_type == "SEQUEL"
+                    && e.node.kind.as_deref() == Some("ANIME")
+                    && matches!(e.node.format.as_deref(), Some("TV") | Some("TV_SHORT") | Some("ONA"))
+            })
+            .map(|e| e.node.id)
+    }
+}
+
+/// Fansub-style names: "[Group] Title - 12 [1080p][ABCD1234].mkv".
+pub fn is_anime_filename(name: &str) -> bool {
+    let group = Regex::new(r"^\s*\[[^\]]+\]").unwrap();
+    let crc = Regex::new(r"\[[0-9A-Fa-f]{8}\]").unwrap();
+    let absolute = Regex::new(r"\s-\s\d{1,4}(v\d)?(\s|\[|\(|\.|$)").unwrap();
+    let season_episode = Regex::new(r"(?i)\bS\d{1,2}E\d{1,3}\b").unwrap();
+    group.is_match(name) || crc.is_match(name) || (absolute.is_match(name) && !season_episode.is_match(name))
+}
+
+/// Split an anime filename into its series title and absolute episode number.
+pub fn parse_anime_name(name: &str) -> (String, Option<u32>) {
+    // Only strip real video extensions; folder names can contain dots
+    let is_video = crate::file_scanner::is_video(Path::new(name));
+    let stem = if is_video { Path::new(name).file_stem().and_then(|s| s.to_str()).unwrap_or(name) } else { name };
+    let brackets = Regex::new(r"[\[\(\{][^\]\)\}]*[\]\)\}]").unwrap();
+    let text = brackets.replace_all(stem, " ").replace('_', " ");
+
+    let episode_re = Regex::new(r"(?i)\s-\s(\d{1,4})(v\d)?\b|\b(?:ep|episode|e)\s?(\d{1,4})\b").unwrap();
+    let (title, episode) = match episode_re.captures(&text) {
+        Some(caps) => {
+            let episode = caps.get(1).or(caps.get(3)).and_then(|m| m.as_str().parse().ok());
+            (text[..caps.get(0).unwrap().start()].to_string(), episode)
+        }
+        None => (text.to_string(), None),
+    };
+
+    let tags = Regex::new(r"(?i)\b(1080p|720p|480p|2160p|4k|hevc|x264|x265|10bit|bd|bluray|web|webrip|multi|dual audio)\b.*$").unwrap();
+    let title = tags.replace(&title, "");
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    (title.trim_end_matches([' ', '-', '.']).to_string(), episode)
+}
+
+async fn query_media(app: &AppHandle, client: &reqwest::Client, search: Option<&str>, id: Option<u64>) -> Result<Option<AlMedia>, String> {
+    let (args, variables, key) = match (search, id) {
+        (_, Some(id)) => ("$id: Int", serde_json::json!({ "id": id }), format!("anilist:id:{}", id)),
+        (Some(q), None) => ("$search: String", serde_json::json!({ "search": q }), format!("anilist:search:{}", q.to_lowercase())),
+        (None, None) => return Ok(None),
+    };
+    let filter = if id.is_some() { "id: $id" } else { "search: $search" };
+    let cache = app.state::<MetadataCache>();
+
+    let body = match cache.get(app, &key, ANILIST_CACHE_TTL) {
+        Some(body) => body,
+        None => {
+            let query = format!("query ({}) {{ Media({}, type: ANIME) {{ {} }} }}", args, filter, MEDIA_FIELDS);
+            let request = client.post(ANILIST_URL)
+                .json(&serde_json::json!({ "query": query, "variables": variables }));
+            let resp = crate::metadata::send_with_retry(request).await;
+            let resp = match resp {
+                Ok(r) => r,
+                Err(e) => return cache.get_stale(app, &key)
+                    .map(|b| serde_json::from_value::<GqlResponse>(b).ok().and_then(|r| r.data).and_then(|d| d.media))
+                    .ok_or_else(|| format!("AniList request failed: {}", e)),
+            };
+            // AniList answers 404 when a search has no match
+            if resp.status().as_u16() == 404 {
+                return Ok(None);
+            }
+            if !resp.status().is_success() {
+                return Err(format!("AniList API Error: {}", resp.status()));
+            }
+            let body: serde_json::Value = resp.json().await.map_err(|e| format!("Failed to parse AniList response: {}", e))?;
+            cache.insert(app, &key, body.clone());
+            body
+        }
+    };
+
+    let parsed: GqlResponse = serde_json::from_value(body).map_err(|e| format!("Failed to parse AniList response: {}", e))?;
+    Ok(parsed.data.and_then(|d| d.media))
+}
+
+/// Walk SEQUEL relations until the absolute episode number fits inside a season.
+async fn map_episode(app: &AppHandle, client: &reqwest::Client, first: &AlMedia, absolute: u32) -> Result<EpisodeMapping, String> {
+    let mut media = first.clone();
+    let mut episode = absolute;
+    for _ in 0..MAX_SEQUEL_HOPS {
+        match (media.episodes, media.sequel()) {
+            (Some(count), Some(next_id)) if episode > count => {
+                let Some(next) = query_media(app, client, None, Some(next_id)).await? else {
+                    break;
+                };
+                episode -= count;
+                media = next;
+            }
+            _ => break,
+        }
+    }
+    Ok(EpisodeMapping {
+        absolute,
+        anilist_id: media.id,
+        title: media.display_title(),
+        episode,
+        season_episodes: media.episodes,
+        mal_id: media.id_mal,
+    })
+}
+
+/// Search AniList for a series title; the id of the best match and its title.
+pub async fn search_anime(app: &AppHandle, title: &str) -> Result<Option<(u64, String)>, String> {
+    let media = query_media(app, crate::metadata::http_client(), Some(title), None).await?;
+    Ok(media.map(|m| (m.id, m.display_title())))
+}
+
+/// Map an absolute episode of the series `anilist_id` onto the season it falls in.
+pub async fn map_series_episode(app: &AppHandle, anilist_id: u64, absolute: u32) -> Result<Option<EpisodeMapping>, String> {
+    let client = crate::metadata::http_client();
+    match query_media(app, client, None, Some(anilist_id)).await? {
+        Some(media) => map_episode(app, client, &media, absolute).await.map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Look up anime info on AniList from a fansub-style file or folder name.
+#[tauri::command]
+pub async fn fetch_anime_info(app: AppHandle, path: String) -> Result<Option<AnimeInfo>, String> {
+    let name = Path::new(&path).file_name().and_then(|n| n.to_str()).ok_or("Invalid path")?;
+    let (title, episode) = parse_anime_name(name);
+    tracing::info!("🎌 Fetching AniList info for: '{}' (parsed: '{}', ep {:?})", name, title, episode);
+    if title.is_empty() {
+        return Ok(None);
+    }
+
+    let client = crate::metadata::http_client();
+    let Some(media) = query_media(&app, client, Some(&title), None).await? else {
+        return Ok(None);
+    };
+    let mapping = match episode {
+        Some(abs) => Some(map_episode(&app, client, &media, abs).await?),
+        None => None,
+    };
+
+    Ok(Some(AnimeInfo {
+        anilist_id: media.id,
+        title: media.display_title(),
+        title_english: media.title.english.clone(),
+        title_native: media.title.native.clone(),
+        cover_url: media.cover_image.as_ref().and_then(|c| c.extra_large.clone().or_else(|| c.large.clone())),
+        episodes: media.episodes,
+        overview: media.description.clone().unwrap_or_default(),
+        average_score: media.average_score,
+        genres: media.genres.clone(),
+        year: media.start_date.as_ref().and_then(|d| d.year),
+        format: media.format.clone(),
+        status: media.status.clone(),
+        episode: mapping,
+    }))
+}
@@ -0,0 +1,437 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{command, AppHandle, Emitter, Manager};
+use tauri_plugin_opener::OpenerExt;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+use crate::anilist::{map_series_episode, parse_anime_name, EpisodeMapping};
+use crate::config::{load_config, save_config, AnimeAccount};
+use crate::util::now_secs;
+
+// Episode progress sync to AniList and MyAnimeList. Once an anime episode plays past the
+// completion threshold its season's list entry is moved up to that episode; entries never
+// go backwards. Shows are matched to AniList once and remembered in the mapping cache
+// (app_data/anime_mappings.json), where the user can correct a wrong match.
+//
+// AniList logs in with its PIN flow (the token is shown on anilist.co and pasted back);
+// MAL uses OAuth with PKCE and a loopback redirect, so its app must be registered with
+// MAL_REDIRECT_URI.
+
+const ANILIST_URL: &str = "https://graphql.anilist.co";
+const ANILIST_AUTHORIZE: &str = "https://anilist.co/api/v2/oauth/authorize";
+const MAL_AUTHORIZE: &str = "https://myanimelist.net/v1/oauth2/authorize";
+const MAL_TOKEN: &str = "https://myanimelist.net/v1/oauth2/token";
+const MAL_API: &str = "https://api.myanimelist.net/v2";
+const MAL_REDIRECT_PORT: u16 = 53682;
+pub const MAL_REDIRECT_URI: &str = "http://localhost:53682/callback";
+const LOGIN_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AnimeMapping {
+    pub anilist_id: u64,
+    pub title: String,
+    pub manual: bool, // set by the user rather than found by searching
+}
+
+static MAPPINGS_LOCK: Mutex<()> = Mutex::new(());
+
+fn mappings_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app.path().app_data_dir().map_err(|e| e.to_string())?.join("anime_mappings.json"))
+}
+
+fn load_mappings(app: &AppHandle) -> BTreeMap<String, AnimeMapping> {
+    let _guard = MAPPINGS_LOCK.lock();
+    mappings_path(app)
+        .ok()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+fn update_mappings(app: &AppHandle, f: impl FnOnce(&mut BTreeMap<String, AnimeMapping>)) -> Result<(), String> {
+    let _guard = MAPPINGS_LOCK.lock().map_err(|e| e.to_string())?;
+    let path = mappings_path(app)?;
+    let mut mappings: BTreeMap<String, AnimeMapping> = fs::read_to_string(&path)
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default();
+    f(&mut mappings);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(&mappings).map_err(|e| e.to_string())?).map_err(|e| e.to_string())
+}
+
+/// Mapping key for a file: its series title as parsed from the name, lowercased.
+fn show_key(path: &str) -> Option<(String, u32)> {
+    let name = Path::new(path).file_name()?.to_str()?;
+    let (title, episode) = parse_anime_name(name);
+    Some((title.to_lowercase(), episode?)).filter(|(title, _)| !title.is_empty())
+}
+
+#[derive(Serialize, Clone, Debug)]
+struct SyncResult {
+    service: &'static str,
+    title: String,
+    episode: u32,
+    error: Option<String>,
+}
+
+// ---- AniList ----
+
+async fn anilist_query(token: &str, query: &str, variables: serde_json::Value) -> Result<serde_json::Value, String> {
+    let resp = crate::metadata::http_client()
+        .post(ANILIST_URL)
+        .bearer_auth(token)
+        .json(&serde_json::json!({ "query": query, "variables": variables }))
+        .send()
+        .await
+        .map_err(|e| format!("AniList request failed: {}", e))?;
+    let status = resp.status();
+    let body: serde_json::Value = resp.json().await.map_err(|e| format!("Failed to parse AniList response: {}", e))?;
+    if let Some(message) = body["errors"][0]["message"].as_str() {
+        return Err(format!("AniList: {}", message));
+    }
+    if !status.is_success() {
+        return Err(format!("AniList API Error: {}", status));
+    }
+    Ok(body["data"].clone())
+}
+
+async fn update_anilist(token: &str, mapping: &EpisodeMapping) -> Result<bool, String> {
+    let current = anilist_query(
+        token,
+        "query ($id: Int) { Media(id: $id) { mediaListEntry { progress status } } }",
+        serde_json::json!({ "id": mapping.anilist_id }),
+    )
+    .await?;
+    let entry = &current["Media"]["mediaListEntry"];
+    let progress = entry["progress"].as_u64().unwrap_or(0);
+    if progress >= mapping.episode as u64 && entry["status"].as_str() != Some("PLANNING") {
+        return Ok(false);
+    }
+    let completed = mapping.season_episodes == Some(mapping.episode);
+    anilist_query(
+        token,
+        "mutation ($id: Int, $progress: Int, $status: MediaListStatus) { SaveMediaListEntry(mediaId: $id, progress: $progress, status: $status) { id } }",
+        serde_json::json!({ "id": mapping.anilist_id, "progress": mapping.episode, "status": if completed { "COMPLETED" } else { "CURRENT" } }),
+    )
+    .await?;
+    Ok(true)
+}
+
+// ---- MyAnimeList ----
+
+#[derive(Deserialize)]
+struct MalToken {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<u64>,
+}
+
+async fn request_mal_token(form: &[(&str, &str)]) -> Result<MalToken, String> {
+    let resp = crate::metadata::http_client()
+        .post(MAL_TOKEN)
+        .form(form)
+        .send()
+        .await
+        .map_err(|e| format!("MyAnimeList request failed: {}", e))?;
+    if !resp.status().is_success() {
+        return Err(format!("MyAnimeList login failed: {}", resp.status()));
+    }
+    resp.json().await.map_err(|e| format!("Failed to parse MyAnimeList token: {}", e))
+}
+
+/// A usable MAL access token, refreshed (and saved) once it has expired.
+async fn mal_access_token(app: &AppHandle) -> Result<Option<String>, String> {
+    let settings = load_config(app).anime_sync;
+    let Some(account) = settings.mal else {
+        return Ok(None);
+    };
+    if account.expires_at.is_none_or(|at| at > now_secs() + 60) {
+        return Ok(Some(account.access_token));
+    }
+    let refresh = account.refresh_token.clone().ok_or("MyAnimeList session expired; log in again")?;
+    let token = request_mal_token(&[
+        ("client_id", settings.mal_client_id.as_str()),
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh.as_str()),
+    ])
+    .await?;
+    let mut config = load_config(app);
+    if let Some(saved) = config.anime_sync.mal.as_mut() {
+        saved.access_token = token.access_token.clone();
+        saved.refresh_token = token.refresh_token.or(Some(refresh));
+        saved.expires_at = token.expires_in.map(|secs| now_secs() + secs);
+    }
+    save_config(app, &config)?;
+    Ok(Some(token.access_token))
+}
+
+async fn update_mal(token: &str, mal_id: u64, mapping: &EpisodeMapping) -> Result<bool, String> {
+    let client = crate::metadata::http_client();
+    let resp = client
+        .get(format!("{}/anime/{}?fields=my_list_status,num_episodes", MAL_API, mal_id))
+        .bearer_auth(token)
+        .send()
+        .await
+        .map_err(|e| format!("MyAnimeList request failed: {}", e))?;
+    if !resp.status().is_success() {
+        return Err(format!("MyAnimeList API Error: {}", resp.status()));
+    }
+    let anime: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
+    let watched = anime["my_list_status"]["num_episodes_watched"].as_u64().unwrap_or(0);
+    if watched >= mapping.episode as u64 {
+        return Ok(false);
+    }
+    let total = anime["num_episodes"].as_u64().filter(|n| *n > 0).or(mapping.season_episodes.map(u64::from));
+    let status = if total == Some(mapping.episode as u64) { "completed" } else { "watching" };
+    let episode = mapping.episode.to_string();
+    let resp = client
+        .put(format!("{}/anime/{}/my_list_status", MAL_API, mal_id))
+        .bearer_auth(token)
+        .form(&[("num_watched_episodes", episode.as_str()), ("status", status)])
+        .send()
+        .await
+        .map_err(|e| format!("MyAnimeList request failed: {}", e))?;
+    if !resp.status().is_success() {
+        return Err(format!("MyAnimeList API Error: {}", resp.status()));
+    }
+    Ok(true)
+}
+
+// ---- Scrobbling ----
+
+/// The AniList series for a show, searched for on first sight and then remembered.
+async fn series_for(app: &AppHandle, key: &str) -> Result<Option<AnimeMapping>, String> {
+    if let Some(mapping) = load_mappings(app).get(key) {
+        return Ok(Some(mapping.clone()));
+    }
+    let Some((anilist_id, title)) = crate::anilist::search_anime(app, key).await? else {
+        return Ok(None);
+    };
+    let mapping = AnimeMapping { anilist_id, title, manual: false };
+    update_mappings(app, |mappings| {
+        mappings.entry(key.to_string()).or_insert_with(|| mapping.clone());
+    })?;
+    Ok(Some(mapping))
+}
+
+async fn scrobble(app: AppHandle, path: String) {
+    let Some((key, absolute)) = show_key(&path) else {
+        return;
+    };
+    let mapping = match series_for(&app, &key).await {
+        Ok(Some(series)) => map_series_episode(&app, series.anilist_id, absolute).await,
+        Ok(None) => Ok(None),
+        Err(e) => Err(e),
+    };
+    let mapping = match mapping {
+        Ok(Some(mapping)) => mapping,
+        Ok(None) => return tracing::info!("🎌 No AniList match for '{}', not syncing", key),
+        Err(e) => return tracing::warn!("🎌 Couldn't map '{}' to AniList: {}", key, e),
+    };
+
+    let report = |service: &'static str, result: Result<bool, String>| {
+        match &result {
+            Ok(true) => tracing::info!("🎌 {} progress: {} episode {}", service, mapping.title, mapping.episode),
+            Ok(false) => return,
+            Err(e) => tracing::error!("🎌 {} update failed for {}: {}", service, mapping.title, e),
+        }
+        let _ = app.emit("anime-sync", SyncResult { service, title: mapping.title.clone(), episode: mapping.episode, error: result.err() });
+    };
+
+    if let Some(account) = load_config(&app).anime_sync.anilist {
+        report("AniList", update_anilist(&account.access_token, &mapping).await);
+    }
+    match (mal_access_token(&app).await, mapping.mal_id) {
+        (Ok(Some(token)), Some(mal_id)) => report("MyAnimeList", update_mal(&token, mal_id, &mapping).await),
+        (Err(e), _) => report("MyAnimeList", Err(e)),
+        _ => {}
+    }
+}
+
+/// Fed from the player loop; syncs each anime episode once it passes the threshold.
+pub struct AnimeScrobbler {
+    path: Option<String>,
+    threshold: f64,
+}
+
+impl AnimeScrobbler {
+    pub fn new() -> Self {
+        Self { path: None, threshold: 1.0 }
+    }
+
+    /// A file finished loading. Only local anime episodes are considered, and only with
+    /// an account connected.
+    pub fn start(&mut self, app: &AppHandle, path: Option<&str>) {
+        let config = load_config(app);
+        let settings = &config.anime_sync;
+        let active = settings.enabled && !config.private_mode && (settings.anilist.is_some() || settings.mal.is_some());
+        self.path = path
+            .filter(|p| active && !p.contains("://"))
+            .filter(|p| {
+                let key = show_key(p);
+                let name = Path::new(p).file_name().and_then(|n| n.to_str()).unwrap_or_default();
+                key.is_some_and(|(key, _)| crate::anilist::is_anime_filename(name) || load_mappings(app).contains_key(&key))
+            })
+            .map(str::to_string);
+        self.threshold = settings.threshold_percent as f64 / 100.0;
+    }
+
+    pub fn progress(&mut self, app: &AppHandle, position: f64, duration: f64) {
+        if duration > 0.0 && position / duration >= self.threshold {
+            if let Some(path) = self.path.take() {
+                tauri::async_runtime::spawn(scrobble(app.clone(), path));
+            }
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.path = None;
+    }
+}
+
+// ---- Commands ----
+
+/// Open AniList's authorization page; it shows a token to paste into `anilist_login`.
+#[command]
+pub fn anilist_open_login(app: AppHandle) -> Result<(), String> {
+    let client_id = load_config(&app).anime_sync.anilist_client_id;
+    if client_id.trim().is_empty() {
+        return Err("Set an AniList client ID first".to_string());
+    }
+    let url = format!("{}?client_id={}&response_type=token", ANILIST_AUTHORIZE, urlencoding::encode(client_id.trim()));
+    app.opener().open_url(url, None::<&str>).map_err(|e| e.to_string())
+}
+
+#[command]
+pub async fn anilist_login(app: AppHandle, token: String) -> Result<AnimeAccount, String> {
+    let token = token.trim().to_string();
+    let viewer = anilist_query(&token, "query { Viewer { id name } }", serde_json::json!({})).await?;
+    let username = viewer["Viewer"]["name"].as_str().ok_or("AniList didn't accept that token")?.to_string();
+    let account = AnimeAccount { username, access_token: token, refresh_token: None, expires_at: None };
+    let mut config = load_config(&app);
+    config.anime_sync.anilist = Some(account.clone());
+    save_config(&app, &config)?;
+    tracing::info!("🎌 Logged in to AniList as {}", account.username);
+    Ok(account)
+}
+
+/// Run the MAL OAuth flow in the browser and wait for its redirect.
+#[command]
+pub async fn mal_login(app: AppHandle) -> Result<AnimeAccount, String> {
+    let client_id = load_config(&app).anime_sync.mal_client_id.trim().to_string();
+    if client_id.is_empty() {
+        return Err("Set a MyAnimeList client ID first".to_string());
+    }
+    // MAL only supports the "plain" PKCE method; 96 characters of random hex
+    let verifier: String = (0..3).map(|_| uuid::Uuid::new_v4().simple().to_string()).collect();
+    let state = uuid::Uuid::new_v4().simple().to_string();
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", MAL_REDIRECT_PORT))
+        .await
+        .map_err(|e| format!("Couldn't listen for the login redirect on port {}: {}", MAL_REDIRECT_PORT, e))?;
+    let url = format!(
+        "{}?response_type=code&client_id={}&code_challenge={}&code_challenge_method=plain&state={}&redirect_uri={}",
+        MAL_AUTHORIZE,
+        urlencoding::encode(&client_id),
+        verifier,
+        state,
+        urlencoding::encode(MAL_REDIRECT_URI)
+    );
+    app.opener().open_url(url, None::<&str>).map_err(|e| e.to_string())?;
+
+    let code = tokio::time::timeout(LOGIN_TIMEOUT, async {
+        loop {
+            let (mut stream, _) = listener.accept().await.map_err(|e| e.to_string())?;
+            let mut request_line = String::new();
+            BufReader::new(&mut stream).read_line(&mut request_line).await.map_err(|e| e.to_string())?;
+            let query = request_line.split_whitespace().nth(1).and_then(|target| target.split_once('?')).map(|(_, q)| q.to_string());
+            let params: BTreeMap<String, String> = query
+                .unwrap_or_default()
+                .split('&')
+                .filter_map(|pair| pair.split_once('='))
+                .map(|(k, v)| (k.to_string(), urlencoding::decode(v).map(|v| v.into_owned()).unwrap_or_default()))
+                .collect();
+            let (body, result) = match (params.get("code"), params.get("state")) {
+                (Some(code), Some(got)) if *got == state => ("Logged in to MyAnimeList. You can close this tab.", Some(Ok(code.clone()))),
+                _ if params.contains_key("error") => ("MyAnimeList login was cancelled.", Some(Err("MyAnimeList login was cancelled".to_string()))),
+                _ => ("", None), // favicon and the like
+            };
+            let response = format!("HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nConnection: close\r\n\r\n<p>{}</p>", body);
+            let _ = stream.write_all(response.as_bytes()).await;
+            if let Some(result) = result {
+                return result;
+            }
+        }
+    })
+    .await
+    .map_err(|_| "Timed out waiting for the MyAnimeList login".to_string())??;
+
+    let token = request_mal_token(&[
+        ("client_id", client_id.as_str()),
+        ("grant_type", "authorization_code"),
+        ("code", code.as_str()),
+        ("code_verifier", verifier.as_str()),
+        ("redirect_uri", MAL_REDIRECT_URI),
+    ])
+    .await?;
+    let resp = crate::metadata::http_client()
+        .get(format!("{}/users/@me", MAL_API))
+        .bearer_auth(&token.access_token)
+        .send()
+        .await
+        .map_err(|e| format!("MyAnimeList request failed: {}", e))?;
+    let user: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
+    let account = AnimeAccount {
+        username: user["name"].as_str().unwrap_or("MyAnimeList user").to_string(),
+        access_token: token.access_token,
+        refresh_token: token.refresh_token,
+        expires_at: token.expires_in.map(|secs| now_secs() + secs),
+    };
+    let mut config = load_config(&app);
+    config.anime_sync.mal = Some(account.clone());
+    save_config(&app, &config)?;
+    tracing::info!("🎌 Logged in to MyAnimeList as {}", account.username);
+    Ok(account)
+}
+
+/// `service` is "anilist" or "mal".
+#[command]
+pub fn anime_sync_logout(app: AppHandle, service: String) -> Result<(), String> {
+    let mut config = load_config(&app);
+    match service.as_str() {
+        "anilist" => config.anime_sync.anilist = None,
+        "mal" => config.anime_sync.mal = None,
+        _ => return Err(format!("Unknown service: {}", service)),
+    }
+    save_config(&app, &config)
+}
+
+#[command]
+pub fn list_anime_mappings(app: AppHandle) -> BTreeMap<String, AnimeMapping> {
+    load_mappings(&app)
+}
+
+/// Pin a show (its mapping key, or any episode path of it) to an AniList id.
+#[command]
+pub async fn set_anime_mapping(app: AppHandle, show: String, anilist_id: u64) -> Result<AnimeMapping, String> {
+    let key = show_key(&show).map(|(key, _)| key).unwrap_or_else(|| show.trim().to_lowercase());
+    let series = map_series_episode(&app, anilist_id, 1).await?.ok_or(format!("No anime with AniList id {}", anilist_id))?;
+    let mapping = AnimeMapping { anilist_id, title: series.title, manual: true };
+    update_mappings(&app, |mappings| {
+        mappings.insert(key, mapping.clone());
+    })?;
+    Ok(mapping)
+}
+
+#[command]
+pub fn delete_anime_mapping(app: AppHandle, show: String) -> Result<(), String> {
+    update_mappings(&app, |mappings| {
+        mappings.remove(&show);
+    })
+}
@@ -0,0 +1,106 @@
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::{command, AppHandle, Emitter};
+
+pub const FORMATS: &[&str] = &["mp3", "flac", "aac"];
+
+#[derive(Serialize, Clone)]
+struct ExtractionProgress {
+    video: String,
+    progress: f32, // 0-100
+}
+
+/// Encoder arguments and file extension for one output format.
+fn codec_args(format: &str) -> (&'static [&'static str], &'static str) {
+    match format {
+        "flac" => (&["-c:a", "flac"], "flac"),
+        "aac" => (&["-c:a", "aac", "-b:a", "192k", "-movflags", "+faststart"], "m4a"),
+        // ID3v2.3 is what most players and tag editors read
+        _ => (&["-c:a", "libmp3lame", "-q:a", "2", "-id3v2_version", "3"], "mp3"),
+    }
+}
+
+/// Whether the source has a container-level title tag.
+fn has_title(input: &str) -> bool {
+    crate::ffmpeg::ffprobe()
+        .args(["-show_entries", "format_tags=title", "-of", "default=noprint_wrappers=1:nokey=1"])
+        .arg(input)
+        .output()
+        .is_ok_and(|o| o.status.success() && !String::from_utf8_lossy(&o.stdout).trim().is_empty())
+}
+
+/// Pull one audio track (the first unless `track` is given) out of a video, optionally only
+/// `start`..`end`. Container and stream tags are carried over, with the file name as the title
+/// when the video has none. Progress arrives as `audio-extraction` events; returns the new file.
+#[command]
+pub async fn extract_audio(
+    app: AppHandle,
+    video: String,
+    format: String,
+    track: Option<u32>,
+    start: Option<f64>,
+    end: Option<f64>,
+) -> Result<String, String> {
+    if !FORMATS.contains(&format.as_str()) {
+        return Err(format!("Unknown audio format '{}'", format));
+    }
+    if let (Some(start), Some(end)) = (start, end) {
+        if end - start < 0.5 {
+            return Err("The range must be at least half a second long".to_string());
+        }
+    }
+    let (args, ext) = codec_args(&format);
+    let dir = crate::conversions::output_dir(&app, &video)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let stem = Path::new(&video).file_stem().and_then(|s| s.to_str()).unwrap_or("audio").to_string();
+    let output = crate::ffmpeg::unique_output(&dir, &stem, ext)?;
+    let input = crate::network_sources::playable_path(&app, &video);
+
+    let worker_output = output.clone();
+    tauri::async_runtime::spawn_blocking(move || -> Result<(), String> {
+        let total = crate::file_scanner::get_video_duration(input.clone()).unwrap_or(0.0);
+        let from = start.unwrap_or(0.0).max(0.0);
+        let length = end.map(|e| e - from).unwrap_or(total - from);
+
+        let mut command = crate::ffmpeg::command();
+        if from > 0.0 {
+            command.arg("-ss").arg(format!("{:.3}", from));
+        }
+        if let Some(end) = end {
+            command.arg("-t").arg(format!("{:.3}", end - from));
+        }
+        command
+            .arg("-i").arg(&input)
+            .arg("-vn").arg("-sn").arg("-dn")
+            .arg("-map").arg(format!("0:a:{}", track.unwrap_or(0)))
+            .arg("-map_metadata").arg("0")
+            .arg("-map_metadata:s:a:0").arg(format!("0:s:a:{}", track.unwrap_or(0)))
+            // Chapter times would be off for a range
+            .arg("-map_chapters").arg(if start.is_some() || end.is_some() { "-1" } else { "0" })
+            .args(args);
+        if !has_title(&input) {
+            command.arg("-metadata").arg(format!("title={}", stem));
+        }
+        command.arg(&worker_output);
+
+        let child = Mutex::new(None);
+        let mut last_emitted = -1.0;
+        let result = crate::ffmpeg::run_with_progress(command, length, &child, |progress| {
+            if progress - last_emitted >= 1.0 {
+                last_emitted = progress;
+                let _ = app.emit("audio-extraction", ExtractionProgress { video: video.clone(), progress });
+            }
+        });
+        if result.is_err() {
+            let _ = fs::remove_file(&worker_output);
+        }
+        result
+    })
+    .await
+    .map_err(|e| format!("Audio extraction failed: {}", e))??;
+
+    tracing::info!("🎵 Extracted audio to {}", output.display());
+    Ok(output.to_string_lossy().to_string())
+}
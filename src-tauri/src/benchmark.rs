@@ -0,0 +1,288 @@
+use libmpv2::Mpv;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{command, AppHandle, Emitter, Manager, State};
+
+const DEFAULT_SECONDS: f64 = 8.0;
+const VOS: &[&str] = &["gpu", "gpu-next"];
+const SAMPLE_EVERY: Duration = Duration::from_millis(500);
+
+pub struct BenchmarkState {
+    cancel: Mutex<Option<Arc<AtomicBool>>>,
+}
+
+impl BenchmarkState {
+    pub fn new() -> Self {
+        Self { cancel: Mutex::new(None) }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BenchmarkRun {
+    pub hwdec: String,
+    pub vo: String,
+    pub hwdec_active: String, // what mpv actually used; "no" when it fell back to software
+    pub frames: i64,
+    pub dropped_frames: i64, // dropped by the VO plus by the decoder
+    pub cpu_percent: f32, // of the whole machine, averaged over the run
+    pub gpu_percent: Option<f32>, // NVIDIA (nvidia-smi) and AMD on Linux only
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BenchmarkResult {
+    pub sample: String,
+    pub runs: Vec<BenchmarkRun>, // best first
+    pub recommended_hwdec: Option<String>,
+    pub recommended_vo: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+struct BenchmarkProgress {
+    done: usize,
+    total: usize,
+    hwdec: String,
+    vo: String,
+}
+
+/// 10 s of 1080p60 HEVC (H.264 when there is no HEVC encoder), made once in the cache folder.
+fn default_sample(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_cache_dir().map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join("benchmark-1080p60.mkv");
+    if path.exists() {
+        return Ok(path);
+    }
+    tracing::info!("⏱️ Encoding the benchmark sample");
+    // Encoded under another name so an interrupted run doesn't leave a truncated sample behind
+    let partial = dir.join("benchmark-1080p60.partial.mkv");
+    for encoder in ["libx265", "libx264"] {
+        let status = crate::ffmpeg::command()
+            .args(["-f", "lavfi", "-i", "testsrc2=s=1920x1080:r=60:d=10"])
+            .args(["-c:v", encoder, "-preset", "ultrafast", "-pix_fmt", "yuv420p"])
+            .arg(&partial)
+            .output()
+            .map_err(|e| format!("ffmpeg not found: {}", e))?
+            .status;
+        if status.success() {
+            std::fs::rename(&partial, &path).map_err(|e| e.to_string())?;
+            return Ok(path);
+        }
+        let _ = std::fs::remove_file(&partial);
+    }
+    Err("ffmpeg couldn't encode a benchmark sample; pick a video instead".to_string())
+}
+
+/// Busy percentage of the first GPU, where a tool or sysfs reports it.
+fn gpu_load() -> Option<f32> {
+    if let Some(load) = crate::ffmpeg::hidden("nvidia-smi")
+        .args(["--query-gpu=utilization.gpu", "--format=csv,noheader,nounits"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8_lossy(&o.stdout).lines().next()?.trim().parse().ok())
+    {
+        return Some(load);
+    }
+    // amdgpu
+    std::fs::read_dir("/sys/class/drm").ok()?.flatten().find_map(|entry| {
+        std::fs::read_to_string(entry.path().join("device/gpu_busy_percent")).ok()?.trim().parse().ok()
+    })
+}
+
+fn mean(samples: &[f32]) -> Option<f32> {
+    (!samples.is_empty()).then(|| samples.iter().sum::<f32>() / samples.len() as f32)
+}
+
+/// Play `seconds` of `sample` from `start` in a small window of its own and count what dropped.
+fn run_one(sample: &Path, start: f64, seconds: f64, hwdec: &str, vo: &str, cancel: &AtomicBool) -> BenchmarkRun {
+    let mut run = BenchmarkRun {
+        hwdec: hwdec.to_string(),
+        vo: vo.to_string(),
+        hwdec_active: "no".to_string(),
+        frames: 0,
+        dropped_frames: 0,
+        cpu_percent: 0.0,
+        gpu_percent: None,
+        error: None,
+    };
+    let mut mpv = match Mpv::new() {
+        Ok(mpv) => mpv,
+        Err(e) => {
+            run.error = Some(format!("mpv failed to start: {}", e));
+            return run;
+        }
+    };
+    // libmpv reads no mpv.conf, so only the settings under test differ between runs
+    let _ = mpv.set_property("vo", vo);
+    let _ = mpv.set_property("hwdec", hwdec);
+    let _ = mpv.set_property("aid", "no");
+    let _ = mpv.set_property("osc", false);
+    let _ = mpv.set_property("force-window", true);
+    let _ = mpv.set_property("ontop", true);
+    let _ = mpv.set_property("geometry", "480x270");
+    let _ = mpv.set_property("title", "FrameX decode benchmark");
+    let _ = mpv.set_property("keep-open", "yes");
+    let _ = mpv.set_property("start", format!("{}", start).as_str());
+    let _ = mpv.set_property("length", format!("{}", seconds).as_str());
+    if let Err(e) = mpv.command("loadfile", &[&crate::mpv_handler::mpv_path(sample)]) {
+        run.error = Some(e.to_string());
+        return run;
+    }
+
+    let cores = std::thread::available_parallelism().map_or(1, |n| n.get()) as f32;
+    let pid = sysinfo::get_current_pid().ok();
+    let mut system = sysinfo::System::new();
+    let refresh_cpu = |system: &mut sysinfo::System| -> Option<f32> {
+        let pid = pid?;
+        system.refresh_processes_specifics(
+            sysinfo::ProcessesToUpdate::Some(&[pid]),
+            false,
+            sysinfo::ProcessRefreshKind::nothing().with_cpu(),
+        );
+        system.process(pid).map(|p| p.cpu_usage() / cores)
+    };
+    refresh_cpu(&mut system);
+
+    let (mut cpu, mut gpu) = (Vec::new(), Vec::new());
+    // estimated-frame-number counts from the start of the file, not of the segment
+    let mut first_frame: Option<i64> = None;
+    let started = Instant::now();
+    let deadline = started + Duration::from_secs_f64(seconds * 3.0 + 15.0);
+    loop {
+        let tick = Instant::now() + SAMPLE_EVERY;
+        while let Some(remaining) = tick.checked_duration_since(Instant::now()) {
+            // Drain mpv's event queue while waiting
+            let _ = mpv.wait_event(remaining.as_secs_f64());
+        }
+        if let Some(load) = refresh_cpu(&mut system) {
+            cpu.push(load);
+        }
+        if let Some(load) = gpu_load() {
+            gpu.push(load);
+        }
+        if let Ok(active) = mpv.get_property::<String>("hwdec-current") {
+            run.hwdec_active = active;
+        }
+        if let Ok(frame) = mpv.get_property::<i64>("estimated-frame-number") {
+            run.frames = frame - *first_frame.get_or_insert(frame);
+        }
+        run.dropped_frames = mpv.get_property::<i64>("frame-drop-count").unwrap_or(0)
+            + mpv.get_property::<i64>("decoder-frame-drop-count").unwrap_or(0);
+
+        if mpv.get_property("eof-reached").unwrap_or(false) {
+            break;
+        }
+        // The file never started or was dropped, e.g. because the VO couldn't be created
+        if started.elapsed() > Duration::from_secs(3) && mpv.get_property("idle-active").unwrap_or(false) {
+            run.error = Some(format!("Playback failed with vo={} hwdec={}", vo, hwdec));
+            break;
+        }
+        if cancel.load(Ordering::SeqCst) {
+            run.error = Some("Cancelled".to_string());
+            break;
+        }
+        if Instant::now() > deadline {
+            run.error = Some("Timed out".to_string());
+            break;
+        }
+    }
+    run.cpu_percent = mean(&cpu).unwrap_or(0.0);
+    run.gpu_percent = mean(&gpu);
+    let _ = mpv.command("quit", &[]);
+    run
+}
+
+/// Fewest drops first, then runs that really used the hardware, then the lowest CPU load.
+fn rank(runs: &mut [BenchmarkRun]) {
+    runs.sort_by(|a, b| {
+        a.error
+            .is_some()
+            .cmp(&b.error.is_some())
+            .then(a.dropped_frames.cmp(&b.dropped_frames))
+            .then((a.hwdec != "no" && a.hwdec_active == "no").cmp(&(b.hwdec != "no" && b.hwdec_active == "no")))
+            .then(a.cpu_percent.total_cmp(&b.cpu_percent))
+    });
+}
+
+fn benchmark(app: &AppHandle, sample: &Path, start: f64, seconds: f64, cancel: &AtomicBool) -> Result<Vec<BenchmarkRun>, String> {
+    let hwdecs: Vec<String> = std::iter::once("no".to_string())
+        .chain(crate::hardware::capabilities(app, false).hwdec.into_iter().filter(|h| h.available).map(|h| h.api))
+        .collect();
+    let combinations: Vec<(&String, &str)> = hwdecs.iter().flat_map(|h| VOS.iter().map(move |vo| (h, *vo))).collect();
+
+    let total = combinations.len();
+    let mut runs = Vec::new();
+    for (done, (hwdec, vo)) in combinations.into_iter().enumerate() {
+        if cancel.load(Ordering::SeqCst) {
+            return Err("Benchmark cancelled".to_string());
+        }
+        let _ = app.emit("benchmark-progress", BenchmarkProgress { done, total, hwdec: hwdec.clone(), vo: vo.to_string() });
+        let run = run_one(sample, start, seconds, hwdec, vo, cancel);
+        tracing::info!(
+            "⏱️ vo={} hwdec={} ({}): {} of {} frames dropped, CPU {:.0}%",
+            vo, hwdec, run.hwdec_active, run.dropped_frames, run.frames, run.cpu_percent
+        );
+        runs.push(run);
+    }
+    let _ = app.emit("benchmark-progress", BenchmarkProgress { done: total, total, hwdec: String::new(), vo: String::new() });
+    rank(&mut runs);
+    Ok(runs)
+}
+
+/// Play `seconds` (8 by default) of `path`, or of a generated 1080p60 clip, with software
+/// decoding and every working hwdec API on each VO, and rank the settings by dropped frames
+/// and load. Emits `benchmark-progress`; each run opens a small window.
+#[command]
+pub async fn run_decode_benchmark(
+    app: AppHandle,
+    state: State<'_, BenchmarkState>,
+    path: Option<String>,
+    start: Option<f64>,
+    seconds: Option<f64>,
+) -> Result<BenchmarkResult, String> {
+    let cancel = Arc::new(AtomicBool::new(false));
+    {
+        let mut running = state.cancel.lock().unwrap();
+        if running.is_some() {
+            return Err("A benchmark is already running".to_string());
+        }
+        *running = Some(cancel.clone());
+    }
+    // The main player would skew the numbers
+    if let Some(tx) = app.state::<crate::mpv_handler::MpvState>().tx.lock().unwrap().as_ref() {
+        let _ = tx.send(crate::mpv_handler::MpvCommand::Pause);
+    }
+
+    let worker_app = app.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || -> Result<BenchmarkResult, String> {
+        let sample = match path.filter(|p| !p.trim().is_empty()) {
+            Some(path) => PathBuf::from(path),
+            None => default_sample(&worker_app)?,
+        };
+        let seconds = seconds.unwrap_or(DEFAULT_SECONDS).clamp(2.0, 60.0);
+        let runs = benchmark(&worker_app, &sample, start.unwrap_or(0.0).max(0.0), seconds, &cancel)?;
+        let best = runs.first().filter(|r| r.error.is_none());
+        Ok(BenchmarkResult {
+            sample: sample.to_string_lossy().to_string(),
+            recommended_hwdec: best.map(|r| r.hwdec.clone()),
+            recommended_vo: best.map(|r| r.vo.clone()),
+            runs,
+        })
+    })
+    .await
+    .map_err(|e| format!("Benchmark failed: {}", e))
+    .and_then(|r| r);
+    *state.cancel.lock().unwrap() = None;
+    result
+}
+
+#[command]
+pub fn cancel_decode_benchmark(state: State<'_, BenchmarkState>) {
+    if let Some(cancel) = state.cancel.lock().unwrap().as_ref() {
+        cancel.store(true, Ordering::SeqCst);
+    }
+}
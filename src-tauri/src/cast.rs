@@ -0,0 +1,616 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::net::{IpAddr, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{command, AppHandle, Emitter, State};
+
+// Chromecast support: mDNS discovery and the Cast v2 protocol (length-prefixed
+// protobuf CastMessages over TLS on port 8009) driving the Default Media Receiver,
+// which streams the file from `media_server`.
+
+const CAST_SERVICE: &str = "_googlecast._tcp.local.";
+const DEFAULT_MEDIA_RECEIVER: &str = "CC1AD845";
+const SENDER_ID: &str = "sender-0";
+const RECEIVER_ID: &str = "receiver-0";
+
+const NS_CONNECTION: &str = "urn:x-cast:com.google.cast.tp.connection";
+const NS_HEARTBEAT: &str = "urn:x-cast:com.google.cast.tp.heartbeat";
+const NS_RECEIVER: &str = "urn:x-cast:com.google.cast.receiver";
+const NS_MEDIA: &str = "urn:x-cast:com.google.cast.media";
+
+const STATUS_INTERVAL: Duration = Duration::from_secs(1);
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+const LAUNCH_TIMEOUT: Duration = Duration::from_secs(20);
+// The protocol caps a CastMessage at 64 KiB
+const MAX_FRAME: usize = 64 * 1024;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CastDevice {
+    pub id: String,
+    pub name: String,
+    pub model: Option<String>,
+    pub address: String,
+    pub port: u16,
+}
+
+/// Emitted as `cast-status` whenever the device reports a change.
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct CastStatus {
+    pub device: String,
+    pub path: String,
+    pub state: String, // IDLE | BUFFERING | PLAYING | PAUSED, as reported by the receiver
+    pub idle_reason: Option<String>, // FINISHED | CANCELLED | INTERRUPTED | ERROR once playback stops
+    pub position: f64,
+    pub duration: f64,
+    pub volume: f64, // 0.0 - 1.0
+}
+
+pub enum CastCommand {
+    Play,
+    Pause,
+    Seek(f64),
+    SetVolume(f64),
+    Stop,
+}
+
+pub struct CastState {
+    tx: Mutex<Option<Sender<CastCommand>>>,
+}
+
+impl CastState {
+    pub fn new() -> Self {
+        Self { tx: Mutex::new(None) }
+    }
+
+    fn send(&self, cmd: CastCommand) -> Result<(), String> {
+        let tx = self.tx.lock().map_err(|e| e.to_string())?;
+        tx.as_ref().ok_or("Nothing is being cast")?.send(cmd).map_err(|_| "The cast session has ended".to_string())
+    }
+}
+
+#[derive(Debug)]
+struct AcceptAnyCert(Arc<rustls::crypto::CryptoProvider>);
+
+// Cast devices present certificates chained to Google's device CA, not a web PKI root;
+// the connection only carries playback commands on the local network.
+impl rustls::client::danger::ServerCertVerifier for AcceptAnyCert {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &rustls::pki_types::CertificateDer<'_>,
+        _intermediates: &[rustls::pki_types::CertificateDer<'_>],
+        _server_name: &rustls::pki_types::ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: rustls::pki_types::UnixTime,
+    ) -> Result<rustls::client::danger::ServerCertVerified, rustls::Error> {
+        Ok(rustls::client::danger::ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &rustls::pki_types::CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &rustls::pki_types::CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+struct CastMessage {
+    source: String,
+    namespace: String,
+    payload: Value,
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn read_varint(data: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *data.get(*pos)?;
+        *pos += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// CastMessage protobuf: protocol_version=1, source_id=2, destination_id=3,
+/// namespace=4, payload_type=5 (0 = string), payload_utf8=6.
+fn encode_message(destination: &str, namespace: &str, payload: &str) -> Vec<u8> {
+    let mut out = vec![0x08, 0x00];
+    for (tag, value) in [(0x12u8, SENDER_ID), (0x1a, destination), (0x22, namespace)] {
+        out.push(tag);
+        write_varint(&mut out, value.len() as u64);
+        out.extend_from_slice(value.as_bytes());
+    }
+    out.extend_from_slice(&[0x28, 0x00, 0x32]);
+    write_varint(&mut out, payload.len() as u64);
+    out.extend_from_slice(payload.as_bytes());
+    out
+}
+
+/// Split one length-prefixed message off the front of `buffer` once all of it has arrived.
+fn take_frame(buffer: &mut Vec<u8>) -> Result<Option<Vec<u8>>, String> {
+    let Some(&[a, b, c, d]) = buffer.get(..4) else {
+        return Ok(None);
+    };
+    let len = u32::from_be_bytes([a, b, c, d]) as usize;
+    if len > MAX_FRAME {
+        return Err(format!("The cast device sent an oversized message ({} bytes)", len));
+    }
+    if buffer.len() < 4 + len {
+        return Ok(None);
+    }
+    let frame = buffer[4..4 + len].to_vec();
+    buffer.drain(..4 + len);
+    Ok(Some(frame))
+}
+
+fn decode_message(data: &[u8]) -> Option<CastMessage> {
+    let (mut pos, mut source, mut namespace, mut payload) = (0, String::new(), String::new(), String::new());
+    while pos < data.len() {
+        let key = read_varint(data, &mut pos)?;
+        match key & 7 {
+            0 => {
+                read_varint(data, &mut pos)?;
+            }
+            2 => {
+                let len = usize::try_from(read_varint(data, &mut pos)?).ok()?;
+                let bytes = data.get(pos..pos.checked_add(len)?)?;
+                pos += len;
+                let text = String::from_utf8_lossy(bytes).to_string();
+                match key >> 3 {
+                    2 => source = text,
+                    4 => namespace = text,
+                    6 => payload = text,
+                    _ => {}
+                }
+            }
+            _ => return None,
+        }
+    }
+    Some(CastMessage { source, namespace, payload: serde_json::from_str(&payload).unwrap_or(Value::Null) })
+}
+
+struct CastChannel {
+    tls: rustls::StreamOwned<rustls::ClientConnection, TcpStream>,
+    buffer: Vec<u8>,
+    request_id: u64,
+}
+
+impl CastChannel {
+    fn connect(address: IpAddr, port: u16) -> Result<Self, String> {
+        let provider = Arc::new(rustls::crypto::aws_lc_rs::default_provider());
+        let config = rustls::ClientConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()
+            .map_err(|e| e.to_string())?
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(AcceptAnyCert(provider)))
+            .with_no_client_auth();
+        let conn = rustls::ClientConnection::new(Arc::new(config), rustls::pki_types::ServerName::from(address))
+            .map_err(|e| e.to_string())?;
+        let socket = TcpStream::connect_timeout(&(address, port).into(), Duration::from_secs(5))
+            .map_err(|e| format!("Couldn't reach the cast device: {}", e))?;
+        // Short reads keep the session loop responsive to commands
+        socket.set_read_timeout(Some(Duration::from_millis(200))).map_err(|e| e.to_string())?;
+        Ok(Self { tls: rustls::StreamOwned::new(conn, socket), buffer: Vec::new(), request_id: 0 })
+    }
+
+    fn send(&mut self, destination: &str, namespace: &str, payload: Value) -> Result<(), String> {
+        let message = encode_message(destination, namespace, &payload.to_string());
+        let mut frame = (message.len() as u32).to_be_bytes().to_vec();
+        frame.extend_from_slice(&message);
+        self.tls.write_all(&frame).and_then(|_| self.tls.flush()).map_err(|e| format!("Cast connection lost: {}", e))
+    }
+
+    /// Like `send`, with a fresh `requestId` added to the payload.
+    fn request(&mut self, destination: &str, namespace: &str, mut payload: Value) -> Result<u64, String> {
+        self.request_id += 1;
+        payload["requestId"] = json!(self.request_id);
+        self.send(destination, namespace, payload)?;
+        Ok(self.request_id)
+    }
+
+    /// The next complete message, or `None` if nothing arrived within the read timeout.
+    /// Heartbeat pings are answered here.
+    fn poll(&mut self) -> Result<Option<CastMessage>, String> {
+        loop {
+            if let Some(frame) = take_frame(&mut self.buffer)? {
+                let Some(message) = decode_message(&frame) else {
+                    continue;
+                };
+                if message.namespace == NS_HEARTBEAT && message.payload["type"] == "PING" {
+                    self.send(&message.source.clone(), NS_HEARTBEAT, json!({ "type": "PONG" }))?;
+                    continue;
+                }
+                return Ok(Some(message));
+            }
+            let mut chunk = [0u8; 4096];
+            match self.tls.read(&mut chunk) {
+                Ok(0) => return Err("The cast device closed the connection".to_string()),
+                Ok(n) => self.buffer.extend_from_slice(&chunk[..n]),
+                Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => return Ok(None),
+                Err(e) => return Err(format!("Cast connection lost: {}", e)),
+            }
+        }
+    }
+
+    /// Read until `pick` accepts a message, answering pings along the way.
+    fn wait_for<T>(&mut self, mut pick: impl FnMut(&CastMessage) -> Option<Result<T, String>>) -> Result<T, String> {
+        let deadline = Instant::now() + LAUNCH_TIMEOUT;
+        while Instant::now() < deadline {
+            if let Some(message) = self.poll()? {
+                if let Some(result) = pick(&message) {
+                    return result;
+                }
+            }
+        }
+        Err("The cast device didn't respond".to_string())
+    }
+}
+
+struct CastSession {
+    channel: CastChannel,
+    transport_id: String,
+    session_id: String,
+    media_session_id: Option<i64>,
+}
+
+impl CastSession {
+    /// Start the Default Media Receiver on the device and load `url` into it.
+    fn start(device: &CastDevice, url: &str, path: &Path, start_position: f64) -> Result<Self, String> {
+        let address: IpAddr = device.address.parse().map_err(|_| format!("Invalid device address '{}'", device.address))?;
+        let mut channel = CastChannel::connect(address, device.port)?;
+        channel.send(RECEIVER_ID, NS_CONNECTION, json!({ "type": "CONNECT" }))?;
+        channel.request(RECEIVER_ID, NS_RECEIVER, json!({ "type": "LAUNCH", "appId": DEFAULT_MEDIA_RECEIVER }))?;
+
+        let (transport_id, session_id) = channel.wait_for(|m| {
+            match m.payload["type"].as_str()? {
+                "RECEIVER_STATUS" => {
+                    let app = m.payload["status"]["applications"]
+                        .as_array()?
+                        .iter()
+                        .find(|a| a["appId"] == DEFAULT_MEDIA_RECEIVER)?;
+                    Some(Ok((app["transportId"].as_str()?.to_string(), app["sessionId"].as_str()?.to_string())))
+                }
+                "LAUNCH_ERROR" => Some(Err(format!("The device refused to start the player: {}", m.payload["reason"]))),
+                _ => None,
+            }
+        })?;
+
+        channel.send(&transport_id, NS_CONNECTION, json!({ "type": "CONNECT" }))?;
+        let title = crate::metadata::clean_video_name(path.file_name().and_then(|n| n.to_str()).unwrap_or_default());
+        let load = channel.request(&transport_id, NS_MEDIA, json!({
+            "type": "LOAD",
+            "media": {
+                "contentId": url,
+                "contentType": crate::media_server::content_type(path),
+                "streamType": "BUFFERED",
+                "metadata": { "metadataType": 0, "title": title },
+            },
+            "autoplay": true,
+            "currentTime": start_position,
+        }))?;
+        let media_session_id = channel.wait_for(|m| {
+            if m.namespace != NS_MEDIA || m.payload["requestId"] != load {
+                return None;
+            }
+            match m.payload["type"].as_str()? {
+                "MEDIA_STATUS" => Some(Ok(m.payload["status"][0]["mediaSessionId"].as_i64())),
+                "LOAD_FAILED" | "LOAD_CANCELLED" | "INVALID_REQUEST" => {
+                    Some(Err("The device couldn't play this file (unsupported format?)".to_string()))
+                }
+                _ => None,
+            }
+        })?;
+
+        Ok(Self { channel, transport_id, session_id, media_session_id })
+    }
+
+    fn media_command(&mut self, kind: &str, extra: Value) -> Result<(), String> {
+        let Some(id) = self.media_session_id else {
+            return Ok(());
+        };
+        let mut payload = json!({ "type": kind, "mediaSessionId": id });
+        if let (Some(payload), Some(extra)) = (payload.as_object_mut(), extra.as_object()) {
+            payload.extend(extra.clone());
+        }
+        self.channel.request(&self.transport_id.clone(), NS_MEDIA, payload).map(|_| ())
+    }
+
+    fn stop(&mut self) {
+        let _ = self.media_command("STOP", json!({}));
+        let session_id = self.session_id.clone();
+        let _ = self.channel.request(RECEIVER_ID, NS_RECEIVER, json!({ "type": "STOP", "sessionId": session_id }));
+        let _ = self.channel.send(RECEIVER_ID, NS_CONNECTION, json!({ "type": "CLOSE" }));
+    }
+}
+
+fn run_session(app: AppHandle, mut session: CastSession, mut status: CastStatus, rx: Receiver<CastCommand>) {
+    let mut autosave = crate::watch_history::PositionAutosave::new();
+    autosave.start(&app, Some(status.path.clone()));
+    let mut last_status = Instant::now();
+    let mut last_heartbeat = Instant::now();
+
+    let result: Result<(), String> = (|| loop {
+        while let Ok(cmd) = rx.try_recv() {
+            match cmd {
+                CastCommand::Play => session.media_command("PLAY", json!({}))?,
+                CastCommand::Pause => session.media_command("PAUSE", json!({}))?,
+                CastCommand::Seek(position) => session.media_command("SEEK", json!({ "currentTime": position }))?,
+                CastCommand::SetVolume(level) => {
+                    session.channel.request(RECEIVER_ID, NS_RECEIVER, json!({ "type": "SET_VOLUME", "volume": { "level": level.clamp(0.0, 1.0) } }))?;
+                }
+                CastCommand::Stop => {
+                    session.stop();
+                    status.state = "IDLE".to_string();
+                    status.idle_reason = Some("CANCELLED".to_string());
+                    return Ok(());
+                }
+            }
+        }
+
+        // The receiver only reports on changes, so ask for the position every second
+        if last_status.elapsed() >= STATUS_INTERVAL {
+            session.media_command("GET_STATUS", json!({}))?;
+            last_status = Instant::now();
+        }
+        if last_heartbeat.elapsed() >= HEARTBEAT_INTERVAL {
+            session.channel.send(RECEIVER_ID, NS_HEARTBEAT, json!({ "type": "PING" }))?;
+            last_heartbeat = Instant::now();
+        }
+
+        let Some(message) = session.channel.poll()? else {
+            continue;
+        };
+        match message.payload["type"].as_str().unwrap_or_default() {
+            "MEDIA_STATUS" => {
+                let Some(media) = message.payload["status"].get(0) else {
+                    continue;
+                };
+                if let Some(id) = media["mediaSessionId"].as_i64() {
+                    session.media_session_id = Some(id);
+                }
+                status.state = media["playerState"].as_str().unwrap_or(&status.state).to_string();
+                status.position = media["currentTime"].as_f64().unwrap_or(status.position);
+                status.duration = media["media"]["duration"].as_f64().unwrap_or(status.duration);
+                status.idle_reason = media["idleReason"].as_str().map(String::from);
+                let _ = app.emit("cast-status", &status);
+                autosave.progress(&app, status.position, status.duration);
+                if status.state == "PAUSED" {
+                    autosave.flush(&app);
+                }
+                if status.state == "IDLE" && status.idle_reason.is_some() {
+                    // Finished, or stopped from the device or another sender
+                    if status.idle_reason.as_deref() == Some("FINISHED") {
+                        status.position = status.duration;
+                        autosave.progress(&app, status.position, status.duration);
+                    }
+                    session.stop();
+                    return Ok(());
+                }
+            }
+            "RECEIVER_STATUS" => {
+                if let Some(level) = message.payload["status"]["volume"]["level"].as_f64() {
+                    status.volume = level;
+                }
+                let running = message.payload["status"]["applications"]
+                    .as_array()
+                    .is_some_and(|apps| apps.iter().any(|a| a["sessionId"] == session.session_id.as_str()));
+                if !running {
+                    status.state = "IDLE".to_string();
+                    status.idle_reason = Some("INTERRUPTED".to_string());
+                    return Ok(());
+                }
+                let _ = app.emit("cast-status", &status);
+            }
+            "CLOSE" if message.source == session.transport_id => {
+                status.state = "IDLE".to_string();
+                status.idle_reason = Some("INTERRUPTED".to_string());
+                return Ok(());
+            }
+            _ => {}
+        }
+    })();
+
+    if let Err(e) = result {
+        tracing::error!("❌ Cast session ended: {}", e);
+        status.state = "IDLE".to_string();
+        status.idle_reason = Some("ERROR".to_string());
+    }
+    autosave.stop(&app);
+    let _ = app.emit("cast-status", &status);
+    tracing::info!("📺 Cast session on {} ended", status.device);
+}
+
+/// Chromecasts (and Cast-enabled TVs/speakers) answering on the local network.
+#[command]
+pub async fn discover_cast_devices(timeout_ms: Option<u64>) -> Result<Vec<CastDevice>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let daemon = mdns_sd::ServiceDaemon::new().map_err(|e| format!("mDNS unavailable: {}", e))?;
+        let events = daemon.browse(CAST_SERVICE).map_err(|e| e.to_string())?;
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms.unwrap_or(3000));
+        let mut devices: Vec<CastDevice> = Vec::new();
+        while let Ok(event) = events.recv_deadline(deadline) {
+            let mdns_sd::ServiceEvent::ServiceResolved(info) = event else {
+                continue;
+            };
+            // Prefer IPv4; the media server URL has to be reachable from the device
+            let address = info.get_addresses_v4().into_iter().next().map(IpAddr::V4)
+                .or_else(|| info.get_addresses().iter().next().map(|a| a.to_ip_addr()));
+            let Some(address) = address else {
+                continue;
+            };
+            let id = info.get_property_val_str("id").unwrap_or(info.get_fullname()).to_string();
+            if devices.iter().any(|d| d.id == id) {
+                continue;
+            }
+            devices.push(CastDevice {
+                id,
+                name: info.get_property_val_str("fn").unwrap_or(info.get_hostname()).to_string(),
+                model: info.get_property_val_str("md").map(String::from),
+                address: address.to_string(),
+                port: info.get_port(),
+            });
+        }
+        let _ = daemon.shutdown();
+        tracing::info!("📺 Found {} cast device(s)", devices.len());
+        Ok(devices)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Cast a local file, resuming from `start_position` (seconds). Progress arrives as
+/// `cast-status` events and is saved to watch history like local playback.
+#[command]
+pub async fn cast_load(
+    app: AppHandle,
+    state: State<'_, CastState>,
+    device: CastDevice,
+    path: String,
+    start_position: Option<f64>,
+) -> Result<(), String> {
+    // Casting is playback too
+    crate::parental::Guard::new(&app).check_playable(&path)?;
+    // One session at a time
+    if let Some(tx) = state.tx.lock().map_err(|e| e.to_string())?.take() {
+        let _ = tx.send(CastCommand::Stop);
+    }
+
+    let file = PathBuf::from(&path);
+    let address: IpAddr = device.address.parse().map_err(|_| format!("Invalid device address '{}'", device.address))?;
+    let url = crate::media_server::share_file(&file, crate::media_server::local_ip_for(address)?)?;
+    let start = start_position.unwrap_or(0.0).max(0.0);
+    let name = device.name.clone();
+    let receiver_url = url.clone();
+    let session = tauri::async_runtime::spawn_blocking(move || CastSession::start(&device, &receiver_url, &file, start))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r)
+        .inspect_err(|_| crate::media_server::revoke_share(&url))?;
+    tracing::info!("📺 Casting {} to {}", path, name);
+
+    let (tx, rx) = channel();
+    *state.tx.lock().map_err(|e| e.to_string())? = Some(tx);
+    let status = CastStatus { device: name, path, state: "BUFFERING".to_string(), position: start, volume: 1.0, ..Default::default() };
+    std::thread::spawn(move || {
+        run_session(app, session, status, rx);
+        crate::media_server::revoke_share(&url);
+    });
+    Ok(())
+}
+
+#[command]
+pub fn cast_play(state: State<'_, CastState>) -> Result<(), String> {
+    state.send(CastCommand::Play)
+}
+
+#[command]
+pub fn cast_pause(state: State<'_, CastState>) -> Result<(), String> {
+    state.send(CastCommand::Pause)
+}
+
+/// Seek to an absolute position in seconds.
+#[command]
+pub fn cast_seek(state: State<'_, CastState>, position: f64) -> Result<(), String> {
+    state.send(CastCommand::Seek(position.max(0.0)))
+}
+
+/// Device volume, 0.0 - 1.0.
+#[command]
+pub fn cast_set_volume(state: State<'_, CastState>, level: f64) -> Result<(), String> {
+    state.send(CastCommand::SetVolume(level))
+}
+
+#[command]
+pub fn cast_stop(state: State<'_, CastState>) -> Result<(), String> {
+    let tx = state.tx.lock().map_err(|e| e.to_string())?.take();
+    if let Some(tx) = tx {
+        let _ = tx.send(CastCommand::Stop);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn varints() {
+        let mut out = Vec::new();
+        write_varint(&mut out, 300);
+        assert_eq!(out, [0xac, 0x02]);
+        let mut pos = 0;
+        assert_eq!(read_varint(&out, &mut pos), Some(300));
+        assert_eq!(pos, 2);
+
+        // Truncated: the continuation bit promises another byte
+        assert_eq!(read_varint(&[0xac], &mut 0), None);
+        assert_eq!(read_varint(&[], &mut 0), None);
+        // More than ten bytes can't be a u64
+        assert_eq!(read_varint(&[0xff; 11], &mut 0), None);
+    }
+
+    #[test]
+    fn message_round_trip() {
+        let data = encode_message(RECEIVER_ID, NS_MEDIA, r#"{"type":"GET_STATUS","requestId":3}"#);
+        let message = decode_message(&data).unwrap();
+        assert_eq!(message.source, SENDER_ID);
+        assert_eq!(message.namespace, NS_MEDIA);
+        assert_eq!(message.payload["type"], "GET_STATUS");
+        assert_eq!(message.payload["requestId"], 3);
+    }
+
+    #[test]
+    fn malformed_messages() {
+        let data = encode_message(RECEIVER_ID, NS_MEDIA, r#"{"type":"PING"}"#);
+        assert!(decode_message(&data[..data.len() - 3]).is_none());
+        // A string length running past the end, or past usize, is rejected rather than panicking
+        assert!(decode_message(&[0x32, 0x05, b'a']).is_none());
+        assert!(decode_message(&[0x32, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]).is_none());
+        // Unsupported wire type
+        assert!(decode_message(&[0x0d, 0, 0, 0, 0]).is_none());
+    }
+
+    #[test]
+    fn frames() {
+        let mut buffer = vec![0, 0, 0];
+        assert_eq!(take_frame(&mut buffer), Ok(None));
+        buffer.extend_from_slice(&[3, b'a', b'b']);
+        assert_eq!(take_frame(&mut buffer), Ok(None));
+        buffer.extend_from_slice(&[b'c', 0, 0]);
+        assert_eq!(take_frame(&mut buffer), Ok(Some(b"abc".to_vec())));
+        assert_eq!(buffer, [0, 0]);
+
+        let mut oversized = ((MAX_FRAME + 1) as u32).to_be_bytes().to_vec();
+        assert!(take_frame(&mut oversized).is_err());
+        let mut largest = (MAX_FRAME as u32).to_be_bytes().to_vec();
+        assert_eq!(take_frame(&mut largest), Ok(None));
+    }
+}
@@ -0,0 +1,278 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Child;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{command, AppHandle, Emitter, Manager, State};
+
+use crate::config::{load_config, ClipSettings};
+
+pub const FORMATS: &[&str] = &["copy", "mp4", "gif", "webp"];
+pub const PRESETS: &[&str] = &["small", "medium", "large"];
+const MAX_CLIP_SECS: f64 = 10.0 * 60.0;
+// Animated images get big fast; keep them short
+const MAX_ANIMATION_SECS: f64 = 30.0;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ClipItem {
+    pub id: String,
+    pub video: String,
+    pub start: f64,
+    pub end: f64,
+    pub format: String, // "copy" (lossless cut) | "mp4" | "gif" | "webp"
+    pub preset: String, // "small" | "medium" | "large"
+    pub status: String, // "encoding" | "done" | "failed" | "cancelled"
+    pub progress: f32,  // 0-100
+    pub output: String,
+    pub error: Option<String>,
+}
+
+/// A clip being exported, shared between its thread and `cancel_clip`.
+pub struct ClipJob {
+    item: Mutex<ClipItem>,
+    cancelled: AtomicBool,
+    child: Mutex<Option<Child>>,
+}
+
+impl ClipJob {
+    fn snapshot(&self) -> ClipItem {
+        self.item.lock().unwrap().clone()
+    }
+
+    /// Change the item and emit `clip-progress` with the result.
+    fn update(&self, app: &AppHandle, f: impl FnOnce(&mut ClipItem)) {
+        let item = {
+            let mut item = self.item.lock().unwrap();
+            f(&mut item);
+            item.clone()
+        };
+        let _ = app.emit("clip-progress", item);
+    }
+}
+
+pub struct ClipState {
+    jobs: Mutex<Vec<Arc<ClipJob>>>,
+}
+
+impl ClipState {
+    pub fn new() -> Self {
+        Self { jobs: Mutex::new(Vec::new()) }
+    }
+}
+
+fn output_dir(app: &AppHandle, settings: &ClipSettings) -> Result<PathBuf, String> {
+    match &settings.output_dir {
+        Some(dir) => Ok(PathBuf::from(dir)),
+        None => Ok(app.path().video_dir().map_err(|e| e.to_string())?.join("FrameX Clips")),
+    }
+}
+
+/// Seconds as "1-02-03" (or "02-03" under an hour), safe in file names.
+fn file_timestamp(secs: f64) -> String {
+    let s = secs.max(0.0) as u64;
+    if s >= 3600 {
+        format!("{}-{:02}-{:02}", s / 3600, (s % 3600) / 60, s % 60)
+    } else {
+        format!("{:02}-{:02}", s / 60, s % 60)
+    }
+}
+
+/// ffmpeg arguments after the input for one format and preset, and the file extension.
+fn encode_args(format: &str, preset: &str, source_ext: &str) -> (Vec<String>, String) {
+    let (height, crf, fps, width, quality) = match preset {
+        "small" => (480, 28, 10, 320, 60),
+        "large" => (1080, 18, 15, 640, 90),
+        _ => (720, 23, 12, 480, 75),
+    };
+    let args: Vec<String> = match format {
+        // Cuts land on the nearest keyframes, but nothing is re-encoded
+        "copy" => vec!["-c", "copy", "-avoid_negative_ts", "make_zero"].into_iter().map(String::from).collect(),
+        "gif" => vec![
+            "-an".to_string(),
+            "-vf".to_string(),
+            format!("fps={},scale={}:-1:flags=lanczos,split[a][b];[a]palettegen[p];[b][p]paletteuse", fps, width),
+            "-loop".to_string(),
+            "0".to_string(),
+        ],
+        "webp" => vec![
+            "-an".to_string(),
+            "-vf".to_string(),
+            format!("fps={},scale={}:-1:flags=lanczos", fps, width),
+            "-c:v".to_string(),
+            "libwebp".to_string(),
+            "-quality".to_string(),
+            quality.to_string(),
+            "-loop".to_string(),
+            "0".to_string(),
+        ],
+        _ => vec![
+            "-vf".to_string(),
+            format!("scale=-2:'min({},ih)'", height),
+            "-c:v".to_string(),
+            "libx264".to_string(),
+            "-preset".to_string(),
+            "veryfast".to_string(),
+            "-crf".to_string(),
+            crf.to_string(),
+            "-pix_fmt".to_string(),
+            "yuv420p".to_string(),
+            "-c:a".to_string(),
+            "aac".to_string(),
+            "-b:a".to_string(),
+            "128k".to_string(),
+            "-movflags".to_string(),
+            "+faststart".to_string(),
+        ],
+    };
+    let ext = match format {
+        "copy" => source_ext,
+        "gif" => "gif",
+        "webp" => "webp",
+        _ => "mp4",
+    };
+    (args, ext.to_string())
+}
+
+/// Container for lossless cuts: the source's own, or Matroska for streams without one.
+fn source_ext(video: &str) -> String {
+    Path::new(video)
+        .extension()
+        .and_then(|e| e.to_str())
+        .filter(|e| !video.contains("://") && e.len() <= 4 && e.chars().all(|c| c.is_ascii_alphanumeric()))
+        .unwrap_or("mkv")
+        .to_lowercase()
+}
+
+fn run_clip(app: &AppHandle, job: &ClipJob, input: &str) -> Result<(), String> {
+    let item = job.snapshot();
+    if job.cancelled.load(Ordering::SeqCst) {
+        return Err("Cancelled".to_string());
+    }
+    let (args, _) = encode_args(&item.format, &item.preset, &source_ext(&item.video));
+    let mut command = crate::ffmpeg::command();
+    command
+        .arg("-ss").arg(format!("{:.3}", item.start))
+        .arg("-t").arg(format!("{:.3}", item.end - item.start))
+        .arg("-i").arg(input)
+        .args(&args)
+        .arg(&item.output);
+
+    let mut last_emitted = -1.0;
+    crate::ffmpeg::run_with_progress(command, item.end - item.start, &job.child, |percent| {
+        if percent - last_emitted >= 1.0 {
+            last_emitted = percent;
+            job.update(app, |item| item.progress = percent);
+        }
+    })
+}
+
+/// Cut `start`..`end` of a video into the clips folder as a background job; progress
+/// arrives as `clip-progress` events.
+#[command]
+pub fn export_clip(
+    app: AppHandle,
+    state: State<'_, ClipState>,
+    video: String,
+    start: f64,
+    end: f64,
+    format: Option<String>,
+    preset: Option<String>,
+) -> Result<ClipItem, String> {
+    let settings = load_config(&app).clips;
+    let format = format.unwrap_or_else(|| settings.default_format.clone());
+    let preset = preset.unwrap_or_else(|| settings.default_preset.clone());
+    if !FORMATS.contains(&format.as_str()) {
+        return Err(format!("Unknown clip format '{}'", format));
+    }
+    if !PRESETS.contains(&preset.as_str()) {
+        return Err(format!("Unknown clip preset '{}'", preset));
+    }
+    let (start, end) = (start.max(0.0), end);
+    if end - start < 0.5 {
+        return Err("The clip must be at least half a second long".to_string());
+    }
+    let limit = if matches!(format.as_str(), "gif" | "webp") { MAX_ANIMATION_SECS } else { MAX_CLIP_SECS };
+    if end - start > limit {
+        return Err(format!("Clips in this format can be at most {} seconds long", limit));
+    }
+
+    let dir = output_dir(&app, &settings)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let source = Path::new(&video);
+    let stem = source.file_stem().and_then(|s| s.to_str()).unwrap_or("clip");
+    let (_, ext) = encode_args(&format, &preset, &source_ext(&video));
+    let name = format!("{} {}-{}", stem, file_timestamp(start), file_timestamp(end));
+    let output = crate::ffmpeg::unique_output(&dir, &name, &ext)?;
+
+    let item = ClipItem {
+        id: uuid::Uuid::new_v4().to_string(),
+        video: video.clone(),
+        start,
+        end,
+        format,
+        preset,
+        status: "encoding".to_string(),
+        progress: 0.0,
+        output: output.to_string_lossy().to_string(),
+        error: None,
+    };
+    let job = Arc::new(ClipJob { item: Mutex::new(item.clone()), cancelled: AtomicBool::new(false), child: Mutex::new(None) });
+    state.jobs.lock().map_err(|e| e.to_string())?.push(job.clone());
+    let _ = app.emit("clip-progress", item.clone());
+
+    let input = crate::network_sources::playable_path(&app, &video);
+    std::thread::spawn(move || {
+        let result = run_clip(&app, &job, &input);
+        let cancelled = job.cancelled.load(Ordering::SeqCst);
+        if result.is_err() || cancelled {
+            let _ = fs::remove_file(&job.snapshot().output);
+        }
+        match result {
+            Ok(()) if !cancelled => {
+                tracing::info!("🎬 Exported clip {}", job.snapshot().output);
+                job.update(&app, |item| {
+                    item.status = "done".to_string();
+                    item.progress = 100.0;
+                });
+            }
+            Ok(()) => job.update(&app, |item| item.status = "cancelled".to_string()),
+            Err(e) => {
+                if !cancelled {
+                    tracing::error!("Clip export failed: {}", e);
+                }
+                job.update(&app, |item| {
+                    item.status = if cancelled { "cancelled" } else { "failed" }.to_string();
+                    item.error = (!cancelled).then_some(e);
+                });
+            }
+        }
+    });
+    Ok(item)
+}
+
+#[command]
+pub fn list_clips(state: State<'_, ClipState>) -> Result<Vec<ClipItem>, String> {
+    Ok(state.jobs.lock().map_err(|e| e.to_string())?.iter().map(|j| j.snapshot()).collect())
+}
+
+/// Stop an export, killing ffmpeg. The partial file is removed.
+#[command]
+pub fn cancel_clip(state: State<'_, ClipState>, id: String) -> Result<bool, String> {
+    let job = state.jobs.lock().map_err(|e| e.to_string())?.iter().find(|j| j.snapshot().id == id).cloned();
+    let Some(job) = job.filter(|j| j.snapshot().status == "encoding") else {
+        return Ok(false);
+    };
+    job.cancelled.store(true, Ordering::SeqCst);
+    if let Some(child) = job.child.lock().map_err(|e| e.to_string())?.as_mut() {
+        let _ = child.kill();
+    }
+    Ok(true)
+}
+
+/// Drop finished, failed and cancelled exports from the list.
+#[command]
+pub fn clear_finished_clips(state: State<'_, ClipState>) -> Result<(), String> {
+    state.jobs.lock().map_err(|e| e.to_string())?.retain(|j| j.snapshot().status == "encoding");
+    Ok(())
+}
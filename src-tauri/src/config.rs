@@ -7,40 +7,532 @@ use tauri::Manager;
 #[derive(Serialize, Deserialize, Debug, Default)]
 pub struct AppConfig {
     pub last_opened_folder: Option<String>,
+    #[serde(default)]
+    pub last_played_video: Option<String>,
+    #[serde(default)]
+    pub startup: StartupOptions,
+    #[serde(default)]
+    pub window_state: Option<WindowState>,
+    #[serde(default)]
+    pub credentials: ProviderCredentials,
+    #[serde(default)]
+    pub ai: AiSettings,
+    #[serde(default)]
+    pub metadata: MetadataSettings,
+    #[serde(default)]
+    pub sync: SyncSettings,
+    #[serde(default)]
+    pub opensubtitles: Option<OpenSubtitlesSession>,
+    #[serde(default)]
+    pub subtitles: SubtitleSettings,
+    #[serde(default)]
+    pub presence: PresenceSettings,
+    #[serde(default)]
+    pub private_mode: bool, // nothing is added to watch history or broadcast while on
+    #[serde(default)]
+    pub network_sources: Vec<crate::network_sources::NetworkSource>,
+    #[serde(default)]
+    pub torrents: TorrentSettings,
+    #[serde(default)]
+    pub downloads: DownloadSettings,
+    #[serde(default)]
+    pub iptv: IptvSettings,
+    #[serde(default)]
+    pub radio_stations: Vec<crate::radio::RadioStation>,
+    #[serde(default)]
+    pub podcasts: PodcastSettings,
+    #[serde(default)]
+    pub global_hotkeys: GlobalHotkeySettings,
+    #[serde(default)]
+    pub watch_party: WatchPartySettings,
+    #[serde(default)]
+    pub anime_sync: AnimeSyncSettings,
+    #[serde(default)]
+    pub lastfm: LastfmSettings,
+    #[serde(default)]
+    pub segments: SegmentSettings,
+    #[serde(default)]
+    pub clips: ClipSettings,
+    #[serde(default)]
+    pub conversions: ConversionSettings,
+    #[serde(default)]
+    pub log_level: Option<String>, // one of `logging::LEVELS`; info when unset
+    #[serde(default)]
+    pub parental: ParentalSettings,
+    #[serde(default)]
+    pub updates: UpdateSettings,
+}
+
+/// A folder path or collection id kept from children; hidden ones also disappear from listings.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ParentalLock {
+    pub target: String,
+    pub hide: bool,
+}
+
+/// Parental controls (see `parental`); nothing is restricted until a PIN is set.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ParentalSettings {
+    pub pin_hash: Option<String>, // hex sha256 of salt + PIN
+    pub pin_salt: String,
+    pub locked_folders: Vec<ParentalLock>,
+    pub locked_collections: Vec<ParentalLock>,
+    pub max_age: Option<u8>,          // block content rated for older viewers; None allows everything
+    pub protected_areas: Vec<String>, // see `parental::AREAS`
+    pub unlock_minutes: u32,
+}
+
+impl Default for ParentalSettings {
+    fn default() -> Self {
+        Self {
+            pin_hash: None,
+            pin_salt: String::new(),
+            locked_folders: Vec::new(),
+            locked_collections: Vec::new(),
+            max_age: None,
+            protected_areas: vec!["settings".to_string()],
+            unlock_minutes: 15,
+        }
+    }
+}
+
+/// Batch conversion output (see `conversions`).
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ConversionSettings {
+    pub output_dir: Option<String>, // defaults to next to each source file
+}
+
+/// Where exported clips go and the format picked by default (see `clips::FORMATS`).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ClipSettings {
+    pub output_dir: Option<String>, // defaults to <Videos>/FrameX Clips
+    pub default_format: String,
+    pub default_preset: String,
+}
+
+impl Default for ClipSettings {
+    fn default() -> Self {
+        Self { output_dir: None, default_format: "mp4".to_string(), default_preset: "medium".to_string() }
+    }
+}
+
+/// What to do on reaching a skip segment, per kind (see `segments::KINDS`):
+/// "skip" jumps past it, "prompt" shows a skip button, "ignore" does nothing.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SegmentSettings {
+    pub actions: std::collections::BTreeMap<String, String>,
+    pub sponsorblock: bool, // look up community segments for YouTube videos
+}
+
+impl Default for SegmentSettings {
+    fn default() -> Self {
+        let actions = [("intro", "prompt"), ("recap", "prompt"), ("credits", "prompt"), ("preview", "prompt"), ("sponsor", "skip")]
+            .into_iter()
+            .map(|(kind, action)| (kind.to_string(), action.to_string()))
+            .collect();
+        Self { actions, sponsorblock: true }
+    }
+}
+
+/// Last.fm scrobbling; the API key and secret are from an API account the user creates.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct LastfmSettings {
+    pub enabled: bool,
+    pub api_key: String,
+    pub api_secret: String,
+    pub session: Option<LastfmSession>,
+}
+
+impl Default for LastfmSettings {
+    fn default() -> Self {
+        Self { enabled: true, api_key: String::new(), api_secret: String::new(), session: None }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LastfmSession {
+    pub username: String,
+    pub key: String,
+}
+
+/// Episode progress sync to AniList / MyAnimeList (see `anime_sync`). The client IDs are
+/// from apps the user registers with each service.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct AnimeSyncSettings {
+    pub enabled: bool,
+    pub threshold_percent: u32, // an episode counts as watched past this much of it
+    pub anilist_client_id: String,
+    pub mal_client_id: String,
+    pub anilist: Option<AnimeAccount>,
+    pub mal: Option<AnimeAccount>,
+}
+
+impl Default for AnimeSyncSettings {
+    fn default() -> Self {
+        Self { enabled: true, threshold_percent: 85, anilist_client_id: String::new(), mal_client_id: String::new(), anilist: None, mal: None }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AnimeAccount {
+    pub username: String,
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_at: Option<u64>, // Unix seconds
+}
+
+/// Watch party identity and the last room joined, so the dialog can prefill them.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct WatchPartySettings {
+    pub display_name: String,
+    pub port: u16, // relay port when hosting, and the default when joining
+    pub last_address: String,
+    pub last_room: String,
+}
+
+impl Default for WatchPartySettings {
+    fn default() -> Self {
+        Self { display_name: String::new(), port: 8999, last_address: String::new(), last_room: String::new() }
+    }
+}
+
+/// System-wide hotkeys: action (see `hotkeys::ACTIONS`) -> accelerator.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct GlobalHotkeySettings {
+    pub enabled: bool,
+    pub bindings: std::collections::BTreeMap<String, String>,
+}
+
+impl Default for GlobalHotkeySettings {
+    fn default() -> Self {
+        let mut bindings: std::collections::BTreeMap<String, String> = [
+            ("seek_forward", "Alt+Shift+Right"),
+            ("seek_backward", "Alt+Shift+Left"),
+            ("volume_up", "Alt+Shift+Up"),
+            ("volume_down", "Alt+Shift+Down"),
+            ("boss_key", "Alt+Shift+H"),
+        ]
+        .into_iter()
+        .map(|(action, key)| (action.to_string(), key.to_string()))
+        .collect();
+        // On Linux media keys already reach FrameX through MPRIS; grabbing them too would
+        // toggle twice
+        if !cfg!(target_os = "linux") {
+            for (action, key) in [("play_pause", "MediaPlayPause"), ("stop", "MediaStop"), ("next", "MediaTrackNext"), ("previous", "MediaTrackPrevious")] {
+                bindings.insert(action.to_string(), key.to_string());
+            }
+        }
+        Self { enabled: true, bindings }
+    }
+}
+
+/// Podcast feed refreshing and episode downloads.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct PodcastSettings {
+    pub refresh_hours: u64, // 0 turns background refreshing off
+    pub download_dir: Option<String>, // defaults to <app data>/podcasts
+}
+
+impl Default for PodcastSettings {
+    fn default() -> Self {
+        Self { refresh_hours: 6, download_dir: None }
+    }
+}
+
+/// How often `updates` looks for a newer release on GitHub.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct UpdateSettings {
+    pub check_hours: u64, // 0 turns background checks off
+    pub include_prereleases: bool,
+    pub skipped_version: Option<String>, // no `update-available` for this one
+    pub last_check: u64, // unix seconds, kept by the checker
+}
+
+impl Default for UpdateSettings {
+    fn default() -> Self {
+        Self { check_hours: 24, include_prereleases: false, skipped_version: None, last_check: 0 }
+    }
+}
+
+/// Buffering for live IPTV streams.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct IptvSettings {
+    pub readahead_secs: u32, // how far ahead mpv buffers a live stream
+    pub cache_mb: u32, // demuxer cache cap
+    pub epg_refresh_hours: u64, // downloaded guides are reused until this old
+}
+
+impl Default for IptvSettings {
+    fn default() -> Self {
+        Self { readahead_secs: 20, cache_mb: 64, epg_refresh_hours: 12 }
+    }
+}
+
+/// yt-dlp downloads into the library.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct DownloadSettings {
+    pub output_dir: Option<String>, // defaults to <Videos>/FrameX
+    pub ytdlp_path: Option<String>, // defaults to `yt-dlp` on PATH
+    pub default_format: String, // yt-dlp format selector
+    pub import_metadata: bool, // turn the thumbnail into the poster and info.json into an .nfo
+}
+
+impl Default for DownloadSettings {
+    fn default() -> Self {
+        Self {
+            output_dir: None,
+            ytdlp_path: None,
+            default_format: "bv*+ba/b".to_string(),
+            import_metadata: true,
+        }
+    }
+}
+
+/// Where streamed torrents are cached and how much bandwidth they may use.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct TorrentSettings {
+    pub cache_dir: Option<String>, // defaults to <app data>/torrents
+    pub max_cache_gb: f64, // older finished downloads are evicted past this; 0 keeps everything
+    pub download_limit_kbps: u32, // 0 = unlimited
+    pub upload_limit_kbps: u32,
+    pub delete_on_close: bool, // drop a torrent's files once it's removed
+}
+
+impl Default for TorrentSettings {
+    fn default() -> Self {
+        Self { cache_dir: None, max_cache_gb: 10.0, download_limit_kbps: 0, upload_limit_kbps: 0, delete_on_close: false }
+    }
+}
+
+/// What Discord Rich Presence may reveal.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct PresenceSettings {
+    pub client_id: String, // Discord application ID whose assets (framex_logo, play, pause) are shown
+    pub hide_filenames: bool, // "Watching a movie" instead of the title
+    pub excluded_folders: Vec<String>, // absolute paths, or a bare name like "Private" to match any folder
+    pub follow_private_mode: bool,
+    // `render_template` placeholders: {title} {show} {season} {episode} {year} {filename} {status} {duration} {track}
+    pub details_template: String,
+    pub state_template: String,
+}
+
+impl Default for PresenceSettings {
+    fn default() -> Self {
+        Self {
+            client_id: String::new(),
+            hide_filenames: false,
+            excluded_folders: Vec::new(),
+            follow_private_mode: true,
+            details_template: "{show}|{title} ({year})|{title}".to_string(),
+            state_template: "S{season}E{episode} · {status}|{status}".to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SubtitleSettings {
+    pub search_cache_hours: u64, // identical searches within this window reuse the last results; 0 disables
+}
+
+impl Default for SubtitleSettings {
+    fn default() -> Self {
+        Self { search_cache_hours: 24 }
+    }
+}
+
+/// A logged-in OpenSubtitles account; its token raises the daily download quota.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OpenSubtitlesSession {
+    pub username: String,
+    pub token: String,
+    pub base_url: String, // API host for this account, e.g. vip-api.opensubtitles.com for VIPs
+    pub logged_in_at: u64,
+}
+
+/// API keys for third-party metadata/subtitle providers, supplied by the user.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ProviderCredentials {
+    pub tmdb_api_key: Option<String>,
+    pub libretranslate_api_key: Option<String>,
+    pub omdb_api_key: Option<String>,
+    pub opensubtitles_api_key: Option<String>,
+    pub jimaku_api_key: Option<String>,
+}
+
+/// Endpoints for the optional AI features (translation, summaries).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct AiSettings {
+    pub llm_endpoint: String, // Ollama-compatible server
+    pub llm_model: String,
+    pub translation_backend: String, // "ollama" | "libretranslate"
+    pub libretranslate_url: String,
+}
+
+impl Default for AiSettings {
+    fn default() -> Self {
+        Self {
+            llm_endpoint: "http://localhost:11434".to_string(),
+            llm_model: "llama3.2".to_string(),
+            translation_backend: "ollama".to_string(),
+            libretranslate_url: "http://localhost:5000".to_string(),
+        }
+    }
+}
+
+/// Languages TMDB titles, overviews and artwork are requested in, tried in order.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct MetadataSettings {
+    pub language: String, // TMDB locale, e.g. "de-DE" or "pt-BR"
+    pub fallback_languages: Vec<String>,
+    pub poster_storage: String, // "sidecar" (next to the media) | "central" (app data artwork cache)
+}
+
+impl Default for MetadataSettings {
+    fn default() -> Self {
+        Self {
+            language: "en-US".to_string(),
+            fallback_languages: vec!["en-US".to_string()],
+            poster_storage: "sidecar".to_string(),
+        }
+    }
+}
+
+/// Where watch history and playlists are synced between machines. Off unless configured.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SyncSettings {
+    pub backend: String, // "none" | "webdav" | "file"
+    pub webdav_url: String, // folder URL; the sync file is kept inside it
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub file_path: String, // sync file on a network share
+    pub last_sync: Option<u64>,
+}
+
+impl Default for SyncSettings {
+    fn default() -> Self {
+        Self {
+            backend: "none".to_string(),
+            webdav_url: String::new(),
+            username: None,
+            password: None,
+            file_path: String::new(),
+            last_sync: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct StartupOptions {
+    pub restore_window: bool,
+    pub reopen_last_folder: bool,
+    pub resume_last_video: bool,
+    pub start_minimized: bool,
+    /// Files opened from Explorer/Finder while something plays are queued instead of played
+    pub enqueue_opened_files: bool,
+}
+
+impl Default for StartupOptions {
+    fn default() -> Self {
+        Self {
+            restore_window: true,
+            reopen_last_folder: true,
+            resume_last_video: false,
+            start_minimized: false,
+            enqueue_opened_files: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WindowState {
+    // Normal (not maximized or fullscreen) geometry
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub monitor: Option<String>,
+    #[serde(default)]
+    pub maximized: bool,
+    #[serde(default)]
+    pub fullscreen: bool,
+    #[serde(default)]
+    pub mini_player: bool, // maximized/fullscreen then describe the window under the mini-player
+}
+
+/// What the frontend should do right after launch, resolved from the startup options.
+#[derive(Serialize, Debug)]
+pub struct StartupState {
+    pub folder: Option<String>,
+    pub video: Option<String>,
+    pub position: Option<f64>,
 }
 
 fn get_config_path(app: &AppHandle) -> Option<PathBuf> {
     app.path().app_data_dir().ok().map(|p| p.join("config.json"))
 }
 
-#[tauri::command]
-pub fn save_last_folder(app: AppHandle, path: String) -> Result<(), String> {
-    save_last_folder_internal(&app, path)
+pub fn load_config(app: &AppHandle) -> AppConfig {
+    let Some(config_path) = get_config_path(app) else {
+        return AppConfig::default();
+    };
+    if !config_path.exists() {
+        return AppConfig::default();
+    }
+    let content = fs::read_to_string(&config_path).unwrap_or_default();
+    serde_json::from_str(&content).unwrap_or_default()
 }
 
-pub fn save_last_folder_internal(app: &AppHandle, path: String) -> Result<(), String> {
+pub fn save_config(app: &AppHandle, config: &AppConfig) -> Result<(), String> {
     let config_path = get_config_path(app).ok_or("Failed to get config path")?;
-    
+
     // Create config directory if it doesn't exist
     if let Some(parent) = config_path.parent() {
         fs::create_dir_all(parent).map_err(|e| e.to_string())?;
     }
 
-    let mut config = if config_path.exists() {
-        let content = fs::read_to_string(&config_path).unwrap_or_default();
-        serde_json::from_str(&content).unwrap_or_default()
-    } else {
-        AppConfig::default()
-    };
-
-    config.last_opened_folder = Some(path);
-
-    let json = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    let json = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
     fs::write(config_path, json).map_err(|e| e.to_string())?;
 
     Ok(())
 }
 
+#[tauri::command]
+pub fn save_last_folder(app: AppHandle, path: String) -> Result<(), String> {
+    save_last_folder_internal(&app, path)
+}
+
+pub fn save_last_folder_internal(app: &AppHandle, path: String) -> Result<(), String> {
+    let mut config = load_config(app);
+    config.last_opened_folder = Some(path);
+    save_config(app, &config)
+}
+
+pub fn save_last_video_internal(app: &AppHandle, path: String) -> Result<(), String> {
+    let mut config = load_config(app);
+    config.last_played_video = Some(path);
+    save_config(app, &config)
+}
+
 #[tauri::command]
 pub fn get_last_folder(app: AppHandle) -> Result<Option<String>, String> {
     let config_path = get_config_path(&app).ok_or("Failed to get config path")?;
@@ -54,3 +546,553 @@ pub fn get_last_folder(app: AppHandle) -> Result<Option<String>, String> {
 
     Ok(config.last_opened_folder)
 }
+
+#[tauri::command]
+pub fn get_startup_options(app: AppHandle) -> Result<StartupOptions, String> {
+    Ok(load_config(&app).startup)
+}
+
+#[tauri::command]
+pub fn save_startup_options(app: AppHandle, options: StartupOptions) -> Result<(), String> {
+    crate::parental::check_area(&app, "settings")?;
+    let mut config = load_config(&app);
+    config.startup = options;
+    save_config(&app, &config)
+}
+
+#[tauri::command]
+pub fn get_startup_state(app: AppHandle) -> Result<StartupState, String> {
+    let config = load_config(&app);
+
+    let folder = if config.startup.reopen_last_folder {
+        config.last_opened_folder.filter(|f| std::path::Path::new(f).is_dir())
+    } else {
+        None
+    };
+
+    let video = if config.startup.resume_last_video {
+        config.last_played_video.filter(|v| std::path::Path::new(v).exists())
+    } else {
+        None
+    };
+
+    let position = match &video {
+        Some(v) => crate::watch_history::get_watch_position(app.clone(), v.clone())?,
+        None => None,
+    };
+
+    Ok(StartupState { folder, video, position })
+}
+
+#[tauri::command]
+pub fn get_provider_credentials(app: AppHandle) -> Result<ProviderCredentials, String> {
+    Ok(load_config(&app).credentials)
+}
+
+#[tauri::command]
+pub fn save_provider_credentials(app: AppHandle, credentials: ProviderCredentials) -> Result<(), String> {
+    crate::parental::check_area(&app, "settings")?;
+    let mut config = load_config(&app);
+    // Treat blank keys as "not set" so the built-in default can take over
+    let clean = |key: Option<String>| key.map(|k| k.trim().to_string()).filter(|k| !k.is_empty());
+    config.credentials = ProviderCredentials {
+        tmdb_api_key: clean(credentials.tmdb_api_key),
+        libretranslate_api_key: clean(credentials.libretranslate_api_key),
+        omdb_api_key: clean(credentials.omdb_api_key),
+        opensubtitles_api_key: clean(credentials.opensubtitles_api_key),
+        jimaku_api_key: clean(credentials.jimaku_api_key),
+    };
+    save_config(&app, &config)
+}
+
+#[tauri::command]
+pub fn get_ai_settings(app: AppHandle) -> Result<AiSettings, String> {
+    Ok(load_config(&app).ai)
+}
+
+#[tauri::command]
+pub fn save_ai_settings(app: AppHandle, settings: AiSettings) -> Result<(), String> {
+    crate::parental::check_area(&app, "settings")?;
+    let mut config = load_config(&app);
+    config.ai = AiSettings {
+        llm_endpoint: settings.llm_endpoint.trim().trim_end_matches('/').to_string(),
+        libretranslate_url: settings.libretranslate_url.trim().trim_end_matches('/').to_string(),
+        ..settings
+    };
+    save_config(&app, &config)
+}
+
+#[tauri::command]
+pub fn get_metadata_settings(app: AppHandle) -> Result<MetadataSettings, String> {
+    Ok(load_config(&app).metadata)
+}
+
+#[tauri::command]
+pub fn save_metadata_settings(app: AppHandle, settings: MetadataSettings) -> Result<(), String> {
+    crate::parental::check_area(&app, "settings")?;
+    let language = settings.language.trim().to_string();
+    if language.is_empty() {
+        return Err("Metadata language is required".to_string());
+    }
+    let mut fallback_languages: Vec<String> = Vec::new();
+    for lang in settings.fallback_languages.iter().map(|l| l.trim()) {
+        if !lang.is_empty() && lang != language && !fallback_languages.iter().any(|l| l == lang) {
+            fallback_languages.push(lang.to_string());
+        }
+    }
+
+    if !matches!(settings.poster_storage.as_str(), "sidecar" | "central") {
+        return Err(format!("Unknown poster storage '{}'", settings.poster_storage));
+    }
+
+    let mut config = load_config(&app);
+    config.metadata = MetadataSettings { language, fallback_languages, poster_storage: settings.poster_storage };
+    save_config(&app, &config)
+}
+
+#[tauri::command]
+pub fn get_subtitle_settings(app: AppHandle) -> Result<SubtitleSettings, String> {
+    Ok(load_config(&app).subtitles)
+}
+
+#[tauri::command]
+pub fn save_subtitle_settings(app: AppHandle, settings: SubtitleSettings) -> Result<(), String> {
+    crate::parental::check_area(&app, "settings")?;
+    let mut config = load_config(&app);
+    config.subtitles = settings;
+    save_config(&app, &config)
+}
+
+#[tauri::command]
+pub fn get_torrent_settings(app: AppHandle) -> Result<TorrentSettings, String> {
+    Ok(load_config(&app).torrents)
+}
+
+#[tauri::command]
+pub fn save_torrent_settings(app: AppHandle, settings: TorrentSettings) -> Result<(), String> {
+    crate::parental::check_area(&app, "settings")?;
+    if settings.max_cache_gb < 0.0 {
+        return Err("The cache limit can't be negative".to_string());
+    }
+    let mut config = load_config(&app);
+    config.torrents = TorrentSettings {
+        cache_dir: settings.cache_dir.map(|d| d.trim().to_string()).filter(|d| !d.is_empty()),
+        ..settings
+    };
+    save_config(&app, &config)?;
+    // Rate limits apply to the running session right away; a new cache folder on next start
+    crate::torrent::apply_limits(&app, &config.torrents);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_update_settings(app: AppHandle) -> Result<UpdateSettings, String> {
+    Ok(load_config(&app).updates)
+}
+
+#[tauri::command]
+pub fn save_update_settings(app: AppHandle, settings: UpdateSettings) -> Result<(), String> {
+    crate::parental::check_area(&app, "settings")?;
+    let mut config = load_config(&app);
+    config.updates = UpdateSettings { last_check: config.updates.last_check, ..settings };
+    save_config(&app, &config)
+}
+
+#[tauri::command]
+pub fn get_download_settings(app: AppHandle) -> Result<DownloadSettings, String> {
+    Ok(load_config(&app).downloads)
+}
+
+#[tauri::command]
+pub fn save_download_settings(app: AppHandle, settings: DownloadSettings) -> Result<(), String> {
+    crate::parental::check_area(&app, "settings")?;
+    let clean = |value: Option<String>| value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+    let default_format = match settings.default_format.trim() {
+        "" => DownloadSettings::default().default_format,
+        format => format.to_string(),
+    };
+    let mut config = load_config(&app);
+    config.downloads = DownloadSettings {
+        output_dir: clean(settings.output_dir),
+        ytdlp_path: clean(settings.ytdlp_path),
+        default_format,
+        ..settings
+    };
+    save_config(&app, &config)
+}
+
+#[tauri::command]
+pub fn get_iptv_settings(app: AppHandle) -> Result<IptvSettings, String> {
+    Ok(load_config(&app).iptv)
+}
+
+#[tauri::command]
+pub fn save_iptv_settings(app: AppHandle, settings: IptvSettings) -> Result<(), String> {
+    crate::parental::check_area(&app, "settings")?;
+    let mut config = load_config(&app);
+    config.iptv = IptvSettings {
+        readahead_secs: settings.readahead_secs.clamp(1, 600),
+        cache_mb: settings.cache_mb.clamp(8, 2048),
+        epg_refresh_hours: settings.epg_refresh_hours.max(1),
+    };
+    save_config(&app, &config)
+}
+
+#[tauri::command]
+pub fn get_podcast_settings(app: AppHandle) -> Result<PodcastSettings, String> {
+    Ok(load_config(&app).podcasts)
+}
+
+#[tauri::command]
+pub fn save_podcast_settings(app: AppHandle, settings: PodcastSettings) -> Result<(), String> {
+    crate::parental::check_area(&app, "settings")?;
+    let mut config = load_config(&app);
+    config.podcasts = PodcastSettings {
+        download_dir: settings.download_dir.map(|d| d.trim().to_string()).filter(|d| !d.is_empty()),
+        ..settings
+    };
+    save_config(&app, &config)
+}
+
+#[tauri::command]
+pub fn get_watch_party_settings(app: AppHandle) -> Result<WatchPartySettings, String> {
+    Ok(load_config(&app).watch_party)
+}
+
+#[tauri::command]
+pub fn save_watch_party_settings(app: AppHandle, settings: WatchPartySettings) -> Result<(), String> {
+    crate::parental::check_area(&app, "settings")?;
+    let mut config = load_config(&app);
+    config.watch_party = WatchPartySettings { port: settings.port.max(1024), ..settings };
+    save_config(&app, &config)
+}
+
+/// Settings without the account tokens.
+#[tauri::command]
+pub fn get_anime_sync_settings(app: AppHandle) -> Result<AnimeSyncSettings, String> {
+    let mut settings = load_config(&app).anime_sync;
+    for account in [&mut settings.anilist, &mut settings.mal].into_iter().flatten() {
+        account.access_token.clear();
+        account.refresh_token = None;
+    }
+    Ok(settings)
+}
+
+/// Accounts are left as they are; they change only by logging in or out.
+#[tauri::command]
+pub fn save_anime_sync_settings(app: AppHandle, settings: AnimeSyncSettings) -> Result<(), String> {
+    crate::parental::check_area(&app, "settings")?;
+    let mut config = load_config(&app);
+    config.anime_sync = AnimeSyncSettings {
+        threshold_percent: settings.threshold_percent.clamp(50, 100),
+        anilist_client_id: settings.anilist_client_id.trim().to_string(),
+        mal_client_id: settings.mal_client_id.trim().to_string(),
+        anilist: config.anime_sync.anilist.take(),
+        mal: config.anime_sync.mal.take(),
+        ..settings
+    };
+    save_config(&app, &config)
+}
+
+/// Settings without the session key.
+#[tauri::command]
+pub fn get_lastfm_settings(app: AppHandle) -> Result<LastfmSettings, String> {
+    let mut settings = load_config(&app).lastfm;
+    if let Some(session) = settings.session.as_mut() {
+        session.key.clear();
+    }
+    Ok(settings)
+}
+
+/// The session is left as it is; it changes only by logging in or out.
+#[tauri::command]
+pub fn save_lastfm_settings(app: AppHandle, settings: LastfmSettings) -> Result<(), String> {
+    crate::parental::check_area(&app, "settings")?;
+    let mut config = load_config(&app);
+    config.lastfm = LastfmSettings {
+        api_key: settings.api_key.trim().to_string(),
+        api_secret: settings.api_secret.trim().to_string(),
+        session: config.lastfm.session.take(),
+        ..settings
+    };
+    save_config(&app, &config)
+}
+
+#[tauri::command]
+pub fn get_segment_settings(app: AppHandle) -> Result<SegmentSettings, String> {
+    Ok(load_config(&app).segments)
+}
+
+#[tauri::command]
+pub fn save_segment_settings(app: AppHandle, settings: SegmentSettings) -> Result<(), String> {
+    crate::parental::check_area(&app, "settings")?;
+    for (kind, action) in &settings.actions {
+        if !crate::segments::KINDS.contains(&kind.as_str()) {
+            return Err(format!("Unknown segment kind '{}'", kind));
+        }
+        if !["skip", "prompt", "ignore"].contains(&action.as_str()) {
+            return Err(format!("Unknown segment action '{}'", action));
+        }
+    }
+    let mut config = load_config(&app);
+    config.segments = settings;
+    save_config(&app, &config)
+}
+
+#[tauri::command]
+pub fn get_clip_settings(app: AppHandle) -> Result<ClipSettings, String> {
+    Ok(load_config(&app).clips)
+}
+
+#[tauri::command]
+pub fn save_clip_settings(app: AppHandle, settings: ClipSettings) -> Result<(), String> {
+    crate::parental::check_area(&app, "settings")?;
+    if !crate::clips::FORMATS.contains(&settings.default_format.as_str()) {
+        return Err(format!("Unknown clip format '{}'", settings.default_format));
+    }
+    if !crate::clips::PRESETS.contains(&settings.default_preset.as_str()) {
+        return Err(format!("Unknown clip preset '{}'", settings.default_preset));
+    }
+    let mut config = load_config(&app);
+    config.clips = ClipSettings {
+        output_dir: settings.output_dir.filter(|d| !d.trim().is_empty()),
+        ..settings
+    };
+    save_config(&app, &config)
+}
+
+#[tauri::command]
+pub fn get_conversion_settings(app: AppHandle) -> Result<ConversionSettings, String> {
+    Ok(load_config(&app).conversions)
+}
+
+#[tauri::command]
+pub fn save_conversion_settings(app: AppHandle, settings: ConversionSettings) -> Result<(), String> {
+    crate::parental::check_area(&app, "settings")?;
+    let mut config = load_config(&app);
+    config.conversions = ConversionSettings { output_dir: settings.output_dir.filter(|d| !d.trim().is_empty()) };
+    save_config(&app, &config)
+}
+
+#[tauri::command]
+pub fn get_global_hotkeys(app: AppHandle) -> Result<GlobalHotkeySettings, String> {
+    Ok(load_config(&app).global_hotkeys)
+}
+
+/// Save and re-register the hotkeys. Returns the combinations another app already holds.
+#[tauri::command]
+pub fn save_global_hotkeys(app: AppHandle, settings: GlobalHotkeySettings) -> Result<Vec<String>, String> {
+    crate::parental::check_area(&app, "settings")?;
+    let mut bindings: std::collections::BTreeMap<String, String> = std::collections::BTreeMap::new();
+    for (action, accelerator) in settings.bindings {
+        let accelerator = accelerator.trim().to_string();
+        if !crate::hotkeys::ACTIONS.contains(&action.as_str()) {
+            return Err(format!("Unknown hotkey action '{}'", action));
+        }
+        if !accelerator.is_empty() {
+            crate::hotkeys::validate(&accelerator)?;
+            if let Some((other, _)) = bindings.iter().find(|(_, a)| a.eq_ignore_ascii_case(&accelerator)) {
+                return Err(format!("{} is already used for {}", accelerator, other));
+            }
+        }
+        bindings.insert(action, accelerator);
+    }
+    let mut config = load_config(&app);
+    config.global_hotkeys = GlobalHotkeySettings { bindings, ..settings };
+    save_config(&app, &config)?;
+    Ok(crate::hotkeys::apply(&app))
+}
+
+#[tauri::command]
+pub fn get_presence_settings(app: AppHandle) -> Result<PresenceSettings, String> {
+    Ok(load_config(&app).presence)
+}
+
+#[tauri::command]
+pub fn save_presence_settings(app: AppHandle, settings: PresenceSettings) -> Result<(), String> {
+    crate::parental::check_area(&app, "settings")?;
+    let mut excluded_folders: Vec<String> = Vec::new();
+    for folder in settings.excluded_folders.iter().map(|f| f.trim().trim_end_matches(['/', '\\'])) {
+        if !folder.is_empty() && !excluded_folders.iter().any(|f| f == folder) {
+            excluded_folders.push(folder.to_string());
+        }
+    }
+    let client_id = settings.client_id.trim().to_string();
+    if !client_id.is_empty() && !client_id.chars().all(|c| c.is_ascii_digit()) {
+        return Err("The Discord application ID is a number".to_string());
+    }
+    let defaults = PresenceSettings::default();
+    let template = |t: &str, default: String| if t.trim().is_empty() { default } else { t.trim().to_string() };
+    let details_template = template(&settings.details_template, defaults.details_template);
+    let state_template = template(&settings.state_template, defaults.state_template);
+    let mut config = load_config(&app);
+    config.presence = PresenceSettings { client_id, excluded_folders, details_template, state_template, ..settings };
+    save_config(&app, &config)?;
+    // Reconnects if the application ID changed
+    app.state::<crate::discord_rpc::DiscordRpcState>().wake();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_private_mode(app: AppHandle) -> Result<bool, String> {
+    Ok(load_config(&app).private_mode)
+}
+
+/// Turning private mode on also clears the Discord activity right away.
+#[tauri::command]
+pub fn set_private_mode(app: AppHandle, enabled: bool) -> Result<(), String> {
+    crate::parental::check_area(&app, "settings")?;
+    let mut config = load_config(&app);
+    config.private_mode = enabled;
+    save_config(&app, &config)?;
+    if enabled && config.presence.follow_private_mode {
+        app.state::<crate::discord_rpc::DiscordRpcState>().publish(None);
+    }
+    tracing::info!("🕶️ Private mode {}", if enabled { "on" } else { "off" });
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_sync_settings(app: AppHandle) -> Result<SyncSettings, String> {
+    Ok(load_config(&app).sync)
+}
+
+#[tauri::command]
+pub fn save_sync_settings(app: AppHandle, settings: SyncSettings) -> Result<(), String> {
+    crate::parental::check_area(&app, "settings")?;
+    let webdav_url = settings.webdav_url.trim().trim_end_matches('/').to_string();
+    let file_path = settings.file_path.trim().to_string();
+    match settings.backend.as_str() {
+        "none" => {}
+        "webdav" if !webdav_url.starts_with("http://") && !webdav_url.starts_with("https://") => {
+            return Err("WebDAV sync needs an http(s) folder URL".to_string());
+        }
+        "file" if file_path.is_empty() => return Err("File sync needs a sync file path".to_string()),
+        "webdav" | "file" => {}
+        other => return Err(format!("Unknown sync backend '{}'", other)),
+    }
+
+    let mut config = load_config(&app);
+    let clean = |value: Option<String>| value.filter(|v| !v.is_empty());
+    config.sync = SyncSettings {
+        webdav_url,
+        file_path,
+        username: clean(settings.username),
+        password: clean(settings.password),
+        // Only sync_now moves this forward
+        last_sync: config.sync.last_sync,
+        ..settings
+    };
+    save_config(&app, &config)
+}
+
+// Last geometry of the main window while it was neither maximized, fullscreen nor minimized
+static NORMAL_BOUNDS: std::sync::Mutex<Option<(i32, i32, u32, u32)>> = std::sync::Mutex::new(None);
+
+fn is_normal(window: &tauri::Window) -> bool {
+    !window.is_minimized().unwrap_or(false) && !window.is_maximized().unwrap_or(false) && !window.is_fullscreen().unwrap_or(false)
+}
+
+/// Follow moves and resizes, so a window closed while maximized still reopens at its
+/// normal size under the maximized one.
+pub fn track_window_bounds(window: &tauri::Window) {
+    // The mini-player's own geometry is never the one to reopen at
+    if !is_normal(window) || crate::window_mode::mini_restore(window.app_handle()).is_some() {
+        return;
+    }
+    if let (Ok(pos), Ok(size)) = (window.outer_position(), window.outer_size()) {
+        *NORMAL_BOUNDS.lock().unwrap() = Some((pos.x, pos.y, size.width, size.height));
+    }
+}
+
+/// Record the main window geometry and mode so they can be restored on next launch.
+pub fn save_window_state(window: &tauri::Window) {
+    let app = window.app_handle();
+    // Don't persist a minimized window's bogus geometry
+    if window.is_minimized().unwrap_or(false) {
+        return;
+    }
+    track_window_bounds(window);
+    let mut config = load_config(app);
+    if let Some(restore) = crate::window_mode::mini_restore(app) {
+        let monitor = window.current_monitor().ok().flatten().and_then(|m| m.name().cloned());
+        // Bounds from before the mini-player, unless it was entered from maximized or fullscreen
+        let (x, y, width, height) = match *NORMAL_BOUNDS.lock().unwrap() {
+            Some(bounds) if restore.maximized || restore.fullscreen => bounds,
+            _ => (restore.position.x, restore.position.y, restore.size.width, restore.size.height),
+        };
+        config.window_state = Some(WindowState {
+            x,
+            y,
+            width,
+            height,
+            monitor,
+            maximized: restore.maximized,
+            fullscreen: restore.fullscreen,
+            mini_player: true,
+        });
+        let _ = save_config(app, &config);
+        return;
+    }
+    let previous = config.window_state.as_ref().map(|s| (s.x, s.y, s.width, s.height));
+    let Some((x, y, width, height)) = NORMAL_BOUNDS.lock().unwrap().or(previous) else {
+        return;
+    };
+    let monitor = window.current_monitor().ok().flatten().and_then(|m| m.name().cloned());
+
+    config.window_state = Some(WindowState {
+        x,
+        y,
+        width,
+        height,
+        monitor,
+        maximized: window.is_maximized().unwrap_or(false),
+        fullscreen: window.is_fullscreen().unwrap_or(false),
+        mini_player: false,
+    });
+    let _ = save_config(app, &config);
+}
+
+/// Apply the saved geometry to the main window at startup.
+pub fn restore_window_state(app: &AppHandle) {
+    let config = load_config(app);
+    if !config.startup.restore_window {
+        return;
+    }
+    let (Some(state), Some(window)) = (config.window_state, app.get_webview_window("main")) else {
+        return;
+    };
+
+    // Only restore the position if it still lands on a connected monitor
+    let monitors = window.available_monitors().unwrap_or_default();
+    let home = monitors.iter().find(|m| {
+        if state.monitor.is_some() && m.name() != state.monitor.as_ref() {
+            return false;
+        }
+        let p = m.position();
+        let s = m.size();
+        state.x >= p.x && state.x < p.x + s.width as i32 && state.y >= p.y && state.y < p.y + s.height as i32
+    });
+    match home {
+        Some(monitor) => {
+            let fit = monitor.work_area().size;
+            let _ = window.set_size(tauri::PhysicalSize::new(state.width.min(fit.width), state.height.min(fit.height)));
+            let _ = window.set_position(tauri::PhysicalPosition::new(state.x, state.y));
+        }
+        None => {
+            // That monitor is gone: keep the size if it fits the primary one and center there
+            if let Some(fit) = window.primary_monitor().ok().flatten().map(|m| m.work_area().size) {
+                let _ = window.set_size(tauri::PhysicalSize::new(state.width.min(fit.width), state.height.min(fit.height)));
+            }
+            let _ = window.center();
+        }
+    }
+
+    if state.mini_player {
+        crate::window_mode::restore_mini(app, state.maximized, state.fullscreen);
+    } else if state.fullscreen {
+        let _ = window.set_fullscreen(true);
+    } else if state.maximized {
+        let _ = window.maximize();
+    }
+}
@@ -0,0 +1,332 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Child;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{command, AppHandle, Emitter, Manager, State};
+
+use crate::config::load_config;
+
+// Batch conversion for device-friendly copies. The queue survives restarts in
+// `conversions.json`; one ffmpeg runs at a time, oldest job first.
+
+pub struct Preset {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub ext: &'static str,
+    args: &'static [&'static str],
+}
+
+pub const PRESETS: &[Preset] = &[
+    Preset {
+        id: "h264_1080p",
+        label: "H.264 1080p",
+        ext: "mp4",
+        args: &[
+            "-map", "0:v:0", "-map", "0:a?",
+            "-vf", "scale=-2:'min(1080,ih)'",
+            "-c:v", "libx264", "-preset", "medium", "-crf", "21", "-pix_fmt", "yuv420p",
+            "-c:a", "aac", "-b:a", "192k",
+            "-movflags", "+faststart",
+        ],
+    },
+    Preset {
+        id: "hevc",
+        label: "HEVC",
+        ext: "mp4",
+        // hvc1 so Apple players accept the file
+        args: &[
+            "-map", "0:v:0", "-map", "0:a?",
+            "-c:v", "libx265", "-preset", "medium", "-crf", "24", "-tag:v", "hvc1",
+            "-c:a", "aac", "-b:a", "160k",
+            "-movflags", "+faststart",
+        ],
+    },
+    Preset {
+        id: "audio",
+        label: "Audio only",
+        ext: "m4a",
+        args: &["-vn", "-map", "0:a:0", "-c:a", "aac", "-b:a", "192k"],
+    },
+    Preset {
+        id: "remux_mp4",
+        label: "Remux to MP4",
+        ext: "mp4",
+        // Streams are copied as they are; subtitles are dropped since MP4 can't carry most formats
+        args: &["-map", "0:v", "-map", "0:a?", "-c", "copy", "-movflags", "+faststart"],
+    },
+];
+
+fn preset(id: &str) -> Option<&'static Preset> {
+    PRESETS.iter().find(|p| p.id == id)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ConversionItem {
+    pub id: String,
+    pub input: String,
+    pub preset: String,
+    pub output: String,
+    pub status: String, // "queued" | "converting" | "done" | "failed" | "cancelled"
+    pub progress: f32,  // 0-100
+    pub error: Option<String>,
+}
+
+/// A queued conversion, shared between the worker and `cancel_conversion`.
+pub struct Conversion {
+    item: Mutex<ConversionItem>,
+    cancelled: AtomicBool,
+    child: Mutex<Option<Child>>,
+}
+
+impl Conversion {
+    fn new(item: ConversionItem) -> Self {
+        Self { item: Mutex::new(item), cancelled: AtomicBool::new(false), child: Mutex::new(None) }
+    }
+
+    fn snapshot(&self) -> ConversionItem {
+        self.item.lock().unwrap().clone()
+    }
+
+    /// Change the item, emit `conversion-progress` and, unless only the progress moved,
+    /// save the queue.
+    fn update(&self, app: &AppHandle, f: impl FnOnce(&mut ConversionItem)) {
+        let (item, status_changed) = {
+            let mut item = self.item.lock().unwrap();
+            let status = item.status.clone();
+            f(&mut item);
+            (item.clone(), item.status != status)
+        };
+        let _ = app.emit("conversion-progress", &item);
+        if status_changed {
+            save_queue(app);
+        }
+    }
+}
+
+pub struct ConversionState {
+    items: Mutex<Vec<Arc<Conversion>>>,
+    worker_running: AtomicBool,
+}
+
+impl ConversionState {
+    pub fn new() -> Self {
+        Self { items: Mutex::new(Vec::new()), worker_running: AtomicBool::new(false) }
+    }
+
+    fn next_queued(&self) -> Option<Arc<Conversion>> {
+        self.items.lock().ok()?.iter().find(|c| c.snapshot().status == "queued").cloned()
+    }
+}
+
+fn queue_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app.path().app_data_dir().map_err(|e| e.to_string())?.join("conversions.json"))
+}
+
+fn save_queue(app: &AppHandle) {
+    let items: Vec<ConversionItem> = app.state::<ConversionState>().items.lock().unwrap().iter().map(|c| c.snapshot()).collect();
+    let result = queue_path(app).and_then(|path| {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::write(&path, serde_json::to_string(&items).map_err(|e| e.to_string())?).map_err(|e| e.to_string())
+    });
+    if let Err(e) = result {
+        tracing::error!("Failed to save conversion queue: {}", e);
+    }
+}
+
+/// Reload the saved queue at startup; a job that was running when FrameX quit starts over.
+pub fn resume_queue(app: &AppHandle) {
+    let items: Vec<ConversionItem> = queue_path(app)
+        .ok()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default();
+    let state = app.state::<ConversionState>();
+    state.items.lock().unwrap().extend(items.into_iter().map(|mut item| {
+        if item.status == "converting" {
+            item.status = "queued".to_string();
+            item.progress = 0.0;
+        }
+        Arc::new(Conversion::new(item))
+    }));
+    if state.next_queued().is_some() {
+        start_worker(app);
+    }
+}
+
+fn start_worker(app: &AppHandle) {
+    let state = app.state::<ConversionState>();
+    if state.worker_running.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_ok() {
+        let app = app.clone();
+        std::thread::spawn(move || run_queue(app));
+    }
+}
+
+fn run_queue(app: AppHandle) {
+    let state = app.state::<ConversionState>();
+    loop {
+        if let Some(conversion) = state.next_queued() {
+            let result = run_conversion(&app, &conversion);
+            let cancelled = conversion.cancelled.load(Ordering::SeqCst);
+            if result.is_err() || cancelled {
+                let _ = fs::remove_file(conversion.snapshot().output);
+            }
+            match result {
+                Ok(()) if !cancelled => {
+                    tracing::info!("🔁 Converted {}", conversion.snapshot().output);
+                    conversion.update(&app, |item| {
+                        item.status = "done".to_string();
+                        item.progress = 100.0;
+                    });
+                }
+                Ok(()) => conversion.update(&app, |item| item.status = "cancelled".to_string()),
+                Err(e) => {
+                    if !cancelled {
+                        tracing::error!("Conversion of {} failed: {}", conversion.snapshot().input, e);
+                    }
+                    conversion.update(&app, |item| {
+                        item.status = if cancelled { "cancelled" } else { "failed" }.to_string();
+                        item.error = (!cancelled).then_some(e);
+                    });
+                }
+            }
+            continue;
+        }
+        state.worker_running.store(false, Ordering::SeqCst);
+        // Something may have been queued between the check and the store
+        if state.next_queued().is_none() || state.worker_running.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_err() {
+            return;
+        }
+    }
+}
+
+fn run_conversion(app: &AppHandle, conversion: &Conversion) -> Result<(), String> {
+    let item = conversion.snapshot();
+    if conversion.cancelled.load(Ordering::SeqCst) {
+        return Err("Cancelled".to_string());
+    }
+    let preset = preset(&item.preset).ok_or_else(|| format!("Unknown preset '{}'", item.preset))?;
+    if let Some(dir) = Path::new(&item.output).parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let input = crate::network_sources::playable_path(app, &item.input);
+    let duration = crate::file_scanner::get_video_duration(input.clone()).unwrap_or(0.0);
+    conversion.update(app, |item| item.status = "converting".to_string());
+
+    let mut command = crate::ffmpeg::command();
+    command.arg("-i").arg(&input).args(preset.args).arg(&item.output);
+    let mut last_emitted = -1.0;
+    crate::ffmpeg::run_with_progress(command, duration, &conversion.child, |percent| {
+        if percent - last_emitted >= 0.5 {
+            last_emitted = percent;
+            conversion.update(app, |item| item.progress = percent);
+        }
+    })
+}
+
+/// Where files made from `input` go: next to it unless an output folder is set. Network
+/// and online sources go to `<Videos>/FrameX` instead.
+pub(crate) fn output_dir(app: &AppHandle, input: &str) -> Result<PathBuf, String> {
+    match load_config(app).conversions.output_dir {
+        Some(dir) => Ok(PathBuf::from(dir)),
+        None if !input.contains("://") => Path::new(input).parent().map(Path::to_path_buf).ok_or_else(|| "The file has no folder".to_string()),
+        None => Ok(app.path().video_dir().map_err(|e| e.to_string())?.join("FrameX")),
+    }
+}
+
+/// `<stem> (<preset>).<ext>` in the output folder.
+fn output_path(app: &AppHandle, input: &str, preset: &Preset) -> Result<PathBuf, String> {
+    let stem = Path::new(input).file_stem().and_then(|s| s.to_str()).unwrap_or("video");
+    let dir = output_dir(app, input)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    crate::ffmpeg::unique_output(&dir, &format!("{} ({})", stem, preset.label), preset.ext)
+}
+
+#[derive(Serialize)]
+pub struct PresetInfo {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub ext: &'static str,
+}
+
+#[command]
+pub fn list_conversion_presets() -> Vec<PresetInfo> {
+    PRESETS.iter().map(|p| PresetInfo { id: p.id, label: p.label, ext: p.ext }).collect()
+}
+
+/// Queue every file for conversion with one preset. Files already waiting with the same
+/// preset are skipped.
+#[command]
+pub fn queue_conversions(app: AppHandle, state: State<'_, ConversionState>, paths: Vec<String>, preset: String) -> Result<Vec<ConversionItem>, String> {
+    let chosen = self::preset(&preset).ok_or_else(|| format!("Unknown preset '{}'", preset))?;
+    let pending: Vec<(String, String)> = state
+        .items
+        .lock()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|c| c.snapshot())
+        .filter(|i| matches!(i.status.as_str(), "queued" | "converting"))
+        .map(|i| (i.input, i.preset))
+        .collect();
+    let mut queued = Vec::new();
+    for input in paths {
+        if pending.iter().any(|(i, p)| *i == input && *p == preset) {
+            continue;
+        }
+        let output = output_path(&app, &input, chosen)?;
+        let item = ConversionItem {
+            id: uuid::Uuid::new_v4().to_string(),
+            input,
+            preset: preset.clone(),
+            output: output.to_string_lossy().to_string(),
+            status: "queued".to_string(),
+            progress: 0.0,
+            error: None,
+        };
+        state.items.lock().map_err(|e| e.to_string())?.push(Arc::new(Conversion::new(item.clone())));
+        let _ = app.emit("conversion-progress", &item);
+        queued.push(item);
+    }
+    save_queue(&app);
+    start_worker(&app);
+    Ok(queued)
+}
+
+#[command]
+pub fn list_conversions(state: State<'_, ConversionState>) -> Result<Vec<ConversionItem>, String> {
+    Ok(state.items.lock().map_err(|e| e.to_string())?.iter().map(|c| c.snapshot()).collect())
+}
+
+/// Cancel a queued or running conversion, killing ffmpeg if it has started.
+#[command]
+pub fn cancel_conversion(app: AppHandle, state: State<'_, ConversionState>, id: String) -> Result<bool, String> {
+    let conversion = state.items.lock().map_err(|e| e.to_string())?.iter().find(|c| c.snapshot().id == id).cloned();
+    let Some(conversion) = conversion.filter(|c| matches!(c.snapshot().status.as_str(), "queued" | "converting")) else {
+        return Ok(false);
+    };
+    conversion.cancelled.store(true, Ordering::SeqCst);
+    if let Some(child) = conversion.child.lock().map_err(|e| e.to_string())?.as_mut() {
+        let _ = child.kill();
+    }
+    // A running job's worker cleans up after it; a queued one only has its placeholder
+    if conversion.snapshot().status == "queued" {
+        let _ = fs::remove_file(conversion.snapshot().output);
+    }
+    conversion.update(&app, |item| item.status = "cancelled".to_string());
+    Ok(true)
+}
+
+/// Drop finished, failed and cancelled jobs from the queue.
+#[command]
+pub fn clear_finished_conversions(app: AppHandle, state: State<'_, ConversionState>) -> Result<(), String> {
+    state
+        .items
+        .lock()
+        .map_err(|e| e.to_string())?
+        .retain(|c| matches!(c.snapshot().status.as_str(), "queued" | "converting"));
+    save_queue(&app);
+    Ok(())
+}
@@ -1,88 +1,600 @@
 use serde::Serialize;
-use tauri::command;
+use serde_json::{json, Value};
+use tauri::{command, AppHandle, Emitter, Manager};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
 use std::sync::Mutex;
+use std::time::Duration;
 
-// Simple Discord RPC state manager
-// This is a lightweight implementation that tracks what should be displayed
-// A full Discord IPC implementation would require platform-specific socket handling
-// This provides the backend hooks so the feature can be connected later
+use crate::util::now_secs;
 
-#[derive(Serialize, Clone, Debug, Default)]
+// Discord Rich Presence over Discord's local IPC socket (a named pipe on Windows).
+// A worker thread owns the connection: it retries with backoff while Discord isn't
+// running, notices restarts through a keepalive ping, and pushes activity changes.
+
+const MIN_BACKOFF: Duration = Duration::from_secs(2);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+const KEEPALIVE: Duration = Duration::from_secs(15);
+
+// IPC opcodes
+const OP_HANDSHAKE: u32 = 0;
+const OP_FRAME: u32 = 1;
+const OP_CLOSE: u32 = 2;
+const OP_PING: u32 = 3;
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
 pub struct RpcActivity {
     pub state: String,
     pub details: String,
     pub large_image: String,
     pub large_text: String,
+    pub small_image: Option<String>,
+    pub small_text: Option<String>,
     pub start_timestamp: Option<u64>,
+    pub end_timestamp: Option<u64>, // with start, Discord shows a "32:10 left" countdown
+}
+
+impl RpcActivity {
+    /// The activity object Discord's SET_ACTIVITY expects; it rejects empty strings.
+    fn to_payload(&self) -> Value {
+        let text = |s: &str| (s.chars().count() >= 2).then(|| s.chars().take(128).collect::<String>());
+        let mut activity = json!({});
+        if let Some(details) = text(&self.details) {
+            activity["details"] = json!(details);
+        }
+        if let Some(state) = text(&self.state) {
+            activity["state"] = json!(state);
+        }
+        let mut timestamps = json!({});
+        if let Some(start) = self.start_timestamp {
+            timestamps["start"] = json!(start);
+        }
+        if let Some(end) = self.end_timestamp {
+            timestamps["end"] = json!(end);
+        }
+        activity["timestamps"] = timestamps;
+        let mut assets = json!({});
+        for (key, value) in [
+            ("large_image", Some(&self.large_image)),
+            ("large_text", Some(&self.large_text)),
+            ("small_image", self.small_image.as_ref()),
+            ("small_text", self.small_text.as_ref()),
+        ] {
+            if let Some(value) = value.and_then(|v| text(v)) {
+                assets[key] = json!(value);
+            }
+        }
+        activity["assets"] = assets;
+        activity
+    }
+}
+
+/// Connection state reported by `discord_rpc_status` and the `discord-rpc-status` event.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum RpcStatus {
+    Connected { user: Option<String> },
+    Disconnected, // presence turned off
+    Unavailable, // Discord isn't running; retrying
+    Error { message: String }, // Discord refused the handshake (bad application ID); retrying
 }
 
 pub struct DiscordRpcState {
-    pub connected: Mutex<bool>,
+    pub enabled: Mutex<bool>,
     pub activity: Mutex<Option<RpcActivity>>,
+    pub status: Mutex<RpcStatus>,
+    worker: Mutex<Option<Sender<()>>>,
 }
 
 impl DiscordRpcState {
     pub fn new() -> Self {
         Self {
-            connected: Mutex::new(false),
+            enabled: Mutex::new(false),
             activity: Mutex::new(None),
+            status: Mutex::new(RpcStatus::Disconnected),
+            worker: Mutex::new(None),
+        }
+    }
+
+    /// Replace the shown activity (`None` clears it). Kept while disconnected so
+    /// connecting mid-playback shows it straight away.
+    pub fn publish(&self, activity: Option<RpcActivity>) {
+        if let Ok(mut current) = self.activity.lock() {
+            *current = activity;
+        }
+        self.wake();
+    }
+
+    /// Nudge the worker to re-check settings and activity.
+    pub fn wake(&self) {
+        if let Some(tx) = self.worker.lock().ok().and_then(|w| w.clone()) {
+            let _ = tx.send(());
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled.lock().map(|e| *e).unwrap_or(false)
+    }
+
+    fn set_status(&self, app: &AppHandle, status: RpcStatus) {
+        let Ok(mut current) = self.status.lock() else {
+            return;
+        };
+        if *current != status {
+            tracing::info!("[Discord RPC] {:?}", status);
+            let _ = app.emit("discord-rpc-status", &status);
+            *current = status;
         }
     }
 }
 
-fn now_timestamp() -> u64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs()
+trait IpcStream: Read + Write + Send {}
+impl<T: Read + Write + Send> IpcStream for T {}
+
+enum ConnectError {
+    Unavailable,
+    Rejected(String),
+}
+
+struct IpcConnection {
+    stream: Box<dyn IpcStream>,
+    client_id: String,
+}
+
+#[cfg(unix)]
+fn open_socket() -> Option<Box<dyn IpcStream>> {
+    use std::os::unix::net::UnixStream;
+    let mut dirs: Vec<String> = ["XDG_RUNTIME_DIR", "TMPDIR", "TMP", "TEMP"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .collect();
+    dirs.push("/tmp".to_string());
+    for dir in &dirs {
+        // Plain install, Flatpak, Snap
+        for sub in ["", "app/com.discordapp.Discord/", "snap.discord/"] {
+            for i in 0..10 {
+                if let Ok(stream) = UnixStream::connect(format!("{}/{}discord-ipc-{}", dir.trim_end_matches('/'), sub, i)) {
+                    let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+                    return Some(Box::new(stream));
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(windows)]
+fn open_socket() -> Option<Box<dyn IpcStream>> {
+    (0..10).find_map(|i| {
+        std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(format!(r"\\.\pipe\discord-ipc-{}", i))
+            .ok()
+            .map(|pipe| Box::new(pipe) as Box<dyn IpcStream>)
+    })
+}
+
+impl IpcConnection {
+    /// Handshake with the running Discord client; returns the logged-in user's name.
+    fn open(client_id: &str) -> Result<(Self, Option<String>), ConnectError> {
+        let stream = open_socket().ok_or(ConnectError::Unavailable)?;
+        let mut conn = Self { stream, client_id: client_id.to_string() };
+        conn.write_frame(OP_HANDSHAKE, &json!({ "v": 1, "client_id": client_id }))
+            .map_err(|_| ConnectError::Unavailable)?;
+        let (op, reply) = conn.read_frame().map_err(|_| ConnectError::Unavailable)?;
+        if op == OP_FRAME && reply["evt"] == "READY" {
+            let user = &reply["data"]["user"];
+            let name = user["global_name"].as_str().or(user["username"].as_str()).map(String::from);
+            return Ok((conn, name));
+        }
+        Err(ConnectError::Rejected(reply["message"].as_str().unwrap_or("Handshake rejected").to_string()))
+    }
+
+    fn write_frame(&mut self, op: u32, payload: &Value) -> std::io::Result<()> {
+        let body = payload.to_string();
+        let mut frame = Vec::with_capacity(8 + body.len());
+        frame.extend_from_slice(&op.to_le_bytes());
+        frame.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        frame.extend_from_slice(body.as_bytes());
+        self.stream.write_all(&frame)?;
+        self.stream.flush()
+    }
+
+    fn read_frame(&mut self) -> std::io::Result<(u32, Value)> {
+        let mut header = [0u8; 8];
+        self.stream.read_exact(&mut header)?;
+        let op = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+        let len = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        let mut body = vec![0u8; len];
+        self.stream.read_exact(&mut body)?;
+        Ok((op, serde_json::from_slice(&body).unwrap_or(Value::Null)))
+    }
+
+    /// Send a command and wait for its reply, so replies never pile up unread.
+    fn request(&mut self, op: u32, payload: &Value) -> std::io::Result<Value> {
+        self.write_frame(op, payload)?;
+        let (reply_op, reply) = self.read_frame()?;
+        if reply_op == OP_CLOSE {
+            return Err(std::io::Error::new(std::io::ErrorKind::ConnectionAborted, "Discord closed the connection"));
+        }
+        Ok(reply)
+    }
+
+    fn set_activity(&mut self, activity: Option<&RpcActivity>) -> std::io::Result<()> {
+        static NONCE: AtomicU64 = AtomicU64::new(0);
+        let reply = self.request(OP_FRAME, &json!({
+            "cmd": "SET_ACTIVITY",
+            "args": { "pid": std::process::id(), "activity": activity.map(|a| a.to_payload()) },
+            "nonce": NONCE.fetch_add(1, Ordering::Relaxed).to_string(),
+        }))?;
+        // A rejected payload isn't a connection problem; log it and carry on
+        if reply["evt"] == "ERROR" {
+            tracing::warn!("[Discord RPC] Activity rejected: {}", reply["data"]["message"]);
+        }
+        Ok(())
+    }
+
+    fn ping(&mut self) -> std::io::Result<()> {
+        self.request(OP_PING, &json!({})).map(|_| ())
+    }
+}
+
+fn run_worker(app: AppHandle, rx: std::sync::mpsc::Receiver<()>) {
+    let state = app.state::<DiscordRpcState>();
+    let mut conn: Option<IpcConnection> = None;
+    let mut sent: Option<Option<RpcActivity>> = None;
+    let mut backoff = MIN_BACKOFF;
+
+    loop {
+        if !state.is_enabled() {
+            // Dropping the socket makes Discord clear the activity
+            conn = None;
+            state.set_status(&app, RpcStatus::Disconnected);
+            if rx.recv().is_err() {
+                return;
+            }
+            continue;
+        }
+
+        let client_id = crate::config::load_config(&app).presence.client_id;
+        if conn.as_ref().is_some_and(|c| c.client_id != client_id) {
+            conn = None;
+        }
+        if conn.is_none() {
+            if client_id.is_empty() {
+                state.set_status(&app, RpcStatus::Error { message: "Set a Discord application ID in settings".to_string() });
+                if rx.recv().is_err() {
+                    return;
+                }
+                continue;
+            }
+            match IpcConnection::open(&client_id) {
+                Ok((opened, user)) => {
+                    conn = Some(opened);
+                    sent = None;
+                    backoff = MIN_BACKOFF;
+                    state.set_status(&app, RpcStatus::Connected { user });
+                }
+                Err(e) => {
+                    state.set_status(&app, match e {
+                        ConnectError::Unavailable => RpcStatus::Unavailable,
+                        ConnectError::Rejected(message) => RpcStatus::Error { message },
+                    });
+                    // A wake (settings change, connect pressed again) retries right away
+                    if let Err(RecvTimeoutError::Disconnected) = rx.recv_timeout(backoff) {
+                        return;
+                    }
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                    continue;
+                }
+            }
+        }
+        let Some(active) = conn.as_mut() else {
+            continue;
+        };
+
+        let current = state.activity.lock().ok().and_then(|a| a.clone());
+        if sent.as_ref() != Some(&current) {
+            if let Some(a) = &current {
+                tracing::info!("[Discord RPC] Activity: {} - {}", a.details, a.state);
+            }
+            if let Err(e) = active.set_activity(current.as_ref()) {
+                tracing::warn!("[Discord RPC] Lost connection: {}", e);
+                conn = None;
+                continue;
+            }
+            sent = Some(current);
+        }
+
+        match rx.recv_timeout(KEEPALIVE) {
+            Ok(()) => {}
+            Err(RecvTimeoutError::Timeout) => {
+                // Notices a Discord restart even when nothing is changing
+                if active.ping().is_err() {
+                    conn = None;
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+    }
 }
 
+/// Turn presence on. Connects in the background and keeps retrying while Discord is closed.
 #[command]
-pub fn discord_rpc_connect(state: tauri::State<'_, DiscordRpcState>) -> Result<bool, String> {
-    let mut connected = state.connected.lock().map_err(|e| e.to_string())?;
-    *connected = true;
-    println!("[Discord RPC] Connected (placeholder - configure Discord App ID for full integration)");
-    Ok(true)
+pub fn discord_rpc_connect(app: AppHandle, state: tauri::State<'_, DiscordRpcState>) -> Result<RpcStatus, String> {
+    *state.enabled.lock().map_err(|e| e.to_string())? = true;
+    let mut worker = state.worker.lock().map_err(|e| e.to_string())?;
+    if worker.is_none() {
+        let (tx, rx) = channel();
+        *worker = Some(tx);
+        let handle = app.clone();
+        std::thread::spawn(move || run_worker(handle, rx));
+    }
+    drop(worker);
+    state.wake();
+    Ok(state.status.lock().map_err(|e| e.to_string())?.clone())
 }
 
 #[command]
 pub fn discord_rpc_update(
+    app: AppHandle,
     state: tauri::State<'_, DiscordRpcState>,
     details: String,
     activity_state: String,
 ) -> Result<(), String> {
-    let connected = state.connected.lock().map_err(|e| e.to_string())?;
-    if !*connected {
+    if !state.is_enabled() {
         return Ok(());
     }
 
-    let activity = RpcActivity {
-        state: activity_state.clone(),
-        details: details.clone(),
+    // Same privacy rules as the player-driven presence
+    let config = crate::config::load_config(&app);
+    if config.private_mode && config.presence.follow_private_mode {
+        return Ok(());
+    }
+    let details = if config.presence.hide_filenames { "Watching a video".to_string() } else { details };
+
+    state.publish(Some(RpcActivity {
+        state: activity_state,
+        details,
         large_image: "framex_logo".to_string(),
         large_text: "FrameX Media Player".to_string(),
-        start_timestamp: Some(now_timestamp()),
-    };
-
-    let mut act = state.activity.lock().map_err(|e| e.to_string())?;
-    *act = Some(activity);
-
-    println!("[Discord RPC] Activity: {} - {}", details, activity_state);
+        start_timestamp: Some(now_secs()),
+        ..Default::default()
+    }));
     Ok(())
 }
 
 #[command]
 pub fn discord_rpc_disconnect(state: tauri::State<'_, DiscordRpcState>) -> Result<(), String> {
-    let mut connected = state.connected.lock().map_err(|e| e.to_string())?;
-    *connected = false;
-    let mut act = state.activity.lock().map_err(|e| e.to_string())?;
-    *act = None;
-    println!("[Discord RPC] Disconnected");
+    *state.enabled.lock().map_err(|e| e.to_string())? = false;
+    state.wake();
     Ok(())
 }
 
 #[command]
-pub fn discord_rpc_status(state: tauri::State<'_, DiscordRpcState>) -> Result<bool, String> {
-    let connected = state.connected.lock().map_err(|e| e.to_string())?;
-    Ok(*connected)
+pub fn discord_rpc_status(state: tauri::State<'_, DiscordRpcState>) -> Result<RpcStatus, String> {
+    Ok(state.status.lock().map_err(|e| e.to_string())?.clone())
+}
+
+// Republish when the countdown drifts by more than this (seeks, speed changes)
+const DRIFT_TOLERANCE_SECS: u64 = 2;
+
+/// Fill `{placeholder}`s from `fields`. `|` separates fallbacks: the first alternative
+/// whose placeholders all have values wins, so "{show} S{season}E{episode}|{title}"
+/// covers both episodes and movies. `None` if no alternative resolves.
+fn render_template(template: &str, fields: &HashMap<&str, String>) -> Option<String> {
+    template.split('|').find_map(|alternative| {
+        let mut out = String::new();
+        let mut rest = alternative;
+        while let Some(open) = rest.find('{') {
+            // An unclosed brace is kept as text
+            let Some(len) = rest[open..].find('}') else {
+                break;
+            };
+            let close = open + len;
+            out.push_str(&rest[..open]);
+            let value = fields.get(&rest[open + 1..close]).filter(|v| !v.is_empty())?;
+            out.push_str(value);
+            rest = &rest[close + 1..];
+        }
+        out.push_str(rest);
+        Some(out.trim().to_string()).filter(|o| !o.is_empty())
+    })
+}
+
+fn format_duration(seconds: f64) -> String {
+    let total = seconds as u64;
+    let (h, m, s) = (total / 3600, total / 60 % 60, total % 60);
+    if h > 0 { format!("{}:{:02}:{:02}", h, m, s) } else { format!("{}:{:02}", m, s) }
+}
+
+/// Placeholders known from the file itself: title, show, season, episode, year, filename.
+fn media_fields(path: &str, media_title: Option<String>) -> HashMap<&'static str, String> {
+    let mut fields = HashMap::new();
+    if path.contains("://") {
+        fields.insert("title", media_title.unwrap_or(path.to_string()));
+        return fields;
+    }
+    let file = std::path::Path::new(path);
+    let name = file.file_name().and_then(|n| n.to_str()).unwrap_or(path);
+    fields.insert("filename", name.to_string());
+    fields.insert("title", crate::metadata::clean_video_name(name));
+    if let Some(year) = crate::metadata::parse_release_year(name) {
+        fields.insert("year", year.to_string());
+    }
+    if let Some(series) = crate::subtitle_downloader::parse_series_episode(file) {
+        fields.insert("show", series.show);
+        fields.insert("season", format!("{:02}", series.season));
+        fields.insert("episode", format!("{:02}", series.episode));
+    }
+    fields
+}
+
+/// Keeps the presence in step with mpv: fed from the player loop like `PositionAutosave`.
+pub struct PresenceTracker {
+    fields: Option<HashMap<&'static str, String>>, // None while nothing may be shown
+    details_template: String,
+    state_template: String,
+    position: f64,
+    duration: f64,
+    paused: bool,
+    sent_start: Option<u64>,
+    track: Option<String>, // ICY title of a radio stream
+    show_track: bool,
+}
+
+impl PresenceTracker {
+    pub fn new() -> Self {
+        Self {
+            fields: None,
+            details_template: String::new(),
+            state_template: String::new(),
+            position: 0.0,
+            duration: 0.0,
+            paused: false,
+            sent_start: None,
+            track: None,
+            show_track: false,
+        }
+    }
+
+    /// A file finished loading. `title` is mpv's media-title, used for streams.
+    pub fn start(&mut self, app: &AppHandle, path: Option<String>, title: Option<String>) {
+        let settings = crate::config::load_config(app).presence;
+        self.details_template = settings.details_template;
+        self.state_template = settings.state_template;
+        self.fields = match path.as_deref() {
+            Some(p) if is_excluded(p, &settings.excluded_folders) => None,
+            Some(p) if settings.hide_filenames => {
+                // Templates could still leak the show or year, so they're bypassed
+                let generic = if p.contains("://") {
+                    "Watching a stream"
+                } else if crate::subtitle_downloader::parse_series_episode(std::path::Path::new(p)).is_some() {
+                    "Watching a show"
+                } else {
+                    "Watching a movie"
+                };
+                self.details_template = "{title}".to_string();
+                self.state_template = "{status}".to_string();
+                Some(HashMap::from([("title", generic.to_string())]))
+            }
+            Some(p) => Some(media_fields(p, title)),
+            None => title.map(|t| HashMap::from([("title", t)])),
+        };
+        if self.fields.is_none() {
+            // An excluded file must not leave the previous one showing
+            app.state::<DiscordRpcState>().publish(None);
+        }
+        self.position = 0.0;
+        self.duration = 0.0;
+        self.paused = false;
+        self.sent_start = None;
+        self.track = None;
+        self.show_track = !settings.hide_filenames;
+        self.update(app);
+    }
+
+    /// The track a radio stream is playing changed.
+    pub fn stream_title(&mut self, app: &AppHandle, title: Option<String>) {
+        if self.show_track && self.track != title {
+            self.track = title;
+            self.update(app);
+        }
+    }
+
+    /// Latest playback position; only republished when the countdown would drift.
+    pub fn progress(&mut self, app: &AppHandle, position: f64, duration: f64) {
+        self.position = position;
+        self.duration = duration;
+        let start = now_secs().saturating_sub(position as u64);
+        let drifted = self.sent_start.is_none_or(|sent| sent.abs_diff(start) > DRIFT_TOLERANCE_SECS);
+        if !self.paused && drifted {
+            self.update(app);
+        }
+    }
+
+    pub fn pause(&mut self, app: &AppHandle, paused: bool) {
+        if self.paused != paused {
+            self.paused = paused;
+            self.update(app);
+        }
+    }
+
+    /// Playback ended or mpv stopped; clear the activity.
+    pub fn stop(&mut self, app: &AppHandle) {
+        if self.fields.take().is_some() {
+            self.sent_start = None;
+            app.state::<DiscordRpcState>().publish(None);
+        }
+    }
+
+    fn update(&mut self, app: &AppHandle) {
+        let Some(fields) = &self.fields else {
+            return;
+        };
+        let config = crate::config::load_config(app);
+        if config.private_mode && config.presence.follow_private_mode {
+            // Counts as sent, so progress doesn't re-check on every tick
+            self.sent_start = Some(now_secs().saturating_sub(self.position as u64));
+            app.state::<DiscordRpcState>().publish(None);
+            return;
+        }
+        let status = if self.paused { "Paused" } else { "Watching" };
+        let mut fields = fields.clone();
+        fields.insert("status", status.to_string());
+        if self.duration > 0.0 {
+            fields.insert("duration", format_duration(self.duration));
+        }
+        if let Some(track) = &self.track {
+            fields.insert("track", track.clone());
+        }
+        // Templates written before {track} existed still show what's on air
+        let state = match &self.track {
+            Some(track) if !self.state_template.contains("{track}") => Some(format!("♪ {}", track)),
+            _ => render_template(&self.state_template, &fields),
+        };
+        let mut activity = RpcActivity {
+            details: render_template(&self.details_template, &fields)
+                .or_else(|| fields.get("title").cloned())
+                .unwrap_or_default(),
+            state: state.unwrap_or(status.to_string()),
+            large_image: "framex_logo".to_string(),
+            large_text: "FrameX Media Player".to_string(),
+            ..Default::default()
+        };
+        if self.paused {
+            // No timestamps: Discord would keep counting down while paused
+            activity.small_image = Some("pause".to_string());
+            activity.small_text = Some("Paused".to_string());
+            self.sent_start = None;
+        } else {
+            let start = now_secs().saturating_sub(self.position as u64);
+            activity.small_image = Some("play".to_string());
+            activity.small_text = Some("Playing".to_string());
+            activity.start_timestamp = Some(start);
+            // Live streams report no duration
+            if self.duration > 0.0 {
+                activity.end_timestamp = Some(start + self.duration as u64);
+            }
+            self.sent_start = Some(start);
+        }
+        app.state::<DiscordRpcState>().publish(Some(activity));
+    }
+}
+
+/// `folders` entries are absolute paths (prefix match) or bare names matched against any folder.
+fn is_excluded(path: &str, folders: &[String]) -> bool {
+    let path = std::path::Path::new(path);
+    folders.iter().any(|folder| {
+        if folder.contains(['/', '\\']) {
+            path.starts_with(folder)
+        } else {
+            path.parent().is_some_and(|dir| {
+                dir.components().any(|c| c.as_os_str().to_str().is_some_and(|name| name.eq_ignore_ascii_case(folder)))
+            })
+        }
+    })
 }
@@ -0,0 +1,223 @@
+use regex::Regex;
+use std::collections::HashMap;
+use std::net::UdpSocket;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::file_scanner::VideoEntry;
+use crate::xml::{attribute, escape_xml, unescape_xml, xml_tag};
+
+// DLNA/UPnP media servers (NAS boxes, Plex, Jellyfin, minidlna) browsed as a library.
+// Paths look like `dlna://<server uuid>/<object id>`; `dlna://` alone lists the servers.
+// Items are plain HTTP URLs, so they play through mpv like any stream.
+
+pub const SCHEME: &str = "dlna://";
+
+const SSDP_ADDR: &str = "239.255.255.250:1900";
+const CONTENT_DIRECTORY: &str = "urn:schemas-upnp-org:service:ContentDirectory:1";
+const PAGE_SIZE: u32 = 200;
+const MAX_ITEMS: u32 = 5000;
+
+#[derive(Clone, Debug)]
+struct DlnaServer {
+    name: String,
+    control_url: String,
+}
+
+// Servers seen by the last discovery, by UDN
+fn servers() -> &'static Mutex<HashMap<String, DlnaServer>> {
+    static SERVERS: OnceLock<Mutex<HashMap<String, DlnaServer>>> = OnceLock::new();
+    SERVERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// SSDP M-SEARCH for ContentDirectory services; returns the description URLs.
+fn ssdp_search(timeout: Duration) -> Result<Vec<String>, String> {
+    let socket = UdpSocket::bind("0.0.0.0:0").map_err(|e| e.to_string())?;
+    socket.set_read_timeout(Some(Duration::from_millis(250))).map_err(|e| e.to_string())?;
+    let request = format!(
+        "M-SEARCH * HTTP/1.1\r\nHOST: {}\r\nMAN: \"ssdp:discover\"\r\nMX: 2\r\nST: {}\r\n\r\n",
+        SSDP_ADDR, CONTENT_DIRECTORY
+    );
+    // UDP is lossy; ask twice
+    for _ in 0..2 {
+        socket.send_to(request.as_bytes(), SSDP_ADDR).map_err(|e| format!("SSDP search failed: {}", e))?;
+    }
+
+    let deadline = Instant::now() + timeout;
+    let mut locations = Vec::new();
+    let mut buf = [0u8; 2048];
+    while Instant::now() < deadline {
+        let Ok((len, _)) = socket.recv_from(&mut buf) else {
+            continue;
+        };
+        let response = String::from_utf8_lossy(&buf[..len]);
+        let location = response.lines().find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.trim().eq_ignore_ascii_case("location").then(|| value.trim().to_string())
+        });
+        if let Some(location) = location {
+            if !locations.contains(&location) {
+                locations.push(location);
+            }
+        }
+    }
+    Ok(locations)
+}
+
+/// Name, UDN and ContentDirectory control URL from a device description.
+async fn describe(location: &str) -> Result<(String, DlnaServer), String> {
+    let xml = crate::metadata::http_client()
+        .get(location)
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .text()
+        .await
+        .map_err(|e| e.to_string())?;
+    let udn = xml_tag(&xml, "UDN").ok_or("No UDN in device description")?;
+    let name = xml_tag(&xml, "friendlyName").unwrap_or_else(|| udn.clone());
+    let service = Regex::new(r"(?s)<service>(.*?)</service>").unwrap();
+    let control = service
+        .captures_iter(&xml)
+        .find(|c| xml_tag(&c[1], "serviceType").is_some_and(|t| t.contains("ContentDirectory")))
+        .and_then(|c| xml_tag(&c[1], "controlURL"))
+        .ok_or("The device has no ContentDirectory service")?;
+    let base = xml_tag(&xml, "URLBase").unwrap_or_else(|| location.to_string());
+    let control_url = reqwest::Url::parse(&base)
+        .and_then(|b| b.join(&control))
+        .map_err(|e| format!("Bad control URL: {}", e))?;
+    let id = udn.trim_start_matches("uuid:").to_string();
+    Ok((id, DlnaServer { name, control_url: control_url.to_string() }))
+}
+
+async fn discover(timeout: Duration) -> Result<Vec<(String, DlnaServer)>, String> {
+    let locations = tauri::async_runtime::spawn_blocking(move || ssdp_search(timeout))
+        .await
+        .map_err(|e| e.to_string())??;
+    let mut found = Vec::new();
+    for location in locations {
+        match describe(&location).await {
+            Ok((id, server)) if !found.iter().any(|(f, _): &(String, DlnaServer)| *f == id) => found.push((id, server)),
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Skipping UPnP device at {}: {}", location, e),
+        }
+    }
+    let mut known = servers().lock().map_err(|e| e.to_string())?;
+    for (id, server) in &found {
+        known.insert(id.clone(), server.clone());
+    }
+    tracing::info!("🗄️ Found {} DLNA server(s)", found.len());
+    Ok(found)
+}
+
+/// One page of ContentDirectory Browse: (DIDL-Lite, returned, total).
+async fn browse_page(control_url: &str, object_id: &str, start: u32) -> Result<(String, u32, u32), String> {
+    let body = format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="utf-8"?>"#,
+            r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/" s:encodingStyle="http://schemas.xmlsoap.org/soap/encoding/">"#,
+            r#"<s:Body><u:Browse xmlns:u="{}">"#,
+            "<ObjectID>{}</ObjectID><BrowseFlag>BrowseDirectChildren</BrowseFlag><Filter>*</Filter>",
+            "<StartingIndex>{}</StartingIndex><RequestedCount>{}</RequestedCount><SortCriteria></SortCriteria>",
+            "</u:Browse></s:Body></s:Envelope>"
+        ),
+        CONTENT_DIRECTORY,
+        escape_xml(object_id),
+        start,
+        PAGE_SIZE
+    );
+    let response = crate::metadata::http_client()
+        .post(control_url)
+        .header("Content-Type", "text/xml; charset=\"utf-8\"")
+        .header("SOAPAction", format!("\"{}#Browse\"", CONTENT_DIRECTORY))
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| format!("DLNA browse failed: {}", e))?;
+    let status = response.status();
+    let xml = response.text().await.map_err(|e| e.to_string())?;
+    if !status.is_success() {
+        let detail = xml_tag(&xml, "errorDescription").unwrap_or_else(|| status.to_string());
+        return Err(format!("DLNA server error: {}", detail));
+    }
+    // `xml_tag` unescapes once, which turns the embedded DIDL-Lite back into markup
+    let didl = xml_tag(&xml, "Result").unwrap_or_default();
+    let returned = xml_tag(&xml, "NumberReturned").and_then(|n| n.parse().ok()).unwrap_or(0);
+    let total = xml_tag(&xml, "TotalMatches").and_then(|n| n.parse().ok()).unwrap_or(0);
+    Ok((didl, returned, total))
+}
+
+fn parse_didl(didl: &str, server_id: &str, entries: &mut Vec<VideoEntry>) {
+    let container = Regex::new(r"(?s)<container(\s[^>]*)?>(.*?)</container>").unwrap();
+    for c in container.captures_iter(didl) {
+        let attrs = c.get(1).map_or("", |m| m.as_str());
+        let Some(id) = attribute(attrs, "id") else {
+            continue;
+        };
+        entries.push(VideoEntry::remote(
+            format!("{}{}/{}", SCHEME, server_id, urlencoding::encode(&id)),
+            xml_tag(&c[2], "dc:title").unwrap_or(id),
+            0,
+            "folder",
+        ));
+    }
+
+    let item = Regex::new(r"(?s)<item(\s[^>]*)?>(.*?)</item>").unwrap();
+    let res = Regex::new(r"(?s)<res(\s[^>]*)?>(.*?)</res>").unwrap();
+    for c in item.captures_iter(didl) {
+        let body = &c[2];
+        let class = xml_tag(body, "upnp:class").unwrap_or_default();
+        // The first video resource is the original file; later ones are transcodes
+        let resource = res.captures_iter(body).find(|r| {
+            let info = r.get(1).and_then(|a| attribute(a.as_str(), "protocolInfo")).unwrap_or_default();
+            info.split(':').nth(2).is_some_and(|mime| mime.starts_with("video/")) || class.starts_with("object.item.videoItem")
+        });
+        let Some(resource) = resource else {
+            continue;
+        };
+        let attrs = resource.get(1).map_or("", |m| m.as_str());
+        let url = unescape_xml(resource[2].trim());
+        let size = attribute(attrs, "size").and_then(|s| s.parse().ok()).unwrap_or(0);
+        let name = xml_tag(body, "dc:title").unwrap_or_else(|| url.rsplit('/').next().unwrap_or_default().to_string());
+        entries.push(VideoEntry::remote(url, name, size, "video"));
+    }
+}
+
+/// `list_videos` for `dlna://` paths.
+pub async fn list(path: &str) -> Result<Vec<VideoEntry>, String> {
+    let rest = path.strip_prefix(SCHEME).unwrap_or(path).trim_matches('/');
+    if rest.is_empty() {
+        let found = discover(Duration::from_secs(3)).await?;
+        return Ok(found
+            .into_iter()
+            .map(|(id, server)| VideoEntry::remote(format!("{}{}/0", SCHEME, id), server.name, 0, "folder"))
+            .collect());
+    }
+
+    let (server_id, object_id) = rest.split_once('/').unwrap_or((rest, "0"));
+    let object_id = urlencoding::decode(object_id).map(|o| o.into_owned()).unwrap_or_else(|_| object_id.to_string());
+    let known = servers().lock().map_err(|e| e.to_string())?.get(server_id).cloned();
+    let server = match known {
+        Some(server) => server,
+        // Opened from history or a bookmark: find the server again
+        None => discover(Duration::from_secs(3))
+            .await?
+            .into_iter()
+            .find(|(id, _)| id == server_id)
+            .map(|(_, server)| server)
+            .ok_or("That DLNA server isn't reachable right now")?,
+    };
+
+    let mut entries = Vec::new();
+    let mut start = 0;
+    loop {
+        let (didl, returned, total) = browse_page(&server.control_url, &object_id, start).await?;
+        parse_didl(&didl, server_id, &mut entries);
+        start += returned;
+        if returned == 0 || start >= total || start >= MAX_ITEMS {
+            break;
+        }
+    }
+    Ok(entries)
+}
@@ -0,0 +1,489 @@
+use serde::Serialize;
+use std::fs;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{command, AppHandle, Emitter, Manager, State};
+
+use crate::config::{load_config, DownloadSettings};
+
+// Lines we ask yt-dlp to print, so progress doesn't depend on its human-readable output
+const PROGRESS_PREFIX: &str = "FRAMEX-PROGRESS ";
+const TITLE_PREFIX: &str = "FRAMEX-TITLE ";
+const ID_PREFIX: &str = "FRAMEX-ID ";
+const FILE_PREFIX: &str = "FRAMEX-FILE ";
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DownloadItem {
+    pub id: String,
+    pub url: String,
+    pub title: Option<String>,
+    pub format: String,
+    pub output_dir: String,
+    pub status: String, // "queued" | "downloading" | "processing" | "done" | "failed" | "cancelled"
+    pub progress: f32,  // 0-100
+    pub speed: Option<String>,
+    pub eta: Option<String>,
+    pub file_path: Option<String>,
+    pub error: Option<String>,
+}
+
+/// A queued download, shared between the worker and `cancel_download`.
+pub struct Download {
+    item: Mutex<DownloadItem>,
+    cancelled: AtomicBool,
+    child: Mutex<Option<Child>>,
+}
+
+impl Download {
+    fn snapshot(&self) -> DownloadItem {
+        self.item.lock().unwrap().clone()
+    }
+
+    /// Change the item and emit `download-progress` with the result.
+    fn update(&self, app: &AppHandle, f: impl FnOnce(&mut DownloadItem)) {
+        let item = {
+            let mut item = self.item.lock().unwrap();
+            f(&mut item);
+            // A cancel wins over whatever the worker was about to report
+            if self.cancelled.load(Ordering::SeqCst) {
+                item.status = "cancelled".to_string();
+            }
+            item.clone()
+        };
+        let _ = app.emit("download-progress", item);
+    }
+}
+
+pub struct DownloadState {
+    items: Mutex<Vec<Arc<Download>>>,
+    worker_running: AtomicBool,
+}
+
+impl DownloadState {
+    pub fn new() -> Self {
+        Self { items: Mutex::new(Vec::new()), worker_running: AtomicBool::new(false) }
+    }
+
+    fn next_queued(&self) -> Option<Arc<Download>> {
+        self.items.lock().ok()?.iter().find(|d| d.snapshot().status == "queued").cloned()
+    }
+}
+
+fn ytdlp(settings: &DownloadSettings) -> Command {
+    #[cfg_attr(not(target_os = "windows"), allow(unused_mut))]
+    crate::ffmpeg::hidden(settings.ytdlp_path.as_deref().unwrap_or("yt-dlp"))
+}
+
+fn output_dir(app: &AppHandle, settings: &DownloadSettings) -> Result<PathBuf, String> {
+    match &settings.output_dir {
+        Some(dir) => Ok(PathBuf::from(dir)),
+        None => Ok(app.path().video_dir().map_err(|e| e.to_string())?.join("FrameX")),
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct DownloadFormat {
+    pub format_id: String,
+    pub ext: String,
+    pub resolution: Option<String>,
+    pub fps: Option<f64>,
+    pub vcodec: Option<String>, // None for audio-only
+    pub acodec: Option<String>, // None for video-only
+    pub filesize: Option<u64>,
+    pub note: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct DownloadFormats {
+    pub title: String,
+    pub duration: Option<f64>,
+    pub thumbnail: Option<String>,
+    pub formats: Vec<DownloadFormat>, // best first
+}
+
+/// Formats yt-dlp offers for `url`, for picking one before queueing.
+#[command]
+pub async fn list_download_formats(app: AppHandle, url: String) -> Result<DownloadFormats, String> {
+    let settings = load_config(&app).downloads;
+    let output = tauri::async_runtime::spawn_blocking(move || {
+        ytdlp(&settings).args(["-J", "--no-playlist", "--no-warnings"]).arg(&url).output()
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| format!("Failed to run yt-dlp (is it installed?): {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(stderr.lines().rfind(|l| l.starts_with("ERROR")).unwrap_or("yt-dlp failed").to_string());
+    }
+    let info: serde_json::Value = serde_json::from_slice(&output.stdout).map_err(|e| format!("Unexpected yt-dlp output: {}", e))?;
+
+    let text = |v: &serde_json::Value, key: &str| v.get(key).and_then(|x| x.as_str()).map(String::from);
+    // yt-dlp reports "none" for a missing stream
+    let codec = |v: &serde_json::Value, key: &str| text(v, key).filter(|c| c != "none");
+    let mut formats: Vec<DownloadFormat> = info
+        .get("formats")
+        .and_then(|f| f.as_array())
+        .into_iter()
+        .flatten()
+        .filter(|f| codec(f, "vcodec").is_some() || codec(f, "acodec").is_some()) // storyboards
+        .map(|f| DownloadFormat {
+            format_id: text(f, "format_id").unwrap_or_default(),
+            ext: text(f, "ext").unwrap_or_default(),
+            resolution: text(f, "resolution").filter(|r| r != "audio only"),
+            fps: f.get("fps").and_then(|x| x.as_f64()),
+            vcodec: codec(f, "vcodec"),
+            acodec: codec(f, "acodec"),
+            filesize: f.get("filesize").or(f.get("filesize_approx")).and_then(|x| x.as_u64()),
+            note: text(f, "format_note"),
+        })
+        .collect();
+    formats.reverse();
+    Ok(DownloadFormats {
+        title: text(&info, "title").unwrap_or_default(),
+        duration: info.get("duration").and_then(|d| d.as_f64()),
+        thumbnail: text(&info, "thumbnail"),
+        formats,
+    })
+}
+
+/// Queue `url` for download; `format` is a yt-dlp selector (defaults to the configured one).
+#[command]
+pub fn queue_download(
+    app: AppHandle,
+    state: State<'_, DownloadState>,
+    url: String,
+    format: Option<String>,
+    output_dir: Option<String>,
+) -> Result<DownloadItem, String> {
+    crate::parental::check_area(&app, "downloads")?;
+    let url = url.trim().to_string();
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err("Downloads need an http(s) URL".to_string());
+    }
+    let settings = load_config(&app).downloads;
+    let output_dir = match output_dir.filter(|d| !d.trim().is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => self::output_dir(&app, &settings)?,
+    };
+    let item = DownloadItem {
+        id: uuid::Uuid::new_v4().to_string(),
+        url,
+        title: None,
+        format: format.filter(|f| !f.trim().is_empty()).unwrap_or(settings.default_format),
+        output_dir: output_dir.to_string_lossy().to_string(),
+        status: "queued".to_string(),
+        progress: 0.0,
+        speed: None,
+        eta: None,
+        file_path: None,
+        error: None,
+    };
+    state.items.lock().map_err(|e| e.to_string())?.push(Arc::new(Download {
+        item: Mutex::new(item.clone()),
+        cancelled: AtomicBool::new(false),
+        child: Mutex::new(None),
+    }));
+    let _ = app.emit("download-progress", item.clone());
+
+    if state.worker_running.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_ok() {
+        let app = app.clone();
+        std::thread::spawn(move || run_queue(app));
+    }
+    Ok(item)
+}
+
+/// One download at a time, oldest first, until the queue is empty.
+fn run_queue(app: AppHandle) {
+    let state = app.state::<DownloadState>();
+    loop {
+        if let Some(download) = state.next_queued() {
+            if let Err(e) = run_download(&app, &download) {
+                let cancelled = download.cancelled.load(Ordering::SeqCst);
+                download.update(&app, |item| {
+                    item.status = if cancelled { "cancelled" } else { "failed" }.to_string();
+                    item.error = (!cancelled).then_some(e.clone());
+                    item.speed = None;
+                    item.eta = None;
+                });
+                if !cancelled {
+                    tracing::error!("Download of {} failed: {}", download.snapshot().url, e);
+                }
+            }
+            continue;
+        }
+        state.worker_running.store(false, Ordering::SeqCst);
+        // Something may have been queued between the check and the store
+        if state.next_queued().is_none() || state.worker_running.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_err() {
+            return;
+        }
+    }
+}
+
+/// `" 12.3%|1.20MiB/s|00:32"` from the progress template.
+fn parse_progress(line: &str) -> Option<(f32, Option<String>, Option<String>)> {
+    let mut parts = line.split('|').map(str::trim);
+    let percent = parts.next()?.trim_end_matches('%').parse().ok()?;
+    let known = |s: Option<&str>| s.filter(|s| !s.is_empty() && *s != "NA" && *s != "N/A" && !s.starts_with("Unknown")).map(String::from);
+    Some((percent, known(parts.next()), known(parts.next())))
+}
+
+/// What an unfinished download of `video_id` leaves behind under our output template:
+/// `.part` files and fragments, `.ytdl` state, per-format pieces (`.f137.mp4`) and the
+/// `.temp.` merge output.
+fn is_partial_file(name: &str, video_id: &str) -> bool {
+    let Some((_, rest)) = name.split_once(&format!("[{}].", video_id)) else {
+        return false;
+    };
+    let format_piece = rest
+        .strip_prefix('f')
+        .and_then(|r| r.split_once('.'))
+        .is_some_and(|(n, _)| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+    rest.contains(".part") || rest.ends_with(".ytdl") || rest.starts_with("temp.") || format_piece
+}
+
+fn remove_partial_files(dir: &Path, video_id: &str) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if is_partial_file(&name, video_id) && entry.file_type().is_ok_and(|t| t.is_file()) {
+            if let Err(e) = fs::remove_file(entry.path()) {
+                tracing::warn!("⚠️ Could not remove {}: {}", name, e);
+            }
+        }
+    }
+}
+
+fn run_download(app: &AppHandle, download: &Download) -> Result<(), String> {
+    let settings = load_config(app).downloads;
+    let item = download.snapshot();
+    fs::create_dir_all(&item.output_dir).map_err(|e| format!("Failed to create {}: {}", item.output_dir, e))?;
+
+    let mut command = ytdlp(&settings);
+    command
+        .args(["--newline", "--no-playlist", "--no-colors", "--progress", "--no-simulate"])
+        .arg("--progress-template")
+        .arg(format!("download:{}%(progress._percent_str)s|%(progress._speed_str)s|%(progress._eta_str)s", PROGRESS_PREFIX))
+        .arg("--print")
+        .arg(format!("before_dl:{}%(title)s", TITLE_PREFIX))
+        .arg("--print")
+        .arg(format!("before_dl:{}%(id)s", ID_PREFIX))
+        .arg("--print")
+        .arg(format!("after_move:{}%(filepath)s", FILE_PREFIX))
+        .args(["--merge-output-format", "mp4/mkv"])
+        .arg("-f")
+        .arg(&item.format)
+        .arg("-P")
+        .arg(&item.output_dir)
+        .args(["-o", "%(title).200B [%(id)s].%(ext)s"]);
+    if settings.import_metadata {
+        command.args(["--write-info-json", "--write-thumbnail", "--convert-thumbnails", "jpg"]);
+    }
+    let mut child = command
+        .arg(&item.url)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run yt-dlp (is it installed?): {}", e))?;
+    let stdout = child.stdout.take().ok_or("Failed to capture yt-dlp output")?;
+    let mut stderr = child.stderr.take().ok_or("Failed to capture yt-dlp output")?;
+    {
+        let mut slot = download.child.lock().map_err(|e| e.to_string())?;
+        *slot = Some(child);
+        // A cancel that came in before the child was stored had nothing to kill
+        if download.cancelled.load(Ordering::SeqCst) {
+            if let Some(child) = slot.as_mut() {
+                let _ = child.kill();
+            }
+        }
+    }
+    download.update(app, |item| item.status = "downloading".to_string());
+
+    // Errors land on stderr; drain it alongside so neither pipe fills up
+    let errors = std::thread::spawn(move || {
+        let mut text = String::new();
+        let _ = stderr.read_to_string(&mut text);
+        text
+    });
+
+    let mut file_path = None;
+    let mut video_id = None;
+    let mut last_emitted = -1.0;
+    for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+        if let Some(progress) = line.strip_prefix(PROGRESS_PREFIX) {
+            let Some((percent, speed, eta)) = parse_progress(progress) else {
+                continue;
+            };
+            // yt-dlp prints several lines a second; a tenth of a percent is plenty
+            if (percent - last_emitted).abs() < 0.1 && percent < 100.0 {
+                continue;
+            }
+            last_emitted = percent;
+            download.update(app, |item| {
+                item.progress = percent;
+                item.speed = speed;
+                item.eta = eta;
+                // Merging / thumbnail conversion follows the last byte
+                if percent >= 100.0 {
+                    item.status = "processing".to_string();
+                }
+            });
+        } else if let Some(title) = line.strip_prefix(TITLE_PREFIX) {
+            download.update(app, |item| item.title = Some(title.to_string()));
+        } else if let Some(id) = line.strip_prefix(ID_PREFIX) {
+            video_id = Some(id.trim().to_string());
+        } else if let Some(path) = line.strip_prefix(FILE_PREFIX) {
+            file_path = Some(PathBuf::from(path));
+        }
+    }
+
+    let status = download
+        .child
+        .lock()
+        .map_err(|e| e.to_string())?
+        .take()
+        .map(|mut c| c.wait())
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    let errors = errors.join().unwrap_or_default();
+    if download.cancelled.load(Ordering::SeqCst) {
+        if let Some(id) = video_id.filter(|id| !id.is_empty()) {
+            remove_partial_files(Path::new(&item.output_dir), &id);
+        }
+        return Err("Cancelled".to_string());
+    }
+    if !status.is_some_and(|s| s.success()) {
+        return Err(errors.lines().rfind(|l| l.starts_with("ERROR")).unwrap_or("yt-dlp failed").to_string());
+    }
+    let file_path = file_path.filter(|p| p.exists()).ok_or("yt-dlp didn't report the downloaded file")?;
+
+    if settings.import_metadata {
+        download.update(app, |item| item.status = "processing".to_string());
+        import_metadata(app, &file_path);
+    }
+    tracing::info!("⬇️ Downloaded {}", file_path.display());
+    download.update(app, |item| {
+        item.status = "done".to_string();
+        item.progress = 100.0;
+        item.speed = None;
+        item.eta = None;
+        item.file_path = Some(file_path.to_string_lossy().to_string());
+    });
+    Ok(())
+}
+
+/// Turn yt-dlp's sidecars into the library's own: `<stem>.jpg` becomes the poster and
+/// `<stem>.info.json` an `.nfo`.
+fn import_metadata(app: &AppHandle, video: &Path) {
+    let (Some(dir), Some(stem)) = (video.parent(), video.file_stem().and_then(|s| s.to_str())) else {
+        return;
+    };
+    let thumbnail = dir.join(format!("{}.jpg", stem));
+    if thumbnail.exists() {
+        match crate::poster_cache::poster_destination(app, video) {
+            Ok(poster) if poster != thumbnail => {
+                if fs::rename(&thumbnail, &poster).is_err() && fs::copy(&thumbnail, &poster).is_ok() {
+                    let _ = fs::remove_file(&thumbnail);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("⚠️ Could not store poster for {}: {}", video.display(), e),
+        }
+    }
+
+    let info_path = dir.join(format!("{}.info.json", stem));
+    let info = fs::read_to_string(&info_path).ok().and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok());
+    if let Some(info) = info {
+        let nfo = video.with_extension("nfo");
+        if !nfo.exists() {
+            if let Err(e) = fs::write(&nfo, crate::nfo_export::online_video_nfo(&info)) {
+                tracing::warn!("⚠️ Could not write {}: {}", nfo.display(), e);
+            }
+        }
+    }
+    let _ = fs::remove_file(&info_path);
+}
+
+#[command]
+pub fn list_downloads(state: State<'_, DownloadState>) -> Result<Vec<DownloadItem>, String> {
+    Ok(state.items.lock().map_err(|e| e.to_string())?.iter().map(|d| d.snapshot()).collect())
+}
+
+/// Cancel a queued or running download, killing yt-dlp if it has started.
+#[command]
+pub fn cancel_download(app: AppHandle, state: State<'_, DownloadState>, id: String) -> Result<bool, String> {
+    let download = state.items.lock().map_err(|e| e.to_string())?.iter().find(|d| d.snapshot().id == id).cloned();
+    let Some(download) = download else {
+        return Ok(false);
+    };
+    if matches!(download.snapshot().status.as_str(), "done" | "failed" | "cancelled") {
+        return Ok(false);
+    }
+    download.cancelled.store(true, Ordering::SeqCst);
+    if let Some(child) = download.child.lock().map_err(|e| e.to_string())?.as_mut() {
+        let _ = child.kill();
+    }
+    download.update(&app, |item| {
+        item.status = "cancelled".to_string();
+        item.speed = None;
+        item.eta = None;
+    });
+    Ok(true)
+}
+
+/// Drop finished, failed and cancelled items from the list.
+#[command]
+pub fn clear_finished_downloads(state: State<'_, DownloadState>) -> Result<(), String> {
+    state
+        .items
+        .lock()
+        .map_err(|e| e.to_string())?
+        .retain(|d| matches!(d.snapshot().status.as_str(), "queued" | "downloading" | "processing"));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_lines() {
+        let some = |percent, speed: Option<&str>, eta: Option<&str>| Some((percent, speed.map(String::from), eta.map(String::from)));
+        assert_eq!(parse_progress(" 12.3%|1.20MiB/s|00:32"), some(12.3, Some("1.20MiB/s"), Some("00:32")));
+        assert_eq!(parse_progress("100.0%|  2.50KiB/s|00:00"), some(100.0, Some("2.50KiB/s"), Some("00:00")));
+        // Speed and ETA aren't known at the start or for some streams
+        assert_eq!(parse_progress("  0.0%|Unknown B/s|Unknown"), some(0.0, None, None));
+        assert_eq!(parse_progress(" 50.0%|NA|NA"), some(50.0, None, None));
+        assert_eq!(parse_progress(" 50.0%"), some(50.0, None, None));
+        assert_eq!(parse_progress("N/A%|1MiB/s|00:10"), None);
+        assert_eq!(parse_progress(""), None);
+    }
+
+    #[test]
+    fn partial_files() {
+        let id = "dQw4w9WgXcQ";
+        for name in [
+            "Song [dQw4w9WgXcQ].mp4.part",
+            "Song [dQw4w9WgXcQ].f137.mp4.part",
+            "Song [dQw4w9WgXcQ].f137.mp4.part-Frag12",
+            "Song [dQw4w9WgXcQ].mp4.ytdl",
+            "Song [dQw4w9WgXcQ].f251.webm",
+            "Song [dQw4w9WgXcQ].temp.mp4",
+        ] {
+            assert!(is_partial_file(name, id), "{}", name);
+        }
+        for name in [
+            "Song [dQw4w9WgXcQ].mp4",
+            "Song [dQw4w9WgXcQ].jpg",
+            "Song [dQw4w9WgXcQ].info.json",
+            "Song [other].mp4.part",
+            "notes.part",
+        ] {
+            assert!(!is_partial_file(name, id), "{}", name);
+        }
+    }
+}
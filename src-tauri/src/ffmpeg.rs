@@ -0,0 +1,91 @@
+use std::ffi::OsStr;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// `program` as a command that doesn't flash a console window on Windows. Every external
+/// tool we run goes through here.
+pub fn hidden(program: impl AsRef<OsStr>) -> Command {
+    #[cfg_attr(not(target_os = "windows"), allow(unused_mut))]
+    let mut command = Command::new(program);
+
+    #[cfg(target_os = "windows")]
+    command.creation_flags(CREATE_NO_WINDOW);
+
+    command
+}
+
+/// An `ffmpeg` command that doesn't flash a console window on Windows, overwrites its
+/// output and reports progress as `key=value` lines on stderr.
+pub fn command() -> Command {
+    let mut command = hidden("ffmpeg");
+    command
+        .args(["-y", "-hide_banner", "-nostdin"])
+        .args(["-loglevel", "error"])
+        .args(["-progress", "pipe:2"]);
+    command
+}
+
+/// An `ffprobe` command that doesn't flash a console window on Windows and only logs errors.
+pub fn ffprobe() -> Command {
+    let mut command = hidden("ffprobe");
+    command.args(["-v", "error"]);
+    command
+}
+
+/// Run a `command()` to completion, calling `progress` with 0-100 as it encodes `duration`
+/// seconds of output. The child is parked in `child` while it runs so it can be killed.
+pub fn run_with_progress(mut command: Command, duration: f64, child: &Mutex<Option<Child>>, mut progress: impl FnMut(f32)) -> Result<(), String> {
+    let mut process = command
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("FFmpeg error: {}. Make sure ffmpeg is installed and in PATH.", e))?;
+    let stderr = process.stderr.take();
+    *child.lock().unwrap() = Some(process);
+
+    // Non-progress lines are ffmpeg errors; keep them for the failure message
+    let mut errors = Vec::new();
+    if let Some(stderr) = stderr {
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+            if let Some(us) = line.strip_prefix("out_time_us=") {
+                let secs = us.trim().parse::<f64>().unwrap_or(0.0) / 1_000_000.0;
+                if duration > 0.0 {
+                    progress((secs / duration * 100.0).clamp(0.0, 100.0) as f32);
+                }
+            } else if !line.contains('=') {
+                errors.push(line);
+            }
+        }
+    }
+
+    let status = child.lock().unwrap().take().map(|mut c| c.wait());
+    match status {
+        Some(Ok(s)) if s.success() => Ok(()),
+        Some(Err(e)) => Err(format!("ffmpeg failed: {}", e)),
+        _ => Err(errors.last().cloned().unwrap_or_else(|| "ffmpeg failed".to_string())),
+    }
+}
+
+/// `name.ext` in `dir`, or `name (2).ext` and so on when that's taken. The name is reserved
+/// by creating it empty, so two jobs started together can't pick the same one; ffmpeg's `-y`
+/// then overwrites only that placeholder. Callers remove it if the job fails or is cancelled.
+pub fn unique_output(dir: &std::path::Path, name: &str, ext: &str) -> Result<std::path::PathBuf, String> {
+    let mut path = dir.join(format!("{}.{}", name, ext));
+    let mut n = 2;
+    loop {
+        match std::fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(_) => return Ok(path),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                path = dir.join(format!("{} ({}).{}", name, n, ext));
+                n += 1;
+            }
+            Err(e) => return Err(format!("Failed to create {}: {}", path.display(), e)),
+        }
+    }
+}
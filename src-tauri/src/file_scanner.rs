@@ -1,28 +1,60 @@
 
+use std::path::Path;
 use tauri::command;
-use std::process::Command;
-#[cfg(target_os = "windows")]
-use std::os::windows::process::CommandExt;
 
-const CREATE_NO_WINDOW: u32 = 0x08000000;
+pub const VIDEO_EXTENSIONS: &[&str] = &["mp4", "mkv", "avi", "mov", "webm", "flv", "wmv"];
+
+/// Whether `path` ends in one of `VIDEO_EXTENSIONS`, in any case.
+pub fn is_video(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| VIDEO_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
 
 #[derive(serde::Serialize)]
 pub struct VideoEntry {
     path: String,
-    name: String,
+    pub(crate) name: String,
     size: u64,
     modified: u64,
     created: u64,
-    entry_type: String, // "video" or "folder"
+    pub(crate) entry_type: String, // "video" or "folder"
     poster_path: Option<String>,
+    rating: Option<u8>, // the user's star rating
+}
+
+impl VideoEntry {
+    /// An entry from a network source; `path` is its URL (videos) or browse path (folders).
+    pub fn remote(path: String, name: String, size: u64, entry_type: &str) -> Self {
+        Self { path, name, size, modified: 0, created: 0, entry_type: entry_type.to_string(), poster_path: None, rating: None }
+    }
 }
 
 use crate::config::save_last_folder_internal;
 
+/// List a folder: local paths, or `dlna://` paths on a media server.
 #[command]
-pub fn list_videos(app: tauri::AppHandle, folder_path: String) -> Result<Vec<VideoEntry>, String> {
+pub async fn list_videos(app: tauri::AppHandle, folder_path: String, sort_by: Option<String>) -> Result<Vec<VideoEntry>, String> {
+    let guard = crate::parental::Guard::new(&app);
+    if !guard.visible(&folder_path) {
+        return Err("This folder is locked by parental controls".to_string());
+    }
+    let mut entries = if folder_path.starts_with(crate::dlna::SCHEME) {
+        crate::dlna::list(&folder_path).await?
+    } else if folder_path.starts_with(crate::network_sources::SCHEME) {
+        crate::network_sources::list(&app, &folder_path).await?
+    } else {
+        tauri::async_runtime::spawn_blocking(move || list_local_videos(app, folder_path, sort_by))
+            .await
+            .map_err(|e| e.to_string())??
+    };
+    entries.retain(|e| guard.visible(&e.path));
+    Ok(entries)
+}
+
+fn list_local_videos(app: tauri::AppHandle, folder_path: String, sort_by: Option<String>) -> Result<Vec<VideoEntry>, String> {
     let _ = save_last_folder_internal(&app, folder_path.clone());
-    let supported_extensions = ["mp4", "mkv", "avi", "mov", "webm", "flv", "wmv"];
+    let supported_extensions = VIDEO_EXTENSIONS;
     let mut entries = Vec::new();
 
     // Read directory (non-recursive)
@@ -44,16 +76,9 @@ pub fn list_videos(app: tauri::AppHandle, folder_path: String) -> Result<Vec<Vid
         
         // Handle Folders
         if path.is_dir() {
-            // Check for poster in the folder: poster.jpg, or {folder_name}.poster.jpg
-            let mut folder_poster_path = None;
-            let poster_check_1 = path.join("poster.jpg");
-            let poster_check_2 = path.join(format!("{}.poster.jpg", &name));
-            
-            if poster_check_1.exists() {
-                folder_poster_path = Some(poster_check_1.to_string_lossy().to_string());
-            } else if poster_check_2.exists() {
-                folder_poster_path = Some(poster_check_2.to_string_lossy().to_string());
-            }
+            // Check for poster in the folder (poster.jpg, {folder_name}.poster.jpg) or the central cache
+            let folder_poster_path = crate::poster_cache::find_poster(&app, &path)
+                .map(|p| p.to_string_lossy().to_string());
 
             entries.push(VideoEntry {
                 path: path_str,
@@ -65,6 +90,7 @@ pub fn list_videos(app: tauri::AppHandle, folder_path: String) -> Result<Vec<Vid
                     .duration_since(std::time::SystemTime::UNIX_EPOCH).unwrap_or_default().as_secs(),
                 entry_type: "folder".to_string(),
                 poster_path: folder_poster_path,
+                rating: None,
             });
             continue;
         }
@@ -80,31 +106,9 @@ pub fn list_videos(app: tauri::AppHandle, folder_path: String) -> Result<Vec<Vid
                          let created = metadata.created().unwrap_or(std::time::SystemTime::UNIX_EPOCH)
                                 .duration_since(std::time::SystemTime::UNIX_EPOCH).unwrap_or_default().as_secs();
                          
-                         // Check for poster - Anywhere, checking the folder name is unnecessary restriction
-                         // let parent_name = path.parent()
-                         //    .and_then(|p| p.file_name())
-                         //    .and_then(|n| n.to_str())
-                         //    .unwrap_or("");
- 
-                         let mut poster_path = None;
-                         
-                         // if parent_name.eq_ignore_ascii_case("Movies") {
-                             let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("video");
-                             let poster_filename = format!("{}.poster.jpg", stem);
-                             // Also check for simple .jpg with same name (common convention)
-                             let simple_poster_filename = format!("{}.jpg", stem);
-                             
-                             let parent_dir = path.parent().unwrap_or(std::path::Path::new(""));
-                             let poster_path_buf = parent_dir.join(&poster_filename);
-                             let simple_poster_path_buf = parent_dir.join(&simple_poster_filename);
-                             
-                             if poster_path_buf.exists() {
-                                 poster_path = Some(poster_path_buf.to_string_lossy().to_string());
-                             } else if simple_poster_path_buf.exists() {
-                                // Only use .jpg if it's not the video itself (unlikely for mp4 but possible for some extensions)
-                                 poster_path = Some(simple_poster_path_buf.to_string_lossy().to_string());
-                             }
-                         // }
+                         // Check for poster: <stem>.poster.jpg, <stem>.jpg, or the central cache
+                         let poster_path = crate::poster_cache::find_poster(&app, &path)
+                             .map(|p| p.to_string_lossy().to_string());
 
                          entries.push(VideoEntry {
                             path: path_str,
@@ -114,6 +118,7 @@ pub fn list_videos(app: tauri::AppHandle, folder_path: String) -> Result<Vec<Vid
                             created,
                             entry_type: "video".to_string(),
                             poster_path, // Add poster path
+                            rating: None,
                         });
                     }
                 }
@@ -121,10 +126,19 @@ pub fn list_videos(app: tauri::AppHandle, folder_path: String) -> Result<Vec<Vid
         }
     }
     
-    // Sort: Folders first, then Videos. Both alphabetical.
+    let paths: Vec<String> = entries.iter().filter(|e| e.entry_type == "video").map(|e| e.path.clone()).collect();
+    let ratings = crate::tags::ratings_for(&app, &paths).unwrap_or_default();
+    for entry in entries.iter_mut() {
+        entry.rating = ratings.get(&entry.path).copied();
+    }
+
+    // Sort: Folders first, then Videos. Both alphabetical, or videos by rating (highest first) with sort_by "rating".
+    let by_rating = sort_by.as_deref() == Some("rating");
     entries.sort_by(|a, b| {
         if a.entry_type != b.entry_type {
             if a.entry_type == "folder" { std::cmp::Ordering::Less } else { std::cmp::Ordering::Greater }
+        } else if by_rating && a.rating != b.rating {
+            b.rating.cmp(&a.rating)
         } else {
             a.name.to_lowercase().cmp(&b.name.to_lowercase())
         }
@@ -135,13 +149,7 @@ pub fn list_videos(app: tauri::AppHandle, folder_path: String) -> Result<Vec<Vid
 
 #[command]
 pub fn get_video_duration(video_path: String) -> Result<f64, String> {
-    let mut command = Command::new("ffprobe");
-    
-    #[cfg(target_os = "windows")]
-    command.creation_flags(CREATE_NO_WINDOW);
-
-    let output = command
-        .arg("-v").arg("error")
+    let output = crate::ffmpeg::ffprobe()
         .arg("-show_entries").arg("format=duration")
         .arg("-of").arg("default=noprint_wrappers=1:nokey=1")
         .arg(&video_path)
@@ -0,0 +1,334 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use tauri::{command, AppHandle};
+
+use crate::ffmpeg::hidden;
+
+/// (ffmpeg hwaccel, mpv hwdec) for the decoders worth probing on this OS, best first.
+#[cfg(target_os = "windows")]
+const HW_APIS: &[(&str, &str)] = &[("d3d11va", "d3d11va"), ("cuda", "nvdec"), ("qsv", "qsv"), ("dxva2", "dxva2"), ("vulkan", "vulkan")];
+#[cfg(target_os = "macos")]
+const HW_APIS: &[(&str, &str)] = &[("videotoolbox", "videotoolbox")];
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const HW_APIS: &[(&str, &str)] = &[("vaapi", "vaapi"), ("cuda", "nvdec"), ("qsv", "qsv"), ("vulkan", "vulkan")];
+
+// Printed when a hwaccel can't decode the stream and ffmpeg falls back to software
+const FALLBACK_MARKERS: &[&str] = &[
+    "Failed setup for format",
+    "hwaccel initialisation returned error",
+    "Failed to get HW",
+    "No support for codec",
+];
+
+#[derive(Serialize, Clone, Debug)]
+pub struct HwdecSupport {
+    pub api: String, // mpv `hwdec` name
+    pub available: bool, // a device could be opened
+    pub hevc: Option<bool>, // None when no test clip could be made
+    pub av1: Option<bool>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DisplayCaps {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+    pub refresh_hz: Option<f64>, // current mode
+    pub refresh_rates: Vec<f64>, // every rate the current resolution offers, when the OS says
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct HwCapabilities {
+    pub gpus: Vec<String>,
+    pub hwdec: Vec<HwdecSupport>,
+    pub hevc_decode: bool,
+    pub av1_decode: bool,
+    pub hdr_display: Option<bool>, // None when the OS doesn't tell
+    pub displays: Vec<DisplayCaps>,
+    pub recommended_hwdec: String,
+}
+
+static REPORT: Mutex<Option<HwCapabilities>> = Mutex::new(None);
+
+fn output(command: &mut Command) -> Option<String> {
+    let out = command.output().ok().filter(|o| o.status.success())?;
+    Some(String::from_utf8_lossy(&out.stdout).to_string())
+}
+
+/// hwaccels compiled into ffmpeg.
+fn compiled_hwaccels() -> Vec<String> {
+    output(hidden("ffmpeg").args(["-hide_banner", "-hwaccels"]))
+        .map(|text| text.lines().skip(1).map(|l| l.trim().to_string()).filter(|l| !l.is_empty()).collect())
+        .unwrap_or_default()
+}
+
+fn device_opens(hwaccel: &str) -> bool {
+    hidden("ffmpeg")
+        .args(["-hide_banner", "-v", "error", "-init_hw_device", hwaccel])
+        .args(["-f", "lavfi", "-i", "nullsrc=s=64x64:d=0.04", "-f", "null", "-"])
+        .output()
+        .is_ok_and(|o| o.status.success())
+}
+
+/// A short clip for each codec, encoded once with whatever software encoder ffmpeg has.
+fn test_clip(codec: &str) -> Option<PathBuf> {
+    let dir = std::env::temp_dir().join("framex-hwprobe");
+    let path = dir.join(format!("{}.mkv", codec));
+    if path.exists() {
+        return Some(path);
+    }
+    let _ = std::fs::create_dir_all(&dir);
+    let encoders: &[&str] = if codec == "hevc" { &["libx265", "hevc"] } else { &["libsvtav1", "libaom-av1", "librav1e"] };
+    encoders.iter().find_map(|encoder| {
+        let ok = hidden("ffmpeg")
+            .args(["-y", "-hide_banner", "-v", "error", "-f", "lavfi", "-i", "testsrc2=s=640x360:r=24:d=0.5"])
+            .args(["-c:v", encoder, "-pix_fmt", "yuv420p"])
+            .args(if *encoder == "libaom-av1" { &["-cpu-used", "8"][..] } else { &[][..] })
+            .arg(&path)
+            .output()
+            .is_ok_and(|o| o.status.success());
+        if ok {
+            Some(path.clone())
+        } else {
+            let _ = std::fs::remove_file(&path);
+            None
+        }
+    })
+}
+
+/// Decode `clip` through `hwaccel`; ffmpeg quietly falls back to software, so its log decides.
+fn hw_decodes(hwaccel: &str, clip: &Path) -> bool {
+    let Ok(out) = hidden("ffmpeg")
+        .args(["-hide_banner", "-v", "verbose", "-hwaccel", hwaccel, "-i"])
+        .arg(clip)
+        .args(["-f", "null", "-"])
+        .output()
+    else {
+        return false;
+    };
+    let log = String::from_utf8_lossy(&out.stderr);
+    out.status.success() && !FALLBACK_MARKERS.iter().any(|m| log.contains(m))
+}
+
+fn probe_hwdec() -> Vec<HwdecSupport> {
+    let compiled = compiled_hwaccels();
+    let hevc = test_clip("hevc");
+    let av1 = test_clip("av1");
+    HW_APIS
+        .iter()
+        .filter(|(hwaccel, _)| compiled.iter().any(|c| c == hwaccel))
+        .map(|(hwaccel, api)| {
+            let available = device_opens(hwaccel);
+            let decodes = |clip: &Option<PathBuf>| clip.as_ref().map(|c| available && hw_decodes(hwaccel, c));
+            HwdecSupport { api: api.to_string(), available, hevc: decodes(&hevc), av1: decodes(&av1) }
+        })
+        .collect()
+}
+
+/// The first working API, preferring one that also takes HEVC; mpv's own pick when
+/// nothing could be probed.
+fn recommend(hwdec: &[HwdecSupport]) -> String {
+    if hwdec.is_empty() {
+        return "auto-safe".to_string();
+    }
+    let mut working = hwdec.iter().filter(|h| h.available);
+    working
+        .clone()
+        .find(|h| h.hevc == Some(true))
+        .or_else(|| working.next())
+        .map(|h| h.api.clone())
+        .unwrap_or_else(|| "no".to_string())
+}
+
+/// (width, height, current rate, all rates) of a screen's current resolution.
+type ModeInfo = (u32, u32, Option<f64>, Vec<f64>);
+
+#[cfg(target_os = "windows")]
+fn probe_os() -> (Vec<String>, Vec<ModeInfo>, Option<bool>) {
+    let script = "Get-CimInstance Win32_VideoController | ForEach-Object { \"$($_.Name)|$($_.CurrentHorizontalResolution)|$($_.CurrentVerticalResolution)|$($_.CurrentRefreshRate)\" }";
+    let text = output(hidden("powershell").args(["-NoProfile", "-NonInteractive", "-Command", script])).unwrap_or_default();
+    let mut gpus = Vec::new();
+    let mut modes = Vec::new();
+    for line in text.lines() {
+        let parts: Vec<&str> = line.trim().split('|').collect();
+        if parts.len() != 4 || parts[0].is_empty() {
+            continue;
+        }
+        gpus.push(parts[0].to_string());
+        if let (Ok(w), Ok(h)) = (parts[1].parse(), parts[2].parse()) {
+            let hz = parts[3].parse::<f64>().ok().filter(|hz| *hz > 1.0);
+            modes.push((w, h, hz, hz.into_iter().collect()));
+        }
+    }
+
+    // dxdiag is slow (a few seconds) but the only stock tool that reports HDR
+    let report = std::env::temp_dir().join("framex-dxdiag.txt");
+    let hdr = hidden("dxdiag")
+        .arg("/t")
+        .arg(&report)
+        .status()
+        .ok()
+        .and_then(|_| std::fs::read_to_string(&report).ok())
+        .map(|text| {
+            text.lines()
+                .filter_map(|l| l.trim().strip_prefix("HDR Support:"))
+                .any(|v| v.trim() == "Supported")
+        });
+    let _ = std::fs::remove_file(&report);
+    (gpus, modes, hdr)
+}
+
+#[cfg(target_os = "macos")]
+fn probe_os() -> (Vec<String>, Vec<ModeInfo>, Option<bool>) {
+    let text = output(hidden("system_profiler").arg("SPDisplaysDataType")).unwrap_or_default();
+    let mut gpus = Vec::new();
+    let mut modes = Vec::new();
+    let mut hdr = None;
+    for line in text.lines().map(str::trim) {
+        if let Some(model) = line.strip_prefix("Chipset Model:") {
+            gpus.push(model.trim().to_string());
+        } else if let Some(mode) = line.strip_prefix("UI Looks like:").or_else(|| line.strip_prefix("Resolution:")) {
+            // "1512 x 982 @ 120.00Hz"
+            let numbers: Vec<f64> = mode
+                .split(|c: char| !c.is_ascii_digit() && c != '.')
+                .filter_map(|n| n.parse().ok())
+                .collect();
+            if let [w, h, hz, ..] = numbers[..] {
+                modes.push((w as u32, h as u32, Some(hz), vec![hz]));
+            }
+        } else if line.contains("XDR") || line.contains("HDR") {
+            hdr = Some(true);
+        }
+    }
+    (gpus, modes, hdr)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn probe_os() -> (Vec<String>, Vec<ModeInfo>, Option<bool>) {
+    let gpus = output(&mut hidden("lspci"))
+        .unwrap_or_default()
+        .lines()
+        .filter(|l| ["VGA compatible controller", "3D controller", "Display controller"].iter().any(|k| l.contains(k)))
+        .filter_map(|l| l.split_once(": ").map(|(_, name)| name.trim().to_string()))
+        .collect();
+
+    // Mode lines under each connected output: "   1920x1080     60.00*+  59.94    50.00"
+    let mut modes = Vec::new();
+    let xrandr = output(hidden("xrandr").arg("--query")).unwrap_or_default();
+    let mut connected = false;
+    for line in xrandr.lines() {
+        if !line.starts_with(' ') {
+            connected = line.contains(" connected");
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        let Some((w, h)) = fields.next().and_then(|res| res.split_once('x')) else { continue };
+        let rates: Vec<&str> = fields.collect();
+        let Some(current) = rates.iter().find(|r| r.contains('*')) else { continue };
+        if !connected {
+            continue;
+        }
+        let parse = |r: &str| r.trim_end_matches(['*', '+']).parse::<f64>().ok();
+        if let (Ok(w), Ok(h)) = (w.parse(), h.trim_end_matches('i').parse()) {
+            modes.push((w, h, parse(current), rates.iter().filter_map(|r| parse(r)).collect()));
+        }
+    }
+
+    // HDR static metadata in the EDID of any connected screen
+    let hdr = std::fs::read_dir("/sys/class/drm").ok().map(|entries| {
+        entries.flatten().any(|entry| {
+            let connected = std::fs::read_to_string(entry.path().join("status")).is_ok_and(|s| s.trim() == "connected");
+            connected && std::fs::read(entry.path().join("edid")).is_ok_and(|edid| edid_supports_hdr(&edid))
+        })
+    });
+    (gpus, modes, hdr)
+}
+
+/// True when a CTA-861 extension carries an HDR Static Metadata block with PQ or HLG.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn edid_supports_hdr(edid: &[u8]) -> bool {
+    edid.chunks(128).skip(1).filter(|block| block.len() == 128 && block[0] == 0x02).any(|block| {
+        let end = (block[2] as usize).clamp(4, 127);
+        let mut i = 4;
+        while i < end {
+            let (tag, len) = (block[i] >> 5, (block[i] & 0x1f) as usize);
+            // Extended tag 6: HDR static metadata; byte 2 lists EOTFs (bit 2 PQ, bit 3 HLG)
+            if tag == 7 && len >= 2 && i + 2 < block.len() && block[i + 1] == 0x06 && block[i + 2] & 0b1100 != 0 {
+                return true;
+            }
+            i += len + 1;
+        }
+        false
+    })
+}
+
+fn probe(app: &AppHandle) -> HwCapabilities {
+    let (gpus, mut modes, hdr_display) = probe_os();
+    let hwdec = probe_hwdec();
+
+    let monitors = app.available_monitors().unwrap_or_default();
+    let displays = monitors
+        .iter()
+        .enumerate()
+        .map(|(i, monitor)| {
+            let size = monitor.size();
+            // Pair each screen with the OS mode of the same resolution, else the next one left
+            // (macOS reports scaled sizes)
+            let mode = match modes.iter().position(|m| m.0 == size.width && m.1 == size.height) {
+                Some(at) => Some(modes.remove(at)),
+                None if !modes.is_empty() => Some(modes.remove(0)),
+                None => None,
+            };
+            let (refresh_hz, refresh_rates) = mode.map(|m| (m.2, m.3)).unwrap_or_default();
+            DisplayCaps {
+                name: monitor.name().cloned().unwrap_or_else(|| format!("Display {}", i + 1)),
+                width: size.width,
+                height: size.height,
+                scale_factor: monitor.scale_factor(),
+                refresh_hz,
+                refresh_rates,
+            }
+        })
+        .collect();
+
+    HwCapabilities {
+        gpus,
+        hevc_decode: hwdec.iter().any(|h| h.hevc == Some(true)),
+        av1_decode: hwdec.iter().any(|h| h.av1 == Some(true)),
+        recommended_hwdec: recommend(&hwdec),
+        hwdec,
+        hdr_display,
+        displays,
+    }
+}
+
+/// The last report, or a fresh one (blocking, several seconds) when there is none or `refresh`.
+pub(crate) fn capabilities(app: &AppHandle, refresh: bool) -> HwCapabilities {
+    if !refresh {
+        if let Some(report) = REPORT.lock().unwrap().clone() {
+            return report;
+        }
+    }
+    let report = probe(app);
+    tracing::info!(
+        "🖥️ Hardware: {} GPU(s), hwdec {:?}, recommending {}",
+        report.gpus.len(),
+        report.hwdec.iter().filter(|h| h.available).map(|h| h.api.as_str()).collect::<Vec<_>>(),
+        report.recommended_hwdec
+    );
+    *REPORT.lock().unwrap() = Some(report.clone());
+    report
+}
+
+/// GPUs, working hwdec APIs with their HEVC/AV1 support, HDR and display refresh rates,
+/// plus the `hwdec` value to recommend. Probing runs ffmpeg a dozen times, so the report
+/// is kept until `refresh` is set.
+#[command]
+pub async fn get_hw_capabilities(app: AppHandle, refresh: Option<bool>) -> Result<HwCapabilities, String> {
+    tauri::async_runtime::spawn_blocking(move || capabilities(&app, refresh.unwrap_or(false)))
+        .await
+        .map_err(|e| e.to_string())
+}
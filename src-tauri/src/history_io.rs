@@ -0,0 +1,281 @@
+use regex::Regex;
+use serde::Serialize;
+use std::fs;
+use tauri::{command, AppHandle};
+
+use crate::watch_history::{all_records, merge_records, parse_datetime, HistoryRecord};
+use crate::xml::xml_tag;
+
+// Jellyfin and .NET count time in 100ns ticks
+const TICKS_PER_SECOND: f64 = 10_000_000.0;
+
+#[derive(Serialize, Debug, Default)]
+pub struct HistoryImportSummary {
+    pub imported: usize,
+    pub watched: usize,
+    pub resume_points: usize,
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn to_csv(records: &[HistoryRecord]) -> String {
+    let mut out = String::from("path,show,position,duration,last_watched,resumable,play_count,times_completed,last_completed\n");
+    for r in records {
+        out.push_str(&format!(
+            "{},{},{:.1},{:.1},{},{},{},{},{}\n",
+            csv_field(&r.path),
+            csv_field(r.show.as_deref().unwrap_or_default()),
+            r.position,
+            r.duration,
+            r.last_watched,
+            r.resumable,
+            r.play_count,
+            r.times_completed,
+            r.last_completed.map(|t| t.to_string()).unwrap_or_default(),
+        ));
+    }
+    out
+}
+
+/// `file:///C:/a%20b.mkv` -> `C:/a b.mkv`, `file:///home/x.mkv` -> `/home/x.mkv`. Other URLs are kept.
+pub fn uri_to_path(uri: &str) -> String {
+    let Some(rest) = uri.strip_prefix("file://") else {
+        return uri.to_string();
+    };
+    let decoded = urlencoding::decode(rest).map(|d| d.into_owned()).unwrap_or_else(|_| rest.to_string());
+    // Drop the slash in front of a Windows drive letter
+    let bytes = decoded.as_bytes();
+    if bytes.len() > 2 && bytes[0] == b'/' && bytes[1].is_ascii_alphabetic() && bytes[2] == b':' {
+        return decoded[1..].to_string();
+    }
+    decoded
+}
+
+fn watched_record(path: String, play_count: u32, watched: bool, position: f64, duration: f64, last_watched: u64) -> HistoryRecord {
+    let play_count = if watched { play_count.max(1) } else { play_count };
+    HistoryRecord {
+        resumable: !watched && position >= 5.0,
+        times_completed: if watched { play_count } else { 0 },
+        last_completed: watched.then_some(last_watched),
+        path,
+        position,
+        duration,
+        last_watched,
+        play_count,
+        show: None,
+        modified: 0,
+    }
+}
+
+/// VLC: `vlc-qt-interface.ini` ([RecentsMRL] list/times, in ms) or the `ml.xml`/`ml.xspf` media library.
+fn parse_vlc(content: &str) -> Vec<HistoryRecord> {
+    if content.contains("[RecentsMRL]") {
+        let value = |key: &str| {
+            content.lines()
+                .map(|l| l.trim())
+                .find_map(|l| l.strip_prefix(key).and_then(|r| r.trim_start().strip_prefix('=')))
+                .map(|v| v.trim().trim_matches('"').to_string())
+                .unwrap_or_default()
+        };
+        let list = value("list");
+        let times = value("times");
+        let times: Vec<f64> = times.split(',').map(|t| t.trim().parse::<f64>().unwrap_or(0.0) / 1000.0).collect();
+        return list.split(',')
+            .map(|uri| uri.trim())
+            .filter(|uri| !uri.is_empty())
+            .enumerate()
+            .map(|(i, uri)| {
+                let position = times.get(i).copied().unwrap_or(0.0);
+                watched_record(uri_to_path(uri), 1, false, position, 0.0, 0)
+            })
+            .collect();
+    }
+
+    let track = Regex::new(r"(?s)<track>(.*?)</track>").unwrap();
+    track.captures_iter(content)
+        .filter_map(|c| {
+            let block = &c[1];
+            let location = xml_tag(block, "location")?;
+            let duration = xml_tag(block, "duration").and_then(|d| d.parse::<f64>().ok()).unwrap_or(0.0) / 1000.0;
+            Some(watched_record(uri_to_path(&location), 1, false, 0.0, duration, 0))
+        })
+        .collect()
+}
+
+/// Kodi: the single-file `videodb.xml` written by "Export video library".
+fn parse_kodi(content: &str) -> Vec<HistoryRecord> {
+    let item = Regex::new(r"(?s)<(movie|episodedetails|musicvideo)>(.*?)</(?:movie|episodedetails|musicvideo)>").unwrap();
+    item.captures_iter(content)
+        .filter_map(|c| {
+            let block = &c[2];
+            let path = xml_tag(block, "filenameandpath")?;
+            let play_count = xml_tag(block, "playcount").and_then(|p| p.parse().ok()).unwrap_or(0);
+            let last_watched = xml_tag(block, "lastplayed").and_then(|d| parse_datetime(&d)).unwrap_or(0);
+            let resume = xml_tag(block, "resume").unwrap_or_default();
+            let position = xml_tag(&resume, "position").and_then(|p| p.parse().ok()).unwrap_or(0.0);
+            let duration = xml_tag(&resume, "total").and_then(|p| p.parse().ok()).unwrap_or(0.0);
+            if play_count == 0 && position <= 0.0 {
+                return None;
+            }
+            Some(watched_record(path, play_count, play_count > 0, position, duration, last_watched))
+        })
+        .collect()
+}
+
+/// Jellyfin: an `/Users/{id}/Items?Recursive=true&Fields=Path` response (or its `Items` array).
+fn parse_jellyfin(content: &str) -> Result<Vec<HistoryRecord>, String> {
+    let value: serde_json::Value = serde_json::from_str(content).map_err(|e| format!("Invalid Jellyfin export: {}", e))?;
+    let items = value.get("Items").unwrap_or(&value).as_array().ok_or("Jellyfin export has no Items array")?;
+    Ok(items
+        .iter()
+        .filter_map(|item| {
+            let path = item["Path"].as_str()?.to_string();
+            let data = &item["UserData"];
+            let play_count = data["PlayCount"].as_u64().unwrap_or(0) as u32;
+            let played = data["Played"].as_bool().unwrap_or(false);
+            let position = data["PlaybackPositionTicks"].as_f64().unwrap_or(0.0) / TICKS_PER_SECOND;
+            if !played && position <= 0.0 {
+                return None;
+            }
+            let duration = item["RunTimeTicks"].as_f64().unwrap_or(0.0) / TICKS_PER_SECOND;
+            let last_watched = data["LastPlayedDate"].as_str().and_then(parse_datetime).unwrap_or(0);
+            Some(watched_record(path, play_count, played, position, duration, last_watched))
+        })
+        .collect())
+}
+
+/// Write the full watch history to `path` as "json" (default) or "csv".
+#[command]
+pub fn export_watch_history(app: AppHandle, path: String, format: Option<String>) -> Result<usize, String> {
+    crate::parental::check_area(&app, "history")?;
+    let records = all_records(&app)?;
+    let content = match format.as_deref().unwrap_or("json") {
+        "json" => serde_json::to_string_pretty(&records).map_err(|e| e.to_string())?,
+        "csv" => to_csv(&records),
+        other => return Err(format!("Unknown export format '{}'", other)),
+    };
+    fs::write(&path, content).map_err(|e| e.to_string())?;
+    tracing::info!("📤 Exported {} watch history entries to {}", records.len(), path);
+    Ok(records.len())
+}
+
+/// Merge history from a FrameX JSON export or another player: "framex", "vlc", "kodi" or "jellyfin".
+#[command]
+pub fn import_watch_history(app: AppHandle, path: String, source: String) -> Result<HistoryImportSummary, String> {
+    crate::parental::check_area(&app, "history")?;
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let records = match source.as_str() {
+        "framex" => serde_json::from_str::<Vec<HistoryRecord>>(&content).map_err(|e| format!("Invalid FrameX export: {}", e))?,
+        "vlc" => parse_vlc(&content),
+        "kodi" => parse_kodi(&content),
+        "jellyfin" => parse_jellyfin(&content)?,
+        other => return Err(format!("Unknown history source '{}'", other)),
+    };
+    let records: Vec<HistoryRecord> = records.into_iter().filter(|r| !r.path.is_empty()).collect();
+
+    let summary = HistoryImportSummary {
+        imported: merge_records(&app, &records)?,
+        watched: records.iter().filter(|r| r.times_completed > 0).count(),
+        resume_points: records.iter().filter(|r| r.resumable).count(),
+    };
+    tracing::info!("📥 Imported {} entries from {} ({} watched, {} resume points)", summary.imported, source, summary.watched, summary.resume_points);
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-01-02 03:04:05 UTC
+    const LAST_PLAYED: u64 = 1_704_164_645;
+
+    #[test]
+    fn vlc_recents() {
+        let ini = "[General]\nfoo=1\n\n[RecentsMRL]\nlist=file:///C:/Videos/a%20b.mkv, file:///home/me/show.mkv, https://example.com/live\ntimes=125000, 0\n";
+        let records = parse_vlc(ini);
+        let paths: Vec<&str> = records.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, ["C:/Videos/a b.mkv", "/home/me/show.mkv", "https://example.com/live"]);
+        assert_eq!(records[0].position, 125.0);
+        assert!(records[0].resumable);
+        assert!(!records[1].resumable);
+        // Fewer times than entries
+        assert_eq!(records[2].position, 0.0);
+        assert_eq!(records[0].times_completed, 0);
+    }
+
+    #[test]
+    fn vlc_media_library() {
+        let xspf = r#"<?xml version="1.0" encoding="UTF-8"?>
+<playlist><trackList>
+  <track><location>file:///home/me/Tom%20&amp;%20Jerry.mkv</location><duration>5400000</duration></track>
+  <track><location>file:///home/me/&lt;untitled&gt;.mkv</location></track>
+  <track><title>No location</title></track>
+</trackList></playlist>"#;
+        let records = parse_vlc(xspf);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].path, "/home/me/Tom & Jerry.mkv");
+        assert_eq!(records[0].duration, 5400.0);
+        assert_eq!(records[1].path, "/home/me/<untitled>.mkv");
+        assert_eq!(records[1].duration, 0.0);
+    }
+
+    #[test]
+    fn kodi_videodb() {
+        let xml = r#"<videodb>
+<movie><title>A</title><filenameandpath>/movies/Fast &amp; Furious.mkv</filenameandpath><playcount>2</playcount>
+  <lastplayed>2024-01-02 03:04:05</lastplayed><resume><position>0.000000</position><total>0.000000</total></resume></movie>
+<episodedetails><filenameandpath>/tv/Show/S01E02.mkv</filenameandpath><playcount>0</playcount>
+  <resume><position>600.5</position><total>1800</total></resume></episodedetails>
+<movie><filenameandpath>/movies/unwatched.mkv</filenameandpath></movie>
+<movie><title>No path</title><playcount>1</playcount></movie>
+</videodb>"#;
+        let records = parse_kodi(xml);
+        assert_eq!(records.len(), 2);
+
+        let watched = &records[0];
+        assert_eq!(watched.path, "/movies/Fast & Furious.mkv");
+        assert_eq!((watched.play_count, watched.times_completed), (2, 2));
+        assert_eq!(watched.last_watched, LAST_PLAYED);
+        assert_eq!(watched.last_completed, Some(LAST_PLAYED));
+        assert!(!watched.resumable);
+
+        let resume = &records[1];
+        assert_eq!(resume.path, "/tv/Show/S01E02.mkv");
+        assert_eq!((resume.position, resume.duration), (600.5, 1800.0));
+        assert!(resume.resumable);
+        assert_eq!(resume.last_watched, 0);
+        assert_eq!(resume.last_completed, None);
+    }
+
+    #[test]
+    fn jellyfin_items() {
+        let json = r#"{"Items": [
+            {"Path": "/media/a.mkv", "RunTimeTicks": 72000000000,
+             "UserData": {"PlayCount": 3, "Played": true, "LastPlayedDate": "2024-01-02T03:04:05.0000000Z"}},
+            {"Path": "/media/b.mkv", "UserData": {"PlaybackPositionTicks": 6000000000, "Played": false}},
+            {"Path": "/media/c.mkv", "UserData": {"Played": false}},
+            {"Name": "No path", "UserData": {"Played": true}},
+            {"Path": "/media/d.mkv"}
+        ]}"#;
+        let records = parse_jellyfin(json).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].path, "/media/a.mkv");
+        assert_eq!((records[0].play_count, records[0].duration), (3, 7200.0));
+        assert_eq!(records[0].last_completed, Some(LAST_PLAYED));
+        assert_eq!(records[1].path, "/media/b.mkv");
+        assert_eq!(records[1].position, 600.0);
+        assert!(records[1].resumable);
+
+        // A bare Items array works too
+        let bare = r#"[{"Path": "/media/a.mkv", "UserData": {"Played": true}}]"#;
+        assert_eq!(parse_jellyfin(bare).unwrap()[0].play_count, 1);
+        assert!(parse_jellyfin("{\"Total\": 0}").is_err());
+        assert!(parse_jellyfin("not json").is_err());
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+use crate::config::load_config;
+use crate::mpv_handler::{MpvCommand, MpvState};
+
+// System-wide hotkeys that work while FrameX is unfocused or minimized. Bindings live in
+// `GlobalHotkeySettings` as action -> accelerator ("Alt+Shift+Right", "MediaPlayPause").
+
+pub const ACTIONS: &[&str] = &[
+    "play_pause", "stop", "next", "previous", "seek_forward", "seek_backward",
+    "volume_up", "volume_down", "mute", "boss_key", "always_on_top", "mini_player",
+];
+
+const SEEK_STEP_SECS: f64 = 10.0;
+const VOLUME_STEP: f64 = 5.0;
+
+// Registered shortcut id -> action
+fn bound() -> &'static Mutex<HashMap<u32, String>> {
+    static BOUND: OnceLock<Mutex<HashMap<u32, String>>> = OnceLock::new();
+    BOUND.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+// Set while the boss key has the window hidden, so the next press brings it back
+static HIDDEN: AtomicBool = AtomicBool::new(false);
+
+fn send(app: &AppHandle, command: MpvCommand) {
+    if let Some(tx) = app.state::<MpvState>().tx.lock().unwrap().as_ref() {
+        let _ = tx.send(command);
+    }
+}
+
+/// The global shortcut plugin's handler.
+pub fn handle(app: &AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state != ShortcutState::Pressed {
+        return;
+    }
+    let Some(action) = bound().lock().ok().and_then(|b| b.get(&shortcut.id()).cloned()) else {
+        return;
+    };
+    match action.as_str() {
+        "play_pause" => send(app, MpvCommand::TogglePause),
+        "stop" => send(app, MpvCommand::Stop),
+        "next" => send(app, MpvCommand::PlaylistNext),
+        "previous" => send(app, MpvCommand::PlaylistPrev),
+        "seek_forward" => send(app, MpvCommand::SeekRelative(SEEK_STEP_SECS)),
+        "seek_backward" => send(app, MpvCommand::SeekRelative(-SEEK_STEP_SECS)),
+        "volume_up" => send(app, MpvCommand::AddVolume(VOLUME_STEP)),
+        "volume_down" => send(app, MpvCommand::AddVolume(-VOLUME_STEP)),
+        "mute" => send(app, MpvCommand::ToggleMute),
+        "boss_key" => {
+            let Some(window) = app.get_webview_window("main") else {
+                return;
+            };
+            if HIDDEN.swap(false, Ordering::SeqCst) {
+                crate::tray::show_main_window(app);
+            } else {
+                send(app, MpvCommand::Pause);
+                let _ = window.hide();
+                HIDDEN.store(true, Ordering::SeqCst);
+            }
+        }
+        "always_on_top" => {
+            let _ = crate::window_mode::toggle_always_on_top(app.clone());
+        }
+        "mini_player" => {
+            let _ = crate::window_mode::toggle_mini_player(app.clone());
+        }
+        _ => {}
+    }
+}
+
+/// Check an accelerator parses; the error names the problem for the settings UI.
+pub fn validate(accelerator: &str) -> Result<(), String> {
+    accelerator.parse::<Shortcut>().map(|_| ()).map_err(|e| format!("'{}': {}", accelerator, e))
+}
+
+/// (Re-)register the configured hotkeys. Returns the ones that couldn't be registered,
+/// usually because another application already owns the combination.
+pub fn apply(app: &AppHandle) -> Vec<String> {
+    let settings = load_config(app).global_hotkeys;
+    let shortcuts = app.global_shortcut();
+    let _ = shortcuts.unregister_all();
+    bound().lock().unwrap().clear();
+    if !settings.enabled {
+        return Vec::new();
+    }
+
+    // Built up front: the handler reads the map on the main thread, where registering runs
+    let mut registered_actions = HashMap::new();
+    let mut failed = Vec::new();
+    for (action, accelerator) in &settings.bindings {
+        if accelerator.trim().is_empty() || !ACTIONS.contains(&action.as_str()) {
+            continue;
+        }
+        let registered = accelerator.parse::<Shortcut>().map_err(|e| e.to_string()).and_then(|shortcut| {
+            shortcuts.register(shortcut).map_err(|e| e.to_string())?;
+            Ok(shortcut)
+        });
+        match registered {
+            Ok(shortcut) => {
+                registered_actions.insert(shortcut.id(), action.clone());
+            }
+            Err(e) => {
+                tracing::warn!("⌨️ Global hotkey {} for {} not registered: {}", accelerator, action, e);
+                failed.push(format!("{} ({})", accelerator, action));
+            }
+        }
+    }
+    tracing::info!("⌨️ Registered {} global hotkey(s)", registered_actions.len());
+    *bound().lock().unwrap() = registered_actions;
+    failed
+}
@@ -0,0 +1,291 @@
+use serde::Serialize;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{command, AppHandle, Emitter, State};
+
+// Finds the intro and credits a season's episodes share by fingerprinting the audio at the
+// start and end of each one and looking for the longest stretch that matches a neighbouring
+// episode. The ranges found are stored as "detected" skip segments.
+
+const SAMPLE_RATE: usize = 8000;
+const FRAME_LEN: usize = 2048;
+const HOP_LEN: usize = 1024; // 0.128s per fingerprint frame
+// 33 log-spaced bands in this range give one 32-bit hash per frame
+const BAND_LOW_HZ: f32 = 300.0;
+const BAND_HIGH_HZ: f32 = 2000.0;
+const BANDS: usize = 33;
+const INTRO_WINDOW_SECS: f64 = 10.0 * 60.0;
+const CREDITS_WINDOW_SECS: f64 = 6.0 * 60.0;
+// Frames quieter than this carry no usable hash (silence matches anything)
+const MIN_FRAME_RMS: f32 = 100.0;
+const MAX_BIT_ERRORS: u32 = 8;
+// Unmatched frames tolerated inside a run, about a second
+const MAX_GAP_FRAMES: usize = 8;
+const MIN_MATCH_SECS: f64 = 15.0;
+// Longer matches are more likely the same file twice than an intro
+const MAX_MATCH_SECS: f64 = 4.0 * 60.0;
+
+pub struct SegmentDetection {
+    cancel: Mutex<Option<Arc<AtomicBool>>>,
+}
+
+impl SegmentDetection {
+    pub fn new() -> Self {
+        Self { cancel: Mutex::new(None) }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DetectedEpisode {
+    pub path: String,
+    pub intro: Option<(f64, f64)>,
+    pub credits: Option<(f64, f64)>,
+}
+
+#[derive(Serialize, Clone)]
+struct DetectionProgress {
+    done: usize,
+    total: usize,
+}
+
+/// Audio fingerprint of one window of an episode.
+struct Fingerprint {
+    offset: f64, // where the window starts in the episode
+    hashes: Vec<Option<u32>>,
+}
+
+/// Mono 8kHz samples of `length` seconds from `start`.
+fn decode_audio(path: &Path, start: f64, length: f64) -> Result<Vec<i16>, String> {
+    let output = crate::ffmpeg::hidden("ffmpeg")
+        .arg("-hide_banner")
+        .arg("-loglevel").arg("error")
+        .arg("-nostdin")
+        .arg("-ss").arg(format!("{:.3}", start))
+        .arg("-t").arg(format!("{:.3}", length))
+        .arg("-i").arg(path)
+        .arg("-vn").arg("-sn")
+        .arg("-ac").arg("1")
+        .arg("-ar").arg(SAMPLE_RATE.to_string())
+        .arg("-f").arg("s16le")
+        .arg("-")
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("FFmpeg error: {}. Make sure ffmpeg is installed and in PATH.", e))?;
+    if !output.status.success() {
+        return Err(format!("Audio decoding failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(output.stdout.chunks_exact(2).map(|b| i16::from_le_bytes([b[0], b[1]])).collect())
+}
+
+/// In-place radix-2 FFT; `re.len()` must be a power of two.
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * std::f32::consts::PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let t_re = re[b] * cos - im[b] * sin;
+                let t_im = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// One hash per frame: bit m says whether the energy difference between bands m and m+1
+/// grew since the previous frame (Haitsma & Kalker), which survives re-encoding and volume changes.
+fn fingerprint(samples: &[i16], offset: f64) -> Fingerprint {
+    let bin_hz = SAMPLE_RATE as f32 / FRAME_LEN as f32;
+    let edges: Vec<usize> = (0..=BANDS)
+        .map(|i| {
+            let hz = BAND_LOW_HZ * (BAND_HIGH_HZ / BAND_LOW_HZ).powf(i as f32 / BANDS as f32);
+            (hz / bin_hz).round() as usize
+        })
+        .collect();
+    let window: Vec<f32> = (0..FRAME_LEN)
+        .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / FRAME_LEN as f32).cos())
+        .collect();
+
+    let mut hashes = Vec::new();
+    let mut previous: Option<Vec<f32>> = None;
+    let (mut re, mut im) = (vec![0.0; FRAME_LEN], vec![0.0; FRAME_LEN]);
+    for frame in samples.windows(FRAME_LEN).step_by(HOP_LEN) {
+        let rms = (frame.iter().map(|&s| (s as f32).powi(2)).sum::<f32>() / FRAME_LEN as f32).sqrt();
+        for i in 0..FRAME_LEN {
+            re[i] = frame[i] as f32 * window[i];
+            im[i] = 0.0;
+        }
+        fft(&mut re, &mut im);
+        let energies: Vec<f32> = edges
+            .windows(2)
+            .map(|e| (e[0]..e[1].max(e[0] + 1)).map(|k| re[k] * re[k] + im[k] * im[k]).sum())
+            .collect();
+        let hash = previous.as_ref().filter(|_| rms >= MIN_FRAME_RMS).map(|prev| {
+            (0..BANDS - 1).fold(0u32, |hash, m| {
+                let delta = (energies[m] - energies[m + 1]) - (prev[m] - prev[m + 1]);
+                hash | (((delta > 0.0) as u32) << m)
+            })
+        });
+        hashes.push(hash);
+        previous = Some(energies);
+    }
+    Fingerprint { offset, hashes }
+}
+
+/// The longest aligned stretch where two fingerprints agree: (start in a, start in b, frames).
+fn longest_match(a: &[Option<u32>], b: &[Option<u32>]) -> Option<(usize, usize, usize)> {
+    let mut best: Option<(usize, usize, usize)> = None;
+    for shift in -(b.len() as isize - 1)..a.len() as isize {
+        let (mut i, mut j) = if shift >= 0 { (shift as usize, 0) } else { (0, (-shift) as usize) };
+        let mut run: Option<(usize, usize)> = None;
+        let mut last_match = 0;
+        while i < a.len() && j < b.len() {
+            if let (Some(x), Some(y)) = (a[i], b[j]) {
+                if (x ^ y).count_ones() <= MAX_BIT_ERRORS {
+                    if run.is_none() || i - last_match > MAX_GAP_FRAMES {
+                        run = Some((i, j));
+                    }
+                    last_match = i;
+                    let (si, sj) = run.expect("run started above");
+                    let len = i - si + 1;
+                    if best.is_none_or(|(_, _, l)| len > l) {
+                        best = Some((si, sj, len));
+                    }
+                }
+            }
+            i += 1;
+            j += 1;
+        }
+    }
+    best
+}
+
+/// For each episode, the longest range it shares with the episode before or after it.
+fn shared_ranges(prints: &[Fingerprint]) -> Vec<Option<(f64, f64)>> {
+    let frame_secs = HOP_LEN as f64 / SAMPLE_RATE as f64;
+    let mut ranges: Vec<Option<(f64, f64)>> = vec![None; prints.len()];
+    let mut keep = |index: usize, range: (f64, f64)| {
+        if ranges[index].is_none_or(|(s, e)| range.1 - range.0 > e - s) {
+            ranges[index] = Some(range);
+        }
+    };
+    for i in 1..prints.len() {
+        let (a, b) = (&prints[i - 1], &prints[i]);
+        let Some((sa, sb, len)) = longest_match(&a.hashes, &b.hashes) else {
+            continue;
+        };
+        let secs = len as f64 * frame_secs;
+        if !(MIN_MATCH_SECS..=MAX_MATCH_SECS).contains(&secs) {
+            continue;
+        }
+        keep(i - 1, (a.offset + sa as f64 * frame_secs, a.offset + sa as f64 * frame_secs + secs));
+        keep(i, (b.offset + sb as f64 * frame_secs, b.offset + sb as f64 * frame_secs + secs));
+    }
+    ranges
+}
+
+/// Find intros and credits across `episodes` (in episode order) and store them as segments.
+fn detect(app: &AppHandle, episodes: &[String], cancel: &AtomicBool) -> Result<Vec<DetectedEpisode>, String> {
+    use rayon::prelude::*;
+
+    let total = episodes.len();
+    let done = AtomicUsize::new(0);
+    let _ = app.emit("segment-detection", DetectionProgress { done: 0, total });
+    let prints: Vec<(Fingerprint, Fingerprint)> = episodes
+        .par_iter()
+        .map(|path| -> Result<(Fingerprint, Fingerprint), String> {
+            if cancel.load(Ordering::SeqCst) {
+                return Err("Detection cancelled".to_string());
+            }
+            let file = Path::new(path);
+            let duration = crate::file_scanner::get_video_duration(path.clone()).unwrap_or(0.0);
+            let intro_len = INTRO_WINDOW_SECS.min(duration / 2.0).max(0.0);
+            let credits_start = (duration - CREDITS_WINDOW_SECS).max(duration / 2.0);
+            let intro = fingerprint(&decode_audio(file, 0.0, intro_len)?, 0.0);
+            let credits = fingerprint(&decode_audio(file, credits_start, duration - credits_start)?, credits_start);
+            let finished = done.fetch_add(1, Ordering::SeqCst) + 1;
+            let _ = app.emit("segment-detection", DetectionProgress { done: finished, total });
+            Ok((intro, credits))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let (intros, credits): (Vec<Fingerprint>, Vec<Fingerprint>) = prints.into_iter().unzip();
+    let intros = shared_ranges(&intros);
+    let credits = shared_ranges(&credits);
+
+    let mut detected = Vec::new();
+    for ((path, intro), credits) in episodes.iter().zip(intros).zip(credits) {
+        let segments: Vec<(f64, f64, String)> = intro
+            .map(|(s, e)| (s, e, "intro".to_string()))
+            .into_iter()
+            .chain(credits.map(|(s, e)| (s, e, "credits".to_string())))
+            .collect();
+        crate::segments::replace_segments(app, path, "detected", &segments)?;
+        detected.push(DetectedEpisode { path: path.clone(), intro, credits });
+    }
+    Ok(detected)
+}
+
+/// Detect the intro and credits of every local episode in the same season as `path`.
+#[command]
+pub async fn detect_season_segments(app: AppHandle, state: State<'_, SegmentDetection>, path: String) -> Result<Vec<DetectedEpisode>, String> {
+    let file = Path::new(&path);
+    let name = file.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    let season = crate::metadata::parse_episode(name).map(|(s, _)| s).unwrap_or(1);
+    let folder = file.parent().ok_or("The video has no folder")?;
+    let episodes: Vec<String> = crate::metadata::local_episodes(folder, season).into_iter().map(|(_, p)| p).collect();
+    if episodes.len() < 2 {
+        return Err("Detection needs at least two episodes of the season in the same folder".to_string());
+    }
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    {
+        let mut running = state.cancel.lock().unwrap();
+        if running.is_some() {
+            return Err("Detection is already running".to_string());
+        }
+        *running = Some(cancel.clone());
+    }
+    tracing::info!("🔎 Detecting intros and credits in {} episodes of season {}", episodes.len(), season);
+    let worker_app = app.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || detect(&worker_app, &episodes, &cancel))
+        .await
+        .map_err(|e| format!("Detection failed: {}", e))
+        .and_then(|r| r);
+    *state.cancel.lock().unwrap() = None;
+    if let Ok(episodes) = &result {
+        let found = episodes.iter().filter(|e| e.intro.is_some()).count();
+        tracing::info!("🔎 Found intros in {} of {} episodes", found, episodes.len());
+    }
+    result
+}
+
+/// Stop a running detection; episodes already being decoded finish first.
+#[command]
+pub fn cancel_segment_detection(state: State<'_, SegmentDetection>) {
+    if let Some(cancel) = state.cancel.lock().unwrap().as_ref() {
+        cancel.store(true, Ordering::SeqCst);
+    }
+}
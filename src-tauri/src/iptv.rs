@@ -0,0 +1,441 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{command, AppHandle, Manager, State, Window};
+
+use crate::config::load_config;
+use crate::xml::{attribute, unescape_xml, xml_tag};
+use crate::mpv_handler::{MpvCommand, MpvState};
+use crate::util::now_secs;
+
+// IPTV: M3U channel lists (`#EXTINF:-1 tvg-id=".." group-title="..",Name` + URL) kept in
+// app data, and XMLTV guides for now/next. Guides can be huge, so only a window of
+// programmes around now is kept in memory, and the raw file is cached on disk.
+
+const GUIDE_PAST_SECS: i64 = 6 * 3600;
+const GUIDE_AHEAD_SECS: i64 = 36 * 3600;
+// Re-read the window from the cached file this often, so a long session never runs past its end
+const GUIDE_REWINDOW_SECS: i64 = 12 * 3600;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Channel {
+    pub id: String, // position in the list; several channels may share a tvg-id
+    pub name: String,
+    pub tvg_id: Option<String>, // links the channel to the guide
+    pub group: Option<String>,
+    pub logo: Option<String>,
+    pub url: String,
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    #[serde(default)]
+    pub referrer: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct IptvPlaylist {
+    pub id: String,
+    pub name: String,
+    pub source: String, // file path or URL, re-read on refresh
+    pub epg_url: Option<String>,
+    pub channels: Vec<Channel>,
+    pub updated: u64,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct IptvStore {
+    playlists: Vec<IptvPlaylist>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Programme {
+    pub title: String,
+    pub description: Option<String>,
+    pub start: i64, // unix seconds
+    pub stop: i64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ChannelGuide {
+    pub channel_id: String,
+    pub now: Option<Programme>,
+    pub next: Option<Programme>,
+}
+
+/// A parsed guide window: channel id -> programmes by start time, around `windowed_at`.
+struct Guide {
+    windowed_at: i64,
+    channels: HashMap<String, Vec<Programme>>,
+}
+
+/// Parsed guides by playlist id.
+pub struct IptvState {
+    guides: Mutex<HashMap<String, Guide>>,
+}
+
+impl IptvState {
+    pub fn new() -> Self {
+        Self { guides: Mutex::new(HashMap::new()) }
+    }
+}
+
+fn store_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app.path().app_data_dir().map_err(|e| e.to_string())?.join("iptv.json"))
+}
+
+fn load_store(app: &AppHandle) -> IptvStore {
+    store_path(app)
+        .ok()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+fn save_store(app: &AppHandle, store: &IptvStore) -> Result<(), String> {
+    let path = store_path(app)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    // Temp file + rename so a crash mid-write can't leave a truncated store
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string(store).map_err(|e| e.to_string())?).map_err(|e| e.to_string())?;
+    fs::rename(&tmp, &path).map_err(|e| e.to_string())
+}
+
+/// A local file or an http(s) URL, gunzipped when needed (many EPGs are `.xml.gz`).
+async fn read_source(source: &str) -> Result<String, String> {
+    let bytes = if source.starts_with("http://") || source.starts_with("https://") {
+        crate::metadata::http_client()
+            .get(source)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("Failed to fetch {}: {}", source, e))?
+            .bytes()
+            .await
+            .map_err(|e| e.to_string())?
+            .to_vec()
+    } else {
+        fs::read(source).map_err(|e| format!("Failed to read {}: {}", source, e))?
+    };
+    if bytes.starts_with(&[0x1f, 0x8b]) {
+        let mut text = String::new();
+        flate2::read::GzDecoder::new(bytes.as_slice())
+            .read_to_string(&mut text)
+            .map_err(|e| format!("Failed to decompress {}: {}", source, e))?;
+        return Ok(text);
+    }
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Channels from an extended M3U, plus the guide URL from the `#EXTM3U` header if any.
+fn parse_m3u(content: &str) -> (Vec<Channel>, Option<String>) {
+    let mut channels = Vec::new();
+    let mut epg_url = None;
+    let (mut info, mut group, mut user_agent, mut referrer): (Option<String>, Option<String>, Option<String>, Option<String>) = (None, None, None, None);
+    for line in content.trim_start_matches('\u{feff}').lines().map(str::trim) {
+        if let Some(header) = line.strip_prefix("#EXTM3U") {
+            epg_url = attribute(header, "url-tvg").or_else(|| attribute(header, "x-tvg-url")).map(|u| u.split(',').next().unwrap_or_default().to_string());
+        } else if line.starts_with("#EXTINF") {
+            info = Some(line.to_string());
+        } else if let Some(g) = line.strip_prefix("#EXTGRP:") {
+            group = Some(g.trim().to_string());
+        } else if let Some(option) = line.strip_prefix("#EXTVLCOPT:") {
+            if let Some(ua) = option.strip_prefix("http-user-agent=") {
+                user_agent = Some(ua.to_string());
+            } else if let Some(r) = option.strip_prefix("http-referrer=") {
+                referrer = Some(r.to_string());
+            }
+        } else if !line.is_empty() && !line.starts_with('#') {
+            let extinf = info.take().unwrap_or_default();
+            // The display name follows the last comma outside the attribute quotes
+            let mut in_quotes = false;
+            let comma = extinf.char_indices().filter(|(_, c)| {
+                if *c == '"' {
+                    in_quotes = !in_quotes;
+                }
+                *c == ',' && !in_quotes
+            }).last().map(|(i, _)| i);
+            let attrs = comma.map_or(extinf.as_str(), |i| &extinf[..i]);
+            let name = comma
+                .map(|i| extinf[i + 1..].trim().to_string())
+                .filter(|n| !n.is_empty())
+                .or_else(|| attribute(attrs, "tvg-name"))
+                .unwrap_or_else(|| line.to_string());
+            channels.push(Channel {
+                id: channels.len().to_string(),
+                name,
+                tvg_id: attribute(attrs, "tvg-id"),
+                group: attribute(attrs, "group-title").or(group.take()),
+                logo: attribute(attrs, "tvg-logo"),
+                url: line.to_string(),
+                user_agent: attribute(attrs, "http-user-agent").or(user_agent.take()),
+                referrer: attribute(attrs, "http-referrer").or(referrer.take()),
+            });
+        }
+    }
+    (channels, epg_url)
+}
+
+/// XMLTV time: `20240101123000 +0100` (the offset is optional) -> unix seconds.
+fn parse_xmltv_time(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let digits = value.get(..14)?;
+    let n = |range: std::ops::Range<usize>| digits.get(range)?.parse::<i64>().ok();
+    let (year, month, day) = (n(0..4)?, n(4..6)?, n(6..8)?);
+    let (hour, minute, second) = (n(8..10)?, n(10..12)?, n(12..14)?);
+    // Days from the civil date (Howard Hinnant's algorithm)
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+    let mut seconds = days * 86400 + hour * 3600 + minute * 60 + second;
+    if let Some(offset) = value.get(14..).map(str::trim).filter(|o| o.len() == 5) {
+        let sign = if offset.starts_with('-') { -1 } else { 1 };
+        let hours: i64 = offset.get(1..3)?.parse().ok()?;
+        let minutes: i64 = offset.get(3..5)?.parse().ok()?;
+        seconds -= sign * (hours * 3600 + minutes * 60);
+    }
+    Some(seconds)
+}
+
+/// Programmes from an XMLTV document that overlap the window around `now`.
+fn parse_xmltv(content: &str, now: i64) -> HashMap<String, Vec<Programme>> {
+    let programme = Regex::new(r"(?s)<programme\b([^>]*)>(.*?)</programme>").unwrap();
+    // <title lang="en"> carries attributes, which xml_tag doesn't match
+    let title = Regex::new(r"(?s)<title\b[^>]*>(.*?)</title>").unwrap();
+    let desc = Regex::new(r"(?s)<desc\b[^>]*>(.*?)</desc>").unwrap();
+    let mut guide: HashMap<String, Vec<Programme>> = HashMap::new();
+    for c in programme.captures_iter(content) {
+        let attrs = &c[1];
+        let (Some(channel), Some(start), Some(stop)) = (
+            attribute(attrs, "channel"),
+            attribute(attrs, "start").and_then(|s| parse_xmltv_time(&s)),
+            attribute(attrs, "stop").and_then(|s| parse_xmltv_time(&s)),
+        ) else {
+            continue;
+        };
+        if stop < now - GUIDE_PAST_SECS || start > now + GUIDE_AHEAD_SECS {
+            continue;
+        }
+        let body = &c[2];
+        guide.entry(channel).or_default().push(Programme {
+            title: title.captures(body).map(|t| unescape_xml(t[1].trim())).unwrap_or_default(),
+            description: desc.captures(body).map(|d| unescape_xml(d[1].trim())).or_else(|| xml_tag(body, "sub-title")),
+            start,
+            stop,
+        });
+    }
+    for programmes in guide.values_mut() {
+        programmes.sort_by_key(|p| p.start);
+    }
+    guide
+}
+
+fn epg_cache_path(app: &AppHandle, playlist_id: &str) -> Result<PathBuf, String> {
+    Ok(app.path().app_data_dir().map_err(|e| e.to_string())?.join("epg").join(format!("{}.xml", playlist_id)))
+}
+
+/// The guide for a playlist: from memory while its window is current, the disk cache while
+/// it's fresh, or downloaded.
+async fn load_guide(app: &AppHandle, state: &IptvState, playlist: &IptvPlaylist, refresh: bool) -> Result<(), String> {
+    let current = state.guides.lock().map_err(|e| e.to_string())?.get(&playlist.id).is_some_and(|g| now_secs() as i64 - g.windowed_at < GUIDE_REWINDOW_SECS);
+    if !refresh && current {
+        return Ok(());
+    }
+    let Some(epg_url) = &playlist.epg_url else {
+        return Ok(());
+    };
+    let max_age = std::time::Duration::from_secs(load_config(app).iptv.epg_refresh_hours * 3600);
+    let cache = epg_cache_path(app, &playlist.id)?;
+    let fresh = fs::metadata(&cache).and_then(|m| m.modified()).ok().and_then(|m| m.elapsed().ok()).is_some_and(|age| age < max_age);
+    let content = match fs::read_to_string(&cache) {
+        Ok(content) if fresh && !refresh => content,
+        stale => match read_source(epg_url).await {
+            Ok(content) => {
+                if let Some(parent) = cache.parent() {
+                    let _ = fs::create_dir_all(parent);
+                }
+                let _ = fs::write(&cache, &content);
+                content
+            }
+            // Offline: an old guide beats none
+            Err(e) => match stale {
+                Ok(content) if !refresh => {
+                    tracing::warn!("Guide for '{}' not refreshed, using the cached copy: {}", playlist.name, e);
+                    content
+                }
+                _ => return Err(e),
+            },
+        },
+    };
+    let windowed_at = now_secs() as i64;
+    let channels = tauri::async_runtime::spawn_blocking(move || parse_xmltv(&content, windowed_at))
+        .await
+        .map_err(|e| e.to_string())?;
+    tracing::info!("📺 Loaded guide for '{}' ({} channels)", playlist.name, channels.len());
+    state.guides.lock().map_err(|e| e.to_string())?.insert(playlist.id.clone(), Guide { windowed_at, channels });
+    Ok(())
+}
+
+/// Import an M3U channel list from a file or URL. `epg_url` overrides the list's own `url-tvg`.
+#[command]
+pub async fn import_iptv_playlist(app: AppHandle, source: String, name: Option<String>, epg_url: Option<String>) -> Result<IptvPlaylist, String> {
+    crate::parental::check_area(&app, "iptv")?;
+    let source = source.trim().to_string();
+    let (channels, list_epg) = parse_m3u(&read_source(&source).await?);
+    if channels.is_empty() {
+        return Err("No channels found in the playlist".to_string());
+    }
+    let name = name.filter(|n| !n.trim().is_empty()).unwrap_or_else(|| {
+        let tail = source.trim_end_matches('/').rsplit(['/', '\\']).next().unwrap_or("IPTV");
+        tail.split('?').next().unwrap_or(tail).trim_end_matches(".m3u8").trim_end_matches(".m3u").to_string()
+    });
+    let playlist = IptvPlaylist {
+        id: uuid::Uuid::new_v4().to_string(),
+        name,
+        source,
+        epg_url: epg_url.filter(|u| !u.trim().is_empty()).or(list_epg),
+        channels,
+        updated: now_secs(),
+    };
+    let mut store = load_store(&app);
+    store.playlists.push(playlist.clone());
+    save_store(&app, &store)?;
+    tracing::info!("📺 Imported IPTV list '{}' ({} channels)", playlist.name, playlist.channels.len());
+    Ok(playlist)
+}
+
+#[command]
+pub fn list_iptv_playlists(app: AppHandle) -> Result<Vec<IptvPlaylist>, String> {
+    crate::parental::check_area(&app, "iptv")?;
+    Ok(load_store(&app).playlists)
+}
+
+/// Re-read a playlist's channels from its source and reload its guide.
+#[command]
+pub async fn refresh_iptv_playlist(app: AppHandle, state: State<'_, IptvState>, id: String) -> Result<IptvPlaylist, String> {
+    let mut store = load_store(&app);
+    let playlist = store.playlists.iter_mut().find(|p| p.id == id).ok_or("IPTV playlist not found")?;
+    let (channels, list_epg) = parse_m3u(&read_source(&playlist.source).await?);
+    if channels.is_empty() {
+        return Err("No channels found in the playlist".to_string());
+    }
+    playlist.channels = channels;
+    playlist.epg_url = playlist.epg_url.take().or(list_epg);
+    playlist.updated = now_secs();
+    let playlist = playlist.clone();
+    save_store(&app, &store)?;
+    load_guide(&app, &state, &playlist, true).await?;
+    Ok(playlist)
+}
+
+#[command]
+pub fn delete_iptv_playlist(app: AppHandle, state: State<'_, IptvState>, id: String) -> Result<(), String> {
+    let mut store = load_store(&app);
+    let before = store.playlists.len();
+    store.playlists.retain(|p| p.id != id);
+    if store.playlists.len() == before {
+        return Err("IPTV playlist not found".to_string());
+    }
+    save_store(&app, &store)?;
+    state.guides.lock().map_err(|e| e.to_string())?.remove(&id);
+    if let Ok(cache) = epg_cache_path(&app, &id) {
+        let _ = fs::remove_file(cache);
+    }
+    Ok(())
+}
+
+/// Set a playlist's guide URL (empty to remove it) and load it.
+#[command]
+pub async fn set_iptv_epg(app: AppHandle, state: State<'_, IptvState>, id: String, epg_url: Option<String>) -> Result<(), String> {
+    let mut store = load_store(&app);
+    let playlist = store.playlists.iter_mut().find(|p| p.id == id).ok_or("IPTV playlist not found")?;
+    playlist.epg_url = epg_url.map(|u| u.trim().to_string()).filter(|u| !u.is_empty());
+    let playlist = playlist.clone();
+    save_store(&app, &store)?;
+    state.guides.lock().map_err(|e| e.to_string())?.remove(&id);
+    load_guide(&app, &state, &playlist, true).await
+}
+
+/// Now/next for every channel of a playlist that the guide knows.
+#[command]
+pub async fn get_iptv_guide(app: AppHandle, state: State<'_, IptvState>, id: String, refresh: Option<bool>) -> Result<Vec<ChannelGuide>, String> {
+    let playlist = load_store(&app).playlists.into_iter().find(|p| p.id == id).ok_or("IPTV playlist not found")?;
+    load_guide(&app, &state, &playlist, refresh.unwrap_or(false)).await?;
+    let guides = state.guides.lock().map_err(|e| e.to_string())?;
+    let Some(guide) = guides.get(&id) else {
+        return Ok(Vec::new());
+    };
+    let now = now_secs() as i64;
+    Ok(playlist
+        .channels
+        .iter()
+        .filter_map(|channel| {
+            // Guides that don't use tvg-ids key channels by display name
+            let programmes = channel.tvg_id.as_ref().and_then(|id| guide.channels.get(id)).or_else(|| guide.channels.get(&channel.name))?;
+            let current = programmes.iter().position(|p| p.start <= now && now < p.stop);
+            let upcoming = match current {
+                Some(i) => programmes.get(i + 1),
+                None => programmes.iter().find(|p| p.start > now),
+            };
+            Some(ChannelGuide {
+                channel_id: channel.id.clone(),
+                now: current.map(|i| programmes[i].clone()),
+                next: upcoming.cloned(),
+            })
+        })
+        .collect())
+}
+
+/// The full loaded schedule for one channel.
+#[command]
+pub fn get_channel_programmes(app: AppHandle, state: State<'_, IptvState>, id: String, channel_id: String) -> Result<Vec<Programme>, String> {
+    let playlist = load_store(&app).playlists.into_iter().find(|p| p.id == id).ok_or("IPTV playlist not found")?;
+    let channel = playlist.channels.into_iter().find(|c| c.id == channel_id).ok_or("Channel not found")?;
+    let guides = state.guides.lock().map_err(|e| e.to_string())?;
+    let Some(guide) = guides.get(&id) else {
+        return Ok(Vec::new());
+    };
+    let programmes = channel.tvg_id.as_ref().and_then(|tvg| guide.channels.get(tvg)).or_else(|| guide.channels.get(&channel.name));
+    Ok(programmes.cloned().unwrap_or_default())
+}
+
+/// Play a channel with live-stream caching and its HTTP headers.
+#[command]
+pub fn play_iptv_channel(window: Window, state: State<'_, MpvState>, id: String, channel_id: String) -> Result<(), String> {
+    let app = window.app_handle().clone();
+    crate::parental::check_area(&app, "iptv")?;
+    let playlist = load_store(&app).playlists.into_iter().find(|p| p.id == id).ok_or("IPTV playlist not found")?;
+    let channel = playlist.channels.into_iter().find(|c| c.id == channel_id).ok_or("Channel not found")?;
+    let settings = load_config(&app).iptv;
+
+    let mut options = vec![
+        ("cache".to_string(), "yes".to_string()),
+        ("cache-secs".to_string(), settings.readahead_secs.to_string()),
+        ("demuxer-readahead-secs".to_string(), settings.readahead_secs.to_string()),
+        ("demuxer-max-bytes".to_string(), format!("{}MiB", settings.cache_mb)),
+        ("network-timeout".to_string(), "15".to_string()),
+        // Live streams drop; have ffmpeg reconnect instead of ending playback
+        ("stream-lavf-o".to_string(), "reconnect=1,reconnect_streamed=1,reconnect_delay_max=5".to_string()),
+    ];
+    if let Some(user_agent) = &channel.user_agent {
+        options.push(("user-agent".to_string(), user_agent.clone()));
+    }
+    if let Some(referrer) = &channel.referrer {
+        options.push(("referrer".to_string(), referrer.clone()));
+    }
+
+    crate::mpv_handler::start_player(&window, &state);
+    app.state::<crate::queue::QueueState>().play_single(&app, channel.url.clone());
+    if let Some(tx) = state.tx.lock().unwrap().as_ref() {
+        let _ = tx.send(MpvCommand::LoadStream(channel.url.clone(), options));
+    }
+    tracing::info!("📺 Tuning to {}", channel.name);
+    Ok(())
+}
@@ -0,0 +1,359 @@
+use libmpv2::Mpv;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::{command, AppHandle, Manager};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::config::{load_config, save_config, LastfmSession};
+use crate::util::now_secs;
+
+// Last.fm scrobbling for music: local files with no video (cover art aside). Tags come from
+// the file itself as mpv reads them on load. A track gets a now-playing update when it
+// starts and is scrobbled once half of it, or four minutes, has actually been listened to
+// (Last.fm's rules). Scrobbles that can't be sent wait in app_data/lastfm_queue.json;
+// ones Last.fm rejects outright are set aside in lastfm_rejected.json.
+
+const API_URL: &str = "https://ws.audioscrobbler.com/2.0/";
+const AUTH_URL: &str = "https://www.last.fm/api/auth/";
+const MIN_TRACK_SECS: f64 = 30.0;
+const MAX_LISTEN_SECS: f64 = 240.0;
+// Position jumps larger than this are seeks, not listening
+const MAX_PROGRESS_STEP: f64 = 2.0;
+const BATCH_SIZE: usize = 50;
+// Oldest scrobbles are dropped past this, so a long offline spell can't grow the queue forever
+const MAX_QUEUE: usize = 2000;
+const LOGIN_POLL: Duration = Duration::from_secs(3);
+const LOGIN_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TrackTags {
+    pub artist: String,
+    pub title: String,
+    pub album: Option<String>,
+    pub album_artist: Option<String>,
+    pub duration: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Scrobble {
+    #[serde(flatten)]
+    track: TrackTags,
+    timestamp: u64, // when playback started, Unix seconds
+}
+
+/// Tags of the loaded file, or None when it isn't music (has real video, or lacks tags).
+pub fn read_tags(mpv: &Mpv) -> Option<TrackTags> {
+    let video = mpv.get_property::<String>("current-tracks/video/id").is_ok();
+    let art = mpv.get_property::<bool>("current-tracks/video/albumart").unwrap_or(false);
+    if video && !art {
+        return None;
+    }
+    let tag = |key: &str| {
+        mpv.get_property::<String>(&format!("metadata/by-key/{}", key))
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    Some(TrackTags {
+        artist: tag("artist").or_else(|| tag("album_artist"))?,
+        title: tag("title")?,
+        album: tag("album"),
+        album_artist: tag("album_artist"),
+        duration: mpv.get_property::<f64>("duration").ok().filter(|d| *d > 0.0),
+    })
+}
+
+// ---- API ----
+
+/// Sign a call: md5 of the sorted parameters (name then value) followed by the secret.
+fn sign(params: &BTreeMap<String, String>, secret: &str) -> String {
+    let mut base: String = params.iter().map(|(k, v)| format!("{}{}", k, v)).collect();
+    base.push_str(secret);
+    format!("{:x}", md5::compute(base))
+}
+
+async fn call(method: &str, mut params: BTreeMap<String, String>, session: Option<&str>, api_key: &str, secret: &str) -> Result<serde_json::Value, String> {
+    params.insert("method".to_string(), method.to_string());
+    params.insert("api_key".to_string(), api_key.to_string());
+    if let Some(sk) = session {
+        params.insert("sk".to_string(), sk.to_string());
+    }
+    let signature = sign(&params, secret);
+    params.insert("api_sig".to_string(), signature);
+    params.insert("format".to_string(), "json".to_string());
+    let resp = crate::metadata::http_client()
+        .post(API_URL)
+        .form(&params)
+        .send()
+        .await
+        .map_err(|e| format!("Last.fm request failed: {}", e))?;
+    if resp.status().is_server_error() {
+        return Err(format!("Last.fm request failed: server returned {}", resp.status()));
+    }
+    let body: serde_json::Value = resp.json().await.map_err(|e| format!("Failed to parse Last.fm response: {}", e))?;
+    if let Some(code) = body["error"].as_u64() {
+        return Err(format!("Last.fm error {}: {}", code, body["message"].as_str().unwrap_or("unknown")));
+    }
+    Ok(body)
+}
+
+fn track_params(track: &TrackTags, index: Option<usize>) -> BTreeMap<String, String> {
+    let key = |name: &str| match index {
+        Some(i) => format!("{}[{}]", name, i),
+        None => name.to_string(),
+    };
+    let mut params = BTreeMap::new();
+    params.insert(key("artist"), track.artist.clone());
+    params.insert(key("track"), track.title.clone());
+    if let Some(album) = &track.album {
+        params.insert(key("album"), album.clone());
+    }
+    if let Some(album_artist) = &track.album_artist {
+        params.insert(key("albumArtist"), album_artist.clone());
+    }
+    if let Some(duration) = track.duration {
+        params.insert(key("duration"), (duration.round() as u64).to_string());
+    }
+    params
+}
+
+// ---- Offline queue ----
+
+static QUEUE_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+fn queue_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app.path().app_data_dir().map_err(|e| e.to_string())?.join("lastfm_queue.json"))
+}
+
+fn load_queue(app: &AppHandle) -> Vec<Scrobble> {
+    queue_path(app)
+        .ok()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+fn save_queue(app: &AppHandle, queue: &[Scrobble]) -> Result<(), String> {
+    let path = queue_path(app)?;
+    write_scrobbles(&path, queue)
+}
+
+fn write_scrobbles(path: &std::path::Path, queue: &[Scrobble]) -> Result<(), String> {
+    if queue.is_empty() {
+        let _ = fs::remove_file(path);
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(path, serde_json::to_string(queue).map_err(|e| e.to_string())?).map_err(|e| e.to_string())
+}
+
+/// Worth sending again later: network trouble, 5xx, and Last.fm's "service offline" (11)
+/// and "temporarily unavailable" (16). Anything else will fail the same way every time.
+fn is_retryable(error: &str) -> bool {
+    match error.strip_prefix("Last.fm error ") {
+        Some(rest) => matches!(rest.split(':').next(), Some("11" | "16")),
+        None => true,
+    }
+}
+
+/// Set a rejected batch aside in `lastfm_rejected.json` so it stops blocking the queue
+/// but isn't lost.
+fn park(app: &AppHandle, batch: &[Scrobble]) -> Result<(), String> {
+    let path = app.path().app_data_dir().map_err(|e| e.to_string())?.join("lastfm_rejected.json");
+    let mut parked: Vec<Scrobble> = fs::read_to_string(&path).ok().and_then(|d| serde_json::from_str(&d).ok()).unwrap_or_default();
+    parked.extend_from_slice(batch);
+    let excess = parked.len().saturating_sub(MAX_QUEUE);
+    parked.drain(..excess);
+    write_scrobbles(&path, &parked)
+}
+
+/// Queue `scrobble` (if any) behind what's waiting and send as much as possible.
+/// Returns how many are still queued.
+async fn submit(app: &AppHandle, scrobble: Option<Scrobble>) -> Result<usize, String> {
+    let _guard = QUEUE_LOCK.lock().await;
+    let mut queue = load_queue(app);
+    queue.extend(scrobble);
+    if queue.len() > MAX_QUEUE {
+        let excess = queue.len() - MAX_QUEUE;
+        tracing::warn!("🎵 Last.fm queue full, dropping the {} oldest scrobble(s)", excess);
+        queue.drain(..excess);
+    }
+    let settings = load_config(app).lastfm;
+    let Some(session) = settings.session.as_ref() else {
+        save_queue(app, &queue)?;
+        return Ok(queue.len());
+    };
+    let mut result = Ok(());
+    while !queue.is_empty() {
+        let batch = &queue[..queue.len().min(BATCH_SIZE)];
+        let mut params = BTreeMap::new();
+        for (i, scrobble) in batch.iter().enumerate() {
+            params.extend(track_params(&scrobble.track, Some(i)));
+            params.insert(format!("timestamp[{}]", i), scrobble.timestamp.to_string());
+        }
+        match call("track.scrobble", params, Some(&session.key), &settings.api_key, &settings.api_secret).await {
+            Ok(_) => {
+                tracing::info!("🎵 Scrobbled {} track(s) to Last.fm", batch.len());
+                queue.drain(..batch.len());
+            }
+            Err(e) if is_retryable(&e) => {
+                result = Err(e);
+                break;
+            }
+            Err(e) => {
+                tracing::warn!("🎵 Last.fm rejected {} scrobble(s), setting them aside: {}", batch.len(), e);
+                park(app, batch)?;
+                queue.drain(..batch.len());
+                result = Err(e);
+            }
+        }
+    }
+    save_queue(app, &queue)?;
+    result.map(|_| queue.len())
+}
+
+/// Send anything left over from an earlier session, shortly after launch.
+pub fn flush_queue_later(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        if load_config(&app).lastfm.session.is_some() && !load_queue(&app).is_empty() {
+            if let Err(e) = submit(&app, None).await {
+                tracing::warn!("🎵 Last.fm queue not sent: {}", e);
+            }
+        }
+    });
+}
+
+// ---- Player tracking ----
+
+/// Fed from the player loop, like `PresenceTracker`.
+pub struct LastfmScrobbler {
+    track: Option<(TrackTags, u64)>, // with its start time
+    listened: f64,
+    last_position: Option<f64>,
+}
+
+impl LastfmScrobbler {
+    pub fn new() -> Self {
+        Self { track: None, listened: 0.0, last_position: None }
+    }
+
+    /// A file finished loading; `tags` is None for anything that isn't music. Streams and
+    /// podcast episodes (even downloaded ones) aren't scrobbled.
+    pub fn start(&mut self, app: &AppHandle, path: Option<&str>, tags: Option<TrackTags>) {
+        let config = load_config(app);
+        let local = path.is_some_and(|p| !p.contains("://"));
+        let active = local && config.lastfm.enabled && config.lastfm.session.is_some() && !config.private_mode;
+        self.track = tags.filter(|t| active && t.duration.is_none_or(|d| d > MIN_TRACK_SECS)).map(|t| (t, now_secs()));
+        self.listened = 0.0;
+        self.last_position = None;
+        let Some((track, _)) = self.track.clone() else {
+            return;
+        };
+        let settings = config.lastfm;
+        tauri::async_runtime::spawn(async move {
+            let Some(session) = settings.session else {
+                return;
+            };
+            if let Err(e) = call("track.updateNowPlaying", track_params(&track, None), Some(&session.key), &settings.api_key, &settings.api_secret).await {
+                tracing::warn!("🎵 Last.fm now playing not sent: {}", e);
+            }
+        });
+    }
+
+    pub fn progress(&mut self, app: &AppHandle, position: f64) {
+        let Some((track, _)) = &self.track else {
+            return;
+        };
+        if let Some(last) = self.last_position {
+            let step = position - last;
+            if step > 0.0 && step < MAX_PROGRESS_STEP {
+                self.listened += step;
+            }
+        }
+        self.last_position = Some(position);
+        let needed = track.duration.map_or(MAX_LISTEN_SECS, |d| (d / 2.0).min(MAX_LISTEN_SECS));
+        if self.listened >= needed {
+            if let Some((track, timestamp)) = self.track.take() {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = submit(&app, Some(Scrobble { track, timestamp })).await {
+                        tracing::warn!("🎵 Scrobble queued for later: {}", e);
+                    }
+                });
+            }
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.track = None;
+    }
+}
+
+// ---- Commands ----
+
+#[derive(Serialize)]
+pub struct LastfmStatus {
+    pub username: Option<String>,
+    pub queued: usize,
+}
+
+#[command]
+pub fn get_lastfm_status(app: AppHandle) -> LastfmStatus {
+    LastfmStatus { username: load_config(&app).lastfm.session.map(|s| s.username), queued: load_queue(&app).len() }
+}
+
+/// Desktop auth: get a token, let the user approve it on last.fm, then trade it for a
+/// session key (which doesn't expire).
+#[command]
+pub async fn lastfm_login(app: AppHandle) -> Result<LastfmStatus, String> {
+    let settings = load_config(&app).lastfm;
+    if settings.api_key.is_empty() || settings.api_secret.is_empty() {
+        return Err("Set a Last.fm API key and secret first".to_string());
+    }
+    let body = call("auth.getToken", BTreeMap::new(), None, &settings.api_key, &settings.api_secret).await?;
+    let token = body["token"].as_str().ok_or("Last.fm didn't return a token")?.to_string();
+    let url = format!("{}?api_key={}&token={}", AUTH_URL, urlencoding::encode(&settings.api_key), token);
+    app.opener().open_url(url, None::<&str>).map_err(|e| e.to_string())?;
+
+    let started = std::time::Instant::now();
+    let session = loop {
+        tokio::time::sleep(LOGIN_POLL).await;
+        let params = BTreeMap::from([("token".to_string(), token.clone())]);
+        match call("auth.getSession", params, None, &settings.api_key, &settings.api_secret).await {
+            Ok(body) => break body,
+            // 14: not authorized yet
+            Err(e) if e.starts_with("Last.fm error 14") && started.elapsed() < LOGIN_TIMEOUT => continue,
+            Err(e) if e.starts_with("Last.fm error 14") => return Err("Timed out waiting for Last.fm approval".to_string()),
+            Err(e) => return Err(e),
+        }
+    };
+    let session = LastfmSession {
+        username: session["session"]["name"].as_str().unwrap_or_default().to_string(),
+        key: session["session"]["key"].as_str().ok_or("Last.fm didn't return a session")?.to_string(),
+    };
+    let mut config = load_config(&app);
+    tracing::info!("🎵 Logged in to Last.fm as {}", session.username);
+    config.lastfm.session = Some(session);
+    save_config(&app, &config)?;
+    let queued = submit(&app, None).await.unwrap_or_else(|_| load_queue(&app).len());
+    Ok(LastfmStatus { username: config.lastfm.session.map(|s| s.username), queued })
+}
+
+#[command]
+pub fn lastfm_logout(app: AppHandle) -> Result<(), String> {
+    let mut config = load_config(&app);
+    config.lastfm.session = None;
+    save_config(&app, &config)
+}
+
+/// Retry the offline queue now.
+#[command]
+pub async fn flush_lastfm_queue(app: AppHandle) -> Result<usize, String> {
+    submit(&app, None).await
+}
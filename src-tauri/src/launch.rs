@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{command, AppHandle, Emitter, Manager, State, Window};
+
+use crate::mpv_handler::{MpvCommand, MpvState};
+
+// Files FrameX was launched with ("Open with", file associations, the command line), and the
+// same from later launches that the single-instance plugin forwards to this process:
+//
+//   framex [--fullscreen] [--start-at=hh:mm:ss] <file>...
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct LaunchArgs {
+    pub files: Vec<String>,
+    pub fullscreen: bool,
+    pub start_at: Option<f64>,
+    /// Append to the running queue instead of replacing it (see `StartupOptions::enqueue_opened_files`)
+    pub enqueue: bool,
+}
+
+/// Arguments waiting for the frontend, which picks them up with `take_launch_args`
+/// once it's loaded (and again on every `launch-args` event).
+pub struct LaunchState {
+    pending: Mutex<Option<LaunchArgs>>,
+}
+
+impl LaunchState {
+    pub fn new() -> Self {
+        Self { pending: Mutex::new(None) }
+    }
+}
+
+/// "90", "01:30", "1:02:03.5" -> seconds.
+fn parse_timestamp(value: &str) -> Option<f64> {
+    let mut seconds = 0.0;
+    for part in value.trim().split(':') {
+        let n: f64 = part.parse().ok().filter(|n: &f64| n.is_finite() && *n >= 0.0)?;
+        seconds = seconds * 60.0 + n;
+    }
+    Some(seconds)
+}
+
+/// Parse argv (without the program name). Relative paths resolve against `cwd`, which for a
+/// forwarded launch is the second process's working directory, not ours.
+pub fn parse(args: impl IntoIterator<Item = String>, cwd: &Path) -> LaunchArgs {
+    let mut parsed = LaunchArgs::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--fullscreen" || arg == "-f" {
+            parsed.fullscreen = true;
+        } else if let Some(value) = arg.strip_prefix("--start-at=") {
+            parsed.start_at = parse_timestamp(value);
+        } else if arg == "--start-at" {
+            parsed.start_at = args.next().as_deref().and_then(parse_timestamp);
+        } else if arg.starts_with('-') {
+            // Unknown flags (and the ones the OS or webview adds) are ignored
+            continue;
+        } else if arg.contains("://") {
+            parsed.files.push(arg);
+        } else {
+            let path = PathBuf::from(&arg);
+            let path = if path.is_absolute() { path } else { cwd.join(path) };
+            parsed.files.push(path.to_string_lossy().into_owned());
+        }
+    }
+    parsed
+}
+
+/// Hand arguments to the frontend. Nothing to do for a bare relaunch beyond showing the window.
+pub fn deliver(app: &AppHandle, mut args: LaunchArgs, forwarded: bool) {
+    if forwarded {
+        crate::tray::show_main_window(app);
+    }
+    if args.files.is_empty() && !args.fullscreen {
+        return;
+    }
+    let playing = app.state::<crate::queue::QueueState>().is_active();
+    args.enqueue = forwarded && playing && crate::config::load_config(app).startup.enqueue_opened_files;
+    tracing::info!("📂 Opening {} file(s) from launch arguments", args.files.len());
+    *app.state::<LaunchState>().pending.lock().unwrap() = Some(args);
+    let _ = app.emit("launch-args", ());
+}
+
+/// The single-instance plugin's callback: a second launch forwards its argv here and exits.
+pub fn forward(app: &AppHandle, argv: Vec<String>, cwd: String) {
+    deliver(app, parse(argv.into_iter().skip(1), Path::new(&cwd)), true);
+}
+
+/// macOS delivers Finder "Open with" as an event instead of arguments.
+#[cfg(target_os = "macos")]
+pub fn opened(app: &AppHandle, urls: Vec<tauri::Url>) {
+    let files = urls
+        .into_iter()
+        .map(|url| match url.to_file_path() {
+            Ok(path) => path.to_string_lossy().into_owned(),
+            Err(()) => url.to_string(),
+        })
+        .collect();
+    deliver(app, LaunchArgs { files, ..LaunchArgs::default() }, true);
+}
+
+#[command]
+pub fn take_launch_args(state: State<'_, LaunchState>) -> Option<LaunchArgs> {
+    state.pending.lock().unwrap().take()
+}
+
+/// Play (or queue) the launch files. The first file starts at `start_at` when given.
+#[command]
+pub fn play_launch_args(window: Window, state: State<'_, MpvState>, args: LaunchArgs) -> Result<(), String> {
+    if args.files.is_empty() {
+        return Ok(());
+    }
+    if args.enqueue {
+        return crate::queue::queue_add(window, state, args.files).map(|_| ());
+    }
+    crate::mpv_handler::start_player(&window, &state);
+    if let (Some(position), Some(tx)) = (args.start_at, state.tx.lock().unwrap().as_ref()) {
+        // Cleared again once the file has loaded
+        let _ = tx.send(MpvCommand::SetOption("start".to_string(), position.to_string()));
+    }
+    if args.files.len() == 1 {
+        crate::mpv_handler::load_video(window, state, args.files[0].clone())?;
+    } else {
+        crate::queue::start_queue(&window, &state, args.files);
+    }
+    Ok(())
+}
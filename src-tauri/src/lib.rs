@@ -4,27 +4,132 @@ fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
+use tauri::Manager;
+
+mod util;
+mod xml;
+mod logging;
+mod diagnostics;
 mod mpv_handler;
+mod mpv_config;
 mod file_scanner;
 mod thumbnail_generator;
 mod config;
 mod ai;
 mod metadata;
+mod metadata_cache;
+mod poster_cache;
+mod anilist;
+mod anime_sync;
+mod nfo_export;
 mod watch_history;
+mod history_io;
+mod sync;
 mod playlist;
+mod playlist_io;
+mod queue;
+mod tags;
 mod subtitle_downloader;
+mod subtitle_extract;
+mod subtitle_ocr;
+mod subtitle_format;
+mod subtitle_pipeline;
 mod discord_rpc;
+mod media_server;
+mod cast;
+mod dlna;
+mod network_sources;
+mod torrent;
+mod downloads;
+mod iptv;
+mod radio;
+mod podcasts;
+mod mpris;
+mod hotkeys;
+mod launch;
+mod lastfm;
+mod segments;
+mod intro_detect;
+mod ffmpeg;
+mod clips;
+mod conversions;
+mod audio_extract;
+mod plugins;
+mod parental;
+mod updates;
+mod hardware;
+mod benchmark;
+mod window_mode;
+mod watch_party;
+mod tray;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    let builder = tauri::Builder::default()
+        // Must come first, so a second launch exits before anything else starts
+        .plugin(tauri_plugin_single_instance::init(launch::forward))
         .manage(mpv_handler::MpvState::new())
         .manage(discord_rpc::DiscordRpcState::new())
+        .manage(cast::CastState::new())
+        .manage(torrent::TorrentState::new())
+        .manage(downloads::DownloadState::new())
+        .manage(iptv::IptvState::new())
+        .manage(ai::whisper::WhisperJobs::new())
+        .manage(subtitle_ocr::OcrJobs::new())
+        .manage(metadata_cache::MetadataCache::new())
+        .manage(watch_history::WatchHistoryDb::new())
+        .manage(queue::QueueState::new())
+        .manage(playlist::PlaylistState::new())
+        .manage(launch::LaunchState::new())
+        .manage(watch_party::WatchPartyState::new())
+        .manage(parental::ParentalState::new())
+        .manage(segments::SegmentState::new())
+        .manage(intro_detect::SegmentDetection::new())
+        .manage(clips::ClipState::new())
+        .manage(conversions::ConversionState::new())
+        .manage(benchmark::BenchmarkState::new())
+        .manage(window_mode::WindowModeState::new())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_http::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().with_handler(hotkeys::handle).build());
+    #[cfg(feature = "updater")]
+    let builder = builder.plugin(tauri_plugin_updater::Builder::new().build());
+    builder
+        .setup(|app| {
+            logging::init(app.handle());
+            diagnostics::install_panic_hook(app.handle());
+            tray::setup_tray(app)?;
+            config::restore_window_state(app.handle());
+            podcasts::start_refresh_loop(app.handle().clone());
+            updates::start_check_loop(app.handle().clone());
+            lastfm::flush_queue_later(app.handle().clone());
+            conversions::resume_queue(app.handle());
+            hotkeys::apply(app.handle());
+
+            let cwd = std::env::current_dir().unwrap_or_default();
+            let args = launch::parse(std::env::args().skip(1), &cwd);
+            let opening_files = !args.files.is_empty();
+            launch::deliver(app.handle(), args, false);
+
+            if config::load_config(app.handle()).startup.start_minimized && !opening_files {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.hide();
+                }
+            }
+            Ok(())
+        })
+        .on_window_event(|window, event| match event {
+            tauri::WindowEvent::CloseRequested { .. } => config::save_window_state(window),
+            tauri::WindowEvent::Moved(_) => config::track_window_bounds(window),
+            tauri::WindowEvent::Resized(size) => {
+                window_mode::keep_aspect(window, size);
+                config::track_window_bounds(window);
+            }
+            _ => {}
+        })
         .invoke_handler(tauri::generate_handler![
             greet, 
             mpv_handler::play_video, 
@@ -64,45 +169,285 @@ pub fn run() {
             mpv_handler::mpv_set_gamma,
             // Stream URL
             mpv_handler::mpv_load_url,
+            // mpv.conf Management
+            mpv_config::mpv_conf_get_options,
+            mpv_config::mpv_conf_set_option,
+            mpv_config::mpv_conf_validate,
+            mpv_config::mpv_conf_restore_defaults,
+            mpv_config::mpv_conf_list_scripts,
             //
             file_scanner::list_videos, 
             file_scanner::get_video_duration,
             thumbnail_generator::generate_thumbnail,
             thumbnail_generator::generate_seek_preview,
             thumbnail_generator::generate_preview,
+            // Network sources
+            network_sources::list_network_sources,
+            network_sources::save_network_source,
+            network_sources::delete_network_source,
             // Config
             config::save_last_folder,
             config::get_last_folder,
+            config::get_startup_options,
+            config::save_startup_options,
+            config::get_startup_state,
+            config::get_provider_credentials,
+            config::save_provider_credentials,
+            config::get_ai_settings,
+            config::save_ai_settings,
+            config::get_metadata_settings,
+            config::save_metadata_settings,
+            config::get_subtitle_settings,
+            config::save_subtitle_settings,
+            config::get_torrent_settings,
+            config::save_torrent_settings,
+            config::get_download_settings,
+            config::save_download_settings,
+            config::get_iptv_settings,
+            config::save_iptv_settings,
+            config::get_podcast_settings,
+            config::save_podcast_settings,
+            config::get_global_hotkeys,
+            config::save_global_hotkeys,
+            config::get_watch_party_settings,
+            config::save_watch_party_settings,
+            config::get_anime_sync_settings,
+            config::save_anime_sync_settings,
+            config::get_lastfm_settings,
+            config::save_lastfm_settings,
+            config::get_segment_settings,
+            config::save_segment_settings,
+            config::get_clip_settings,
+            config::save_clip_settings,
+            config::get_conversion_settings,
+            config::save_conversion_settings,
+            config::get_presence_settings,
+            config::save_presence_settings,
+            config::get_private_mode,
+            config::set_private_mode,
+            config::get_sync_settings,
+            config::save_sync_settings,
             // AI
             ai::whisper::run_whisper,
+            ai::whisper::cancel_whisper,
+            ai::whisper::list_audio_tracks,
+            ai::whisper::get_whisper_backends,
+            ai::transcript::index_transcript,
+            ai::transcript::get_transcript,
+            ai::transcript::search_transcript,
+            ai::chapters::generate_chapters,
+            ai::translate::translate_subtitles,
+            ai::summary::summarize_video,
+            ai::summary::get_video_summary,
             // Metadata
             metadata::fetch_metadata,
             metadata::check_poster_exists,
             metadata::read_poster,
+            poster_cache::migrate_posters_to_cache,
             metadata::fetch_movie_info,
             metadata::fetch_folder_poster,
             metadata::fetch_tv_info,
+            metadata::fetch_season_info,
+            metadata::search_metadata_candidates,
+            metadata::apply_metadata_match,
+            metadata::get_metadata_match,
+            metadata::get_artwork,
+            metadata::fetch_media_info,
+            metadata::refresh_metadata,
+            metadata::clear_metadata,
+            metadata::refresh_folder_metadata,
+            metadata::identify_folder,
+            metadata::get_metadata_provider,
+            metadata::set_metadata_provider,
+            anilist::fetch_anime_info,
+            anime_sync::anilist_open_login,
+            anime_sync::anilist_login,
+            anime_sync::mal_login,
+            anime_sync::anime_sync_logout,
+            anime_sync::list_anime_mappings,
+            anime_sync::set_anime_mapping,
+            anime_sync::delete_anime_mapping,
+            lastfm::get_lastfm_status,
+            lastfm::lastfm_login,
+            lastfm::lastfm_logout,
+            lastfm::flush_lastfm_queue,
+            nfo_export::export_nfo,
+            nfo_export::export_library_nfo,
             // Watch History
             watch_history::save_watch_position,
             watch_history::get_watch_position,
             watch_history::get_watch_history,
+            watch_history::query_watch_history,
+            watch_history::get_continue_watching,
+            watch_history::get_collection_progress,
             watch_history::clear_watch_history,
+            watch_history::get_watch_stats,
+            watch_history::get_next_up,
+            watch_history::mark_watched,
+            watch_history::mark_unwatched,
+            watch_history::get_watched_status,
+            segments::get_segments,
+            segments::get_current_segments,
+            segments::add_segment,
+            segments::delete_segment,
+            intro_detect::detect_season_segments,
+            intro_detect::cancel_segment_detection,
+            clips::export_clip,
+            clips::list_clips,
+            clips::cancel_clip,
+            clips::clear_finished_clips,
+            conversions::list_conversion_presets,
+            conversions::queue_conversions,
+            conversions::list_conversions,
+            conversions::cancel_conversion,
+            conversions::clear_finished_conversions,
+            audio_extract::extract_audio,
+            plugins::list_plugins,
+            plugins::set_plugin_enabled,
+            plugins::install_plugin,
+            plugins::uninstall_plugin,
+            plugins::set_plugin_options,
+            logging::get_log_level,
+            logging::set_log_level,
+            logging::get_recent_logs,
+            diagnostics::list_crash_reports,
+            diagnostics::export_diagnostics,
+            parental::get_parental_controls,
+            parental::set_parental_pin,
+            parental::unlock_parental,
+            parental::lock_parental,
+            parental::save_parental_controls,
+            parental::is_area_locked,
+            updates::check_for_updates,
+            updates::skip_update,
+            updates::can_install_updates,
+            updates::install_update,
+            hardware::get_hw_capabilities,
+            benchmark::run_decode_benchmark,
+            benchmark::cancel_decode_benchmark,
+            window_mode::get_window_mode,
+            window_mode::set_always_on_top,
+            window_mode::toggle_always_on_top,
+            window_mode::set_mini_player,
+            window_mode::toggle_mini_player,
+            config::get_update_settings,
+            config::save_update_settings,
+            history_io::export_watch_history,
+            history_io::import_watch_history,
+            sync::sync_now,
             // Playlists & Collections
             playlist::save_playlist,
             playlist::get_playlists,
+            playlist::save_folder_playlist,
+            playlist::save_smart_playlist,
+            playlist::validate_playlist,
+            playlist::relocate_items,
+            tags::add_tags,
+            tags::remove_tags,
+            tags::set_favorite,
+            tags::get_tags,
+            tags::list_tags,
+            tags::get_videos_by_tag,
+            tags::get_favorites,
+            tags::set_rating,
+            tags::get_ratings,
+            playlist::playlist_add_items,
+            playlist::playlist_remove_item,
+            playlist::playlist_move_item,
+            queue::play_playlist,
+            queue::queue_play_next,
+            queue::queue_add,
+            queue::get_queue,
             playlist::delete_playlist,
             playlist::save_collection,
             playlist::get_collections,
             playlist::delete_collection,
+            playlist::set_collection_children,
+            playlist::set_collection_poster,
+            playlist::set_collection_sort,
+            playlist::get_collection_details,
+            playlist::get_playback_prefs,
+            playlist_io::import_playlist,
+            playlist_io::export_playlist,
             // Subtitle Downloader
+            subtitle_downloader::compute_video_hash,
             subtitle_downloader::search_subtitles,
             subtitle_downloader::download_subtitle,
+            subtitle_downloader::auto_fetch_subtitle,
+            subtitle_downloader::batch_download_subtitles,
+            subtitle_downloader::opensubtitles_login,
+            subtitle_downloader::opensubtitles_logout,
+            subtitle_downloader::get_subtitle_quota,
+            subtitle_downloader::clear_subtitle_cache,
+            subtitle_format::convert_subtitle,
+            subtitle_format::normalize_subtitle_encoding,
+            subtitle_extract::list_embedded_subtitles,
+            subtitle_extract::extract_embedded_subtitles,
+            subtitle_ocr::ocr_subtitle_track,
+            subtitle_ocr::cancel_subtitle_ocr,
+            subtitle_pipeline::ensure_subtitles,
             // Discord RPC
             discord_rpc::discord_rpc_connect,
             discord_rpc::discord_rpc_update,
             discord_rpc::discord_rpc_disconnect,
             discord_rpc::discord_rpc_status,
+            // Casting
+            cast::discover_cast_devices,
+            cast::cast_load,
+            cast::cast_play,
+            cast::cast_pause,
+            cast::cast_seek,
+            cast::cast_set_volume,
+            cast::cast_stop,
+            // Torrents
+            torrent::torrent_add,
+            torrent::torrent_list,
+            torrent::torrent_remove,
+            // Downloads
+            downloads::list_download_formats,
+            downloads::queue_download,
+            downloads::list_downloads,
+            downloads::cancel_download,
+            downloads::clear_finished_downloads,
+            // IPTV
+            iptv::import_iptv_playlist,
+            iptv::list_iptv_playlists,
+            iptv::refresh_iptv_playlist,
+            iptv::delete_iptv_playlist,
+            iptv::set_iptv_epg,
+            iptv::get_iptv_guide,
+            iptv::get_channel_programmes,
+            iptv::play_iptv_channel,
+            // Radio
+            radio::list_radio_stations,
+            radio::save_radio_station,
+            radio::delete_radio_station,
+            radio::play_radio_station,
+            // Podcasts
+            podcasts::subscribe_podcast,
+            podcasts::list_podcasts,
+            podcasts::get_podcast_episodes,
+            podcasts::refresh_podcasts,
+            podcasts::unsubscribe_podcast,
+            podcasts::play_podcast_episode,
+            podcasts::download_podcast_episode,
+            podcasts::delete_podcast_download,
+            // Watch party
+            watch_party::host_watch_party,
+            watch_party::join_watch_party,
+            watch_party::leave_watch_party,
+            watch_party::get_watch_party,
+            watch_party::send_watch_party_chat,
+            // Launch arguments
+            launch::take_launch_args,
+            launch::play_launch_args,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|_app, _event| {
+            #[cfg(target_os = "macos")]
+            if let tauri::RunEvent::Opened { urls } = _event {
+                launch::opened(_app, urls);
+            }
+        });
 }
@@ -0,0 +1,122 @@
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tauri::{command, AppHandle, Manager};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{fmt, prelude::*, reload, EnvFilter, Registry};
+
+use crate::config::{load_config, save_config};
+
+pub const LEVELS: &[&str] = &["error", "warn", "info", "debug", "trace"];
+const DEFAULT_LEVEL: &str = "info";
+const RECENT_LINES: usize = 2000;
+const KEEP_DAYS: usize = 7;
+
+struct Logging {
+    filter: reload::Handle<EnvFilter, Registry>,
+}
+
+static LOGGING: OnceLock<Logging> = OnceLock::new();
+static RECENT: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Our own events at `level`; dependencies only from warn up, or they drown everything out.
+fn filter_for(level: &str) -> EnvFilter {
+    EnvFilter::new(format!("warn,{}={}", env!("CARGO_CRATE_NAME"), level))
+}
+
+/// Keeps the last formatted lines in memory for the debug panel.
+struct RecentWriter;
+
+impl Write for RecentWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let text = String::from_utf8_lossy(buf);
+        let mut recent = RECENT.lock().unwrap_or_else(|e| e.into_inner());
+        for line in text.lines().filter(|l| !l.is_empty()) {
+            if recent.len() == RECENT_LINES {
+                recent.pop_front();
+            }
+            recent.push_back(line.to_string());
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+pub fn log_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app.path().app_data_dir().map_err(|e| e.to_string())?.join("logs"))
+}
+
+/// Install the global subscriber: the console, a daily log file in app data (a week kept),
+/// and the in-memory buffer behind `get_recent_logs`.
+pub fn init(app: &AppHandle) {
+    let level = load_config(app).log_level.unwrap_or_else(|| DEFAULT_LEVEL.to_string());
+    let (filter, handle) = reload::Layer::new(filter_for(&level));
+
+    let dir = log_dir(app).unwrap_or_else(|_| PathBuf::from("logs"));
+    let file = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix("framex")
+        .filename_suffix("log")
+        .max_log_files(KEEP_DAYS)
+        .build(&dir);
+    let file_layer = match file {
+        Ok(appender) => Some(fmt::layer().with_ansi(false).with_writer(appender)),
+        Err(e) => {
+            eprintln!("Log files unavailable in {}: {}", dir.display(), e);
+            None
+        }
+    };
+
+    let installed = Registry::default()
+        .with(filter)
+        .with(fmt::layer())
+        .with(file_layer)
+        .with(fmt::layer().with_ansi(false).with_target(false).with_writer(|| RecentWriter))
+        .try_init();
+    if installed.is_ok() {
+        let _ = LOGGING.set(Logging { filter: handle });
+        tracing::info!("📝 Logging at {} level", level);
+    }
+}
+
+#[command]
+pub fn get_log_level(app: AppHandle) -> String {
+    load_config(&app).log_level.unwrap_or_else(|| DEFAULT_LEVEL.to_string())
+}
+
+/// Change the level immediately and remember it for the next start.
+#[command]
+pub fn set_log_level(app: AppHandle, level: String) -> Result<(), String> {
+    crate::parental::check_area(&app, "settings")?;
+    if !LEVELS.contains(&level.as_str()) {
+        return Err(format!("Unknown log level '{}'", level));
+    }
+    if let Some(logging) = LOGGING.get() {
+        logging.filter.reload(filter_for(&level)).map_err(|e| e.to_string())?;
+    }
+    let mut config = load_config(&app);
+    config.log_level = Some(level.clone());
+    save_config(&app, &config)?;
+    tracing::info!("📝 Log level set to {}", level);
+    Ok(())
+}
+
+/// The most recent log lines, oldest first, optionally only those containing `filter`.
+#[command]
+pub fn get_recent_logs(limit: Option<usize>, filter: Option<String>) -> Vec<String> {
+    let recent = RECENT.lock().unwrap_or_else(|e| e.into_inner());
+    let filter = filter.filter(|f| !f.is_empty()).map(|f| f.to_lowercase());
+    let mut lines: Vec<String> = recent
+        .iter()
+        .rev()
+        .filter(|l| filter.as_ref().is_none_or(|f| l.to_lowercase().contains(f)))
+        .take(limit.unwrap_or(500))
+        .cloned()
+        .collect();
+    lines.reverse();
+    lines
+}
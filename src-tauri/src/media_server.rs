@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::net::{IpAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+// A small HTTP server so LAN devices (Chromecast, ...) can stream local files.
+// Only files handed to `share_file` are reachable, each under a random token that is
+// revoked when the cast ends or expires after going unused for a while.
+
+// Long enough for a paused cast to resume
+const SHARE_IDLE_TTL: Duration = Duration::from_secs(12 * 3600);
+// A stalled or idle client gives its thread back after this
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+const WRITE_TIMEOUT: Duration = Duration::from_secs(60);
+const MAX_HEADER_BYTES: u64 = 16 * 1024;
+
+struct Share {
+    path: PathBuf,
+    last_used: Instant,
+}
+
+struct MediaServer {
+    port: u16,
+    files: Mutex<HashMap<String, Share>>, // token -> file
+}
+
+static SERVER: OnceLock<MediaServer> = OnceLock::new();
+
+fn server() -> Result<&'static MediaServer, String> {
+    if let Some(server) = SERVER.get() {
+        return Ok(server);
+    }
+    let listener = TcpListener::bind("0.0.0.0:0").map_err(|e| format!("Failed to start media server: {}", e))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+    let server = SERVER.get_or_init(|| MediaServer { port, files: Mutex::new(HashMap::new()) });
+    if server.port == port {
+        tracing::info!("📡 Media server listening on port {}", port);
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                std::thread::spawn(move || {
+                    if let Err(e) = handle(stream) {
+                        tracing::error!("Media server request failed: {}", e);
+                    }
+                });
+            }
+        });
+    }
+    Ok(server)
+}
+
+/// URL a device on the LAN can fetch `path` from; `host` is this machine's address on
+/// the device's network (see `local_ip_for`). Each call gets its own token, so revoking
+/// one share doesn't cut off another of the same file.
+pub fn share_file(path: &Path, host: IpAddr) -> Result<String, String> {
+    if !path.is_file() {
+        return Err(format!("File not found: {}", path.display()));
+    }
+    let server = server()?;
+    let mut files = server.files.lock().map_err(|e| e.to_string())?;
+    files.retain(|_, share| share.last_used.elapsed() < SHARE_IDLE_TTL);
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    files.insert(token.clone(), Share { path: path.to_path_buf(), last_used: Instant::now() });
+    // The file name is cosmetic; some receivers pick the format from it
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("media");
+    let host = match host {
+        IpAddr::V6(v6) => format!("[{}]", v6),
+        IpAddr::V4(v4) => v4.to_string(),
+    };
+    Ok(format!("http://{}:{}/media/{}/{}", host, server.port, token, urlencoding::encode(name)))
+}
+
+/// Stop serving a URL from `share_file`.
+pub fn revoke_share(url: &str) {
+    let path = url.split_once("://").map_or(url, |(_, rest)| rest.find('/').map_or("", |i| &rest[i..]));
+    if let (Some(server), Some(token)) = (SERVER.get(), token_of(path)) {
+        if let Ok(mut files) = server.files.lock() {
+            files.remove(token);
+        }
+    }
+}
+
+fn token_of(target: &str) -> Option<&str> {
+    target.strip_prefix("/media/")?.split('/').next()
+}
+
+/// The shared file for a token, if it's still valid; using it keeps it alive.
+fn shared_path(token: &str) -> Option<PathBuf> {
+    let mut files = SERVER.get()?.files.lock().ok()?;
+    let share = files.get_mut(token)?;
+    if share.last_used.elapsed() >= SHARE_IDLE_TTL {
+        files.remove(token);
+        return None;
+    }
+    share.last_used = Instant::now();
+    Some(share.path.clone())
+}
+
+/// This machine's address on the route to `device`.
+pub fn local_ip_for(device: IpAddr) -> Result<IpAddr, String> {
+    let socket = std::net::UdpSocket::bind(if device.is_ipv6() { "[::]:0" } else { "0.0.0.0:0" }).map_err(|e| e.to_string())?;
+    // UDP connect only picks a route; nothing is sent
+    socket.connect((device, 9)).map_err(|e| format!("No route to {}: {}", device, e))?;
+    Ok(socket.local_addr().map_err(|e| e.to_string())?.ip())
+}
+
+pub fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase().as_str() {
+        "mp4" | "m4v" => "video/mp4",
+        "mkv" => "video/x-matroska",
+        "webm" => "video/webm",
+        "mov" => "video/quicktime",
+        "avi" => "video/x-msvideo",
+        "ts" | "m2ts" => "video/mp2t",
+        "mp3" => "audio/mpeg",
+        "m4a" | "aac" => "audio/mp4",
+        "flac" => "audio/flac",
+        "ogg" | "opus" => "audio/ogg",
+        "wav" => "audio/wav",
+        "vtt" => "text/vtt",
+        "srt" => "application/x-subrip",
+        _ => "application/octet-stream",
+    }
+}
+
+/// `bytes=a-b`, `bytes=a-` or `bytes=-n` as an inclusive range within `size`.
+pub(crate) fn parse_range(header: &str, size: u64) -> Option<(u64, u64)> {
+    let spec = header.trim().strip_prefix("bytes=")?.split(',').next()?.trim();
+    let (start, end) = spec.split_once('-')?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix) => {
+            let n: u64 = suffix.parse().ok()?;
+            (size.saturating_sub(n), size.checked_sub(1)?)
+        }
+        (start, "") => (start.parse().ok()?, size.checked_sub(1)?),
+        (start, end) => (start.parse().ok()?, end.parse::<u64>().ok()?.min(size.checked_sub(1)?)),
+    };
+    (start <= end && start < size).then_some((start, end))
+}
+
+fn handle(stream: TcpStream) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?.take(MAX_HEADER_BYTES));
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut range = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("range") {
+                range = Some(value.trim().to_string());
+            }
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let target = parts.next().unwrap_or_default();
+    let mut out = stream;
+    if method != "GET" && method != "HEAD" {
+        return write!(out, "HTTP/1.1 405 Method Not Allowed\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+    }
+    let Some(path) = token_of(target).and_then(shared_path) else {
+        return write!(out, "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+    };
+
+    let mut file = File::open(&path)?;
+    let size = file.metadata()?.len();
+    let common = format!(
+        "Content-Type: {}\r\nAccept-Ranges: bytes\r\nAccess-Control-Allow-Origin: *\r\nConnection: close\r\n",
+        content_type(&path)
+    );
+    let (start, end) = match range.as_deref() {
+        Some(header) => match parse_range(header, size) {
+            Some(r) => {
+                write!(out, "HTTP/1.1 206 Partial Content\r\n{}Content-Range: bytes {}-{}/{}\r\nContent-Length: {}\r\n\r\n", common, r.0, r.1, size, r.1 - r.0 + 1)?;
+                r
+            }
+            None => {
+                return write!(out, "HTTP/1.1 416 Range Not Satisfiable\r\nContent-Range: bytes */{}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", size);
+            }
+        },
+        None => {
+            write!(out, "HTTP/1.1 200 OK\r\n{}Content-Length: {}\r\n\r\n", common, size)?;
+            (0, size.saturating_sub(1))
+        }
+    };
+    if method == "HEAD" || size == 0 {
+        return Ok(());
+    }
+    file.seek(SeekFrom::Start(start))?;
+    // Receivers drop connections mid-file all the time when seeking, and stop reading
+    // while paused with a full buffer (the write timeout ends those; they come back with a range)
+    match std::io::copy(&mut file.take(end - start + 1), &mut out) {
+        Err(e) if matches!(
+            e.kind(),
+            std::io::ErrorKind::BrokenPipe | std::io::ErrorKind::ConnectionReset | std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock
+        ) => Ok(()),
+        other => other.map(|_| ()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_range;
+
+    #[test]
+    fn ranges() {
+        let cases = [
+            ("bytes=0-99", 1000, Some((0, 99))),
+            ("bytes=500-", 1000, Some((500, 999))),
+            ("bytes=900-5000", 1000, Some((900, 999))),
+            (" bytes=0-0 ", 1000, Some((0, 0))),
+            // Suffix ranges: the last n bytes
+            ("bytes=-200", 1000, Some((800, 999))),
+            ("bytes=-2000", 1000, Some((0, 999))),
+            ("bytes=-0", 1000, None),
+            // Nothing can be served from an empty file
+            ("bytes=0-", 0, None),
+            ("bytes=-10", 0, None),
+            ("bytes=0-10", 0, None),
+            // Backwards or past the end
+            ("bytes=50-10", 1000, None),
+            ("bytes=1000-", 1000, None),
+            // Multi-range requests get the first range
+            ("bytes=0-9,20-29", 1000, Some((0, 9))),
+            ("bytes=-5, 0-1", 1000, Some((995, 999))),
+            // Not a byte range
+            ("items=0-9", 1000, None),
+            ("bytes=abc-", 1000, None),
+            ("bytes=10", 1000, None),
+        ];
+        for (header, size, expected) in cases {
+            assert_eq!(parse_range(header, size), expected, "{:?} of {}", header, size);
+        }
+    }
+}
@@ -1,13 +1,206 @@
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::io::Write; 
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
 use tauri::AppHandle;
-use tauri::Manager;
+use tauri::{Emitter, Manager};
 use regex::Regex;
 use base64::{Engine as _, engine::general_purpose};
 
-// TMDB API Key
-const TMDB_API_KEY: &str = "d47c8f61c0cacd4e41aeadb58ffa938e";
+// Optional built-in TMDB key, injected at build time (never committed to source)
+const BUILTIN_TMDB_API_KEY: Option<&str> = option_env!("FRAMEX_TMDB_API_KEY");
+
+const TMDB_BASE_URL: &str = "https://api.themoviedb.org/3";
+
+// How long cached TMDB responses are trusted before refetching
+const SEARCH_CACHE_TTL: u64 = 3 * 24 * 60 * 60;
+const DETAIL_CACHE_TTL: u64 = 14 * 24 * 60 * 60;
+// A file's hash never changes, so its OpenSubtitles match is kept much longer
+const HASH_MATCH_CACHE_TTL: u64 = 90 * 24 * 60 * 60;
+// Hash matches whose TMDB runtime is off by more than this are treated as collisions
+const MAX_RUNTIME_DRIFT: f64 = 0.25;
+
+// TMDB allows roughly 40 requests/second; stay well under it when scanning a library
+const MAX_CONCURRENT_REQUESTS: usize = 8;
+const MAX_RETRIES: u32 = 4;
+const BASE_BACKOFF_MS: u64 = 500;
+// Items identified at once by identify_folder; each may issue several requests
+const IDENTIFY_CONCURRENCY: usize = 4;
+
+// Error prefixes the frontend can match on to explain what went wrong
+pub const ERR_TMDB_NO_KEY: &str = "TMDB_NO_KEY";
+pub const ERR_TMDB_AUTH: &str = "TMDB_AUTH";
+pub const ERR_TMDB_QUOTA: &str = "TMDB_QUOTA";
+
+/// Configured TMDB language followed by its fallbacks, without duplicates.
+fn metadata_languages(app: &AppHandle) -> Vec<String> {
+    let settings = crate::config::load_config(app).metadata;
+    let mut languages: Vec<String> = Vec::new();
+    for lang in std::iter::once(settings.language).chain(settings.fallback_languages) {
+        let lang = lang.trim().to_string();
+        if !lang.is_empty() && !languages.contains(&lang) {
+            languages.push(lang);
+        }
+    }
+    if languages.is_empty() {
+        languages.push("en-US".to_string());
+    }
+    languages
+}
+
+fn metadata_language(app: &AppHandle) -> String {
+    metadata_languages(app).swap_remove(0)
+}
+
+/// ISO 639-1 part of a TMDB locale ("pt-BR" -> "pt"), as used to tag images.
+fn image_language(lang: &str) -> &str {
+    lang.split('-').next().unwrap_or(lang)
+}
+
+fn has_text(value: &Option<String>) -> bool {
+    value.as_deref().is_some_and(|v| !v.trim().is_empty())
+}
+
+/// Resolve the TMDB key: user-supplied key from settings first, then the built-in default.
+fn tmdb_api_key(app: &AppHandle) -> Result<String, String> {
+    crate::config::load_config(app)
+        .credentials
+        .tmdb_api_key
+        .filter(|k| !k.is_empty())
+        .or_else(|| BUILTIN_TMDB_API_KEY.map(|k| k.to_string()))
+        .ok_or_else(|| format!("{}: No TMDB API key configured. Add one in Settings → Integrations.", ERR_TMDB_NO_KEY))
+}
+
+/// Map a failed TMDB response status to a user-facing error, keeping auth/quota errors distinct.
+fn tmdb_status_error(context: &str, status: reqwest::StatusCode) -> String {
+    match status.as_u16() {
+        401 => format!("{}: TMDB rejected the API key. Check it in Settings → Integrations.", ERR_TMDB_AUTH),
+        429 => format!("{}: TMDB rate limit reached. Try again in a moment.", ERR_TMDB_QUOTA),
+        _ => format!("{}: {}", context, status),
+    }
+}
+
+/// One client for all metadata requests so connections (and TLS sessions) are reused.
+pub fn http_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .user_agent("FrameX v0.1.0")
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap_or_default()
+    })
+}
+
+fn request_slots() -> &'static tokio::sync::Semaphore {
+    static SLOTS: OnceLock<tokio::sync::Semaphore> = OnceLock::new();
+    SLOTS.get_or_init(|| tokio::sync::Semaphore::new(MAX_CONCURRENT_REQUESTS))
+}
+
+/// Send a request, retrying 429/5xx and connection failures with exponential backoff
+/// (or the server's Retry-After, when given).
+pub async fn send_with_retry(request: reqwest::RequestBuilder) -> Result<reqwest::Response, reqwest::Error> {
+    let mut attempt = 0;
+    loop {
+        // Streaming bodies can't be cloned; send those once
+        let Some(this_try) = request.try_clone() else {
+            return request.send().await;
+        };
+        let permit = request_slots().acquire().await.ok();
+        let result = this_try.send().await;
+        drop(permit);
+
+        let retryable = match &result {
+            Ok(resp) => resp.status().as_u16() == 429 || resp.status().is_server_error(),
+            Err(e) => e.is_timeout() || e.is_connect(),
+        };
+        if !retryable || attempt >= MAX_RETRIES {
+            return result;
+        }
+
+        let retry_after = result.as_ref().ok()
+            .and_then(|r| r.headers().get(reqwest::header::RETRY_AFTER))
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok());
+        let delay = match retry_after {
+            Some(secs) => Duration::from_secs(secs.min(30)),
+            None => Duration::from_millis(BASE_BACKOFF_MS << attempt),
+        };
+        tracing::info!("⏳ Request throttled or failed, retrying in {:?} (attempt {}/{})", delay, attempt + 1, MAX_RETRIES);
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// Per-key lock so identical concurrent lookups hit TMDB once and the rest read the cache.
+fn inflight_lock(key: &str) -> Arc<tokio::sync::Mutex<()>> {
+    static INFLIGHT: OnceLock<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>> = OnceLock::new();
+    let mut map = INFLIGHT.get_or_init(|| Mutex::new(HashMap::new())).lock().unwrap();
+    // Forget locks nobody is waiting on
+    map.retain(|_, lock| Arc::strong_count(lock) > 1);
+    Arc::clone(map.entry(key.to_string()).or_default())
+}
+
+/// GET a TMDB endpoint through the metadata cache.
+/// Fresh entries skip the network; stale ones are served when TMDB is unreachable.
+async fn tmdb_get<T: DeserializeOwned>(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    endpoint: &str,
+    params: &[(&str, &str)],
+    ttl: u64,
+) -> Result<T, String> {
+    let cache = app.state::<crate::metadata_cache::MetadataCache>();
+    let query: Vec<String> = params.iter().map(|(k, v)| format!("{}={}", k, urlencoding::encode(v))).collect();
+    let key = format!("{}?{}", endpoint, query.join("&"));
+    let parse = |body: serde_json::Value| serde_json::from_value::<T>(body).map_err(|e| format!("Failed to parse TMDB response: {}", e));
+
+    if let Some(body) = cache.get(app, &key, ttl) {
+        return parse(body);
+    }
+
+    // Wait for an identical in-flight request, then re-check the cache it filled
+    let lock = inflight_lock(&key);
+    let _inflight = lock.lock().await;
+    if let Some(body) = cache.get(app, &key, ttl) {
+        return parse(body);
+    }
+
+    let offline_fallback = |err: String| match cache.get_stale(app, &key) {
+        Some(body) => {
+            tracing::warn!("⚠️  {} - using cached response for {}", err, endpoint);
+            parse(body)
+        }
+        None => Err(err),
+    };
+
+    let api_key = match tmdb_api_key(app) {
+        Ok(k) => k,
+        Err(e) => return offline_fallback(e),
+    };
+    let url = format!("{}/{}?api_key={}&{}", TMDB_BASE_URL, endpoint, api_key, query.join("&"));
+
+    let resp = match send_with_retry(client.get(&url)).await {
+        Ok(r) => r,
+        Err(e) => return offline_fallback(format!("TMDB request failed: {}", e)),
+    };
+    if !resp.status().is_success() {
+        let err = tmdb_status_error("TMDB API Error", resp.status());
+        // Auth problems need fixing by the user; anything else can fall back
+        if resp.status().as_u16() == 401 {
+            return Err(err);
+        }
+        return offline_fallback(err);
+    }
+
+    let body: serde_json::Value = resp.json().await.map_err(|e| format!("Failed to parse TMDB response: {}", e))?;
+    cache.insert(app, &key, body.clone());
+    parse(body)
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 struct TmdbSearchResult {
@@ -63,11 +256,14 @@ struct TmdbMovieDetail {
     #[serde(rename = "budget")]
     budget: Option<u64>,
     revenue: Option<u64>,
+    imdb_id: Option<String>,
+    belongs_to_collection: Option<TmdbCollectionRef>,
 }
 
 /// The struct returned to the frontend with all movie info
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MovieInfo {
+    pub tmdb_id: u64,
     pub title: String,
     pub overview: String,
     pub release_date: String,
@@ -80,6 +276,425 @@ pub struct MovieInfo {
     pub status: String,
     pub budget: u64,
     pub revenue: u64,
+    pub imdb_id: Option<String>,
+    // From OMDb, when the user has configured a key
+    pub imdb_rating: Option<f64>,
+    pub rotten_tomatoes: Option<u32>, // percent
+    pub metacritic: Option<u32>,      // out of 100
+    // TMDB franchise this movie belongs to, mirrored as a FrameX collection
+    pub collection: Option<String>,
+    pub certification: Option<Certification>,
+}
+
+// --- Hash Identification ---
+
+/// Exact TMDB movie ID for a file via its OpenSubtitles moviehash, cross-checked against
+/// the file's duration. None means "fall back to the title search".
+async fn hash_match_movie(app: &AppHandle, client: &reqwest::Client, video_path: &str, lang: &str) -> Option<u64> {
+    let api_key = crate::config::load_config(app).credentials.opensubtitles_api_key?;
+    let hash = crate::subtitle_downloader::compute_moviehash(std::path::Path::new(video_path)).ok()?;
+    let cache = app.state::<crate::metadata_cache::MetadataCache>();
+    let key = format!("oshash:{}", hash);
+
+    // Misses are cached too (as null) so unknown files don't hit the API every time
+    let feature: Option<crate::subtitle_downloader::HashFeature> = match cache.get(app, &key, HASH_MATCH_CACHE_TTL) {
+        Some(body) => serde_json::from_value(body).ok()?,
+        None => {
+            let feature = match crate::subtitle_downloader::identify_by_hash(&hash, &api_key).await {
+                Ok(f) => f,
+                Err(e) => {
+                    tracing::error!("Hash lookup failed for '{}': {}", video_path, e);
+                    return None;
+                }
+            };
+            cache.insert(app, &key, serde_json::to_value(&feature).ok()?);
+            feature
+        }
+    };
+    let feature = feature.filter(|f| f.feature_type == "Movie")?;
+    let tmdb_id = feature.tmdb_id?;
+
+    let path = video_path.to_string();
+    let duration = tauri::async_runtime::spawn_blocking(move || crate::file_scanner::get_video_duration(path))
+        .await
+        .ok()
+        .and_then(Result::ok)
+        .unwrap_or(0.0);
+    if duration > 0.0 {
+        let detail: TmdbMovieDetail = tmdb_get(app, client, &format!("movie/{}", tmdb_id), &[("language", lang)], DETAIL_CACHE_TTL).await.ok()?;
+        if let Some(runtime) = detail.runtime.filter(|r| *r > 0) {
+            let expected = runtime as f64 * 60.0;
+            if (duration - expected).abs() / expected > MAX_RUNTIME_DRIFT {
+                tracing::warn!("⚠️  Hash match '{}' runs {}m but the file is {:.0}m, ignoring", detail.title, runtime, duration / 60.0);
+                return None;
+            }
+        }
+    }
+
+    tracing::info!("🎯 Identified '{}' by file hash as TMDB movie {}", video_path, tmdb_id);
+    Some(tmdb_id)
+}
+
+// --- Content Ratings ---
+
+/// Age rating such as "PG-13" or "TV-MA", and the country whose rating board issued it.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Certification {
+    pub rating: String,
+    pub country: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TmdbReleaseDates {
+    results: Vec<TmdbCountryReleases>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TmdbCountryReleases {
+    iso_3166_1: String,
+    release_dates: Vec<TmdbReleaseDate>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TmdbReleaseDate {
+    certification: String,
+    #[serde(rename = "type")]
+    release_type: u8,
+}
+
+#[derive(Debug, Deserialize)]
+struct TmdbContentRatings {
+    results: Vec<TmdbContentRating>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TmdbContentRating {
+    iso_3166_1: String,
+    rating: String,
+}
+
+/// Countries to take a rating from: the regions of the metadata languages, then the US.
+fn certification_countries(languages: &[String]) -> Vec<String> {
+    let mut countries: Vec<String> = Vec::new();
+    for country in languages.iter().filter_map(|l| l.split_once('-')).map(|(_, region)| region.to_uppercase()).chain(["US".to_string()]) {
+        if !countries.contains(&country) {
+            countries.push(country);
+        }
+    }
+    countries
+}
+
+async fn fetch_movie_certification(app: &AppHandle, client: &reqwest::Client, movie_id: u64, languages: &[String]) -> Option<Certification> {
+    let dates: TmdbReleaseDates = tmdb_get(app, client, &format!("movie/{}/release_dates", movie_id), &[], DETAIL_CACHE_TTL).await.ok()?;
+    certification_countries(languages).into_iter().find_map(|country| {
+        let releases = dates.results.iter().find(|r| r.iso_3166_1 == country)?;
+        // Prefer the theatrical rating (type 3), then any other release that carries one
+        let rating = releases.release_dates.iter()
+            .filter(|d| !d.certification.trim().is_empty())
+            .min_by_key(|d| (d.release_type != 3, d.release_type))?;
+        Some(Certification { rating: rating.certification.trim().to_string(), country })
+    })
+}
+
+async fn fetch_tv_certification(app: &AppHandle, client: &reqwest::Client, tv_id: u64, languages: &[String]) -> Option<Certification> {
+    let ratings: TmdbContentRatings = tmdb_get(app, client, &format!("tv/{}/content_ratings", tv_id), &[], DETAIL_CACHE_TTL).await.ok()?;
+    certification_countries(languages).into_iter().find_map(|country| {
+        let rating = ratings.results.iter().find(|r| r.iso_3166_1 == country && !r.rating.trim().is_empty())?;
+        Some(Certification { rating: rating.rating.trim().to_string(), country })
+    })
+}
+
+// --- Franchise Collections ---
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TmdbCollectionRef {
+    id: u64,
+    name: String,
+    poster_path: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TmdbCollectionDetail {
+    overview: Option<String>,
+}
+
+/// Collection poster, cached once in `artwork/collection_<id>/poster.jpg`.
+async fn collection_poster(app: &AppHandle, client: &reqwest::Client, franchise: &TmdbCollectionRef) -> Result<Option<String>, String> {
+    let Some(poster_rel) = &franchise.poster_path else {
+        return Ok(None);
+    };
+    let dest = artwork_dir(app, "collection", franchise.id)?.join("poster.jpg");
+    if !dest.exists() {
+        let img_bytes = send_with_retry(client.get(format!("https://image.tmdb.org/t/p/w500{}", poster_rel)))
+            .await
+            .map_err(|e| format!("Failed to download collection poster: {}", e))?
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to read collection poster bytes: {}", e))?;
+        fs::write(&dest, &img_bytes).map_err(|e| format!("Failed to save collection poster: {}", e))?;
+    }
+    Ok(Some(dest.to_string_lossy().to_string()))
+}
+
+/// Add the video to the FrameX collection for its TMDB franchise, creating it if needed.
+async fn sync_franchise_collection(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    franchise: &TmdbCollectionRef,
+    video_path: &str,
+    lang: &str,
+) -> Result<(), String> {
+    let overview = tmdb_get::<TmdbCollectionDetail>(app, client, &format!("collection/{}", franchise.id), &[("language", lang)], DETAIL_CACHE_TTL)
+        .await
+        .ok()
+        .and_then(|d| d.overview)
+        .unwrap_or_default();
+    let poster = collection_poster(app, client, franchise).await.unwrap_or_else(|e| {
+        tracing::warn!("{}", e);
+        None
+    });
+    let collection = crate::playlist::add_to_tmdb_collection(app, franchise.id, &franchise.name, &overview, video_path, poster)?;
+    tracing::info!("📚 '{}' is in collection '{}' ({} items)", video_path, collection.name, collection.items.len());
+    Ok(())
+}
+
+// --- OMDb Ratings ---
+
+#[derive(Debug, Default)]
+struct ExternalRatings {
+    imdb: Option<f64>,
+    rotten_tomatoes: Option<u32>,
+    metacritic: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OmdbResponse {
+    #[serde(rename = "Response")]
+    response: String,
+    #[serde(rename = "imdbRating")]
+    imdb_rating: Option<String>,
+    #[serde(rename = "Ratings", default)]
+    ratings: Vec<OmdbRating>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OmdbRating {
+    #[serde(rename = "Source")]
+    source: String,
+    #[serde(rename = "Value")]
+    value: String,
+}
+
+/// IMDb / Rotten Tomatoes / Metacritic scores from OMDb. Optional: no key, no ratings.
+async fn fetch_omdb_ratings(app: &AppHandle, client: &reqwest::Client, imdb_id: &str) -> ExternalRatings {
+    let Some(api_key) = crate::config::load_config(app).credentials.omdb_api_key else {
+        return ExternalRatings::default();
+    };
+    let cache = app.state::<crate::metadata_cache::MetadataCache>();
+    let key = format!("omdb:{}", imdb_id);
+
+    let body = match cache.get(app, &key, DETAIL_CACHE_TTL) {
+        Some(body) => Some(body),
+        None => {
+            let url = format!("https://www.omdbapi.com/?apikey={}&i={}", api_key, urlencoding::encode(imdb_id));
+            match send_with_retry(client.get(&url)).await {
+                Ok(resp) if resp.status().is_success() => match resp.json::<serde_json::Value>().await {
+                    Ok(body) => {
+                        cache.insert(app, &key, body.clone());
+                        Some(body)
+                    }
+                    Err(e) => {
+                        tracing::warn!("⚠️  OMDb parse error: {}", e);
+                        None
+                    }
+                },
+                Ok(resp) => {
+                    tracing::warn!("⚠️  OMDb API Error: {}", resp.status());
+                    cache.get_stale(app, &key)
+                }
+                Err(e) => {
+                    tracing::warn!("⚠️  OMDb request failed: {}", e);
+                    cache.get_stale(app, &key)
+                }
+            }
+        }
+    };
+
+    let Some(omdb) = body.and_then(|b| serde_json::from_value::<OmdbResponse>(b).ok()).filter(|o| o.response == "True") else {
+        return ExternalRatings::default();
+    };
+    // Values look like "7.5/10", "87%", "70/100"; "N/A" when missing
+    let leading_number = |v: &str| v.split(['/', '%']).next().and_then(|n| n.trim().parse::<f64>().ok());
+    let source = |name: &str| omdb.ratings.iter().find(|r| r.source == name).and_then(|r| leading_number(&r.value));
+
+    ExternalRatings {
+        imdb: omdb.imdb_rating.as_deref().and_then(leading_number).or_else(|| source("Internet Movie Database")),
+        rotten_tomatoes: source("Rotten Tomatoes").map(|v| v as u32),
+        metacritic: source("Metacritic").map(|v| v as u32),
+    }
+}
+
+// --- Manual Matching ---
+
+/// A user-chosen TMDB entry for a file or folder, overriding automatic first-result matching.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MetadataMatch {
+    pub tmdb_id: u64,
+    pub media_type: String, // "movie" | "tv"
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct MetadataMatchData {
+    matches: HashMap<String, MetadataMatch>,
+    #[serde(default)]
+    providers: HashMap<String, String>, // folder -> "tmdb" | "anilist"
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MetadataCandidate {
+    pub tmdb_id: u64,
+    pub media_type: String,
+    pub title: String,
+    pub year: Option<String>,
+    pub poster_url: Option<String>,
+    pub overview: String,
+    pub vote_average: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TmdbCandidateSearch {
+    results: Vec<TmdbCandidate>,
+}
+
+// Movie and TV results share a shape apart from title/date field names
+#[derive(Debug, Deserialize)]
+struct TmdbCandidate {
+    id: u64,
+    title: Option<String>,
+    name: Option<String>,
+    release_date: Option<String>,
+    first_air_date: Option<String>,
+    poster_path: Option<String>,
+    overview: Option<String>,
+    vote_average: Option<f64>,
+}
+
+fn get_matches_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_data_dir().ok().map(|p| p.join("metadata_matches.json"))
+}
+
+fn load_matches(app: &AppHandle) -> MetadataMatchData {
+    let Some(path) = get_matches_path(app) else {
+        return MetadataMatchData::default();
+    };
+    let content = fs::read_to_string(&path).unwrap_or_default();
+    serde_json::from_str(&content).unwrap_or_default()
+}
+
+fn save_matches(app: &AppHandle, data: &MetadataMatchData) -> Result<(), String> {
+    let path = get_matches_path(app).ok_or("Failed to get matches path")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(data).map_err(|e| e.to_string())?;
+    fs::write(path, json).map_err(|e| e.to_string())
+}
+
+/// The pinned match for a path, if it is of the requested media type.
+fn pinned_match(app: &AppHandle, path: &str, media_type: &str) -> Option<u64> {
+    load_matches(app)
+        .matches
+        .get(path)
+        .filter(|m| m.media_type == media_type)
+        .map(|m| m.tmdb_id)
+}
+
+fn validate_media_type(media_type: &str) -> Result<&'static str, String> {
+    match media_type {
+        "movie" => Ok("movie"),
+        "tv" => Ok("tv"),
+        other => Err(format!("Unsupported media type '{}'", other)),
+    }
+}
+
+/// Top TMDB results for a query so the user can pick the right one.
+#[tauri::command]
+pub async fn search_metadata_candidates(
+    app: AppHandle,
+    query: String,
+    media_type: String,
+    limit: Option<usize>,
+) -> Result<Vec<MetadataCandidate>, String> {
+    let media_type = validate_media_type(&media_type)?;
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let client = http_client();
+    let lang = metadata_language(&app);
+    let params = [("query", query), ("language", lang.as_str()), ("page", "1")];
+    let search: TmdbCandidateSearch = tmdb_get(&app, client, &format!("search/{}", media_type), &params, SEARCH_CACHE_TTL).await?;
+
+    Ok(search.results.into_iter().take(limit.unwrap_or(10)).map(|r| {
+        let date = r.release_date.or(r.first_air_date).filter(|d| d.len() >= 4);
+        MetadataCandidate {
+            tmdb_id: r.id,
+            media_type: media_type.to_string(),
+            title: r.title.or(r.name).unwrap_or_default(),
+            year: date.map(|d| d[..4].to_string()),
+            poster_url: r.poster_path.map(|p| format!("https://image.tmdb.org/t/p/w342{}", p)),
+            overview: r.overview.unwrap_or_default(),
+            vote_average: r.vote_average.unwrap_or(0.0),
+        }
+    }).collect())
+}
+
+/// Pin a TMDB entry to a file or folder and replace its poster with the chosen one.
+#[tauri::command]
+pub async fn apply_metadata_match(
+    app: AppHandle,
+    path: String,
+    tmdb_id: u64,
+    media_type: String,
+) -> Result<Option<String>, String> {
+    let media_type = validate_media_type(&media_type)?;
+    let target = std::path::Path::new(&path);
+    if !target.exists() {
+        return Err("Path does not exist".to_string());
+    }
+
+    // Resolve the entry first so a bad ID is rejected before anything is pinned
+    let client = http_client();
+    let lang = metadata_language(&app);
+    let entry: TmdbCandidate = tmdb_get(&app, client, &format!("{}/{}", media_type, tmdb_id), &[("language", lang.as_str())], DETAIL_CACHE_TTL).await?;
+
+    let mut data = load_matches(&app);
+    data.matches.insert(path.clone(), MetadataMatch { tmdb_id, media_type: media_type.to_string() });
+    save_matches(&app, &data)?;
+    tracing::info!("📌 Pinned '{}' to TMDB {} {}", path, media_type, tmdb_id);
+
+    let Some(poster_rel) = entry.poster_path else {
+        return Ok(None);
+    };
+    let img_bytes = send_with_retry(client.get(format!("https://image.tmdb.org/t/p/w500{}", poster_rel)))
+        .await
+        .map_err(|e| format!("Failed to download poster: {}", e))?
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read poster bytes: {}", e))?;
+
+    // Only now that the new one is here: drop the posters FrameX wrote (never the user's own)
+    crate::poster_cache::remove_posters(&app, target);
+    let poster_path = crate::poster_cache::poster_destination(&app, target)?;
+    fs::write(&poster_path, &img_bytes).map_err(|e| format!("Failed to save poster: {}", e))?;
+
+    Ok(Some(poster_path.to_string_lossy().to_string()))
+}
+
+#[tauri::command]
+pub fn get_metadata_match(app: AppHandle, path: String) -> Option<MetadataMatch> {
+    load_matches(&app).matches.get(&path).cloned()
 }
 
 #[tauri::command]
@@ -89,68 +704,82 @@ pub async fn fetch_metadata(app: AppHandle, video_path: String) -> Result<Option
     let video_name = path.file_name()
         .and_then(|n| n.to_str())
         .ok_or("Invalid video path")?;
-    let video_dir = path.parent()
+    path.parent()
         .ok_or("Could not get video directory")?;
     
     // 2. Clean the video name
-    let clean_name = clean_video_name(video_name);
-    println!("🎬 Fetching poster for: '{}' (cleaned: '{}')", video_name, clean_name);
+    let parsed = parse_video_name(video_name);
+    let clean_name = parsed.title.clone();
+    tracing::info!("🎬 Fetching poster for: '{}' (cleaned: '{}')", video_name, clean_name);
 
     if clean_name.trim().is_empty() {
-        println!("⚠️  Cleaned name is empty, skipping");
+        tracing::warn!("⚠️  Cleaned name is empty, skipping");
         return Ok(None);
     }
 
-    // 3. Check if poster already exists
-    let poster_filename = format!("{}.poster.jpg", 
-        path.file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("poster")
-    );
-    let poster_path = video_dir.join(&poster_filename);
-    
-    if poster_path.exists() {
-        println!("✅ Poster already exists: {}", poster_path.display());
-        return Ok(Some(poster_path.to_string_lossy().to_string()));
+    // 3. Check if poster already exists (beside the video or in the central cache)
+    if let Some(existing) = crate::poster_cache::find_poster(&app, path) {
+        tracing::info!("✅ Poster already exists: {}", existing.display());
+        return Ok(Some(existing.to_string_lossy().to_string()));
     }
+    let poster_path = crate::poster_cache::poster_destination(&app, path)?;
 
-    // 4. Search TMDB API
-    let client = reqwest::Client::new();
-    let encoded_name = urlencoding::encode(&clean_name);
-    let url = format!(
-        "https://api.themoviedb.org/3/search/movie?api_key={}&query={}&language=en-US&page=1",
-        TMDB_API_KEY, encoded_name
-    );
-    
-    println!("🔍 Searching TMDB API: {}", url);
-
-    let resp = client.get(&url)
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+    // 4. Search TMDB API (posters are optional, so a missing key is not an error)
+    let client = http_client();
+    let lang = metadata_language(&app);
 
-    if !resp.status().is_success() {
-        println!("❌ API Error: {}", resp.status());
-        return Err(format!("API Error: {}", resp.status()));
+    // A pinned match or an exact hash match skips the search entirely
+    let exact_id = match pinned_match(&app, &video_path, "movie") {
+        Some(id) => Some(id),
+        None => hash_match_movie(&app, client, &video_path, &lang).await,
+    };
+    if let Some(movie_id) = exact_id {
+        let entry: TmdbCandidate = match tmdb_get(&app, client, &format!("movie/{}", movie_id), &[("language", lang.as_str())], DETAIL_CACHE_TTL).await {
+            Ok(e) => e,
+            Err(e) if e.starts_with(ERR_TMDB_NO_KEY) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let Some(poster_rel) = entry.poster_path else {
+            return Ok(None);
+        };
+        let img_bytes = send_with_retry(client.get(format!("https://image.tmdb.org/t/p/original{}", poster_rel)))
+            .await
+            .map_err(|e| format!("Image download failed: {}", e))?
+            .bytes()
+            .await
+            .map_err(|e| format!("Image bytes error: {}", e))?;
+        fs::write(&poster_path, &img_bytes).map_err(|e| e.to_string())?;
+        return Ok(Some(poster_path.to_string_lossy().to_string()));
     }
 
-    let result: TmdbSearchResult = resp.json().await.map_err(|e| format!("JSON Parse error: {}", e))?;
+    tracing::info!("🔍 Searching TMDB API for '{}'", clean_name);
+
+    let result: TmdbSearchResult = match search_by_name(&app, client, "search/movie", &parsed, &lang, |r: &TmdbSearchResult| !r.results.is_empty()).await {
+        Ok(r) => r,
+        Err(e) if e.starts_with(ERR_TMDB_NO_KEY) => {
+            tracing::warn!("⚠️  {}", e);
+            return Ok(None);
+        }
+        Err(e) => {
+            tracing::warn!("❌ {}", e);
+            return Err(e);
+        }
+    };
     
-    println!("📊 TMDB returned {} results", result.results.len());
+    tracing::info!("📊 TMDB returned {} results", result.results.len());
 
     if let Some(movie) = result.results.first() {
         if let Some(poster_path_tmdb) = &movie.poster_path {
             // 5. Construct high-res poster URL
             let poster_url = format!("https://image.tmdb.org/t/p/original{}", poster_path_tmdb);
-            println!("✅ Found movie: '{}' ({}) with poster URL: {}", 
+            tracing::info!("✅ Found movie: '{}' ({}) with poster URL: {}", 
                 movie.title, 
                 movie.release_date.as_deref().unwrap_or("Unknown"), 
                 poster_url
             );
 
             // 6. Download Image
-            let img_bytes = client.get(&poster_url)
-                .send()
+            let img_bytes = send_with_retry(client.get(&poster_url))
                 .await
                 .map_err(|e| format!("Image download failed: {}", e))?
                 .bytes()
@@ -161,71 +790,142 @@ pub async fn fetch_metadata(app: AppHandle, video_path: String) -> Result<Option
             let mut file = fs::File::create(&poster_path).map_err(|e| e.to_string())?;
             file.write_all(&img_bytes).map_err(|e| e.to_string())?;
             
-            println!("💾 Saved poster to: {}", poster_path.display());
+            tracing::info!("💾 Saved poster to: {}", poster_path.display());
 
             return Ok(Some(poster_path.to_string_lossy().to_string()));
         } else {
-            println!("⚠️  Movie found but no poster available");
+            tracing::warn!("⚠️  Movie found but no poster available");
         }
     }
 
-    println!("❌ No results found for '{}'", clean_name);
+    tracing::warn!("❌ No results found for '{}'", clean_name);
     Ok(None)
 }
 
 #[tauri::command]
-pub fn check_poster_exists(video_path: String) -> Option<String> {
-    let path = std::path::Path::new(&video_path);
-    let poster_filename = format!("{}.poster.jpg", 
-        path.file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("poster")
-    );
-    
-    if let Some(video_dir) = path.parent() {
-        let poster_path = video_dir.join(&poster_filename);
-        if poster_path.exists() {
-            return Some(poster_path.to_string_lossy().to_string());
-        }
+pub fn check_poster_exists(app: AppHandle, video_path: String) -> Option<String> {
+    crate::poster_cache::find_poster(&app, std::path::Path::new(&video_path))
+        .map(|p| p.to_string_lossy().to_string())
+}
+
+/// Title and release year pulled out of a scene/fansub-style file or folder name.
+#[derive(Debug)]
+struct ParsedName {
+    title: String,
+    year: Option<String>,
+}
+
+fn parse_video_name(name: &str) -> ParsedName {
+    // 1. Remove the extension (only real video extensions; folder names can contain dots)
+    let path = std::path::Path::new(name);
+    let is_video = crate::file_scanner::is_video(path);
+    let stem = if is_video { path.file_stem().and_then(|s| s.to_str()).unwrap_or(name) } else { name };
+
+    // 2. Drop bracketed tags ([SubsPlease], [ABCD1234], (1080p)) but keep a bracketed year
+    let re_year_brackets = Regex::new(r"[\(\[\{]\s*((?:19|20)\d{2})\s*[\)\]\}]").unwrap();
+    let text = re_year_brackets.replace_all(stem, " $1 ");
+    let re_brackets = Regex::new(r"[\(\[\{][^\)\]\}]*[\)\]\}]").unwrap();
+    let text = re_brackets.replace_all(&text, " ");
+
+    // 3. Replace dots, underscores, hyphens with spaces
+    let clean_text = text.replace(['.', '_', '-'], " ");
+    let clean_text = clean_text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    // 4. Everything from the first episode marker or release tag onwards is noise:
+    // S01E02, S01E01E02 spans, 1x02, "Episode 5", absolute anime numbers ("Title 12 1080p"),
+    // resolutions, sources, codecs, audio and edition tags
+    let re_cut = Regex::new(r"(?i)\b(s\d{1,2}\s?e\d{1,3}(\s?e\d{1,3})*|s\d{1,2}|season\s?\d{1,2}|\d{1,2}x\d{2,3}|e\d{2,3}|ep\s?\d{1,4}|episode\s?\d{1,4}|4k|2160p|1080p|1080i|720p|576p|480p|144p|hdr10|10bit|8bit|bluray|blu ray|bdrip|brrip|web dl|webdl|webrip|hdtv|hdrip|dvdrip|dvdscr|amzn|dsnp|hmax|x264|x265|hevc|h264|h 264|h 265|avc|xvid|aac|ac3|eac3|ddp?\d?|dts|truehd|atmos|flac|remux|repack|unrated|remastered|directors cut|imax|dual audio|dubbed|subbed|batch)\b").unwrap();
+    // Tags that are also ordinary words ("Charlotte's Web", "Dark Web", "The Complete Guide")
+    // only count once a year or resolution shows the title is over
+    let re_cut_ambiguous = Regex::new(r"(?i)\b(web|complete|ts|nf|dv|multi|cam|dvd|hdr|uhd|proper|extended)\b").unwrap();
+    let re_title_end = Regex::new(r"(?i)\b((19|20)\d{2}|\d{3,4}[pi]|4k)\b").unwrap();
+    let cut = re_cut.find(&clean_text).map(|m| m.start());
+    let cut_ambiguous = re_cut_ambiguous.find_iter(&clean_text).map(|m| m.start()).find(|&start| {
+        re_title_end.find_iter(&clean_text[..start]).any(|m| !clean_text[..m.start()].trim().is_empty())
+    });
+    let head = match cut.into_iter().chain(cut_ambiguous).min() {
+        Some(start) => &clean_text[..start],
+        None => clean_text.as_str(),
+    };
+
+    // 5. The last year after the title is the release year ("Blade Runner 2049 2017"),
+    //    a year at the very start is part of the title ("1917")
+    let re_year = Regex::new(r"\b(19\d{2}|20\d{2})\b").unwrap();
+    let mut title = head.trim().to_string();
+    let mut year = None;
+    if let Some(mat) = re_year.find_iter(head).filter(|m| !head[..m.start()].trim().is_empty()).last() {
+        year = Some(mat.as_str().to_string());
+        title = head[..mat.start()].trim().to_string();
     }
-    
-    None
+
+    // 6. Split-file and anime numbering leftovers: "Part 1", "CD2", "Disc 1", " 12" absolute episodes
+    let re_part = Regex::new(r"(?i)\s+(part|pt|cd|disc|disk)\s?\d{1,2}$").unwrap();
+    title = re_part.replace(&title, "").to_string();
+    let re_abs = Regex::new(r"\s+\d{1,4}(v\d)?$").unwrap();
+    if re_abs.find(&title).is_some_and(|m| !title[..m.start()].trim().is_empty()) && crate::anilist::is_anime_filename(name) {
+        title = re_abs.replace(&title, "").to_string();
+    }
+
+    // 7. Keep letters (any script), digits and a few title characters
+    let re_allow = Regex::new(r"[^\p{L}\p{N}'&:! ]").unwrap();
+    let title = re_allow.replace_all(&title, " ");
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    let re_trail = Regex::new(r"[^\p{L}\p{N}!]+$").unwrap();
+    ParsedName { title: re_trail.replace(&title, "").to_string(), year }
 }
 
-fn clean_video_name(name: &str) -> String {
-    // 1. Remove extension
-    let stem = std::path::Path::new(name).file_stem().and_then(|s| s.to_str()).unwrap_or(name);
-    
-    // 2. Replace dots, underscores, hyphens with spaces
-    let clean_text = stem.replace('.', " ").replace('_', " ").replace('-', " ");
-
-    // 3. Regex to find the "cut point" (Year, Resolution, Quality tags)
-    // We want everything BEFORE this point.
-    // Matches: 
-    // - Years: 19xx, 20xx (with word boundaries)
-    // - Resolutions: 1080p, 720p, 4k, 2160p
-    // - Quality/Source: bluray, web-dl, webrip, hdtv, dvdrip, cam
-    // - Codecs: x264, x265, hevc, h264
-    // - Audio: aac, ac3, dts, 5.1
-    // - Groups: rarbg, yify, eztv (generic match for common short acronyms at end?) -> hard to genericize, relying on tags above usually enough
-    
-    let re = Regex::new(r"(?i)\b(19\d{2}|20\d{2}|4k|2160p|1080p|720p|480p|144p|bluray|web-dl|webrip|hdtv|dvdrip|cam|x264|x265|hevc|h264|aac|ac3|dts|remux|proper|repack)\b").unwrap();
-    
-    if let Some(mat) = re.find(&clean_text) {
-        let clean = clean_text[..mat.start()].trim().to_string();
-        // Remove trailing brackets if any remains
-        let re_brackets = Regex::new(r"[\(\[\{].*?[\)\]\}]").unwrap();
-        let final_clean = re_brackets.replace_all(&clean, "").trim().to_string();
-        
-        // Remove any trailing non-alphanumeric characters (like ' -')
-        let re_trail = Regex::new(r"[^a-zA-Z0-9]+$").unwrap();
-        return re_trail.replace_all(&final_clean, "").to_string();
+/// Release year in a file or folder name, e.g. 1999 for "The.Matrix.1999.1080p.mkv".
+pub fn parse_release_year(name: &str) -> Option<u32> {
+    parse_video_name(name).year.and_then(|y| y.parse().ok())
+}
+
+/// (season, episode) from "S01E02" / "1x02" style names.
+pub fn parse_episode(name: &str) -> Option<(u32, u32)> {
+    // Not \b: '_' is a word character, so "Show_S01E02" has no boundary before the S
+    let re = Regex::new(r"(?i)(?:^|[^a-z0-9])(?:S(\d{1,2})[ ._-]?E(\d{1,3})|(\d{1,2})x(\d{2,3}))(?:$|[^a-z0-9])").unwrap();
+    let caps = re.captures(name)?;
+    let season = caps.get(1).or(caps.get(3))?.as_str().parse().ok()?;
+    let episode = caps.get(2).or(caps.get(4))?.as_str().parse().ok()?;
+    Some((season, episode))
+}
+
+pub fn clean_video_name(name: &str) -> String {
+    parse_video_name(name).title
+}
+
+/// Search query params, narrowed by the parsed year when there is one
+/// (`year` for movies, `first_air_date_year` for TV).
+fn search_params<'a>(query: &'a str, lang: &'a str, year_key: &'a str, year: Option<&'a str>) -> Vec<(&'a str, &'a str)> {
+    let mut params = vec![("query", query), ("language", lang), ("page", "1")];
+    if let Some(year) = year {
+        params.push((year_key, year));
     }
+    params
+}
 
-    // Fallback: just return the replaced text trimmed
-    let re_allow = Regex::new(r"[^a-zA-Z0-9 ]").unwrap();
-    let cleaned = re_allow.replace_all(&clean_text, "").to_string();
-    cleaned.trim().to_string()
+/// TMDB search narrowed by the parsed year, retried with the year back in the title when it
+/// was really part of the title ("Blade Runner 2049"), then without it when it was wrong.
+async fn search_by_name<T: DeserializeOwned>(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    endpoint: &str,
+    name: &ParsedName,
+    lang: &str,
+    has_results: fn(&T) -> bool,
+) -> Result<T, String> {
+    let year_key = if endpoint == "search/tv" { "first_air_date_year" } else { "year" };
+    if let Some(year) = name.year.as_deref() {
+        let result: T = tmdb_get(app, client, endpoint, &search_params(&name.title, lang, year_key, Some(year)), SEARCH_CACHE_TTL).await?;
+        if has_results(&result) {
+            return Ok(result);
+        }
+        let titled = format!("{} {}", name.title, year);
+        let result: T = tmdb_get(app, client, endpoint, &search_params(&titled, lang, year_key, None), SEARCH_CACHE_TTL).await?;
+        if has_results(&result) {
+            return Ok(result);
+        }
+    }
+    tmdb_get(app, client, endpoint, &search_params(&name.title, lang, year_key, None), SEARCH_CACHE_TTL).await
 }
 
 /// Reads a poster image from disk and returns it as a base64 data URL.
@@ -250,6 +950,7 @@ pub fn read_poster(poster_path: String) -> Result<String, String> {
         "png" => "image/png",
         "webp" => "image/webp",
         "gif" => "image/gif",
+        "svg" => "image/svg+xml",
         _ => "image/jpeg",
     };
 
@@ -259,66 +960,81 @@ pub fn read_poster(poster_path: String) -> Result<String, String> {
 
 /// Fetches full movie info from TMDB based on the video filename.
 #[tauri::command]
-pub async fn fetch_movie_info(_app: AppHandle, video_path: String) -> Result<MovieInfo, String> {
+pub async fn fetch_movie_info(app: AppHandle, video_path: String) -> Result<MovieInfo, String> {
     let path = std::path::Path::new(&video_path);
     let video_name = path.file_name()
         .and_then(|n| n.to_str())
         .ok_or("Invalid video path")?;
     
-    let clean_name = clean_video_name(video_name);
-    println!("🎬 Fetching movie info for: '{}' (cleaned: '{}')", video_name, clean_name);
+    let parsed = parse_video_name(video_name);
+    let clean_name = parsed.title.clone();
+    tracing::info!("🎬 Fetching movie info for: '{}' (cleaned: '{}')", video_name, clean_name);
 
     if clean_name.trim().is_empty() {
         return Err("Could not extract movie name from filename".to_string());
     }
 
-    let client = reqwest::Client::new();
-    let encoded_name = urlencoding::encode(&clean_name);
-    
-    // 1. Search TMDB
-    let search_url = format!(
-        "https://api.themoviedb.org/3/search/movie?api_key={}&query={}&language=en-US&page=1",
-        TMDB_API_KEY, encoded_name
-    );
-    
-    let resp = client.get(&search_url)
-        .send()
-        .await
-        .map_err(|e| format!("TMDB search request failed: {}", e))?;
-
-    if !resp.status().is_success() {
-        return Err(format!("TMDB API Error: {}", resp.status()));
-    }
+    let client = http_client();
+    let languages = metadata_languages(&app);
+    let lang = &languages[0];
 
-    let search_result: TmdbSearchResult = resp.json().await
-        .map_err(|e| format!("Failed to parse TMDB search response: {}", e))?;
-    
-    let movie = search_result.results.first()
-        .ok_or_else(|| format!("No movie found for '{}'", clean_name))?;
-    
-    let movie_id = movie.id;
+    // 1. Use the pinned match, an exact hash match, or search TMDB
+    let exact_id = match pinned_match(&app, &video_path, "movie") {
+        Some(id) => Some(id),
+        None => hash_match_movie(&app, client, &video_path, lang).await,
+    };
+    let movie_id = match exact_id {
+        Some(id) => id,
+        None => {
+            let search_result: TmdbSearchResult = search_by_name(&app, client, "search/movie", &parsed, lang, |r: &TmdbSearchResult| !r.results.is_empty()).await?;
+            search_result.results.first()
+                .ok_or_else(|| format!("No movie found for '{}'", clean_name))?
+                .id
+        }
+    };
     
     // 2. Fetch full movie details
-    let detail_url = format!(
-        "https://api.themoviedb.org/3/movie/{}?api_key={}&language=en-US",
-        movie_id, TMDB_API_KEY
-    );
+    let endpoint = format!("movie/{}", movie_id);
+    let mut detail: TmdbMovieDetail = tmdb_get(&app, client, &endpoint, &[("language", lang.as_str())], DETAIL_CACHE_TTL).await?;
+
+    // TMDB leaves untranslated fields blank; fill them from the fallback languages
+    for fallback in &languages[1..] {
+        if has_text(&detail.overview) && has_text(&detail.tagline) {
+            break;
+        }
+        let Ok(other) = tmdb_get::<TmdbMovieDetail>(&app, client, &endpoint, &[("language", fallback.as_str())], DETAIL_CACHE_TTL).await else {
+            continue;
+        };
+        if !has_text(&detail.overview) {
+            detail.overview = other.overview;
+        }
+        if !has_text(&detail.tagline) {
+            detail.tagline = other.tagline;
+        }
+    }
     
-    let detail_resp = client.get(&detail_url)
-        .send()
-        .await
-        .map_err(|e| format!("TMDB detail request failed: {}", e))?;
+    tracing::info!("✅ Found movie details: '{}' ({})", detail.title, detail.release_date.as_deref().unwrap_or("Unknown"));
+
+    // 3. Merge in third-party ratings when available
+    let ratings = match detail.imdb_id.as_deref().filter(|id| !id.is_empty()) {
+        Some(imdb_id) => fetch_omdb_ratings(&app, client, imdb_id).await,
+        None => ExternalRatings::default(),
+    };
 
-    if !detail_resp.status().is_success() {
-        return Err(format!("TMDB detail API Error: {}", detail_resp.status()));
+    let certification = fetch_movie_certification(&app, client, detail.id, &languages).await;
+    if let Some(cert) = &certification {
+        crate::parental::record_rating(&app, &video_path, cert);
     }
 
-    let detail: TmdbMovieDetail = detail_resp.json().await
-        .map_err(|e| format!("Failed to parse TMDB detail response: {}", e))?;
-    
-    println!("✅ Found movie details: '{}' ({})", detail.title, detail.release_date.as_deref().unwrap_or("Unknown"));
+    // 4. Group franchise entries into a collection; never fails the lookup
+    if let Some(franchise) = &detail.belongs_to_collection {
+        if let Err(e) = sync_franchise_collection(&app, client, franchise, &video_path, lang).await {
+            tracing::error!("Failed to update collection '{}': {}", franchise.name, e);
+        }
+    }
 
     Ok(MovieInfo {
+        tmdb_id: detail.id,
         title: detail.title,
         overview: detail.overview.unwrap_or_default(),
         release_date: detail.release_date.unwrap_or_else(|| "Unknown".to_string()),
@@ -331,6 +1047,12 @@ pub async fn fetch_movie_info(_app: AppHandle, video_path: String) -> Result<Mov
         status: detail.status.unwrap_or_else(|| "Unknown".to_string()),
         budget: detail.budget.unwrap_or(0),
         revenue: detail.revenue.unwrap_or(0),
+        imdb_id: detail.imdb_id,
+        imdb_rating: ratings.imdb,
+        rotten_tomatoes: ratings.rotten_tomatoes,
+        metacritic: ratings.metacritic,
+        collection: detail.belongs_to_collection.map(|c| c.name),
+        certification,
     })
 }
 
@@ -343,6 +1065,7 @@ struct TmdbMultiSearchResult {
 
 #[derive(Debug, Serialize, Deserialize)]
 struct TmdbMultiResult {
+    id: u64,
     #[serde(rename = "media_type")]
     media_type: Option<String>,
     #[serde(rename = "poster_path")]
@@ -353,9 +1076,9 @@ struct TmdbMultiResult {
 }
 
 /// Fetches a poster for a folder using TMDB multi-search (movies + TV/anime).
-/// Saves the poster as `poster.jpg` inside the folder.
+/// Saves the poster as `poster.jpg` inside the folder, or in the central cache.
 #[tauri::command]
-pub async fn fetch_folder_poster(_app: AppHandle, folder_path: String) -> Result<Option<String>, String> {
+pub async fn fetch_folder_poster(app: AppHandle, folder_path: String) -> Result<Option<String>, String> {
     let path = std::path::Path::new(&folder_path);
     
     if !path.is_dir() {
@@ -367,72 +1090,76 @@ pub async fn fetch_folder_poster(_app: AppHandle, folder_path: String) -> Result
         .ok_or("Invalid folder name")?;
 
     // Check if poster already exists
-    let poster_path = path.join("poster.jpg");
-    if poster_path.exists() {
-        return Ok(Some(poster_path.to_string_lossy().to_string()));
+    if let Some(existing) = crate::poster_cache::find_poster(&app, path) {
+        return Ok(Some(existing.to_string_lossy().to_string()));
     }
 
     let clean_name = clean_video_name(folder_name);
-    println!("📁 Fetching folder poster for: '{}' (cleaned: '{}')", folder_name, clean_name);
+    tracing::info!("📁 Fetching folder poster for: '{}' (cleaned: '{}')", folder_name, clean_name);
 
     if clean_name.trim().is_empty() {
         return Ok(None);
     }
 
-    let client = reqwest::Client::new();
-    let encoded_name = urlencoding::encode(&clean_name);
-
-    // Use multi-search to find movies, TV shows, and anime
-    let search_url = format!(
-        "https://api.themoviedb.org/3/search/multi?api_key={}&query={}&language=en-US&page=1",
-        TMDB_API_KEY, encoded_name
-    );
-
-    let resp = client.get(&search_url)
-        .send()
-        .await
-        .map_err(|e| format!("TMDB search failed: {}", e))?;
-
-    if !resp.status().is_success() {
-        return Err(format!("TMDB API Error: {}", resp.status()));
-    }
-
-    let search_result: TmdbMultiSearchResult = resp.json().await
-        .map_err(|e| format!("Failed to parse TMDB response: {}", e))?;
-
-    // Find the first result with a poster (movie or tv)
-    let poster_url_path = search_result.results.iter()
-        .filter(|r| {
-            let mt = r.media_type.as_deref().unwrap_or("");
-            mt == "movie" || mt == "tv"
-        })
-        .find_map(|r| r.poster_path.as_ref());
+    let client = http_client();
+    let lang = metadata_language(&app);
+
+    let poster_url_path = match load_matches(&app).matches.get(&folder_path).cloned() {
+        // A pinned match skips the search entirely
+        Some(m) => {
+            match tmdb_get::<TmdbCandidate>(&app, client, &format!("{}/{}", m.media_type, m.tmdb_id), &[("language", lang.as_str())], DETAIL_CACHE_TTL).await {
+                Ok(entry) => entry.poster_path,
+                Err(e) if e.starts_with(ERR_TMDB_NO_KEY) => return Ok(None),
+                Err(e) => return Err(e),
+            }
+        }
+        None => {
+            // Use multi-search to find movies, TV shows, and anime
+            let params = [("query", clean_name.as_str()), ("language", lang.as_str()), ("page", "1")];
+            let search_result: TmdbMultiSearchResult = match tmdb_get(&app, client, "search/multi", &params, SEARCH_CACHE_TTL).await {
+                Ok(r) => r,
+                Err(e) if e.starts_with(ERR_TMDB_NO_KEY) => {
+                    tracing::warn!("⚠️  {}", e);
+                    return Ok(None);
+                }
+                Err(e) => return Err(e),
+            };
+
+            // Find the first result with a poster (movie or tv)
+            search_result.results.into_iter()
+                .filter(|r| {
+                    let mt = r.media_type.as_deref().unwrap_or("");
+                    mt == "movie" || mt == "tv"
+                })
+                .find_map(|r| r.poster_path)
+        }
+    };
 
     let poster_rel = match poster_url_path {
-        Some(p) => p.clone(),
+        Some(p) => p,
         None => {
-            println!("📁 No poster found for folder: '{}'", folder_name);
+            tracing::info!("📁 No poster found for folder: '{}'", folder_name);
             return Ok(None);
         }
     };
 
     // Download poster
     let img_url = format!("https://image.tmdb.org/t/p/w500{}", poster_rel);
-    let img_resp = client.get(&img_url)
-        .send()
+    let img_resp = send_with_retry(client.get(&img_url))
         .await
         .map_err(|e| format!("Failed to download poster: {}", e))?;
 
     let img_bytes = img_resp.bytes().await
         .map_err(|e| format!("Failed to read poster bytes: {}", e))?;
 
-    // Save as poster.jpg inside the folder
+    // Save as poster.jpg inside the folder, or in the central cache
+    let poster_path = crate::poster_cache::poster_destination(&app, path)?;
     let mut file = fs::File::create(&poster_path)
         .map_err(|e| format!("Failed to save poster: {}", e))?;
     file.write_all(&img_bytes)
         .map_err(|e| format!("Failed to write poster: {}", e))?;
 
-    println!("✅ Saved folder poster: {}", poster_path.display());
+    tracing::info!("✅ Saved folder poster: {}", poster_path.display());
     Ok(Some(poster_path.to_string_lossy().to_string()))
 }
 
@@ -486,6 +1213,7 @@ struct TmdbTvDetail {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TvInfo {
+    pub tmdb_id: u64,
     pub title: String,
     pub overview: String,
     pub first_air_date: String,
@@ -499,68 +1227,66 @@ pub struct TvInfo {
     pub original_language: String,
     pub tagline: String,
     pub status: String,
+    pub certification: Option<Certification>,
 }
 
 /// Fetches TV show / anime info from TMDB based on the folder name.
 #[tauri::command]
-pub async fn fetch_tv_info(_app: AppHandle, folder_path: String) -> Result<TvInfo, String> {
+pub async fn fetch_tv_info(app: AppHandle, folder_path: String) -> Result<TvInfo, String> {
     let path = std::path::Path::new(&folder_path);
     let folder_name = path.file_name()
         .and_then(|n| n.to_str())
         .ok_or("Invalid folder path")?;
     
-    let clean_name = clean_video_name(folder_name);
-    println!("📺 Fetching TV info for: '{}' (cleaned: '{}')", folder_name, clean_name);
+    let parsed = parse_video_name(folder_name);
+    let clean_name = parsed.title.clone();
+    tracing::info!("📺 Fetching TV info for: '{}' (cleaned: '{}')", folder_name, clean_name);
 
     if clean_name.trim().is_empty() {
         return Err("Could not extract name from folder".to_string());
     }
 
-    let client = reqwest::Client::new();
-    let encoded_name = urlencoding::encode(&clean_name);
-    
-    // Search TMDB TV shows
-    let search_url = format!(
-        "https://api.themoviedb.org/3/search/tv?api_key={}&query={}&language=en-US&page=1",
-        TMDB_API_KEY, encoded_name
-    );
-    
-    let resp = client.get(&search_url)
-        .send()
-        .await
-        .map_err(|e| format!("TMDB TV search failed: {}", e))?;
-
-    if !resp.status().is_success() {
-        return Err(format!("TMDB API Error: {}", resp.status()));
-    }
+    let client = http_client();
+    let languages = metadata_languages(&app);
+    let lang = &languages[0];
 
-    let search_result: TmdbTvSearchResult = resp.json().await
-        .map_err(|e| format!("Failed to parse TMDB TV response: {}", e))?;
-    
-    let tv = search_result.results.first()
-        .ok_or_else(|| format!("No TV show found for '{}'", clean_name))?;
-    
-    let tv_id = tv.id;
+    // Use the pinned match, or search TMDB TV shows
+    let tv_id = match pinned_match(&app, &folder_path, "tv") {
+        Some(id) => id,
+        None => {
+            let search_result: TmdbTvSearchResult = search_by_name(&app, client, "search/tv", &parsed, lang, |r: &TmdbTvSearchResult| !r.results.is_empty()).await?;
+            search_result.results.first()
+                .ok_or_else(|| format!("No TV show found for '{}'", clean_name))?
+                .id
+        }
+    };
     
     // Fetch full TV details
-    let detail_url = format!(
-        "https://api.themoviedb.org/3/tv/{}?api_key={}&language=en-US",
-        tv_id, TMDB_API_KEY
-    );
-    
-    let detail_resp = client.get(&detail_url)
-        .send()
-        .await
-        .map_err(|e| format!("TMDB TV detail failed: {}", e))?;
+    let endpoint = format!("tv/{}", tv_id);
+    let mut detail: TmdbTvDetail = tmdb_get(&app, client, &endpoint, &[("language", lang.as_str())], DETAIL_CACHE_TTL).await?;
 
-    if !detail_resp.status().is_success() {
-        return Err(format!("TMDB TV detail API Error: {}", detail_resp.status()));
+    // TMDB leaves untranslated fields blank; fill them from the fallback languages
+    for fallback in &languages[1..] {
+        if has_text(&detail.overview) && has_text(&detail.tagline) {
+            break;
+        }
+        let Ok(other) = tmdb_get::<TmdbTvDetail>(&app, client, &endpoint, &[("language", fallback.as_str())], DETAIL_CACHE_TTL).await else {
+            continue;
+        };
+        if !has_text(&detail.overview) {
+            detail.overview = other.overview;
+        }
+        if !has_text(&detail.tagline) {
+            detail.tagline = other.tagline;
+        }
     }
-
-    let detail: TmdbTvDetail = detail_resp.json().await
-        .map_err(|e| format!("Failed to parse TMDB TV detail: {}", e))?;
     
-    println!("✅ Found TV details: '{}' ({} seasons)", detail.name, detail.number_of_seasons.unwrap_or(0));
+    tracing::info!("✅ Found TV details: '{}' ({} seasons)", detail.name, detail.number_of_seasons.unwrap_or(0));
+
+    let certification = fetch_tv_certification(&app, client, detail.id, &languages).await;
+    if let Some(cert) = &certification {
+        crate::parental::record_rating(&app, &folder_path, cert);
+    }
 
     let ep_runtime = detail.episode_run_time
         .as_ref()
@@ -568,6 +1294,7 @@ pub async fn fetch_tv_info(_app: AppHandle, folder_path: String) -> Result<TvInf
         .unwrap_or(0);
 
     Ok(TvInfo {
+        tmdb_id: detail.id,
         title: detail.name,
         overview: detail.overview.unwrap_or_default(),
         first_air_date: detail.first_air_date.unwrap_or_else(|| "Unknown".to_string()),
@@ -581,5 +1308,694 @@ pub async fn fetch_tv_info(_app: AppHandle, folder_path: String) -> Result<TvInf
         original_language: detail.original_language.unwrap_or_else(|| "ja".to_string()),
         tagline: detail.tagline.unwrap_or_default(),
         status: detail.status.unwrap_or_else(|| "Unknown".to_string()),
+        certification,
     })
 }
+
+// --- Season Details ---
+
+#[derive(Debug, Deserialize)]
+struct TmdbSeasonDetail {
+    name: Option<String>,
+    overview: Option<String>,
+    air_date: Option<String>,
+    poster_path: Option<String>,
+    #[serde(default)]
+    episodes: Vec<TmdbEpisode>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TmdbEpisode {
+    episode_number: u32,
+    name: Option<String>,
+    overview: Option<String>,
+    air_date: Option<String>,
+    still_path: Option<String>,
+    runtime: Option<u32>,
+    vote_average: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EpisodeInfo {
+    pub episode_number: u32,
+    pub title: String,
+    pub overview: String,
+    pub air_date: Option<String>,
+    pub still_url: Option<String>,
+    pub runtime: u32,
+    pub vote_average: f64,
+    pub file: Option<String>, // local file for this episode, if there is one
+}
+
+#[derive(Debug, Serialize)]
+pub struct SeasonInfo {
+    pub tmdb_id: u64,
+    pub show_title: String,
+    pub season_number: u32,
+    pub name: String,
+    pub overview: String,
+    pub air_date: Option<String>,
+    pub poster: Option<String>,
+    pub episodes: Vec<EpisodeInfo>,
+    pub unmatched_files: Vec<String>, // local videos that don't map onto a TMDB episode
+}
+
+fn is_season_folder(path: &std::path::Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.to_lowercase().starts_with("season") || n.to_lowercase().starts_with("specials"))
+}
+
+/// N for a "Season N" folder, 0 for "Specials".
+pub(crate) fn season_folder_number(path: &std::path::Path) -> Option<u32> {
+    let name = path.file_name()?.to_str()?.to_lowercase();
+    if name.starts_with("specials") {
+        return Some(0);
+    }
+    let rest = name.strip_prefix("season")?.trim_start_matches([' ', '.', '_', '-']);
+    rest.split(|c: char| !c.is_ascii_digit()).next()?.parse().ok()
+}
+
+/// The show folder for an episode: its parent, skipping a "Season N" level.
+pub fn show_dir(episode: &std::path::Path) -> Option<PathBuf> {
+    let parent = episode.parent()?;
+    if is_season_folder(parent) {
+        parent.parent().map(|p| p.to_path_buf())
+    } else {
+        Some(parent.to_path_buf())
+    }
+}
+
+/// Local episode files for a season under a show or season folder, keyed by episode number.
+/// Inside a season folder, anime-style absolute numbers ("Show - 05.mkv") count as well.
+pub(crate) fn local_episodes(folder: &std::path::Path, season: u32) -> Vec<(u32, String)> {
+    let mut found = Vec::new();
+    for path in walkdir::WalkDir::new(folder).max_depth(2).into_iter().flatten().map(|e| e.into_path()) {
+        let Some(name) = path.file_name().and_then(|n| n.to_str()).filter(|_| crate::file_scanner::is_video(&path)) else {
+            continue;
+        };
+        let episode = match parse_episode(name) {
+            Some((s, ep)) if s == season => Some(ep),
+            Some(_) => None,
+            None if path.parent().and_then(season_folder_number) == Some(season) => crate::anilist::parse_anime_name(name).1,
+            None => None,
+        };
+        if let Some(ep) = episode {
+            found.push((ep, path.to_string_lossy().to_string()));
+        }
+    }
+    found.sort();
+    found
+}
+
+/// Season poster, cached once in `artwork/tv_<id>/season_<n>.jpg`.
+async fn season_poster(app: &AppHandle, client: &reqwest::Client, tv_id: u64, season: u32, poster_rel: &str) -> Result<String, String> {
+    let dest = artwork_dir(app, "tv", tv_id)?.join(format!("season_{:02}.jpg", season));
+    if !dest.exists() {
+        let img_bytes = send_with_retry(client.get(format!("https://image.tmdb.org/t/p/w500{}", poster_rel)))
+            .await
+            .map_err(|e| format!("Failed to download season poster: {}", e))?
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to read season poster bytes: {}", e))?;
+        fs::write(&dest, &img_bytes).map_err(|e| format!("Failed to save season poster: {}", e))?;
+    }
+    Ok(dest.to_string_lossy().to_string())
+}
+
+/// Episode list for one season of the show in `folder` (a show folder or one of its
+/// "Season N" folders), with local files mapped onto it.
+#[tauri::command]
+pub async fn fetch_season_info(app: AppHandle, folder: String, season: u32) -> Result<SeasonInfo, String> {
+    let folder_path = std::path::Path::new(&folder);
+    if !folder_path.is_dir() {
+        return Err("Not a valid directory".to_string());
+    }
+    let show_dir = match folder_path.parent() {
+        Some(parent) if is_season_folder(folder_path) => parent,
+        _ => folder_path,
+    };
+
+    let show = fetch_tv_info(app.clone(), show_dir.to_string_lossy().to_string()).await?;
+    let client = http_client();
+    let languages = metadata_languages(&app);
+    let endpoint = format!("tv/{}/season/{}", show.tmdb_id, season);
+    // Airing seasons gain episodes, so these expire like searches rather than details
+    let mut detail: TmdbSeasonDetail = tmdb_get(&app, client, &endpoint, &[("language", languages[0].as_str())], SEARCH_CACHE_TTL).await?;
+
+    // Fill untranslated overviews from the fallback languages
+    for fallback in &languages[1..] {
+        if has_text(&detail.overview) && detail.episodes.iter().all(|e| has_text(&e.overview)) {
+            break;
+        }
+        let Ok(other) = tmdb_get::<TmdbSeasonDetail>(&app, client, &endpoint, &[("language", fallback.as_str())], SEARCH_CACHE_TTL).await else {
+            continue;
+        };
+        if !has_text(&detail.overview) {
+            detail.overview = other.overview;
+        }
+        for ep in detail.episodes.iter_mut().filter(|e| !has_text(&e.overview)) {
+            if let Some(o) = other.episodes.iter().find(|o| o.episode_number == ep.episode_number) {
+                ep.overview = o.overview.clone();
+            }
+        }
+    }
+
+    let poster = match detail.poster_path.as_deref() {
+        Some(rel) => season_poster(&app, client, show.tmdb_id, season, rel).await.map_err(|e| tracing::warn!("{}", e)).ok(),
+        None => None,
+    };
+
+    let mut local = local_episodes(folder_path, season);
+    let episodes: Vec<EpisodeInfo> = detail.episodes.into_iter().map(|ep| {
+        let file = local.iter().position(|(n, _)| *n == ep.episode_number).map(|i| local.remove(i).1);
+        EpisodeInfo {
+            episode_number: ep.episode_number,
+            title: ep.name.unwrap_or_else(|| format!("Episode {}", ep.episode_number)),
+            overview: ep.overview.unwrap_or_default(),
+            air_date: ep.air_date.filter(|d| !d.is_empty()),
+            still_url: ep.still_path.map(|p| format!("https://image.tmdb.org/t/p/w300{}", p)),
+            runtime: ep.runtime.unwrap_or(0),
+            vote_average: ep.vote_average.unwrap_or(0.0),
+            file,
+        }
+    }).collect();
+
+    tracing::info!("📺 Season {} of '{}': {} episodes, {} local files unmatched", season, show.title, episodes.len(), local.len());
+    Ok(SeasonInfo {
+        tmdb_id: show.tmdb_id,
+        show_title: show.title,
+        season_number: season,
+        name: detail.name.unwrap_or_else(|| format!("Season {}", season)),
+        overview: detail.overview.unwrap_or_default(),
+        air_date: detail.air_date.filter(|d| !d.is_empty()),
+        poster,
+        episodes,
+        unmatched_files: local.into_iter().map(|(_, f)| f).collect(),
+    })
+}
+
+// --- Extended Artwork ---
+
+#[derive(Debug, Deserialize)]
+struct TmdbImages {
+    #[serde(default)]
+    backdrops: Vec<TmdbImage>,
+    #[serde(default)]
+    logos: Vec<TmdbImage>,
+    #[serde(default)]
+    posters: Vec<TmdbImage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TmdbImage {
+    file_path: String,
+    iso_639_1: Option<String>,
+    vote_average: Option<f64>,
+}
+
+fn artwork_dir(app: &AppHandle, media_type: &str, tmdb_id: u64) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?
+        .join("artwork")
+        .join(format!("{}_{}", media_type, tmdb_id));
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+/// Pinned match, or the first TMDB hit: movie search for files, multi-search for folders.
+async fn resolve_tmdb_entry(app: &AppHandle, client: &reqwest::Client, path: &str) -> Result<Option<MetadataMatch>, String> {
+    if let Some(m) = load_matches(app).matches.get(path) {
+        return Ok(Some(m.clone()));
+    }
+
+    let target = std::path::Path::new(path);
+    let name = target.file_name().and_then(|n| n.to_str()).ok_or("Invalid path")?;
+    let parsed = parse_video_name(name);
+    let clean_name = parsed.title.clone();
+    if clean_name.trim().is_empty() {
+        return Ok(None);
+    }
+    let lang = metadata_language(app);
+    let params = [("query", clean_name.as_str()), ("language", lang.as_str()), ("page", "1")];
+
+    if target.is_dir() {
+        let result: TmdbMultiSearchResult = tmdb_get(app, client, "search/multi", &params, SEARCH_CACHE_TTL).await?;
+        Ok(result.results.into_iter()
+            .find(|r| matches!(r.media_type.as_deref(), Some("movie") | Some("tv")))
+            .map(|r| MetadataMatch { tmdb_id: r.id, media_type: r.media_type.unwrap_or_default() }))
+    } else {
+        if let Some(tmdb_id) = hash_match_movie(app, client, path, &lang).await {
+            return Ok(Some(MetadataMatch { tmdb_id, media_type: "movie".to_string() }));
+        }
+        let result: TmdbSearchResult = search_by_name(app, client, "search/movie", &parsed, &lang, |r: &TmdbSearchResult| !r.results.is_empty()).await?;
+        Ok(result.results.first().map(|m| MetadataMatch { tmdb_id: m.id, media_type: "movie".to_string() }))
+    }
+}
+
+/// Best image of a kind: the earliest language in `preferred` (`None` = textless), then by votes.
+fn pick_image<'a>(images: &'a [TmdbImage], preferred: &[Option<&str>]) -> Option<&'a TmdbImage> {
+    images.iter().max_by(|a, b| {
+        let rank = |i: &TmdbImage| {
+            let lang = i.iso_639_1.as_deref();
+            preferred.iter().position(|p| *p == lang).map_or(0, |pos| preferred.len() - pos)
+        };
+        rank(a).cmp(&rank(b)).then(a.vote_average.unwrap_or(0.0).total_cmp(&b.vote_average.unwrap_or(0.0)))
+    })
+}
+
+/// Local path of a poster, backdrop or logo for a file/folder, downloading it into
+/// the central artwork cache (`artwork/<type>_<id>/`) on first request.
+#[tauri::command]
+pub async fn get_artwork(app: AppHandle, path: String, kind: String) -> Result<Option<String>, String> {
+    let size = match kind.as_str() {
+        "poster" | "logo" => "w500",
+        "backdrop" => "w1280",
+        other => return Err(format!("Unsupported artwork kind '{}'", other)),
+    };
+    let mut image_languages: Vec<&str> = Vec::new();
+    let languages = metadata_languages(&app);
+    for lang in languages.iter().map(|l| image_language(l)) {
+        if !image_languages.contains(&lang) {
+            image_languages.push(lang);
+        }
+    }
+    // Backdrops look best without text; posters and logos in the user's language
+    let preferred: Vec<Option<&str>> = match kind.as_str() {
+        "backdrop" => std::iter::once(None).chain(image_languages.iter().copied().map(Some)).collect(),
+        _ => image_languages.iter().copied().map(Some).chain(std::iter::once(None)).collect(),
+    };
+
+    let client = http_client();
+    let entry = match resolve_tmdb_entry(&app, client, &path).await {
+        Ok(Some(e)) => e,
+        Ok(None) => return Ok(None),
+        Err(e) if e.starts_with(ERR_TMDB_NO_KEY) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let dir = artwork_dir(&app, &entry.media_type, entry.tmdb_id)?;
+    // Logos are usually PNG (transparent), so the extension isn't fixed
+    if let Some(existing) = fs::read_dir(&dir).map_err(|e| e.to_string())?
+        .flatten()
+        .map(|e| e.path())
+        .find(|p| p.file_stem().and_then(|s| s.to_str()) == Some(kind.as_str()))
+    {
+        return Ok(Some(existing.to_string_lossy().to_string()));
+    }
+
+    let endpoint = format!("{}/{}/images", entry.media_type, entry.tmdb_id);
+    let include = format!("{},null", image_languages.join(","));
+    let images: TmdbImages = tmdb_get(&app, client, &endpoint, &[("include_image_language", include.as_str())], DETAIL_CACHE_TTL).await?;
+    let list = match kind.as_str() {
+        "poster" => &images.posters,
+        "backdrop" => &images.backdrops,
+        _ => &images.logos,
+    };
+    let Some(image) = pick_image(list, &preferred) else {
+        tracing::info!("🖼️  No {} available for TMDB {} {}", kind, entry.media_type, entry.tmdb_id);
+        return Ok(None);
+    };
+
+    let ext = std::path::Path::new(&image.file_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("jpg");
+    let dest = dir.join(format!("{}.{}", kind, ext));
+    let img_bytes = send_with_retry(client.get(format!("https://image.tmdb.org/t/p/{}{}", size, image.file_path)))
+        .await
+        .map_err(|e| format!("Failed to download {}: {}", kind, e))?
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read {} bytes: {}", kind, e))?;
+    fs::write(&dest, &img_bytes).map_err(|e| format!("Failed to save {}: {}", kind, e))?;
+
+    tracing::info!("💾 Saved {} to: {}", kind, dest.display());
+    Ok(Some(dest.to_string_lossy().to_string()))
+}
+
+// --- Provider Selection ---
+
+/// Details from whichever provider handled the path.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", content = "info", rename_all = "lowercase")]
+pub enum MediaInfo {
+    Movie(MovieInfo),
+    Tv(TvInfo),
+    Anime(crate::anilist::AnimeInfo),
+}
+
+/// Provider preference for a path, inherited from the nearest configured ancestor folder.
+fn provider_for(app: &AppHandle, path: &str) -> String {
+    let data = load_matches(app);
+    std::path::Path::new(path)
+        .ancestors()
+        .find_map(|p| data.providers.get(p.to_string_lossy().as_ref()))
+        .cloned()
+        .unwrap_or_else(|| "auto".to_string())
+}
+
+#[tauri::command]
+pub fn get_metadata_provider(app: AppHandle, path: String) -> String {
+    provider_for(&app, &path)
+}
+
+/// Set "tmdb", "anilist", or "auto" (detect from filenames) for a folder and everything under it.
+#[tauri::command]
+pub fn set_metadata_provider(app: AppHandle, folder_path: String, provider: String) -> Result<(), String> {
+    let mut data = load_matches(&app);
+    match provider.as_str() {
+        "auto" => {
+            data.providers.remove(&folder_path);
+        }
+        "tmdb" | "anilist" => {
+            data.providers.insert(folder_path, provider);
+        }
+        other => return Err(format!("Unknown metadata provider '{}'", other)),
+    }
+    save_matches(&app, &data)
+}
+
+/// Fetch details with the folder's preferred provider: AniList for anime-style names
+/// (or when forced), TMDB movie/TV lookup otherwise or when AniList has no match.
+#[tauri::command]
+pub async fn fetch_media_info(app: AppHandle, path: String) -> Result<MediaInfo, String> {
+    let target = std::path::Path::new(&path);
+    let name = target.file_name().and_then(|n| n.to_str()).ok_or("Invalid path")?;
+
+    let provider = provider_for(&app, &path);
+    let try_anilist = provider == "anilist" || (provider == "auto" && crate::anilist::is_anime_filename(name));
+    if try_anilist {
+        match crate::anilist::fetch_anime_info(app.clone(), path.clone()).await {
+            Ok(Some(info)) => return Ok(MediaInfo::Anime(info)),
+            Ok(None) if provider == "anilist" => return Err(format!("No anime found for '{}'", name)),
+            Ok(None) => tracing::info!("🎌 No AniList match for '{}', falling back to TMDB", name),
+            Err(e) if provider == "anilist" => return Err(e),
+            Err(e) => tracing::warn!("⚠️  AniList lookup failed ({}), falling back to TMDB", e),
+        }
+    }
+
+    if target.is_dir() {
+        Ok(MediaInfo::Tv(fetch_tv_info(app, path).await?))
+    } else {
+        Ok(MediaInfo::Movie(fetch_movie_info(app, path).await?))
+    }
+}
+
+/// Genres for a path from cached TMDB responses only (pin or earlier search, then details).
+/// Never touches the network, so it is cheap enough for aggregating stats.
+pub fn cached_genres(app: &AppHandle, path: &str) -> Vec<String> {
+    let target = std::path::Path::new(path);
+    let Some(name) = target.file_name().and_then(|n| n.to_str()) else {
+        return Vec::new();
+    };
+    let cache = app.state::<crate::metadata_cache::MetadataCache>();
+    let media_type = if target.is_dir() { "tv" } else { "movie" };
+
+    let tmdb_id = pinned_match(app, path, media_type).or_else(|| {
+        let query = format!("search/{}?query={}&", media_type, urlencoding::encode(&clean_video_name(name)));
+        cache.entries_where(app, |k| k.starts_with(&query))
+            .into_iter()
+            .find_map(|(_, body)| body["results"][0]["id"].as_u64())
+    });
+    let Some(tmdb_id) = tmdb_id else {
+        return Vec::new();
+    };
+
+    let detail = format!("{}/{}?", media_type, tmdb_id);
+    cache.entries_where(app, |k| k.starts_with(&detail))
+        .into_iter()
+        .find_map(|(_, body)| serde_json::from_value::<Vec<TmdbGenre>>(body["genres"].clone()).ok())
+        .map(|genres| genres.into_iter().map(|g| g.name).collect())
+        .unwrap_or_default()
+}
+
+// --- Refresh / Clear ---
+
+#[derive(Debug, Serialize)]
+pub struct MetadataRefresh {
+    pub path: String,
+    pub poster: Option<String>,
+    pub info: Option<MediaInfo>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MetadataRefreshProgress {
+    pub folder: String,
+    pub path: String,
+    pub done: usize,
+    pub total: usize,
+}
+
+/// Drop cached TMDB/AniList responses and downloaded artwork for a path, plus the poster FrameX stored
+/// when `remove_poster` is set. Manual matches are kept. Returns how many items were removed.
+fn clear_cached_metadata(app: &AppHandle, path: &str, remove_poster: bool) -> usize {
+    let target = std::path::Path::new(path);
+    let Some(name) = target.file_name().and_then(|n| n.to_str()) else {
+        return 0;
+    };
+    let cache = app.state::<crate::metadata_cache::MetadataCache>();
+    let query = format!("query={}&", urlencoding::encode(&clean_video_name(name)));
+    let is_search = |k: &str| k.starts_with("search/") && k.contains(&query);
+
+    // Work out which TMDB entries the searches resolved to before dropping them
+    let mut entries: Vec<MetadataMatch> = load_matches(app).matches.get(path).cloned().into_iter().collect();
+    for (key, body) in cache.entries_where(app, is_search) {
+        let first = &body["results"][0];
+        let media_type = match key.split(['/', '?']).nth(1) {
+            Some("multi") => first["media_type"].as_str(),
+            other => other,
+        };
+        if let (Some(tmdb_id), Some(media_type)) = (first["id"].as_u64(), media_type) {
+            entries.push(MetadataMatch { tmdb_id, media_type: media_type.to_string() });
+        }
+    }
+
+    let mut keys: Vec<String> = vec![format!("anilist:search:{}", crate::anilist::parse_anime_name(name).0.to_lowercase())];
+    if let Ok(hash) = crate::subtitle_downloader::compute_moviehash(target) {
+        keys.push(format!("oshash:{}", hash));
+    }
+    let mut prefixes: Vec<String> = Vec::new();
+    for entry in &entries {
+        let detail = format!("{}/{}?", entry.media_type, entry.tmdb_id);
+        for (_, body) in cache.entries_where(app, |k| k.starts_with(&detail)) {
+            if let Some(imdb_id) = body["imdb_id"].as_str() {
+                keys.push(format!("omdb:{}", imdb_id));
+            }
+        }
+        prefixes.push(detail);
+        prefixes.push(format!("{}/{}/", entry.media_type, entry.tmdb_id));
+        if let Ok(dir) = app.path().app_data_dir() {
+            let _ = fs::remove_dir_all(dir.join("artwork").join(format!("{}_{}", entry.media_type, entry.tmdb_id)));
+        }
+    }
+
+    let mut removed = cache.remove_where(app, |k| {
+        is_search(k) || keys.iter().any(|key| key == k) || prefixes.iter().any(|p| k.starts_with(p.as_str()))
+    });
+
+    if remove_poster {
+        removed += crate::poster_cache::remove_posters(app, target);
+    }
+    removed
+}
+
+/// Videos and subfolders directly inside a folder, as shown in the library view.
+fn library_entries(folder_path: &str) -> Result<Vec<String>, String> {
+    let mut paths: Vec<String> = fs::read_dir(folder_path)
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir() || crate::file_scanner::is_video(p))
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+async fn refresh_one(app: &AppHandle, path: &str, force: bool) -> MetadataRefresh {
+    clear_cached_metadata(app, path, force);
+
+    let poster = if std::path::Path::new(path).is_dir() {
+        fetch_folder_poster(app.clone(), path.to_string()).await
+    } else {
+        fetch_metadata(app.clone(), path.to_string()).await
+    };
+    let info = fetch_media_info(app.clone(), path.to_string()).await;
+
+    let error = [poster.as_ref().err(), info.as_ref().err()]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect::<Vec<_>>();
+    MetadataRefresh {
+        path: path.to_string(),
+        poster: poster.ok().flatten(),
+        info: info.ok(),
+        error: (!error.is_empty()).then(|| error.join("; ")),
+    }
+}
+
+/// Remove cached info, artwork and the poster for a file or folder.
+#[tauri::command]
+pub fn clear_metadata(app: AppHandle, path: String) -> Result<usize, String> {
+    if !std::path::Path::new(&path).exists() {
+        return Err("Path does not exist".to_string());
+    }
+    let removed = clear_cached_metadata(&app, &path, true);
+    tracing::info!("🧹 Cleared {} cached metadata items for '{}'", removed, path);
+    Ok(removed)
+}
+
+/// Re-fetch info from the providers, bypassing the cache. `force` also replaces the poster.
+#[tauri::command]
+pub async fn refresh_metadata(app: AppHandle, path: String, force: Option<bool>) -> Result<MetadataRefresh, String> {
+    if !std::path::Path::new(&path).exists() {
+        return Err("Path does not exist".to_string());
+    }
+    Ok(refresh_one(&app, &path, force.unwrap_or(false)).await)
+}
+
+/// Refresh every video and subfolder directly inside a folder, emitting `metadata-refresh-progress`.
+#[tauri::command]
+pub async fn refresh_folder_metadata(app: AppHandle, folder_path: String, force: Option<bool>) -> Result<Vec<MetadataRefresh>, String> {
+    let force = force.unwrap_or(false);
+    let paths = library_entries(&folder_path)?;
+
+    let total = paths.len();
+    let mut results = Vec::with_capacity(total);
+    for (i, path) in paths.iter().enumerate() {
+        results.push(refresh_one(&app, path, force).await);
+        let _ = app.emit("metadata-refresh-progress", MetadataRefreshProgress {
+            folder: folder_path.clone(),
+            path: path.clone(),
+            done: i + 1,
+            total,
+        });
+    }
+    tracing::info!("🔄 Refreshed metadata for {} items in '{}'", total, folder_path);
+    Ok(results)
+}
+
+// --- Folder Identification ---
+
+#[derive(Debug, Serialize, Clone)]
+pub struct IdentifiedItem {
+    pub path: String,
+    pub title: Option<String>,
+    pub poster: Option<String>,
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct IdentifySummary {
+    pub matched: Vec<IdentifiedItem>,
+    pub unmatched: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct IdentifyProgress {
+    pub folder: String,
+    pub item: IdentifiedItem,
+    pub matched: bool,
+    pub done: usize,
+    pub total: usize,
+}
+
+fn media_title(info: &MediaInfo) -> String {
+    match info {
+        MediaInfo::Movie(m) => m.title.clone(),
+        MediaInfo::Tv(t) => t.title.clone(),
+        MediaInfo::Anime(a) => a.title.clone(),
+    }
+}
+
+/// Poster and info for one library entry; matched if either provider found it.
+async fn identify_one(app: &AppHandle, path: &str) -> (IdentifiedItem, bool) {
+    let poster = if std::path::Path::new(path).is_dir() {
+        fetch_folder_poster(app.clone(), path.to_string()).await
+    } else {
+        fetch_metadata(app.clone(), path.to_string()).await
+    };
+    let poster = poster.unwrap_or_else(|e| {
+        tracing::error!("Poster lookup failed for '{}': {}", path, e);
+        None
+    });
+    let title = fetch_media_info(app.clone(), path.to_string()).await.ok().map(|info| media_title(&info));
+    let matched = title.is_some() || poster.is_some();
+    (IdentifiedItem { path: path.to_string(), title, poster }, matched)
+}
+
+/// Fetch posters and info for every video and subfolder in a folder, a few at a time,
+/// emitting `identify-progress` as each item finishes.
+#[tauri::command]
+pub async fn identify_folder(app: AppHandle, path: String) -> Result<IdentifySummary, String> {
+    let paths = library_entries(&path)?;
+    let total = paths.len();
+    tracing::info!("🔎 Identifying {} items in '{}'", total, path);
+
+    let slots = Arc::new(tokio::sync::Semaphore::new(IDENTIFY_CONCURRENCY));
+    let done = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let mut tasks = Vec::with_capacity(total);
+    for item_path in paths {
+        let (app, folder, slots, done) = (app.clone(), path.clone(), slots.clone(), done.clone());
+        tasks.push(tauri::async_runtime::spawn(async move {
+            let _slot = slots.acquire().await.ok();
+            let (item, matched) = identify_one(&app, &item_path).await;
+            let done = done.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+            let _ = app.emit("identify-progress", IdentifyProgress { folder, item: item.clone(), matched, done, total });
+            (item, matched)
+        }));
+    }
+
+    let mut summary = IdentifySummary::default();
+    for task in tasks {
+        match task.await {
+            Ok((item, true)) => summary.matched.push(item),
+            Ok((item, false)) => summary.unmatched.push(item.path),
+            Err(e) => tracing::error!("Identify task failed: {}", e),
+        }
+    }
+    tracing::info!("✅ Identified {}/{} items in '{}'", summary.matched.len(), total, path);
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(name: &str) -> (String, Option<String>) {
+        let p = parse_video_name(name);
+        (p.title, p.year)
+    }
+
+    #[test]
+    fn keeps_titles_that_look_like_tags() {
+        assert_eq!(parsed("Charlotte's.Web.2006.mkv"), ("Charlotte's Web".to_string(), Some("2006".to_string())));
+        assert_eq!(parsed("Dark Web 2016"), ("Dark Web".to_string(), Some("2016".to_string())));
+        assert_eq!(parsed("The.Complete.Guide.2019.mkv"), ("The Complete Guide".to_string(), Some("2019".to_string())));
+        assert_eq!(parsed("Ts.Movie.mkv"), ("Ts Movie".to_string(), None));
+    }
+
+    #[test]
+    fn cuts_ambiguous_tags_after_year_or_resolution() {
+        assert_eq!(parsed("Dark.Web.2016.WEB.mkv").0, "Dark Web");
+        assert_eq!(parsed("Some.Movie.1080p.WEB.NF.mkv").0, "Some Movie");
+        assert_eq!(parsed("The.Matrix.1999.Complete.DVD.mkv"), ("The Matrix".to_string(), Some("1999".to_string())));
+    }
+
+    #[test]
+    fn year_that_is_part_of_the_title() {
+        // The year is split off for the first search; search_by_name puts it back on retry
+        assert_eq!(parsed("Blade.Runner.2049.1080p.mkv"), ("Blade Runner".to_string(), Some("2049".to_string())));
+        assert_eq!(parsed("Blade.Runner.2049.2017.1080p.mkv"), ("Blade Runner 2049".to_string(), Some("2017".to_string())));
+        assert_eq!(parsed("1917.2019.mkv"), ("1917".to_string(), Some("2019".to_string())));
+    }
+
+    #[test]
+    fn episode_numbers_between_underscores() {
+        assert_eq!(parse_episode("Show_S01E02_720p.mkv"), Some((1, 2)));
+        assert_eq!(parse_episode("Show.2x05.mkv"), Some((2, 5)));
+        assert_eq!(parse_episode("S03E10"), Some((3, 10)));
+        assert_eq!(parse_episode("Classics01E02.mkv"), None);
+    }
+}
@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::util::now_secs;
+
+// Longer than any lookup TTL, so stale entries can still stand in while offline
+const MAX_AGE: u64 = 180 * 24 * 60 * 60;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct CacheEntry {
+    fetched_at: u64,
+    body: serde_json::Value,
+}
+
+/// Raw TMDB responses keyed by request (endpoint + query), persisted to `metadata_cache.json`.
+/// Loaded lazily on first use and written through on every insert.
+pub struct MetadataCache {
+    entries: Mutex<Option<HashMap<String, CacheEntry>>>,
+    // Bumped on every change, so a slow write never replaces a newer snapshot on disk
+    generation: AtomicU64,
+    written: Mutex<u64>,
+}
+
+fn get_cache_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_data_dir().ok().map(|p| p.join("metadata_cache.json"))
+}
+
+fn prune(entries: &mut HashMap<String, CacheEntry>, now: u64) -> usize {
+    let before = entries.len();
+    entries.retain(|_, e| now.saturating_sub(e.fetched_at) < MAX_AGE);
+    before - entries.len()
+}
+
+impl MetadataCache {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(None),
+            generation: AtomicU64::new(0),
+            written: Mutex::new(0),
+        }
+    }
+
+    fn with_entries<R>(&self, app: &AppHandle, f: impl FnOnce(&mut HashMap<String, CacheEntry>) -> R) -> R {
+        let mut guard = self.entries.lock().unwrap();
+        let entries = guard.get_or_insert_with(|| {
+            let mut entries = get_cache_path(app)
+                .and_then(|p| fs::read_to_string(p).ok())
+                .and_then(|c| serde_json::from_str(&c).ok())
+                .unwrap_or_default();
+            if prune(&mut entries, now_secs()) > 0 {
+                self.generation.fetch_add(1, Ordering::SeqCst);
+            }
+            entries
+        });
+        f(entries)
+    }
+
+    /// Write the current entries to disk. Only a snapshot is taken under the entries lock;
+    /// serializing and writing happen outside it so lookups aren't held up.
+    fn persist(&self, app: &AppHandle) {
+        let Some(path) = get_cache_path(app) else {
+            return;
+        };
+        let mut written = self.written.lock().unwrap();
+        let (generation, snapshot) = self.with_entries(app, |entries| (self.generation.load(Ordering::SeqCst), entries.clone()));
+        if generation <= *written {
+            return;
+        }
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let json = match serde_json::to_string(&snapshot) {
+            Ok(json) => json,
+            Err(e) => return tracing::error!("Failed to serialize metadata cache: {}", e),
+        };
+        let tmp = path.with_extension("json.tmp");
+        match fs::write(&tmp, json).and_then(|_| fs::rename(&tmp, &path)) {
+            Ok(()) => *written = generation,
+            Err(e) => tracing::error!("Failed to write metadata cache: {}", e),
+        }
+    }
+
+    /// Cached body if it is younger than `ttl` seconds.
+    pub fn get(&self, app: &AppHandle, key: &str, ttl: u64) -> Option<serde_json::Value> {
+        let now = now_secs();
+        self.with_entries(app, |entries| {
+            entries
+                .get(key)
+                .filter(|e| now.saturating_sub(e.fetched_at) < ttl)
+                .map(|e| e.body.clone())
+        })
+    }
+
+    /// Cached body regardless of age, for when TMDB can't be reached.
+    pub fn get_stale(&self, app: &AppHandle, key: &str) -> Option<serde_json::Value> {
+        self.with_entries(app, |entries| entries.get(key).map(|e| e.body.clone()))
+    }
+
+    /// Every cached (key, body) whose key matches, regardless of age.
+    pub fn entries_where(&self, app: &AppHandle, matches: impl Fn(&str) -> bool) -> Vec<(String, serde_json::Value)> {
+        self.with_entries(app, |entries| {
+            entries
+                .iter()
+                .filter(|(k, _)| matches(k))
+                .map(|(k, e)| (k.clone(), e.body.clone()))
+                .collect()
+        })
+    }
+
+    /// Drop matching entries so the next lookup goes to the network. Returns how many were removed.
+    pub fn remove_where(&self, app: &AppHandle, matches: impl Fn(&str) -> bool) -> usize {
+        let removed = self.with_entries(app, |entries| {
+            let before = entries.len();
+            entries.retain(|k, _| !matches(k));
+            let removed = before - entries.len();
+            if removed > 0 {
+                self.generation.fetch_add(1, Ordering::SeqCst);
+            }
+            removed
+        });
+        if removed > 0 {
+            self.persist(app);
+        }
+        removed
+    }
+
+    pub fn insert(&self, app: &AppHandle, key: &str, body: serde_json::Value) {
+        self.with_entries(app, |entries| {
+            let now = now_secs();
+            prune(entries, now);
+            entries.insert(key.to_string(), CacheEntry { fetched_at: now, body });
+            self.generation.fetch_add(1, Ordering::SeqCst);
+        });
+        self.persist(app);
+    }
+}
@@ -0,0 +1,160 @@
+// MPRIS (the D-Bus media player interface on Linux), so desktop media controls, media keys
+// and headset buttons drive FrameX. Fed from the player loop like `PresenceTracker`; the
+// service only exists while mpv runs. Elsewhere this is a no-op.
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use souvlaki::{MediaControlEvent, MediaControls, MediaMetadata, MediaPlayback, MediaPosition, PlatformConfig, SeekDirection};
+    use std::path::Path;
+    use std::time::{Duration, Instant};
+    use tauri::{AppHandle, Manager};
+
+    use crate::mpv_handler::{MpvCommand, MpvState};
+
+    // Clients extrapolate the position from the play state; only jumps larger than this are sent
+    const SEEK_TOLERANCE_SECS: f64 = 2.0;
+    const SEEK_STEP_SECS: f64 = 10.0;
+
+    pub struct MediaSession {
+        controls: Option<MediaControls>,
+        paused: bool,
+        // Position last sent to clients, and when
+        sent: (f64, Instant),
+    }
+
+    fn send(app: &AppHandle, command: MpvCommand) {
+        if let Some(tx) = app.state::<MpvState>().tx.lock().unwrap().as_ref() {
+            let _ = tx.send(command);
+        }
+    }
+
+    fn handle_event(app: &AppHandle, event: MediaControlEvent) {
+        let seconds = |d: Duration, direction: SeekDirection| match direction {
+            SeekDirection::Forward => d.as_secs_f64(),
+            SeekDirection::Backward => -d.as_secs_f64(),
+        };
+        match event {
+            MediaControlEvent::Play => send(app, MpvCommand::Play),
+            MediaControlEvent::Pause => send(app, MpvCommand::Pause),
+            MediaControlEvent::Toggle => send(app, MpvCommand::TogglePause),
+            MediaControlEvent::Stop => send(app, MpvCommand::Stop),
+            MediaControlEvent::Next => send(app, MpvCommand::PlaylistNext),
+            MediaControlEvent::Previous => send(app, MpvCommand::PlaylistPrev),
+            MediaControlEvent::Seek(direction) => send(app, MpvCommand::Seek(seconds(Duration::from_secs_f64(SEEK_STEP_SECS), direction))),
+            MediaControlEvent::SeekBy(direction, by) => send(app, MpvCommand::Seek(seconds(by, direction))),
+            MediaControlEvent::SetPosition(MediaPosition(at)) => send(app, MpvCommand::SeekAbsolute(at.as_secs_f64())),
+            MediaControlEvent::SetVolume(volume) => send(app, MpvCommand::SetVolume((volume * 100.0).clamp(0.0, 130.0))),
+            MediaControlEvent::OpenUri(uri) => {
+                let path = match uri.strip_prefix("file://") {
+                    Some(file) => urlencoding::decode(file).map(|f| f.into_owned()).unwrap_or_else(|_| file.to_string()),
+                    None => uri,
+                };
+                app.state::<crate::queue::QueueState>().play_single(app, path.clone());
+                send(app, MpvCommand::LoadFile(path));
+            }
+            MediaControlEvent::Raise => crate::tray::show_main_window(app),
+            MediaControlEvent::Quit => app.exit(0),
+        }
+    }
+
+    impl MediaSession {
+        pub fn new(app: &AppHandle) -> Self {
+            let config = PlatformConfig { display_name: "FrameX", dbus_name: "framex", hwnd: None };
+            let controls = MediaControls::new(config).ok().and_then(|mut controls| {
+                let handle = app.clone();
+                match controls.attach(move |event| handle_event(&handle, event)) {
+                    Ok(()) => Some(controls),
+                    Err(e) => {
+                        tracing::warn!("MPRIS unavailable: {:?}", e);
+                        None
+                    }
+                }
+            });
+            Self { controls, paused: false, sent: (0.0, Instant::now()) }
+        }
+
+        /// A file finished loading.
+        pub fn start(&mut self, app: &AppHandle, path: Option<&str>, title: Option<String>, duration: Option<f64>) {
+            let Some(controls) = self.controls.as_mut() else {
+                return;
+            };
+            let file = path.map(Path::new);
+            let series = file.filter(|_| !path.is_some_and(|p| p.contains("://"))).and_then(crate::subtitle_downloader::parse_series_episode);
+            let title = title.or_else(|| file.and_then(|f| f.file_name()).and_then(|n| n.to_str()).map(crate::metadata::clean_video_name));
+            let cover = file
+                .and_then(|f| crate::poster_cache::find_poster(app, f))
+                .map(|poster| format!("file://{}", poster.to_string_lossy()));
+            let _ = controls.set_metadata(MediaMetadata {
+                title: title.as_deref(),
+                album: series.as_ref().map(|s| s.show.as_str()),
+                cover_url: cover.as_deref(),
+                duration: duration.filter(|d| *d > 0.0).map(Duration::from_secs_f64),
+                ..Default::default()
+            });
+            self.paused = false;
+            self.push(0.0);
+        }
+
+        /// Latest position; only sent when playback jumped (a seek) rather than ran on.
+        pub fn progress(&mut self, position: f64) {
+            let (sent, at) = self.sent;
+            let expected = if self.paused { sent } else { sent + at.elapsed().as_secs_f64() };
+            if (position - expected).abs() > SEEK_TOLERANCE_SECS {
+                self.push(position);
+            }
+        }
+
+        pub fn pause(&mut self, paused: bool, position: f64) {
+            if self.paused != paused {
+                self.paused = paused;
+                self.push(position);
+            }
+        }
+
+        pub fn volume(&mut self, volume: f64) {
+            if let Some(controls) = self.controls.as_mut() {
+                let _ = controls.set_volume((volume / 100.0).clamp(0.0, 1.0));
+            }
+        }
+
+        pub fn stop(&mut self) {
+            if let Some(controls) = self.controls.as_mut() {
+                let _ = controls.set_playback(MediaPlayback::Stopped);
+                let _ = controls.set_metadata(MediaMetadata::default());
+            }
+        }
+
+        fn push(&mut self, position: f64) {
+            self.sent = (position, Instant::now());
+            let Some(controls) = self.controls.as_mut() else {
+                return;
+            };
+            let progress = Some(MediaPosition(Duration::from_secs_f64(position.max(0.0))));
+            let _ = controls.set_playback(if self.paused {
+                MediaPlayback::Paused { progress }
+            } else {
+                MediaPlayback::Playing { progress }
+            });
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    use tauri::AppHandle;
+
+    pub struct MediaSession;
+
+    impl MediaSession {
+        pub fn new(_app: &AppHandle) -> Self {
+            Self
+        }
+        pub fn start(&mut self, _app: &AppHandle, _path: Option<&str>, _title: Option<String>, _duration: Option<f64>) {}
+        pub fn progress(&mut self, _position: f64) {}
+        pub fn pause(&mut self, _paused: bool, _position: f64) {}
+        pub fn volume(&mut self, _volume: f64) {}
+        pub fn stop(&mut self) {}
+    }
+}
+
+pub use imp::MediaSession;
@@ -0,0 +1,235 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{command, AppHandle, State};
+use regex::Regex;
+
+use crate::mpv_handler::{MpvCommand, MpvState};
+
+// Pristine copies of the bundled config, used by "restore defaults"
+const DEFAULT_MPV_CONF: &str = include_str!("../mpv-config/mpv.conf");
+const DEFAULT_INPUT_CONF: &str = include_str!("../mpv-config/input.conf");
+
+#[derive(Serialize, Clone, Debug)]
+pub struct MpvConfOption {
+    pub name: String,
+    pub value: Option<String>, // None for bare flags like `fullscreen`
+    pub line: usize,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct MpvConfIssue {
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct MpvScript {
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+}
+
+/// Locate the bundled mpv-config directory relative to the exe or the working directory.
+pub fn find_mpv_config_dir() -> Option<PathBuf> {
+    fn find_in(base_dir: &Path) -> Option<PathBuf> {
+        // Try increasing levels of parent directories
+        let attempts = [
+            base_dir.join("mpv-config"),
+            base_dir.join("..").join("mpv-config"),
+            base_dir.join("..").join("..").join("mpv-config"),
+            base_dir.join("..").join("..").join("..").join("mpv-config"),
+            base_dir.join("src-tauri").join("mpv-config"),
+        ];
+
+        for path in &attempts {
+            if path.exists() {
+                // Canonicalize if possible to resolve ..
+                return path.canonicalize().ok().or(Some(path.clone()));
+            }
+        }
+        None
+    }
+
+    std::env::current_exe().ok().and_then(|exe| {
+        exe.parent().and_then(find_in)
+    }).or_else(|| {
+        std::env::current_dir().ok().and_then(|cwd| find_in(&cwd))
+    })
+}
+
+fn get_conf_path() -> Result<PathBuf, String> {
+    find_mpv_config_dir()
+        .map(|d| d.join("mpv.conf"))
+        .ok_or_else(|| "mpv-config directory not found".to_string())
+}
+
+fn option_name_regex() -> Regex {
+    Regex::new(r"^[A-Za-z0-9][A-Za-z0-9\-/]*$").unwrap()
+}
+
+/// Parse the top-level (non-profile) options of an mpv.conf.
+fn parse_options(content: &str) -> Vec<MpvConfOption> {
+    let mut options = Vec::new();
+    for (idx, raw) in content.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        // Options after the first [profile] header belong to that profile
+        if line.starts_with('[') {
+            break;
+        }
+        let (name, value) = match line.split_once('=') {
+            Some((k, v)) => (k.trim().to_string(), Some(v.trim().to_string())),
+            None => (line.to_string(), None),
+        };
+        options.push(MpvConfOption { name, value, line: idx + 1 });
+    }
+    options
+}
+
+fn validate_content(content: &str) -> Vec<MpvConfIssue> {
+    let name_re = option_name_regex();
+    let mut issues = Vec::new();
+    for (idx, raw) in content.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') {
+            if !line.ends_with(']') || line.len() < 3 {
+                issues.push(MpvConfIssue { line: idx + 1, message: format!("Malformed profile header: {}", line) });
+            }
+            continue;
+        }
+        let name = line.split_once('=').map(|(k, _)| k.trim()).unwrap_or(line);
+        if !name_re.is_match(name) {
+            issues.push(MpvConfIssue { line: idx + 1, message: format!("Invalid option name: {}", name) });
+        }
+    }
+    issues
+}
+
+pub(crate) fn send_to_mpv(state: &State<'_, MpvState>, cmd: MpvCommand) {
+    if let Some(tx) = state.tx.lock().unwrap().as_ref() {
+        let _ = tx.send(cmd);
+    }
+}
+
+#[command]
+pub fn mpv_conf_get_options() -> Result<Vec<MpvConfOption>, String> {
+    let content = fs::read_to_string(get_conf_path()?).map_err(|e| e.to_string())?;
+    Ok(parse_options(&content))
+}
+
+#[command]
+pub fn mpv_conf_validate() -> Result<Vec<MpvConfIssue>, String> {
+    let content = fs::read_to_string(get_conf_path()?).map_err(|e| e.to_string())?;
+    Ok(validate_content(&content))
+}
+
+/// Set (or add) a top-level option in mpv.conf and apply it to the running player.
+/// Passing `value: None` removes the option.
+#[command]
+pub fn mpv_conf_set_option(app: AppHandle, state: State<'_, MpvState>, name: String, value: Option<String>) -> Result<(), String> {
+    crate::parental::check_area(&app, "settings")?;
+    let name = name.trim().to_string();
+    if !option_name_regex().is_match(&name) {
+        return Err(format!("Invalid option name: {}", name));
+    }
+    if value.as_deref().is_some_and(|v| v.contains('\n') || v.contains('\r')) {
+        return Err("Option value must be a single line".to_string());
+    }
+
+    let conf_path = get_conf_path()?;
+    let content = fs::read_to_string(&conf_path).unwrap_or_default();
+
+    let new_line = value.as_ref().map(|v| format!("{}={}", name, v));
+    let mut lines: Vec<String> = Vec::new();
+    let mut replaced = false;
+    let mut in_profile = false;
+    let mut profile_start = None;
+
+    for raw in content.lines() {
+        let trimmed = raw.trim();
+        if trimmed.starts_with('[') && !in_profile {
+            in_profile = true;
+            profile_start = Some(lines.len());
+        }
+        let key = trimmed.split_once('=').map(|(k, _)| k.trim()).unwrap_or(trimmed);
+        if !in_profile && !trimmed.starts_with('#') && key == name {
+            // Collapse duplicates into a single entry at the first occurrence
+            if !replaced {
+                if let Some(l) = &new_line {
+                    lines.push(l.clone());
+                }
+                replaced = true;
+            }
+            continue;
+        }
+        lines.push(raw.to_string());
+    }
+
+    if !replaced {
+        if let Some(l) = new_line {
+            // Append before the first profile so it stays a global option
+            match profile_start {
+                Some(idx) => lines.insert(idx, l),
+                None => lines.push(l),
+            }
+        }
+    }
+
+    let mut output = lines.join("\n");
+    output.push('\n');
+    fs::write(&conf_path, output).map_err(|e| e.to_string())?;
+
+    // Hot-reload; options that can't change at runtime take effect on next start
+    match value {
+        Some(value) => send_to_mpv(&state, MpvCommand::SetOption(name, value)),
+        None => send_to_mpv(&state, MpvCommand::ResetOption(name)),
+    }
+    Ok(())
+}
+
+/// Overwrite mpv.conf and input.conf with the bundled defaults.
+#[command]
+pub fn mpv_conf_restore_defaults(app: AppHandle, state: State<'_, MpvState>) -> Result<(), String> {
+    crate::parental::check_area(&app, "settings")?;
+    let config_dir = find_mpv_config_dir().ok_or("mpv-config directory not found")?;
+    fs::write(config_dir.join("mpv.conf"), DEFAULT_MPV_CONF).map_err(|e| e.to_string())?;
+    fs::write(config_dir.join("input.conf"), DEFAULT_INPUT_CONF).map_err(|e| e.to_string())?;
+
+    for opt in parse_options(DEFAULT_MPV_CONF) {
+        send_to_mpv(&state, MpvCommand::SetOption(opt.name, opt.value.unwrap_or_else(|| "yes".to_string())));
+    }
+    Ok(())
+}
+
+#[command]
+pub fn mpv_conf_list_scripts() -> Result<Vec<MpvScript>, String> {
+    let config_dir = find_mpv_config_dir().ok_or("mpv-config directory not found")?;
+    let scripts_dir = config_dir.join("scripts");
+    if !scripts_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut scripts = Vec::new();
+    for entry in fs::read_dir(&scripts_dir).map_err(|e| e.to_string())?.flatten() {
+        let path = entry.path();
+        let is_script = path.extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e == "lua" || e == "js");
+        if !is_script {
+            continue;
+        }
+        scripts.push(MpvScript {
+            name: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+            path: path.to_string_lossy().to_string(),
+            size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+        });
+    }
+    scripts.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(scripts)
+}
@@ -54,6 +54,33 @@ pub enum MpvCommand {
     SetContrast(f64),
     SetSaturation(f64),
     SetGamma(f64),
+
+    // Raw option update (mpv.conf hot-reload)
+    SetOption(String, String),
+    // Put an option back to mpv's built-in default (removed from mpv.conf)
+    ResetOption(String),
+
+    // Apply a chapters sidecar (FFMETADATA) to the current file
+    LoadChapters(String),
+
+    // Replace mpv's playlist with these files and start at the index
+    LoadQueue(Vec<String>, usize),
+    // Add files to the end of mpv's playlist
+    AppendToQueue(Vec<String>),
+    // Add files to mpv's playlist starting at the index
+    InsertIntoQueue(Vec<String>, usize),
+    // Load an external subtitle file for the current video and select it
+    AddSubtitle(String),
+    // Load a live stream with its own options (user agent, cache); undone on the next load
+    LoadStream(String, Vec<(String, String)>),
+    // Move through mpv's playlist
+    PlaylistNext,
+    PlaylistPrev,
+    // Load an mpv script; unload asks it to exit through a script message
+    LoadScript(String),
+    UnloadScript(String),
+    // Set (or with None, remove) one `script-opts` entry
+    SetScriptOpt(String, Option<String>),
 }
 
 // Use Arc<Mutex> so the thread can clear the sender on shutdown
@@ -71,6 +98,32 @@ impl MpvState {
     }
 }
 
+/// Put back the options a `LoadStream` overrode.
+fn restore_options(mpv: &Mpv, overrides: &mut Vec<(String, String)>) {
+    for (name, value) in overrides.drain(..).rev() {
+        let _ = mpv.set_property(&name, value.as_str());
+    }
+}
+
+/// What mpv should open for `path`, remembering resolved network URLs in `net_paths`.
+fn resolve(app: &AppHandle, net_paths: &mut std::collections::HashMap<String, String>, path: &str) -> String {
+    let resolved = if path.starts_with(crate::podcasts::SCHEME) {
+        crate::podcasts::playable_path(app, path)
+    } else {
+        crate::network_sources::playable_path(app, path)
+    };
+    if resolved != path {
+        net_paths.insert(resolved.clone(), path.to_string());
+    }
+    resolved
+}
+
+/// Path in the form mpv expects, without the \\?\ prefix and with forward slashes.
+pub(crate) fn mpv_path(path: &std::path::Path) -> String {
+    let s = path.to_string_lossy();
+    s.strip_prefix(r"\\?\").unwrap_or(&s).replace('\\', "/")
+}
+
 // Initialize the MPV thread if it hasn't been already
 fn ensure_mpv_running(state: &State<'_, MpvState>, wid: Option<i64>, app_handle: AppHandle) {
     let mut tx_guard = state.tx.lock().unwrap();
@@ -98,38 +151,14 @@ fn ensure_mpv_running(state: &State<'_, MpvState>, wid: Option<i64>, app_handle:
         let mut mpv = match Mpv::new() {
             Ok(m) => m,
             Err(e) => {
-                eprintln!("Failed to create MPV instance: {}", e);
+                tracing::error!("Failed to create MPV instance: {}", e);
                 *shared_tx.lock().unwrap() = None;
                 return;
             }
         };
         
-        // Helper to find config dir relative to a base path
-        fn find_mpv_config(base_dir: &std::path::Path) -> Option<std::path::PathBuf> {
-            // Try increasing levels of parent directories
-            let attempts = [
-                base_dir.join("mpv-config"),
-                base_dir.join("..").join("mpv-config"),
-                base_dir.join("..").join("..").join("mpv-config"),
-                base_dir.join("..").join("..").join("..").join("mpv-config"),
-                base_dir.join("src-tauri").join("mpv-config"),
-            ];
-            
-            for path in &attempts {
-                if path.exists() {
-                     // Canonicalize if possible to resolve ..
-                     return path.canonicalize().ok().or(Some(path.clone()));
-                }
-            }
-            None
-        }
-
         // Try to find the config directory based on exe location OR current working directory
-        let config_dir_opt = std::env::current_exe().ok().and_then(|exe| {
-            exe.parent().and_then(|p| find_mpv_config(p))
-        }).or_else(|| {
-            std::env::current_dir().ok().and_then(|cwd| find_mpv_config(&cwd))
-        });
+        let config_dir_opt = crate::mpv_config::find_mpv_config_dir();
 
         // Configure MPV settings
         // IMPORTANT: Set config FIRST so mpv.conf is loaded and osc=no takes effect
@@ -138,13 +167,13 @@ fn ensure_mpv_running(state: &State<'_, MpvState>, wid: Option<i64>, app_handle:
              let clean_config = if config_str.starts_with(r"\\?\") { &config_str[4..] } else { &config_str };
              let final_config = clean_config.replace("\\", "/");
              
-             println!("Setting MPV config-dir: {}", final_config);
+             tracing::info!("Setting MPV config-dir: {}", final_config);
              let _ = mpv.set_property("config", "yes");
              let _ = mpv.set_property("config-dir", final_config.as_str());
              
              // Also set include to explicitly load mpv.conf
              let conf_file = format!("{}/mpv.conf", final_config);
-             println!("Loading config file: {}", conf_file);
+             tracing::debug!("Loading config file: {}", conf_file);
              // include property doesn't exist, so we rely on config-dir
         }
         
@@ -152,7 +181,11 @@ fn ensure_mpv_running(state: &State<'_, MpvState>, wid: Option<i64>, app_handle:
         let _ = mpv.set_property("input-default-bindings", true);
         let _ = mpv.set_property("vo", "gpu");
         let _ = mpv.set_property("hwdec", "auto"); // Enable HW decoding by default
-        let _ = mpv.set_property("log-file", "mpv_debug.log"); // Enable logging
+        // Kept with our logs so it ends up in diagnostics bundles
+        if let Ok(dir) = crate::logging::log_dir(&app_handle) {
+            let _ = std::fs::create_dir_all(&dir);
+            let _ = mpv.set_property("log-file", mpv_path(&dir.join(crate::diagnostics::MPV_LOG)).as_str());
+        }
         let _ = mpv.set_property("msg-level", "all=warn"); // Set log level
         if let Some(w) = wid {
             let _ = mpv.set_property("wid", w);
@@ -172,12 +205,15 @@ fn ensure_mpv_running(state: &State<'_, MpvState>, wid: Option<i64>, app_handle:
         let _ = mpv.observe_property("volume", libmpv2::Format::Double, 0);
         let _ = mpv.observe_property("pause", libmpv2::Format::Flag, 0);
         let _ = mpv.observe_property("hwdec-current", libmpv2::Format::String, 0);
+        let _ = mpv.observe_property("playlist-pos", libmpv2::Format::Int64, 0);
+        // Now-playing track from Shoutcast/Icecast streams
+        let _ = mpv.observe_property("metadata/by-key/icy-title", libmpv2::Format::String, 0);
         
         // Load custom scripts (LOAD ALL LUA FILES)
         if let Some(config_dir) = config_dir_opt {
              let scripts_dir = config_dir.join("scripts");
              if scripts_dir.exists() {
-                 println!("Loading scripts from: {:?}", scripts_dir);
+                 tracing::debug!("Loading scripts from: {:?}", scripts_dir);
                  if let Ok(entries) = std::fs::read_dir(scripts_dir) {
                      for entry in entries.flatten() {
                          let path = entry.path();
@@ -186,9 +222,9 @@ fn ensure_mpv_running(state: &State<'_, MpvState>, wid: Option<i64>, app_handle:
                              let clean_script = if script_str.starts_with(r"\\?\") { &script_str[4..] } else { &script_str };
                              let final_script = clean_script.replace("\\", "/");
                              
-                             println!("Loading script: {}", final_script);
+                             tracing::debug!("Loading script: {}", final_script);
                              if let Err(e) = mpv.command("load-script", &[final_script.as_str()]) {
-                                  eprintln!("Failed load-script {}: {}", final_script, e);
+                                  tracing::error!("Failed load-script {}: {}", final_script, e);
                                   let _ = mpv.command("script-load", &[final_script.as_str()]);
                              }
                          }
@@ -196,10 +232,10 @@ fn ensure_mpv_running(state: &State<'_, MpvState>, wid: Option<i64>, app_handle:
                  }
              }
         } else {
-             println!("CRITICAL: No config directory found!");
+             tracing::error!("CRITICAL: No config directory found!");
         }
         
-        println!("MPV initialized (OSC disabled)");
+        tracing::info!("MPV initialized (OSC disabled)");
         
         // Bind keyboard shortcuts for controls
         let _ = mpv.command("keybind", &["1", "seek -10"]);
@@ -220,7 +256,7 @@ fn ensure_mpv_running(state: &State<'_, MpvState>, wid: Option<i64>, app_handle:
         let _ = mpv.command("keybind", &["SPACE", "cycle pause"]);
         let _ = mpv.command("keybind", &["f", "cycle fullscreen"]);
         let _ = mpv.command("keybind", &["m", "cycle mute"]);
-        println!("Keyboard shortcuts bound!");
+        tracing::info!("Keyboard shortcuts bound!");
         
         // DIRECT mouse bindings
         let _ = mpv.command("keybind", &["MBTN_LEFT", "script-message click_evt"]);
@@ -229,15 +265,30 @@ fn ensure_mpv_running(state: &State<'_, MpvState>, wid: Option<i64>, app_handle:
         // Unused OSD helper removed
 
         let mut show_osd = false;
+        let mut autosave = crate::watch_history::PositionAutosave::new();
+        let mut presence = crate::discord_rpc::PresenceTracker::new();
+        let mut anime_sync = crate::anime_sync::AnimeScrobbler::new();
+        let mut lastfm = crate::lastfm::LastfmScrobbler::new();
+        let mut segments = crate::segments::SegmentSkipper::new();
+        let mut media_session = crate::mpris::MediaSession::new(&app_handle);
+        // Resolved network URLs (they may carry credentials) -> the `net://` path shown to the user
+        let mut net_paths: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+        // Options a LoadStream changed, with their previous values
+        let mut stream_overrides: Vec<(String, String)> = Vec::new();
 
         loop {
             // Check for commands from Tauri
             while let Ok(cmd) = rx.try_recv() {
                 match cmd {
                     MpvCommand::LoadFile(path) => {
-                        let file_path = if path.starts_with("http") { path } else { path };
-                        println!("Loading: {}", file_path);
-                        if let Err(e) = mpv.command("loadfile", &[&file_path]) { eprintln!("Error: {}", e); }
+                        restore_options(&mpv, &mut stream_overrides);
+                        let file_path = resolve(&app_handle, &mut net_paths, &path);
+                        tracing::info!("Loading: {}", path);
+                        // Pick up generated chapters for unchaptered files
+                        let chapters = crate::ai::chapters::chapters_sidecar_path(std::path::Path::new(&file_path));
+                        let chapters_file = if chapters.exists() { chapters.to_string_lossy().to_string() } else { String::new() };
+                        let _ = mpv.set_property("chapters-file", chapters_file.as_str());
+                        if let Err(e) = mpv.command("loadfile", &[&file_path]) { tracing::error!("Error: {}", e); }
                         let _ = mpv.set_property("pause", false);
                     },
                     MpvCommand::Play => { let _ = mpv.set_property("pause", false); },
@@ -252,6 +303,8 @@ fn ensure_mpv_running(state: &State<'_, MpvState>, wid: Option<i64>, app_handle:
                     MpvCommand::CycleSubtitles => { let _ = mpv.command("cycle", &["sub"]); },
                     MpvCommand::CycleAudio => { let _ = mpv.command("cycle", &["audio"]); },
                     MpvCommand::Stop => { let _ = mpv.command("stop", &[]); },
+                    MpvCommand::PlaylistNext => { let _ = mpv.command("playlist-next", &[]); },
+                    MpvCommand::PlaylistPrev => { let _ = mpv.command("playlist-prev", &[]); },
                     
                     // Settings Handlers
                     MpvCommand::GetTracks => {
@@ -338,6 +391,93 @@ fn ensure_mpv_running(state: &State<'_, MpvState>, wid: Option<i64>, app_handle:
                     MpvCommand::SetContrast(val) => { let _ = mpv.set_property("contrast", val as i64); },
                     MpvCommand::SetSaturation(val) => { let _ = mpv.set_property("saturation", val as i64); },
                     MpvCommand::SetGamma(val) => { let _ = mpv.set_property("gamma", val as i64); },
+
+                    MpvCommand::SetOption(name, value) => {
+                        if let Err(e) = mpv.set_property(&name, value.as_str()) {
+                            tracing::warn!("Option {} not applied live: {}", name, e);
+                        }
+                    },
+                    MpvCommand::ResetOption(name) => {
+                        let default = mpv.get_property::<String>(&format!("option-info/{}/default-value", name));
+                        if let Err(e) = default.and_then(|value| mpv.set_property(&name, value.as_str())) {
+                            tracing::warn!("Option {} not reset live, the default applies on restart: {}", name, e);
+                        }
+                    },
+                    MpvCommand::LoadChapters(chapters_file) => {
+                        // chapters-file is read at load time, so reload in place at the current position
+                        let current: Option<String> = mpv.get_property("path").ok();
+                        let pos: f64 = mpv.get_property("time-pos").unwrap_or(0.0);
+                        let paused: bool = mpv.get_property("pause").unwrap_or(false);
+                        let _ = mpv.set_property("chapters-file", chapters_file.as_str());
+                        if let Some(current) = current {
+                            let _ = mpv.set_property("start", format!("{}", pos).as_str());
+                            if let Err(e) = mpv.command("loadfile", &[&current]) { tracing::error!("Error: {}", e); }
+                            let _ = mpv.set_property("pause", paused);
+                        }
+                    },
+                    MpvCommand::LoadQueue(items, start) => {
+                        restore_options(&mpv, &mut stream_overrides);
+                        let _ = mpv.set_property("chapters-file", "");
+                        // Load the start item first so mpv never begins playing items[0],
+                        // then move the ones before it to the front
+                        let start = start.min(items.len().saturating_sub(1));
+                        let order = items.iter().skip(start).chain(items.iter().take(start));
+                        for (i, item) in order.enumerate() {
+                            let mode = if i == 0 { "replace" } else { "append" };
+                            if let Err(e) = mpv.command("loadfile", &[&resolve(&app_handle, &mut net_paths, item), mode]) { tracing::error!("Error: {}", e); }
+                        }
+                        for i in 0..start {
+                            let _ = mpv.command("playlist-move", &[&(items.len() - start + i).to_string(), &i.to_string()]);
+                        }
+                        let _ = mpv.set_property("pause", false);
+                    },
+                    MpvCommand::AppendToQueue(items) => {
+                        for item in &items {
+                            if let Err(e) = mpv.command("loadfile", &[&resolve(&app_handle, &mut net_paths, item), "append"]) { tracing::error!("Error: {}", e); }
+                        }
+                    },
+                    MpvCommand::AddSubtitle(path) => {
+                        // Senders pass a UTF-8 file (see subtitle_format::utf8_copy)
+                        if let Err(e) = mpv.command("sub-add", &[&path, "select"]) { tracing::error!("Error: {}", e); }
+                    },
+                    MpvCommand::LoadStream(url, options) => {
+                        restore_options(&mpv, &mut stream_overrides);
+                        for (name, value) in options {
+                            let previous = mpv.get_property::<String>(&name).unwrap_or_default();
+                            match mpv.set_property(&name, value.as_str()) {
+                                Ok(()) => stream_overrides.push((name, previous)),
+                                Err(e) => tracing::warn!("Stream option {} not applied: {}", name, e),
+                            }
+                        }
+                        tracing::info!("Loading stream: {}", url);
+                        let _ = mpv.set_property("chapters-file", "");
+                        if let Err(e) = mpv.command("loadfile", &[&url]) { tracing::error!("Error: {}", e); }
+                        let _ = mpv.set_property("pause", false);
+                    },
+                    MpvCommand::LoadScript(path) => {
+                        if let Err(e) = mpv.command("load-script", &[&path]) { tracing::error!("Failed load-script {}: {}", path, e); }
+                    },
+                    MpvCommand::UnloadScript(name) => {
+                        let _ = mpv.command("script-message-to", &[&name, "framex-unload"]);
+                    },
+                    MpvCommand::SetScriptOpt(key, value) => {
+                        let result = match value {
+                            Some(value) => mpv.command("change-list", &["script-opts", "append", &format!("{}={}", key, value)]),
+                            None => mpv.command("change-list", &["script-opts", "remove", &key]),
+                        };
+                        if let Err(e) = result { tracing::warn!("script-opts {} not applied: {}", key, e); }
+                    },
+                    MpvCommand::InsertIntoQueue(items, at) => {
+                        for (i, item) in items.iter().enumerate() {
+                            if let Err(e) = mpv.command("loadfile", &[&resolve(&app_handle, &mut net_paths, item), "append"]) { tracing::error!("Error: {}", e); continue; }
+                            // Appended at the end; move it up into place
+                            let last: i64 = mpv.get_property("playlist-count").unwrap_or(1) - 1;
+                            let to = (at + i) as i64;
+                            if last != to {
+                                let _ = mpv.command("playlist-move", &[&last.to_string(), &to.to_string()]);
+                            }
+                        }
+                    },
                 }
             }
             
@@ -351,41 +491,104 @@ fn ensure_mpv_running(state: &State<'_, MpvState>, wid: Option<i64>, app_handle:
                 // ClientMessage handler removed for now to fix build
 
                 Some(Ok(Event::Shutdown)) => {
-                    println!("MPV Shutdown");
+                    tracing::info!("MPV Shutdown");
+                    autosave.stop(&app_handle);
+                    presence.stop(&app_handle);
+                    media_session.stop();
                     break; 
                 },
-                Some(Err(e)) => eprintln!("MPV Error: {}", e),
+                Some(Err(e)) => tracing::error!("MPV Error: {}", e),
                 Some(Ok(Event::PropertyChange { name, .. })) => {
                     if name == "time-pos" {
-                        let pos: f64 = mpv.get_property("time-pos").unwrap_or(0.0);
-                        let dur: f64 = mpv.get_property("duration").unwrap_or(1.0);
-                        let _ = app_handle.emit("mpv-progress", (pos, dur));
+                        let pos: Option<f64> = mpv.get_property("time-pos").ok();
+                        let dur: Option<f64> = mpv.get_property("duration").ok();
+                        let _ = app_handle.emit("mpv-progress", (pos.unwrap_or(0.0), dur.unwrap_or(1.0)));
+                        // time-pos goes unavailable while stopping; keep the last real position
+                        if let (Some(pos), Some(dur)) = (pos, dur) {
+                            autosave.progress(&app_handle, pos, dur);
+                            presence.progress(&app_handle, pos, dur);
+                            anime_sync.progress(&app_handle, pos, dur);
+                            lastfm.progress(&app_handle, pos);
+                            media_session.progress(pos);
+                            crate::watch_party::progress(&app_handle, pos);
+                            if let Some(to) = segments.progress(&app_handle, pos) {
+                                let _ = mpv.command("seek", &[&to.to_string(), "absolute"]);
+                            }
+                        }
                     } else if name == "volume" {
                         let vol: f64 = mpv.get_property("volume").unwrap_or(100.0);
                         let _ = app_handle.emit("mpv-volume", vol);
+                        media_session.volume(vol);
                     } else if name == "pause" {
                         let paused: bool = mpv.get_property("pause").unwrap_or(false);
                         let _ = app_handle.emit("mpv-pause", paused);
+                        // keep-open also pauses at the end of the file
+                        if paused {
+                            autosave.flush(&app_handle);
+                        }
+                        presence.pause(&app_handle, paused);
+                        let pos: f64 = mpv.get_property("time-pos").unwrap_or(0.0);
+                        media_session.pause(paused, pos);
+                        crate::watch_party::paused(&app_handle, paused, pos);
                     } else if name == "hwdec-current" {
                          let cur: String = mpv.get_property("hwdec-current").unwrap_or("no".into());
                          let _ = app_handle.emit("mpv-hwdec-change", cur);
+                    } else if name == "playlist-pos" {
+                        // mpv advanced (EOF, or next/prev); mirror it in the queue state
+                        let pos: i64 = mpv.get_property("playlist-pos").unwrap_or(-1);
+                        app_handle.state::<crate::queue::QueueState>().set_index(&app_handle, pos);
+                    } else if name == "metadata/by-key/icy-title" {
+                        let title = mpv.get_property::<String>("metadata/by-key/icy-title").ok().map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+                        let _ = app_handle.emit("mpv-stream-title", title.clone());
+                        presence.stream_title(&app_handle, title);
                     }
                 }
+                Some(Ok(Event::FileLoaded)) => {
+                    // A start offset from a chapters reload must not leak into the next file
+                    let _ = mpv.set_property("start", "none");
+                    let path: Option<String> = mpv.get_property::<String>("path").ok().map(|p| net_paths.get(&p).cloned().unwrap_or(p));
+                    // Catches files reached through mpv's own playlist
+                    if path.as_deref().is_some_and(|p| crate::parental::blocks_playback(&app_handle, p)) {
+                        let _ = mpv.command("stop", &[]);
+                    } else {
+                        autosave.start(&app_handle, path.clone());
+                        media_session.start(&app_handle, path.as_deref(), mpv.get_property("media-title").ok(), mpv.get_property("duration").ok());
+                        crate::watch_party::file_loaded(&app_handle, path.as_deref());
+                        anime_sync.start(&app_handle, path.as_deref());
+                        lastfm.start(&app_handle, path.as_deref(), crate::lastfm::read_tags(&mpv));
+                        segments.start(&app_handle, path.as_deref());
+                        presence.start(&app_handle, path, mpv.get_property("media-title").ok());
+                    }
+                }
+                Some(Ok(Event::PlaybackRestart)) => {
+                    // Playback resumed after loading or a seek
+                    let pos: f64 = mpv.get_property("time-pos").unwrap_or(0.0);
+                    crate::watch_party::restarted(&app_handle, mpv.get_property("pause").unwrap_or(false), pos);
+                }
+                Some(Ok(Event::EndFile(_))) => {
+                    autosave.stop(&app_handle);
+                    presence.stop(&app_handle);
+                    media_session.stop();
+                    anime_sync.stop();
+                    lastfm.stop();
+                    segments.stop(&app_handle);
+                    crate::watch_party::file_loaded(&app_handle, None);
+                }
                 Some(Ok(event)) => {
-                   // println!("MPV Event: {:?}", event); // Quiet logs
+                    tracing::trace!("MPV Event: {:?}", event);
                 }
                 _ => {}
             }
         }
         
         // IMPORTANT: Clear the sender so next load_video will spawn a new MPV instance
-        println!("MPV Thread Exited - Clearing state for restart");
+        tracing::info!("MPV Thread Exited - Clearing state for restart");
         *shared_tx.lock().unwrap() = None;
     });
 }
 
-#[command]
-pub fn load_video(window: Window, state: State<'_, MpvState>, path: String) {
+/// Spawn the mpv thread embedded in this window if it isn't running yet.
+pub fn start_player(window: &Window, state: &State<'_, MpvState>) {
     let wid = window.window_handle().ok().and_then(|h| {
         match h.as_raw() {
             RawWindowHandle::Win32(w) => Some(w.hwnd.get() as i64),
@@ -393,10 +596,19 @@ pub fn load_video(window: Window, state: State<'_, MpvState>, path: String) {
         }
     });
 
-    ensure_mpv_running(&state, wid, window.app_handle().clone());
+    ensure_mpv_running(state, wid, window.app_handle().clone());
+}
+
+#[command]
+pub fn load_video(window: Window, state: State<'_, MpvState>, path: String) -> Result<(), String> {
+    crate::parental::Guard::new(window.app_handle()).check_playable(&path)?;
+    start_player(&window, &state);
+    window.app_handle().state::<crate::queue::QueueState>().play_single(window.app_handle(), path.clone());
+    let _ = crate::config::save_last_video_internal(window.app_handle(), path.clone());
     if let Some(tx) = state.tx.lock().unwrap().as_ref() {
         let _ = tx.send(MpvCommand::LoadFile(path));
     }
+    Ok(())
 }
 
 #[command]
@@ -422,8 +634,8 @@ pub fn set_volume(state: State<'_, MpvState>, volume: f64) {
 
 // Keep old play_video for compatibility
 #[command]
-pub fn play_video(window: Window, state: State<'_, MpvState>, path: String) {
-    load_video(window, state, path);
+pub fn play_video(window: Window, state: State<'_, MpvState>, path: String) -> Result<(), String> {
+    load_video(window, state, path)
 }
 
 #[command]
@@ -618,14 +830,8 @@ pub fn mpv_set_gamma(state: State<'_, MpvState>, value: f64) {
 
 #[command]
 pub fn mpv_load_url(window: Window, state: State<'_, MpvState>, url: String) {
-    let wid = window.window_handle().ok().and_then(|h| {
-        match h.as_raw() {
-            RawWindowHandle::Win32(w) => Some(w.hwnd.get() as i64),
-            _ => None,
-        }
-    });
-
-    ensure_mpv_running(&state, wid, window.app_handle().clone());
+    start_player(&window, &state);
+    window.app_handle().state::<crate::queue::QueueState>().play_single(window.app_handle(), url.clone());
     if let Some(tx) = state.tx.lock().unwrap().as_ref() {
         let _ = tx.send(MpvCommand::LoadFile(url));
     }
@@ -0,0 +1,233 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use walkdir::WalkDir;
+
+use crate::file_scanner::is_video;
+use crate::metadata::{parse_episode, show_dir, MovieInfo, TvInfo};
+use crate::xml::escape_xml;
+
+const XML_HEADER: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n";
+
+#[derive(Serialize, Debug, Default)]
+pub struct NfoExportSummary {
+    pub written: Vec<String>,
+    pub skipped: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+fn tag(out: &mut String, name: &str, value: &str) {
+    if !value.is_empty() && value != "Unknown" {
+        out.push_str(&format!("  <{0}>{1}</{0}>\n", name, escape_xml(value)));
+    }
+}
+
+fn rating_block(out: &mut String, value: f64, votes: u64) {
+    out.push_str(&format!(
+        "  <ratings>\n    <rating name=\"themoviedb\" max=\"10\" default=\"true\">\n      <value>{:.1}</value>\n      <votes>{}</votes>\n    </rating>\n  </ratings>\n",
+        value, votes
+    ));
+}
+
+fn movie_nfo(info: &MovieInfo) -> String {
+    let mut out = String::from(XML_HEADER);
+    out.push_str("<movie>\n");
+    tag(&mut out, "title", &info.title);
+    tag(&mut out, "plot", &info.overview);
+    tag(&mut out, "tagline", &info.tagline);
+    if info.runtime > 0 {
+        tag(&mut out, "runtime", &info.runtime.to_string());
+    }
+    tag(&mut out, "premiered", &info.release_date);
+    if let Some(year) = info.release_date.get(..4).filter(|y| y.chars().all(|c| c.is_ascii_digit())) {
+        tag(&mut out, "year", year);
+    }
+    rating_block(&mut out, info.vote_average, info.vote_count);
+    if let Some(cert) = &info.certification {
+        tag(&mut out, "mpaa", &cert.rating);
+    }
+    for genre in &info.genres {
+        tag(&mut out, "genre", genre);
+    }
+    out.push_str(&format!("  <uniqueid type=\"tmdb\" default=\"true\">{}</uniqueid>\n", info.tmdb_id));
+    if let Some(imdb_id) = &info.imdb_id {
+        out.push_str(&format!("  <uniqueid type=\"imdb\">{}</uniqueid>\n", escape_xml(imdb_id)));
+    }
+    out.push_str("</movie>\n");
+    out
+}
+
+fn tvshow_nfo(info: &TvInfo) -> String {
+    let mut out = String::from(XML_HEADER);
+    out.push_str("<tvshow>\n");
+    tag(&mut out, "title", &info.title);
+    tag(&mut out, "plot", &info.overview);
+    tag(&mut out, "tagline", &info.tagline);
+    tag(&mut out, "premiered", &info.first_air_date);
+    tag(&mut out, "status", &info.status);
+    tag(&mut out, "season", &info.number_of_seasons.to_string());
+    tag(&mut out, "episode", &info.number_of_episodes.to_string());
+    rating_block(&mut out, info.vote_average, info.vote_count);
+    if let Some(cert) = &info.certification {
+        tag(&mut out, "mpaa", &cert.rating);
+    }
+    for genre in &info.genres {
+        tag(&mut out, "genre", genre);
+    }
+    out.push_str(&format!("  <uniqueid type=\"tmdb\" default=\"true\">{}</uniqueid>\n", info.tmdb_id));
+    out.push_str("</tvshow>\n");
+    out
+}
+
+fn episode_nfo(show: &str, season: u32, episode: u32) -> String {
+    let mut out = String::from(XML_HEADER);
+    out.push_str("<episodedetails>\n");
+    // No <title>: we don't fetch episode names, and a made-up one would override the scraper's
+    tag(&mut out, "showtitle", show);
+    tag(&mut out, "season", &season.to_string());
+    tag(&mut out, "episode", &episode.to_string());
+    out.push_str("</episodedetails>\n");
+    out
+}
+
+/// `<movie>` NFO for a downloaded online video, from yt-dlp's `.info.json`: the uploader
+/// goes in `<studio>`, the page URL in a `<uniqueid>` of its site.
+pub fn online_video_nfo(info: &serde_json::Value) -> String {
+    let text = |key: &str| info.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+    let mut out = String::from(XML_HEADER);
+    out.push_str("<movie>\n");
+    tag(&mut out, "title", &text("title"));
+    tag(&mut out, "plot", &text("description"));
+    tag(&mut out, "studio", &text("uploader"));
+    if let Some(minutes) = info.get("duration").and_then(|d| d.as_f64()).map(|d| (d / 60.0).round() as u64).filter(|m| *m > 0) {
+        tag(&mut out, "runtime", &minutes.to_string());
+    }
+    // upload_date is YYYYMMDD
+    let date = text("upload_date");
+    if date.len() == 8 && date.chars().all(|c| c.is_ascii_digit()) {
+        tag(&mut out, "premiered", &format!("{}-{}-{}", &date[..4], &date[4..6], &date[6..]));
+        tag(&mut out, "year", &date[..4]);
+    }
+    for genre in info.get("categories").and_then(|c| c.as_array()).into_iter().flatten().filter_map(|g| g.as_str()) {
+        tag(&mut out, "genre", genre);
+    }
+    let site = text("extractor_key").to_lowercase();
+    let url = text("webpage_url");
+    if !url.is_empty() {
+        out.push_str(&format!("  <uniqueid type=\"{}\" default=\"true\">{}</uniqueid>\n", escape_xml(if site.is_empty() { "url" } else { &site }), escape_xml(&url)));
+    }
+    out.push_str("</movie>\n");
+    out
+}
+
+fn write_nfo(path: &Path, content: &str, summary: &mut NfoExportSummary) {
+    match fs::write(path, content) {
+        Ok(()) => summary.written.push(path.to_string_lossy().to_string()),
+        Err(e) => summary.errors.push(format!("{}: {}", path.display(), e)),
+    }
+}
+
+/// `<stem>.nfo`, which both Kodi and Jellyfin read for movies and episodes.
+fn sidecar_nfo(video: &Path) -> PathBuf {
+    video.with_extension("nfo")
+}
+
+async fn export_show(app: &AppHandle, dir: &Path, episodes: &[PathBuf], overwrite: bool, summary: &mut NfoExportSummary) {
+    let info = match crate::metadata::fetch_tv_info(app.clone(), dir.to_string_lossy().to_string()).await {
+        Ok(info) => info,
+        Err(e) => {
+            summary.errors.push(format!("{}: {}", dir.display(), e));
+            return;
+        }
+    };
+
+    let show_nfo = dir.join("tvshow.nfo");
+    if overwrite || !show_nfo.exists() {
+        write_nfo(&show_nfo, &tvshow_nfo(&info), summary);
+    } else {
+        summary.skipped.push(show_nfo.to_string_lossy().to_string());
+    }
+
+    for ep in episodes {
+        let nfo = sidecar_nfo(ep);
+        let name = ep.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        match parse_episode(name) {
+            Some((season, episode)) if overwrite || !nfo.exists() => write_nfo(&nfo, &episode_nfo(&info.title, season, episode), summary),
+            _ => summary.skipped.push(nfo.to_string_lossy().to_string()),
+        }
+    }
+}
+
+async fn export_movie(app: &AppHandle, video: &Path, overwrite: bool, summary: &mut NfoExportSummary) {
+    let nfo = sidecar_nfo(video);
+    if !overwrite && nfo.exists() {
+        summary.skipped.push(nfo.to_string_lossy().to_string());
+        return;
+    }
+    match crate::metadata::fetch_movie_info(app.clone(), video.to_string_lossy().to_string()).await {
+        Ok(info) => write_nfo(&nfo, &movie_nfo(&info), summary),
+        Err(e) => summary.errors.push(format!("{}: {}", video.display(), e)),
+    }
+}
+
+/// Write Kodi/Jellyfin `.nfo` sidecars for a single video or a show folder.
+#[tauri::command]
+pub async fn export_nfo(app: AppHandle, path: String, overwrite: Option<bool>) -> Result<NfoExportSummary, String> {
+    let overwrite = overwrite.unwrap_or(false);
+    let target = Path::new(&path);
+    let mut summary = NfoExportSummary::default();
+
+    if target.is_dir() {
+        let episodes: Vec<PathBuf> = WalkDir::new(target)
+            .max_depth(2)
+            .into_iter()
+            .flatten()
+            .map(|e| e.into_path())
+            .filter(|p| is_video(p))
+            .collect();
+        export_show(&app, target, &episodes, overwrite, &mut summary).await;
+    } else if is_video(target) {
+        let name = target.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        match (parse_episode(name), show_dir(target)) {
+            (Some(_), Some(dir)) => export_show(&app, &dir, &[target.to_path_buf()], overwrite, &mut summary).await,
+            _ => export_movie(&app, target, overwrite, &mut summary).await,
+        }
+    } else {
+        return Err("Not a video file or folder".to_string());
+    }
+    Ok(summary)
+}
+
+/// Export NFOs for every video under a library root, grouping episodes by show folder.
+#[tauri::command]
+pub async fn export_library_nfo(app: AppHandle, root: String, overwrite: Option<bool>) -> Result<NfoExportSummary, String> {
+    let overwrite = overwrite.unwrap_or(false);
+    if !Path::new(&root).is_dir() {
+        return Err("Not a valid directory".to_string());
+    }
+
+    let mut shows: Vec<(PathBuf, Vec<PathBuf>)> = Vec::new();
+    let mut movies = Vec::new();
+    for path in WalkDir::new(&root).into_iter().flatten().map(|e| e.into_path()).filter(|p| is_video(p)) {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        match (parse_episode(name), show_dir(&path)) {
+            (Some(_), Some(dir)) => match shows.iter_mut().find(|(d, _)| *d == dir) {
+                Some((_, eps)) => eps.push(path),
+                None => shows.push((dir, vec![path])),
+            },
+            _ => movies.push(path),
+        }
+    }
+
+    let mut summary = NfoExportSummary::default();
+    for (dir, episodes) in &shows {
+        export_show(&app, dir, episodes, overwrite, &mut summary).await;
+    }
+    for movie in &movies {
+        export_movie(&app, movie, overwrite, &mut summary).await;
+    }
+
+    tracing::info!("📝 NFO export: {} written, {} skipped, {} errors", summary.written.len(), summary.skipped.len(), summary.errors.len());
+    Ok(summary)
+}
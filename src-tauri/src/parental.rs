@@ -0,0 +1,376 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{command, AppHandle, Emitter, Manager, State};
+
+use crate::config::{load_config, save_config, ParentalLock, ParentalSettings};
+use crate::metadata::Certification;
+
+/// App areas the UI asks about before opening them (see `is_area_locked`).
+pub const AREAS: &[&str] = &["settings", "downloads", "torrents", "iptv", "history"];
+const MAX_ATTEMPTS: u32 = 5;
+const LOCKOUT: Duration = Duration::from_secs(60);
+
+/// The unlock session and wrong-PIN throttling; both reset when the app restarts, which locks it.
+pub struct ParentalState {
+    unlocked_until: Mutex<Option<Instant>>,
+    failures: Mutex<(u32, Option<Instant>)>, // wrong PINs in a row, and when the lockout ends
+}
+
+impl ParentalState {
+    pub fn new() -> Self {
+        Self { unlocked_until: Mutex::new(None), failures: Mutex::new((0, None)) }
+    }
+
+    fn remaining(&self) -> Option<Duration> {
+        let until = (*self.unlocked_until.lock().unwrap())?;
+        until.checked_duration_since(Instant::now()).filter(|d| !d.is_zero())
+    }
+}
+
+/// Minimum viewer age a rating stands for: US film and TV boards, the letter ratings used
+/// in the UK and France, and the plain ages ("12", "16+", "FSK 18") most other boards use.
+pub fn rating_age(rating: &str) -> Option<u8> {
+    let rating = rating.trim().to_uppercase();
+    let age = match rating.as_str() {
+        "G" | "U" | "TP" | "AL" | "TV-Y" | "TV-G" => 0,
+        "TV-Y7" | "TV-Y7-FV" => 7,
+        "PG" | "TV-PG" => 10,
+        "PG-13" => 13,
+        "TV-14" => 14,
+        "R" | "TV-MA" => 17,
+        "NC-17" | "R18" | "X" => 18,
+        other => {
+            let digits: String = other.trim_start_matches(|c: char| !c.is_ascii_digit()).chars().take_while(char::is_ascii_digit).collect();
+            return digits.parse().ok().filter(|age| *age <= 21);
+        }
+    };
+    Some(age)
+}
+
+/// Remember the rating fetched for a movie file or show folder, for `Guard`.
+pub fn record_rating(app: &AppHandle, path: &str, certification: &Certification) {
+    let result = crate::watch_history::with_db(app, |conn| {
+        conn.execute(
+            "INSERT OR REPLACE INTO content_ratings (path, rating, country) VALUES (?1, ?2, ?3)",
+            (path, &certification.rating, &certification.country),
+        )
+    });
+    if let Err(e) = result {
+        tracing::error!("Failed to store rating for {}: {}", path, e);
+    }
+}
+
+/// What's off limits right now. Built per request; everything is allowed while no PIN is set
+/// or the unlock session is running.
+pub struct Guard {
+    settings: Option<ParentalSettings>,
+    ages: HashMap<String, u8>,
+    collection_items: Vec<(String, bool)>, // items of locked collections, and whether to hide them
+}
+
+impl Guard {
+    pub fn new(app: &AppHandle) -> Self {
+        let settings = load_config(app).parental;
+        let unrestricted = Self { settings: None, ages: HashMap::new(), collection_items: Vec::new() };
+        if settings.pin_hash.is_none() || app.state::<ParentalState>().remaining().is_some() {
+            return unrestricted;
+        }
+
+        let ages = match settings.max_age {
+            Some(_) => crate::watch_history::with_db(app, |conn| {
+                let mut stmt = conn.prepare("SELECT path, rating FROM content_ratings")?;
+                let rows = stmt.query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?)))?;
+                rows.collect::<rusqlite::Result<Vec<_>>>()
+            })
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(path, rating)| Some((path, rating_age(&rating)?)))
+            .collect(),
+            None => HashMap::new(),
+        };
+        let collection_items = settings
+            .locked_collections
+            .iter()
+            .flat_map(|lock| {
+                let items = crate::playlist::find_items(app, &lock.target).map(|(_, items)| items).unwrap_or_default();
+                items.into_iter().map(|item| (item, lock.hide))
+            })
+            .collect();
+        Self { settings: Some(settings), ages, collection_items }
+    }
+
+    /// Some(hide) when a locked folder or collection covers the path.
+    fn lock_for(&self, settings: &ParentalSettings, path: &str) -> Option<bool> {
+        let target = Path::new(path);
+        let folder = settings.locked_folders.iter().filter(|l| target.starts_with(&l.target)).map(|l| l.hide);
+        let collection = self.collection_items.iter().filter(|(item, _)| item == path).map(|(_, hide)| *hide);
+        folder.chain(collection).reduce(|a, b| a || b)
+    }
+
+    /// The rating of the file, or the nearest folder above it with one (a show's rating covers its episodes).
+    fn age_allowed(&self, settings: &ParentalSettings, path: &str) -> bool {
+        let Some(max_age) = settings.max_age else {
+            return true;
+        };
+        Path::new(path)
+            .ancestors()
+            .find_map(|p| self.ages.get(p.to_string_lossy().as_ref()))
+            .is_none_or(|age| *age <= max_age)
+    }
+
+    pub fn visible(&self, path: &str) -> bool {
+        let Some(settings) = &self.settings else {
+            return true;
+        };
+        self.lock_for(settings, path) != Some(true) && self.age_allowed(settings, path)
+    }
+
+    pub fn playable(&self, path: &str) -> bool {
+        let Some(settings) = &self.settings else {
+            return true;
+        };
+        self.lock_for(settings, path).is_none() && self.age_allowed(settings, path)
+    }
+
+    pub fn check_playable(&self, path: &str) -> Result<(), String> {
+        if self.playable(path) {
+            Ok(())
+        } else {
+            Err("This video is blocked by parental controls".to_string())
+        }
+    }
+
+    fn collection_lock(&self, id: &str) -> Option<bool> {
+        let settings = self.settings.as_ref()?;
+        settings.locked_collections.iter().find(|l| l.target == id).map(|l| l.hide)
+    }
+
+    pub fn collection_visible(&self, id: &str) -> bool {
+        self.collection_lock(id) != Some(true)
+    }
+
+    pub fn check_collection(&self, id: &str) -> Result<(), String> {
+        match self.collection_lock(id) {
+            Some(_) => Err("This collection is locked by parental controls".to_string()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Whether mpv just loaded something it mustn't play (e.g. reached through its own playlist);
+/// emits `parental-blocked` so the UI can say why it stopped.
+pub fn blocks_playback(app: &AppHandle, path: &str) -> bool {
+    let blocked = !Guard::new(app).playable(path);
+    if blocked {
+        tracing::info!("🔒 Stopped {}: blocked by parental controls", path);
+        let _ = app.emit("parental-blocked", path);
+    }
+    blocked
+}
+
+fn hash_pin(salt: &str, pin: &str) -> String {
+    hex::encode(Sha256::digest(format!("{}{}", salt, pin).as_bytes()))
+}
+
+fn verify_pin(state: &ParentalState, settings: &ParentalSettings, pin: &str) -> Result<(), String> {
+    let mut failures = state.failures.lock().unwrap();
+    if let Some(until) = failures.1 {
+        match until.checked_duration_since(Instant::now()) {
+            Some(wait) => return Err(format!("Too many wrong PINs; try again in {} seconds", wait.as_secs() + 1)),
+            None => *failures = (0, None),
+        }
+    }
+    if settings.pin_hash.as_deref() == Some(hash_pin(&settings.pin_salt, pin).as_str()) {
+        *failures = (0, None);
+        return Ok(());
+    }
+    failures.0 += 1;
+    if failures.0 >= MAX_ATTEMPTS {
+        failures.1 = Some(Instant::now() + LOCKOUT);
+    }
+    Err("Wrong PIN".to_string())
+}
+
+/// Start (or extend) the unlock session, telling the UI when it ends so listings refresh.
+fn start_session(app: &AppHandle, state: &ParentalState, minutes: u32) -> Duration {
+    let length = Duration::from_secs(u64::from(minutes.max(1)) * 60);
+    *state.unlocked_until.lock().unwrap() = Some(Instant::now() + length);
+    let _ = app.emit("parental-changed", ());
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(length + Duration::from_millis(200));
+        if app.state::<ParentalState>().remaining().is_none() {
+            let _ = app.emit("parental-changed", ());
+        }
+    });
+    length
+}
+
+#[derive(Serialize, Debug)]
+pub struct ParentalStatus {
+    pub enabled: bool,
+    pub unlocked: bool,
+    pub unlocked_secs: u64, // left in the unlock session
+    #[serde(flatten)]
+    pub rules: ParentalRules,
+}
+
+/// The editable part of the settings; the PIN is changed through `set_parental_pin`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ParentalRules {
+    pub locked_folders: Vec<ParentalLock>,
+    pub locked_collections: Vec<ParentalLock>,
+    pub max_age: Option<u8>,
+    pub protected_areas: Vec<String>,
+    pub unlock_minutes: u32,
+}
+
+#[command]
+pub fn get_parental_controls(app: AppHandle, state: State<'_, ParentalState>) -> ParentalStatus {
+    let settings = load_config(&app).parental;
+    let remaining = state.remaining();
+    ParentalStatus {
+        enabled: settings.pin_hash.is_some(),
+        unlocked: settings.pin_hash.is_none() || remaining.is_some(),
+        unlocked_secs: remaining.map_or(0, |d| d.as_secs()),
+        rules: ParentalRules {
+            locked_folders: settings.locked_folders,
+            locked_collections: settings.locked_collections,
+            max_age: settings.max_age,
+            protected_areas: settings.protected_areas,
+            unlock_minutes: settings.unlock_minutes,
+        },
+    }
+}
+
+/// Set, change or (with `pin: None`) remove the PIN. Changing or removing needs the current one.
+#[command]
+pub fn set_parental_pin(app: AppHandle, state: State<'_, ParentalState>, current: Option<String>, pin: Option<String>) -> Result<(), String> {
+    let mut config = load_config(&app);
+    if config.parental.pin_hash.is_some() {
+        verify_pin(&state, &config.parental, current.as_deref().unwrap_or_default())?;
+    }
+    match pin {
+        Some(pin) => {
+            if !(4..=8).contains(&pin.len()) || !pin.chars().all(|c| c.is_ascii_digit()) {
+                return Err("The PIN must be 4 to 8 digits".to_string());
+            }
+            config.parental.pin_salt = uuid::Uuid::new_v4().to_string();
+            config.parental.pin_hash = Some(hash_pin(&config.parental.pin_salt, &pin));
+        }
+        None => config.parental.pin_hash = None,
+    }
+    save_config(&app, &config)?;
+    // Whoever just set the PIN keeps going without typing it again
+    start_session(&app, &state, config.parental.unlock_minutes);
+    Ok(())
+}
+
+/// Lift all restrictions for the configured number of minutes; returns the session length in seconds.
+#[command]
+pub fn unlock_parental(app: AppHandle, state: State<'_, ParentalState>, pin: String) -> Result<u64, String> {
+    let settings = load_config(&app).parental;
+    if settings.pin_hash.is_none() {
+        return Ok(0);
+    }
+    verify_pin(&state, &settings, &pin)?;
+    tracing::info!("🔓 Parental controls unlocked for {} minutes", settings.unlock_minutes);
+    Ok(start_session(&app, &state, settings.unlock_minutes).as_secs())
+}
+
+#[command]
+pub fn lock_parental(app: AppHandle, state: State<'_, ParentalState>) {
+    *state.unlocked_until.lock().unwrap() = None;
+    let _ = app.emit("parental-changed", ());
+}
+
+/// Save the rules. Only possible while unlocked (or before a PIN is set).
+#[command]
+pub fn save_parental_controls(app: AppHandle, state: State<'_, ParentalState>, rules: ParentalRules) -> Result<(), String> {
+    let mut config = load_config(&app);
+    if config.parental.pin_hash.is_some() && state.remaining().is_none() {
+        return Err("Unlock parental controls first".to_string());
+    }
+    if let Some(area) = rules.protected_areas.iter().find(|a| !AREAS.contains(&a.as_str())) {
+        return Err(format!("Unknown app area '{}'", area));
+    }
+    if !(1..=240).contains(&rules.unlock_minutes) {
+        return Err("The unlock session must last between 1 and 240 minutes".to_string());
+    }
+    config.parental.locked_folders = rules.locked_folders;
+    config.parental.locked_collections = rules.locked_collections;
+    config.parental.max_age = rules.max_age;
+    config.parental.protected_areas = rules.protected_areas;
+    config.parental.unlock_minutes = rules.unlock_minutes;
+    save_config(&app, &config)?;
+    let _ = app.emit("parental-changed", ());
+    Ok(())
+}
+
+fn area_locked(app: &AppHandle, area: &str) -> bool {
+    let settings = load_config(app).parental;
+    settings.pin_hash.is_some()
+        && app.state::<ParentalState>().remaining().is_none()
+        && settings.protected_areas.iter().any(|a| a == area)
+}
+
+/// Err while `area` is protected and locked; called by the commands behind each area.
+pub fn check_area(app: &AppHandle, area: &str) -> Result<(), String> {
+    if area_locked(app, area) {
+        return Err(format!("The {} area is locked by parental controls", area));
+    }
+    Ok(())
+}
+
+/// Whether opening this app area needs the PIN right now.
+#[command]
+pub fn is_area_locked(app: AppHandle, area: String) -> bool {
+    area_locked(&app, &area)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rating_age;
+
+    #[test]
+    fn rating_ages() {
+        let cases = [
+            // MPAA
+            ("G", Some(0)),
+            ("PG", Some(10)),
+            ("PG-13", Some(13)),
+            ("R", Some(17)),
+            ("NC-17", Some(18)),
+            // US TV
+            ("TV-Y", Some(0)),
+            ("TV-Y7-FV", Some(7)),
+            ("tv-pg", Some(10)),
+            ("TV-14", Some(14)),
+            (" TV-MA ", Some(17)),
+            // BBFC
+            ("U", Some(0)),
+            ("12A", Some(12)),
+            ("15", Some(15)),
+            ("18", Some(18)),
+            ("R18", Some(18)),
+            // FSK
+            ("FSK 0", Some(0)),
+            ("FSK 6", Some(6)),
+            ("FSK16", Some(16)),
+            ("FSK 18", Some(18)),
+            // Unrated or unknown
+            ("", None),
+            ("NR", None),
+            ("Unrated", None),
+            ("99", None),
+        ];
+        for (rating, age) in cases {
+            assert_eq!(rating_age(rating), age, "{:?}", rating);
+        }
+    }
+}
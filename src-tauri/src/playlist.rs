@@ -1,7 +1,12 @@
 use serde::{Deserialize, Serialize};
-use tauri::command;
+use tauri::{command, AppHandle};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::util::now_secs;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Playlist {
@@ -10,6 +15,70 @@ pub struct Playlist {
     pub items: Vec<String>,
     pub created: u64,
     pub modified: u64,
+    // Set for folder-backed playlists; `items` is then rebuilt from the folder on every load
+    #[serde(default)]
+    pub source: Option<FolderSource>,
+    // Set for smart playlists; `items` is then whatever matches the rules on load
+    #[serde(default)]
+    pub rules: Option<SmartRules>,
+}
+
+/// What a smart playlist matches. All given conditions must hold.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct SmartRules {
+    pub tags: Vec<String>,     // must carry every one of these
+    pub any_tags: Vec<String>, // must carry at least one of these
+    pub favorite: Option<bool>,
+    pub min_rating: Option<u8>,
+    pub sort: Option<String>, // "path" (default) | "rating"
+}
+
+impl SmartRules {
+    fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.any_tags.is_empty() && self.favorite.is_none() && self.min_rating.unwrap_or(0) == 0
+    }
+}
+
+/// A playlist as listed by `get_playlists`; `entries`/`totals` are only filled in with `enrich`.
+#[derive(Serialize, Debug)]
+pub struct PlaylistListing {
+    #[serde(flatten)]
+    pub playlist: Playlist,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entries: Option<Vec<PlaylistEntryInfo>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub totals: Option<PlaylistTotals>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PlaylistEntryInfo {
+    pub path: String,
+    pub exists: bool,
+    pub duration: f64,
+    pub poster_path: Option<String>,
+    pub watched: bool,
+    pub position: Option<f64>, // resume point, if started
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct PlaylistTotals {
+    pub item_count: usize,
+    pub missing: usize,
+    pub watched: usize,
+    pub total_runtime: f64,
+    pub remaining_seconds: f64,
+}
+
+/// The folder an auto-updating playlist mirrors.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct FolderSource {
+    pub folder: String,
+    // Wildcard filter on the file name, e.g. "*S01E*" or "*.{mkv,mp4}"
+    #[serde(default)]
+    pub pattern: Option<String>,
+    #[serde(default)]
+    pub recursive: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -19,12 +88,96 @@ pub struct Collection {
     pub description: String,
     pub items: Vec<String>,
     pub poster_path: Option<String>,
+    // Set when the collection mirrors a TMDB franchise (belongs_to_collection)
+    #[serde(default)]
+    pub tmdb_collection_id: Option<u64>,
+    #[serde(default)]
+    pub modified: u64,
+    // Ids of collections nested inside this one (franchise -> trilogy -> movies)
+    #[serde(default)]
+    pub children: Vec<String>,
+    #[serde(default)]
+    pub sort_order: Option<String>, // "manual" (default) | "name" | "year"
+}
+
+/// A collection with its nested collections resolved and library totals.
+#[derive(Serialize, Debug)]
+pub struct CollectionDetails {
+    #[serde(flatten)]
+    pub collection: Collection, // `items` in the collection's sort order
+    pub nested: Vec<CollectionDetails>,
+    pub all_items: Vec<String>, // own items, then each nested collection's, in play order
+    pub item_count: usize,
+    pub total_runtime: f64,
+    pub total_size: u64,
+}
+
+/// Remembers a deleted playlist/collection so sync doesn't bring it back from another device.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Tombstone {
+    pub id: String,
+    pub deleted_at: u64,
 }
 
-#[derive(Serialize, Deserialize, Default)]
+/// A playlist or collection entry whose file is gone.
+#[derive(Serialize, Debug)]
+pub struct MissingItem {
+    pub index: usize,
+    pub path: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PlaylistValidation {
+    pub id: String,
+    pub name: String,
+    pub total: usize,
+    pub missing: Vec<MissingItem>,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct RelocateSummary {
+    pub playlists: usize,
+    pub collections: usize,
+    pub items: usize,
+}
+
+/// How a playlist or collection was last played, applied the next time it's started.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct PlaybackPrefs {
+    pub shuffle: bool,
+    pub repeat: String, // "off" | "one" | "all"
+    pub last_index: Option<usize>, // in the playlist's own (unshuffled) order
+}
+
+impl Default for PlaybackPrefs {
+    fn default() -> Self {
+        Self {
+            shuffle: false,
+            repeat: "off".to_string(),
+            last_index: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
 struct PlaylistStore {
     playlists: Vec<Playlist>,
     collections: Vec<Collection>,
+    #[serde(default)]
+    deleted: Vec<Tombstone>,
+    // Keyed by playlist or collection id
+    #[serde(default)]
+    playback: HashMap<String, PlaybackPrefs>,
+}
+
+/// Playlists, collections and deletions as exchanged with the sync target.
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct PlaylistSyncData {
+    pub playlists: Vec<Playlist>,
+    pub collections: Vec<Collection>,
+    pub deleted: Vec<Tombstone>,
 }
 
 fn get_store_path() -> PathBuf {
@@ -41,112 +194,798 @@ fn dirs_config_path() -> PathBuf {
     path
 }
 
+fn read_store_file(path: &Path) -> Result<PlaylistStore, String> {
+    let data = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&data).map_err(|e| e.to_string())
+}
+
+/// Read playlists.json, falling back to the backup if it's unreadable. A broken file is
+/// renamed to `.corrupt` so the next save can't overwrite the only copy worth recovering.
 fn load_store() -> PlaylistStore {
     let path = get_store_path();
-    if path.exists() {
-        let data = fs::read_to_string(&path).unwrap_or_default();
-        serde_json::from_str(&data).unwrap_or_default()
-    } else {
-        PlaylistStore::default()
+    if !path.exists() {
+        return PlaylistStore::default();
+    }
+    match read_store_file(&path) {
+        Ok(store) => store,
+        Err(e) => {
+            tracing::warn!("⚠️ playlists.json is unreadable ({}), restoring the backup", e);
+            let _ = fs::rename(&path, path.with_extension("json.corrupt"));
+            read_store_file(&path.with_extension("json.bak")).unwrap_or_default()
+        }
     }
 }
 
+/// Write to a temp file and rename it over playlists.json, keeping the previous version as `.bak`,
+/// so a crash mid-write can't leave a truncated store behind.
 fn save_store(store: &PlaylistStore) -> Result<(), String> {
     let path = get_store_path();
     let data = serde_json::to_string_pretty(store).map_err(|e| e.to_string())?;
-    fs::write(&path, data).map_err(|e| e.to_string())
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, data).map_err(|e| e.to_string())?;
+    if path.exists() {
+        fs::copy(&path, path.with_extension("json.bak")).map_err(|e| e.to_string())?;
+    }
+    fs::rename(&tmp, &path).map_err(|e| e.to_string())
+}
+
+/// The playlist store, loaded on first use. Every command goes through this lock so two
+/// invocations can't interleave their read-modify-write of playlists.json.
+pub struct PlaylistState {
+    store: Mutex<Option<PlaylistStore>>,
+}
+
+impl PlaylistState {
+    pub fn new() -> Self {
+        Self {
+            store: Mutex::new(None),
+        }
+    }
+}
+
+fn read_store<R>(app: &AppHandle, f: impl FnOnce(&PlaylistStore) -> R) -> R {
+    let state = app.state::<PlaylistState>();
+    let mut guard = state.store.lock().unwrap();
+    f(guard.get_or_insert_with(load_store))
 }
 
-fn now_timestamp() -> u64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs()
+/// Change the store and save it. If `f` or the save fails, nothing is changed.
+fn write_store<R>(app: &AppHandle, f: impl FnOnce(&mut PlaylistStore) -> Result<R, String>) -> Result<R, String> {
+    let state = app.state::<PlaylistState>();
+    let mut guard = state.store.lock().unwrap();
+    let mut store = guard.get_or_insert_with(load_store).clone();
+    let result = f(&mut store)?;
+    save_store(&store)?;
+    *guard = Some(store);
+    Ok(result)
+}
+
+/// Translate a `*`/`?`/`{a,b}` wildcard into an anchored, case-insensitive regex.
+fn glob_to_regex(pattern: &str) -> Result<regex::Regex, String> {
+    let mut re = String::from("(?i)^");
+    let mut in_group = false;
+    for ch in pattern.chars() {
+        match ch {
+            '*' => re.push_str(".*"),
+            '?' => re.push('.'),
+            '{' if !in_group => {
+                in_group = true;
+                re.push_str("(?:");
+            }
+            '}' if in_group => {
+                in_group = false;
+                re.push(')');
+            }
+            ',' if in_group => re.push('|'),
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    if in_group {
+        return Err(format!("Unclosed '{{' in pattern '{}'", pattern));
+    }
+    re.push('$');
+    regex::Regex::new(&re).map_err(|e| e.to_string())
+}
+
+/// Video files currently in the source folder, sorted by path.
+fn scan_folder(source: &FolderSource) -> Result<Vec<String>, String> {
+    let root = Path::new(&source.folder);
+    if !root.is_dir() {
+        return Err(format!("Folder not found: {}", source.folder));
+    }
+    let filter = source.pattern.as_deref().filter(|p| !p.trim().is_empty()).map(glob_to_regex).transpose()?;
+    let mut items: Vec<String> = walkdir::WalkDir::new(root)
+        .max_depth(if source.recursive { usize::MAX } else { 1 })
+        .into_iter()
+        .flatten()
+        .map(|e| e.into_path())
+        .filter(|p| crate::file_scanner::is_video(p))
+        .filter(|p| {
+            let name = p.file_name().unwrap_or_default().to_string_lossy();
+            filter.as_ref().is_none_or(|re| re.is_match(&name))
+        })
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+    items.sort_by_key(|p| p.to_lowercase());
+    Ok(items)
+}
+
+/// Bring a folder-backed or smart playlist's items up to date. If the folder is
+/// unavailable (e.g. an unplugged drive) the last known items are kept.
+fn refresh_items(app: &AppHandle, playlist: &mut Playlist) {
+    let items = match (&playlist.source, &playlist.rules) {
+        (Some(source), _) => scan_folder(source),
+        (None, Some(rules)) => crate::tags::smart_playlist_items(app, rules),
+        (None, None) => return,
+    };
+    match items {
+        Ok(items) => playlist.items = items,
+        Err(e) => tracing::warn!("⚠️ Could not refresh playlist '{}': {}", playlist.name, e),
+    }
 }
 
 // ---- Playlist Commands ----
 
 #[command]
-pub fn save_playlist(name: String, items: Vec<String>, id: Option<String>) -> Result<Playlist, String> {
-    let mut store = load_store();
-    let now = now_timestamp();
+pub fn save_playlist(app: AppHandle, name: String, items: Vec<String>, id: Option<String>) -> Result<Playlist, String> {
+    write_store(&app, |store| {
+        let now = now_secs();
 
-    if let Some(ref playlist_id) = id {
-        // Update existing
-        if let Some(p) = store.playlists.iter_mut().find(|p| &p.id == playlist_id) {
-            p.name = name;
-            p.items = items;
-            p.modified = now;
-            let updated = p.clone();
-            save_store(&store)?;
-            return Ok(updated);
+        if let Some(ref playlist_id) = id {
+            // Update existing
+            if let Some(p) = store.playlists.iter_mut().find(|p| &p.id == playlist_id) {
+                p.name = name;
+                p.items = items;
+                p.modified = now;
+                return Ok(p.clone());
+            }
         }
+
+        // Create new
+        let playlist = Playlist {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            items,
+            created: now,
+            modified: now,
+            source: None,
+            rules: None,
+        };
+        store.playlists.push(playlist.clone());
+        Ok(playlist)
+    })
+}
+
+/// Create or update a playlist that follows the video files in `folder`, optionally
+/// filtered by a wildcard `pattern` on the file name.
+#[command]
+pub fn save_folder_playlist(
+    app: AppHandle,
+    name: String,
+    folder: String,
+    pattern: Option<String>,
+    recursive: Option<bool>,
+    id: Option<String>,
+) -> Result<Playlist, String> {
+    let source = FolderSource {
+        folder,
+        pattern: pattern.filter(|p| !p.trim().is_empty()),
+        recursive: recursive.unwrap_or(false),
+    };
+    let items = scan_folder(&source)?;
+    write_store(&app, |store| {
+        let now = now_secs();
+
+        let existing = id.as_ref().and_then(|id| store.playlists.iter_mut().find(|p| &p.id == id));
+        let playlist = match existing {
+            Some(p) => {
+                p.name = name;
+                p.items = items;
+                p.source = Some(source);
+                p.rules = None;
+                p.modified = now;
+                p.clone()
+            }
+            None => {
+                let playlist = Playlist {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    name,
+                    items,
+                    created: now,
+                    modified: now,
+                    source: Some(source),
+                    rules: None,
+                };
+                store.playlists.push(playlist.clone());
+                playlist
+            }
+        };
+        Ok(playlist)
+    })
+}
+
+/// Create or update a smart playlist, whose items are the files matching `rules`.
+#[command]
+pub fn save_smart_playlist(app: AppHandle, name: String, rules: SmartRules, id: Option<String>) -> Result<Playlist, String> {
+    if rules.is_empty() {
+        return Err("A smart playlist needs at least one rule".to_string());
     }
+    let items = crate::tags::smart_playlist_items(&app, &rules)?;
+    write_store(&app, |store| {
+        let now = now_secs();
+
+        let existing = id.as_ref().and_then(|id| store.playlists.iter_mut().find(|p| &p.id == id));
+        let playlist = match existing {
+            Some(p) => {
+                p.name = name;
+                p.items = items;
+                p.source = None;
+                p.rules = Some(rules);
+                p.modified = now;
+                p.clone()
+            }
+            None => {
+                let playlist = Playlist {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    name,
+                    items,
+                    created: now,
+                    modified: now,
+                    source: None,
+                    rules: Some(rules),
+                };
+                store.playlists.push(playlist.clone());
+                playlist
+            }
+        };
+        Ok(playlist)
+    })
+}
+
+/// Per-item existence, duration, poster and watched state, plus totals for the playlist.
+fn enrich_playlist(app: &AppHandle, playlist: &Playlist) -> Result<(Vec<PlaylistEntryInfo>, PlaylistTotals), String> {
+    let records = crate::watch_history::records_for(app, &playlist.items)?;
+    let durations = crate::watch_history::item_durations(&playlist.items, &records);
+    let entries: Vec<PlaylistEntryInfo> = playlist.items
+        .iter()
+        .zip(durations)
+        .map(|(path, duration)| {
+            let record = records.get(path);
+            let local = !path.contains("://");
+            PlaylistEntryInfo {
+                path: path.clone(),
+                exists: !local || Path::new(path).exists(),
+                duration,
+                poster_path: if local {
+                    crate::poster_cache::find_poster(app, Path::new(path)).map(|p| p.to_string_lossy().to_string())
+                } else {
+                    None
+                },
+                watched: record.is_some_and(|r| r.times_completed > 0 && !r.resumable),
+                position: record.filter(|r| r.resumable).map(|r| r.position),
+            }
+        })
+        .collect();
 
-    // Create new
-    let playlist = Playlist {
-        id: uuid::Uuid::new_v4().to_string(),
-        name,
-        items,
-        created: now,
-        modified: now,
+    let totals = PlaylistTotals {
+        item_count: entries.len(),
+        missing: entries.iter().filter(|e| !e.exists).count(),
+        watched: entries.iter().filter(|e| e.watched).count(),
+        total_runtime: entries.iter().map(|e| e.duration).sum(),
+        remaining_seconds: entries
+            .iter()
+            .filter(|e| !e.watched)
+            .map(|e| (e.duration - e.position.unwrap_or(0.0)).max(0.0))
+            .sum(),
     };
-    store.playlists.push(playlist.clone());
-    save_store(&store)?;
-    Ok(playlist)
+    Ok((entries, totals))
 }
 
+/// All playlists. With `enrich`, each also carries per-item details and totals so the
+/// playlists page needs no per-item lookups.
 #[command]
-pub fn get_playlists() -> Result<Vec<Playlist>, String> {
-    let store = load_store();
-    Ok(store.playlists)
+pub async fn get_playlists(app: AppHandle, enrich: Option<bool>) -> Result<Vec<PlaylistListing>, String> {
+    let enrich = enrich.unwrap_or(false);
+    // Refreshing folder playlists and probing durations both touch the disk
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut playlists = read_store(&app, |store| store.playlists.clone());
+        playlists.iter_mut().for_each(|p| refresh_items(&app, p));
+        playlists
+            .into_iter()
+            .map(|playlist| {
+                let (entries, totals) = if enrich {
+                    let (entries, totals) = enrich_playlist(&app, &playlist)?;
+                    (Some(entries), Some(totals))
+                } else {
+                    (None, None)
+                };
+                Ok(PlaylistListing { playlist, entries, totals })
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
 #[command]
-pub fn delete_playlist(id: String) -> Result<(), String> {
-    let mut store = load_store();
-    store.playlists.retain(|p| p.id != id);
-    save_store(&store)
+pub fn delete_playlist(app: AppHandle, id: String) -> Result<(), String> {
+    write_store(&app, |store| {
+        store.playlists.retain(|p| p.id != id);
+        store.playback.remove(&id);
+        store.deleted.push(Tombstone { id, deleted_at: now_secs() });
+        Ok(())
+    })
+}
+
+/// Change one playlist under the store lock, bumping `modified`.
+fn update_playlist(app: &AppHandle, id: &str, change: impl FnOnce(&mut Playlist) -> Result<(), String>) -> Result<Playlist, String> {
+    write_store(app, |store| {
+        let playlist = store.playlists.iter_mut().find(|p| p.id == id).ok_or("Playlist not found")?;
+        if playlist.source.is_some() {
+            return Err("This playlist follows a folder; change the folder's contents instead".to_string());
+        }
+        if playlist.rules.is_some() {
+            return Err("This is a smart playlist; change its rules instead".to_string());
+        }
+        change(playlist)?;
+        playlist.modified = now_secs();
+        Ok(playlist.clone())
+    })
+}
+
+/// Insert items at `index` (default: the end).
+#[command]
+pub fn playlist_add_items(app: AppHandle, id: String, items: Vec<String>, index: Option<usize>) -> Result<Playlist, String> {
+    update_playlist(&app, &id, |p| {
+        let at = index.unwrap_or(p.items.len()).min(p.items.len());
+        p.items.splice(at..at, items);
+        Ok(())
+    })
+}
+
+#[command]
+pub fn playlist_remove_item(app: AppHandle, id: String, index: usize) -> Result<Playlist, String> {
+    update_playlist(&app, &id, |p| {
+        if index >= p.items.len() {
+            return Err(format!("Item {} is out of range", index));
+        }
+        p.items.remove(index);
+        Ok(())
+    })
+}
+
+/// Move the item at `from` so it ends up at `to` (drag-reorder).
+#[command]
+pub fn playlist_move_item(app: AppHandle, id: String, from: usize, to: usize) -> Result<Playlist, String> {
+    update_playlist(&app, &id, |p| {
+        if from >= p.items.len() || to >= p.items.len() {
+            return Err(format!("Cannot move item {} to {}: playlist has {} items", from, to, p.items.len()));
+        }
+        let item = p.items.remove(from);
+        p.items.insert(to, item);
+        Ok(())
+    })
 }
 
 // ---- Collection Commands ----
 
 #[command]
-pub fn save_collection(name: String, description: String, items: Vec<String>, id: Option<String>) -> Result<Collection, String> {
-    let mut store = load_store();
+pub fn save_collection(app: AppHandle, name: String, description: String, items: Vec<String>, id: Option<String>) -> Result<Collection, String> {
+    write_store(&app, |store| {
+        if let Some(ref coll_id) = id {
+            if let Some(c) = store.collections.iter_mut().find(|c| &c.id == coll_id) {
+                c.name = name;
+                c.description = description;
+                c.items = items;
+                c.modified = now_secs();
+                return Ok(c.clone());
+            }
+        }
+
+        let collection = Collection {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            description,
+            items,
+            poster_path: None,
+            tmdb_collection_id: None,
+            modified: now_secs(),
+            children: Vec::new(),
+            sort_order: None,
+        };
+        store.collections.push(collection.clone());
+        Ok(collection)
+    })
+}
+
+/// Add a file to the collection mirroring a TMDB franchise, creating it on first sight.
+/// The name is only set on creation so a user rename sticks.
+pub fn add_to_tmdb_collection(
+    app: &AppHandle,
+    tmdb_id: u64,
+    name: &str,
+    description: &str,
+    item: &str,
+    poster_path: Option<String>,
+) -> Result<Collection, String> {
+    write_store(app, |store| {
+        let index = match store.collections.iter().position(|c| c.tmdb_collection_id == Some(tmdb_id)) {
+            Some(i) => i,
+            None => {
+                store.collections.push(Collection {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    name: name.to_string(),
+                    description: String::new(),
+                    items: Vec::new(),
+                    poster_path: None,
+                    tmdb_collection_id: Some(tmdb_id),
+                    modified: now_secs(),
+                    children: Vec::new(),
+                    sort_order: None,
+                });
+                store.collections.len() - 1
+            }
+        };
+
+        let collection = &mut store.collections[index];
+        if !collection.items.iter().any(|i| i == item) {
+            collection.items.push(item.to_string());
+        }
+        if collection.description.is_empty() {
+            collection.description = description.to_string();
+        }
+        if collection.poster_path.is_none() {
+            collection.poster_path = poster_path;
+        }
+        collection.modified = now_secs();
+        Ok(collection.clone())
+    })
+}
+
+#[command]
+pub fn get_collections(app: AppHandle) -> Result<Vec<Collection>, String> {
+    let guard = crate::parental::Guard::new(&app);
+    let mut collections = read_store(&app, |store| store.collections.clone());
+    collections.retain(|c| guard.collection_visible(&c.id));
+    Ok(collections)
+}
+
+#[command]
+pub fn delete_collection(app: AppHandle, id: String) -> Result<(), String> {
+    write_store(&app, |store| {
+        store.collections.retain(|c| c.id != id);
+        store.playback.remove(&id);
+        for c in store.collections.iter_mut().filter(|c| c.children.contains(&id)) {
+            c.children.retain(|child| child != &id);
+        }
+        store.deleted.push(Tombstone { id, deleted_at: now_secs() });
+        Ok(())
+    })
+}
+
+/// Change one collection under the store lock, bumping `modified`.
+fn update_collection(app: &AppHandle, id: &str, change: impl FnOnce(&mut PlaylistStore, usize) -> Result<(), String>) -> Result<Collection, String> {
+    write_store(app, |store| {
+        let index = store.collections.iter().position(|c| c.id == id).ok_or("Collection not found")?;
+        change(store, index)?;
+        let collection = &mut store.collections[index];
+        collection.modified = now_secs();
+        Ok(collection.clone())
+    })
+}
+
+/// Whether `target` is reachable from `from` through nested collections.
+fn contains_collection(store: &PlaylistStore, from: &str, target: &str, seen: &mut Vec<String>) -> bool {
+    if from == target {
+        return true;
+    }
+    if seen.iter().any(|s| s == from) {
+        return false;
+    }
+    seen.push(from.to_string());
+    let children = store.collections.iter().find(|c| c.id == from).map(|c| c.children.clone()).unwrap_or_default();
+    children.iter().any(|child| contains_collection(store, child, target, seen))
+}
+
+/// Set which collections are nested inside this one, in display order.
+#[command]
+pub fn set_collection_children(app: AppHandle, id: String, children: Vec<String>) -> Result<Collection, String> {
+    update_collection(&app, &id, |store, index| {
+        for child in &children {
+            if !store.collections.iter().any(|c| &c.id == child) {
+                return Err(format!("Collection {} not found", child));
+            }
+            if contains_collection(store, child, &id, &mut Vec::new()) {
+                return Err("A collection can't contain itself".to_string());
+            }
+        }
+        store.collections[index].children = children;
+        Ok(())
+    })
+}
+
+/// Use a custom poster image for the collection, or `None` to clear it.
+#[command]
+pub fn set_collection_poster(app: AppHandle, id: String, poster_path: Option<String>) -> Result<Collection, String> {
+    if let Some(path) = &poster_path {
+        if !Path::new(path).is_file() {
+            return Err(format!("Poster not found: {}", path));
+        }
+    }
+    update_collection(&app, &id, |store, index| {
+        store.collections[index].poster_path = poster_path;
+        Ok(())
+    })
+}
+
+#[command]
+pub fn set_collection_sort(app: AppHandle, id: String, sort_order: String) -> Result<Collection, String> {
+    if !matches!(sort_order.as_str(), "manual" | "name" | "year") {
+        return Err(format!("Unknown sort order '{}'", sort_order));
+    }
+    update_collection(&app, &id, |store, index| {
+        store.collections[index].sort_order = Some(sort_order);
+        Ok(())
+    })
+}
+
+fn item_name(path: &str) -> String {
+    let name = Path::new(path).file_name().and_then(|n| n.to_str()).unwrap_or(path);
+    crate::metadata::clean_video_name(name).to_lowercase()
+}
+
+/// Items in the collection's sort order. Items without a year sort after dated ones.
+fn sorted_items(collection: &Collection) -> Vec<String> {
+    let mut items = collection.items.clone();
+    match collection.sort_order.as_deref() {
+        Some("name") => items.sort_by_cached_key(|p| item_name(p)),
+        Some("year") => items.sort_by_cached_key(|p| {
+            let name = Path::new(p).file_name().and_then(|n| n.to_str()).unwrap_or(p);
+            (crate::metadata::parse_release_year(name).unwrap_or(u32::MAX), item_name(p))
+        }),
+        _ => {}
+    }
+    items
+}
+
+/// Own items followed by every nested collection's, depth first, skipping cycles.
+fn collection_play_order(store: &PlaylistStore, collection: &Collection, seen: &mut Vec<String>) -> Vec<String> {
+    seen.push(collection.id.clone());
+    let mut items = sorted_items(collection);
+    for child in &collection.children {
+        if seen.contains(child) {
+            continue;
+        }
+        if let Some(c) = store.collections.iter().find(|c| &c.id == child) {
+            items.extend(collection_play_order(store, c, seen));
+        }
+    }
+    items
+}
 
-    if let Some(ref coll_id) = id {
-        if let Some(c) = store.collections.iter_mut().find(|c| &c.id == coll_id) {
-            c.name = name;
-            c.description = description;
-            c.items = items;
-            let updated = c.clone();
-            save_store(&store)?;
-            return Ok(updated);
+fn build_details(
+    store: &PlaylistStore,
+    collection: &Collection,
+    runtimes: &HashMap<String, f64>,
+    seen: &mut Vec<String>,
+) -> CollectionDetails {
+    seen.push(collection.id.clone());
+    let mut nested = Vec::new();
+    for child in &collection.children {
+        if seen.contains(child) {
+            continue;
         }
+        if let Some(c) = store.collections.iter().find(|c| &c.id == child) {
+            nested.push(build_details(store, c, runtimes, seen));
+        }
+    }
+
+    let items = sorted_items(collection);
+    let mut all_items = items.clone();
+    all_items.extend(nested.iter().flat_map(|n| n.all_items.iter().cloned()));
+    let size = |path: &String| fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    CollectionDetails {
+        item_count: all_items.len(),
+        total_runtime: all_items.iter().map(|p| runtimes.get(p).copied().unwrap_or(0.0)).sum(),
+        total_size: all_items.iter().map(size).sum(),
+        all_items,
+        nested,
+        collection: Collection { items, ..collection.clone() },
     }
+}
+
+/// A collection with nested collections, sorted items and total runtime/size. Runtimes come
+/// from the watch history, probing files that were never played.
+#[command]
+pub async fn get_collection_details(app: AppHandle, id: String) -> Result<CollectionDetails, String> {
+    crate::parental::Guard::new(&app).check_collection(&id)?;
+    let store = read_store(&app, |store| store.clone());
+    let collection = store.collections.iter().find(|c| c.id == id).ok_or("Collection not found")?.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let items = collection_play_order(&store, &collection, &mut Vec::new());
+        let records = crate::watch_history::records_for(&app, &items)?;
+        let durations = crate::watch_history::item_durations(&items, &records);
+        let runtimes = items.into_iter().zip(durations).collect();
+        Ok(build_details(&store, &collection, &runtimes, &mut Vec::new()))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Name and items of the playlist or collection with this id.
+pub fn find_items(app: &AppHandle, id: &str) -> Option<(String, Vec<String>)> {
+    let (playlist, collection) = read_store(app, |store| {
+        let playlist = store.playlists.iter().find(|p| p.id == id).cloned();
+        let collection = store.collections.iter()
+            .find(|c| c.id == id)
+            .map(|c| (c.name.clone(), collection_play_order(store, c, &mut Vec::new())));
+        (playlist, collection)
+    });
+    playlist
+        .map(|mut p| {
+            refresh_items(app, &mut p);
+            (p.name, p.items)
+        })
+        .or(collection)
+}
 
-    let collection = Collection {
-        id: uuid::Uuid::new_v4().to_string(),
-        name,
-        description,
-        items,
-        poster_path: None,
+// ---- Playback preferences ----
+
+pub fn playback_prefs(app: &AppHandle, id: &str) -> PlaybackPrefs {
+    read_store(app, |store| store.playback.get(id).cloned().unwrap_or_default())
+}
+
+pub fn save_playback_prefs(app: &AppHandle, id: &str, prefs: PlaybackPrefs) -> Result<(), String> {
+    write_store(app, |store| {
+        store.playback.insert(id.to_string(), prefs);
+        Ok(())
+    })
+}
+
+/// Remember `path` as the last item played from this playlist or collection.
+pub fn remember_last_item(app: &AppHandle, id: &str, path: &str) -> Result<(), String> {
+    let Some((_, items)) = find_items(app, id) else {
+        return Ok(());
     };
-    store.collections.push(collection.clone());
-    save_store(&store)?;
-    Ok(collection)
+    let Some(index) = items.iter().position(|i| i == path) else {
+        return Ok(());
+    };
+    if playback_prefs(app, id).last_index == Some(index) {
+        return Ok(());
+    }
+    write_store(app, |store| {
+        store.playback.entry(id.to_string()).or_default().last_index = Some(index);
+        Ok(())
+    })
+}
+
+/// Shuffle/repeat mode and the last played item for a playlist or collection.
+#[command]
+pub fn get_playback_prefs(app: AppHandle, id: String) -> PlaybackPrefs {
+    playback_prefs(&app, &id)
 }
 
+// ---- Maintenance ----
+
+/// Flag the items of a playlist or collection whose files no longer exist.
+/// Stream URLs are never reported.
 #[command]
-pub fn get_collections() -> Result<Vec<Collection>, String> {
-    let store = load_store();
-    Ok(store.collections)
+pub fn validate_playlist(app: AppHandle, id: String) -> Result<PlaylistValidation, String> {
+    let (name, items) = find_items(&app, &id).ok_or("Playlist or collection not found")?;
+    let missing = items
+        .iter()
+        .enumerate()
+        .filter(|(_, path)| !path.contains("://") && !Path::new(path).exists())
+        .map(|(index, path)| MissingItem { index, path: path.clone() })
+        .collect();
+    Ok(PlaylistValidation { id, name, total: items.len(), missing })
+}
+
+/// `path` with `old_root` swapped for `new_root`, if it lives under `old_root`.
+/// Either slash style matches, and the drive letter/case is ignored on Windows.
+fn relocate_path(path: &str, old_root: &str, new_root: &str) -> Option<String> {
+    let old_root = old_root.trim_end_matches(['/', '\\']);
+    let head = path.get(..old_root.len())?;
+    let same = if cfg!(target_os = "windows") {
+        head.replace('\\', "/").eq_ignore_ascii_case(&old_root.replace('\\', "/"))
+    } else {
+        head == old_root
+    };
+    let rest = &path[old_root.len()..];
+    if !same || !(rest.is_empty() || rest.starts_with(['/', '\\'])) {
+        return None;
+    }
+    Some(format!("{}{}", new_root.trim_end_matches(['/', '\\']), rest))
 }
 
+fn relocate_list(items: &mut [String], old_root: &str, new_root: &str) -> usize {
+    let mut changed = 0;
+    for item in items.iter_mut() {
+        if let Some(moved) = relocate_path(item, old_root, new_root) {
+            *item = moved;
+            changed += 1;
+        }
+    }
+    changed
+}
+
+/// Rewrite every playlist and collection item under `old_root` to live under `new_root`,
+/// e.g. after a drive letter changed or a library folder was moved.
 #[command]
-pub fn delete_collection(id: String) -> Result<(), String> {
-    let mut store = load_store();
-    store.collections.retain(|c| c.id != id);
-    save_store(&store)
+pub fn relocate_items(app: AppHandle, old_root: String, new_root: String) -> Result<RelocateSummary, String> {
+    if old_root.trim_end_matches(['/', '\\']).is_empty() {
+        return Err("The old location cannot be empty".to_string());
+    }
+    let summary = write_store(&app, |store| {
+        let mut summary = RelocateSummary::default();
+        let now = now_secs();
+
+        for p in store.playlists.iter_mut() {
+            let mut changed = relocate_list(&mut p.items, &old_root, &new_root);
+            if let Some(source) = p.source.as_mut() {
+                if let Some(folder) = relocate_path(&source.folder, &old_root, &new_root) {
+                    source.folder = folder;
+                    changed += 1;
+                }
+            }
+            if changed > 0 {
+                p.modified = now;
+                summary.playlists += 1;
+                summary.items += changed;
+            }
+        }
+        for c in store.collections.iter_mut() {
+            let changed = relocate_list(&mut c.items, &old_root, &new_root);
+            if changed > 0 {
+                c.modified = now;
+                summary.collections += 1;
+                summary.items += changed;
+            }
+        }
+
+        Ok(summary)
+    })?;
+    tracing::info!("📦 Relocated {} items from {} to {}", summary.items, old_root, new_root);
+    Ok(summary)
+}
+
+// ---- Sync ----
+
+/// Merge another device's playlists into the local store, newest `modified` winning per id,
+/// and return the merged result for upload.
+pub fn merge_sync_data(app: &AppHandle, remote: PlaylistSyncData) -> Result<PlaylistSyncData, String> {
+    write_store(app, |store| {
+        for tomb in remote.deleted {
+            match store.deleted.iter_mut().find(|t| t.id == tomb.id) {
+                Some(t) => t.deleted_at = t.deleted_at.max(tomb.deleted_at),
+                None => store.deleted.push(tomb),
+            }
+        }
+        for playlist in remote.playlists {
+            match store.playlists.iter_mut().find(|p| p.id == playlist.id) {
+                Some(p) if p.modified >= playlist.modified => {}
+                Some(p) => *p = playlist,
+                None => store.playlists.push(playlist),
+            }
+        }
+        for collection in remote.collections {
+            match store.collections.iter_mut().find(|c| c.id == collection.id) {
+                Some(c) if c.modified >= collection.modified => {}
+                Some(c) => *c = collection,
+                None => store.collections.push(collection),
+            }
+        }
+
+        // A deletion wins over edits made before it
+        let deleted = store.deleted.clone();
+        let is_deleted = |id: &str, modified: u64| deleted.iter().any(|t| t.id == id && t.deleted_at >= modified);
+        store.playlists.retain(|p| !is_deleted(&p.id, p.modified));
+        store.collections.retain(|c| !is_deleted(&c.id, c.modified));
+
+        Ok(PlaylistSyncData {
+            playlists: store.playlists.clone(),
+            collections: store.collections.clone(),
+            deleted: store.deleted.clone(),
+        })
+    })
 }
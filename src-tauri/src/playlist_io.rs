@@ -0,0 +1,166 @@
+use regex::Regex;
+use std::fs;
+use std::path::Path;
+use tauri::{command, AppHandle};
+
+use crate::history_io::uri_to_path;
+use crate::playlist::Playlist;
+use crate::xml::{escape_xml, unescape_xml, xml_tag};
+
+/// `C:\a b.mkv` -> `file:///C:/a%20b.mkv`, `/home/x.mkv` -> `file:///home/x.mkv`. URLs are kept.
+fn path_to_uri(path: &str) -> String {
+    if path.contains("://") {
+        return path.to_string();
+    }
+    let slashed = path.replace('\\', "/");
+    let encoded: Vec<String> = slashed
+        .split('/')
+        .map(|segment| {
+            // Keep the drive letter's colon readable
+            if segment.len() == 2 && segment.ends_with(':') {
+                segment.to_string()
+            } else {
+                urlencoding::encode(segment).into_owned()
+            }
+        })
+        .collect();
+    let joined = encoded.join("/");
+    if joined.starts_with('/') {
+        format!("file://{}", joined)
+    } else {
+        format!("file:///{}", joined)
+    }
+}
+
+/// Entries are often relative to the playlist file (`..\Videos\a.mkv` in WPL, `a.mkv` in M3U).
+fn resolve_entry(entry: &str, base: &Path) -> String {
+    if entry.contains("://") {
+        return entry.to_string();
+    }
+    let native = if cfg!(target_os = "windows") { entry.to_string() } else { entry.replace('\\', "/") };
+    let path = Path::new(&native);
+    if path.is_absolute() {
+        native
+    } else {
+        base.join(path).to_string_lossy().to_string()
+    }
+}
+
+fn item_title(path: &str) -> String {
+    let name = Path::new(path).file_name().and_then(|n| n.to_str()).unwrap_or(path);
+    crate::metadata::clean_video_name(name)
+}
+
+/// M3U/M3U8: one entry per line, `#` lines are directives (`#EXTINF`, `#EXTM3U`).
+fn parse_m3u(content: &str) -> (Option<String>, Vec<String>) {
+    let mut name = None;
+    let mut items = Vec::new();
+    for line in content.trim_start_matches('\u{feff}').lines().map(|l| l.trim()) {
+        if let Some(title) = line.strip_prefix("#PLAYLIST:") {
+            name = Some(title.trim().to_string());
+        } else if !line.is_empty() && !line.starts_with('#') {
+            items.push(uri_to_path(line));
+        }
+    }
+    (name, items)
+}
+
+fn parse_xspf(content: &str) -> (Option<String>, Vec<String>) {
+    let head = content.split("<trackList").next().unwrap_or_default();
+    let track = Regex::new(r"(?s)<track>(.*?)</track>").unwrap();
+    let items = track
+        .captures_iter(content)
+        .filter_map(|c| xml_tag(&c[1], "location"))
+        .map(|location| uri_to_path(&location))
+        .collect();
+    (xml_tag(head, "title"), items)
+}
+
+/// Windows Media Player: `<smil><body><seq><media src="..."/>`.
+fn parse_wpl(content: &str) -> (Option<String>, Vec<String>) {
+    let media = Regex::new(r#"<media\s[^>]*?src\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap();
+    let items = media
+        .captures_iter(content)
+        .filter_map(|c| c.get(1).or(c.get(2)))
+        .map(|m| uri_to_path(&unescape_xml(m.as_str())))
+        .collect();
+    (xml_tag(content, "title"), items)
+}
+
+fn to_m3u(name: &str, items: &[String]) -> String {
+    let mut out = format!("#EXTM3U\n#PLAYLIST:{}\n", name);
+    for item in items {
+        out.push_str(&format!("#EXTINF:-1,{}\n{}\n", item_title(item), item));
+    }
+    out
+}
+
+fn to_xspf(name: &str, items: &[String]) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<playlist version=\"1\" xmlns=\"http://xspf.org/ns/0/\">\n");
+    out.push_str(&format!("  <title>{}</title>\n  <trackList>\n", escape_xml(name)));
+    for item in items {
+        out.push_str(&format!(
+            "    <track>\n      <location>{}</location>\n      <title>{}</title>\n    </track>\n",
+            escape_xml(&path_to_uri(item)),
+            escape_xml(&item_title(item)),
+        ));
+    }
+    out.push_str("  </trackList>\n</playlist>\n");
+    out
+}
+
+fn to_wpl(name: &str, items: &[String]) -> String {
+    let mut out = String::from("<?wpl version=\"1.0\"?>\n<smil>\n  <head>\n    <meta name=\"Generator\" content=\"FrameX\"/>\n");
+    out.push_str(&format!("    <title>{}</title>\n  </head>\n  <body>\n    <seq>\n", escape_xml(name)));
+    for item in items {
+        out.push_str(&format!("      <media src=\"{}\"/>\n", escape_xml(item)));
+    }
+    out.push_str("    </seq>\n  </body>\n</smil>\n");
+    out
+}
+
+fn extension(path: &str) -> String {
+    Path::new(path).extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase()
+}
+
+/// Create a playlist from an M3U/M3U8, XSPF or WPL file, chosen by extension.
+/// The playlist's own title is used unless `name` is given, falling back to the file name.
+#[command]
+pub fn import_playlist(app: AppHandle, path: String, name: Option<String>) -> Result<Playlist, String> {
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let (title, entries) = match extension(&path).as_str() {
+        "m3u" | "m3u8" => parse_m3u(&content),
+        "xspf" => parse_xspf(&content),
+        "wpl" => parse_wpl(&content),
+        other => return Err(format!("Unsupported playlist format '.{}'", other)),
+    };
+    let file = Path::new(&path);
+    let base = file.parent().unwrap_or(Path::new("."));
+    let items: Vec<String> = entries.iter().map(|e| resolve_entry(e, base)).collect();
+    if items.is_empty() {
+        return Err("The playlist has no entries".to_string());
+    }
+
+    let name = name
+        .or(title)
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or_else(|| file.file_stem().and_then(|s| s.to_str()).unwrap_or("Imported").to_string());
+    let playlist = crate::playlist::save_playlist(app, name, items, None)?;
+    tracing::info!("📥 Imported playlist '{}' ({} items) from {}", playlist.name, playlist.items.len(), path);
+    Ok(playlist)
+}
+
+/// Write a playlist or collection to `path` as M3U/M3U8, XSPF or WPL, chosen by extension.
+#[command]
+pub fn export_playlist(app: AppHandle, id: String, path: String) -> Result<usize, String> {
+    let (name, items) = crate::playlist::find_items(&app, &id).ok_or("Playlist or collection not found")?;
+    let content = match extension(&path).as_str() {
+        "m3u" | "m3u8" => to_m3u(&name, &items),
+        "xspf" => to_xspf(&name, &items),
+        "wpl" => to_wpl(&name, &items),
+        other => return Err(format!("Unsupported playlist format '.{}'", other)),
+    };
+    fs::write(&path, content).map_err(|e| e.to_string())?;
+    tracing::info!("📤 Exported playlist '{}' ({} items) to {}", name, items.len(), path);
+    Ok(items.len())
+}
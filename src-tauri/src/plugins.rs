@@ -0,0 +1,244 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{command, AppHandle, State};
+
+use crate::mpv_config::{find_mpv_config_dir, send_to_mpv};
+use crate::mpv_handler::{mpv_path, MpvCommand, MpvState};
+
+// Disabled scripts live next to scripts/ so mpv's own autoload doesn't pick them up either
+const DISABLED_DIR: &str = "scripts-disabled";
+const MAX_SCRIPT_BYTES: usize = 2 * 1024 * 1024;
+// Shipped with FrameX; the player UI depends on them
+const BUNDLED_SCRIPTS: [&str; 2] = ["framex-osc.lua", "framex-controls.lua"];
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Plugin {
+    pub name: String, // script name mpv knows it by (file stem)
+    pub file: String,
+    pub enabled: bool,
+    pub size: u64,
+    pub options: BTreeMap<String, String>,
+}
+
+struct Dirs {
+    scripts: PathBuf,
+    disabled: PathBuf,
+    opts: PathBuf,
+}
+
+fn dirs() -> Result<Dirs, String> {
+    let config_dir = find_mpv_config_dir().ok_or("mpv-config directory not found")?;
+    Ok(Dirs {
+        scripts: config_dir.join("scripts"),
+        disabled: config_dir.join(DISABLED_DIR),
+        opts: config_dir.join("script-opts"),
+    })
+}
+
+fn is_script(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()).is_some_and(|e| e == "lua" || e == "js")
+}
+
+/// The script file name from a user-supplied one, refusing anything that isn't a plain .lua/.js name.
+fn script_file(file: &str) -> Result<String, String> {
+    let name = Path::new(file).file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    if name.is_empty() || name != file || !is_script(Path::new(name)) {
+        return Err(format!("'{}' is not a .lua or .js script", file));
+    }
+    Ok(name.to_string())
+}
+
+fn ensure_not_bundled(file: &str) -> Result<(), String> {
+    if BUNDLED_SCRIPTS.iter().any(|b| b.eq_ignore_ascii_case(file)) {
+        return Err(format!("{} is part of FrameX and can't be replaced, removed or disabled", file));
+    }
+    Ok(())
+}
+
+fn script_name(file: &str) -> String {
+    Path::new(file).file_stem().and_then(|s| s.to_str()).unwrap_or(file).to_string()
+}
+
+/// Read `script-opts/<name>.conf` (`key=value` per line).
+fn read_options(dirs: &Dirs, name: &str) -> BTreeMap<String, String> {
+    let content = fs::read_to_string(dirs.opts.join(format!("{}.conf", name))).unwrap_or_default();
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .filter_map(|l| l.split_once('='))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect()
+}
+
+fn plugin_at(dirs: &Dirs, path: &Path, enabled: bool) -> Plugin {
+    let file = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let name = script_name(&file);
+    Plugin {
+        options: read_options(dirs, &name),
+        name,
+        size: fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        file,
+        enabled,
+    }
+}
+
+fn load(state: &State<'_, MpvState>, path: &Path) {
+    send_to_mpv(state, MpvCommand::LoadScript(mpv_path(path)));
+}
+
+#[command]
+pub fn list_plugins() -> Result<Vec<Plugin>, String> {
+    let dirs = dirs()?;
+    let mut plugins = Vec::new();
+    for (dir, enabled) in [(&dirs.scripts, true), (&dirs.disabled, false)] {
+        let Ok(entries) = fs::read_dir(dir) else { continue };
+        for path in entries.flatten().map(|e| e.path()).filter(|p| is_script(p)) {
+            plugins.push(plugin_at(&dirs, &path, enabled));
+        }
+    }
+    plugins.sort_by_key(|p| p.name.to_lowercase());
+    Ok(plugins)
+}
+
+/// Enable or disable a script, loading or unloading it in the running player.
+/// mpv can't stop a script from outside, so unloading asks it to exit through the
+/// `framex-unload` script message; scripts that don't handle it stay active until restart.
+#[command]
+pub fn set_plugin_enabled(app: AppHandle, state: State<'_, MpvState>, file: String, enabled: bool) -> Result<(), String> {
+    crate::parental::check_area(&app, "settings")?;
+    let file = script_file(&file)?;
+    if !enabled {
+        ensure_not_bundled(&file)?;
+    }
+    let dirs = dirs()?;
+    let (from, to) = if enabled { (&dirs.disabled, &dirs.scripts) } else { (&dirs.scripts, &dirs.disabled) };
+    if to.join(&file).exists() {
+        return Ok(());
+    }
+    if !from.join(&file).exists() {
+        return Err(format!("Script '{}' is not installed", file));
+    }
+    fs::create_dir_all(to).map_err(|e| e.to_string())?;
+    fs::rename(from.join(&file), to.join(&file)).map_err(|e| format!("Failed to move {}: {}", file, e))?;
+
+    if enabled {
+        load(&state, &to.join(&file));
+        tracing::info!("🧩 Enabled script {}", file);
+    } else {
+        send_to_mpv(&state, MpvCommand::UnloadScript(script_name(&file)));
+        tracing::info!("🧩 Disabled script {}", file);
+    }
+    Ok(())
+}
+
+/// Raw-file URL for GitHub "blob" pages, which serve HTML rather than the script.
+fn raw_url(url: &str) -> String {
+    match url.strip_prefix("https://github.com/") {
+        Some(rest) if rest.contains("/blob/") => format!("https://raw.githubusercontent.com/{}", rest.replacen("/blob/", "/", 1)),
+        _ => url.to_string(),
+    }
+}
+
+async fn download(url: &str) -> Result<(String, Vec<u8>), String> {
+    let url = tauri::Url::parse(&raw_url(url)).map_err(|e| format!("Invalid URL: {}", e))?;
+    if url.scheme() != "https" {
+        return Err("Scripts can only be downloaded over https".to_string());
+    }
+    let file = url.path_segments().and_then(|mut s| s.next_back()).unwrap_or_default().to_string();
+    let file = script_file(&file).map_err(|_| "The URL must point to a .lua or .js file".to_string())?;
+    let response = crate::metadata::http_client().get(url).send().await.map_err(|e| format!("Download failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Download failed: server returned {}", response.status()));
+    }
+    let bytes = response.bytes().await.map_err(|e| format!("Download failed: {}", e))?;
+    if bytes.len() > MAX_SCRIPT_BYTES {
+        return Err("That file is too large to be an mpv script".to_string());
+    }
+    Ok((file, bytes.to_vec()))
+}
+
+/// Install a script from a local file or an https URL and load it right away.
+/// Installing over an existing script of the same name replaces it.
+#[command]
+pub async fn install_plugin(app: AppHandle, state: State<'_, MpvState>, source: String) -> Result<Plugin, String> {
+    crate::parental::check_area(&app, "settings")?;
+    let (file, content) = if source.starts_with("http://") || source.starts_with("https://") {
+        download(&source).await?
+    } else {
+        let path = Path::new(&source);
+        let file = script_file(path.file_name().and_then(|n| n.to_str()).unwrap_or_default())?;
+        (file, fs::read(path).map_err(|e| format!("Failed to read {}: {}", source, e))?)
+    };
+    ensure_not_bundled(&file)?;
+    if std::str::from_utf8(&content).is_err() {
+        return Err(format!("{} is not a text script", file));
+    }
+
+    let dirs = dirs()?;
+    fs::create_dir_all(&dirs.scripts).map_err(|e| e.to_string())?;
+    let target = dirs.scripts.join(&file);
+    if target.exists() {
+        send_to_mpv(&state, MpvCommand::UnloadScript(script_name(&file)));
+    }
+    let _ = fs::remove_file(dirs.disabled.join(&file));
+    fs::write(&target, content).map_err(|e| format!("Failed to install {}: {}", file, e))?;
+    load(&state, &target);
+
+    tracing::info!("🧩 Installed script {}", file);
+    Ok(plugin_at(&dirs, &target, true))
+}
+
+/// Unload a script and delete it along with its options file.
+#[command]
+pub fn uninstall_plugin(app: AppHandle, state: State<'_, MpvState>, file: String) -> Result<(), String> {
+    crate::parental::check_area(&app, "settings")?;
+    let file = script_file(&file)?;
+    ensure_not_bundled(&file)?;
+    let dirs = dirs()?;
+    if dirs.scripts.join(&file).exists() {
+        send_to_mpv(&state, MpvCommand::UnloadScript(script_name(&file)));
+        fs::remove_file(dirs.scripts.join(&file)).map_err(|e| e.to_string())?;
+    } else {
+        fs::remove_file(dirs.disabled.join(&file)).map_err(|_| format!("Script '{}' is not installed", file))?;
+    }
+    let _ = fs::remove_file(dirs.opts.join(format!("{}.conf", script_name(&file))));
+    Ok(())
+}
+
+/// Replace a script's options. They're saved to script-opts/<name>.conf, which scripts
+/// using mp.options read at startup, and pushed into the running player's `script-opts`.
+#[command]
+pub fn set_plugin_options(app: AppHandle, state: State<'_, MpvState>, name: String, options: BTreeMap<String, String>) -> Result<(), String> {
+    crate::parental::check_area(&app, "settings")?;
+    if name.is_empty() || name.contains(['/', '\\', '.']) {
+        return Err(format!("Invalid script name: {}", name));
+    }
+    for (key, value) in &options {
+        if key.is_empty() || key.contains(['=', ',', '\n', '\r']) || value.contains(['\n', '\r']) {
+            return Err(format!("Invalid option: {}={}", key, value));
+        }
+    }
+
+    let dirs = dirs()?;
+    let previous = read_options(&dirs, &name);
+    let path = dirs.opts.join(format!("{}.conf", name));
+    if options.is_empty() {
+        let _ = fs::remove_file(&path);
+    } else {
+        fs::create_dir_all(&dirs.opts).map_err(|e| e.to_string())?;
+        let content: String = options.iter().map(|(k, v)| format!("{}={}\n", k, v)).collect();
+        fs::write(&path, content).map_err(|e| e.to_string())?;
+    }
+
+    // script-opts keys are prefixed with the script name
+    for key in previous.keys().filter(|k| !options.contains_key(*k)) {
+        send_to_mpv(&state, MpvCommand::SetScriptOpt(format!("{}-{}", name, key), None));
+    }
+    for (key, value) in options {
+        send_to_mpv(&state, MpvCommand::SetScriptOpt(format!("{}-{}", name, key), Some(value)));
+    }
+    Ok(())
+}
@@ -0,0 +1,530 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{command, AppHandle, Emitter, Manager, State, Window};
+
+use crate::config::load_config;
+use crate::util::now_secs;
+use crate::xml::{attribute, unescape_xml, xml_tag};
+
+// Podcast subscriptions: RSS feeds kept in app data and refreshed in the background.
+// Episodes play as `podcast://<feed id>/<episode id>/<title>` paths, which mpv resolves
+// to the downloaded file or the enclosure URL. The path is the same either way, so
+// watch history keeps one resume point per episode whether it was streamed or downloaded.
+// Only the two ids are read back; the title is there so history entries read well.
+
+pub const SCHEME: &str = "podcast://";
+
+// How often the background loop looks for feeds due a refresh
+const REFRESH_CHECK: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Episode {
+    pub id: String,
+    pub guid: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub published: Option<u64>, // unix seconds
+    pub duration: Option<f64>, // seconds, from itunes:duration
+    pub url: String, // the enclosure
+    pub mime: Option<String>,
+    pub size: Option<u64>,
+    pub image: Option<String>,
+    #[serde(default)]
+    pub download_path: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PodcastFeed {
+    pub id: String,
+    pub url: String,
+    pub title: String,
+    pub author: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub episodes: Vec<Episode>, // newest first
+    pub last_refreshed: u64,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct PodcastStore {
+    feeds: Vec<PodcastFeed>,
+}
+
+/// An episode with its `podcast://` path and listening progress from watch history.
+#[derive(Serialize, Debug)]
+pub struct EpisodeView {
+    #[serde(flatten)]
+    pub episode: Episode,
+    pub path: String,
+    pub position: Option<f64>,
+    pub finished: bool,
+}
+
+#[derive(Serialize, Clone)]
+struct DownloadProgress {
+    feed_id: String,
+    episode_id: String,
+    downloaded: u64,
+    total: Option<u64>,
+    done: bool,
+    error: Option<String>,
+}
+
+// The refresh loop and commands both rewrite the store; reads and writes go through this
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+fn store_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app.path().app_data_dir().map_err(|e| e.to_string())?.join("podcasts.json"))
+}
+
+fn load_store(app: &AppHandle) -> PodcastStore {
+    let _guard = STORE_LOCK.lock();
+    store_path(app)
+        .ok()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+/// Read-modify-write of the store under the lock. Nothing async may happen inside.
+fn update_store<T>(app: &AppHandle, f: impl FnOnce(&mut PodcastStore) -> Result<T, String>) -> Result<T, String> {
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let path = store_path(app)?;
+    let mut store: PodcastStore = fs::read_to_string(&path)
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default();
+    let result = f(&mut store)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(&path, serde_json::to_string(&store).map_err(|e| e.to_string())?).map_err(|e| e.to_string())?;
+    Ok(result)
+}
+
+fn short_id(seed: &str, len: usize) -> String {
+    uuid::Uuid::new_v5(&uuid::Uuid::NAMESPACE_URL, seed.as_bytes()).simple().to_string()[..len].to_string()
+}
+
+/// Title text safe for a path segment or file name.
+fn safe_name(title: &str) -> String {
+    let name: String = title
+        .chars()
+        .map(|c| if matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') || c.is_control() { '_' } else { c })
+        .take(120)
+        .collect();
+    match name.trim().trim_end_matches('.') {
+        "" => "episode".to_string(),
+        name => name.to_string(),
+    }
+}
+
+pub fn episode_path(feed_id: &str, episode: &Episode) -> String {
+    format!("{}{}/{}/{}", SCHEME, feed_id, episode.id, safe_name(&episode.title))
+}
+
+fn split_path(path: &str) -> Option<(&str, &str)> {
+    let mut parts = path.strip_prefix(SCHEME)?.split('/');
+    Some((parts.next()?, parts.next()?))
+}
+
+/// What mpv opens for a `podcast://` path: the download if it's still there, else the stream.
+pub fn playable_path(app: &AppHandle, path: &str) -> String {
+    let Some((feed_id, episode_id)) = split_path(path) else {
+        return path.to_string();
+    };
+    let store = load_store(app);
+    let episode = store
+        .feeds
+        .iter()
+        .find(|f| f.id == feed_id)
+        .and_then(|f| f.episodes.iter().find(|e| e.id == episode_id));
+    match episode {
+        Some(episode) => match &episode.download_path {
+            Some(file) if std::path::Path::new(file).exists() => file.clone(),
+            _ => episode.url.clone(),
+        },
+        None => {
+            tracing::warn!("Unknown podcast episode {}", path);
+            path.to_string()
+        }
+    }
+}
+
+/// `<tag ...>text</tag>` that may carry attributes (which `xml_tag` doesn't match); CDATA unwrapped.
+fn element(xml: &str, tag: &str) -> Option<String> {
+    let re = Regex::new(&format!(r"(?s)<{}(?:\s[^>]*)?>(.*?)</{}>", regex::escape(tag), regex::escape(tag))).unwrap();
+    let text = re.captures(xml)?[1].trim().to_string();
+    let text = match text.strip_prefix("<![CDATA[").and_then(|t| t.strip_suffix("]]>")) {
+        Some(inner) => inner.trim().to_string(),
+        None => unescape_xml(&text),
+    };
+    Some(text).filter(|t| !t.is_empty())
+}
+
+/// The attributes of the first `<tag .../>` (enclosure, itunes:image).
+fn empty_tag_attrs<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{} ", tag)).or_else(|| xml.find(&format!("<{}\t", tag)))?;
+    let rest = &xml[start + tag.len() + 1..];
+    Some(&rest[..rest.find('>')?])
+}
+
+/// Show notes as plain text: tags dropped, entities decoded.
+fn plain_text(html: &str) -> String {
+    let tags = Regex::new(r"(?s)<[^>]*>").unwrap();
+    let text = unescape_xml(&tags.replace_all(&html.replace("<br", "\n<br").replace("</p>", "\n</p>"), ""));
+    text.lines().map(str::trim).filter(|l| !l.is_empty()).collect::<Vec<_>>().join("\n")
+}
+
+/// RFC 2822 (`Tue, 10 Jun 2003 04:00:00 GMT` / `+0200`) as unix seconds.
+fn parse_pub_date(text: &str) -> Option<u64> {
+    let re = Regex::new(r"(\d{1,2})\s+([A-Za-z]{3})[a-z]*\s+(\d{4})\s+(\d{1,2}):(\d{2})(?::(\d{2}))?\s*([+-]\d{4}|[A-Za-z]+)?").unwrap();
+    let caps = re.captures(text)?;
+    const MONTHS: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+    let month = MONTHS.iter().position(|m| caps[2].eq_ignore_ascii_case(m))? + 1;
+    let iso = format!(
+        "{}-{:02}-{:02} {:02}:{}:{}",
+        &caps[3],
+        month,
+        caps[1].parse::<u32>().ok()?,
+        caps[4].parse::<u32>().ok()?,
+        &caps[5],
+        caps.get(6).map_or("00", |s| s.as_str())
+    );
+    let utc = crate::watch_history::parse_datetime(&iso)? as i64;
+    let offset = match caps.get(7).map(|z| z.as_str()) {
+        Some(z) if z.starts_with(['+', '-']) => {
+            let sign = if z.starts_with('-') { -1 } else { 1 };
+            sign * (z[1..3].parse::<i64>().ok()? * 3600 + z[3..5].parse::<i64>().ok()? * 60)
+        }
+        _ => 0, // GMT/UT/Z; other zone names are rare enough to read as UTC
+    };
+    u64::try_from(utc - offset).ok()
+}
+
+/// itunes:duration is `HH:MM:SS`, `MM:SS` or plain seconds.
+fn parse_duration(text: &str) -> Option<f64> {
+    text.trim()
+        .split(':')
+        .try_fold(0.0, |total, part| part.trim().parse::<f64>().ok().map(|n| total * 60.0 + n))
+        .filter(|d| *d > 0.0)
+}
+
+/// Feed metadata and episodes (those with an enclosure) from an RSS document.
+fn parse_feed(xml: &str, url: &str) -> Result<PodcastFeed, String> {
+    let channel = Regex::new(r"(?s)<channel\b[^>]*>(.*)</channel>").unwrap();
+    let channel = channel.captures(xml).map(|c| c[1].to_string()).ok_or("Not an RSS feed")?;
+    let item = Regex::new(r"(?s)<item\b[^>]*>(.*?)</item>").unwrap();
+    // Channel fields come before the first item
+    let header = channel.split("<item").next().unwrap_or_default();
+
+    let mut episodes = Vec::new();
+    for c in item.captures_iter(&channel) {
+        let body = &c[1];
+        let Some(enclosure) = empty_tag_attrs(body, "enclosure") else {
+            continue;
+        };
+        let Some(media_url) = attribute(enclosure, "url") else {
+            continue;
+        };
+        let guid = element(body, "guid").unwrap_or_else(|| media_url.clone());
+        episodes.push(Episode {
+            id: short_id(&guid, 12),
+            guid,
+            title: element(body, "title").unwrap_or_else(|| "Untitled episode".to_string()),
+            description: element(body, "itunes:summary")
+                .or_else(|| element(body, "description"))
+                .or_else(|| element(body, "content:encoded"))
+                .map(|d| plain_text(&d)),
+            published: element(body, "pubDate").and_then(|d| parse_pub_date(&d)),
+            duration: element(body, "itunes:duration").and_then(|d| parse_duration(&d)),
+            url: media_url,
+            mime: attribute(enclosure, "type"),
+            size: attribute(enclosure, "length").and_then(|l| l.parse().ok()).filter(|l| *l > 0),
+            image: empty_tag_attrs(body, "itunes:image").and_then(|a| attribute(a, "href")),
+            download_path: None,
+        });
+    }
+    episodes.sort_by_key(|e| std::cmp::Reverse(e.published));
+
+    Ok(PodcastFeed {
+        id: short_id(url, 8),
+        url: url.to_string(),
+        title: element(header, "title").unwrap_or_else(|| url.to_string()),
+        author: element(header, "itunes:author").or_else(|| element(header, "managingEditor")),
+        description: element(header, "itunes:summary").or_else(|| element(header, "description")).map(|d| plain_text(&d)),
+        image: empty_tag_attrs(header, "itunes:image")
+            .and_then(|a| attribute(a, "href"))
+            .or_else(|| element(header, "image").and_then(|i| xml_tag(&i, "url"))),
+        episodes,
+        last_refreshed: now_secs(),
+    })
+}
+
+async fn fetch_feed(url: &str) -> Result<PodcastFeed, String> {
+    let xml = crate::metadata::http_client()
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to fetch feed: {}", e))?
+        .text()
+        .await
+        .map_err(|e| e.to_string())?;
+    parse_feed(&xml, url)
+}
+
+/// Fold a fresh copy of a feed into the stored one, keeping download paths. Returns new episodes.
+fn merge_feed(existing: &mut PodcastFeed, fresh: PodcastFeed) -> usize {
+    let known: HashMap<String, Option<String>> = existing.episodes.iter().map(|e| (e.id.clone(), e.download_path.clone())).collect();
+    let mut added = 0;
+    let mut episodes = fresh.episodes;
+    for episode in &mut episodes {
+        match known.get(&episode.id) {
+            Some(download) => episode.download_path = download.clone(),
+            None => added += 1,
+        }
+    }
+    // Downloaded episodes stay even after the feed drops them
+    for old in &existing.episodes {
+        if old.download_path.is_some() && !episodes.iter().any(|e| e.id == old.id) {
+            episodes.push(old.clone());
+        }
+    }
+    episodes.sort_by_key(|e| std::cmp::Reverse(e.published));
+    *existing = PodcastFeed { id: existing.id.clone(), episodes, ..fresh };
+    added
+}
+
+async fn refresh_one(app: &AppHandle, id: &str) -> Result<usize, String> {
+    let url = load_store(app).feeds.into_iter().find(|f| f.id == id).map(|f| f.url).ok_or("Podcast not found")?;
+    let fresh = fetch_feed(&url).await?;
+    update_store(app, |store| {
+        let feed = store.feeds.iter_mut().find(|f| f.id == id).ok_or("Podcast not found")?;
+        Ok(merge_feed(feed, fresh))
+    })
+}
+
+/// Background refresh of feeds older than `refresh_hours`; emits `podcasts-updated`.
+pub fn start_refresh_loop(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        // Let startup finish first
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        loop {
+            let max_age = load_config(&app).podcasts.refresh_hours * 3600;
+            let due: Vec<String> = load_store(&app)
+                .feeds
+                .into_iter()
+                .filter(|f| max_age > 0 && now_secs().saturating_sub(f.last_refreshed) >= max_age)
+                .map(|f| f.id)
+                .collect();
+            let mut added = 0;
+            for id in &due {
+                match refresh_one(&app, id).await {
+                    Ok(n) => added += n,
+                    Err(e) => tracing::error!("Podcast refresh failed for {}: {}", id, e),
+                }
+            }
+            if !due.is_empty() {
+                tracing::info!("🎙️ Refreshed {} podcast(s), {} new episode(s)", due.len(), added);
+                let _ = app.emit("podcasts-updated", added);
+            }
+            tokio::time::sleep(REFRESH_CHECK).await;
+        }
+    });
+}
+
+#[command]
+pub async fn subscribe_podcast(app: AppHandle, url: String) -> Result<PodcastFeed, String> {
+    let url = url.trim().to_string();
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err("A feed URL is required".to_string());
+    }
+    let feed = fetch_feed(&url).await?;
+    update_store(&app, |store| {
+        if store.feeds.iter().any(|f| f.id == feed.id) {
+            return Err(format!("Already subscribed to {}", feed.title));
+        }
+        store.feeds.push(feed.clone());
+        Ok(())
+    })?;
+    tracing::info!("🎙️ Subscribed to '{}' ({} episodes)", feed.title, feed.episodes.len());
+    Ok(feed)
+}
+
+/// Subscriptions, with only their latest episode.
+#[command]
+pub fn list_podcasts(app: AppHandle) -> Result<Vec<PodcastFeed>, String> {
+    Ok(load_store(&app)
+        .feeds
+        .into_iter()
+        .map(|f| PodcastFeed { episodes: f.episodes.into_iter().take(1).collect(), ..f })
+        .collect())
+}
+
+#[command]
+pub fn get_podcast_episodes(app: AppHandle, id: String) -> Result<Vec<EpisodeView>, String> {
+    let feed = load_store(&app).feeds.into_iter().find(|f| f.id == id).ok_or("Podcast not found")?;
+    let paths: Vec<String> = feed.episodes.iter().map(|e| episode_path(&feed.id, e)).collect();
+    let records = crate::watch_history::records_for(&app, &paths)?;
+    Ok(feed
+        .episodes
+        .into_iter()
+        .zip(paths)
+        .map(|(episode, path)| {
+            let record = records.get(&path);
+            EpisodeView {
+                position: record.filter(|r| r.resumable).map(|r| r.position),
+                finished: record.is_some_and(|r| !r.resumable || r.times_completed > 0),
+                episode,
+                path,
+            }
+        })
+        .collect())
+}
+
+/// Refresh one feed now, or every feed when `id` is omitted. Returns the number of new episodes.
+#[command]
+pub async fn refresh_podcasts(app: AppHandle, id: Option<String>) -> Result<usize, String> {
+    let ids = match id {
+        Some(id) => vec![id],
+        None => load_store(&app).feeds.into_iter().map(|f| f.id).collect(),
+    };
+    let mut added = 0;
+    for id in &ids {
+        added += refresh_one(&app, id).await?;
+    }
+    Ok(added)
+}
+
+/// Unsubscribe; downloaded episodes are deleted too.
+#[command]
+pub fn unsubscribe_podcast(app: AppHandle, id: String) -> Result<(), String> {
+    let feed = update_store(&app, |store| {
+        let index = store.feeds.iter().position(|f| f.id == id).ok_or("Podcast not found")?;
+        Ok(store.feeds.remove(index))
+    })?;
+    for file in feed.episodes.iter().filter_map(|e| e.download_path.as_ref()) {
+        let _ = fs::remove_file(file);
+    }
+    Ok(())
+}
+
+/// Play an episode, picking up where it was left off.
+#[command]
+pub fn play_podcast_episode(window: Window, state: State<'_, crate::mpv_handler::MpvState>, path: String) -> Result<(), String> {
+    let resume = crate::watch_history::get_watch_position(window.app_handle().clone(), path.clone())?;
+    crate::mpv_handler::start_player(&window, &state);
+    if let (Some(position), Some(tx)) = (resume, state.tx.lock().unwrap().as_ref()) {
+        // Cleared again once the file has loaded
+        let _ = tx.send(crate::mpv_handler::MpvCommand::SetOption("start".to_string(), position.to_string()));
+    }
+    crate::mpv_handler::load_video(window, state, path)
+}
+
+fn download_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    match load_config(app).podcasts.download_dir {
+        Some(dir) => Ok(PathBuf::from(dir)),
+        None => Ok(app.path().app_data_dir().map_err(|e| e.to_string())?.join("podcasts")),
+    }
+}
+
+/// Download an episode for offline listening, emitting `podcast-download-progress`.
+#[command]
+pub async fn download_podcast_episode(app: AppHandle, feed_id: String, episode_id: String) -> Result<String, String> {
+    let feed = load_store(&app).feeds.into_iter().find(|f| f.id == feed_id).ok_or("Podcast not found")?;
+    let episode = feed.episodes.iter().find(|e| e.id == episode_id).cloned().ok_or("Episode not found")?;
+
+    let progress = |downloaded: u64, total: Option<u64>, done: bool, error: Option<String>| {
+        let _ = app.emit("podcast-download-progress", DownloadProgress {
+            feed_id: feed_id.clone(),
+            episode_id: episode_id.clone(),
+            downloaded,
+            total,
+            done,
+            error,
+        });
+    };
+    let result = async {
+        let mut response = crate::metadata::http_client()
+            .get(&episode.url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("Download failed: {}", e))?;
+        let total = response.content_length().or(episode.size);
+        let ext = episode
+            .url
+            .split(['?', '#'])
+            .next()
+            .and_then(|u| u.rsplit('/').next())
+            .and_then(|name| name.rsplit_once('.').map(|(_, ext)| ext.to_lowercase()))
+            .filter(|ext| ext.len() <= 4 && ext.chars().all(|c| c.is_ascii_alphanumeric()))
+            .unwrap_or_else(|| "mp3".to_string());
+        let dir = download_dir(&app)?.join(safe_name(&feed.title));
+        fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        let target = dir.join(format!("{}.{}", safe_name(&episode.title), ext));
+        let partial = target.with_extension(format!("{}.part", ext));
+
+        let mut file = fs::File::create(&partial).map_err(|e| e.to_string())?;
+        let mut downloaded = 0u64;
+        let mut last_emit = Instant::now();
+        while let Some(chunk) = response.chunk().await.map_err(|e| format!("Download failed: {}", e))? {
+            file.write_all(&chunk).map_err(|e| e.to_string())?;
+            downloaded += chunk.len() as u64;
+            if last_emit.elapsed() >= Duration::from_millis(500) {
+                last_emit = Instant::now();
+                progress(downloaded, total, false, None);
+            }
+        }
+        drop(file);
+        fs::rename(&partial, &target).map_err(|e| e.to_string())?;
+        Ok::<_, String>((target.to_string_lossy().to_string(), downloaded, total))
+    }
+    .await;
+
+    match result {
+        Ok((path, downloaded, total)) => {
+            update_store(&app, |store| {
+                let episode = store
+                    .feeds
+                    .iter_mut()
+                    .find(|f| f.id == feed_id)
+                    .and_then(|f| f.episodes.iter_mut().find(|e| e.id == episode_id))
+                    .ok_or("Episode not found")?;
+                episode.download_path = Some(path.clone());
+                Ok(())
+            })?;
+            progress(downloaded, total, true, None);
+            tracing::info!("🎙️ Downloaded '{}'", episode.title);
+            Ok(path)
+        }
+        Err(e) => {
+            progress(0, None, true, Some(e.clone()));
+            Err(e)
+        }
+    }
+}
+
+#[command]
+pub fn delete_podcast_download(app: AppHandle, feed_id: String, episode_id: String) -> Result<(), String> {
+    let file = update_store(&app, |store| {
+        let episode = store
+            .feeds
+            .iter_mut()
+            .find(|f| f.id == feed_id)
+            .and_then(|f| f.episodes.iter_mut().find(|e| e.id == episode_id))
+            .ok_or("Episode not found")?;
+        Ok(episode.download_path.take())
+    })?;
+    if let Some(file) = file {
+        fs::remove_file(&file).map_err(|e| format!("Failed to delete {}: {}", file, e))?;
+    }
+    Ok(())
+}
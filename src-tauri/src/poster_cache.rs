@@ -0,0 +1,187 @@
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{command, AppHandle, Manager};
+use walkdir::WalkDir;
+
+use crate::file_scanner::is_video;
+
+#[derive(Serialize, Debug, Default)]
+pub struct PosterMigrationSummary {
+    pub moved: Vec<String>,
+    pub copied: Vec<String>, // user artwork, left in place
+    pub skipped: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+fn is_central(app: &AppHandle) -> bool {
+    crate::config::load_config(app).metadata.poster_storage == "central"
+}
+
+/// Poster sidecar FrameX writes: `<stem>.poster.jpg` next to a video, `poster.jpg` inside a folder.
+pub fn sidecar_poster(path: &Path) -> Option<PathBuf> {
+    if path.is_dir() {
+        return Some(path.join("poster.jpg"));
+    }
+    let stem = path.file_stem()?.to_str()?;
+    Some(path.parent()?.join(format!("{}.poster.jpg", stem)))
+}
+
+/// `app_data/artwork/posters/<path hash>.jpg`, which never touches the media folder.
+pub fn central_poster(app: &AppHandle, path: &Path) -> Option<PathBuf> {
+    let dir = app.path().app_data_dir().ok()?.join("artwork").join("posters");
+    Some(crate::util::hashed_file(&dir, &path.to_string_lossy(), "jpg"))
+}
+
+// Sidecar posters FrameX created itself; anything else next to the media belongs to the user
+fn written_sidecars_path(app: &AppHandle) -> Option<PathBuf> {
+    Some(app.path().app_data_dir().ok()?.join("artwork").join("written_sidecars.json"))
+}
+
+fn load_written_sidecars(app: &AppHandle) -> HashSet<String> {
+    written_sidecars_path(app)
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_written_sidecars(app: &AppHandle, written: &HashSet<String>) {
+    let Some(path) = written_sidecars_path(app) else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(json) = serde_json::to_string(written) {
+        let _ = fs::write(path, json);
+    }
+}
+
+/// Where a newly downloaded poster for `path` should be written, per the storage setting.
+/// A sidecar the user put there themselves is never overwritten; the central cache is used instead.
+pub fn poster_destination(app: &AppHandle, path: &Path) -> Result<PathBuf, String> {
+    let mut central = is_central(app);
+    let mut dest = if central { central_poster(app, path) } else { sidecar_poster(path) };
+    if let Some(sidecar) = dest.as_ref().filter(|d| !central && d.exists()) {
+        if !load_written_sidecars(app).contains(&*sidecar.to_string_lossy()) {
+            central = true;
+            dest = central_poster(app, path);
+        }
+    }
+    let dest = dest.ok_or("Could not resolve poster path")?;
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    if !central && !dest.exists() {
+        let mut written = load_written_sidecars(app);
+        if written.insert(dest.to_string_lossy().to_string()) {
+            save_written_sidecars(app, &written);
+        }
+    }
+    Ok(dest)
+}
+
+/// Delete the posters FrameX stored for `path`: the central copy and sidecars it wrote itself.
+/// User-supplied artwork next to the media is left alone. Returns how many files were removed.
+pub fn remove_posters(app: &AppHandle, path: &Path) -> usize {
+    let mut removed = 0;
+    if let Some(central) = central_poster(app, path).filter(|p| p.exists()) {
+        if fs::remove_file(&central).is_ok() {
+            removed += 1;
+        }
+    }
+    let mut written = load_written_sidecars(app);
+    if let Some(sidecar) = sidecar_poster(path) {
+        let key = sidecar.to_string_lossy().to_string();
+        if written.contains(&key) {
+            if sidecar.exists() && fs::remove_file(&sidecar).is_ok() {
+                removed += 1;
+            }
+            if !sidecar.exists() {
+                written.remove(&key);
+                save_written_sidecars(app, &written);
+            }
+        }
+    }
+    removed
+}
+
+/// An existing poster for `path`: the central cache first, then the sidecar conventions.
+pub fn find_poster(app: &AppHandle, path: &Path) -> Option<PathBuf> {
+    if let Some(central) = central_poster(app, path).filter(|p| p.exists()) {
+        return Some(central);
+    }
+    let mut candidates = vec![sidecar_poster(path)?];
+    if path.is_dir() {
+        // Also accept `{folder_name}.poster.jpg` inside the folder
+        if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+            candidates.push(path.join(format!("{}.poster.jpg", name)));
+        }
+    } else if let (Some(parent), Some(stem)) = (path.parent(), path.file_stem().and_then(|s| s.to_str())) {
+        // Plain `<stem>.jpg` is a common convention too
+        candidates.push(parent.join(format!("{}.jpg", stem)));
+    }
+    candidates.into_iter().find(|p| p.exists())
+}
+
+fn move_file(from: &Path, to: &Path) -> Result<(), String> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    // Different volume: copy, then remove the original
+    fs::copy(from, to).map_err(|e| e.to_string())?;
+    fs::remove_file(from).map_err(|e| format!("copied, but could not remove original: {}", e))
+}
+
+/// Bring existing `<stem>.poster.jpg` / folder `poster.jpg` sidecars under `root` into the central
+/// cache. Sidecars FrameX wrote are moved; anything else is the user's and is only copied.
+#[command]
+pub fn migrate_posters_to_cache(app: AppHandle, root: String) -> Result<PosterMigrationSummary, String> {
+    if !Path::new(&root).is_dir() {
+        return Err("Not a valid directory".to_string());
+    }
+
+    let mut summary = PosterMigrationSummary::default();
+    let mut written = load_written_sidecars(&app);
+    let written_before = written.len();
+    for path in WalkDir::new(&root).into_iter().flatten().map(|e| e.into_path()) {
+        if !is_video(&path) && !path.is_dir() {
+            continue;
+        }
+        let Some(sidecar) = sidecar_poster(&path).filter(|p| p.exists()) else {
+            continue;
+        };
+        let Some(dest) = central_poster(&app, &path) else {
+            continue;
+        };
+        if dest.exists() {
+            summary.skipped.push(sidecar.to_string_lossy().to_string());
+            continue;
+        }
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let key = sidecar.to_string_lossy().to_string();
+        if written.contains(&key) {
+            match move_file(&sidecar, &dest) {
+                Ok(()) => {
+                    written.remove(&key);
+                    summary.moved.push(key);
+                }
+                Err(e) => summary.errors.push(format!("{}: {}", sidecar.display(), e)),
+            }
+        } else {
+            match fs::copy(&sidecar, &dest) {
+                Ok(_) => summary.copied.push(key),
+                Err(e) => summary.errors.push(format!("{}: {}", sidecar.display(), e)),
+            }
+        }
+    }
+    if written.len() != written_before {
+        save_written_sidecars(&app, &written);
+    }
+
+    tracing::info!("🖼️  Poster migration: {} moved, {} copied, {} skipped, {} errors", summary.moved.len(), summary.copied.len(), summary.skipped.len(), summary.errors.len());
+    Ok(summary)
+}
@@ -0,0 +1,241 @@
+use serde::Serialize;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Mutex;
+use tauri::{command, AppHandle, Emitter, Manager, State, Window};
+
+use crate::mpv_handler::{MpvCommand, MpvState};
+
+/// What mpv is playing through, mirrored for the UI via `queue-changed`.
+#[derive(Serialize, Clone, Debug)]
+pub struct QueueSnapshot {
+    pub playlist_id: Option<String>,
+    pub items: Vec<String>, // in play order (already shuffled)
+    pub index: Option<usize>,
+    pub shuffle: bool,
+    pub repeat: String, // "off" | "one" | "all"
+}
+
+impl Default for QueueSnapshot {
+    fn default() -> Self {
+        Self {
+            playlist_id: None,
+            items: Vec::new(),
+            index: None,
+            shuffle: false,
+            repeat: "off".to_string(),
+        }
+    }
+}
+
+pub struct QueueState {
+    queue: Mutex<QueueSnapshot>,
+}
+
+impl QueueState {
+    pub fn new() -> Self {
+        Self {
+            queue: Mutex::new(QueueSnapshot::default()),
+        }
+    }
+
+    fn replace(&self, app: &AppHandle, queue: QueueSnapshot) {
+        *self.queue.lock().unwrap() = queue.clone();
+        let _ = app.emit("queue-changed", queue);
+    }
+
+    /// A file opened directly ends any playlist playback and becomes a one-item queue,
+    /// so more items can be queued after it.
+    pub fn play_single(&self, app: &AppHandle, path: String) {
+        let queue = QueueSnapshot {
+            items: vec![path],
+            index: Some(0),
+            ..QueueSnapshot::default()
+        };
+        self.replace(app, queue);
+    }
+
+    /// Whether something is playing (or paused) through the queue.
+    pub fn is_active(&self) -> bool {
+        self.queue.lock().unwrap().index.is_some()
+    }
+
+    /// Insert `paths` at `at` (clamped to the end), returning the updated queue and where they went.
+    fn insert(&self, app: &AppHandle, at: usize, paths: Vec<String>) -> (QueueSnapshot, usize) {
+        let (queue, at) = {
+            let mut queue = self.queue.lock().unwrap();
+            let at = at.min(queue.items.len());
+            queue.items.splice(at..at, paths);
+            (queue.clone(), at)
+        };
+        let _ = app.emit("queue-changed", queue.clone());
+        (queue, at)
+    }
+
+    /// mpv's `playlist-pos` changed.
+    pub fn set_index(&self, app: &AppHandle, pos: i64) {
+        let queue = {
+            let mut queue = self.queue.lock().unwrap();
+            let index = usize::try_from(pos).ok().filter(|&i| i < queue.items.len());
+            if queue.items.is_empty() || queue.index == index {
+                return;
+            }
+            queue.index = index;
+            queue.clone()
+        };
+        if let Some(path) = queue.index.map(|i| queue.items[i].clone()) {
+            if let Some(id) = &queue.playlist_id {
+                if let Err(e) = crate::playlist::remember_last_item(app, id, &path) {
+                    tracing::error!("Failed to remember playlist position: {}", e);
+                }
+            }
+            let _ = crate::config::save_last_video_internal(app, path);
+        }
+        let _ = app.emit("queue-changed", queue);
+    }
+}
+
+/// Fisher-Yates shuffle seeded by the std hasher's random keys (no rand dependency).
+fn shuffle_items(items: &mut [String]) {
+    let random = RandomState::new();
+    for i in (1..items.len()).rev() {
+        let mut hasher = random.build_hasher();
+        hasher.write_usize(i);
+        let j = (hasher.finish() % (i as u64 + 1)) as usize;
+        items.swap(i, j);
+    }
+}
+
+fn send(state: &State<'_, MpvState>, cmd: MpvCommand) {
+    if let Some(tx) = state.tx.lock().unwrap().as_ref() {
+        let _ = tx.send(cmd);
+    }
+}
+
+/// Play a playlist or collection through mpv starting at `start_index`.
+/// With `shuffle`, the starting item plays first and the rest follow in random order.
+/// Options left out fall back to how this playlist was last played (see `PlaybackPrefs`),
+/// so it resumes at the last item with the same shuffle/repeat mode.
+#[command]
+pub fn play_playlist(
+    window: Window,
+    state: State<'_, MpvState>,
+    id: String,
+    start_index: Option<usize>,
+    shuffle: Option<bool>,
+    repeat: Option<String>,
+) -> Result<QueueSnapshot, String> {
+    let app = window.app_handle();
+    let (_, mut items) = crate::playlist::find_items(app, &id).ok_or("Playlist or collection not found")?;
+    if items.is_empty() {
+        return Err("Playlist is empty".to_string());
+    }
+    let prefs = crate::playlist::playback_prefs(app, &id);
+    let start = start_index
+        .or(prefs.last_index.filter(|&i| i < items.len()))
+        .unwrap_or(0);
+    if start >= items.len() {
+        return Err(format!("Item {} is out of range", start));
+    }
+    let guard = crate::parental::Guard::new(app);
+    guard.check_collection(&id)?;
+    guard.check_playable(&items[start])?;
+    let shuffle = shuffle.unwrap_or(prefs.shuffle);
+    let repeat = repeat.unwrap_or(prefs.repeat);
+    if !matches!(repeat.as_str(), "off" | "one" | "all") {
+        return Err(format!("Unknown repeat mode '{}'", repeat));
+    }
+    let prefs = crate::playlist::PlaybackPrefs {
+        shuffle,
+        repeat: repeat.clone(),
+        last_index: Some(start),
+    };
+    if let Err(e) = crate::playlist::save_playback_prefs(app, &id, prefs) {
+        tracing::error!("Failed to save playlist playback preferences: {}", e);
+    }
+
+    // Blocked items are left out, keeping the chosen one in place
+    let start = start - items[..start].iter().filter(|item| !guard.playable(item)).count();
+    items.retain(|item| guard.playable(item));
+    let start = if shuffle {
+        let first = items.remove(start);
+        shuffle_items(&mut items);
+        items.insert(0, first);
+        0
+    } else {
+        start
+    };
+
+    crate::mpv_handler::start_player(&window, &state);
+    let queue = QueueSnapshot {
+        playlist_id: Some(id),
+        items: items.clone(),
+        index: Some(start),
+        shuffle,
+        repeat: repeat.clone(),
+    };
+    app.state::<QueueState>().replace(app, queue.clone());
+    let _ = crate::config::save_last_video_internal(app, items[start].clone());
+
+    send(&state, MpvCommand::SetLoop(repeat));
+    send(&state, MpvCommand::LoadQueue(items, start));
+    Ok(queue)
+}
+
+/// With nothing queued, queue commands just start playing the given files.
+pub(crate) fn start_queue(window: &Window, state: &State<'_, MpvState>, paths: Vec<String>) -> QueueSnapshot {
+    crate::mpv_handler::start_player(window, state);
+    let queue = QueueSnapshot {
+        items: paths.clone(),
+        index: Some(0),
+        ..QueueSnapshot::default()
+    };
+    let app = window.app_handle();
+    app.state::<QueueState>().replace(app, queue.clone());
+    let _ = crate::config::save_last_video_internal(app, paths[0].clone());
+    send(state, MpvCommand::LoadQueue(paths, 0));
+    queue
+}
+
+/// "Play next": insert the files right after the one playing now.
+#[command]
+pub fn queue_play_next(window: Window, state: State<'_, MpvState>, mut paths: Vec<String>) -> Result<QueueSnapshot, String> {
+    let app = window.app_handle();
+    let guard = crate::parental::Guard::new(app);
+    paths.retain(|p| guard.playable(p));
+    if paths.is_empty() {
+        return Err("Nothing to queue".to_string());
+    }
+    let queue_state = app.state::<QueueState>();
+    let current = queue_state.queue.lock().unwrap().index;
+    let Some(current) = current else {
+        return Ok(start_queue(&window, &state, paths));
+    };
+    let (queue, at) = queue_state.insert(app, current + 1, paths.clone());
+    send(&state, MpvCommand::InsertIntoQueue(paths, at));
+    Ok(queue)
+}
+
+/// "Add to queue": append the files after everything already queued.
+#[command]
+pub fn queue_add(window: Window, state: State<'_, MpvState>, mut paths: Vec<String>) -> Result<QueueSnapshot, String> {
+    let app = window.app_handle();
+    let guard = crate::parental::Guard::new(app);
+    paths.retain(|p| guard.playable(p));
+    if paths.is_empty() {
+        return Err("Nothing to queue".to_string());
+    }
+    let queue_state = app.state::<QueueState>();
+    if queue_state.queue.lock().unwrap().index.is_none() {
+        return Ok(start_queue(&window, &state, paths));
+    }
+    let (queue, _) = queue_state.insert(app, usize::MAX, paths.clone());
+    send(&state, MpvCommand::AppendToQueue(paths));
+    Ok(queue)
+}
+
+/// The queue in play order with the index of the item playing now.
+#[command]
+pub fn get_queue(app: AppHandle) -> QueueSnapshot {
+    app.state::<QueueState>().queue.lock().unwrap().clone()
+}
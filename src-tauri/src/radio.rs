@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle, Manager, State, Window};
+
+use crate::config::{load_config, save_config};
+use crate::mpv_handler::{MpvCommand, MpvState};
+
+// Saved internet radio / audio stream stations. Playback goes through mpv like any stream;
+// the track title Shoutcast/Icecast servers send in-band (ICY metadata) surfaces as mpv's
+// `icy-title` metadata, which the player loop forwards as `mpv-stream-title`.
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RadioStation {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub url: String, // the stream itself, or a .pls/.m3u pointing at it
+    #[serde(default)]
+    pub logo: Option<String>,
+    #[serde(default)]
+    pub genre: Option<String>,
+}
+
+#[command]
+pub fn list_radio_stations(app: AppHandle) -> Result<Vec<RadioStation>, String> {
+    Ok(load_config(&app).radio_stations)
+}
+
+/// Add a station, or update the one with the same `id`.
+#[command]
+pub fn save_radio_station(app: AppHandle, station: RadioStation) -> Result<RadioStation, String> {
+    let clean = |value: Option<String>| value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+    let mut station = RadioStation {
+        name: station.name.trim().to_string(),
+        url: station.url.trim().to_string(),
+        logo: clean(station.logo),
+        genre: clean(station.genre),
+        ..station
+    };
+    if !station.url.contains("://") {
+        return Err("A stream URL is required".to_string());
+    }
+    if station.id.is_empty() {
+        station.id = uuid::Uuid::new_v4().simple().to_string()[..8].to_string();
+    }
+    if station.name.is_empty() {
+        station.name = station.url.clone();
+    }
+
+    let mut config = load_config(&app);
+    match config.radio_stations.iter_mut().find(|s| s.id == station.id) {
+        Some(existing) => *existing = station.clone(),
+        None => config.radio_stations.push(station.clone()),
+    }
+    save_config(&app, &config)?;
+    tracing::info!("📻 Saved radio station '{}'", station.name);
+    Ok(station)
+}
+
+#[command]
+pub fn delete_radio_station(app: AppHandle, id: String) -> Result<(), String> {
+    let mut config = load_config(&app);
+    let before = config.radio_stations.len();
+    config.radio_stations.retain(|s| s.id != id);
+    if config.radio_stations.len() == before {
+        return Err("Radio station not found".to_string());
+    }
+    save_config(&app, &config)
+}
+
+#[command]
+pub fn play_radio_station(window: Window, state: State<'_, MpvState>, id: String) -> Result<RadioStation, String> {
+    let app = window.app_handle().clone();
+    let station = load_config(&app).radio_stations.into_iter().find(|s| s.id == id).ok_or("Radio station not found")?;
+    let options = vec![
+        ("cache".to_string(), "yes".to_string()),
+        ("demuxer-readahead-secs".to_string(), "10".to_string()),
+        ("network-timeout".to_string(), "15".to_string()),
+        // Servers drop idle listeners now and then; reconnect rather than stop
+        ("stream-lavf-o".to_string(), "reconnect=1,reconnect_streamed=1,reconnect_delay_max=5".to_string()),
+        // The station name until the server sends a track title
+        ("force-media-title".to_string(), station.name.clone()),
+    ];
+
+    crate::mpv_handler::start_player(&window, &state);
+    app.state::<crate::queue::QueueState>().play_single(&app, station.url.clone());
+    if let Some(tx) = state.tx.lock().unwrap().as_ref() {
+        let _ = tx.send(MpvCommand::LoadStream(station.url.clone(), options));
+    }
+    tracing::info!("📻 Tuning to {}", station.name);
+    Ok(station)
+}
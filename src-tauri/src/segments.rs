@@ -0,0 +1,316 @@
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::{command, AppHandle, Emitter, Manager, State};
+
+use crate::util::now_secs;
+use crate::watch_history::with_db;
+
+// Skip ranges inside a file: marked by the user ("manual"), fetched from SponsorBlock for
+// YouTube videos ("sponsorblock"), or found by intro/credits detection ("detected").
+// They live in the watch history database, keyed by the path shown to the user.
+
+pub const KINDS: &[&str] = &["intro", "recap", "credits", "preview", "sponsor"];
+
+const SPONSORBLOCK_API: &str = "https://sponsor.ajay.app/api/skipSegments";
+const SPONSORBLOCK_CATEGORIES: &str = r#"["sponsor","selfpromo","interaction","intro","outro","preview"]"#;
+// Community segments are looked up again once the stored ones are this old
+const SPONSORBLOCK_TTL_SECS: u64 = 24 * 60 * 60;
+// Playing into a segment within this much of its start auto-skips it; seeking further in only prompts
+const ENTRY_WINDOW_SECS: f64 = 2.0;
+const MIN_SEGMENT_SECS: f64 = 1.0;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Segment {
+    pub id: i64,
+    pub start: f64,
+    pub end: f64,
+    pub kind: String,
+    pub source: String,
+}
+
+/// Segments of the file mpv is playing, emitted as `segments` whenever they change.
+#[derive(Serialize, Clone, Debug)]
+pub struct FileSegments {
+    pub path: String,
+    pub segments: Vec<Segment>,
+}
+
+pub struct SegmentState {
+    current: Mutex<Option<FileSegments>>,
+}
+
+impl SegmentState {
+    pub fn new() -> Self {
+        Self { current: Mutex::new(None) }
+    }
+}
+
+fn stored(app: &AppHandle, path: &str) -> Result<Vec<Segment>, String> {
+    with_db(app, |conn| {
+        let mut stmt = conn.prepare("SELECT id, start, end, kind, source FROM segments WHERE path = ?1 ORDER BY start")?;
+        let rows = stmt.query_map(params![path], |row| {
+            Ok(Segment { id: row.get(0)?, start: row.get(1)?, end: row.get(2)?, kind: row.get(3)?, source: row.get(4)? })
+        })?;
+        rows.collect()
+    })
+}
+
+/// Swap all of one source's segments for a file, e.g. after a fresh SponsorBlock lookup or
+/// a detection run. Manual segments are never touched by other sources.
+pub fn replace_segments(app: &AppHandle, path: &str, source: &str, segments: &[(f64, f64, String)]) -> Result<(), String> {
+    let added = now_secs();
+    with_db(app, |conn| {
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM segments WHERE path = ?1 AND source = ?2", params![path, source])?;
+        {
+            let mut stmt = tx.prepare("INSERT INTO segments (path, start, end, kind, source, added) VALUES (?1, ?2, ?3, ?4, ?5, ?6)")?;
+            for (start, end, kind) in segments {
+                if end - start >= MIN_SEGMENT_SECS {
+                    stmt.execute(params![path, start, end, kind, source, added])?;
+                }
+            }
+        }
+        // Kept apart from the segments so a lookup that found nothing still counts
+        tx.execute(
+            "INSERT OR REPLACE INTO segment_lookups (path, source, checked) VALUES (?1, ?2, ?3)",
+            params![path, source, added],
+        )?;
+        tx.commit()
+    })?;
+    refresh(app, path);
+    Ok(())
+}
+
+/// Re-read a file's segments if it's the one playing, and tell the frontend.
+fn refresh(app: &AppHandle, path: &str) {
+    let state = app.state::<SegmentState>();
+    let mut current = state.current.lock().unwrap();
+    let Some(file) = current.as_mut().filter(|f| f.path == path) else {
+        return;
+    };
+    match stored(app, path) {
+        Ok(segments) => file.segments = segments,
+        Err(e) => tracing::error!("Failed to load segments: {}", e),
+    }
+    let _ = app.emit("segments", file.clone());
+}
+
+/// The video ID of a YouTube watch, shorts, embed or youtu.be URL.
+fn youtube_id(path: &str) -> Option<String> {
+    let url = tauri::Url::parse(path).ok()?;
+    let host = url.host_str()?.trim_start_matches("www.").trim_start_matches("m.").trim_start_matches("music.");
+    let id = match host {
+        "youtu.be" => url.path_segments()?.next()?.to_string(),
+        "youtube.com" | "youtube-nocookie.com" => {
+            let mut segments = url.path_segments()?;
+            match segments.next()? {
+                "watch" => url.query_pairs().find(|(k, _)| k == "v")?.1.into_owned(),
+                "shorts" | "embed" | "live" => segments.next()?.to_string(),
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    (id.len() == 11 && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')).then_some(id)
+}
+
+#[derive(Deserialize)]
+struct SponsorBlockVideo {
+    #[serde(rename = "videoID")]
+    video_id: String,
+    segments: Vec<SponsorBlockSegment>,
+}
+
+#[derive(Deserialize)]
+struct SponsorBlockSegment {
+    category: String,
+    segment: [f64; 2],
+    #[serde(rename = "actionType", default)]
+    action_type: String,
+}
+
+/// Look up community segments. Only the first characters of the hashed video ID are sent,
+/// so SponsorBlock can't tell which video is being watched.
+async fn fetch_sponsorblock(video_id: &str) -> Result<Vec<(f64, f64, String)>, String> {
+    let hash = hex::encode(Sha256::digest(video_id.as_bytes()));
+    let url = tauri::Url::parse_with_params(&format!("{}/{}", SPONSORBLOCK_API, &hash[..4]), &[("categories", SPONSORBLOCK_CATEGORIES)])
+        .map_err(|e| e.to_string())?;
+    let response = crate::metadata::http_client().get(url).send().await.map_err(|e| format!("SponsorBlock request failed: {}", e))?;
+    // 404 is the answer for "no segments"
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(Vec::new());
+    }
+    if !response.status().is_success() {
+        return Err(format!("SponsorBlock returned {}", response.status()));
+    }
+    let videos: Vec<SponsorBlockVideo> = response.json().await.map_err(|e| format!("Invalid SponsorBlock response: {}", e))?;
+    Ok(videos
+        .into_iter()
+        .filter(|v| v.video_id == video_id)
+        .flat_map(|v| v.segments)
+        .filter(|s| s.action_type.is_empty() || s.action_type == "skip")
+        .filter_map(|s| {
+            let kind = match s.category.as_str() {
+                "sponsor" | "selfpromo" | "interaction" => "sponsor",
+                "intro" => "intro",
+                "outro" => "credits",
+                "preview" => "preview",
+                _ => return None,
+            };
+            Some((s.segment[0], s.segment[1], kind.to_string()))
+        })
+        .collect())
+}
+
+/// Whether SponsorBlock should be asked (again) about this file.
+fn sponsorblock_stale(app: &AppHandle, path: &str) -> bool {
+    let checked: Option<u64> = with_db(app, |conn| {
+        conn.query_row("SELECT checked FROM segment_lookups WHERE path = ?1 AND source = 'sponsorblock'", params![path], |row| row.get(0))
+            .optional()
+    })
+    .unwrap_or(None);
+    checked.is_none_or(|checked| now_secs().saturating_sub(checked) > SPONSORBLOCK_TTL_SECS)
+}
+
+/// Load segments for a newly started file, looking up SponsorBlock in the background.
+fn load(app: &AppHandle, path: String) {
+    let state = app.state::<SegmentState>();
+    let segments = stored(app, &path).unwrap_or_else(|e| {
+        tracing::error!("Failed to load segments: {}", e);
+        Vec::new()
+    });
+    let file = FileSegments { path: path.clone(), segments };
+    let _ = app.emit("segments", file.clone());
+    *state.current.lock().unwrap() = Some(file);
+
+    let config = crate::config::load_config(app);
+    if !config.segments.sponsorblock || config.private_mode {
+        return;
+    }
+    let Some(video_id) = youtube_id(&path) else {
+        return;
+    };
+    if !sponsorblock_stale(app, &path) {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match fetch_sponsorblock(&video_id).await {
+            Ok(segments) => {
+                tracing::info!("⏭️ SponsorBlock: {} segment(s) for {}", segments.len(), video_id);
+                if let Err(e) = replace_segments(&app, &path, "sponsorblock", &segments) {
+                    tracing::error!("Failed to store SponsorBlock segments: {}", e);
+                }
+            }
+            Err(e) => tracing::warn!("⚠️ {}", e),
+        }
+    });
+}
+
+/// Lives in the mpv event loop: notices entering a segment and either skips it (returning
+/// where to seek) or emits `segment-active` so the frontend can offer a skip button.
+pub struct SegmentSkipper {
+    actions: BTreeMap<String, String>,
+    inside: Option<i64>,
+}
+
+impl SegmentSkipper {
+    pub fn new() -> Self {
+        Self { actions: BTreeMap::new(), inside: None }
+    }
+
+    pub fn start(&mut self, app: &AppHandle, path: Option<&str>) {
+        self.stop(app);
+        self.actions = crate::config::load_config(app).segments.actions;
+        if let Some(path) = path {
+            load(app, path.to_string());
+        }
+    }
+
+    pub fn progress(&mut self, app: &AppHandle, pos: f64) -> Option<f64> {
+        let state = app.state::<SegmentState>();
+        let hit = state
+            .current
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|f| f.segments.iter().find(|s| pos >= s.start && pos < s.end).cloned());
+        if hit.as_ref().map(|s| s.id) == self.inside {
+            return None;
+        }
+        self.inside = hit.as_ref().map(|s| s.id);
+        let Some(segment) = hit else {
+            let _ = app.emit("segment-active", None::<Segment>);
+            return None;
+        };
+        match self.actions.get(&segment.kind).map(String::as_str).unwrap_or("prompt") {
+            "skip" if pos - segment.start <= ENTRY_WINDOW_SECS => {
+                tracing::info!("⏭️ Skipping {} ({:.0}s - {:.0}s)", segment.kind, segment.start, segment.end);
+                let _ = app.emit("segment-skipped", &segment);
+                let _ = app.emit("segment-active", None::<Segment>);
+                Some(segment.end)
+            }
+            "ignore" => {
+                let _ = app.emit("segment-active", None::<Segment>);
+                None
+            }
+            _ => {
+                let _ = app.emit("segment-active", Some(&segment));
+                None
+            }
+        }
+    }
+
+    pub fn stop(&mut self, app: &AppHandle) {
+        self.inside = None;
+        *app.state::<SegmentState>().current.lock().unwrap() = None;
+        let _ = app.emit("segment-active", None::<Segment>);
+    }
+}
+
+#[command]
+pub fn get_segments(app: AppHandle, path: String) -> Result<Vec<Segment>, String> {
+    stored(&app, &path)
+}
+
+/// Segments of the file playing right now, for a frontend that missed the `segments` event.
+#[command]
+pub fn get_current_segments(state: State<'_, SegmentState>) -> Option<FileSegments> {
+    state.current.lock().unwrap().clone()
+}
+
+#[command]
+pub fn add_segment(app: AppHandle, path: String, start: f64, end: f64, kind: String) -> Result<Segment, String> {
+    if !KINDS.contains(&kind.as_str()) {
+        return Err(format!("Unknown segment kind '{}'", kind));
+    }
+    let (start, end) = if start <= end { (start.max(0.0), end) } else { (end.max(0.0), start) };
+    if end - start < MIN_SEGMENT_SECS {
+        return Err("A segment must be at least a second long".to_string());
+    }
+    let id = with_db(&app, |conn| {
+        conn.execute(
+            "INSERT INTO segments (path, start, end, kind, source, added) VALUES (?1, ?2, ?3, ?4, 'manual', ?5)",
+            params![path, start, end, kind, now_secs()],
+        )?;
+        Ok(conn.last_insert_rowid())
+    })?;
+    refresh(&app, &path);
+    Ok(Segment { id, start, end, kind, source: "manual".to_string() })
+}
+
+#[command]
+pub fn delete_segment(app: AppHandle, id: i64) -> Result<(), String> {
+    let path: Option<String> = with_db(&app, |conn| {
+        let path = conn.query_row("SELECT path FROM segments WHERE id = ?1", params![id], |row| row.get(0)).optional()?;
+        conn.execute("DELETE FROM segments WHERE id = ?1", params![id])?;
+        Ok(path)
+    })?;
+    if let Some(path) = path {
+        refresh(&app, &path);
+    }
+    Ok(())
+}
@@ -1,6 +1,66 @@
 use serde::{Deserialize, Serialize};
-use tauri::command;
+use tauri::{command, AppHandle, Emitter, Manager, State};
 use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::config::OpenSubtitlesSession;
+use crate::util::now_secs;
+
+// OpenSubtitles "moviehash" reads this much from each end of the file
+const HASH_CHUNK_SIZE: u64 = 64 * 1024;
+const OPENSUBTITLES_HOST: &str = "api.opensubtitles.com";
+
+// Remaining downloads and reset time reported by the latest OpenSubtitles download
+static LAST_QUOTA: Mutex<Option<(i64, Option<String>)>> = Mutex::new(None);
+
+/// Why a download failed, typed so the UI can explain quota limits instead of showing API text.
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SubtitleError {
+    // Daily download limit used up (OpenSubtitles answers 406)
+    QuotaExceeded { message: String, remaining: i64, reset_time: Option<String>, logged_in: bool },
+    // Missing or rejected API key, wrong password or an expired login
+    Unauthorized { message: String },
+    Other { message: String },
+}
+
+impl std::fmt::Display for SubtitleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SubtitleError::QuotaExceeded { message, reset_time: Some(reset), .. } => write!(f, "{} (resets in {})", message, reset),
+            SubtitleError::QuotaExceeded { message, .. } | SubtitleError::Unauthorized { message } | SubtitleError::Other { message } => {
+                write!(f, "{}", message)
+            }
+        }
+    }
+}
+
+impl From<String> for SubtitleError {
+    fn from(message: String) -> Self {
+        SubtitleError::Other { message }
+    }
+}
+
+impl From<&str> for SubtitleError {
+    fn from(message: &str) -> Self {
+        SubtitleError::Other { message: message.to_string() }
+    }
+}
+
+/// OpenSubtitles downloads left today. Only logged-in accounts can be queried; otherwise
+/// the numbers are the ones reported by the last download, if any.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct SubtitleQuota {
+    pub logged_in: bool,
+    pub username: Option<String>,
+    pub level: Option<String>,
+    pub vip: bool,
+    pub allowed_downloads: Option<i64>,
+    pub remaining_downloads: Option<i64>,
+    pub reset_time: Option<String>, // e.g. "23 hours and 5 minutes"
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct SubtitleResult {
@@ -11,6 +71,93 @@ pub struct SubtitleResult {
     pub hearing_impaired: bool,
     pub file_id: i64,
     pub release: String,
+    // Found through the file hash, so it's synced to this exact release
+    #[serde(default)]
+    pub hash_match: bool,
+    #[serde(default = "default_provider")]
+    pub provider: String, // SubtitleProvider::id
+    // Direct file link, for providers that don't go through a file id
+    #[serde(default)]
+    pub download_url: Option<String>,
+    // 0-1: how likely it is to be in sync with the local file (see `match_confidence`)
+    #[serde(default)]
+    pub match_confidence: f64,
+}
+
+fn default_provider() -> String {
+    SubtitleProvider::OpenSubtitles.id().to_string()
+}
+
+/// Where subtitles come from. Results from all of them are merged, so one provider's
+/// quota running out doesn't stop a search.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubtitleProvider {
+    OpenSubtitles,
+    Jimaku, // anime, Japanese subtitles only
+}
+
+/// API key per provider; providers without one are skipped.
+#[derive(Clone, Debug, Default)]
+pub struct ProviderKeys {
+    pub opensubtitles: String,
+    pub jimaku: String,
+    pub opensubtitles_session: Option<OpenSubtitlesSession>,
+}
+
+/// One subtitle search, shared by every provider.
+pub struct SubtitleSearch<'a> {
+    pub query: &'a str,
+    pub language: &'a str,
+    pub moviehash: Option<&'a str>,
+    pub file_name: Option<&'a str>, // the video's own name, for providers that parse it themselves
+    pub series: Option<&'a SeriesEpisode>,
+}
+
+/// Which episode of which show a file is; providers search by these instead of the file name.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SeriesEpisode {
+    pub show: String,
+    pub season: u32,
+    pub episode: u32,
+}
+
+/// The subtitle `auto_fetch_subtitle` picked, downloaded and loaded.
+#[derive(Serialize, Debug)]
+pub struct AutoFetchedSubtitle {
+    pub path: String,
+    pub language: String,
+    pub release: String,
+    pub hash_match: bool,
+    pub score: f64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BatchSubtitleProgress {
+    pub video_path: String,
+    pub index: usize, // 1-based
+    pub total: usize,
+    pub status: String, // "searching" | "downloaded" | "not_found" | "failed"
+}
+
+#[derive(Serialize, Debug)]
+pub struct BatchSubtitleFailure {
+    pub video_path: String,
+    pub error: String,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct BatchSubtitleReport {
+    pub downloaded: Vec<AutoFetchedSubtitle>,
+    pub not_found: Vec<String>,
+    pub failed: Vec<BatchSubtitleFailure>,
+    pub already_had: usize, // videos skipped because they have subtitles in the language
+}
+
+/// What `search_subtitles` needs to look a file up by hash.
+#[derive(Serialize, Debug)]
+pub struct VideoHash {
+    pub moviehash: String,
+    pub file_size: u64,
 }
 
 #[derive(Deserialize)]
@@ -31,6 +178,22 @@ struct OsAttributes {
     hearing_impaired: Option<bool>,
     release: Option<String>,
     files: Vec<OsFile>,
+    moviehash_match: Option<bool>,
+    feature_details: Option<HashFeature>,
+}
+
+/// The movie or episode OpenSubtitles has linked to a release, as found through its file hash.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HashFeature {
+    #[serde(default)]
+    pub feature_type: String, // "Movie" | "Episode"
+    pub title: Option<String>,
+    pub year: Option<u32>,
+    pub imdb_id: Option<u64>,
+    pub tmdb_id: Option<u64>,
+    pub parent_tmdb_id: Option<u64>, // the show, for episodes
+    pub season_number: Option<u32>,
+    pub episode_number: Option<u32>,
 }
 
 #[derive(Deserialize)]
@@ -42,29 +205,620 @@ struct OsFile {
 #[derive(Deserialize)]
 struct OsDownloadResponse {
     link: String,
+    remaining: Option<i64>,
+    reset_time: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct OsErrorBody {
+    message: Option<String>,
+    remaining: Option<i64>,
+    reset_time: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OsLoginResponse {
+    token: String,
+    base_url: Option<String>,
+    user: OsUser,
+}
+
+#[derive(Deserialize)]
+struct OsUserInfo {
+    data: OsUser,
+}
+
+#[derive(Deserialize)]
+struct OsUser {
+    allowed_downloads: Option<i64>,
+    remaining_downloads: Option<i64>,
+    level: Option<String>,
+    vip: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct JimakuEntry {
+    id: i64,
+}
+
+#[derive(Deserialize)]
+struct JimakuFile {
+    url: String,
+    name: String,
+}
+
+const JIMAKU_URL: &str = "https://jimaku.cc/api";
+// RAR and 7z archives can't be unpacked here, so they aren't offered
+const JIMAKU_EXTENSIONS: &[&str] = &["srt", "ass", "ssa", "vtt", "zip", "gz"];
+// What can be taken out of a downloaded archive
+const ARCHIVE_SUBTITLE_EXTENSIONS: &[&str] = &["srt", "ass", "ssa", "vtt"];
+// Far past any real subtitle; stops a small archive from unpacking into gigabytes
+const MAX_UNPACKED_BYTES: u64 = 32 * 1024 * 1024;
+
+impl SubtitleProvider {
+    pub const ALL: [SubtitleProvider; 2] = [SubtitleProvider::OpenSubtitles, SubtitleProvider::Jimaku];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            SubtitleProvider::OpenSubtitles => "opensubtitles",
+            SubtitleProvider::Jimaku => "jimaku",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|p| p.id() == id.trim().to_lowercase())
+            .ok_or_else(|| format!("Unknown subtitle provider '{}'", id))
+    }
+
+    fn supports(self, language: &str) -> bool {
+        match self {
+            SubtitleProvider::OpenSubtitles => true,
+            SubtitleProvider::Jimaku => crate::subtitle_pipeline::language_matches(language, "ja"),
+        }
+    }
+
+    async fn search(self, search: &SubtitleSearch<'_>, key: &str) -> Result<Vec<SubtitleResult>, String> {
+        match self {
+            SubtitleProvider::OpenSubtitles => search_opensubtitles(search, key).await,
+            SubtitleProvider::Jimaku => search_jimaku(search, key).await,
+        }
+    }
+
+    /// Returns where the file ended up: the extension follows the subtitle found in an archive.
+    async fn download(self, file_id: i64, download_url: Option<&str>, save_path: &Path, keys: &ProviderKeys) -> Result<PathBuf, SubtitleError> {
+        match self {
+            SubtitleProvider::OpenSubtitles => {
+                download_subtitle_to(file_id, save_path, &keys.opensubtitles, keys.opensubtitles_session.as_ref()).await
+            }
+            SubtitleProvider::Jimaku => {
+                let url = download_url.ok_or("Jimaku results need a download URL")?;
+                Ok(download_jimaku(url, save_path, &keys.jimaku).await?)
+            }
+        }
+    }
+}
+
+impl ProviderKeys {
+    /// Keys saved in Settings → Integrations, with an explicit OpenSubtitles key taking precedence.
+    pub fn load(app: &AppHandle, opensubtitles: Option<String>) -> Self {
+        let config = crate::config::load_config(app);
+        let credentials = config.credentials;
+        ProviderKeys {
+            opensubtitles_session: config.opensubtitles,
+            opensubtitles: opensubtitles
+                .filter(|k| !k.is_empty())
+                .or(credentials.opensubtitles_api_key)
+                .unwrap_or_default(),
+            jimaku: credentials.jimaku_api_key.unwrap_or_default(),
+        }
+    }
+
+    fn get(&self, provider: SubtitleProvider) -> &str {
+        match provider {
+            SubtitleProvider::OpenSubtitles => &self.opensubtitles,
+            SubtitleProvider::Jimaku => &self.jimaku,
+        }
+    }
+
+    fn any(&self) -> bool {
+        SubtitleProvider::ALL.into_iter().any(|p| !self.get(p).is_empty())
+    }
 }
 
+/// OpenSubtitles moviehash: file size plus the wrapping sum of the first and last 64 KiB as u64 words.
+pub fn compute_moviehash(path: &Path) -> Result<String, String> {
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let size = file.metadata().map_err(|e| e.to_string())?.len();
+    if size < HASH_CHUNK_SIZE {
+        return Err("File too small to hash".to_string());
+    }
+
+    let mut hash = size;
+    let mut buf = vec![0u8; HASH_CHUNK_SIZE as usize];
+    for offset in [0, size - HASH_CHUNK_SIZE] {
+        file.seek(SeekFrom::Start(offset)).map_err(|e| e.to_string())?;
+        file.read_exact(&mut buf).map_err(|e| e.to_string())?;
+        for word in buf.chunks_exact(8) {
+            hash = hash.wrapping_add(u64::from_le_bytes(word.try_into().unwrap()));
+        }
+    }
+    Ok(format!("{:016x}", hash))
+}
+
+/// OpenSubtitles hash and size of a local video, for `search_subtitles`.
 #[command]
+pub fn compute_video_hash(video_path: String) -> Result<VideoHash, String> {
+    let path = Path::new(&video_path);
+    let file_size = fs::metadata(path).map_err(|e| e.to_string())?.len();
+    Ok(VideoHash { moviehash: compute_moviehash(path)?, file_size })
+}
+
+/// Search every provider with a key (or just `providers`), merging and de-duplicating the results.
+/// OpenSubtitles is searched by file hash first (from `compute_video_hash`), falling back to
+/// the text query when there's no hash or nothing is synced to this exact file.
+#[command]
+#[allow(clippy::too_many_arguments)]
 pub async fn search_subtitles(
+    app: AppHandle,
     query: String,
     language: Option<String>,
     api_key: String,
+    moviehash: Option<String>,
+    file_size: Option<u64>,
+    file_name: Option<String>,
+    providers: Option<Vec<String>>,
+    video_path: Option<String>,
+    series: Option<SeriesEpisode>,
+) -> Result<Vec<SubtitleResult>, String> {
+    let lang = language.unwrap_or_else(|| "en".to_string());
+    let keys = ProviderKeys::load(&app, Some(api_key));
+    let providers = match providers {
+        Some(ids) => ids.iter().map(|id| SubtitleProvider::from_id(id)).collect::<Result<Vec<_>, _>>()?,
+        None => SubtitleProvider::ALL.to_vec(),
+    };
+    // Files under one hash chunk have no valid hash
+    let hash = moviehash.filter(|h| !h.is_empty() && file_size.is_none_or(|s| s >= HASH_CHUNK_SIZE));
+    // Episode files are looked up by show, season and episode, parsed from the path unless given
+    let series = series.or_else(|| video_path.as_deref().and_then(|p| parse_series_episode(Path::new(p))));
+    let search = SubtitleSearch {
+        query: &query,
+        language: &lang,
+        moviehash: hash.as_deref(),
+        file_name: file_name.as_deref(),
+        series: series.as_ref(),
+    };
+    let mut results = search_providers(&app, &search, &keys, &providers).await?;
+    rank_results(&mut results, file_name.as_deref().unwrap_or(&query));
+    Ok(results)
+}
+
+/// Cache key for one provider's answer to a search: everything that changes what it returns.
+fn search_cache_key(provider: SubtitleProvider, search: &SubtitleSearch<'_>) -> String {
+    let series = search.series.map(|s| format!("{}:{}:{}", s.show.to_lowercase(), s.season, s.episode)).unwrap_or_default();
+    format!(
+        "subtitles:{}:{}:{}:{}:{}:{}",
+        provider.id(),
+        search.language.to_lowercase(),
+        search.moviehash.unwrap_or_default(),
+        series,
+        search.query.trim().to_lowercase(),
+        search.file_name.unwrap_or_default().to_lowercase(),
+    )
+}
+
+/// A provider's results, reused from the metadata cache when the same search ran within
+/// `search_cache_hours`, so reopening the dialog or retrying doesn't spend API quota.
+async fn cached_search(
+    app: &AppHandle,
+    provider: SubtitleProvider,
+    search: &SubtitleSearch<'_>,
+    key: &str,
+) -> Result<Vec<SubtitleResult>, String> {
+    let ttl = crate::config::load_config(app).subtitles.search_cache_hours * 60 * 60;
+    let cache = app.state::<crate::metadata_cache::MetadataCache>();
+    let cache_key = search_cache_key(provider, search);
+    if ttl > 0 {
+        if let Some(results) = cache.get(app, &cache_key, ttl).and_then(|body| serde_json::from_value(body).ok()) {
+            return Ok(results);
+        }
+    }
+    let results = provider.search(search, key).await?;
+    // Nothing found isn't cached: subtitles for new releases turn up within hours
+    if ttl > 0 && !results.is_empty() {
+        if let Ok(body) = serde_json::to_value(&results) {
+            cache.insert(app, &cache_key, body);
+        }
+    }
+    Ok(results)
+}
+
+/// Forget cached subtitle searches, e.g. after new subtitles were uploaded.
+#[command]
+pub fn clear_subtitle_cache(app: AppHandle) -> usize {
+    app.state::<crate::metadata_cache::MetadataCache>().remove_where(&app, |k| k.starts_with("subtitles:"))
+}
+
+/// Results from each keyed provider that covers the language. A provider failing (quota,
+/// outage) only drops its own results; the search fails when every provider did.
+pub async fn search_providers(
+    app: &AppHandle,
+    search: &SubtitleSearch<'_>,
+    keys: &ProviderKeys,
+    providers: &[SubtitleProvider],
 ) -> Result<Vec<SubtitleResult>, String> {
+    if !keys.any() {
+        return Err("A subtitle provider API key is required. Set it in Settings → Integrations.".to_string());
+    }
+
+    let mut results = Vec::new();
+    let mut errors = Vec::new();
+    let mut searched = 0;
+    for provider in providers.iter().copied() {
+        let key = keys.get(provider);
+        if key.is_empty() || !provider.supports(search.language) {
+            continue;
+        }
+        searched += 1;
+        match cached_search(app, provider, search, key).await {
+            Ok(found) => results.extend(found),
+            Err(e) => {
+                tracing::error!("⚠️ {} subtitle search failed: {}", provider.id(), e);
+                errors.push(format!("{}: {}", provider.id(), e));
+            }
+        }
+    }
+    if searched > 0 && errors.len() == searched {
+        return Err(errors.join("; "));
+    }
+    Ok(merge_results(results))
+}
+
+fn dedup_key(result: &SubtitleResult) -> String {
+    let name = if result.file_name.is_empty() { &result.release } else { &result.file_name };
+    let tokens = release_tokens(name);
+    if tokens.is_empty() {
+        return format!("{}:{}", result.provider, result.id);
+    }
+    format!("{}|{}", tokens.join(" "), result.language.to_lowercase())
+}
+
+/// The same file is often mirrored across providers; keep one copy per name and language,
+/// preferring a hash match, then the most downloaded. Hash matches are listed first.
+fn merge_results(results: Vec<SubtitleResult>) -> Vec<SubtitleResult> {
+    let mut merged: Vec<(String, SubtitleResult)> = Vec::new();
+    for result in results {
+        let key = dedup_key(&result);
+        match merged.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, existing)) => {
+                if (result.hash_match, result.download_count) > (existing.hash_match, existing.download_count) {
+                    *existing = result;
+                }
+            }
+            None => merged.push((key, result)),
+        }
+    }
+    let mut merged: Vec<SubtitleResult> = merged.into_iter().map(|(_, r)| r).collect();
+    merged.sort_by_key(|r| !r.hash_match);
+    merged
+}
+
+async fn search_opensubtitles(search: &SubtitleSearch<'_>, api_key: &str) -> Result<Vec<SubtitleResult>, String> {
+    if let Some(hash) = search.moviehash {
+        let mut results = query_subtitles(&format!("moviehash={}", urlencoding::encode(hash)), search.language, api_key).await?;
+        results.retain(|r| r.hash_match);
+        if !results.is_empty() {
+            return Ok(results);
+        }
+    }
+    if let Some(series) = search.series {
+        let filter = format!(
+            "query={}&season_number={}&episode_number={}&type=episode",
+            urlencoding::encode(&series.show),
+            series.season,
+            series.episode
+        );
+        return query_subtitles(&filter, search.language, api_key).await;
+    }
+    if search.query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    query_subtitles(&format!("query={}", urlencoding::encode(search.query)), search.language, api_key).await
+}
+
+/// Show, season and episode for an episode file. `Show.S01E07.mkv` carries all three;
+/// terse names like `E07.mkv` or `07 - Title.mkv` take the season and show from the
+/// `Show/Season 1/` folders around them.
+pub fn parse_series_episode(video_path: &Path) -> Option<SeriesEpisode> {
+    let name = video_path.file_name()?.to_str()?;
+    let show_from_folder = || {
+        let dir = crate::metadata::show_dir(video_path)?;
+        let folder = dir.file_name()?.to_str()?;
+        Some(crate::metadata::clean_video_name(folder)).filter(|s| !s.is_empty())
+    };
+
+    if let Some((season, episode)) = crate::metadata::parse_episode(name) {
+        let title = crate::metadata::clean_video_name(name);
+        let show = if title.trim().is_empty() || crate::metadata::parse_episode(&title).is_some() {
+            show_from_folder()?
+        } else {
+            title
+        };
+        return Some(SeriesEpisode { show, season, episode });
+    }
+
+    let season = crate::metadata::season_folder_number(video_path.parent()?)?;
+    // "720p" or "1080i" would otherwise read as episode 720
+    let resolution = regex::Regex::new(r"(?i)\b\d{3,4}[pi]\b|\b[48]k\b").unwrap();
+    let stem = resolution.replace_all(name_stem(name), " ").trim().to_lowercase();
+    let digits = stem
+        .strip_prefix("episode")
+        .or_else(|| stem.strip_prefix("ep"))
+        .or_else(|| stem.strip_prefix('e'))
+        .unwrap_or(&stem)
+        .trim_start_matches([' ', '.', '_', '-']);
+    let number: String = digits.chars().take_while(|c| c.is_ascii_digit()).collect();
+    let episode = number.parse().ok().filter(|_| number.len() <= 3)?;
+    Some(SeriesEpisode { show: show_from_folder()?, season, episode })
+}
+
+async fn jimaku_get<T: serde::de::DeserializeOwned>(url: &str, api_key: &str) -> Result<T, String> {
+    let response = crate::metadata::http_client()
+        .get(url)
+        .header("Authorization", api_key)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("API error {}: {}", status, body));
+    }
+    response.json().await.map_err(|e| format!("Parse error: {}", e))
+}
+
+/// Jimaku indexes subtitles by anime entry, then episode. The title and episode are read from
+/// the video's file name when there is one (fansub names like `[Group] Title - 05`), else the query.
+async fn search_jimaku(search: &SubtitleSearch<'_>, api_key: &str) -> Result<Vec<SubtitleResult>, String> {
+    let (title, episode) = match search.series {
+        Some(series) => (series.show.clone(), Some(series.episode)),
+        None => crate::anilist::parse_anime_name(search.file_name.unwrap_or(search.query)),
+    };
+    let title = if title.is_empty() { search.query.trim().to_string() } else { title };
+    if title.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let entries: Vec<JimakuEntry> = jimaku_get(
+        &format!("{}/entries/search?anime=true&query={}", JIMAKU_URL, urlencoding::encode(&title)),
+        api_key,
+    ).await?;
+    let Some(entry) = entries.first() else {
+        return Ok(Vec::new());
+    };
+
+    let mut url = format!("{}/entries/{}/files", JIMAKU_URL, entry.id);
+    if let Some(episode) = episode {
+        url.push_str(&format!("?episode={}", episode));
+    }
+    let files: Vec<JimakuFile> = jimaku_get(&url, api_key).await?;
+
+    Ok(files
+        .into_iter()
+        .filter(|f| {
+            Path::new(&f.name)
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| JIMAKU_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+        })
+        .map(|f| SubtitleResult {
+            id: format!("jimaku:{}", f.url),
+            release: f.name.clone(),
+            file_name: f.name,
+            language: "ja".to_string(),
+            download_count: 0,
+            hearing_impaired: false,
+            file_id: 0,
+            hash_match: false,
+            provider: SubtitleProvider::Jimaku.id().to_string(),
+            download_url: Some(f.url),
+            match_confidence: 0.0,
+        })
+        .collect())
+}
+
+async fn download_jimaku(url: &str, save_path: &Path, api_key: &str) -> Result<PathBuf, String> {
+    let response = crate::metadata::http_client()
+        .get(url)
+        .header("Authorization", api_key)
+        .send()
+        .await
+        .map_err(|e| format!("File download failed: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("File download failed: {}", e))?;
+    let content_type = content_type(&response);
+    let file_bytes = response.bytes().await.map_err(|e| format!("Read bytes: {}", e))?;
+    save_subtitle_bytes(&file_bytes, content_type.as_deref(), save_path)
+}
+
+fn content_type(response: &reqwest::Response) -> Option<String> {
+    response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_lowercase())
+}
+
+/// Some links serve a gzip or zip archive instead of the subtitle itself. Unpack it in memory;
+/// from a zip holding several subtitles, take the one whose name best matches `hint`.
+/// Returns the subtitle's bytes and, when it came out of a zip, its entry name.
+fn unpack_subtitle(bytes: &[u8], content_type: Option<&str>, hint: &str) -> Result<(Vec<u8>, Option<String>), String> {
+    let content_type = content_type.unwrap_or_default();
+    if bytes.starts_with(&[0x1f, 0x8b]) || content_type.contains("gzip") {
+        let out = read_capped(flate2::read::GzDecoder::new(bytes)).map_err(|e| format!("Failed to decompress subtitles: {}", e))?;
+        return Ok((out, None));
+    }
+    if bytes.starts_with(b"PK\x03\x04") || content_type.contains("zip") {
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes))
+            .map_err(|e| format!("Failed to open subtitle archive: {}", e))?;
+        let names: Vec<String> = archive
+            .file_names()
+            .filter(|name| {
+                Path::new(name)
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| ARCHIVE_SUBTITLE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+            })
+            .map(|name| name.to_string())
+            .collect();
+        // Reversed so the first entry wins ties
+        let best = names
+            .iter()
+            .rev()
+            .max_by(|a, b| release_similarity(hint, a).total_cmp(&release_similarity(hint, b)))
+            .ok_or("The downloaded archive has no subtitle files")?;
+        let entry = archive.by_name(best).map_err(|e| e.to_string())?;
+        let out = read_capped(entry).map_err(|e| format!("Failed to extract {}: {}", best, e))?;
+        return Ok((out, Some(best.clone())));
+    }
+    Ok((bytes.to_vec(), None))
+}
+
+/// Decompressed bytes, refusing more than MAX_UNPACKED_BYTES.
+fn read_capped(reader: impl Read) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    reader.take(MAX_UNPACKED_BYTES + 1).read_to_end(&mut out).map_err(|e| e.to_string())?;
+    if out.len() as u64 > MAX_UNPACKED_BYTES {
+        return Err("it unpacks to more than a subtitle could be".to_string());
+    }
+    Ok(out)
+}
+
+/// Unpack (if needed) and save as UTF-8. A subtitle taken from an archive keeps its own
+/// extension, so an `.ass` inside a zip isn't saved as `.srt`.
+fn save_subtitle_bytes(bytes: &[u8], content_type: Option<&str>, save_path: &Path) -> Result<PathBuf, String> {
+    let hint = save_path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    let (data, entry) = unpack_subtitle(bytes, content_type, hint)?;
+    let path = match entry {
+        Some(entry) => {
+            let ext = subtitle_extension(&entry);
+            if save_path.extension().and_then(|e| e.to_str()) == Some(ext.as_str()) {
+                save_path.to_path_buf()
+            } else if save_path.exists() {
+                // Replacing: the same file under its real extension may be replaced too
+                save_path.with_extension(ext)
+            } else {
+                // save_path was picked as free; keep the renamed one from landing on another file
+                free_alternative(&save_path.with_extension(ext))
+            }
+        }
+        None => save_path.to_path_buf(),
+    };
+    crate::subtitle_format::save_as_utf8(&data, &path, None)?;
+    Ok(path)
+}
+
+fn subtitle_extension(file_name: &str) -> String {
+    Path::new(file_name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .filter(|e| crate::subtitle_format::SubtitleFormat::parse(e).is_ok())
+        .unwrap_or_else(|| "srt".to_string())
+}
+
+/// `path`, or the first free `<base>.2.<ext>`, `.3`, ... numbered the way
+/// `subtitle_save_path` numbers alternatives.
+fn free_alternative(path: &Path) -> PathBuf {
+    if !path.exists() {
+        return path.to_path_buf();
+    }
+    let (Some(dir), Some(stem), Some(ext)) = (path.parent(), path.file_stem().and_then(|s| s.to_str()), path.extension().and_then(|e| e.to_str())) else {
+        return path.to_path_buf();
+    };
+    // Already numbered ("Movie.en.2"): count on from the base
+    let base = match stem.rsplit_once('.') {
+        Some((base, n)) if n.parse::<u32>().is_ok() => base,
+        _ => stem,
+    };
+    (2..).map(|n| dir.join(format!("{}.{}.{}", base, n, ext))).find(|p| !p.exists()).unwrap()
+}
+
+/// A language tag that is safe in a file name: letters, digits, `_` and `-` only.
+pub fn is_valid_language_tag(language: &str) -> bool {
+    !language.is_empty() && language.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// `<video stem>.<lang>.<ext>` next to the video, which mpv and most players load on their own.
+/// An existing file is replaced, unless `keep_alternatives` is set: then the new one is
+/// numbered `<stem>.<lang>.2.<ext>`, `.3`, ... so both stay available.
+pub fn subtitle_save_path(video_path: &Path, language: &str, ext: &str, keep_alternatives: bool) -> Result<PathBuf, String> {
+    let language = language.trim().to_lowercase();
+    if !is_valid_language_tag(&language) {
+        return Err(format!("Invalid subtitle language '{}'", language));
+    }
+    let (Some(dir), Some(stem)) = (video_path.parent(), video_path.file_stem()) else {
+        return Err("Invalid video path".to_string());
+    };
+    let base = format!("{}.{}", stem.to_string_lossy(), language);
+    let path = dir.join(format!("{}.{}", base, ext));
+    if !keep_alternatives || !path.exists() {
+        return Ok(path);
+    }
+    Ok((2..)
+        .map(|n| dir.join(format!("{}.{}.{}", base, n, ext)))
+        .find(|p| !p.exists())
+        .unwrap())
+}
+
+/// Download a search result through the provider it came from.
+pub async fn download_result(result: &SubtitleResult, save_path: &Path, keys: &ProviderKeys) -> Result<PathBuf, SubtitleError> {
+    let provider = SubtitleProvider::from_id(&result.provider)?;
+    provider.download(result.file_id, result.download_url.as_deref(), save_path, keys).await
+}
+
+/// Search by file hash, which only returns subtitles synced to this exact release.
+pub async fn search_subtitles_by_hash(video_path: &Path, lang: &str, api_key: &str) -> Result<Vec<SubtitleResult>, String> {
+    let hash = compute_moviehash(video_path)?;
+    query_subtitles(&format!("moviehash={}", hash), lang, api_key).await
+}
+
+async fn query_subtitles(filter: &str, lang: &str, api_key: &str) -> Result<Vec<SubtitleResult>, String> {
+    let data = fetch_subtitle_data(&format!("{}&languages={}", filter, urlencoding::encode(lang)), api_key).await?;
+
+    let results: Vec<SubtitleResult> = data.into_iter().map(|d| {
+        let file = d.attributes.files.first();
+        SubtitleResult {
+            id: d.id,
+            file_name: file.and_then(|f| f.file_name.clone()).unwrap_or_default(),
+            language: d.attributes.language.unwrap_or_else(|| lang.to_string()),
+            download_count: d.attributes.download_count.unwrap_or(0),
+            hearing_impaired: d.attributes.hearing_impaired.unwrap_or(false),
+            file_id: file.map(|f| f.file_id).unwrap_or(0),
+            release: d.attributes.release.unwrap_or_default(),
+            hash_match: d.attributes.moviehash_match == Some(true),
+            provider: default_provider(),
+            download_url: None,
+            match_confidence: 0.0,
+        }
+    }).collect();
+
+    Ok(results)
+}
+
+async fn fetch_subtitle_data(filter: &str, api_key: &str) -> Result<Vec<OsSubtitleData>, String> {
     if api_key.is_empty() {
         return Err("OpenSubtitles API key is required. Set it in Settings → Integrations.".to_string());
     }
 
-    let lang = language.unwrap_or_else(|| "en".to_string());
-    let url = format!(
-        "https://api.opensubtitles.com/api/v1/subtitles?query={}&languages={}",
-        urlencoding::encode(&query),
-        urlencoding::encode(&lang)
-    );
+    let url = format!("https://api.opensubtitles.com/api/v1/subtitles?{}", filter);
 
     let client = reqwest::Client::new();
     let response = client
         .get(&url)
-        .header("Api-Key", &api_key)
+        .header("Api-Key", api_key)
         .header("Content-Type", "application/json")
         .header("User-Agent", "FrameX v0.1.0")
         .send()
@@ -81,70 +835,609 @@ pub async fn search_subtitles(
         .json()
         .await
         .map_err(|e| format!("Parse error: {}", e))?;
+    Ok(data.data)
+}
 
-    let results: Vec<SubtitleResult> = data.data.into_iter().map(|d| {
-        let file = d.attributes.files.first();
-        SubtitleResult {
-            id: d.id,
-            file_name: file.and_then(|f| f.file_name.clone()).unwrap_or_default(),
-            language: d.attributes.language.unwrap_or_else(|| lang.clone()),
-            download_count: d.attributes.download_count.unwrap_or(0),
-            hearing_impaired: d.attributes.hearing_impaired.unwrap_or(false),
-            file_id: file.map(|f| f.file_id).unwrap_or(0),
-            release: d.attributes.release.unwrap_or_default(),
+/// Movie/episode a moviehash belongs to, taken from the subtitles uploaded for that exact file.
+/// Only entries OpenSubtitles flags as true hash matches count; the most common feature wins.
+pub async fn identify_by_hash(hash: &str, api_key: &str) -> Result<Option<HashFeature>, String> {
+    let data = fetch_subtitle_data(&format!("moviehash={}", hash), api_key).await?;
+
+    let mut votes: Vec<(HashFeature, usize)> = Vec::new();
+    for feature in data.into_iter()
+        .filter(|d| d.attributes.moviehash_match == Some(true))
+        .filter_map(|d| d.attributes.feature_details)
+        .filter(|f| f.tmdb_id.is_some() || f.imdb_id.is_some())
+    {
+        match votes.iter_mut().find(|(f, _)| f.tmdb_id == feature.tmdb_id && f.imdb_id == feature.imdb_id) {
+            Some((_, count)) => *count += 1,
+            None => votes.push((feature, 1)),
         }
-    }).collect();
+    }
+    Ok(votes.into_iter().max_by_key(|(_, count)| *count).map(|(f, _)| f))
+}
+
+/// The name without a video or subtitle extension. Release names have no extension, and
+/// `file_stem` would cut them at the last dot (`...BluRay.x264-GRP` -> `...BluRay`).
+fn name_stem(name: &str) -> &str {
+    match name.rsplit_once('.') {
+        Some((stem, ext))
+            if crate::file_scanner::is_video(Path::new(name))
+                || ARCHIVE_SUBTITLE_EXTENSIONS.contains(&ext.to_lowercase().as_str()) =>
+        {
+            stem
+        }
+        _ => name,
+    }
+}
+
+fn release_tokens(name: &str) -> Vec<String> {
+    name_stem(name).to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_string())
+        .collect()
+}
+
+/// Share of the video's name tokens (title, year, group, source, resolution) found in the release name.
+fn release_similarity(video_name: &str, release: &str) -> f64 {
+    let video = release_tokens(video_name);
+    if video.is_empty() {
+        return 0.0;
+    }
+    let release = release_tokens(release);
+    video.iter().filter(|t| release.contains(t)).count() as f64 / video.len() as f64
+}
+
+/// The parts of a release name that decide whether subtitles line up with it.
+#[derive(Default, Debug, PartialEq)]
+struct ReleaseInfo {
+    resolution: Option<&'static str>,
+    source: Option<&'static str>,
+    group: Option<String>,
+}
+
+fn release_info(name: &str) -> ReleaseInfo {
+    let tokens = release_tokens(name);
+    let has = |wanted: &[&str]| tokens.iter().any(|t| wanted.contains(&t.as_str()));
+    let resolution = ["2160p", "1080p", "720p", "576p", "480p"]
+        .into_iter()
+        .find(|r| has(&[r]))
+        .or_else(|| has(&["4k", "uhd"]).then_some("2160p"));
+    // Sources that share a cut and frame rate count as one
+    let source = if has(&["bluray", "bdrip", "brrip", "bdremux", "remux", "bd"]) {
+        Some("bluray")
+    } else if has(&["web", "webdl", "webrip"]) {
+        Some("web")
+    } else if has(&["hdtv", "pdtv", "tvrip"]) {
+        Some("hdtv")
+    } else if has(&["dvdrip", "dvd", "dvdscr"]) {
+        Some("dvd")
+    } else {
+        None
+    };
+
+    // Scene style `...x264-GROUP`, or fansub style `[Group] Title - 01`
+    let stem = name_stem(name);
+    let group = stem
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once(']'))
+        .map(|(group, _)| group.to_string())
+        .or_else(|| {
+            let (_, tail) = stem.rsplit_once('-')?;
+            let group = tail.split(['.', ' ', '[']).next()?;
+            let known = ["dl", "rip", "web"].contains(&group.to_lowercase().as_str());
+            (group.len() >= 2 && !known && group.chars().all(|c| c.is_alphanumeric())).then(|| group.to_string())
+        })
+        .map(|g| g.trim().to_lowercase())
+        .filter(|g| !g.is_empty());
+
+    ReleaseInfo { resolution, source, group }
+}
+
+/// 0-1 likelihood that subtitles for `release` are in sync with the local `video_name`.
+/// The release group weighs most (same encode), then source (same cut), resolution and the
+/// overall name overlap; attributes the local name doesn't carry are left out of the total.
+pub fn match_confidence(video_name: &str, release: &str) -> f64 {
+    let video = release_info(video_name);
+    let sub = release_info(release);
+    let mut score = 0.25 * release_similarity(video_name, release);
+    let mut weight = 0.25;
+    if let Some(group) = &video.group {
+        weight += 0.35;
+        if sub.group.as_ref() == Some(group) {
+            score += 0.35;
+        }
+    }
+    if let Some(source) = video.source {
+        weight += 0.25;
+        if sub.source == Some(source) {
+            score += 0.25;
+        }
+    }
+    if let Some(resolution) = video.resolution {
+        weight += 0.15;
+        if sub.resolution == Some(resolution) {
+            score += 0.15;
+        }
+    }
+    score / weight
+}
+
+fn result_release(result: &SubtitleResult) -> &str {
+    if result.release.is_empty() { &result.file_name } else { &result.release }
+}
+
+/// Fill in `match_confidence` against the local file and sort by it, best first.
+/// Hash matches are synced to the exact file, so they're certain.
+pub fn rank_results(results: &mut [SubtitleResult], video_name: &str) {
+    for result in results.iter_mut() {
+        result.match_confidence = if result.hash_match { 1.0 } else { match_confidence(video_name, result_release(result)) };
+    }
+    results.sort_by(|a, b| b.match_confidence.total_cmp(&a.match_confidence).then(b.download_count.cmp(&a.download_count)));
+}
+
+/// Higher is better: a hash match beats any name match, then release confidence,
+/// then popularity (log-scaled so a few thousand downloads can't outweigh the right release).
+pub fn score_result(result: &SubtitleResult, video_name: &str) -> f64 {
+    let hash = if result.hash_match { 100.0 } else { 0.0 };
+    hash + match_confidence(video_name, result_release(result)) * 10.0 + (result.download_count.max(0) as f64 + 1.0).log10()
+}
 
+/// Results from every provider for the file: OpenSubtitles by hash, falling back to the show,
+/// season and episode for episodes or a text search on the cleaned-up name; the others by name.
+async fn find_candidates(app: &AppHandle, video_path: &Path, language: &str, keys: &ProviderKeys) -> Result<Vec<SubtitleResult>, String> {
+    let name = video_path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    let hash = compute_moviehash(video_path).ok();
+    let series = parse_series_episode(video_path);
+    let query = crate::metadata::clean_video_name(name);
+    let search = SubtitleSearch {
+        query: &query,
+        language,
+        moviehash: hash.as_deref(),
+        file_name: Some(name),
+        series: series.as_ref(),
+    };
+    let mut results = search_providers(app, &search, keys, &SubtitleProvider::ALL).await?;
+    results.retain(|r| r.file_id != 0 || r.download_url.is_some());
     Ok(results)
 }
 
+/// Download the best-scoring subtitle for a video, trying `languages` in order.
+/// The file is saved next to the video as `<name>.<lang>.<ext>`.
+async fn fetch_best_subtitle(app: &AppHandle, path: &Path, languages: &[String], keys: &ProviderKeys) -> Result<Option<AutoFetchedSubtitle>, SubtitleError> {
+    let video_name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string();
+
+    for language in languages.iter().map(|l| l.trim().to_lowercase()).filter(|l| !l.is_empty()) {
+        let candidates = find_candidates(app, path, &language, keys).await?;
+        let Some((best, score)) = candidates
+            .into_iter()
+            .map(|r| {
+                let score = score_result(&r, &video_name);
+                (r, score)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+        else {
+            continue;
+        };
+
+        let save_path = subtitle_save_path(path, &language, &subtitle_extension(&best.file_name), false)?;
+        let save_path = download_result(&best, &save_path, keys).await?;
+        tracing::info!("💬 Fetched {} subtitles for {} from {} ({}, score {:.1})", language, video_name, best.provider, best.release, score);
+        return Ok(Some(AutoFetchedSubtitle {
+            path: save_path.to_string_lossy().to_string(),
+            language,
+            release: best.release,
+            hash_match: best.hash_match,
+            score,
+        }));
+    }
+    Ok(None)
+}
+
+/// Find, download and load the best subtitle for a video, trying `languages` in order.
+/// The file is saved next to the video as `<name>.<lang>.<ext>` and selected in the running player.
+#[command]
+pub async fn auto_fetch_subtitle(
+    app: AppHandle,
+    state: State<'_, crate::mpv_handler::MpvState>,
+    video_path: String,
+    languages: Vec<String>,
+    api_key: Option<String>,
+) -> Result<Option<AutoFetchedSubtitle>, SubtitleError> {
+    let keys = ProviderKeys::load(&app, api_key);
+    let path = Path::new(&video_path);
+    if !path.is_file() {
+        return Err("Video file does not exist".into());
+    }
+    let fetched = fetch_best_subtitle(&app, path, &languages, &keys).await?;
+    if let Some(fetched) = &fetched {
+        let load = crate::subtitle_format::utf8_copy(&app, Path::new(&fetched.path)).unwrap_or_else(|e| {
+            tracing::warn!("⚠️ Could not decode subtitles to UTF-8: {}", e);
+            PathBuf::from(&fetched.path)
+        });
+        if let Some(tx) = state.tx.lock().unwrap().as_ref() {
+            let _ = tx.send(crate::mpv_handler::MpvCommand::AddSubtitle(load.to_string_lossy().to_string()));
+        }
+    }
+    Ok(fetched)
+}
+
+/// Download subtitles in `language` for every video in a folder (or season) that doesn't
+/// already have them, embedded or as a sidecar. Emits `subtitle-batch-progress` per file.
+#[command]
+pub async fn batch_download_subtitles(
+    app: AppHandle,
+    folder: String,
+    language: String,
+    recursive: Option<bool>,
+    api_key: Option<String>,
+) -> Result<BatchSubtitleReport, String> {
+    let keys = ProviderKeys::load(&app, api_key);
+    if !keys.any() {
+        return Err("A subtitle provider API key is required. Set it in Settings → Integrations.".to_string());
+    }
+    let language = language.trim().to_lowercase();
+    let mut videos: Vec<std::path::PathBuf> = walkdir::WalkDir::new(&folder)
+        .max_depth(if recursive.unwrap_or(false) { usize::MAX } else { 1 })
+        .into_iter()
+        .flatten()
+        .map(|e| e.into_path())
+        .filter(|p| crate::file_scanner::is_video(p))
+        .collect();
+    videos.sort();
+
+    let mut report = BatchSubtitleReport::default();
+    let missing: Vec<_> = videos
+        .into_iter()
+        .filter(|v| {
+            let has = crate::subtitle_pipeline::find_sidecar(v, &language).is_some()
+                || crate::subtitle_pipeline::find_embedded(v, &language).is_some();
+            if has {
+                report.already_had += 1;
+            }
+            !has
+        })
+        .collect();
+
+    let total = missing.len();
+    let languages = [language.clone()];
+    for (i, video) in missing.iter().enumerate() {
+        let video_path = video.to_string_lossy().to_string();
+        let progress = |status: &str| {
+            let _ = app.emit("subtitle-batch-progress", BatchSubtitleProgress {
+                video_path: video_path.clone(),
+                index: i + 1,
+                total,
+                status: status.to_string(),
+            });
+        };
+        progress("searching");
+        match fetch_best_subtitle(&app, video, &languages, &keys).await {
+            Ok(Some(fetched)) => {
+                progress("downloaded");
+                report.downloaded.push(fetched);
+            }
+            Ok(None) => {
+                progress("not_found");
+                report.not_found.push(video_path.clone());
+            }
+            Err(error) => {
+                progress("failed");
+                report.failed.push(BatchSubtitleFailure { video_path: video_path.clone(), error: error.to_string() });
+            }
+        }
+    }
+
+    tracing::info!(
+        "💬 Batch subtitles for {}: {} downloaded, {} not found, {} failed, {} already had {}",
+        folder, report.downloaded.len(), report.not_found.len(), report.failed.len(), report.already_had, language
+    );
+    Ok(report)
+}
+
+/// Download a search result next to the video as `<stem>.<language>.<ext>` (see
+/// `subtitle_save_path`); the remote `file_name` only supplies the extension.
+/// `provider` defaults to OpenSubtitles; others download from the result's `download_url`.
 #[command]
+#[allow(clippy::too_many_arguments)]
 pub async fn download_subtitle(
+    app: AppHandle,
     file_id: i64,
-    save_dir: String,
     file_name: String,
     api_key: String,
-) -> Result<String, String> {
+    provider: Option<String>,
+    download_url: Option<String>,
+    video_path: String,
+    language: String,
+    keep_alternatives: Option<bool>,
+) -> Result<String, SubtitleError> {
+    let provider = match provider {
+        Some(id) => SubtitleProvider::from_id(&id)?,
+        None => SubtitleProvider::OpenSubtitles,
+    };
+    let keys = ProviderKeys::load(&app, Some(api_key));
+    let save_path = subtitle_save_path(Path::new(&video_path), &language, &subtitle_extension(&file_name), keep_alternatives.unwrap_or(false))?;
+    let saved = provider.download(file_id, download_url.as_deref(), &save_path, &keys).await?;
+    Ok(saved.to_string_lossy().to_string())
+}
+
+fn api_base(session: Option<&OpenSubtitlesSession>) -> String {
+    let host = session.map(|s| s.base_url.as_str()).filter(|h| !h.is_empty()).unwrap_or(OPENSUBTITLES_HOST);
+    format!("https://{}/api/v1", host.trim_start_matches("https://").trim_end_matches('/'))
+}
+
+fn os_request(builder: reqwest::RequestBuilder, api_key: &str, session: Option<&OpenSubtitlesSession>) -> reqwest::RequestBuilder {
+    let builder = builder
+        .header("Api-Key", api_key)
+        .header("Content-Type", "application/json")
+        .header("User-Agent", "FrameX v0.1.0");
+    match session {
+        Some(session) => builder.header("Authorization", format!("Bearer {}", session.token)),
+        None => builder,
+    }
+}
+
+/// Log in to OpenSubtitles so downloads count against the account's higher daily quota.
+/// The session is kept in the app config until `opensubtitles_logout`.
+#[command]
+pub async fn opensubtitles_login(
+    app: AppHandle,
+    username: String,
+    password: String,
+    api_key: Option<String>,
+) -> Result<SubtitleQuota, SubtitleError> {
+    let keys = ProviderKeys::load(&app, api_key);
+    if keys.opensubtitles.is_empty() {
+        return Err(SubtitleError::Unauthorized { message: "OpenSubtitles API key is required.".to_string() });
+    }
+    let response = os_request(crate::metadata::http_client().post(format!("{}/login", api_base(None))), &keys.opensubtitles, None)
+        .json(&serde_json::json!({ "username": username.trim(), "password": password }))
+        .send()
+        .await
+        .map_err(|e| format!("Login request failed: {}", e))?;
+    match response.status().as_u16() {
+        401 => return Err(SubtitleError::Unauthorized { message: "Wrong OpenSubtitles username or password".to_string() }),
+        s if !(200..300).contains(&s) => return Err(format!("Login API error: {}", response.status()).into()),
+        _ => {}
+    }
+    let login: OsLoginResponse = response.json().await.map_err(|e| format!("Parse login response: {}", e))?;
+
+    let mut config = crate::config::load_config(&app);
+    config.opensubtitles = Some(OpenSubtitlesSession {
+        username: username.trim().to_string(),
+        token: login.token,
+        base_url: login.base_url.unwrap_or_else(|| OPENSUBTITLES_HOST.to_string()),
+        logged_in_at: now_secs(),
+    });
+    crate::config::save_config(&app, &config)?;
+    tracing::info!("🔑 Logged in to OpenSubtitles as {}", username.trim());
+
+    Ok(SubtitleQuota {
+        logged_in: true,
+        username: Some(username.trim().to_string()),
+        level: login.user.level,
+        vip: login.user.vip.unwrap_or(false),
+        allowed_downloads: login.user.allowed_downloads,
+        remaining_downloads: login.user.remaining_downloads.or(login.user.allowed_downloads),
+        reset_time: None,
+    })
+}
+
+#[command]
+pub async fn opensubtitles_logout(app: AppHandle) -> Result<(), String> {
+    let mut config = crate::config::load_config(&app);
+    let Some(session) = config.opensubtitles.take() else {
+        return Ok(());
+    };
+    // Best effort: the token is forgotten locally either way
+    let api_key = config.credentials.opensubtitles_api_key.clone().unwrap_or_default();
+    let request = crate::metadata::http_client().delete(format!("{}/logout", api_base(Some(&session))));
+    let _ = os_request(request, &api_key, Some(&session)).send().await;
+    crate::config::save_config(&app, &config)
+}
+
+/// Downloads left today: from the account when logged in, else what the last download reported.
+#[command]
+pub async fn get_subtitle_quota(app: AppHandle, api_key: Option<String>) -> Result<SubtitleQuota, SubtitleError> {
+    let keys = ProviderKeys::load(&app, api_key);
+    let last = LAST_QUOTA.lock().unwrap().clone();
+    let Some(session) = keys.opensubtitles_session.as_ref() else {
+        return Ok(SubtitleQuota {
+            remaining_downloads: last.as_ref().map(|(remaining, _)| *remaining),
+            reset_time: last.and_then(|(_, reset)| reset),
+            ..Default::default()
+        });
+    };
+
+    let request = crate::metadata::http_client().get(format!("{}/infos/user", api_base(Some(session))));
+    let response = os_request(request, &keys.opensubtitles, Some(session))
+        .send()
+        .await
+        .map_err(|e| format!("Quota request failed: {}", e))?;
+    if response.status().as_u16() == 401 {
+        return Err(SubtitleError::Unauthorized { message: "OpenSubtitles login expired, log in again".to_string() });
+    }
+    if !response.status().is_success() {
+        return Err(format!("Quota API error: {}", response.status()).into());
+    }
+    let info: OsUserInfo = response.json().await.map_err(|e| format!("Parse quota response: {}", e))?;
+    Ok(SubtitleQuota {
+        logged_in: true,
+        username: Some(session.username.clone()),
+        level: info.data.level,
+        vip: info.data.vip.unwrap_or(false),
+        allowed_downloads: info.data.allowed_downloads,
+        remaining_downloads: info.data.remaining_downloads,
+        reset_time: last.and_then(|(_, reset)| reset),
+    })
+}
+
+/// Download an OpenSubtitles file. With a `session`, it counts against the account's quota.
+pub async fn download_subtitle_to(
+    file_id: i64,
+    save_path: &Path,
+    api_key: &str,
+    session: Option<&OpenSubtitlesSession>,
+) -> Result<PathBuf, SubtitleError> {
     if api_key.is_empty() {
-        return Err("OpenSubtitles API key is required.".to_string());
+        return Err(SubtitleError::Unauthorized { message: "OpenSubtitles API key is required.".to_string() });
     }
 
     let client = reqwest::Client::new();
 
     // Step 1: Get download link
-    let dl_response = client
-        .post("https://api.opensubtitles.com/api/v1/download")
-        .header("Api-Key", &api_key)
-        .header("Content-Type", "application/json")
-        .header("User-Agent", "FrameX v0.1.0")
+    let dl_response = os_request(client.post(format!("{}/download", api_base(session))), api_key, session)
         .json(&serde_json::json!({ "file_id": file_id }))
         .send()
         .await
         .map_err(|e| format!("Download request failed: {}", e))?;
 
-    if !dl_response.status().is_success() {
-        return Err(format!("Download API error: {}", dl_response.status()));
+    match dl_response.status().as_u16() {
+        406 => {
+            let body: OsErrorBody = dl_response.json().await.unwrap_or_default();
+            *LAST_QUOTA.lock().unwrap() = Some((body.remaining.unwrap_or(0), body.reset_time.clone()));
+            return Err(SubtitleError::QuotaExceeded {
+                message: body.message.unwrap_or_else(|| "Daily OpenSubtitles download limit reached".to_string()),
+                remaining: body.remaining.unwrap_or(0),
+                reset_time: body.reset_time,
+                logged_in: session.is_some(),
+            });
+        }
+        401 => {
+            let message = if session.is_some() { "OpenSubtitles login expired, log in again" } else { "OpenSubtitles rejected the API key" };
+            return Err(SubtitleError::Unauthorized { message: message.to_string() });
+        }
+        s if !(200..300).contains(&s) => return Err(format!("Download API error: {}", dl_response.status()).into()),
+        _ => {}
     }
 
     let dl_data: OsDownloadResponse = dl_response
         .json()
         .await
         .map_err(|e| format!("Parse download response: {}", e))?;
+    if let Some(remaining) = dl_data.remaining {
+        tracing::info!("💬 OpenSubtitles downloads left today: {}", remaining);
+        *LAST_QUOTA.lock().unwrap() = Some((remaining, dl_data.reset_time.clone()));
+    }
 
     // Step 2: Download the actual file
-    let file_bytes = client
+    let file_response = client
         .get(&dl_data.link)
         .send()
         .await
-        .map_err(|e| format!("File download failed: {}", e))?
+        .map_err(|e| format!("File download failed: {}", e))?;
+    let content_type = content_type(&file_response);
+    let file_bytes = file_response
         .bytes()
         .await
         .map_err(|e| format!("Read bytes: {}", e))?;
 
-    // Step 3: Save to disk
-    let save_path = std::path::Path::new(&save_dir).join(&file_name);
-    fs::write(&save_path, &file_bytes)
-        .map_err(|e| format!("Save failed: {}", e))?;
+    // Step 3: Unpack if it's an archive and save to disk as UTF-8
+    Ok(save_subtitle_bytes(&file_bytes, content_type.as_deref(), save_path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    Ok(save_path.to_string_lossy().to_string())
+    fn episode(path: &str) -> Option<(String, u32, u32)> {
+        parse_series_episode(Path::new(path)).map(|e| (e.show, e.season, e.episode))
+    }
+
+    #[test]
+    fn series_episode_from_name() {
+        let some = |show: &str, season, episode| Some((show.to_string(), season, episode));
+        assert_eq!(episode("/tv/Show/Season 1/Show.S01E07.1080p.WEB-DL.mkv"), some("Show", 1, 7));
+        assert_eq!(episode("/tv/Show/Season 1/Show s02e10 720p.mkv"), some("Show", 2, 10));
+        assert_eq!(episode("/tv/Other/Show 1x02.mkv"), some("Show", 1, 2));
+        // No title in the name: the show comes from the folders
+        assert_eq!(episode("/tv/Breaking Bad/Season 2/S02E03.mkv"), some("Breaking Bad", 2, 3));
+        assert_eq!(episode("/tv/Breaking Bad/2x03.mkv"), some("Breaking Bad", 2, 3));
+    }
+
+    #[test]
+    fn series_episode_from_folders() {
+        let some = |show: &str, season, episode| Some((show.to_string(), season, episode));
+        assert_eq!(episode("/tv/Show/Season 3/E07.mkv"), some("Show", 3, 7));
+        assert_eq!(episode("/tv/Show/Season 3/Episode 12.mkv"), some("Show", 3, 12));
+        assert_eq!(episode("/tv/Show/Season 3/07 - Title.mkv"), some("Show", 3, 7));
+        assert_eq!(episode("/tv/Show/Specials/03.mkv"), some("Show", 0, 3));
+        // Resolution tags are not episode numbers
+        assert_eq!(episode("/tv/Show/Season 1/Ep 05 1080p.mkv"), some("Show", 1, 5));
+        assert_eq!(episode("/tv/Show/Season 1/720p - 05.mkv"), some("Show", 1, 5));
+        assert_eq!(episode("/tv/Show/Season 1/1080p.mkv"), None);
+        assert_eq!(episode("/tv/Show/Season 1/4K.mkv"), None);
+    }
+
+    #[test]
+    fn bare_numbers_need_a_season_folder() {
+        assert_eq!(episode("/tv/Show/05.mkv"), None);
+        assert_eq!(episode("/movies/2012.mkv"), None);
+        // Four digits is a year or a resolution, not an episode
+        assert_eq!(episode("/tv/Show/Season 1/2012.mkv"), None);
+    }
+
+    fn zip_of(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        use std::io::Write;
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            zip.start_file(*name, zip::write::SimpleFileOptions::default()).unwrap();
+            zip.write_all(content).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    fn gzip_of(content: &[u8]) -> Vec<u8> {
+        use std::io::Write;
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(content).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn unpacks_subtitles() {
+        let plain = b"1\n00:00:01,000 --> 00:00:02,000\nHi\n";
+        assert_eq!(unpack_subtitle(plain, Some("text/plain"), "").unwrap(), (plain.to_vec(), None));
+        assert_eq!(unpack_subtitle(&gzip_of(plain), None, "").unwrap(), (plain.to_vec(), None));
+
+        let archive = zip_of(&[
+            ("readme.txt", b"not a subtitle"),
+            ("Movie.2020.720p.WEB.srt", b"720p"),
+            ("Subs/Movie.2020.1080p.BluRay-GRP.ass", b"1080p"),
+        ]);
+        let (data, entry) = unpack_subtitle(&archive, Some("application/zip"), "Movie.2020.1080p.BluRay-GRP.en.srt").unwrap();
+        assert_eq!(data, b"1080p");
+        assert_eq!(entry.as_deref(), Some("Subs/Movie.2020.1080p.BluRay-GRP.ass"));
+
+        assert!(unpack_subtitle(&zip_of(&[("readme.txt", b"hi")]), None, "").is_err());
+    }
+
+    #[test]
+    fn refuses_oversized_archives() {
+        let huge = vec![0u8; MAX_UNPACKED_BYTES as usize + 1];
+        assert!(unpack_subtitle(&zip_of(&[("bomb.srt", &huge)]), None, "").is_err());
+        assert!(unpack_subtitle(&gzip_of(&huge), None, "").is_err());
+
+        assert!(read_capped(std::io::repeat(0)).is_err());
+        assert_eq!(read_capped(std::io::repeat(0).take(MAX_UNPACKED_BYTES)).unwrap().len() as u64, MAX_UNPACKED_BYTES);
+        assert_eq!(read_capped(&b"abc"[..]).unwrap(), b"abc");
+    }
+
+    #[test]
+    fn free_alternatives() {
+        let dir = std::env::temp_dir().join(format!("framex-test-subtitles-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("Movie.en.srt");
+        assert_eq!(free_alternative(&target), target);
+
+        fs::write(&target, "").unwrap();
+        assert_eq!(free_alternative(&target), dir.join("Movie.en.2.srt"));
+        fs::write(dir.join("Movie.en.2.srt"), "").unwrap();
+        assert_eq!(free_alternative(&target), dir.join("Movie.en.3.srt"));
+        // A numbered name counts on from its base instead of becoming `.2.2`
+        assert_eq!(free_alternative(&dir.join("Movie.en.2.srt")), dir.join("Movie.en.3.srt"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::command;
+
+// Codecs ffmpeg can turn into SRT; bitmap ones (PGS, VobSub, DVB) need OCR instead
+const TEXT_CODECS: &[&str] = &["subrip", "srt", "ass", "ssa", "webvtt", "mov_text", "text"];
+
+/// A subtitle stream inside a video container.
+#[derive(Serialize, Clone, Debug)]
+pub struct EmbeddedSubtitle {
+    pub index: u32, // ffmpeg stream index within the file
+    pub track: u32, // 0-based among subtitle streams, as used by `find_embedded`
+    pub codec: String,
+    pub language: Option<String>,
+    pub title: Option<String>,
+    pub default: bool,
+    pub forced: bool,
+    pub text: bool, // false for bitmap subtitles, which can't be extracted as text
+}
+
+#[derive(Serialize, Debug)]
+pub struct ExtractedSubtitle {
+    pub index: u32,
+    pub path: String,
+}
+
+#[derive(Deserialize)]
+struct ProbeOutput {
+    #[serde(default)]
+    streams: Vec<ProbeStream>,
+}
+
+#[derive(Deserialize)]
+struct ProbeStream {
+    index: u32,
+    #[serde(default)]
+    codec_name: String,
+    #[serde(default)]
+    tags: HashMap<String, String>,
+    #[serde(default)]
+    disposition: HashMap<String, i64>,
+}
+
+pub fn probe_subtitles(video_path: &Path) -> Result<Vec<EmbeddedSubtitle>, String> {
+    let output = crate::ffmpeg::ffprobe()
+        .arg("-select_streams").arg("s")
+        .arg("-show_entries").arg("stream=index,codec_name:stream_tags=language,title:stream_disposition=default,forced")
+        .arg("-of").arg("json")
+        .arg(video_path)
+        .output()
+        .map_err(|e| format!("Failed to run ffprobe: {}. Make sure ffmpeg is installed and in PATH.", e))?;
+    if !output.status.success() {
+        return Err(format!("ffprobe failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    let probe: ProbeOutput = serde_json::from_slice(&output.stdout).map_err(|e| format!("Parse error: {}", e))?;
+
+    Ok(probe.streams.into_iter().enumerate().map(|(track, s)| EmbeddedSubtitle {
+        index: s.index,
+        track: track as u32,
+        text: TEXT_CODECS.contains(&s.codec_name.as_str()),
+        codec: s.codec_name,
+        language: s.tags.get("language").filter(|l| !l.is_empty() && *l != "und").cloned(),
+        title: s.tags.get("title").cloned(),
+        default: s.disposition.get("default") == Some(&1),
+        forced: s.disposition.get("forced") == Some(&1),
+    }).collect())
+}
+
+fn extract_stream(video_path: &Path, stream: &EmbeddedSubtitle, save_path: &Path) -> Result<(), String> {
+    let output = crate::ffmpeg::hidden("ffmpeg")
+        .arg("-v").arg("error")
+        .arg("-y")
+        .arg("-i").arg(video_path)
+        .arg("-map").arg(format!("0:{}", stream.index))
+        .arg("-c:s").arg("srt")
+        .arg(save_path)
+        .output()
+        .map_err(|e| format!("FFmpeg error: {}. Make sure ffmpeg is installed and in PATH.", e))?;
+    if !output.status.success() {
+        return Err(format!("ffmpeg failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(())
+}
+
+/// Subtitle streams embedded in a video, text and bitmap.
+#[command]
+pub async fn list_embedded_subtitles(video_path: String) -> Result<Vec<EmbeddedSubtitle>, String> {
+    tauri::async_runtime::spawn_blocking(move || probe_subtitles(Path::new(&video_path)))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Extract embedded text subtitles to SRT sidecars named `<stem>.<lang>.srt` (see
+/// `subtitle_save_path`), so they can be edited or re-synced. `streams` picks stream
+/// indexes from `list_embedded_subtitles`; every text stream is extracted without it.
+#[command]
+pub async fn extract_embedded_subtitles(video_path: String, streams: Option<Vec<u32>>) -> Result<Vec<ExtractedSubtitle>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let path = Path::new(&video_path);
+        let available = probe_subtitles(path)?;
+        let selected: Vec<&EmbeddedSubtitle> = match &streams {
+            Some(indexes) => indexes
+                .iter()
+                .map(|i| {
+                    let stream = available.iter().find(|s| s.index == *i).ok_or(format!("No subtitle stream {}", i))?;
+                    if !stream.text {
+                        return Err(format!("Stream {} is a {} bitmap subtitle and can't be extracted as text", i, stream.codec));
+                    }
+                    Ok(stream)
+                })
+                .collect::<Result<_, String>>()?,
+            None => available.iter().filter(|s| s.text).collect(),
+        };
+
+        let mut extracted = Vec::new();
+        for stream in selected {
+            let language = stream.language.as_deref().filter(|l| crate::subtitle_downloader::is_valid_language_tag(l)).unwrap_or("und");
+            // Numbered when a language has several tracks (full and SDH, say)
+            let save_path = crate::subtitle_downloader::subtitle_save_path(path, language, "srt", true)?;
+            extract_stream(path, stream, &save_path)?;
+            tracing::info!("💬 Extracted subtitle stream {} ({}) to {}", stream.index, stream.codec, save_path.display());
+            extracted.push(ExtractedSubtitle { index: stream.index, path: save_path.to_string_lossy().to_string() });
+        }
+        Ok(extracted)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
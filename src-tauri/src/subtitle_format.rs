@@ -0,0 +1,344 @@
+use encoding_rs::Encoding;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// A single subtitle cue, times in seconds.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct Cue {
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SubtitleFormat {
+    Vtt,
+    Srt,
+    Ass,
+}
+
+impl SubtitleFormat {
+    pub fn parse(format: &str) -> Result<Self, String> {
+        match format.trim().to_lowercase().as_str() {
+            "vtt" | "webvtt" => Ok(Self::Vtt),
+            "srt" => Ok(Self::Srt),
+            "ass" | "ssa" => Ok(Self::Ass),
+            other => Err(format!("Unsupported subtitle format '{}'", other)),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Vtt => "vtt",
+            Self::Srt => "srt",
+            Self::Ass => "ass",
+        }
+    }
+}
+
+fn split_time(seconds: f64) -> (u64, u64, u64, u64) {
+    let total_ms = (seconds.max(0.0) * 1000.0).round() as u64;
+    (total_ms / 3_600_000, (total_ms % 3_600_000) / 60_000, (total_ms % 60_000) / 1000, total_ms % 1000)
+}
+
+fn format_vtt_time(seconds: f64) -> String {
+    let (h, m, s, ms) = split_time(seconds);
+    format!("{:02}:{:02}:{:02}.{:03}", h, m, s, ms)
+}
+
+fn format_srt_time(seconds: f64) -> String {
+    let (h, m, s, ms) = split_time(seconds);
+    format!("{:02}:{:02}:{:02},{:03}", h, m, s, ms)
+}
+
+// ASS uses centiseconds and a single-digit hour
+fn format_ass_time(seconds: f64) -> String {
+    let (h, m, s, ms) = split_time(seconds);
+    format!("{}:{:02}:{:02}.{:02}", h, m, s, ms / 10)
+}
+
+pub fn to_vtt(cues: &[Cue]) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for cue in cues {
+        out.push_str(&format!("{} --> {}\n{}\n\n", format_vtt_time(cue.start), format_vtt_time(cue.end), cue.text));
+    }
+    out
+}
+
+pub fn to_srt(cues: &[Cue]) -> String {
+    let mut out = String::new();
+    for (i, cue) in cues.iter().enumerate() {
+        out.push_str(&format!("{}\n{} --> {}\n{}\n\n", i + 1, format_srt_time(cue.start), format_srt_time(cue.end), cue.text));
+    }
+    out
+}
+
+/// Minimal styled ASS: one readable default style, line breaks converted to `\N`.
+pub fn to_ass(cues: &[Cue]) -> String {
+    let mut out = String::from(
+        "[Script Info]\n\
+         ScriptType: v4.00+\n\
+         PlayResX: 1920\n\
+         PlayResY: 1080\n\
+         WrapStyle: 0\n\
+         ScaledBorderAndShadow: yes\n\n\
+         [V4+ Styles]\n\
+         Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding\n\
+         Style: Default,Arial,64,&H00FFFFFF,&H000000FF,&H00000000,&H80000000,0,0,0,0,100,100,0,0,1,3,1,2,60,60,50,1\n\n\
+         [Events]\n\
+         Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n",
+    );
+    for cue in cues {
+        let text = cue.text.replace("\r\n", "\n").replace('\n', "\\N");
+        out.push_str(&format!("Dialogue: 0,{},{},Default,,0,0,0,,{}\n", format_ass_time(cue.start), format_ass_time(cue.end), text));
+    }
+    out
+}
+
+/// Parse "HH:MM:SS.mmm", "MM:SS.mmm" or SRT's "HH:MM:SS,mmm" into seconds.
+pub fn parse_timestamp(ts: &str) -> Option<f64> {
+    let ts = ts.trim().replace(',', ".");
+    let parts: Vec<&str> = ts.split(':').collect();
+    let (h, m, s) = match parts.as_slice() {
+        [h, m, s] => (h.parse::<f64>().ok()?, m.parse::<f64>().ok()?, s.parse::<f64>().ok()?),
+        [m, s] => (0.0, m.parse::<f64>().ok()?, s.parse::<f64>().ok()?),
+        _ => return None,
+    };
+    Some(h * 3600.0 + m * 60.0 + s)
+}
+
+fn strip_tags(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Parse WebVTT or SRT content (both use `start --> end` timing lines).
+pub fn parse_vtt_or_srt(content: &str) -> Vec<Cue> {
+    let content = content.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+    let mut cues = Vec::new();
+
+    for block in content.split("\n\n") {
+        let mut lines = block.lines().skip_while(|l| !l.contains("-->"));
+        let Some(timing) = lines.next() else {
+            continue;
+        };
+        let Some((start, rest)) = timing.split_once("-->") else {
+            continue;
+        };
+        // VTT cue settings (e.g. "align:start") follow the end time
+        let end = rest.split_whitespace().next().unwrap_or("");
+        let (Some(start), Some(end)) = (parse_timestamp(start), parse_timestamp(end)) else {
+            continue;
+        };
+
+        let text = lines.map(strip_tags).collect::<Vec<_>>().join("\n").trim().to_string();
+        if !text.is_empty() {
+            cues.push(Cue { start, end, text });
+        }
+    }
+    cues
+}
+
+/// Dialogue text without override blocks (`{\i1}`, `{\pos(..)}`) and with ASS escapes as plain text.
+fn clean_ass_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_block = false;
+    for c in text.chars() {
+        match c {
+            '{' => in_block = true,
+            '}' if in_block => in_block = false,
+            _ if !in_block => out.push(c),
+            _ => {}
+        }
+    }
+    out.replace("\\N", "\n").replace("\\n", "\n").replace("\\h", " ")
+}
+
+/// Parse the `[Events]` of an ASS/SSA script. Styling is dropped; comments and
+/// vector drawings (`{\p1}`), which have no text, are skipped.
+pub fn parse_ass(content: &str) -> Vec<Cue> {
+    let content = content.trim_start_matches('\u{feff}');
+    let mut in_events = false;
+    // Field order comes from the section's Format line; this is the v4+ default
+    let mut fields: Vec<String> = "Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text"
+        .split(',')
+        .map(|f| f.trim().to_lowercase())
+        .collect();
+    let mut cues = Vec::new();
+
+    for line in content.lines().map(|l| l.trim()) {
+        if line.starts_with('[') {
+            in_events = line.eq_ignore_ascii_case("[events]");
+            continue;
+        }
+        if !in_events {
+            continue;
+        }
+        if let Some(format) = line.strip_prefix("Format:") {
+            fields = format.split(',').map(|f| f.trim().to_lowercase()).collect();
+            continue;
+        }
+        let Some(event) = line.strip_prefix("Dialogue:") else {
+            continue;
+        };
+
+        // Text is the last field and may itself contain commas
+        let values: Vec<&str> = event.splitn(fields.len(), ',').collect();
+        let field = |name: &str| fields.iter().position(|f| f == name).and_then(|i| values.get(i)).map(|v| v.trim());
+        let (Some(start), Some(end), Some(raw)) = (field("start"), field("end"), field("text")) else {
+            continue;
+        };
+        if raw.contains("\\p1") {
+            continue;
+        }
+        let (Some(start), Some(end)) = (parse_timestamp(start), parse_timestamp(end)) else {
+            continue;
+        };
+        let text = clean_ass_text(raw).lines().map(|l| l.trim()).collect::<Vec<_>>().join("\n").trim().to_string();
+        if !text.is_empty() {
+            cues.push(Cue { start, end, text });
+        }
+    }
+    // Scripts may list events in any order; SRT and VTT expect them by time
+    cues.sort_by(|a, b| a.start.total_cmp(&b.start));
+    cues
+}
+
+pub fn parse(content: &str, format: SubtitleFormat) -> Vec<Cue> {
+    match format {
+        SubtitleFormat::Vtt | SubtitleFormat::Srt => parse_vtt_or_srt(content),
+        SubtitleFormat::Ass => parse_ass(content),
+    }
+}
+
+pub fn render(cues: &[Cue], format: SubtitleFormat) -> String {
+    match format {
+        SubtitleFormat::Vtt => to_vtt(cues),
+        SubtitleFormat::Srt => to_srt(cues),
+        SubtitleFormat::Ass => to_ass(cues),
+    }
+}
+
+/// Decode subtitle bytes to text. A BOM or valid UTF-8 is trusted; anything else is a legacy
+/// codepage (Windows-1250/1256, GBK, ...) guessed by chardetng, unless `encoding` names it.
+pub fn decode_subtitle(bytes: &[u8], encoding: Option<&str>) -> Result<(String, &'static Encoding), String> {
+    let encoding = match encoding.map(|e| e.trim()).filter(|e| !e.is_empty()) {
+        Some(label) => Encoding::for_label(label.as_bytes()).ok_or_else(|| format!("Unknown encoding '{}'", label))?,
+        None => match Encoding::for_bom(bytes) {
+            Some((encoding, _)) => encoding,
+            None if std::str::from_utf8(bytes).is_ok() => encoding_rs::UTF_8,
+            None => {
+                let mut detector = chardetng::EncodingDetector::new();
+                detector.feed(bytes, true);
+                detector.guess(None, true)
+            }
+        },
+    };
+    // `decode` also strips a BOM matching the encoding
+    let (text, _, _) = encoding.decode(bytes);
+    Ok((text.into_owned(), encoding))
+}
+
+/// Read a subtitle file in whatever encoding it was saved in.
+pub fn read_subtitle_file(path: &Path) -> Result<String, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read subtitles: {}", e))?;
+    Ok(decode_subtitle(&bytes, None)?.0)
+}
+
+/// Save downloaded subtitle bytes as UTF-8 (without BOM), which every player reads correctly.
+pub fn save_as_utf8(bytes: &[u8], path: &Path, encoding: Option<&str>) -> Result<&'static str, String> {
+    let (text, encoding) = decode_subtitle(bytes, encoding)?;
+    fs::write(path, text).map_err(|e| format!("Save failed: {}", e))?;
+    if encoding != encoding_rs::UTF_8 {
+        tracing::info!("💬 Converted {} from {} to UTF-8", path.display(), encoding.name());
+    }
+    Ok(encoding.name())
+}
+
+fn is_plain_utf8(bytes: &[u8]) -> bool {
+    !bytes.starts_with(b"\xEF\xBB\xBF") && std::str::from_utf8(bytes).is_ok()
+}
+
+/// `<file>.orig`, the untouched bytes of a subtitle file that was re-encoded in place.
+fn original_backup(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".orig");
+    PathBuf::from(name)
+}
+
+/// Rewrite a subtitle file as UTF-8 when it isn't already. Returns the encoding it was in.
+/// The original bytes are kept as `<file>.orig` and re-decoded from there, so a wrong guess
+/// can be fixed by calling again with the right `encoding`.
+pub fn normalize_to_utf8(path: &Path, encoding: Option<&str>) -> Result<&'static str, String> {
+    let backup = original_backup(path);
+    let bytes = match fs::read(&backup) {
+        Ok(bytes) => bytes,
+        Err(_) => fs::read(path).map_err(|e| format!("Failed to read subtitles: {}", e))?,
+    };
+    let forced = encoding.is_some_and(|e| !e.trim().is_empty());
+    if !forced && is_plain_utf8(&bytes) {
+        return Ok(encoding_rs::UTF_8.name());
+    }
+    if !backup.exists() {
+        fs::write(&backup, &bytes).map_err(|e| format!("Failed to back up subtitles: {}", e))?;
+    }
+    save_as_utf8(&bytes, path, encoding)
+}
+
+/// A UTF-8 version of a subtitle file for mpv, which shows legacy codepages as mojibake:
+/// the file itself when it already is UTF-8, otherwise a decoded copy in the app cache.
+pub fn utf8_copy(app: &AppHandle, path: &Path) -> Result<PathBuf, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read subtitles: {}", e))?;
+    if is_plain_utf8(&bytes) {
+        return Ok(path.to_path_buf());
+    }
+    let dir = app.path().app_cache_dir().map_err(|e| e.to_string())?.join("subtitles");
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("srt");
+    let copy = dir.join(format!("{}.{}", crate::util::hash_path(&path.to_string_lossy()), ext));
+    save_as_utf8(&bytes, &copy, None)?;
+    Ok(copy)
+}
+
+/// Re-encode a subtitle file as UTF-8, detecting its encoding or using `encoding`
+/// (a label like "windows-1256" or "gbk") when the guess was wrong.
+#[tauri::command]
+pub fn normalize_subtitle_encoding(path: String, encoding: Option<String>) -> Result<String, String> {
+    normalize_to_utf8(Path::new(&path), encoding.as_deref()).map(|e| e.to_string())
+}
+
+pub fn write_subtitles(cues: &[Cue], format: SubtitleFormat, output_path: &Path) -> Result<(), String> {
+    fs::write(output_path, render(cues, format)).map_err(|e| format!("Failed to write subtitles: {}", e))
+}
+
+/// Convert a subtitle file between SRT, VTT and ASS, keeping the timing and the plain text.
+/// Written next to the input with the new extension unless `output_path` is given.
+#[tauri::command]
+pub fn convert_subtitle(input_path: String, format: String, output_path: Option<String>) -> Result<String, String> {
+    let input = Path::new(&input_path);
+    let from = SubtitleFormat::parse(input.extension().and_then(|e| e.to_str()).unwrap_or_default())?;
+    let to = SubtitleFormat::parse(&format)?;
+    let output = output_path.map(std::path::PathBuf::from).unwrap_or_else(|| input.with_extension(to.extension()));
+    if output == input {
+        return Err("The subtitles are already in that format".to_string());
+    }
+
+    let content = read_subtitle_file(input)?;
+    let cues = parse(&content, from);
+    if cues.is_empty() {
+        return Err("No subtitle cues found".to_string());
+    }
+    write_subtitles(&cues, to, &output)?;
+    tracing::info!("💬 Converted {} cues from {} to {}", cues.len(), input_path, output.display());
+    Ok(output.to_string_lossy().to_string())
+}
@@ -0,0 +1,476 @@
+use image::{GrayImage, Luma};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{command, AppHandle, Emitter, State};
+
+use crate::subtitle_format::{self, Cue, SubtitleFormat};
+
+// PGS timestamps are on the 90kHz MPEG clock
+const PGS_CLOCK: f64 = 90_000.0;
+// Shown until the next caption when a stream never clears the last one
+const LAST_CAPTION_SECONDS: f64 = 5.0;
+// White margin around each caption; Tesseract misreads glyphs touching the edge
+const IMAGE_PADDING: u32 = 12;
+
+#[derive(Serialize, Clone)]
+pub struct OcrProgress {
+    pub job_id: String,
+    pub status: String,
+    pub progress: f32,
+}
+
+/// A running OCR job, shared between the worker and `cancel_subtitle_ocr`.
+pub struct OcrJob {
+    id: String,
+    cancelled: AtomicBool,
+    child: Mutex<Option<Child>>,
+    temp_dir: PathBuf,
+}
+
+impl OcrJob {
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+pub struct OcrJobs {
+    pub jobs: Mutex<HashMap<String, Arc<OcrJob>>>,
+}
+
+impl OcrJobs {
+    pub fn new() -> Self {
+        Self {
+            jobs: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+/// Optional knobs for `ocr_subtitle_track`.
+#[derive(Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct OcrOptions {
+    pub language: Option<String>,       // Tesseract language ("eng", "fra", "chi_sim"); defaults to the track's tag
+    pub tesseract_path: Option<String>, // defaults to `tesseract` on PATH
+    // Replaces the built-in PGS decoder, e.g. for VobSub: `{input}` is the track in a .mks,
+    // `{output}` the SRT the tool must write and `{lang}` the language
+    pub external_command: Option<String>,
+    pub job_id: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct OcrResult {
+    pub path: String,
+    pub cues: usize,
+}
+
+/// One caption as shown on screen: its timing and the palette-resolved bitmap.
+struct Caption {
+    start: f64,
+    end: Option<f64>,
+    image: GrayImage,
+}
+
+/// A composition being assembled: when it shows, with which palette, and object ids at positions.
+struct Composition {
+    pts: f64,
+    palette_id: u8,
+    placed: Vec<(usize, usize, usize)>,
+}
+
+struct PgsObject {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>, // palette indexes
+}
+
+fn emit_progress(app: &AppHandle, job_id: &str, status: &str, progress: f32) {
+    let _ = app.emit("subtitle-ocr-progress", OcrProgress {
+        job_id: job_id.to_string(),
+        status: status.to_string(),
+        progress,
+    });
+}
+
+fn be16(data: &[u8], at: usize) -> usize {
+    ((data[at] as usize) << 8) | data[at + 1] as usize
+}
+
+/// PGS run-length encoding: a non-zero byte is one pixel; `0x00` starts a run whose
+/// top two bits say whether the length is long and whether a colour follows.
+fn decode_rle(data: &[u8], width: usize, height: usize) -> Vec<u8> {
+    let mut pixels = vec![0u8; width * height];
+    let (mut x, mut y, mut i) = (0usize, 0usize, 0usize);
+    let mut next = || {
+        let b = data.get(i).copied();
+        i += 1;
+        b
+    };
+    while y < height {
+        let Some(b) = next() else { break };
+        let (count, color) = if b != 0 {
+            (1, b)
+        } else {
+            let Some(flags) = next() else { break };
+            if flags == 0 {
+                x = 0;
+                y += 1;
+                continue;
+            }
+            let short = (flags & 0x3F) as usize;
+            let count = if flags & 0x40 != 0 { (short << 8) | next().unwrap_or(0) as usize } else { short };
+            let color = if flags & 0x80 != 0 { next().unwrap_or(0) } else { 0 };
+            (count, color)
+        };
+        for _ in 0..count {
+            if x < width {
+                pixels[y * width + x] = color;
+            }
+            x += 1;
+        }
+    }
+    pixels
+}
+
+/// Draw the caption's objects as dark text on white, which is what Tesseract reads best.
+fn render_caption(placed: &[(usize, usize, &PgsObject)], palette: &[(u8, u8); 256]) -> Option<GrayImage> {
+    let left = placed.iter().map(|(x, _, _)| *x).min()?;
+    let top = placed.iter().map(|(_, y, _)| *y).min()?;
+    let right = placed.iter().map(|(x, _, o)| x + o.width).max()?;
+    let bottom = placed.iter().map(|(_, y, o)| y + o.height).max()?;
+    let pad = IMAGE_PADDING as usize;
+    let mut image = GrayImage::from_pixel((right - left + pad * 2) as u32, (bottom - top + pad * 2) as u32, Luma([255]));
+
+    for (ox, oy, object) in placed {
+        for row in 0..object.height {
+            for col in 0..object.width {
+                let (luma, alpha) = palette[object.pixels[row * object.width + col] as usize];
+                // Composite over black, then invert: white fill turns dark, the outline light
+                let value = luma as u32 * alpha as u32 / 255;
+                let (x, y) = (ox - left + col + pad, oy - top + row + pad);
+                image.put_pixel(x as u32, y as u32, Luma([255 - value as u8]));
+            }
+        }
+    }
+    Some(image)
+}
+
+/// Parse a PGS (`.sup`) stream into captions. Each display set is a composition (PCS) naming
+/// the objects (ODS) to show with a palette (PDS); a composition with no objects clears the screen.
+fn parse_pgs(data: &[u8]) -> Vec<Caption> {
+    let mut captions: Vec<Caption> = Vec::new();
+    let mut palettes: HashMap<u8, [(u8, u8); 256]> = HashMap::new();
+    let mut objects: HashMap<usize, PgsObject> = HashMap::new();
+    let mut pending: HashMap<usize, (usize, usize, Vec<u8>)> = HashMap::new();
+    let mut composition: Option<Composition> = None;
+
+    let mut at = 0;
+    while at + 13 <= data.len() {
+        if &data[at..at + 2] != b"PG" {
+            break;
+        }
+        let pts = u32::from_be_bytes([data[at + 2], data[at + 3], data[at + 4], data[at + 5]]) as f64 / PGS_CLOCK;
+        let kind = data[at + 10];
+        let size = be16(data, at + 11);
+        let Some(segment) = data.get(at + 13..at + 13 + size) else { break };
+        at += 13 + size;
+
+        match kind {
+            // Presentation composition
+            0x16 if segment.len() >= 11 => {
+                if segment[7] & 0x80 != 0 {
+                    objects.clear(); // epoch start: earlier objects are gone
+                }
+                let count = segment[10] as usize;
+                let mut placed = Vec::new();
+                let mut pos = 11;
+                for _ in 0..count {
+                    if pos + 8 > segment.len() {
+                        break;
+                    }
+                    let cropped = segment[pos + 3] & 0x80 != 0;
+                    placed.push((be16(segment, pos), be16(segment, pos + 4), be16(segment, pos + 6)));
+                    pos += if cropped { 16 } else { 8 };
+                }
+                composition = Some(Composition { pts, palette_id: segment[9], placed });
+            }
+            // Palette definition: (id, Y, Cr, Cb, alpha) entries
+            0x14 if segment.len() >= 2 => {
+                let palette = palettes.entry(segment[0]).or_insert([(0, 0); 256]);
+                for entry in segment[2..].chunks_exact(5) {
+                    palette[entry[0] as usize] = (entry[1], entry[4]);
+                }
+            }
+            // Object definition, possibly split over several segments
+            0x15 if segment.len() >= 4 => {
+                let id = be16(segment, 0);
+                let flags = segment[3];
+                if flags & 0x80 != 0 && segment.len() >= 11 {
+                    pending.insert(id, (be16(segment, 7), be16(segment, 9), segment[11..].to_vec()));
+                } else if let Some((_, _, rle)) = pending.get_mut(&id) {
+                    rle.extend_from_slice(&segment[4..]);
+                }
+                if flags & 0x40 != 0 {
+                    if let Some((width, height, rle)) = pending.remove(&id) {
+                        objects.insert(id, PgsObject { width, height, pixels: decode_rle(&rle, width, height) });
+                    }
+                }
+            }
+            // End of display set
+            0x80 => {
+                let Some(Composition { pts, palette_id, placed }) = composition.take() else { continue };
+                if let Some(open) = captions.last_mut().filter(|c| c.end.is_none()) {
+                    open.end = Some(pts);
+                }
+                let palette = palettes.get(&palette_id).copied().unwrap_or([(0, 0); 256]);
+                let shown: Vec<(usize, usize, &PgsObject)> = placed
+                    .iter()
+                    .filter_map(|(id, x, y)| objects.get(id).map(|o| (*x, *y, o)))
+                    .collect();
+                if let Some(image) = render_caption(&shown, &palette) {
+                    captions.push(Caption { start: pts, end: None, image });
+                }
+            }
+            _ => {}
+        }
+    }
+    captions
+}
+
+fn tesseract(image_path: &Path, language: &str, binary: &str) -> Result<String, String> {
+    let output = crate::ffmpeg::hidden(binary)
+        .arg(image_path)
+        .arg("stdout")
+        .arg("-l").arg(language)
+        .arg("--psm").arg("6") // a single block of text
+        .output()
+        .map_err(|e| format!("Failed to run Tesseract: {}. Install it or set its path.", e))?;
+    if !output.status.success() {
+        return Err(format!("Tesseract failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    Ok(text.lines().map(|l| l.trim()).filter(|l| !l.is_empty()).collect::<Vec<_>>().join("\n"))
+}
+
+/// Tesseract's traineddata name for a track's language tag. Matroska uses ISO 639-2/B
+/// ("fre", "ger"), Tesseract mostly 639-2/T ("fra", "deu"); anything it doesn't have
+/// installed falls back to English.
+fn tesseract_language(binary: &str, tag: &str) -> String {
+    let tag = tag.trim().to_lowercase();
+    let code = match tag.as_str() {
+        "fre" => "fra",
+        "ger" => "deu",
+        "chi" | "zho" => "chi_sim",
+        "dut" => "nld",
+        "cze" => "ces",
+        "gre" => "ell",
+        "rum" => "ron",
+        "slo" => "slk",
+        "per" => "fas",
+        "arm" => "hye",
+        "baq" => "eus",
+        "bur" => "mya",
+        "geo" => "kat",
+        "ice" => "isl",
+        "mac" => "mkd",
+        "may" => "msa",
+        "alb" => "sqi",
+        "tib" => "bod",
+        "wel" => "cym",
+        other => other,
+    };
+
+    // --list-langs prints a header line, then one name per line (on stderr in older versions)
+    let installed: Vec<String> = crate::ffmpeg::hidden(binary)
+        .arg("--list-langs")
+        .output()
+        .map(|o| [o.stdout, o.stderr].concat())
+        .map(|out| String::from_utf8_lossy(&out).lines().skip(1).map(|l| l.trim().to_string()).collect())
+        .unwrap_or_default();
+    // Can't tell what's installed; let Tesseract report it. "eng+fra" is checked part by part.
+    if !code.is_empty() && (installed.is_empty() || code.split('+').all(|c| installed.iter().any(|l| l == c))) {
+        return code.to_string();
+    }
+    tracing::warn!("Tesseract has no '{}' language data; using English", code);
+    "eng".to_string()
+}
+
+fn ffmpeg_copy_stream(video_path: &Path, stream: u32, output: &Path) -> Result<(), String> {
+    let result = crate::ffmpeg::hidden("ffmpeg")
+        .arg("-v").arg("error")
+        .arg("-y")
+        .arg("-i").arg(video_path)
+        .arg("-map").arg(format!("0:{}", stream))
+        .arg("-c:s").arg("copy")
+        .arg(output)
+        .output()
+        .map_err(|e| format!("FFmpeg error: {}. Make sure ffmpeg is installed and in PATH.", e))?;
+    if !result.status.success() {
+        return Err(format!("ffmpeg failed: {}", String::from_utf8_lossy(&result.stderr).trim()));
+    }
+    Ok(())
+}
+
+/// OCR every caption of a PGS stream with Tesseract. Consecutive captions that read the
+/// same (fades are several display sets) are merged into one cue.
+fn ocr_pgs(app: &AppHandle, job: &OcrJob, sup_path: &Path, language: &str, binary: &str) -> Result<Vec<Cue>, String> {
+    let data = fs::read(sup_path).map_err(|e| e.to_string())?;
+    let captions = parse_pgs(&data);
+    if captions.is_empty() {
+        return Err("No captions found in the subtitle track".to_string());
+    }
+
+    let total = captions.len();
+    let mut cues: Vec<Cue> = Vec::new();
+    for (i, caption) in captions.into_iter().enumerate() {
+        if job.is_cancelled() {
+            return Err("OCR cancelled".to_string());
+        }
+        let image_path = job.temp_dir.join("caption.png");
+        caption.image.save(&image_path).map_err(|e| e.to_string())?;
+        let text = tesseract(&image_path, language, binary)?;
+        emit_progress(app, &job.id, &format!("Reading caption {}/{}...", i + 1, total), 10.0 + 85.0 * (i + 1) as f32 / total as f32);
+        if text.is_empty() {
+            continue;
+        }
+
+        let end = caption.end.unwrap_or(caption.start + LAST_CAPTION_SECONDS);
+        match cues.last_mut() {
+            Some(last) if last.text == text && (caption.start - last.end).abs() < 0.1 => last.end = end,
+            _ => cues.push(Cue { start: caption.start, end, text }),
+        }
+    }
+    Ok(cues)
+}
+
+/// Hand the track to a user-supplied OCR tool (SubtitleEdit's CLI, vobsub2srt, ...).
+fn run_external(job: &OcrJob, template: &str, input: &Path, output: &Path, language: &str) -> Result<Vec<Cue>, String> {
+    let mut parts = template.split_whitespace().map(|part| {
+        part.replace("{input}", &input.to_string_lossy())
+            .replace("{output}", &output.to_string_lossy())
+            .replace("{lang}", language)
+    });
+    let program = parts.next().ok_or("The external OCR command is empty")?;
+    let child = crate::ffmpeg::hidden(program)
+        .args(parts)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run the external OCR tool: {}", e))?;
+    *job.child.lock().map_err(|e| e.to_string())? = Some(child);
+
+    // Release the lock while waiting so `cancel_subtitle_ocr` can kill the tool
+    let status = loop {
+        if let Some(child) = job.child.lock().map_err(|e| e.to_string())?.as_mut() {
+            if let Some(status) = child.try_wait().map_err(|e| e.to_string())? {
+                break status;
+            }
+        }
+        std::thread::sleep(std::time::Duration::from_millis(200));
+    };
+    if job.is_cancelled() {
+        return Err("OCR cancelled".to_string());
+    }
+    if !status.success() {
+        return Err(format!("The external OCR tool exited with {}", status));
+    }
+    let content = subtitle_format::read_subtitle_file(output)?;
+    Ok(subtitle_format::parse_vtt_or_srt(&content))
+}
+
+/// Convert an image-based subtitle track (PGS, VobSub) to an SRT sidecar via OCR, in the
+/// background with `subtitle-ocr-progress` events. PGS is decoded here and read by Tesseract;
+/// other formats need `external_command`.
+#[command]
+pub async fn ocr_subtitle_track(
+    app: AppHandle,
+    jobs: State<'_, OcrJobs>,
+    video_path: String,
+    stream_index: u32,
+    options: Option<OcrOptions>,
+) -> Result<OcrResult, String> {
+    let options = options.unwrap_or_default();
+    let video = PathBuf::from(&video_path);
+    let probe_video = video.clone();
+    let streams = tauri::async_runtime::spawn_blocking(move || crate::subtitle_extract::probe_subtitles(&probe_video))
+        .await
+        .map_err(|e| e.to_string())??;
+    let stream = streams.into_iter().find(|s| s.index == stream_index).ok_or(format!("No subtitle stream {}", stream_index))?;
+    if stream.text {
+        return Err("This is a text subtitle track; extract it instead of running OCR".to_string());
+    }
+    let external = options.external_command.filter(|c| !c.trim().is_empty());
+    if external.is_none() && stream.codec != "hdmv_pgs_subtitle" {
+        return Err(format!("{} subtitles need an external OCR tool", stream.codec));
+    }
+    let language = options
+        .language
+        .filter(|l| !l.trim().is_empty())
+        .or_else(|| stream.language.clone())
+        .unwrap_or_else(|| "eng".to_string());
+    let binary = options.tesseract_path.filter(|p| !p.trim().is_empty()).unwrap_or_else(|| "tesseract".to_string());
+
+    let job_id = options.job_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let job = Arc::new(OcrJob {
+        id: job_id.clone(),
+        cancelled: AtomicBool::new(false),
+        child: Mutex::new(None),
+        temp_dir: std::env::temp_dir().join(format!("framex_ocr_{}", job_id)),
+    });
+    jobs.jobs.lock().map_err(|e| e.to_string())?.insert(job_id.clone(), Arc::clone(&job));
+    emit_progress(&app, &job_id, "Extracting subtitle track...", 0.0);
+
+    let worker_app = app.clone();
+    let worker_job = Arc::clone(&job);
+    let worker_language = language.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || -> Result<Vec<Cue>, String> {
+        fs::create_dir_all(&worker_job.temp_dir).map_err(|e| format!("Failed to create temp dir: {}", e))?;
+        let track = worker_job.temp_dir.join(if external.is_some() { "track.mks" } else { "track.sup" });
+        ffmpeg_copy_stream(&video, stream_index, &track)?;
+        emit_progress(&worker_app, &worker_job.id, "Reading captions...", 10.0);
+        match &external {
+            Some(template) => run_external(&worker_job, template, &track, &worker_job.temp_dir.join("track.srt"), &worker_language),
+            None => ocr_pgs(&worker_app, &worker_job, &track, &tesseract_language(&binary, &worker_language), &binary),
+        }
+    })
+    .await
+    .map_err(|e| format!("OCR task failed: {}", e))
+    .and_then(|r| r);
+
+    let _ = fs::remove_dir_all(&job.temp_dir);
+    if let Ok(mut map) = jobs.jobs.lock() {
+        map.remove(&job_id);
+    }
+
+    let cues = result?;
+    if cues.is_empty() {
+        return Err("OCR found no text".to_string());
+    }
+    let save_path = crate::subtitle_downloader::subtitle_save_path(Path::new(&video_path), &language, "srt", true)?;
+    subtitle_format::write_subtitles(&cues, SubtitleFormat::Srt, &save_path)?;
+    tracing::info!("🔠 OCR'd {} cues from stream {} to {}", cues.len(), stream_index, save_path.display());
+
+    emit_progress(&app, &job_id, "OCR complete!", 100.0);
+    Ok(OcrResult { path: save_path.to_string_lossy().to_string(), cues: cues.len() })
+}
+
+/// Stop a running OCR job, killing the external tool if one is in use.
+#[command]
+pub fn cancel_subtitle_ocr(app: AppHandle, jobs: State<'_, OcrJobs>, job_id: String) -> Result<bool, String> {
+    let job = jobs.jobs.lock().map_err(|e| e.to_string())?.get(&job_id).cloned();
+    let Some(job) = job else {
+        return Ok(false);
+    };
+
+    job.cancelled.store(true, Ordering::SeqCst);
+    if let Some(child) = job.child.lock().map_err(|e| e.to_string())?.as_mut() {
+        let _ = child.kill();
+    }
+
+    emit_progress(&app, &job_id, "OCR cancelled", 0.0);
+    Ok(true)
+}
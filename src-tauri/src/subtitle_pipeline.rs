@@ -0,0 +1,211 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use tauri::{command, AppHandle, Emitter, State};
+
+use crate::ai::whisper::{WhisperJobs, WhisperOptions};
+
+const SUBTITLE_EXTENSIONS: &[&str] = &["srt", "vtt", "ass", "ssa", "sub"];
+
+// ISO 639-1 -> 639-2 (bibliographic and terminology) for matching container tags
+const LANGUAGE_CODES: &[(&str, &[&str])] = &[
+    ("en", &["eng"]), ("es", &["spa"]), ("fr", &["fre", "fra"]), ("de", &["ger", "deu"]),
+    ("it", &["ita"]), ("pt", &["por"]), ("ru", &["rus"]), ("ja", &["jpn"]),
+    ("ko", &["kor"]), ("zh", &["chi", "zho"]), ("ar", &["ara"]), ("hi", &["hin"]),
+    ("nl", &["dut", "nld"]), ("sv", &["swe"]), ("pl", &["pol"]), ("tr", &["tur"]),
+    ("el", &["gre", "ell"]), ("he", &["heb"]), ("cs", &["cze", "ces"]), ("da", &["dan"]),
+    ("fi", &["fin"]), ("no", &["nor"]), ("hu", &["hun"]), ("ro", &["rum", "ron"]),
+    ("uk", &["ukr"]), ("vi", &["vie"]), ("th", &["tha"]), ("id", &["ind"]),
+];
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct EnsureSubtitlesOptions {
+    pub api_key: Option<String>,          // OpenSubtitles; stage is skipped without it
+    pub allow_whisper: bool,              // false: stop and let the UI offer transcription
+    pub whisper: Option<WhisperOptions>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SubtitlePipelineProgress {
+    pub video_path: String,
+    pub stage: String, // "embedded" | "sidecar" | "opensubtitles" | "whisper" | "done"
+    pub status: String,
+    pub progress: f32,
+    pub job_id: Option<String>, // set during the whisper stage to correlate with whisper-progress
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct EnsuredSubtitles {
+    pub source: String, // "embedded" | "sidecar" | "opensubtitles" | "whisper" | "none"
+    pub path: Option<String>,
+    pub track: Option<u32>, // embedded subtitle track, 0-based among subtitle streams
+    pub language: String,
+}
+
+#[derive(Deserialize)]
+struct ProbeOutput {
+    #[serde(default)]
+    streams: Vec<ProbeStream>,
+}
+
+#[derive(Deserialize)]
+struct ProbeStream {
+    #[serde(default)]
+    tags: std::collections::HashMap<String, String>,
+}
+
+fn emit_stage(app: &AppHandle, video_path: &str, stage: &str, status: &str, progress: f32, job_id: Option<String>) {
+    let _ = app.emit("subtitle-pipeline", SubtitlePipelineProgress {
+        video_path: video_path.to_string(),
+        stage: stage.to_string(),
+        status: status.to_string(),
+        progress,
+        job_id,
+    });
+}
+
+/// Whether a tag like "en", "eng" or "pt-BR" refers to the requested 2-letter language.
+pub fn language_matches(tag: &str, wanted: &str) -> bool {
+    let tag = tag.trim().to_lowercase();
+    let wanted = wanted.trim().to_lowercase();
+    let primary = tag.split(['-', '_']).next().unwrap_or("");
+    if primary == wanted || tag == wanted {
+        return true;
+    }
+    LANGUAGE_CODES
+        .iter()
+        .any(|(short, long)| *short == wanted && long.contains(&primary))
+}
+
+pub fn find_embedded(video_path: &Path, language: &str) -> Option<u32> {
+    let output = crate::ffmpeg::ffprobe()
+        .arg("-select_streams").arg("s")
+        .arg("-show_entries").arg("stream=index:stream_tags=language")
+        .arg("-of").arg("json")
+        .arg(video_path)
+        .output()
+        .ok()?;
+    let probe: ProbeOutput = serde_json::from_slice(&output.stdout).ok()?;
+
+    probe.streams.iter().position(|s| {
+        s.tags.get("language").is_some_and(|l| language_matches(l, language))
+    }).map(|i| i as u32)
+}
+
+/// Subtitle files next to the video named `<stem>.<lang>.<ext>` (or any tag matching the language).
+pub fn find_sidecar(video_path: &Path, language: &str) -> Option<String> {
+    let stem = video_path.file_stem()?.to_string_lossy().to_string();
+    let dir = video_path.parent()?;
+
+    let mut untagged = None;
+    for entry in fs::read_dir(dir).ok()?.flatten() {
+        let path = entry.path();
+        let is_subtitle = path.extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| SUBTITLE_EXTENSIONS.contains(&e.to_lowercase().as_str()));
+        let Some(name) = path.file_stem().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+        if !is_subtitle || !name.starts_with(&stem) {
+            continue;
+        }
+
+        let suffix = name[stem.len()..].trim_start_matches(['.', '_', '-', ' ']);
+        if suffix.is_empty() {
+            untagged = Some(path.to_string_lossy().to_string());
+        } else if suffix.split(['.', '_', ' ']).any(|tag| language_matches(tag, language)) {
+            return Some(path.to_string_lossy().to_string());
+        }
+    }
+    // An untagged `<stem>.srt` is most likely in the user's own language
+    untagged
+}
+
+async fn fetch_opensubtitles(app: &AppHandle, video_path: &Path, language: &str, api_key: &str) -> Result<Option<String>, String> {
+    let mut results = crate::subtitle_downloader::search_subtitles_by_hash(video_path, language, api_key).await?;
+    results.retain(|r| r.file_id != 0);
+    let Some(best) = results.into_iter().max_by_key(|r| r.download_count) else {
+        return Ok(None);
+    };
+
+    let save_path = crate::subtitle_downloader::subtitle_save_path(video_path, language, "srt", false)?;
+    let session = crate::config::load_config(app).opensubtitles;
+    let saved = crate::subtitle_downloader::download_subtitle_to(best.file_id, &save_path, api_key, session.as_ref())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(Some(saved.to_string_lossy().to_string()))
+}
+
+/// Find or produce subtitles for a video in one pass:
+/// embedded track -> local sidecar -> OpenSubtitles hash match -> Whisper.
+#[command]
+pub async fn ensure_subtitles(
+    app: AppHandle,
+    jobs: State<'_, WhisperJobs>,
+    video_path: String,
+    language: String,
+    options: Option<EnsureSubtitlesOptions>,
+) -> Result<EnsuredSubtitles, String> {
+    let options = options.unwrap_or_default();
+    let language = language.trim().to_lowercase();
+    let path = Path::new(&video_path);
+    if !path.exists() {
+        return Err("Video file does not exist".to_string());
+    }
+
+    let found = |source: &str, path: Option<String>, track: Option<u32>| EnsuredSubtitles {
+        source: source.to_string(),
+        path,
+        track,
+        language: language.clone(),
+    };
+
+    emit_stage(&app, &video_path, "embedded", "Checking embedded subtitle tracks...", 0.0, None);
+    if let Some(track) = find_embedded(path, &language) {
+        emit_stage(&app, &video_path, "done", "Using embedded subtitles", 100.0, None);
+        return Ok(found("embedded", None, Some(track)));
+    }
+
+    emit_stage(&app, &video_path, "sidecar", "Looking for subtitle files...", 5.0, None);
+    if let Some(sidecar) = find_sidecar(path, &language) {
+        emit_stage(&app, &video_path, "done", "Using local subtitle file", 100.0, None);
+        return Ok(found("sidecar", Some(sidecar), None));
+    }
+
+    match options.api_key.as_deref().filter(|k| !k.is_empty()) {
+        Some(api_key) => {
+            emit_stage(&app, &video_path, "opensubtitles", "Searching OpenSubtitles...", 10.0, None);
+            match fetch_opensubtitles(&app, path, &language, api_key).await {
+                Ok(Some(downloaded)) => {
+                    emit_stage(&app, &video_path, "done", "Downloaded subtitles from OpenSubtitles", 100.0, None);
+                    return Ok(found("opensubtitles", Some(downloaded), None));
+                }
+                Ok(None) => tracing::info!("No OpenSubtitles hash match for {}", video_path),
+                // Provider problems shouldn't block the Whisper fallback
+                Err(e) => tracing::error!("OpenSubtitles lookup failed: {}", e),
+            }
+        }
+        None => tracing::info!("No OpenSubtitles API key, skipping online search"),
+    }
+
+    if !options.allow_whisper {
+        emit_stage(&app, &video_path, "done", "No subtitles found", 100.0, None);
+        return Ok(found("none", None, None));
+    }
+
+    let mut whisper = options.whisper.unwrap_or_default();
+    let job_id = whisper.job_id.get_or_insert_with(|| uuid::Uuid::new_v4().to_string()).clone();
+    // English from any audio is Whisper's translate task; other languages are transcribed as-is
+    if language == "en" {
+        whisper.task = Some("translate".to_string());
+    } else {
+        whisper.task = Some("transcribe".to_string());
+        whisper.language = Some(language.clone());
+    }
+
+    emit_stage(&app, &video_path, "whisper", "Generating subtitles with Whisper...", 20.0, Some(job_id.clone()));
+    let result = crate::ai::whisper::run_whisper(app.clone(), jobs, video_path.clone(), Some(whisper)).await?;
+    emit_stage(&app, &video_path, "done", "Generated subtitles", 100.0, Some(job_id));
+    Ok(found("whisper", Some(result.path), None))
+}
@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use tauri::{command, AppHandle};
+
+use crate::config::{load_config, save_config, SyncSettings};
+use crate::playlist::PlaylistSyncData;
+use crate::util::now_secs;
+use crate::watch_history::{all_records, merge_records, HistoryRecord};
+
+const SYNC_FILE_NAME: &str = "framex-sync.json";
+const SYNC_FORMAT_VERSION: u32 = 1;
+// Attempts when another device uploads between our read and write
+const SYNC_ATTEMPTS: usize = 3;
+
+/// Everything devices share, kept as one JSON file on the sync target.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct SyncSnapshot {
+    version: u32,
+    updated: u64,
+    history: Vec<HistoryRecord>,
+    playlists: PlaylistSyncData,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SyncSummary {
+    pub backend: String,
+    pub history_received: usize,
+    pub history_sent: usize,
+    pub playlists: usize,
+    pub collections: usize,
+    pub synced_at: u64,
+}
+
+fn webdav_file_url(settings: &SyncSettings) -> String {
+    format!("{}/{}", settings.webdav_url, SYNC_FILE_NAME)
+}
+
+fn webdav_request(settings: &SyncSettings, method: reqwest::Method, url: &str) -> reqwest::RequestBuilder {
+    let request = crate::metadata::http_client().request(method, url);
+    match &settings.username {
+        Some(user) => request.basic_auth(user, settings.password.as_ref()),
+        None => request,
+    }
+}
+
+/// Which version of the sync file was read, so the upload only replaces that exact one.
+struct RemoteVersion {
+    exists: bool,
+    etag: Option<String>, // WebDAV only
+}
+
+enum WriteOutcome {
+    Written,
+    // Someone else uploaded since it was read (412 Precondition Failed)
+    Conflict,
+}
+
+/// The snapshot currently on the sync target (None before the first sync) and its version.
+async fn read_remote(settings: &SyncSettings) -> Result<(Option<SyncSnapshot>, RemoteVersion), String> {
+    let missing = RemoteVersion { exists: false, etag: None };
+    let (content, etag) = match settings.backend.as_str() {
+        "webdav" => {
+            let resp = webdav_request(settings, reqwest::Method::GET, &webdav_file_url(settings))
+                .send()
+                .await
+                .map_err(|e| format!("WebDAV request failed: {}", e))?;
+            if resp.status() == reqwest::StatusCode::NOT_FOUND {
+                return Ok((None, missing));
+            }
+            if !resp.status().is_success() {
+                return Err(format!("WebDAV Error: {}", resp.status()));
+            }
+            let etag = resp.headers().get(reqwest::header::ETAG).and_then(|v| v.to_str().ok()).map(|s| s.to_string());
+            (resp.text().await.map_err(|e| e.to_string())?, etag)
+        }
+        "file" => {
+            if !Path::new(&settings.file_path).exists() {
+                return Ok((None, missing));
+            }
+            (fs::read_to_string(&settings.file_path).map_err(|e| e.to_string())?, None)
+        }
+        _ => return Err("Sync is not configured".to_string()),
+    };
+    let snapshot = serde_json::from_str(&content).map_err(|e| format!("Sync file is not valid: {}", e))?;
+    Ok((Some(snapshot), RemoteVersion { exists: true, etag }))
+}
+
+async fn write_remote(settings: &SyncSettings, snapshot: &SyncSnapshot, version: &RemoteVersion) -> Result<WriteOutcome, String> {
+    let json = serde_json::to_string(snapshot).map_err(|e| e.to_string())?;
+    match settings.backend.as_str() {
+        "webdav" => {
+            let url = webdav_file_url(settings);
+            let put = || {
+                let request = webdav_request(settings, reqwest::Method::PUT, &url).body(json.clone());
+                // Only replace the version merged above; create only if there was none
+                let request = match (&version.etag, version.exists) {
+                    (Some(etag), _) => request.header(reqwest::header::IF_MATCH, etag.as_str()),
+                    (None, false) => request.header(reqwest::header::IF_NONE_MATCH, "*"),
+                    (None, true) => request,
+                };
+                request.send()
+            };
+            let mut resp = put().await.map_err(|e| format!("WebDAV request failed: {}", e))?;
+            // 409 Conflict: the folder doesn't exist yet
+            if resp.status() == reqwest::StatusCode::CONFLICT {
+                let mkcol = reqwest::Method::from_bytes(b"MKCOL").map_err(|e| e.to_string())?;
+                webdav_request(settings, mkcol, &settings.webdav_url)
+                    .send()
+                    .await
+                    .map_err(|e| format!("WebDAV request failed: {}", e))?;
+                resp = put().await.map_err(|e| format!("WebDAV request failed: {}", e))?;
+            }
+            if resp.status() == reqwest::StatusCode::PRECONDITION_FAILED {
+                return Ok(WriteOutcome::Conflict);
+            }
+            if !resp.status().is_success() {
+                return Err(format!("WebDAV Error: {}", resp.status()));
+            }
+            Ok(WriteOutcome::Written)
+        }
+        "file" => {
+            let path = Path::new(&settings.file_path);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            // Write then rename so another machine never reads a half-written file
+            let tmp = path.with_extension("tmp");
+            fs::write(&tmp, json).map_err(|e| e.to_string())?;
+            fs::rename(&tmp, path).map_err(|e| e.to_string())?;
+            Ok(WriteOutcome::Written)
+        }
+        _ => Err("Sync is not configured".to_string()),
+    }
+}
+
+/// Pull the shared snapshot, merge it into local history and playlists (newest change wins),
+/// then upload the merged state.
+#[command]
+pub async fn sync_now(app: AppHandle) -> Result<SyncSummary, String> {
+    // Sync reads and rewrites the whole history
+    crate::parental::check_area(&app, "history")?;
+    let settings = load_config(&app).sync;
+    if settings.backend == "none" {
+        return Err("Sync is not configured".to_string());
+    }
+
+    let mut history_received = 0;
+    let mut attempt = 0;
+    let (snapshot, now) = loop {
+        attempt += 1;
+        let (remote, version) = read_remote(&settings).await?;
+        let remote = remote.unwrap_or_default();
+        if remote.version > SYNC_FORMAT_VERSION {
+            return Err("The sync file was written by a newer version of FrameX".to_string());
+        }
+        history_received += merge_records(&app, &remote.history)?;
+        let playlists = crate::playlist::merge_sync_data(&app, remote.playlists)?;
+
+        let now = now_secs();
+        let snapshot = SyncSnapshot {
+            version: SYNC_FORMAT_VERSION,
+            updated: now,
+            history: all_records(&app)?,
+            playlists,
+        };
+        match write_remote(&settings, &snapshot, &version).await? {
+            WriteOutcome::Written => break (snapshot, now),
+            WriteOutcome::Conflict if attempt < SYNC_ATTEMPTS => {
+                tracing::info!("🔄 The sync file changed while merging, retrying");
+            }
+            WriteOutcome::Conflict => return Err("Another device kept updating the sync file; try again".to_string()),
+        }
+    };
+
+    let mut config = load_config(&app);
+    config.sync.last_sync = Some(now);
+    save_config(&app, &config)?;
+
+    let summary = SyncSummary {
+        backend: settings.backend,
+        history_received,
+        history_sent: snapshot.history.len(),
+        playlists: snapshot.playlists.playlists.len(),
+        collections: snapshot.playlists.collections.len(),
+        synced_at: now,
+    };
+    tracing::info!("🔄 Synced via {}: {} history entries in, {} out", summary.backend, summary.history_received, summary.history_sent);
+    Ok(summary)
+}
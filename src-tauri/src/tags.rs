@@ -0,0 +1,231 @@
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::{command, AppHandle};
+
+use crate::playlist::SmartRules;
+use crate::util::now_secs;
+use crate::watch_history::with_db;
+
+const MAX_STARS: u8 = 5;
+
+/// The user's labels on one file.
+#[derive(Debug, Serialize, Default, Clone)]
+pub struct VideoTags {
+    pub tags: Vec<String>,
+    pub favorite: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: u32,
+}
+
+/// Trimmed, non-empty tags. Case is kept as typed but matching ignores it.
+fn clean_tags(tags: Vec<String>) -> Vec<String> {
+    tags.into_iter()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// Add every tag to every path; tags a file already has are left alone.
+#[command]
+pub fn add_tags(app: AppHandle, paths: Vec<String>, tags: Vec<String>) -> Result<usize, String> {
+    let tags = clean_tags(tags);
+    with_db(&app, |conn| {
+        let tx = conn.transaction()?;
+        let mut added = 0;
+        {
+            let mut stmt = tx.prepare("INSERT OR IGNORE INTO tags (path, tag) VALUES (?1, ?2)")?;
+            for path in &paths {
+                for tag in &tags {
+                    added += stmt.execute(params![path, tag])?;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(added)
+    })
+}
+
+#[command]
+pub fn remove_tags(app: AppHandle, paths: Vec<String>, tags: Vec<String>) -> Result<usize, String> {
+    let tags = clean_tags(tags);
+    with_db(&app, |conn| {
+        let tx = conn.transaction()?;
+        let mut removed = 0;
+        {
+            let mut stmt = tx.prepare("DELETE FROM tags WHERE path = ?1 AND tag = ?2")?;
+            for path in &paths {
+                for tag in &tags {
+                    removed += stmt.execute(params![path, tag])?;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(removed)
+    })
+}
+
+#[command]
+pub fn set_favorite(app: AppHandle, path: String, favorite: bool) -> Result<(), String> {
+    with_db(&app, |conn| {
+        if favorite {
+            conn.execute(
+                "INSERT OR IGNORE INTO favorites (path, added) VALUES (?1, ?2)",
+                params![path, now_secs() as i64],
+            )?;
+        } else {
+            conn.execute("DELETE FROM favorites WHERE path = ?1", [&path])?;
+        }
+        Ok(())
+    })
+}
+
+/// Tags and favorite flag for each path; untagged files get an empty entry.
+#[command]
+pub fn get_tags(app: AppHandle, paths: Vec<String>) -> Result<HashMap<String, VideoTags>, String> {
+    with_db(&app, |conn| {
+        let mut tag_stmt = conn.prepare("SELECT tag FROM tags WHERE path = ?1 ORDER BY tag")?;
+        let mut fav_stmt = conn.prepare("SELECT 1 FROM favorites WHERE path = ?1")?;
+        let mut result = HashMap::new();
+        for path in paths {
+            let tags = tag_stmt.query_map([&path], |r| r.get(0))?.collect::<rusqlite::Result<_>>()?;
+            let favorite = fav_stmt.exists([&path])?;
+            result.insert(path, VideoTags { tags, favorite });
+        }
+        Ok(result)
+    })
+}
+
+/// Every tag in use with how many files carry it, most used first.
+#[command]
+pub fn list_tags(app: AppHandle) -> Result<Vec<TagCount>, String> {
+    with_db(&app, |conn| {
+        let mut stmt = conn.prepare("SELECT tag, COUNT(*) FROM tags GROUP BY tag ORDER BY COUNT(*) DESC, tag")?;
+        let tags = stmt
+            .query_map([], |r| Ok(TagCount { tag: r.get(0)?, count: r.get(1)? }))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(tags)
+    })
+}
+
+#[command]
+pub fn get_videos_by_tag(app: AppHandle, tag: String) -> Result<Vec<String>, String> {
+    let guard = crate::parental::Guard::new(&app);
+    let mut paths: Vec<String> = with_db(&app, |conn| {
+        let mut stmt = conn.prepare("SELECT path FROM tags WHERE tag = ?1 ORDER BY path")?;
+        let paths = stmt.query_map([tag.trim()], |r| r.get(0))?.collect::<rusqlite::Result<_>>()?;
+        Ok(paths)
+    })?;
+    paths.retain(|p| guard.visible(p));
+    Ok(paths)
+}
+
+/// Favorites, most recently added first.
+#[command]
+pub fn get_favorites(app: AppHandle) -> Result<Vec<String>, String> {
+    let guard = crate::parental::Guard::new(&app);
+    let mut paths: Vec<String> = with_db(&app, |conn| {
+        let mut stmt = conn.prepare("SELECT path FROM favorites ORDER BY added DESC")?;
+        let paths = stmt.query_map([], |r| r.get(0))?.collect::<rusqlite::Result<_>>()?;
+        Ok(paths)
+    })?;
+    paths.retain(|p| guard.visible(p));
+    Ok(paths)
+}
+
+/// Rate a file from 1 to 5 stars; 0 removes the rating.
+#[command]
+pub fn set_rating(app: AppHandle, path: String, stars: u8) -> Result<(), String> {
+    if stars > MAX_STARS {
+        return Err(format!("Ratings go from 0 to {} stars", MAX_STARS));
+    }
+    with_db(&app, |conn| {
+        if stars == 0 {
+            conn.execute("DELETE FROM ratings WHERE path = ?1", [&path])?;
+        } else {
+            conn.execute(
+                "INSERT INTO ratings (path, stars, rated) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(path) DO UPDATE SET stars = excluded.stars, rated = excluded.rated",
+                params![path, stars, now_secs() as i64],
+            )?;
+        }
+        Ok(())
+    })
+}
+
+/// Star ratings for `paths`, or every rated file when no paths are given. Unrated files are left out.
+#[command]
+pub fn get_ratings(app: AppHandle, paths: Option<Vec<String>>) -> Result<HashMap<String, u8>, String> {
+    match paths {
+        Some(paths) => ratings_for(&app, &paths),
+        None => with_db(&app, |conn| {
+            let mut stmt = conn.prepare("SELECT path, stars FROM ratings")?;
+            let ratings = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?.collect::<rusqlite::Result<_>>()?;
+            Ok(ratings)
+        }),
+    }
+}
+
+/// Ratings of the given files, for merging into library listings.
+pub fn ratings_for(app: &AppHandle, paths: &[String]) -> Result<HashMap<String, u8>, String> {
+    with_db(app, |conn| {
+        let mut stmt = conn.prepare("SELECT stars FROM ratings WHERE path = ?1")?;
+        let mut ratings = HashMap::new();
+        for path in paths {
+            if let Some(stars) = stmt.query_row([path], |r| r.get(0)).optional()? {
+                ratings.insert(path.clone(), stars);
+            }
+        }
+        Ok(ratings)
+    })
+}
+
+/// Files matching a smart playlist's rules, in the order the rules ask for (path by default).
+pub fn smart_playlist_items(app: &AppHandle, rules: &SmartRules) -> Result<Vec<String>, String> {
+    let mut clauses = Vec::new();
+    let mut args: Vec<rusqlite::types::Value> = Vec::new();
+    for tag in &rules.tags {
+        args.push(tag.trim().to_string().into());
+        clauses.push(format!("path IN (SELECT path FROM tags WHERE tag = ?{})", args.len()));
+    }
+    if !rules.any_tags.is_empty() {
+        let first = args.len() + 1;
+        args.extend(rules.any_tags.iter().map(|t| t.trim().to_string().into()));
+        let slots: Vec<String> = (first..=args.len()).map(|i| format!("?{}", i)).collect();
+        clauses.push(format!("path IN (SELECT path FROM tags WHERE tag IN ({}))", slots.join(", ")));
+    }
+    match rules.favorite {
+        None => {}
+        Some(true) => clauses.push("path IN (SELECT path FROM favorites)".to_string()),
+        Some(false) => clauses.push("path NOT IN (SELECT path FROM favorites)".to_string()),
+    }
+    if let Some(min) = rules.min_rating.filter(|&m| m > 0) {
+        clauses.push(format!("path IN (SELECT path FROM ratings WHERE stars >= {})", min));
+    }
+    if clauses.is_empty() {
+        return Ok(Vec::new());
+    }
+    let order = match rules.sort.as_deref() {
+        None | Some("path") => "path COLLATE NOCASE",
+        Some("rating") => "(SELECT stars FROM ratings r WHERE r.path = known.path) DESC NULLS LAST, path COLLATE NOCASE",
+        Some(other) => return Err(format!("Unknown smart playlist sort '{}'", other)),
+    };
+
+    // Any file the library knows about: tagged, favorited, rated or in the watch history
+    let sql = format!(
+        "SELECT path FROM (SELECT path FROM tags UNION SELECT path FROM favorites
+                           UNION SELECT path FROM ratings UNION SELECT path FROM videos) AS known
+         WHERE {} ORDER BY {}",
+        clauses.join(" AND "),
+        order
+    );
+    with_db(app, |conn| {
+        let mut stmt = conn.prepare(&sql)?;
+        let paths = stmt.query_map(rusqlite::params_from_iter(&args), |r| r.get(0))?.collect::<rusqlite::Result<_>>()?;
+        Ok(paths)
+    })
+}
@@ -0,0 +1,387 @@
+use librqbit::api::TorrentIdOrHash;
+use librqbit::limits::LimitsConfig;
+use librqbit::{AddTorrent, AddTorrentOptions, ManagedTorrent, Session, SessionOptions};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{command, AppHandle, Emitter, Manager, State};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufReader};
+
+use crate::config::{load_config, TorrentSettings};
+use crate::file_scanner::is_video;
+
+// Magnet links and .torrent files streamed while they download. librqbit fetches the
+// pieces under the reader first, so a local HTTP endpoint that serves a file's stream
+// (seeks included) is all mpv needs:
+//   http://127.0.0.1:<port>/torrent/<info hash>/<file index>/<name>
+
+const ADD_TIMEOUT: Duration = Duration::from_secs(120); // magnet metadata can take a while to find
+
+pub struct TorrentState {
+    session: tokio::sync::OnceCell<Arc<Session>>,
+    torrents: Mutex<HashMap<String, Arc<ManagedTorrent>>>, // info hash -> torrent
+    port: OnceLock<u16>,
+}
+
+impl TorrentState {
+    pub fn new() -> Self {
+        Self { session: tokio::sync::OnceCell::new(), torrents: Mutex::new(HashMap::new()), port: OnceLock::new() }
+    }
+
+    /// The torrent session, started (with the stream server and progress events) on first use.
+    async fn session(&self, app: &AppHandle) -> Result<Arc<Session>, String> {
+        self.session
+            .get_or_try_init(|| async {
+                let settings = load_config(app).torrents;
+                let dir = cache_dir(app, &settings)?;
+                std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+                let session = Session::new_with_opts(
+                    dir.clone(),
+                    SessionOptions {
+                        disable_dht_persistence: true,
+                        listen_port_range: Some(6881..6891),
+                        ratelimits: limits(&settings),
+                        ..Default::default()
+                    },
+                )
+                .await
+                .map_err(|e| format!("Failed to start the torrent engine: {:#}", e))?;
+
+                let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.map_err(|e| e.to_string())?;
+                let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+                let _ = self.port.set(port);
+                let server_app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    while let Ok((stream, _)) = listener.accept().await {
+                        let app = server_app.clone();
+                        tauri::async_runtime::spawn(async move {
+                            if let Err(e) = serve(&app, stream).await {
+                                tracing::error!("Torrent stream request failed: {}", e);
+                            }
+                        });
+                    }
+                });
+                tauri::async_runtime::spawn(report_progress(app.clone()));
+
+                tracing::info!("🧲 Torrent engine started (cache: {}, stream port {})", dir.display(), port);
+                Ok(session)
+            })
+            .await
+            .cloned()
+    }
+
+    fn get(&self, id: &str) -> Option<Arc<ManagedTorrent>> {
+        self.torrents.lock().ok()?.get(id).cloned()
+    }
+}
+
+/// Where downloads go. A user-chosen folder gets a FrameX subfolder, since eviction deletes
+/// whatever is in the cache and that folder may hold other things.
+fn cache_dir(app: &AppHandle, settings: &TorrentSettings) -> Result<PathBuf, String> {
+    match &settings.cache_dir {
+        Some(dir) => Ok(PathBuf::from(dir).join("FrameX Torrents")),
+        None => Ok(app.path().app_data_dir().map_err(|e| e.to_string())?.join("torrents")),
+    }
+}
+
+fn limits(settings: &TorrentSettings) -> LimitsConfig {
+    LimitsConfig {
+        download_bps: NonZeroU32::new(settings.download_limit_kbps.saturating_mul(1024)),
+        upload_bps: NonZeroU32::new(settings.upload_limit_kbps.saturating_mul(1024)),
+    }
+}
+
+/// Push new rate limits into a running session.
+pub fn apply_limits(app: &AppHandle, settings: &TorrentSettings) {
+    if let Some(session) = app.state::<TorrentState>().session.get() {
+        let limits = limits(settings);
+        session.ratelimits.set_download_bps(limits.download_bps);
+        session.ratelimits.set_upload_bps(limits.upload_bps);
+    }
+}
+
+/// Bytes under `path`; symlinks count as themselves and aren't followed.
+fn dir_size(path: &Path) -> u64 {
+    match std::fs::symlink_metadata(path) {
+        Ok(m) if m.is_dir() => std::fs::read_dir(path)
+            .map(|entries| entries.flatten().map(|e| dir_size(&e.path())).sum())
+            .unwrap_or(0),
+        Ok(m) => m.len(),
+        Err(_) => 0,
+    }
+}
+
+/// Delete the oldest cached downloads until the cache fits `max_cache_gb`, sparing `keep`
+/// (the top-level names of torrents still open).
+fn evict_cache(dir: &Path, settings: &TorrentSettings, keep: &HashSet<String>) {
+    if settings.max_cache_gb <= 0.0 {
+        return;
+    }
+    let max_bytes = (settings.max_cache_gb * 1024.0 * 1024.0 * 1024.0) as u64;
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut cached: Vec<(PathBuf, u64, std::time::SystemTime)> = entries
+        .flatten()
+        .map(|e| {
+            let modified = e.metadata().and_then(|m| m.modified()).unwrap_or(std::time::UNIX_EPOCH);
+            (e.path(), dir_size(&e.path()), modified)
+        })
+        .collect();
+    let mut total: u64 = cached.iter().map(|(_, size, _)| size).sum();
+    cached.sort_by_key(|(_, _, modified)| *modified);
+    for (path, size, _) in cached {
+        if total <= max_bytes {
+            break;
+        }
+        if path.file_name().is_some_and(|n| keep.contains(&*n.to_string_lossy())) {
+            continue;
+        }
+        let is_dir = std::fs::symlink_metadata(&path).is_ok_and(|m| m.is_dir());
+        let removed = if is_dir { std::fs::remove_dir_all(&path) } else { std::fs::remove_file(&path) };
+        if removed.is_ok() {
+            tracing::info!("🧹 Evicted cached torrent {}", path.display());
+            total = total.saturating_sub(size);
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+pub struct TorrentFile {
+    pub index: usize,
+    pub name: String,
+    pub size: u64,
+}
+
+#[derive(Serialize)]
+pub struct TorrentInfo {
+    pub id: String, // info hash
+    pub name: String,
+    pub files: Vec<TorrentFile>,
+    pub file_index: usize, // the file being streamed
+    pub stream_url: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct TorrentProgress {
+    pub id: String,
+    pub name: String,
+    pub state: String, // "initializing" | "live" | "paused" | "error"
+    pub progress_bytes: u64,
+    pub total_bytes: u64,
+    pub download_speed: f64, // MiB/s
+    pub upload_speed: f64,
+    pub peers: usize,
+    pub finished: bool,
+    pub error: Option<String>,
+}
+
+fn progress(id: &str, torrent: &ManagedTorrent) -> TorrentProgress {
+    let stats = torrent.stats();
+    let live = stats.live.as_ref();
+    TorrentProgress {
+        id: id.to_string(),
+        name: torrent.name().unwrap_or_default(),
+        state: stats.state.to_string(),
+        progress_bytes: stats.progress_bytes,
+        total_bytes: stats.total_bytes,
+        download_speed: live.map_or(0.0, |l| l.download_speed.mbps),
+        upload_speed: live.map_or(0.0, |l| l.upload_speed.mbps),
+        peers: live.map_or(0, |l| l.snapshot.peer_stats.live),
+        finished: stats.finished,
+        error: stats.error,
+    }
+}
+
+/// Emits "torrent-progress" with every open torrent once a second.
+async fn report_progress(app: AppHandle) {
+    let mut interval = tokio::time::interval(Duration::from_secs(1));
+    loop {
+        interval.tick().await;
+        let torrents: Vec<(String, Arc<ManagedTorrent>)> = match app.state::<TorrentState>().torrents.lock() {
+            Ok(t) => t.iter().map(|(id, t)| (id.clone(), t.clone())).collect(),
+            Err(_) => continue,
+        };
+        if torrents.is_empty() {
+            continue;
+        }
+        let report: Vec<TorrentProgress> = torrents.iter().map(|(id, t)| progress(id, t)).collect();
+        let _ = app.emit("torrent-progress", report);
+    }
+}
+
+/// Add a magnet link, .torrent URL or local .torrent file and stream one of its files:
+/// `file_index`, or the largest video. Adding the same torrent again switches files.
+#[command]
+pub async fn torrent_add(app: AppHandle, state: State<'_, TorrentState>, source: String, file_index: Option<usize>) -> Result<TorrentInfo, String> {
+    crate::parental::check_area(&app, "torrents")?;
+    let session = state.session(&app).await?;
+    let source = source.trim().to_string();
+    // The incoming torrent's name (its cache folder), so eviction can't delete a partial download being resumed
+    let (add, incoming) = if source.starts_with("magnet:") {
+        let name = librqbit::Magnet::parse(&source).ok().and_then(|m| m.name);
+        (AddTorrent::from_url(source.clone()), name)
+    } else {
+        let bytes = if source.starts_with("http://") || source.starts_with("https://") {
+            let response = crate::metadata::http_client().get(&source).send().await.map_err(|e| format!("Couldn't fetch {}: {}", source, e))?;
+            if !response.status().is_success() {
+                return Err(format!("Couldn't fetch {}: {}", source, response.status()));
+            }
+            response.bytes().await.map_err(|e| e.to_string())?.to_vec()
+        } else {
+            std::fs::read(&source).map_err(|e| format!("Couldn't read {}: {}", source, e))?
+        };
+        let name = librqbit::torrent_from_bytes::<librqbit::ByteBuf>(&bytes)
+            .ok()
+            .and_then(|t| t.info.name.map(|n| String::from_utf8_lossy(n.as_ref()).into_owned()));
+        (AddTorrent::from_bytes(bytes), name)
+    };
+
+    let settings = load_config(&app).torrents;
+    let mut keep: HashSet<String> =
+        state.torrents.lock().map_err(|e| e.to_string())?.values().filter_map(|t| t.name()).collect();
+    let dir = cache_dir(&app, &settings)?;
+    let evict = |keep: HashSet<String>| {
+        let (dir, settings) = (dir.clone(), settings.clone());
+        tauri::async_runtime::spawn_blocking(move || evict_cache(&dir, &settings, &keep))
+    };
+    // Magnets without a display name only reveal it once added
+    let evicted = match &incoming {
+        Some(name) => {
+            keep.insert(name.clone());
+            evict(keep.clone()).await.map_err(|e| e.to_string())?;
+            true
+        }
+        None => false,
+    };
+
+    // librqbit overwrites only files it owns; needed to resume a partly cached download
+    let options = AddTorrentOptions { overwrite: true, ..Default::default() };
+    let torrent = tokio::time::timeout(ADD_TIMEOUT, session.add_torrent(add, Some(options)))
+        .await
+        .map_err(|_| "Timed out looking for the torrent's metadata; are there any seeders?".to_string())?
+        .map_err(|e| format!("Failed to add torrent: {:#}", e))?
+        .into_handle()
+        .ok_or("The torrent has no files")?;
+    if !evicted {
+        keep.extend(torrent.name());
+        evict(keep).await.map_err(|e| e.to_string())?;
+    }
+    torrent.wait_until_initialized().await.map_err(|e| format!("Torrent failed to start: {:#}", e))?;
+
+    let files: Vec<TorrentFile> = torrent
+        .with_metadata(|m| {
+            m.file_infos
+                .iter()
+                .enumerate()
+                .map(|(index, f)| TorrentFile { index, name: f.relative_filename.to_string_lossy().to_string(), size: f.len })
+                .collect()
+        })
+        .map_err(|e| e.to_string())?;
+    let largest = |videos_only: bool| files.iter().filter(|f| !videos_only || is_video(Path::new(&f.name))).max_by_key(|f| f.size).map(|f| f.index);
+    let file_index = match file_index {
+        Some(i) if i < files.len() => i,
+        Some(i) => return Err(format!("The torrent has no file #{}", i)),
+        None => largest(true).or(largest(false)).ok_or("The torrent has no files")?,
+    };
+    // Only fetch what's being watched
+    session
+        .update_only_files(&torrent, &HashSet::from([file_index]))
+        .await
+        .map_err(|e| format!("Failed to select file: {:#}", e))?;
+
+    let id = torrent.info_hash().as_string();
+    let name = torrent.name().unwrap_or_else(|| files[file_index].name.clone());
+    state.torrents.lock().map_err(|e| e.to_string())?.insert(id.clone(), torrent);
+    let port = state.port.get().copied().ok_or("The torrent stream server isn't running")?;
+    let file_name = Path::new(&files[file_index].name).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let stream_url = format!("http://127.0.0.1:{}/torrent/{}/{}/{}", port, id, file_index, urlencoding::encode(&file_name));
+    tracing::info!("🧲 Streaming '{}' from torrent {}", files[file_index].name, name);
+    Ok(TorrentInfo { id, name, files, file_index, stream_url })
+}
+
+#[command]
+pub fn torrent_list(state: State<'_, TorrentState>) -> Result<Vec<TorrentProgress>, String> {
+    let torrents = state.torrents.lock().map_err(|e| e.to_string())?;
+    Ok(torrents.iter().map(|(id, t)| progress(id, t)).collect())
+}
+
+/// Stop a torrent; its files stay cached unless `delete_on_close` is set.
+#[command]
+pub async fn torrent_remove(app: AppHandle, state: State<'_, TorrentState>, id: String) -> Result<(), String> {
+    let torrent = state.torrents.lock().map_err(|e| e.to_string())?.remove(&id).ok_or("Torrent not found")?;
+    let session = state.session(&app).await?;
+    let delete_files = load_config(&app).torrents.delete_on_close;
+    session
+        .delete(TorrentIdOrHash::Hash(torrent.info_hash()), delete_files)
+        .await
+        .map_err(|e| format!("Failed to remove torrent: {:#}", e))
+}
+
+async fn serve(app: &AppHandle, stream: tokio::net::TcpStream) -> std::io::Result<()> {
+    let (read, mut out) = stream.into_split();
+    let mut reader = BufReader::new(read);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    let mut range = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("range") {
+                range = Some(value.trim().to_string());
+            }
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let target = parts.next().unwrap_or_default();
+    if method != "GET" && method != "HEAD" {
+        return out.write_all(b"HTTP/1.1 405 Method Not Allowed\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").await;
+    }
+    let mut segments = target.strip_prefix("/torrent/").unwrap_or_default().split('/');
+    let (id, index, name) = (segments.next().unwrap_or_default(), segments.next().and_then(|i| i.parse().ok()), segments.next().unwrap_or_default());
+    let torrent = app.state::<TorrentState>().get(id);
+    let (Some(torrent), Some(index)) = (torrent, index) else {
+        return out.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").await;
+    };
+    let mut file = torrent.stream(index).map_err(std::io::Error::other)?;
+    let size = file.len();
+
+    let common = format!(
+        "Content-Type: {}\r\nAccept-Ranges: bytes\r\nConnection: close\r\n",
+        crate::media_server::content_type(Path::new(&*urlencoding::decode(name).unwrap_or_default()))
+    );
+    let (start, end) = match range.as_deref() {
+        Some(header) => match crate::media_server::parse_range(header, size) {
+            Some(r) => {
+                let head = format!("HTTP/1.1 206 Partial Content\r\n{}Content-Range: bytes {}-{}/{}\r\nContent-Length: {}\r\n\r\n", common, r.0, r.1, size, r.1 - r.0 + 1);
+                out.write_all(head.as_bytes()).await?;
+                r
+            }
+            None => {
+                let head = format!("HTTP/1.1 416 Range Not Satisfiable\r\nContent-Range: bytes */{}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", size);
+                return out.write_all(head.as_bytes()).await;
+            }
+        },
+        None => {
+            out.write_all(format!("HTTP/1.1 200 OK\r\n{}Content-Length: {}\r\n\r\n", common, size).as_bytes()).await?;
+            (0, size.saturating_sub(1))
+        }
+    };
+    if method == "HEAD" || size == 0 {
+        return Ok(());
+    }
+    // Seeking moves the stream, and librqbit reprioritizes pieces from there
+    file.seek(std::io::SeekFrom::Start(start)).await?;
+    match tokio::io::copy(&mut file.take(end - start + 1), &mut out).await {
+        Err(e) if matches!(e.kind(), std::io::ErrorKind::BrokenPipe | std::io::ErrorKind::ConnectionReset) => Ok(()),
+        other => other.map(|_| ()),
+    }
+}
@@ -0,0 +1,56 @@
+use std::sync::OnceLock;
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::TrayIconBuilder;
+use tauri::{App, AppHandle, Manager, Wry};
+
+use crate::window_mode::WindowMode;
+
+// The check items, kept so they follow mode changes made from the UI or hotkeys
+static MODE_ITEMS: OnceLock<(CheckMenuItem<Wry>, CheckMenuItem<Wry>)> = OnceLock::new();
+
+pub(crate) fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+pub(crate) fn sync_window_mode(mode: &WindowMode) {
+    if let Some((on_top, mini)) = MODE_ITEMS.get() {
+        let _ = on_top.set_checked(mode.always_on_top);
+        let _ = mini.set_checked(mode.mini_player);
+    }
+}
+
+/// Create the system tray icon with Show / window mode / Quit entries.
+pub fn setup_tray(app: &App) -> tauri::Result<()> {
+    let show = MenuItem::with_id(app, "show", "Show FrameX", true, None::<&str>)?;
+    let on_top = CheckMenuItem::with_id(app, "always_on_top", "Always on top", true, false, None::<&str>)?;
+    let mini = CheckMenuItem::with_id(app, "mini_player", "Mini player", true, false, None::<&str>)?;
+    let separator = PredefinedMenuItem::separator(app)?;
+    let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+    let menu = Menu::with_items(app, &[&show, &on_top, &mini, &separator, &quit])?;
+    let _ = MODE_ITEMS.set((on_top, mini));
+
+    let mut builder = TrayIconBuilder::with_id("main")
+        .tooltip("FrameX")
+        .menu(&menu)
+        .on_menu_event(|app, event| match event.id.as_ref() {
+            "show" => show_main_window(app),
+            "always_on_top" => {
+                let _ = crate::window_mode::toggle_always_on_top(app.clone());
+            }
+            "mini_player" => {
+                show_main_window(app);
+                let _ = crate::window_mode::toggle_mini_player(app.clone());
+            }
+            "quit" => app.exit(0),
+            _ => {}
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    Ok(())
+}
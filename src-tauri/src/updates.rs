@@ -0,0 +1,169 @@
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{command, AppHandle, Emitter};
+
+use crate::config::{load_config, save_config};
+use crate::util::now_secs;
+
+const RELEASES_URL: &str = "https://api.github.com/repos/Deveshsamant/FrameX---Media-Player/releases?per_page=20";
+const CHECK_INTERVAL: Duration = Duration::from_secs(3600);
+
+#[derive(Serialize, Clone, Debug)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current: String,
+    pub name: String,
+    pub notes: String, // release body, markdown
+    pub url: String,   // release page
+    pub published_at: Option<String>,
+    pub prerelease: bool,
+}
+
+#[derive(Deserialize, Debug)]
+struct Release {
+    tag_name: String,
+    name: Option<String>,
+    body: Option<String>,
+    html_url: String,
+    published_at: Option<String>,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    draft: bool,
+}
+
+/// "v1.2.3" and "1.2.3" alike; tags that aren't semver are ignored.
+fn parse_version(tag: &str) -> Option<Version> {
+    Version::parse(tag.trim().trim_start_matches(['v', 'V'])).ok()
+}
+
+async fn newest_release(include_prereleases: bool) -> Result<Option<(Version, Release)>, String> {
+    let releases: Vec<Release> = crate::metadata::http_client()
+        .get(RELEASES_URL)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach GitHub: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("GitHub releases unavailable: {}", e))?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(releases
+        .into_iter()
+        .filter(|r| !r.draft && (include_prereleases || !r.prerelease))
+        .filter_map(|r| parse_version(&r.tag_name).map(|v| (v, r)))
+        .filter(|(v, _)| include_prereleases || v.pre.is_empty())
+        .max_by(|(a, _), (b, _)| a.cmp(b)))
+}
+
+/// Look for a release newer than the running build. `respect_skip` hides the version the
+/// user chose to skip, as the background check does.
+async fn check(app: &AppHandle, respect_skip: bool) -> Result<Option<UpdateInfo>, String> {
+    let settings = load_config(app).updates;
+    let current = app.package_info().version.clone();
+    let newest = newest_release(settings.include_prereleases).await?;
+
+    let mut config = load_config(app);
+    config.updates.last_check = now_secs();
+    save_config(app, &config)?;
+
+    let Some((version, release)) = newest.filter(|(v, _)| *v > current) else {
+        return Ok(None);
+    };
+    if respect_skip && settings.skipped_version.as_deref().and_then(parse_version).as_ref() == Some(&version) {
+        return Ok(None);
+    }
+    Ok(Some(UpdateInfo {
+        version: version.to_string(),
+        current: current.to_string(),
+        name: release.name.filter(|n| !n.trim().is_empty()).unwrap_or_else(|| release.tag_name.clone()),
+        notes: release.body.unwrap_or_default(),
+        url: release.html_url,
+        published_at: release.published_at,
+        prerelease: release.prerelease,
+    }))
+}
+
+/// Background check every `check_hours`; emits `update-available` with the `UpdateInfo`.
+pub fn start_check_loop(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        // Let startup finish first
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        loop {
+            let settings = load_config(&app).updates;
+            let due = settings.check_hours > 0 && now_secs().saturating_sub(settings.last_check) >= settings.check_hours * 3600;
+            if due {
+                match check(&app, true).await {
+                    Ok(Some(info)) => {
+                        tracing::info!("⬆️ FrameX {} is available (running {})", info.version, info.current);
+                        let _ = app.emit("update-available", info);
+                    }
+                    Ok(None) => tracing::debug!("No update available"),
+                    Err(e) => tracing::warn!("Update check failed: {}", e),
+                }
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// Check right away, including a version that was skipped.
+#[command]
+pub async fn check_for_updates(app: AppHandle) -> Result<Option<UpdateInfo>, String> {
+    check(&app, false).await
+}
+
+/// Stop announcing `version`; a later release is announced as usual.
+#[command]
+pub fn skip_update(app: AppHandle, version: String) -> Result<(), String> {
+    let mut config = load_config(&app);
+    config.updates.skipped_version = Some(version);
+    save_config(&app, &config)
+}
+
+/// Whether `install_update` works in this build (the `updater` feature).
+#[command]
+pub fn can_install_updates() -> bool {
+    cfg!(feature = "updater")
+}
+
+/// Download and install the update through Tauri's updater, then restart. Emits
+/// `update-progress` with (downloaded, total) bytes.
+#[command]
+pub async fn install_update(app: AppHandle) -> Result<(), String> {
+    crate::parental::check_area(&app, "settings")?;
+    #[cfg(feature = "updater")]
+    {
+        use tauri_plugin_updater::UpdaterExt;
+
+        let update = app
+            .updater()
+            .map_err(|e| e.to_string())?
+            .check()
+            .await
+            .map_err(|e| format!("Update check failed: {}", e))?
+            .ok_or("No update available")?;
+        tracing::info!("⬆️ Installing FrameX {}", update.version);
+        let mut downloaded = 0u64;
+        let progress = app.clone();
+        update
+            .download_and_install(
+                move |chunk, total| {
+                    downloaded += chunk as u64;
+                    let _ = progress.emit("update-progress", (downloaded, total));
+                },
+                || {},
+            )
+            .await
+            .map_err(|e| format!("Update failed: {}", e))?;
+        app.restart();
+    }
+    #[cfg(not(feature = "updater"))]
+    {
+        let _ = app;
+        Err("This build can't install updates itself; download the new version from the release page".to_string())
+    }
+}
@@ -0,0 +1,29 @@
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Seconds since the Unix epoch (0 if the clock is set before it).
+pub fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+/// Stable file-name key for a path. SHA-256 rather than std's hasher, whose output may
+/// change between Rust releases and would orphan every cache file named after it.
+pub fn hash_path(path: &str) -> String {
+    hex::encode(&Sha256::digest(path.as_bytes())[..16])
+}
+
+/// `dir/<hash_path(path)>.<ext>`, first renaming a file left under the old DefaultHasher name.
+pub fn hashed_file(dir: &Path, path: &str, ext: &str) -> PathBuf {
+    let file = dir.join(format!("{}.{}", hash_path(path), ext));
+    if !file.exists() {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        path.hash(&mut hasher);
+        let legacy = dir.join(format!("{:x}.{}", hasher.finish(), ext));
+        if legacy.exists() {
+            let _ = std::fs::rename(&legacy, &file);
+        }
+    }
+    file
+}
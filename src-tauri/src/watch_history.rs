@@ -1,53 +1,376 @@
+use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use tauri::AppHandle;
 use tauri::Manager;
 
+use crate::util::now_secs;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct WatchEntry {
     pub path: String,
     pub position: f64,
     pub duration: f64,
     pub last_watched: u64,
+    pub show: Option<String>,
+    pub play_count: u32,
+    pub finished: bool, // played past 95%, so there is no resume point
+}
+
+/// One `videos` row, as written by history export and merged in by import.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct HistoryRecord {
+    pub path: String,
+    pub show: Option<String>,
+    pub position: f64,
+    pub duration: f64,
+    pub last_watched: u64,
+    pub resumable: bool, // `position` is a resume point
+    pub play_count: u32,
+    pub times_completed: u32,
+    pub last_completed: Option<u64>,
+    pub modified: u64, // last local change to the row; 0 in files from before it was tracked
+}
+
+#[derive(Debug, Serialize)]
+pub struct WatchStatsBucket {
+    pub label: String, // "YYYY-MM-DD" (for weeks, the Monday)
+    pub seconds: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WatchStatsTotal {
+    pub name: String,
+    pub seconds: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WatchStats {
+    pub range: String,
+    pub total_seconds: f64,
+    pub total_hours: f64,
+    pub videos_watched: usize,
+    /// Playbacks finished within the range; marking a file watched doesn't count
+    pub completed: u32,
+    pub per_day: Vec<WatchStatsBucket>,
+    pub per_week: Vec<WatchStatsBucket>,
+    pub top_shows: Vec<WatchStatsTotal>,
+    pub top_genres: Vec<WatchStatsTotal>,
+}
+
+// Shape of the old `watch_history.json`, only read to import it once
+#[derive(Deserialize, Default)]
+struct LegacyHistory {
+    #[serde(default)]
+    entries: HashMap<String, LegacyEntry>,
+    #[serde(default)]
+    activity: Vec<LegacyActivity>,
+    #[serde(default)]
+    completed: HashMap<String, LegacyCompleted>,
+}
+
+#[derive(Deserialize)]
+struct LegacyEntry {
+    path: String,
+    position: f64,
+    duration: f64,
+    last_watched: u64,
+}
+
+#[derive(Deserialize)]
+struct LegacyActivity {
+    path: String,
+    day: u64,
+    seconds: f64,
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
-struct WatchHistoryData {
-    entries: HashMap<String, WatchEntry>,
+#[derive(Deserialize)]
+struct LegacyCompleted {
+    path: String,
+    times: u32,
+    last_completed: u64,
+}
+
+// Longest gap between position saves that still counts as continuous playback
+const MAX_SAVE_GAP_SECS: u64 = 10 * 60;
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+const TOP_STATS_LIMIT: usize = 10;
+// How often the mpv loop writes the resume point while a file is playing
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(10);
+const CONTINUE_WATCHING_LIMIT: u32 = 20;
+// Resume thumbnails snap to this many seconds so small position changes reuse the cached frame
+const THUMBNAIL_STEP_SECS: f64 = 10.0;
+
+// `videos` holds one row per file ever played or marked watched; `activity` is seconds played and playbacks
+// finished per file per day;
+// `tags`, `favorites` and `ratings` are the user's own labels and don't require the file to have been played;
+// `segments` are skip ranges (intros, credits, sponsors) marked by the user or fetched for the file;
+// `content_ratings` are age ratings from TMDB for a movie file or a show folder, used by parental controls
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS videos (
+    path TEXT PRIMARY KEY,
+    show TEXT,
+    position REAL NOT NULL DEFAULT 0,
+    duration REAL NOT NULL DEFAULT 0,
+    last_watched INTEGER NOT NULL DEFAULT 0,
+    resumable INTEGER NOT NULL DEFAULT 0,
+    play_count INTEGER NOT NULL DEFAULT 0,
+    times_completed INTEGER NOT NULL DEFAULT 0,
+    last_completed INTEGER,
+    modified INTEGER NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS videos_last_watched ON videos (last_watched);
+CREATE INDEX IF NOT EXISTS videos_show ON videos (show, last_watched);
+CREATE INDEX IF NOT EXISTS videos_last_completed ON videos (last_completed);
+CREATE TABLE IF NOT EXISTS activity (
+    path TEXT NOT NULL,
+    day INTEGER NOT NULL,
+    seconds REAL NOT NULL,
+    completions INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (path, day)
+);
+CREATE INDEX IF NOT EXISTS activity_day ON activity (day);
+CREATE TABLE IF NOT EXISTS tags (
+    path TEXT NOT NULL,
+    tag TEXT NOT NULL COLLATE NOCASE,
+    PRIMARY KEY (path, tag)
+);
+CREATE INDEX IF NOT EXISTS tags_tag ON tags (tag);
+CREATE TABLE IF NOT EXISTS favorites (
+    path TEXT PRIMARY KEY,
+    added INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS ratings (
+    path TEXT PRIMARY KEY,
+    stars INTEGER NOT NULL,
+    rated INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS segments (
+    id INTEGER PRIMARY KEY,
+    path TEXT NOT NULL,
+    start REAL NOT NULL,
+    end REAL NOT NULL,
+    kind TEXT NOT NULL,
+    source TEXT NOT NULL,
+    added INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS segments_path ON segments (path, start);
+CREATE TABLE IF NOT EXISTS segment_lookups (
+    path TEXT NOT NULL,
+    source TEXT NOT NULL,
+    checked INTEGER NOT NULL,
+    PRIMARY KEY (path, source)
+);
+CREATE TABLE IF NOT EXISTS content_ratings (
+    path TEXT PRIMARY KEY,
+    rating TEXT NOT NULL,
+    country TEXT NOT NULL
+);
+";
+
+/// Watch history in `watch_history.db` (SQLite), opened lazily on first use.
+/// An older `watch_history.json` is imported the first time and renamed out of the way.
+pub struct WatchHistoryDb {
+    conn: Mutex<Option<Connection>>,
+}
+
+impl WatchHistoryDb {
+    pub fn new() -> Self {
+        Self {
+            conn: Mutex::new(None),
+        }
+    }
+
+    fn with_conn<R>(&self, app: &AppHandle, f: impl FnOnce(&mut Connection) -> rusqlite::Result<R>) -> Result<R, String> {
+        let mut guard = self.conn.lock().unwrap();
+        if guard.is_none() {
+            *guard = Some(open_db(app)?);
+        }
+        let conn = guard.as_mut().expect("connection opened above");
+        f(conn).map_err(|e| format!("Watch history database error: {}", e))
+    }
 }
 
-fn get_history_path(app: &AppHandle) -> Option<PathBuf> {
-    app.path()
-        .app_data_dir()
-        .ok()
-        .map(|p| p.join("watch_history.json"))
+pub(crate) fn with_db<R>(app: &AppHandle, f: impl FnOnce(&mut Connection) -> rusqlite::Result<R>) -> Result<R, String> {
+    app.state::<WatchHistoryDb>().with_conn(app, f)
 }
 
-fn load_history(app: &AppHandle) -> WatchHistoryData {
-    let Some(path) = get_history_path(app) else {
-        return WatchHistoryData::default();
+fn open_db(app: &AppHandle) -> Result<Connection, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let mut conn = Connection::open(dir.join("watch_history.db")).map_err(|e| e.to_string())?;
+    // WAL keeps the frequent position saves from rewriting the whole file
+    conn.query_row("PRAGMA journal_mode = WAL", [], |_| Ok(())).map_err(|e| e.to_string())?;
+    conn.execute_batch(SCHEMA).map_err(|e| e.to_string())?;
+    add_modified_column(&conn).map_err(|e| e.to_string())?;
+    add_completions_column(&conn).map_err(|e| e.to_string())?;
+    if let Err(e) = import_legacy_json(app, &mut conn) {
+        tracing::error!("Failed to import watch_history.json: {}", e);
+    }
+    Ok(conn)
+}
+
+/// Databases from before `videos.modified` existed: add it, dated by the last watch or completion.
+fn add_modified_column(conn: &Connection) -> rusqlite::Result<()> {
+    let has_column = conn
+        .prepare("SELECT 1 FROM pragma_table_info('videos') WHERE name = 'modified'")?
+        .exists([])?;
+    if !has_column {
+        conn.execute_batch(
+            "ALTER TABLE videos ADD COLUMN modified INTEGER NOT NULL DEFAULT 0;
+             UPDATE videos SET modified = MAX(last_watched, COALESCE(last_completed, 0));",
+        )?;
+    }
+    Ok(())
+}
+
+/// Databases from before `activity.completions` existed: add it, crediting each file's completions
+/// to the day of its last one, the only date that was kept.
+fn add_completions_column(conn: &Connection) -> rusqlite::Result<()> {
+    let has_column = conn
+        .prepare("SELECT 1 FROM pragma_table_info('activity') WHERE name = 'completions'")?
+        .exists([])?;
+    if !has_column {
+        conn.execute_batch(&format!(
+            "ALTER TABLE activity ADD COLUMN completions INTEGER NOT NULL DEFAULT 0;
+             INSERT INTO activity (path, day, seconds, completions)
+                 SELECT path, last_completed / {SECS_PER_DAY}, 0, times_completed FROM videos
+                 WHERE last_completed IS NOT NULL AND times_completed > 0
+             ON CONFLICT(path, day) DO UPDATE SET completions = excluded.completions;"
+        ))?;
+    }
+    Ok(())
+}
+
+/// Move entries from the pre-SQLite JSON store into the database, then rename the file.
+fn import_legacy_json(app: &AppHandle, conn: &mut Connection) -> Result<(), String> {
+    let Some(path) = app.path().app_data_dir().ok().map(|p| p.join("watch_history.json")) else {
+        return Ok(());
     };
     if !path.exists() {
-        return WatchHistoryData::default();
+        return Ok(());
     }
-    let content = fs::read_to_string(&path).unwrap_or_default();
-    serde_json::from_str(&content).unwrap_or_default()
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let legacy: LegacyHistory = serde_json::from_str(&content).unwrap_or_default();
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    for e in legacy.entries.values() {
+        tx.execute(
+            "INSERT OR IGNORE INTO videos (path, show, position, duration, last_watched, resumable, play_count, modified)
+             VALUES (?1, ?2, ?3, ?4, ?5, 1, 1, ?5)",
+            params![e.path, show_name(&e.path), e.position, e.duration, e.last_watched],
+        ).map_err(|e| e.to_string())?;
+    }
+    for c in legacy.completed.values() {
+        tx.execute(
+            "INSERT INTO videos (path, show, last_watched, play_count, times_completed, last_completed, modified)
+             VALUES (?1, ?2, ?3, ?4, ?4, ?3, ?3)
+             ON CONFLICT(path) DO UPDATE SET times_completed = excluded.times_completed, last_completed = excluded.last_completed,
+                 modified = MAX(modified, excluded.modified)",
+            params![c.path, show_name(&c.path), c.last_completed, c.times],
+        ).map_err(|e| e.to_string())?;
+        tx.execute(
+            "INSERT INTO activity (path, day, seconds, completions) VALUES (?1, ?2, 0, ?3)
+             ON CONFLICT(path, day) DO UPDATE SET completions = completions + excluded.completions",
+            params![c.path, c.last_completed / SECS_PER_DAY, c.times],
+        ).map_err(|e| e.to_string())?;
+    }
+    for a in &legacy.activity {
+        tx.execute(
+            "INSERT INTO activity (path, day, seconds) VALUES (?1, ?2, ?3)
+             ON CONFLICT(path, day) DO UPDATE SET seconds = seconds + excluded.seconds",
+            params![a.path, a.day, a.seconds],
+        ).map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    fs::rename(&path, path.with_extension("json.migrated")).map_err(|e| e.to_string())?;
+    tracing::info!("📼 Imported {} watch history entries into SQLite", legacy.entries.len() + legacy.completed.len());
+    Ok(())
 }
 
-fn save_history(app: &AppHandle, data: &WatchHistoryData) -> Result<(), String> {
-    let path = get_history_path(app).ok_or("Failed to get history path")?;
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+/// Credit the time played since the previous save to today, ignoring seeks and long pauses.
+fn record_activity(conn: &Connection, path: &str, played: f64, elapsed: u64, now: u64) -> rusqlite::Result<()> {
+    // Allow up to 2x playback speed; anything faster is a seek
+    if played <= 0.0 || elapsed > MAX_SAVE_GAP_SECS || played > elapsed as f64 * 2.0 + 5.0 {
+        return Ok(());
     }
-    let json = serde_json::to_string_pretty(data).map_err(|e| e.to_string())?;
-    fs::write(path, json).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO activity (path, day, seconds) VALUES (?1, ?2, ?3)
+         ON CONFLICT(path, day) DO UPDATE SET seconds = seconds + excluded.seconds",
+        params![path, now / SECS_PER_DAY, played],
+    )?;
     Ok(())
 }
 
+fn entry_from_row(row: &rusqlite::Row) -> rusqlite::Result<WatchEntry> {
+    let resumable: bool = row.get("resumable")?;
+    Ok(WatchEntry {
+        path: row.get("path")?,
+        position: row.get("position")?,
+        duration: row.get("duration")?,
+        last_watched: row.get("last_watched")?,
+        show: row.get("show")?,
+        play_count: row.get("play_count")?,
+        finished: !resumable,
+    })
+}
+
+/// "YYYY-MM-DD" for a day number counted from 1970-01-01 (civil-from-days).
+fn format_day(day: u64) -> String {
+    let z = day as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + (m <= 2) as i64;
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+/// Unix time for "YYYY-MM-DD HH:MM:SS" or ISO 8601 ("...T...Z"), read as UTC.
+pub fn parse_datetime(text: &str) -> Option<u64> {
+    let re = regex::Regex::new(r"^(\d{4})-(\d{2})-(\d{2})(?:[ T](\d{2}):(\d{2}):(\d{2}))?").unwrap();
+    let caps = re.captures(text.trim())?;
+    let num = |i: usize| caps.get(i).map_or(Some(0), |m| m.as_str().parse::<i64>().ok());
+    let (y, m, d) = (num(1)?, num(2)?, num(3)?);
+    // days-from-civil, the inverse of format_day
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+    let secs = days * SECS_PER_DAY as i64 + num(4)? * 3600 + num(5)? * 60 + num(6)?;
+    u64::try_from(secs).ok()
+}
+
+/// Show name for an episode file (its show folder), or None for movies.
+fn show_name(path: &str) -> Option<String> {
+    let target = std::path::Path::new(path);
+    let name = target.file_name()?.to_str()?;
+    if crate::metadata::parse_episode(name).is_none() && !crate::anilist::is_anime_filename(name) {
+        return None;
+    }
+    crate::metadata::show_dir(target)?.file_name()?.to_str().map(|n| n.to_string())
+}
+
+fn top_totals(totals: HashMap<String, f64>) -> Vec<WatchStatsTotal> {
+    let mut list: Vec<WatchStatsTotal> = totals.into_iter().map(|(name, seconds)| WatchStatsTotal { name, seconds }).collect();
+    list.sort_by(|a, b| b.seconds.total_cmp(&a.seconds));
+    list.truncate(TOP_STATS_LIMIT);
+    list
+}
+
 /// Save the current playback position for a video.
-/// Only saves if position is > 5s and < 95% of duration (to avoid saving at the very end).
+/// Only saves if position is > 5s; past 95% of duration the video counts as finished and won't resume.
+/// Nothing is recorded in private mode.
 #[tauri::command]
 pub fn save_watch_position(
     app: AppHandle,
@@ -55,66 +378,718 @@ pub fn save_watch_position(
     position: f64,
     duration: f64,
 ) -> Result<(), String> {
-    if duration <= 0.0 {
+    if duration <= 0.0 || crate::config::load_config(&app).private_mode {
         return Ok(());
     }
 
-    let progress_percent = position / duration;
-
-    // If nearly finished (>95%), remove the entry so it doesn't resume
-    if progress_percent > 0.95 {
-        let mut data = load_history(&app);
-        data.entries.remove(&path);
-        save_history(&app, &data)?;
+    let finished = position / duration > 0.95;
+    // Only save if past the first 5 seconds
+    if !finished && position < 5.0 {
         return Ok(());
     }
 
-    // Only save if past the first 5 seconds
-    if position < 5.0 {
-        return Ok(());
+    let now = now_secs();
+    let show = show_name(&path);
+    with_db(&app, |conn| {
+        let tx = conn.transaction()?;
+        let prev: Option<(f64, u64, bool)> = tx
+            .query_row(
+                "SELECT position, last_watched, resumable FROM videos WHERE path = ?1",
+                [&path],
+                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+            )
+            .optional()?;
+        if let Some((prev_position, prev_watched, _)) = prev {
+            record_activity(&tx, &path, position - prev_position, now.saturating_sub(prev_watched), now)?;
+        }
+
+        // The first save, or one after a long break, starts a new play
+        let new_play = prev.is_none_or(|(_, last, _)| now.saturating_sub(last) > MAX_SAVE_GAP_SECS);
+        // Only the first save past the mark counts as a completion
+        let completes = finished && prev.is_some_and(|(_, _, resumable)| resumable);
+        tx.execute(
+            "INSERT INTO videos (path, show, position, duration, last_watched, resumable, play_count, modified)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, 1, ?5)
+             ON CONFLICT(path) DO UPDATE SET
+                 show = excluded.show, position = excluded.position, duration = excluded.duration,
+                 last_watched = excluded.last_watched, resumable = excluded.resumable, modified = excluded.modified,
+                 play_count = play_count + ?7,
+                 times_completed = times_completed + ?8,
+                 last_completed = CASE WHEN ?8 > 0 THEN excluded.last_watched ELSE last_completed END",
+            params![path, show, position, duration, now, !finished, new_play as u32, completes as u32],
+        )?;
+        if completes {
+            tx.execute(
+                "INSERT INTO activity (path, day, seconds, completions) VALUES (?1, ?2, 0, 1)
+                 ON CONFLICT(path, day) DO UPDATE SET completions = completions + 1",
+                params![path, now / SECS_PER_DAY],
+            )?;
+        }
+        tx.commit()
+    })
+}
+
+fn record_from_row(row: &rusqlite::Row) -> rusqlite::Result<HistoryRecord> {
+    Ok(HistoryRecord {
+        path: row.get("path")?,
+        show: row.get("show")?,
+        position: row.get("position")?,
+        duration: row.get("duration")?,
+        last_watched: row.get("last_watched")?,
+        resumable: row.get("resumable")?,
+        play_count: row.get("play_count")?,
+        times_completed: row.get("times_completed")?,
+        last_completed: row.get("last_completed")?,
+        modified: row.get("modified")?,
+    })
+}
+
+/// Every row of the history, for export.
+pub fn all_records(app: &AppHandle) -> Result<Vec<HistoryRecord>, String> {
+    with_db(app, |conn| {
+        let mut stmt = conn.prepare("SELECT * FROM videos ORDER BY last_watched DESC")?;
+        let rows = stmt.query_map([], record_from_row)?;
+        rows.collect()
+    })
+}
+
+/// History rows for the given paths; paths never played are absent.
+pub fn records_for(app: &AppHandle, paths: &[String]) -> Result<HashMap<String, HistoryRecord>, String> {
+    with_db(app, |conn| {
+        let mut stmt = conn.prepare("SELECT * FROM videos WHERE path = ?1")?;
+        let mut records = HashMap::new();
+        for path in paths {
+            if let Some(record) = stmt.query_row([path], record_from_row).optional()? {
+                records.insert(path.clone(), record);
+            }
+        }
+        Ok(records)
+    })
+}
+
+/// Merge imported records into the history, last write wins: a record replaces the local
+/// row whole when it was modified more recently, so "mark unwatched" survives a sync.
+/// Records without a `modified` time are dated by their last watch or completion.
+pub fn merge_records(app: &AppHandle, records: &[HistoryRecord]) -> Result<usize, String> {
+    with_db(app, |conn| {
+        let tx = conn.transaction()?;
+        let mut merged = 0;
+        for r in records {
+            let show = r.show.clone().or_else(|| show_name(&r.path));
+            let modified = if r.modified > 0 { r.modified } else { r.last_watched.max(r.last_completed.unwrap_or(0)) };
+            merged += tx.execute(
+                "INSERT INTO videos (path, show, position, duration, last_watched, resumable, play_count, times_completed, last_completed, modified)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                 ON CONFLICT(path) DO UPDATE SET
+                     show = COALESCE(excluded.show, show),
+                     position = excluded.position,
+                     duration = excluded.duration,
+                     last_watched = excluded.last_watched,
+                     resumable = excluded.resumable,
+                     play_count = excluded.play_count,
+                     times_completed = excluded.times_completed,
+                     last_completed = excluded.last_completed,
+                     modified = excluded.modified
+                 WHERE excluded.modified > videos.modified",
+                params![r.path, show, r.position, r.duration, r.last_watched, r.resumable, r.play_count, r.times_completed, r.last_completed, modified],
+            )?;
+        }
+        tx.commit()?;
+        Ok(merged)
+    })
+}
+
+/// Resume-point tracking driven by the mpv event loop, so a force quit loses at most a few seconds.
+pub struct PositionAutosave {
+    path: Option<String>,
+    position: f64,
+    duration: f64,
+    last_save: Instant,
+}
+
+impl PositionAutosave {
+    pub fn new() -> Self {
+        Self {
+            path: None,
+            position: 0.0,
+            duration: 0.0,
+            last_save: Instant::now(),
+        }
     }
 
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::SystemTime::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs();
-
-    let mut data = load_history(&app);
-    data.entries.insert(
-        path.clone(),
-        WatchEntry {
-            path,
-            position,
-            duration,
-            last_watched: now,
-        },
-    );
-    save_history(&app, &data)?;
-    Ok(())
+    /// A file finished loading. Streams (URLs) aren't tracked, podcast episodes are.
+    pub fn start(&mut self, app: &AppHandle, path: Option<String>) {
+        self.stop(app);
+        self.path = path.filter(|p| !p.contains("://") || p.starts_with(crate::podcasts::SCHEME));
+        self.last_save = Instant::now();
+    }
+
+    /// Latest playback position; saved every AUTOSAVE_INTERVAL.
+    pub fn progress(&mut self, app: &AppHandle, position: f64, duration: f64) {
+        self.position = position;
+        self.duration = duration;
+        if self.last_save.elapsed() >= AUTOSAVE_INTERVAL {
+            self.flush(app);
+        }
+    }
+
+    /// Save the last known position now (pause, end of file, shutdown).
+    pub fn flush(&mut self, app: &AppHandle) {
+        self.last_save = Instant::now();
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Err(e) = save_watch_position(app.clone(), path.clone(), self.position, self.duration) {
+            tracing::error!("Failed to autosave position for {}: {}", path, e);
+        }
+    }
+
+    /// Flush and forget the current file once playback ends.
+    pub fn stop(&mut self, app: &AppHandle) {
+        self.flush(app);
+        self.path = None;
+        self.position = 0.0;
+        self.duration = 0.0;
+    }
 }
 
 /// Get the saved watch position for a specific video.
 #[tauri::command]
 pub fn get_watch_position(app: AppHandle, path: String) -> Result<Option<f64>, String> {
-    let data = load_history(&app);
-    Ok(data.entries.get(&path).map(|e| e.position))
+    with_db(&app, |conn| {
+        conn.query_row(
+            "SELECT position FROM videos WHERE path = ?1 AND resumable = 1",
+            [&path],
+            |r| r.get(0),
+        )
+        .optional()
+    })
 }
 
-/// Get all watch history entries, sorted by last_watched (most recent first).
+/// Filters for `query_watch_history`; every field is optional.
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct HistoryFilter {
+    pub from: Option<u64>, // last_watched range, Unix seconds
+    pub to: Option<u64>,
+    pub path_prefix: Option<String>, // e.g. a library root
+    pub show: Option<String>,
+    pub status: Option<String>, // "in_progress" | "completed" | "unfinished"
+    pub tag: Option<String>,
+    pub favorite: Option<bool>,
+    pub offset: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HistoryPage {
+    pub entries: Vec<WatchEntry>,
+    pub total: u64, // matches before offset/limit
+}
+
+fn query_history(app: &AppHandle, filter: &HistoryFilter) -> Result<HistoryPage, String> {
+    let mut clauses = vec!["play_count > 0".to_string()];
+    let mut args: Vec<rusqlite::types::Value> = Vec::new();
+    // Every '?' in a clause binds the same (next) argument
+    let mut arg = |clause: &str, value: rusqlite::types::Value, clauses: &mut Vec<String>| {
+        args.push(value);
+        clauses.push(clause.replace('?', &format!("?{}", args.len())));
+    };
+    if let Some(from) = filter.from {
+        arg("last_watched >= ?", (from as i64).into(), &mut clauses);
+    }
+    if let Some(to) = filter.to {
+        arg("last_watched <= ?", (to as i64).into(), &mut clauses);
+    }
+    if let Some(prefix) = filter.path_prefix.as_ref().filter(|p| !p.is_empty()) {
+        // substr instead of LIKE so '%' and '_' in paths need no escaping
+        arg("substr(path, 1, length(?)) = ?", prefix.clone().into(), &mut clauses);
+    }
+    if let Some(show) = &filter.show {
+        arg("show = ? COLLATE NOCASE", show.clone().into(), &mut clauses);
+    }
+    if let Some(tag) = &filter.tag {
+        arg("path IN (SELECT path FROM tags WHERE tag = ?)", tag.clone().into(), &mut clauses);
+    }
+    match filter.favorite {
+        None => {}
+        Some(true) => clauses.push("path IN (SELECT path FROM favorites)".to_string()),
+        Some(false) => clauses.push("path NOT IN (SELECT path FROM favorites)".to_string()),
+    }
+    match filter.status.as_deref() {
+        None => {}
+        Some("in_progress") => clauses.push("resumable = 1".to_string()),
+        Some("completed") => clauses.push("times_completed > 0".to_string()),
+        Some("unfinished") => clauses.push("times_completed = 0".to_string()),
+        Some(other) => return Err(format!("Unknown history status '{}'", other)),
+    }
+    let condition = clauses.join(" AND ");
+
+    let guard = crate::parental::Guard::new(app);
+    let mut entries: Vec<WatchEntry> = with_db(app, |conn| {
+        let sql = format!("SELECT * FROM videos WHERE {} ORDER BY last_watched DESC", condition);
+        let mut stmt = conn.prepare(&sql)?;
+        let entries = stmt.query_map(rusqlite::params_from_iter(&args), entry_from_row)?.collect::<rusqlite::Result<_>>()?;
+        Ok(entries)
+    })?;
+    // Page after the parental filter so hidden rows don't leave short pages or a wrong total
+    entries.retain(|e| guard.visible(&e.path));
+    let total = entries.len() as u64;
+    let entries = entries
+        .into_iter()
+        .skip(filter.offset.unwrap_or(0) as usize)
+        .take(filter.limit.map_or(usize::MAX, |l| l as usize))
+        .collect();
+    Ok(HistoryPage { entries, total })
+}
+
+/// Every video ever played, most recent first. Pass `offset`/`limit` to page through it.
+#[tauri::command]
+pub fn get_watch_history(app: AppHandle, offset: Option<u32>, limit: Option<u32>) -> Result<Vec<WatchEntry>, String> {
+    crate::parental::check_area(&app, "history")?;
+    let filter = HistoryFilter { offset, limit, ..Default::default() };
+    Ok(query_history(&app, &filter)?.entries)
+}
+
+/// Watch history filtered by date range, library root, show, completion state, tag and favorite flag,
+/// one page at a time.
+#[tauri::command]
+pub fn query_watch_history(app: AppHandle, filters: HistoryFilter) -> Result<HistoryPage, String> {
+    crate::parental::check_area(&app, "history")?;
+    query_history(&app, &filters)
+}
+
+/// Everything a Continue Watching card shows, so the row needs a single invoke.
+#[derive(Debug, Serialize)]
+pub struct ResumeCard {
+    #[serde(flatten)]
+    pub entry: WatchEntry,
+    pub title: String,
+    pub season: Option<u32>, // 0 for absolute-numbered (anime) episodes
+    pub episode: Option<u32>,
+    pub percent: f64,
+    pub remaining_seconds: f64,
+    pub position_label: String,  // "1:02:03"
+    pub remaining_label: String, // "23 min left"
+    pub thumbnail: Option<String>, // cached JPEG of the frame at the resume point
+}
+
+/// "M:SS", or "H:MM:SS" from an hour up.
+fn format_clock(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    let (h, m, s) = (total / 3600, total / 60 % 60, total % 60);
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{}:{:02}", m, s)
+    }
+}
+
+fn format_remaining(seconds: f64) -> String {
+    let minutes = (seconds / 60.0).round() as u64;
+    match (minutes / 60, minutes % 60) {
+        (0, 0) => "<1 min left".to_string(),
+        (0, m) => format!("{} min left", m),
+        (h, 0) => format!("{} h left", h),
+        (h, m) => format!("{} h {} min left", h, m),
+    }
+}
+
+fn resume_card(entry: WatchEntry, with_thumbnail: bool) -> ResumeCard {
+    let path = std::path::Path::new(&entry.path);
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    let key = episode_key(path);
+    let remaining = (entry.duration - entry.position).max(0.0);
+    let thumbnail = if with_thumbnail && path.exists() {
+        let time = (entry.position / THUMBNAIL_STEP_SECS).floor() * THUMBNAIL_STEP_SECS;
+        crate::thumbnail_generator::thumbnail_file_at(&entry.path, &entry.path, time)
+            .ok()
+            .map(|p| p.to_string_lossy().to_string())
+    } else {
+        None
+    };
+    ResumeCard {
+        title: crate::metadata::clean_video_name(name),
+        season: key.map(|(season, _)| season),
+        episode: key.map(|(_, episode)| episode),
+        percent: if entry.duration > 0.0 { (entry.position / entry.duration * 100.0).min(100.0) } else { 0.0 },
+        remaining_seconds: remaining,
+        position_label: format_clock(entry.position),
+        remaining_label: format_remaining(remaining),
+        thumbnail,
+        entry,
+    }
+}
+
+/// In-progress videos with progress, labels and a resume-point thumbnail, most recent first.
 #[tauri::command]
-pub fn get_watch_history(app: AppHandle) -> Result<Vec<WatchEntry>, String> {
-    let data = load_history(&app);
-    let mut entries: Vec<WatchEntry> = data.entries.into_values().collect();
-    entries.sort_by(|a, b| b.last_watched.cmp(&a.last_watched));
-    // Only return the most recent 50
-    entries.truncate(50);
-    Ok(entries)
+pub async fn get_continue_watching(app: AppHandle, limit: Option<u32>, thumbnails: Option<bool>) -> Result<Vec<ResumeCard>, String> {
+    let filter = HistoryFilter {
+        status: Some("in_progress".to_string()),
+        limit: Some(limit.unwrap_or(CONTINUE_WATCHING_LIMIT)),
+        ..Default::default()
+    };
+    let entries = query_history(&app, &filter)?.entries;
+    let with_thumbnails = thumbnails.unwrap_or(true);
+    // ffmpeg runs once per uncached card; keep it off the async runtime and in parallel
+    tauri::async_runtime::spawn_blocking(move || {
+        use rayon::prelude::*;
+        entries.into_par_iter().map(|e| resume_card(e, with_thumbnails)).collect()
+    })
+    .await
+    .map_err(|e| e.to_string())
 }
 
 /// Clear all watch history.
 #[tauri::command]
 pub fn clear_watch_history(app: AppHandle) -> Result<(), String> {
-    let data = WatchHistoryData::default();
-    save_history(&app, &data)?;
-    Ok(())
+    crate::parental::check_area(&app, "history")?;
+    with_db(&app, |conn| conn.execute_batch("DELETE FROM videos; DELETE FROM activity;"))
+}
+
+/// Aggregate watch time over a range: "week", "month", "year" or "all" (default).
+#[tauri::command]
+pub fn get_watch_stats(app: AppHandle, range: Option<String>) -> Result<WatchStats, String> {
+    crate::parental::check_area(&app, "history")?;
+    let range = range.unwrap_or_else(|| "all".to_string());
+    let days = match range.as_str() {
+        "week" => Some(7),
+        "month" => Some(30),
+        "year" => Some(365),
+        "all" => None,
+        other => return Err(format!("Unknown stats range '{}'", other)),
+    };
+    let now = now_secs();
+    let today = now / SECS_PER_DAY;
+    let since_day = days.map(|d| today.saturating_sub(d - 1)).unwrap_or(0);
+
+    let (activity, completed) = with_db(&app, |conn| {
+        let mut stmt = conn.prepare("SELECT path, day, seconds FROM activity WHERE day >= ?1 AND seconds > 0")?;
+        let activity = stmt
+            .query_map([since_day], |r| Ok((r.get::<_, String>(0)?, r.get::<_, u64>(1)?, r.get::<_, f64>(2)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        let completed: u32 = conn.query_row(
+            "SELECT COALESCE(SUM(completions), 0) FROM activity WHERE day >= ?1",
+            [since_day],
+            |r| r.get(0),
+        )?;
+        Ok((activity, completed))
+    })?;
+
+    let mut per_day: HashMap<u64, f64> = HashMap::new();
+    let mut per_week: HashMap<u64, f64> = HashMap::new();
+    let mut per_video: HashMap<&str, f64> = HashMap::new();
+    for (path, day, seconds) in &activity {
+        *per_day.entry(*day).or_default() += seconds;
+        // 1970-01-01 was a Thursday; weeks start on Monday
+        *per_week.entry(day - (day + 3) % 7).or_default() += seconds;
+        *per_video.entry(path.as_str()).or_default() += seconds;
+    }
+
+    let mut shows: HashMap<String, f64> = HashMap::new();
+    let mut genres: HashMap<String, f64> = HashMap::new();
+    for (path, seconds) in &per_video {
+        let show = show_name(path);
+        let genre_source = match &show {
+            Some(_) => crate::metadata::show_dir(std::path::Path::new(path)).map(|d| d.to_string_lossy().to_string()),
+            None => Some(path.to_string()),
+        };
+        if let Some(show) = show {
+            *shows.entry(show).or_default() += seconds;
+        }
+        for genre in genre_source.map(|p| crate::metadata::cached_genres(&app, &p)).unwrap_or_default() {
+            *genres.entry(genre).or_default() += seconds;
+        }
+    }
+
+    let to_buckets = |map: HashMap<u64, f64>| {
+        let mut buckets: Vec<(u64, f64)> = map.into_iter().collect();
+        buckets.sort_by_key(|(day, _)| *day);
+        buckets.into_iter().map(|(day, seconds)| WatchStatsBucket { label: format_day(day), seconds }).collect::<Vec<_>>()
+    };
+    let total_seconds: f64 = per_video.values().sum();
+
+    Ok(WatchStats {
+        range,
+        total_seconds,
+        total_hours: total_seconds / 3600.0,
+        videos_watched: per_video.len(),
+        completed,
+        per_day: to_buckets(per_day),
+        per_week: to_buckets(per_week),
+        top_shows: top_totals(shows),
+        top_genres: top_totals(genres),
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct NextUpEntry {
+    pub show: String,
+    pub path: String,
+    pub season: u32, // 0 for absolute-numbered (anime) episodes
+    pub episode: u32,
+    pub after: String, // the episode that was finished
+    pub position: Option<f64>, // resume point if it was already started
+    pub last_watched: u64,
+}
+
+const NEXT_UP_LIMIT: usize = 20;
+
+/// (season, episode) of a file, treating anime-style absolute numbers as season 0.
+fn episode_key(path: &std::path::Path) -> Option<(u32, u32)> {
+    let name = path.file_name()?.to_str()?;
+    crate::metadata::parse_episode(name)
+        .or_else(|| crate::anilist::parse_anime_name(name).1.map(|ep| (0, ep)))
+}
+
+/// The episode file that comes right after `finished` in its show folder.
+fn next_episode(finished: &std::path::Path) -> Option<(std::path::PathBuf, (u32, u32))> {
+    let current = episode_key(finished)?;
+    let show = crate::metadata::show_dir(finished)?;
+    walkdir::WalkDir::new(&show)
+        .max_depth(2)
+        .into_iter()
+        .flatten()
+        .map(|e| e.into_path())
+        .filter(|p| crate::file_scanner::is_video(p))
+        .filter_map(|p| episode_key(&p).map(|k| (p, k)))
+        .filter(|(_, k)| *k > current)
+        .min_by_key(|(_, k)| *k)
+}
+
+/// The next episode to watch for each show, based on the most recently finished episode.
+#[tauri::command]
+pub fn get_next_up(app: AppHandle) -> Result<Vec<NextUpEntry>, String> {
+    let finished: Vec<(String, u64)> = with_db(&app, |conn| {
+        let mut stmt = conn.prepare(
+            "SELECT path, last_completed FROM videos WHERE show IS NOT NULL AND last_completed IS NOT NULL ORDER BY last_completed DESC",
+        )?;
+        let rows = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?;
+        rows.collect()
+    })?;
+
+    let guard = crate::parental::Guard::new(&app);
+    let mut seen_shows: Vec<std::path::PathBuf> = Vec::new();
+    let mut next_up = Vec::new();
+    for (done, last_completed) in finished.into_iter().filter(|(done, _)| guard.visible(done)) {
+        let path = std::path::Path::new(&done);
+        let Some(show) = crate::metadata::show_dir(path) else {
+            continue;
+        };
+        // Only the latest finished episode of a show decides what's next
+        if seen_shows.contains(&show) {
+            continue;
+        }
+        seen_shows.push(show.clone());
+
+        let Some((next, (season, episode))) = next_episode(path) else {
+            continue;
+        };
+        let next = next.to_string_lossy().to_string();
+        let started: Option<(f64, u64)> = with_db(&app, |conn| {
+            conn.query_row(
+                "SELECT position, last_watched FROM videos WHERE path = ?1 AND resumable = 1",
+                [&next],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .optional()
+        })?;
+        next_up.push(NextUpEntry {
+            show: show.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string(),
+            position: started.map(|(position, _)| position),
+            last_watched: started.map(|(_, watched)| watched).unwrap_or(last_completed),
+            path: next,
+            season,
+            episode,
+            after: done,
+        });
+        if next_up.len() >= NEXT_UP_LIMIT {
+            break;
+        }
+    }
+    Ok(next_up)
+}
+
+/// The video itself, or every video under a folder (all seasons of a show).
+fn videos_under(path: &str) -> Vec<String> {
+    let target = std::path::Path::new(path);
+    if !target.is_dir() {
+        return vec![path.to_string()];
+    }
+    walkdir::WalkDir::new(target)
+        .into_iter()
+        .flatten()
+        .map(|e| e.into_path())
+        .filter(|p| crate::file_scanner::is_video(p))
+        .map(|p| p.to_string_lossy().to_string())
+        .collect()
+}
+
+/// Mark a video, or every video in a folder, as watched. Clears any resume point.
+#[tauri::command]
+pub fn mark_watched(app: AppHandle, path: String) -> Result<usize, String> {
+    let now = now_secs();
+    let videos = videos_under(&path);
+    with_db(&app, |conn| {
+        let tx = conn.transaction()?;
+        for video in &videos {
+            tx.execute(
+                "INSERT INTO videos (path, show, times_completed, last_completed, modified) VALUES (?1, ?2, 1, ?3, ?3)
+                 ON CONFLICT(path) DO UPDATE SET
+                     resumable = 0, times_completed = MAX(times_completed, 1), last_completed = excluded.last_completed,
+                     modified = excluded.modified",
+                params![video, show_name(video), now],
+            )?;
+        }
+        tx.commit()
+    })?;
+    Ok(videos.len())
+}
+
+/// Mark a video, or every video in a folder, as unwatched, forgetting its completions and resume point.
+#[tauri::command]
+pub fn mark_unwatched(app: AppHandle, path: String) -> Result<usize, String> {
+    let now = now_secs();
+    let videos = videos_under(&path);
+    with_db(&app, |conn| {
+        let tx = conn.transaction()?;
+        for video in &videos {
+            tx.execute(
+                "UPDATE videos SET resumable = 0, times_completed = 0, last_completed = NULL, modified = ?2 WHERE path = ?1",
+                params![video, now],
+            )?;
+        }
+        tx.commit()
+    })?;
+    Ok(videos.len())
+}
+
+/// Watched flags for library entries. A folder counts as watched once every video in it is.
+#[tauri::command]
+pub fn get_watched_status(app: AppHandle, paths: Vec<String>) -> Result<HashMap<String, bool>, String> {
+    let targets: Vec<(String, Vec<String>)> = paths
+        .into_iter()
+        .map(|path| {
+            let videos = videos_under(&path);
+            (path, videos)
+        })
+        .collect();
+    with_db(&app, |conn| {
+        let mut stmt = conn.prepare("SELECT 1 FROM videos WHERE path = ?1 AND times_completed > 0")?;
+        let mut status = HashMap::new();
+        for (path, videos) in targets {
+            let mut watched = !videos.is_empty();
+            for video in &videos {
+                if !stmt.exists([video])? {
+                    watched = false;
+                    break;
+                }
+            }
+            status.insert(path, watched);
+        }
+        Ok(status)
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct CollectionItemProgress {
+    pub path: String,
+    pub completed: bool,
+    pub position: Option<f64>, // resume point, if started
+    pub duration: f64,
+}
+
+/// How far through a playlist or collection the user is, and where to pick it back up.
+#[derive(Debug, Serialize)]
+pub struct CollectionProgress {
+    pub id: String,
+    pub name: String,
+    pub total_items: usize,
+    pub completed_items: usize,
+    pub current_index: Option<usize>, // None once everything is watched
+    pub current_path: Option<String>,
+    pub resume_position: Option<f64>,
+    pub total_runtime: f64,
+    pub remaining_seconds: f64,
+    pub items: Vec<CollectionItemProgress>,
+}
+
+/// Index to resume at: the most recently watched item if it's unfinished, otherwise the
+/// first unfinished item after it, wrapping to any earlier one that was skipped.
+fn resume_index(items: &[String], records: &HashMap<String, HistoryRecord>) -> Option<usize> {
+    let done = |i: usize| records.get(&items[i]).is_some_and(|r| r.times_completed > 0 && !r.resumable);
+    let latest = (0..items.len())
+        .filter_map(|i| {
+            let r = records.get(&items[i])?;
+            Some((i, r.last_watched.max(r.last_completed.unwrap_or(0))))
+        })
+        .filter(|(_, t)| *t > 0)
+        .max_by_key(|(_, t)| *t)
+        .map(|(i, _)| i);
+    let start = latest.unwrap_or(0);
+    (start..items.len()).chain(0..start).find(|&i| !done(i))
+}
+
+/// Runtime of each item: the duration stored in the history, or probed from the file for
+/// unplayed items (0 if it can't be read).
+pub fn item_durations(items: &[String], records: &HashMap<String, HistoryRecord>) -> Vec<f64> {
+    use rayon::prelude::*;
+    items
+        .par_iter()
+        .map(|path| match records.get(path).filter(|r| r.duration > 0.0) {
+            Some(r) => r.duration,
+            None if std::path::Path::new(path).is_file() => crate::file_scanner::get_video_duration(path.clone()).unwrap_or(0.0),
+            None => 0.0,
+        })
+        .collect()
+}
+
+/// Progress through a playlist's or collection's items, built from the watch history.
+pub fn collection_progress(app: &AppHandle, id: &str, name: &str, items: &[String]) -> Result<CollectionProgress, String> {
+    let records = records_for(app, items)?;
+    let durations = item_durations(items, &records);
+
+    let items_progress: Vec<CollectionItemProgress> = items
+        .iter()
+        .zip(&durations)
+        .map(|(path, &duration)| {
+            let record = records.get(path);
+            CollectionItemProgress {
+                path: path.clone(),
+                completed: record.is_some_and(|r| r.times_completed > 0 && !r.resumable),
+                position: record.filter(|r| r.resumable).map(|r| r.position),
+                duration,
+            }
+        })
+        .collect();
+
+    let current_index = resume_index(items, &records);
+    let remaining_seconds = items_progress
+        .iter()
+        .filter(|i| !i.completed)
+        .map(|i| (i.duration - i.position.unwrap_or(0.0)).max(0.0))
+        .sum();
+
+    Ok(CollectionProgress {
+        id: id.to_string(),
+        name: name.to_string(),
+        total_items: items.len(),
+        completed_items: items_progress.iter().filter(|i| i.completed).count(),
+        current_path: current_index.map(|i| items[i].clone()),
+        resume_position: current_index.and_then(|i| items_progress[i].position),
+        current_index,
+        total_runtime: durations.iter().sum(),
+        remaining_seconds,
+        items: items_progress,
+    })
+}
+
+/// Progress through a playlist or collection (by id), including the item to resume at.
+#[tauri::command]
+pub async fn get_collection_progress(app: AppHandle, id: String) -> Result<CollectionProgress, String> {
+    let (name, items) = crate::playlist::find_items(&app, &id).ok_or("Playlist or collection not found")?;
+    // Probing durations shells out to ffprobe
+    tauri::async_runtime::spawn_blocking(move || collection_progress(&app, &id, &name, &items))
+        .await
+        .map_err(|e| e.to_string())?
 }
@@ -0,0 +1,656 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::async_runtime::JoinHandle;
+use tauri::{command, AppHandle, Emitter, Manager, State};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+use crate::config::{load_config, save_config};
+use crate::mpv_handler::{MpvCommand, MpvState};
+
+// Watch together: everyone plays their own copy of a file and play/pause/seek is mirrored
+// through a relay. Hosting runs the relay in this process (and joins it); joining connects
+// to another FrameX host. Messages are newline-delimited JSON over TCP, grouped into rooms.
+//
+// Positions travel with the relay's clock time they were taken at; each client estimates
+// its offset to that clock from ping round trips, so receivers can add the time a message
+// spent in flight before seeking.
+
+const PING_INTERVAL: Duration = Duration::from_secs(5);
+// A clock sample is replaced by a worse (slower) one only after this long
+const CLOCK_SAMPLE_TTL: Duration = Duration::from_secs(60);
+// Closer than this to the shared position counts as in sync (and as an echo of it)
+const DRIFT_TOLERANCE_SECS: f64 = 1.0;
+const MAX_CHAT_LEN: usize = 500;
+const MAX_NAME_LEN: usize = 40;
+// File names are compared between peers, so this only stops abuse: no file system allows longer
+const MAX_FILE_NAME_LEN: usize = 255;
+// A connection that hasn't said hello by then is dropped
+const HELLO_TIMEOUT: Duration = Duration::from_secs(10);
+// Longer lines end the connection instead of growing a buffer without bound
+const MAX_LINE_BYTES: u64 = 64 * 1024;
+// Transit time the relay adds to a playing position it restamps; more is a bad clock
+const MAX_TRANSIT_MS: u64 = 5000;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PlaybackState {
+    pub paused: bool,
+    pub position: f64,
+    pub at: u64, // relay clock, ms since the epoch
+    pub by: String,
+    pub file: Option<String>, // file name only, never the path
+}
+
+impl PlaybackState {
+    fn position_at(&self, now: u64) -> f64 {
+        if self.paused {
+            self.position
+        } else {
+            self.position + now.saturating_sub(self.at) as f64 / 1000.0
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ChatMessage {
+    pub from: String,
+    pub text: String,
+    pub at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Participant {
+    pub name: String,
+    pub file: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Message {
+    Hello {
+        room: String,
+        name: String,
+        #[serde(default)]
+        passphrase: String,
+    },
+    Welcome { state: Option<PlaybackState> },
+    Rejected { reason: String },
+    Participants { participants: Vec<Participant> },
+    File { name: Option<String> },
+    Playback(PlaybackState),
+    Chat(ChatMessage),
+    Ping { sent: u64 },
+    Pong { sent: u64, relay: u64 },
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// One line without its newline; None at end of stream.
+async fn read_line<R: AsyncRead + Unpin>(reader: &mut BufReader<R>) -> Result<Option<String>, String> {
+    let mut buf = Vec::new();
+    let read = (&mut *reader).take(MAX_LINE_BYTES + 1).read_until(b'\n', &mut buf).await.map_err(|e| e.to_string())?;
+    if read == 0 {
+        return Ok(None);
+    }
+    if buf.last() != Some(&b'\n') && buf.len() as u64 > MAX_LINE_BYTES {
+        return Err("message too long".to_string());
+    }
+    let line = String::from_utf8(buf).map_err(|e| e.to_string())?;
+    Ok(Some(line.trim_end_matches(['\r', '\n']).to_string()))
+}
+
+fn encode(message: &Message) -> String {
+    let mut line = serde_json::to_string(message).unwrap_or_default();
+    line.push('\n');
+    line
+}
+
+// ---- Relay ----
+
+struct Peer {
+    name: String,
+    file: Option<String>,
+    tx: UnboundedSender<String>,
+}
+
+#[derive(Default)]
+struct Room {
+    peers: HashMap<u64, Peer>,
+    state: Option<PlaybackState>,
+}
+
+type Rooms = Arc<Mutex<HashMap<String, Room>>>;
+
+impl Room {
+    fn broadcast(&self, from: Option<u64>, message: &Message) {
+        let line = encode(message);
+        for (id, peer) in &self.peers {
+            if Some(*id) != from {
+                let _ = peer.tx.send(line.clone());
+            }
+        }
+    }
+
+    fn announce_participants(&self) {
+        let participants = self.peers.values().map(|p| Participant { name: p.name.clone(), file: p.file.clone() }).collect();
+        self.broadcast(None, &Message::Participants { participants });
+    }
+}
+
+/// Serve rooms to peers; with a non-empty `passphrase`, only to peers that know it.
+async fn run_relay(listener: TcpListener, passphrase: String) {
+    let rooms: Rooms = Arc::default();
+    let passphrase = Arc::new(passphrase);
+    let next_id = Arc::new(AtomicU64::new(1));
+    while let Ok((stream, addr)) = listener.accept().await {
+        let (rooms, passphrase) = (rooms.clone(), passphrase.clone());
+        let id = next_id.fetch_add(1, Ordering::Relaxed);
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = serve_peer(stream, &rooms, &passphrase, id).await {
+                tracing::warn!("Watch party peer {} dropped: {}", addr, e);
+            }
+        });
+    }
+}
+
+async fn serve_peer(stream: TcpStream, rooms: &Rooms, expected_passphrase: &str, id: u64) -> Result<(), String> {
+    let (read, mut write) = stream.into_split();
+    let mut reader = BufReader::new(read);
+    let hello = tokio::time::timeout(HELLO_TIMEOUT, read_line(&mut reader)).await.map_err(|_| "no hello in time".to_string())??;
+    let Some(Message::Hello { room, name, passphrase }) = hello.and_then(|line| serde_json::from_str(&line).ok()) else {
+        return Err("expected a hello".to_string());
+    };
+    if passphrase != expected_passphrase {
+        let reason = if passphrase.is_empty() { "This party needs a passphrase" } else { "Wrong passphrase" };
+        let _ = write.write_all(encode(&Message::Rejected { reason: reason.to_string() }).as_bytes()).await;
+        return Err("wrong passphrase".to_string());
+    }
+    let name: String = name.trim().chars().take(MAX_NAME_LEN).collect();
+
+    let (tx, mut rx) = unbounded_channel::<String>();
+    tauri::async_runtime::spawn(async move {
+        while let Some(line) = rx.recv().await {
+            if write.write_all(line.as_bytes()).await.is_err() {
+                break;
+            }
+        }
+    });
+    {
+        let mut rooms = rooms.lock().unwrap();
+        let entry = rooms.entry(room.clone()).or_default();
+        let _ = tx.send(encode(&Message::Welcome { state: entry.state.clone() }));
+        entry.peers.insert(id, Peer { name, file: None, tx: tx.clone() });
+        entry.announce_participants();
+    }
+
+    let result = loop {
+        let line = match read_line(&mut reader).await {
+            Ok(Some(line)) => line,
+            Ok(None) => break Ok(()),
+            Err(e) => break Err(e),
+        };
+        let Ok(message) = serde_json::from_str::<Message>(&line) else {
+            continue;
+        };
+        let mut rooms = rooms.lock().unwrap();
+        let Some(entry) = rooms.get_mut(&room) else {
+            break Ok(());
+        };
+        match message {
+            Message::Ping { sent } => {
+                let _ = tx.send(encode(&Message::Pong { sent, relay: now_ms() }));
+            }
+            Message::Playback(mut state) => {
+                if !state.position.is_finite() || state.position < 0.0 {
+                    continue;
+                }
+                state.file = state.file.map(|f| f.chars().take(MAX_FILE_NAME_LEN).collect());
+                // Restamp on our clock so one peer's bad clock can't send everyone elsewhere
+                let now = now_ms();
+                if !state.paused {
+                    state.position += now.saturating_sub(state.at).min(MAX_TRANSIT_MS) as f64 / 1000.0;
+                }
+                state.at = now;
+                if let Some(peer) = entry.peers.get(&id) {
+                    state.by = peer.name.clone();
+                }
+                entry.broadcast(Some(id), &Message::Playback(state.clone()));
+                entry.state = Some(state);
+            }
+            Message::Chat(mut chat) => {
+                chat.text = chat.text.chars().take(MAX_CHAT_LEN).collect();
+                chat.at = now_ms();
+                if let Some(peer) = entry.peers.get(&id) {
+                    chat.from = peer.name.clone();
+                }
+                entry.broadcast(Some(id), &Message::Chat(chat));
+            }
+            Message::File { name } => {
+                if let Some(peer) = entry.peers.get_mut(&id) {
+                    peer.file = name.map(|n| n.chars().take(MAX_FILE_NAME_LEN).collect());
+                }
+                entry.announce_participants();
+            }
+            _ => {}
+        }
+    };
+
+    let mut rooms = rooms.lock().unwrap();
+    if let Some(entry) = rooms.get_mut(&room) {
+        entry.peers.remove(&id);
+        if entry.peers.is_empty() {
+            rooms.remove(&room);
+        } else {
+            entry.announce_participants();
+        }
+    }
+    result
+}
+
+// ---- Client ----
+
+#[derive(Default)]
+struct Shared {
+    participants: Vec<Participant>,
+    // Latest shared playback state, from anyone (including us)
+    state: Option<PlaybackState>,
+    // Best clock sample: (round trip ms, relay - local offset ms, taken)
+    clock: Option<(u64, i64, Instant)>,
+    // Local playback, as reported by the player loop
+    position: Option<f64>,
+    file: Option<String>,
+    ready: bool, // a file is loaded and its initial seek is done
+    awaiting_restart: bool,
+}
+
+impl Shared {
+    fn relay_now(&self) -> u64 {
+        let offset = self.clock.map_or(0, |(_, offset, _)| offset);
+        (now_ms() as i64 + offset).max(0) as u64
+    }
+
+    /// `paused`/`position` just happened locally; true when that's where the shared
+    /// state already is (typically us applying a remote change).
+    fn matches(&self, paused: bool, position: f64) -> bool {
+        self.state.as_ref().is_some_and(|s| {
+            s.paused == paused && (s.position_at(self.relay_now()) - position).abs() < DRIFT_TOLERANCE_SECS
+        })
+    }
+}
+
+struct Session {
+    id: u64,
+    name: String,
+    room: String,
+    address: String,
+    hosting: bool,
+    outgoing: UnboundedSender<Message>,
+    shared: Arc<Mutex<Shared>>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+pub struct WatchPartyState {
+    session: Mutex<Option<Session>>,
+}
+
+impl WatchPartyState {
+    pub fn new() -> Self {
+        Self { session: Mutex::new(None) }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct WatchPartyInfo {
+    pub name: String,
+    pub room: String,
+    pub address: String,
+    pub hosting: bool,
+    pub participants: Vec<Participant>,
+    pub latency_ms: Option<u64>,
+}
+
+fn info(session: &Session) -> WatchPartyInfo {
+    let shared = session.shared.lock().unwrap();
+    WatchPartyInfo {
+        name: session.name.clone(),
+        room: session.room.clone(),
+        address: session.address.clone(),
+        hosting: session.hosting,
+        participants: shared.participants.clone(),
+        latency_ms: shared.clock.map(|(rtt, _, _)| rtt / 2),
+    }
+}
+
+fn emit_info(app: &AppHandle) {
+    let state = app.state::<WatchPartyState>();
+    let info = state.session.lock().unwrap().as_ref().map(info);
+    let _ = app.emit("watch-party", info);
+}
+
+fn send_mpv(app: &AppHandle, command: MpvCommand) {
+    if let Some(tx) = app.state::<MpvState>().tx.lock().unwrap().as_ref() {
+        let _ = tx.send(command);
+    }
+}
+
+/// Bring local playback to a shared state, seeking only when we've drifted.
+fn apply(app: &AppHandle, shared: &Shared, state: &PlaybackState) {
+    if !shared.ready || (state.file.is_some() && shared.file.is_some() && state.file != shared.file) {
+        return;
+    }
+    let target = state.position_at(shared.relay_now());
+    send_mpv(app, if state.paused { MpvCommand::Pause } else { MpvCommand::Play });
+    if shared.position.is_none_or(|p| (p - target).abs() > DRIFT_TOLERANCE_SECS) {
+        send_mpv(app, MpvCommand::SeekAbsolute(target));
+    }
+}
+
+fn handle_message(app: &AppHandle, shared: &Arc<Mutex<Shared>>, message: Message) {
+    match message {
+        Message::Welcome { state: Some(state) } | Message::Playback(state) => {
+            let mut shared = shared.lock().unwrap();
+            apply(app, &shared, &state);
+            shared.state = Some(state.clone());
+            drop(shared);
+            let _ = app.emit("watch-party-playback", state);
+        }
+        Message::Participants { participants } => {
+            shared.lock().unwrap().participants = participants;
+            emit_info(app);
+        }
+        Message::Chat(chat) => {
+            let _ = app.emit("watch-party-chat", chat);
+        }
+        Message::Pong { sent, relay } => {
+            let now = now_ms();
+            let rtt = now.saturating_sub(sent);
+            let offset = relay as i64 + (rtt / 2) as i64 - now as i64;
+            let mut shared = shared.lock().unwrap();
+            let better = shared.clock.is_none_or(|(best, _, taken)| rtt <= best || taken.elapsed() > CLOCK_SAMPLE_TTL);
+            if better {
+                shared.clock = Some((rtt, offset, Instant::now()));
+            }
+        }
+        _ => {}
+    }
+}
+
+async fn connect(
+    app: &AppHandle,
+    address: String,
+    name: String,
+    room: String,
+    passphrase: String,
+    hosting: bool,
+    relay: Option<JoinHandle<()>>,
+) -> Result<WatchPartyInfo, String> {
+    let stream = tokio::time::timeout(Duration::from_secs(10), TcpStream::connect(&address))
+        .await
+        .map_err(|_| format!("Timed out connecting to {}", address))?
+        .map_err(|e| format!("Couldn't connect to {}: {}", address, e))?;
+    let _ = stream.set_nodelay(true);
+    let (read, mut write) = stream.into_split();
+
+    static NEXT_SESSION: AtomicU64 = AtomicU64::new(1);
+    let id = NEXT_SESSION.fetch_add(1, Ordering::Relaxed);
+    // Whatever plays already is in sync once the welcome brings us to the room's position
+    let file = shared_file_name(app, current_file(app).as_deref());
+    let shared = Arc::new(Mutex::new(Shared { file: file.clone(), ready: file.is_some(), ..Shared::default() }));
+    let (outgoing, mut outgoing_rx) = unbounded_channel::<Message>();
+    let _ = outgoing.send(Message::Hello { room: room.clone(), name: name.clone(), passphrase });
+    let _ = outgoing.send(Message::File { name: file });
+
+    let mut tasks: Vec<JoinHandle<()>> = relay.into_iter().collect();
+    tasks.push(tauri::async_runtime::spawn(async move {
+        while let Some(message) = outgoing_rx.recv().await {
+            if write.write_all(encode(&message).as_bytes()).await.is_err() {
+                break;
+            }
+        }
+    }));
+    let pinger = outgoing.clone();
+    tasks.push(tauri::async_runtime::spawn(async move {
+        loop {
+            if pinger.send(Message::Ping { sent: now_ms() }).is_err() {
+                break;
+            }
+            tokio::time::sleep(PING_INTERVAL).await;
+        }
+    }));
+
+    // The relay answers the hello with a welcome, or turns us away
+    let mut reader = BufReader::new(read);
+    let first = match tokio::time::timeout(Duration::from_secs(10), read_line(&mut reader)).await {
+        Err(_) => Err(format!("{} didn't answer", address)),
+        Ok(Err(e)) => Err(format!("Connection to {} failed: {}", address, e)),
+        Ok(Ok(line)) => match line.and_then(|line| serde_json::from_str::<Message>(&line).ok()) {
+            Some(Message::Rejected { reason }) => Err(reason),
+            Some(welcome @ Message::Welcome { .. }) => Ok(welcome),
+            _ => Err(format!("{} isn't a FrameX watch party", address)),
+        },
+    };
+    match first {
+        Ok(welcome) => handle_message(app, &shared, welcome),
+        Err(e) => {
+            // Also stops the relay when hosting
+            for task in &tasks {
+                task.abort();
+            }
+            return Err(e);
+        }
+    }
+
+    let (handle, reader_shared) = (app.clone(), shared.clone());
+    tasks.push(tauri::async_runtime::spawn(async move {
+        while let Ok(Some(line)) = read_line(&mut reader).await {
+            if let Ok(message) = serde_json::from_str::<Message>(&line) {
+                handle_message(&handle, &reader_shared, message);
+            }
+        }
+        tracing::info!("🎉 Watch party connection closed");
+        let state = handle.state::<WatchPartyState>();
+        let mut session = state.session.lock().unwrap();
+        if session.as_ref().is_some_and(|s| s.id == id) {
+            // Aborting this task on drop is harmless: it has nothing left to await
+            let ended = session.take();
+            drop(session);
+            drop(ended);
+            let _ = handle.emit("watch-party", None::<WatchPartyInfo>);
+        }
+    }));
+
+    let session = Session { id, name, room, address, hosting, outgoing, shared, tasks };
+    let state = app.state::<WatchPartyState>();
+    let info = info(&session);
+    *state.session.lock().unwrap() = Some(session);
+    emit_info(app);
+    Ok(info)
+}
+
+// ---- Player hooks (called from the mpv loop) ----
+
+fn current_file(app: &AppHandle) -> Option<String> {
+    app.state::<MpvState>().tx.lock().unwrap().as_ref()?;
+    let queue = crate::queue::get_queue(app.clone());
+    queue.index.and_then(|i| queue.items.get(i).cloned())
+}
+
+/// What others see of a path: its file name, or nothing in private mode.
+fn shared_file_name(app: &AppHandle, path: Option<&str>) -> Option<String> {
+    if load_config(app).private_mode {
+        return None;
+    }
+    Path::new(path?).file_name().map(|n| n.to_string_lossy().into_owned())
+}
+
+fn with_session(app: &AppHandle, f: impl FnOnce(&Session, &mut Shared)) {
+    let state = app.state::<WatchPartyState>();
+    let session = state.session.lock().unwrap();
+    if let Some(session) = session.as_ref() {
+        let mut shared = session.shared.lock().unwrap();
+        f(session, &mut shared);
+    }
+}
+
+fn share(session: &Session, shared: &mut Shared, paused: bool, position: f64) {
+    if !shared.ready || shared.matches(paused, position) {
+        return;
+    }
+    let state = PlaybackState { paused, position, at: shared.relay_now(), by: session.name.clone(), file: shared.file.clone() };
+    shared.state = Some(state.clone());
+    let _ = session.outgoing.send(Message::Playback(state));
+}
+
+/// A file finished loading (None: nothing is loaded).
+pub fn file_loaded(app: &AppHandle, path: Option<&str>) {
+    let file = shared_file_name(app, path);
+    with_session(app, |session, shared| {
+        shared.file = file;
+        shared.position = None;
+        shared.ready = false;
+        shared.awaiting_restart = path.is_some();
+        let _ = session.outgoing.send(Message::File { name: shared.file.clone() });
+    });
+}
+
+/// Playback (re)started after loading or a seek.
+pub fn restarted(app: &AppHandle, paused: bool, position: f64) {
+    let mut catch_up = None;
+    with_session(app, |session, shared| {
+        shared.position = Some(position);
+        if shared.awaiting_restart {
+            // The initial start of a file: join in where the room is instead of sharing 0:00
+            shared.awaiting_restart = false;
+            shared.ready = true;
+            catch_up = shared.state.clone();
+            return;
+        }
+        share(session, shared, paused, position);
+    });
+    if let Some(state) = catch_up {
+        with_session(app, |_, shared| apply(app, shared, &state));
+    }
+}
+
+pub fn paused(app: &AppHandle, paused: bool, position: f64) {
+    with_session(app, |session, shared| share(session, shared, paused, position));
+}
+
+pub fn progress(app: &AppHandle, position: f64) {
+    with_session(app, |_, shared| shared.position = Some(position));
+}
+
+// ---- Commands ----
+
+fn remember(app: &AppHandle, name: &str, room: &str, address: Option<&str>) {
+    let mut config = load_config(app);
+    config.watch_party.display_name = name.to_string();
+    config.watch_party.last_room = room.to_string();
+    if let Some(address) = address {
+        config.watch_party.last_address = address.to_string();
+    }
+    let _ = save_config(app, &config);
+}
+
+fn check_names(name: &str, room: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Pick a display name first".to_string());
+    }
+    if name.trim().chars().count() > MAX_NAME_LEN {
+        return Err(format!("Display names can be at most {} characters", MAX_NAME_LEN));
+    }
+    if room.trim().is_empty() {
+        return Err("Enter a room name".to_string());
+    }
+    Ok(())
+}
+
+/// Start a relay on the configured port and join its room. With a passphrase, only
+/// those who enter it can join.
+#[command]
+pub async fn host_watch_party(app: AppHandle, name: String, room: String, passphrase: Option<String>) -> Result<WatchPartyInfo, String> {
+    let passphrase = passphrase.unwrap_or_default().trim().to_string();
+    check_names(&name, &room)?;
+    leave_watch_party(app.clone(), app.state::<WatchPartyState>());
+    let port = load_config(&app).watch_party.port;
+    let listener = TcpListener::bind(("0.0.0.0", port)).await.map_err(|e| format!("Couldn't listen on port {}: {}", port, e))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+    tracing::info!("🎉 Hosting watch party '{}' on port {}", room, port);
+    let relay = tauri::async_runtime::spawn(run_relay(listener, passphrase.clone()));
+
+    // What to tell friends: our address on the LAN (a public one needs port forwarding)
+    let lan = crate::media_server::local_ip_for([8, 8, 8, 8].into()).map(|ip| ip.to_string()).unwrap_or_else(|_| "localhost".to_string());
+    remember(&app, name.trim(), room.trim(), None);
+    let mut info = connect(&app, format!("127.0.0.1:{}", port), name.trim().to_string(), room.trim().to_string(), passphrase, true, Some(relay)).await?;
+    info.address = format!("{}:{}", lan, port);
+    if let Some(session) = app.state::<WatchPartyState>().session.lock().unwrap().as_mut() {
+        session.address = info.address.clone();
+    }
+    emit_info(&app);
+    Ok(info)
+}
+
+/// Join a room on another host's relay ("host:port"; the port defaults to ours).
+#[command]
+pub async fn join_watch_party(app: AppHandle, address: String, name: String, room: String, passphrase: Option<String>) -> Result<WatchPartyInfo, String> {
+    check_names(&name, &room)?;
+    let address = address.trim().to_string();
+    if address.is_empty() {
+        return Err("Enter the host's address".to_string());
+    }
+    let target = if address.rsplit_once(':').is_some_and(|(_, port)| port.parse::<u16>().is_ok()) {
+        address.clone()
+    } else {
+        format!("{}:{}", address, load_config(&app).watch_party.port)
+    };
+    leave_watch_party(app.clone(), app.state::<WatchPartyState>());
+    remember(&app, name.trim(), room.trim(), Some(&address));
+    tracing::info!("🎉 Joining watch party '{}' at {}", room, target);
+    let passphrase = passphrase.unwrap_or_default().trim().to_string();
+    connect(&app, target, name.trim().to_string(), room.trim().to_string(), passphrase, false, None).await
+}
+
+#[command]
+pub fn leave_watch_party(app: AppHandle, state: State<'_, WatchPartyState>) {
+    let session = state.session.lock().unwrap().take();
+    if session.is_some() {
+        drop(session);
+        let _ = app.emit("watch-party", None::<WatchPartyInfo>);
+    }
+}
+
+#[command]
+pub fn get_watch_party(state: State<'_, WatchPartyState>) -> Option<WatchPartyInfo> {
+    state.session.lock().unwrap().as_ref().map(info)
+}
+
+#[command]
+pub fn send_watch_party_chat(state: State<'_, WatchPartyState>, text: String) -> Result<ChatMessage, String> {
+    let text: String = text.trim().chars().take(MAX_CHAT_LEN).collect();
+    if text.is_empty() {
+        return Err("Nothing to send".to_string());
+    }
+    let session = state.session.lock().unwrap();
+    let session = session.as_ref().ok_or("Not in a watch party")?;
+    let chat = ChatMessage { from: session.name.clone(), text, at: now_ms() };
+    session.outgoing.send(Message::Chat(chat.clone())).map_err(|_| "Connection closed".to_string())?;
+    Ok(chat)
+}
@@ -0,0 +1,187 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{command, AppHandle, Emitter, LogicalSize, Manager, PhysicalPosition, PhysicalSize, WebviewWindow};
+
+// Mini-player size (logical pixels) and its distance from the screen corner
+const MINI_WIDTH: f64 = 400.0;
+const MINI_MIN_WIDTH: f64 = 240.0;
+const MINI_ASPECT: f64 = 16.0 / 9.0;
+const MINI_MARGIN: f64 = 24.0;
+
+/// What the main window goes back to when the mini-player closes.
+#[derive(Clone, Debug)]
+pub(crate) struct Restore {
+    pub position: PhysicalPosition<i32>,
+    pub size: PhysicalSize<u32>,
+    pub maximized: bool,
+    pub fullscreen: bool,
+    pub always_on_top: bool,
+}
+
+#[derive(Default)]
+struct Mode {
+    always_on_top: bool,
+    mini: Option<Restore>,
+}
+
+pub struct WindowModeState {
+    mode: Mutex<Mode>,
+}
+
+impl WindowModeState {
+    pub fn new() -> Self {
+        Self { mode: Mutex::new(Mode::default()) }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct WindowMode {
+    pub always_on_top: bool,
+    pub mini_player: bool,
+}
+
+fn main_window(app: &AppHandle) -> Result<WebviewWindow, String> {
+    app.get_webview_window("main").ok_or_else(|| "The main window is gone".to_string())
+}
+
+fn current(app: &AppHandle) -> WindowMode {
+    let state = app.state::<WindowModeState>();
+    let mode = state.mode.lock().unwrap();
+    WindowMode { always_on_top: mode.always_on_top, mini_player: mode.mini.is_some() }
+}
+
+fn changed(app: &AppHandle) -> WindowMode {
+    let mode = current(app);
+    crate::tray::sync_window_mode(&mode);
+    let _ = app.emit("window-mode-changed", mode.clone());
+    mode
+}
+
+/// The geometry to save for next launch while the mini-player is up (see `config::save_window_state`).
+pub(crate) fn mini_restore(app: &AppHandle) -> Option<Restore> {
+    app.state::<WindowModeState>().mode.lock().unwrap().mini.clone()
+}
+
+fn apply_on_top(app: &AppHandle, window: &WebviewWindow, enabled: bool) -> Result<(), String> {
+    window.set_always_on_top(enabled).map_err(|e| e.to_string())?;
+    app.state::<WindowModeState>().mode.lock().unwrap().always_on_top = enabled;
+    Ok(())
+}
+
+/// Shrink to a small always-on-top 16:9 window in the bottom-right corner of the current screen.
+fn enter_mini(app: &AppHandle, window: &WebviewWindow, restore: Restore) -> Result<(), String> {
+    if restore.fullscreen {
+        let _ = window.set_fullscreen(false);
+    }
+    if restore.maximized {
+        let _ = window.unmaximize();
+    }
+    app.state::<WindowModeState>().mode.lock().unwrap().mini = Some(restore);
+
+    let monitor = window.current_monitor().ok().flatten().or_else(|| window.primary_monitor().ok().flatten());
+    let scale = monitor.as_ref().map_or(1.0, |m| m.scale_factor());
+    let size = LogicalSize::new(MINI_WIDTH, MINI_WIDTH / MINI_ASPECT).to_physical::<u32>(scale);
+    window.set_min_size(Some(LogicalSize::new(MINI_MIN_WIDTH, MINI_MIN_WIDTH / MINI_ASPECT))).map_err(|e| e.to_string())?;
+    window.set_size(size).map_err(|e| e.to_string())?;
+    if let Some(area) = monitor.map(|m| *m.work_area()) {
+        let margin = (MINI_MARGIN * scale) as i32;
+        let x = area.position.x + area.size.width as i32 - size.width as i32 - margin;
+        let y = area.position.y + area.size.height as i32 - size.height as i32 - margin;
+        let _ = window.set_position(PhysicalPosition::new(x, y));
+    }
+    apply_on_top(app, window, true)
+}
+
+fn leave_mini(app: &AppHandle, window: &WebviewWindow) -> Result<(), String> {
+    let Some(restore) = app.state::<WindowModeState>().mode.lock().unwrap().mini.take() else {
+        return Ok(());
+    };
+    let _ = window.set_min_size(None::<LogicalSize<f64>>);
+    let _ = window.set_size(restore.size);
+    let _ = window.set_position(restore.position);
+    apply_on_top(app, window, restore.always_on_top)?;
+    if restore.fullscreen {
+        let _ = window.set_fullscreen(true);
+    } else if restore.maximized {
+        let _ = window.maximize();
+    }
+    Ok(())
+}
+
+pub fn set_mini_player_mode(app: &AppHandle, enabled: bool) -> Result<WindowMode, String> {
+    let window = main_window(app)?;
+    let active = current(app).mini_player;
+    if enabled && !active {
+        let restore = Restore {
+            position: window.outer_position().map_err(|e| e.to_string())?,
+            size: window.outer_size().map_err(|e| e.to_string())?,
+            maximized: window.is_maximized().unwrap_or(false),
+            fullscreen: window.is_fullscreen().unwrap_or(false),
+            always_on_top: current(app).always_on_top,
+        };
+        enter_mini(app, &window, restore)?;
+        tracing::info!("🪟 Mini-player on");
+    } else if !enabled && active {
+        leave_mini(app, &window)?;
+        tracing::info!("🪟 Mini-player off");
+    }
+    Ok(changed(app))
+}
+
+pub fn set_always_on_top_mode(app: &AppHandle, enabled: bool) -> Result<WindowMode, String> {
+    apply_on_top(app, &main_window(app)?, enabled)?;
+    Ok(changed(app))
+}
+
+/// Start in the mini-player the last session closed in, from the just-restored normal geometry.
+pub(crate) fn restore_mini(app: &AppHandle, maximized: bool, fullscreen: bool) {
+    let Ok(window) = main_window(app) else {
+        return;
+    };
+    let (Ok(position), Ok(size)) = (window.outer_position(), window.outer_size()) else {
+        return;
+    };
+    let restore = Restore { position, size, maximized, fullscreen, always_on_top: false };
+    if let Err(e) = enter_mini(app, &window, restore) {
+        tracing::warn!("Mini-player not restored: {}", e);
+    }
+    changed(app);
+}
+
+/// Keep the mini-player at 16:9 while it is resized by its edges.
+pub fn keep_aspect(window: &tauri::Window, size: &PhysicalSize<u32>) {
+    if window.label() != "main" || mini_restore(window.app_handle()).is_none() {
+        return;
+    }
+    let height = (size.width as f64 / MINI_ASPECT).round() as u32;
+    if size.height.abs_diff(height) > 2 {
+        let _ = window.set_size(PhysicalSize::new(size.width, height));
+    }
+}
+
+#[command]
+pub fn get_window_mode(app: AppHandle) -> WindowMode {
+    current(&app)
+}
+
+#[command]
+pub fn set_always_on_top(app: AppHandle, enabled: bool) -> Result<WindowMode, String> {
+    set_always_on_top_mode(&app, enabled)
+}
+
+#[command]
+pub fn toggle_always_on_top(app: AppHandle) -> Result<WindowMode, String> {
+    let enabled = !current(&app).always_on_top;
+    set_always_on_top_mode(&app, enabled)
+}
+
+#[command]
+pub fn set_mini_player(app: AppHandle, enabled: bool) -> Result<WindowMode, String> {
+    set_mini_player_mode(&app, enabled)
+}
+
+#[command]
+pub fn toggle_mini_player(app: AppHandle) -> Result<WindowMode, String> {
+    let enabled = !current(&app).mini_player;
+    set_mini_player_mode(&app, enabled)
+}
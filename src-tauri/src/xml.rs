@@ -0,0 +1,80 @@
+use regex::Regex;
+use std::sync::OnceLock;
+
+// The handful of XML helpers the importers, exporters and feed readers share. None of
+// those formats needs a real parser; a tag or attribute lookup on the raw text does.
+
+pub fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+pub fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Unescaped, trimmed text of the first `<name>...</name>` in `block`.
+pub fn xml_tag(block: &str, name: &str) -> Option<String> {
+    let re = Regex::new(&format!(r"(?s)<{0}(?:\s[^>]*)?>(.*?)</{0}>", regex::escape(name))).unwrap();
+    re.captures(block).map(|c| unescape_xml(c[1].trim()))
+}
+
+// Compiled once; IPTV guides can hold hundreds of thousands of programmes
+fn attribute_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#"(?:^|\s)([\w:.-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap())
+}
+
+/// Unescaped, trimmed value of `name="..."` (or single-quoted) among a tag's attributes;
+/// None when missing or empty. Names match case-insensitively.
+pub fn attribute(attrs: &str, name: &str) -> Option<String> {
+    attribute_re()
+        .captures_iter(attrs)
+        .find(|c| c[1].eq_ignore_ascii_case(name))
+        .and_then(|c| c.get(2).or(c.get(3)))
+        .map(|v| unescape_xml(v.as_str().trim()))
+        .filter(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_round_trip() {
+        let text = r#"Tom & Jerry <"Director's Cut">"#;
+        assert_eq!(escape_xml(text), "Tom &amp; Jerry &lt;&quot;Director&apos;s Cut&quot;&gt;");
+        assert_eq!(unescape_xml(&escape_xml(text)), text);
+        // Unescaped once only: an escaped entity stays an entity
+        assert_eq!(unescape_xml("&amp;lt;"), "&lt;");
+    }
+
+    #[test]
+    fn tags() {
+        let block = "<item><title lang=\"en\"> A &amp; B </title><empty/></item>";
+        assert_eq!(xml_tag(block, "title").as_deref(), Some("A & B"));
+        assert_eq!(xml_tag(block, "tit"), None);
+        assert_eq!(xml_tag(block, "empty"), None);
+    }
+
+    #[test]
+    fn attributes() {
+        let attrs = r#" url="http://a.tv/x.mp3?a=1&amp;b=2" type='audio/mpeg' tvg-ID="ch.1" length = "123" title="" cover="" xurl="no""#;
+        assert_eq!(attribute(attrs, "url").as_deref(), Some("http://a.tv/x.mp3?a=1&b=2"));
+        assert_eq!(attribute(attrs, "type").as_deref(), Some("audio/mpeg"));
+        assert_eq!(attribute(attrs, "tvg-id").as_deref(), Some("ch.1"));
+        assert_eq!(attribute(attrs, "length").as_deref(), Some("123"));
+        assert_eq!(attribute(attrs, "title"), None);
+        assert_eq!(attribute(attrs, "missing"), None);
+        assert_eq!(attribute(r#"<enclosure url="it's here"/>"#, "url").as_deref(), Some("it's here"));
+    }
+}
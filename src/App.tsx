@@ -7,7 +7,7 @@ import {
   Play, Pause, Settings, FolderOpen, Volume2, SkipBack, SkipForward,
   Maximize2, Minimize2, Film, MonitorPlay, Library, Grid, List,
   X, Minus, Square, Copy, ArrowLeft, Captions, Check, ArrowUpDown, Clock,
-  FileText, Info, Calendar as CalendarIcon, Brain
+  FileText, Info, Calendar as CalendarIcon, Brain, Users, Scissors, Clapperboard, Pin, PictureInPicture2
 } from "lucide-react";
 import { useFile } from "./context/FileContext";
 import { useGestures } from "./hooks/useGestures";
@@ -18,7 +18,16 @@ import TimelinePreview from "./components/TimelinePreview";
 import { useSettings } from "./context/SettingsContext";
 import { AISidebar } from "./components/AISidebar/AISidebar";
 import StreamDialog from "./components/StreamDialog/StreamDialog";
+import IptvGuide from "./components/IptvGuide/IptvGuide";
+import RadioStations, { type RadioStation } from "./components/RadioStations/RadioStations";
+import Podcasts from "./components/Podcasts/Podcasts";
+import WatchParty from "./components/WatchParty/WatchParty";
+import SegmentEditor, { SEGMENT_LABELS, type Segment } from "./components/Segments/SegmentEditor";
+import ClipExport from "./components/ClipExport/ClipExport";
+import Conversions from "./components/Conversions/Conversions";
 import PlaylistManager from "./components/PlaylistManager/PlaylistManager";
+import PinPrompt from "./components/ParentalControls/PinPrompt";
+import UpdateBanner from "./components/Updates/UpdateBanner";
 
 interface Track {
   id: number;
@@ -36,6 +45,35 @@ interface VideoEntry {
   created: number;
   entry_type: 'video' | 'folder';
   poster_path?: string;
+  rating?: number | null;
+}
+
+interface IdentifyProgress {
+  folder: string;
+  item: { path: string; title?: string; poster?: string };
+  matched: boolean;
+  done: number;
+  total: number;
+}
+
+interface TorrentProgress {
+  id: string;
+  name: string;
+  state: string;
+  progress_bytes: number;
+  total_bytes: number;
+  download_speed: number;
+  upload_speed: number;
+  peers: number;
+  finished: boolean;
+  error: string | null;
+}
+
+interface LaunchArgs {
+  files: string[];
+  fullscreen: boolean;
+  start_at: number | null;
+  enqueue: boolean;
 }
 
 interface MovieInfo {
@@ -264,9 +302,21 @@ function App() {
   // App Settings State
   // App Settings State
   const [showAppSettings, setShowAppSettings] = useState(false);
+  const [pinGate, setPinGate] = useState<{ message?: string; then: () => void } | null>(null);
   const [showAISidebar, setShowAISidebar] = useState(false);
   const [showStreamDialog, setShowStreamDialog] = useState(false);
+  const [showLiveTv, setShowLiveTv] = useState(false);
+  const [showRadio, setShowRadio] = useState(false);
+  const [showPodcasts, setShowPodcasts] = useState(false);
+  const [streamTitle, setStreamTitle] = useState<string | null>(null);
   const [showPlaylistManager, setShowPlaylistManager] = useState(false);
+  const [showWatchParty, setShowWatchParty] = useState(false);
+  const [showSegments, setShowSegments] = useState(false);
+  const [showClipExport, setShowClipExport] = useState(false);
+  const [showConversions, setShowConversions] = useState(false);
+  const [fileSegments, setFileSegments] = useState<{ path: string; segments: Segment[] } | null>(null);
+  const [activeSegment, setActiveSegment] = useState<Segment | null>(null);
+  const [activeTorrent, setActiveTorrent] = useState<TorrentProgress | null>(null);
 
 
   // Playback Speed State
@@ -279,6 +329,7 @@ function App() {
 
   // Library State
   const [library, setLibrary] = useState<VideoEntry[]>([]);
+  const [libraryFolder, setLibraryFolder] = useState<string | null>(null);
   const [viewMode, setViewMode] = useState<'grid' | 'list'>('grid');
   const [sortOption, setSortOption] = useState<SortOption>('name-asc');
   const [showSortMenu, setShowSortMenu] = useState(false);
@@ -288,6 +339,7 @@ function App() {
 
   // Window State
   const [isMaximized, setIsMaximized] = useState(false);
+  const [windowMode, setWindowMode] = useState({ always_on_top: false, mini_player: false });
 
   // Info Modal State
   const [currentInfo, setCurrentInfo] = useState<MovieInfo | TvInfo | null>(null);
@@ -305,6 +357,16 @@ function App() {
     return () => window.removeEventListener('resize', updateMaximizedState);
   }, []);
 
+  // Always-on-top and the mini-player can also be switched from the tray and global hotkeys
+  useEffect(() => {
+    invoke<typeof windowMode>('get_window_mode').then(setWindowMode).catch(console.error);
+    const unlisten = listen<typeof windowMode>('window-mode-changed', e => setWindowMode(e.payload));
+    return () => { unlisten.then(f => f()); };
+  }, []);
+
+  const toggleAlwaysOnTop = () => invoke('toggle_always_on_top').catch(console.error);
+  const toggleMiniPlayer = () => invoke('toggle_mini_player').catch(console.error);
+
   // Load Last Opened Folder on Startup
   useEffect(() => {
     const loadLastFolder = async () => {
@@ -320,6 +382,45 @@ function App() {
     loadLastFolder();
   }, []);
 
+  // Files from the command line, "Open with" and later launches forwarded to this instance
+  useEffect(() => {
+    const openLaunchArgs = async () => {
+      const args = await invoke<LaunchArgs | null>('take_launch_args');
+      if (!args) return;
+      try {
+        if (args.files.length > 0) {
+          await invoke('play_launch_args', { args });
+          if (!args.enqueue) {
+            setFile(args.files[0]);
+            setIsPlaying(true);
+            setIsPlayerActive(true);
+          }
+        }
+        const win = Window.getCurrent();
+        if (args.fullscreen && !(await win.isFullscreen())) {
+          await win.setFullscreen(true);
+          setIsFullscreen(true);
+        }
+      } catch (e) {
+        console.error('Failed to open launch files:', e);
+      }
+    };
+    openLaunchArgs();
+    const unlisten = listen('launch-args', openLaunchArgs);
+    return () => { unlisten.then(f => f()); };
+  }, []);
+
+  // Skip segments of the playing file, and the one playback is inside (when it offers a skip button)
+  useEffect(() => {
+    invoke<{ path: string; segments: Segment[] } | null>('get_current_segments').then(setFileSegments).catch(() => {});
+    const unlistenSegments = listen<{ path: string; segments: Segment[] }>('segments', (event) => setFileSegments(event.payload));
+    const unlistenActive = listen<Segment | null>('segment-active', (event) => setActiveSegment(event.payload));
+    return () => {
+      unlistenSegments.then(f => f());
+      unlistenActive.then(f => f());
+    };
+  }, []);
+
   // MPV Progress Listener
   useEffect(() => {
     let unlisten: (() => void) | undefined;
@@ -453,6 +554,12 @@ function App() {
     }
   }, [isPlayerActive, currentFile]);
 
+  // Now-playing track of a radio stream (ICY metadata)
+  useEffect(() => {
+    const unlisten = listen<string | null>('mpv-stream-title', (e) => setStreamTitle(e.payload));
+    return () => { unlisten.then(f => f()); };
+  }, []);
+
   async function handleBack() {
     setIsPlayerActive(false);
     setIsPlaying(false);
@@ -473,6 +580,9 @@ function App() {
         filters: [{
           name: 'Video',
           extensions: ['mkv', 'mp4', 'avi', 'mov', 'webm', 'flv', 'wmv']
+        }, {
+          name: 'Audio',
+          extensions: ['mp3', 'flac', 'm4a', 'ogg', 'opus', 'wav']
         }]
       });
 
@@ -492,14 +602,15 @@ function App() {
       // Invoke backend command to scan folder
       const videos = await invoke<VideoEntry[]>("list_videos", { folderPath: path });
       setLibrary(videos);
+      setLibraryFolder(path);
 
       // If manually loading a folder, we might want to clear the current file?
       // But if we are starting up, currentFile is null anyway.
       // If we are switching folders, yes.
       setFile(null);
 
-      // Queue duration fetching
-      const paths = videos.map(v => v.path);
+      // Queue duration fetching (probing every file on a media server is too slow)
+      const paths = path.includes('://') ? [] : videos.map(v => v.path);
       setDurationQueue(paths);
 
     } catch (err) {
@@ -604,6 +715,49 @@ function App() {
     }
   }
 
+  async function handlePlayPlaylist(id: string, startIndex: number) {
+    setIsLoading(true);
+    try {
+      await invoke("play_playlist", { id, startIndex });
+      setIsPlaying(true);
+      setIsPlayerActive(true);
+    } catch (err) {
+      console.error("Failed to play playlist:", err);
+    } finally {
+      setIsLoading(false);
+    }
+  }
+
+  // PIN-protected areas ask for the PIN first; the backend refuses them too
+  const openArea = (area: string, open: () => void) => {
+    invoke<boolean>('is_area_locked', { area })
+      .then(locked => locked ? setPinGate({ then: open }) : open())
+      .catch(open);
+  };
+
+  // mpv reached a blocked file on its own (e.g. through its playlist)
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    listen<string>('parental-blocked', (event) => {
+      const path = event.payload;
+      setPinGate({
+        message: `${path.split(/[\\/]/).pop()} is blocked by parental controls`,
+        then: () => invoke('play_video', { path }).catch(e => console.error('Failed to play video:', e)),
+      });
+    }).then(u => { unlisten = u; });
+    return () => { if (unlisten) unlisten(); };
+  }, []);
+
+  // Keep the current file in step as the backend queue advances
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    listen<{ items: string[]; index: number | null }>('queue-changed', (event) => {
+      const { items, index } = event.payload;
+      if (index !== null && items[index]) setFile(items[index]);
+    }).then(u => { unlisten = u; });
+    return () => { if (unlisten) unlisten(); };
+  }, []);
+
   // Next/Previous Video Navigation
   function playPreviousVideo() {
     if (sortedLibrary.length === 0) return;
@@ -699,39 +853,109 @@ function App() {
   const [previewCache, setPreviewCache] = useState<Record<string, string>>({});
   const [posterCache, setPosterCache] = useState<Record<string, string>>({});
 
-  // Load posters for library entries (and handle folders correctly)
+  // Identify the open folder in the backend; posters stream in as each entry is matched
   useEffect(() => {
-    if (library.length === 0) return;
-    library.forEach(async (entry) => {
-      if (posterCache[entry.path]) return;
+    if (!libraryFolder || library.length === 0) return;
+    let unlisten: (() => void) | undefined;
+    let cancelled = false;
+    listen<IdentifyProgress>('identify-progress', async (event) => {
+      const { folder, item } = event.payload;
+      if (folder !== libraryFolder || !item.poster) return;
       try {
-        // Check if poster already exists on disk
-        const posterPath = await invoke<string | null>('check_poster_exists', { videoPath: entry.path });
-        if (posterPath) {
-          const dataUrl = await invoke<string>('read_poster', { posterPath });
-          setPosterCache(prev => ({ ...prev, [entry.path]: dataUrl }));
-        } else {
-          // Fetching
-          if (entry.entry_type === 'folder') {
-            // User Request: "subfolder under anime folder"
-            if (entry.path.toLowerCase().includes('anime')) {
-              const fetched = await invoke<string | null>('fetch_folder_poster', { folderPath: entry.path });
-              if (fetched) {
-                const dataUrl = await invoke<string>('read_poster', { posterPath: fetched });
-                setPosterCache(prev => ({ ...prev, [entry.path]: dataUrl }));
-              }
-            }
-          }
-          // User Request: "dont extract thumbnail posters for any of files"
-          // We removed the else block that fetched metadata for videos.
-          // Videos will now only use local .poster.jpg (if exists) or generated thumbnails.
-        }
+        const dataUrl = await invoke<string>('read_poster', { posterPath: item.poster });
+        setPosterCache(prev => ({ ...prev, [item.path]: dataUrl }));
       } catch (e) {
         // Silently fail - poster is optional
-        console.debug('Poster not available for', entry.path, e);
+        console.debug('Poster not available for', item.path, e);
       }
+    }).then(u => {
+      if (cancelled) { u(); return; }
+      unlisten = u;
+      invoke('identify_folder', { path: libraryFolder }).catch(e => console.debug('Identify failed for', libraryFolder, e));
     });
-  }, [library]);
+    return () => { cancelled = true; if (unlisten) unlisten(); };
+  }, [library, libraryFolder]);
+
+  // Download progress of the torrent being streamed
+  useEffect(() => {
+    if (!activeTorrent) return;
+    const unlisten = listen<TorrentProgress[]>('torrent-progress', (event) => {
+      const current = event.payload.find(t => t.id === activeTorrent.id);
+      if (current) setActiveTorrent(current);
+    });
+    return () => { unlisten.then(f => f()); };
+  }, [activeTorrent?.id]);
+
+  const playStream = async (url: string) => {
+    setShowStreamDialog(false);
+    // The previous torrent stops seeding once something else plays
+    const previous = activeTorrent?.id;
+    const closePrevious = (next?: string) => {
+      if (previous && previous !== next) invoke('torrent_remove', { id: previous }).catch(() => {});
+    };
+    let target = url;
+    if (url.startsWith('magnet:') || url.toLowerCase().endsWith('.torrent')) {
+      try {
+        const info = await invoke<{ id: string; name: string; stream_url: string }>('torrent_add', { source: url });
+        setActiveTorrent({ id: info.id, name: info.name, state: 'initializing', progress_bytes: 0, total_bytes: 0, download_speed: 0, upload_speed: 0, peers: 0, finished: false, error: null });
+        closePrevious(info.id);
+        target = info.stream_url;
+      } catch (e) {
+        console.error('Failed to open torrent:', e);
+        return;
+      }
+    } else {
+      closePrevious();
+      setActiveTorrent(null);
+    }
+    setFile(target);
+    invoke('mpv_load_url', { url: target });
+    setIsPlayerActive(true);
+  };
+
+  const playStation = async (station: RadioStation) => {
+    setShowRadio(false);
+    if (activeTorrent) {
+      invoke('torrent_remove', { id: activeTorrent.id }).catch(() => {});
+      setActiveTorrent(null);
+    }
+    try {
+      await invoke('play_radio_station', { id: station.id });
+      setFile(station.url);
+      setIsPlaying(true);
+      setIsPlayerActive(true);
+    } catch (e) {
+      console.error('Failed to play station:', e);
+    }
+  };
+
+  const playEpisode = async (path: string) => {
+    setShowPodcasts(false);
+    try {
+      await invoke('play_podcast_episode', { path });
+      setFile(path);
+      setIsPlaying(true);
+      setIsPlayerActive(true);
+    } catch (e) {
+      console.error('Failed to play episode:', e);
+    }
+  };
+
+  const playChannel = async (playlistId: string, channel: { id: string; url: string }) => {
+    setShowLiveTv(false);
+    if (activeTorrent) {
+      invoke('torrent_remove', { id: activeTorrent.id }).catch(() => {});
+      setActiveTorrent(null);
+    }
+    try {
+      await invoke('play_iptv_channel', { id: playlistId, channelId: channel.id });
+      setFile(channel.url);
+      setIsPlaying(true);
+      setIsPlayerActive(true);
+    } catch (e) {
+      console.error('Failed to play channel:', e);
+    }
+  };
 
   // Process Queue Effect
   useEffect(() => {
@@ -823,7 +1047,7 @@ function App() {
           {/* Settings & Window Controls */}
           <div className="flex items-center gap-2">
             <button
-              onClick={() => setShowAppSettings(true)}
+              onClick={() => openArea('settings', () => setShowAppSettings(true))}
               className="pointer-events-auto p-2 text-slate-400 hover:text-white hover:bg-white/10 rounded-lg transition-all hover:rotate-90 duration-300"
               title="App Settings"
             >
@@ -836,7 +1060,7 @@ function App() {
           </div>
         </header>
       ) : (
-        !isFullscreen && (
+        !isFullscreen && !windowMode.mini_player && (
           /* Player Header with Back Navigation */
           <header data-tauri-drag-region className={`relative z-50 h-16 flex items-center justify-between px-6 bg-gradient-to-b from-black/80 to-transparent select-none transition-opacity duration-300 ${showControls ? 'opacity-100' : 'opacity-0 pointer-events-none'}`}>
             <div className="flex items-center gap-3">
@@ -1017,7 +1241,7 @@ function App() {
               </button>
             </div>
           ) : (
-            <HomeScreen onOpenFile={handleOpenFile} onOpenFolder={handleOpenFolder} onOpenStream={() => setShowStreamDialog(true)} />
+            <HomeScreen onOpenFile={handleOpenFile} onOpenFolder={handleOpenFolder} onOpenStream={() => setShowStreamDialog(true)} onOpenNetwork={() => loadFolder('net://')} onOpenLiveTv={() => openArea('iptv', () => setShowLiveTv(true))} onOpenRadio={() => setShowRadio(true)} onOpenPodcasts={() => setShowPodcasts(true)} onOpenConvert={() => setShowConversions(true)} />
           )}
         </main>
       )}
@@ -1026,21 +1250,66 @@ function App() {
       <StreamDialog
         isOpen={showStreamDialog}
         onClose={() => setShowStreamDialog(false)}
-        onPlay={(url) => {
-          setShowStreamDialog(false);
-          setFile(url);
-          invoke('mpv_load_url', { url });
-          setIsPlayerActive(true);
-        }}
+        onPlay={playStream}
+      />
+
+      {/* Live TV */}
+      <IptvGuide
+        isOpen={showLiveTv}
+        onClose={() => setShowLiveTv(false)}
+        onPlay={playChannel}
+      />
+
+      {/* Radio */}
+      <RadioStations
+        isOpen={showRadio}
+        onClose={() => setShowRadio(false)}
+        onPlay={playStation}
+      />
+
+      {/* Podcasts */}
+      <Podcasts
+        isOpen={showPodcasts}
+        onClose={() => setShowPodcasts(false)}
+        onPlay={playEpisode}
+      />
+
+      {/* Watch Party */}
+      <WatchParty
+        isOpen={showWatchParty}
+        onClose={() => setShowWatchParty(false)}
+      />
+
+      {/* Skip Segments */}
+      <SegmentEditor
+        isOpen={showSegments}
+        onClose={() => setShowSegments(false)}
+        path={fileSegments?.path ?? null}
+        currentTime={currentTime}
+        segments={fileSegments?.segments ?? []}
+      />
+
+      {/* Clip Export */}
+      <ClipExport
+        isOpen={showClipExport}
+        onClose={() => setShowClipExport(false)}
+        path={currentFile}
+        currentTime={currentTime}
+      />
+
+      {/* Batch Conversion */}
+      <Conversions
+        isOpen={showConversions}
+        onClose={() => setShowConversions(false)}
       />
 
       {/* Playlist Manager */}
       <PlaylistManager
         isOpen={showPlaylistManager}
         onClose={() => setShowPlaylistManager(false)}
-        onPlayFile={(path) => {
+        onPlayPlaylist={(id, startIndex) => {
           setShowPlaylistManager(false);
-          handlePlay(path);
+          handlePlayPlaylist(id, startIndex);
         }}
         libraryFiles={library.filter(e => e.entry_type === 'video').map(e => e.path)}
       />
@@ -1062,9 +1331,47 @@ function App() {
         <AISidebar videoPath={currentFile} />
       )}
 
-      {isPlayerActive && (
+      {isPlayerActive && activeSegment && (
+        <button
+          onClick={() => invoke("mpv_seek_absolute", { position: activeSegment.end })}
+          className="absolute right-8 bottom-44 z-50 px-5 py-2.5 bg-white/90 hover:bg-white text-slate-950 text-sm font-semibold rounded-lg shadow-lg flex items-center gap-2 transition-colors"
+        >
+          Skip {SEGMENT_LABELS[activeSegment.kind] ?? activeSegment.kind} <SkipForward size={16} />
+        </button>
+      )}
+
+      {/* Mini-player: header and footer give way to a drag strip and a small control bar */}
+      {windowMode.mini_player && (
+        <div className={`fixed inset-0 z-[60] pointer-events-none select-none transition-opacity duration-300 ${showControls ? 'opacity-100' : 'opacity-0'}`}>
+          <div data-tauri-drag-region className={`absolute inset-x-0 top-0 h-8 bg-gradient-to-b from-black/70 to-transparent ${showControls ? 'pointer-events-auto' : ''}`} />
+          <div className={`absolute inset-x-0 bottom-0 flex items-center justify-center gap-2 p-2 bg-gradient-to-t from-black/80 to-transparent ${showControls ? 'pointer-events-auto' : ''}`}>
+            {isPlayerActive && (
+              <button onClick={togglePause} className="w-9 h-9 rounded-full bg-white text-slate-950 flex items-center justify-center hover:scale-110 transition-all" title={isPlaying ? "Pause" : "Play"}>
+                {isPlaying ? <Pause size={16} fill="currentColor" /> : <Play size={16} fill="currentColor" className="ml-0.5" />}
+              </button>
+            )}
+            <button onClick={toggleMiniPlayer} className="p-2 text-white/80 hover:text-white hover:bg-white/20 rounded-full" title="Exit Mini Player">
+              <Maximize2 size={16} />
+            </button>
+          </div>
+        </div>
+      )}
+
+      {isPlayerActive && !windowMode.mini_player && (
         <footer className={`relative z-50 min-h-20 px-4 md:px-8 py-3 backdrop-blur-xl bg-slate-950/90 border-t border-white/5 flex flex-col gap-3 mt-auto transition-opacity duration-300 ${!showControls ? 'opacity-0 pointer-events-none' : 'opacity-100'}`}>
 
+          {activeTorrent && !activeTorrent.finished && currentFile?.includes(activeTorrent.id) && (
+            <div className="text-[10px] text-slate-400 px-1 truncate">
+              {activeTorrent.error
+                ? <span className="text-red-400">{activeTorrent.error}</span>
+                : `${activeTorrent.name} · ${activeTorrent.total_bytes ? Math.floor(activeTorrent.progress_bytes / activeTorrent.total_bytes * 100) : 0}% · ${activeTorrent.download_speed.toFixed(1)} MiB/s · ${activeTorrent.peers} peers`}
+            </div>
+          )}
+
+          {streamTitle && (
+            <div className="text-[10px] text-slate-400 px-1 truncate">♪ {streamTitle}</div>
+          )}
+
           {/* Progress Bar */}
           <div className="w-full flex items-center gap-3 px-1 my-1 relative group/timeline">
             <TimelinePreview
@@ -1075,6 +1382,18 @@ function App() {
             />
 
             <span className="text-xs font-medium text-slate-300 w-12 text-right">{formatTime(currentTime)}</span>
+            {/* Segment markers, drawn over the track */}
+            {duration > 0 && fileSegments?.segments.map(s => (
+              <div
+                key={s.id}
+                className="absolute top-1/2 -translate-y-1/2 h-1 bg-amber-400/60 rounded-full pointer-events-none z-30"
+                style={{
+                  left: `calc(4rem + (100% - 8rem) * ${s.start / duration})`,
+                  width: `calc((100% - 8rem) * ${(Math.min(s.end, duration) - s.start) / duration})`
+                }}
+                title={SEGMENT_LABELS[s.kind] ?? s.kind}
+              />
+            ))}
             <input
               type="range"
               min={0}
@@ -1160,6 +1479,33 @@ function App() {
               <Brain size={20} />
             </button>
 
+            {/* Watch Party */}
+            <button
+              onClick={() => setShowWatchParty(true)}
+              className="p-2 rounded-lg text-slate-400 hover:text-white hover:bg-white/10 transition-all"
+              title="Watch Party"
+            >
+              <Users size={20} />
+            </button>
+
+            {/* Skip Segments */}
+            <button
+              onClick={() => setShowSegments(true)}
+              className="p-2 rounded-lg text-slate-400 hover:text-white hover:bg-white/10 transition-all"
+              title="Skip Segments"
+            >
+              <Scissors size={20} />
+            </button>
+
+            {/* Clip Export */}
+            <button
+              onClick={() => setShowClipExport(true)}
+              className="p-2 rounded-lg text-slate-400 hover:text-white hover:bg-white/10 transition-all"
+              title="Export Clip"
+            >
+              <Clapperboard size={20} />
+            </button>
+
             <div className="w-px h-8 bg-white/10 mx-2" />
 
             {/* Volume Control */}
@@ -1267,12 +1613,26 @@ function App() {
               </div>
 
               <button
-                onClick={() => setShowAppSettings(!showAppSettings)}
+                onClick={() => showAppSettings ? setShowAppSettings(false) : openArea('settings', () => setShowAppSettings(true))}
                 className="p-2 text-white/70 hover:text-white hover:bg-white/10 rounded-full transition-all"
                 title="Settings"
               >
                 <Settings size={20} />
               </button>
+              <button
+                onClick={toggleAlwaysOnTop}
+                className={`p-2 rounded-full transition-all ${windowMode.always_on_top ? 'text-violet-400 bg-white/10' : 'text-white/70 hover:text-white hover:bg-white/10'}`}
+                title={windowMode.always_on_top ? "Stop Keeping on Top" : "Always on Top"}
+              >
+                <Pin size={20} />
+              </button>
+              <button
+                onClick={toggleMiniPlayer}
+                className="p-2 text-white/70 hover:text-white hover:bg-white/10 rounded-full transition-all"
+                title="Mini Player"
+              >
+                <PictureInPicture2 size={20} />
+              </button>
               <button
                 onClick={toggleFullscreen}
                 className="p-2 text-white/70 hover:text-white hover:bg-white/10 rounded-full transition-all"
@@ -1295,6 +1655,16 @@ function App() {
         />
       )}
 
+      <UpdateBanner />
+
+      {pinGate && (
+        <PinPrompt
+          message={pinGate.message}
+          onUnlocked={() => { pinGate.then(); setPinGate(null); }}
+          onClose={() => setPinGate(null)}
+        />
+      )}
+
       {/* Info Modals */}
       {/* Info Modals */}
       {currentInfo && infoModalType === 'movie' && (
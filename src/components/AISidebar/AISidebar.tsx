@@ -55,17 +55,32 @@ export const AISidebar: React.FC<AISidebarProps> = ({ videoPath }) => {
     const [whisperModel, setWhisperModel] = useState('base');
     const [whisperLanguage, setWhisperLanguage] = useState('auto');
     const [showAdvanced, setShowAdvanced] = useState(false);
+    const jobIdRef = React.useRef<string | null>(null);
+    const [audioTracks, setAudioTracks] = useState<{ index: number; language?: string; title?: string; codec: string }[]>([]);
+    const [audioTrack, setAudioTrack] = useState(0);
+    const [outputFormat, setOutputFormat] = useState<'vtt' | 'srt' | 'ass'>('vtt');
+    const [diarize, setDiarize] = useState(false);
 
     // Info state
     const [videoInfo, setVideoInfo] = useState<Record<string, string>>({});
 
     React.useEffect(() => {
         const unlisten = listen('whisper-progress', (event: any) => {
-            setProgress(event.payload as string);
+            if (event.payload.job_id !== jobIdRef.current) return;
+            setProgress(event.payload.status as string);
         });
         return () => { unlisten.then(fn => fn()); };
     }, []);
 
+    React.useEffect(() => {
+        setAudioTracks([]);
+        setAudioTrack(0);
+        if (!videoPath) return;
+        invoke<typeof audioTracks>('list_audio_tracks', { videoPath })
+            .then(setAudioTracks)
+            .catch(() => setAudioTracks([]));
+    }, [videoPath]);
+
     React.useEffect(() => {
         if (videoPath) {
             // Extract basic file info
@@ -79,28 +94,45 @@ export const AISidebar: React.FC<AISidebarProps> = ({ videoPath }) => {
         }
     }, [videoPath]);
 
-    const handleGenerate = async () => {
+    const handleGenerate = async (task: 'transcribe' | 'translate' = 'transcribe') => {
         if (!videoPath) return;
         setLoading(true);
         setError('');
         setProgress('Starting Whisper...');
         setSubtitlesPath('');
 
+        const jobId = crypto.randomUUID();
+        jobIdRef.current = jobId;
+
         try {
-            const result = await invoke<string>('run_whisper', {
+            const result = await invoke<{ path: string; language: string }>('run_whisper', {
                 videoPath,
-                model: whisperModel,
+                options: {
+                    model: whisperModel,
+                    language: whisperLanguage,
+                    task,
+                    audio_track: audioTrack,
+                    output_format: outputFormat,
+                    diarize,
+                    job_id: jobId,
+                },
             });
-            setSubtitlesPath(result);
+            setSubtitlesPath(result.path);
             setProgress('');
         } catch (e) {
             setError(String(e));
             setProgress('');
         } finally {
+            jobIdRef.current = null;
             setLoading(false);
         }
     };
 
+    const handleCancel = async () => {
+        if (!jobIdRef.current) return;
+        await invoke('cancel_whisper', { jobId: jobIdRef.current });
+    };
+
     const renderSubtitlesTab = () => (
         <div className="space-y-4">
             {/* AI Whisper Generation */}
@@ -154,11 +186,53 @@ export const AISidebar: React.FC<AISidebarProps> = ({ videoPath }) => {
                                 ))}
                             </select>
                         </div>
+                        <div>
+                            <label className="text-[10px] text-slate-500 uppercase tracking-wider font-semibold block mb-1">Output Format</label>
+                            <div className="grid grid-cols-3 gap-1">
+                                {(['vtt', 'srt', 'ass'] as const).map(f => (
+                                    <button
+                                        key={f}
+                                        onClick={() => setOutputFormat(f)}
+                                        className={`px-2 py-1.5 text-[10px] rounded-md transition-all font-medium uppercase ${outputFormat === f
+                                            ? 'bg-blue-500/30 text-blue-300 border border-blue-500/40'
+                                            : 'bg-white/5 text-slate-400 hover:text-white border border-transparent'
+                                            }`}
+                                    >
+                                        {f}
+                                    </button>
+                                ))}
+                            </div>
+                        </div>
+                        <label className="flex items-center justify-between text-xs text-slate-400 cursor-pointer">
+                            <span>Speaker labels (English)</span>
+                            <input
+                                type="checkbox"
+                                checked={diarize}
+                                onChange={(e) => setDiarize(e.target.checked)}
+                                className="accent-blue-500"
+                            />
+                        </label>
+                        {audioTracks.length > 1 && (
+                            <div>
+                                <label className="text-[10px] text-slate-500 uppercase tracking-wider font-semibold block mb-1">Audio Track</label>
+                                <select
+                                    value={audioTrack}
+                                    onChange={(e) => setAudioTrack(Number(e.target.value))}
+                                    className="w-full px-2 py-1.5 bg-white/5 border border-white/10 rounded-md text-xs text-white outline-none cursor-pointer"
+                                >
+                                    {audioTracks.map(t => (
+                                        <option key={t.index} value={t.index} className="bg-slate-900">
+                                            #{t.index + 1} {t.title || t.language || t.codec}
+                                        </option>
+                                    ))}
+                                </select>
+                            </div>
+                        )}
                     </div>
                 )}
 
                 <button
-                    onClick={handleGenerate}
+                    onClick={() => handleGenerate('transcribe')}
                     disabled={loading || !videoPath}
                     className="w-full py-2.5 bg-gradient-to-r from-blue-600 to-indigo-600 hover:from-blue-500 hover:to-indigo-500 disabled:from-slate-700 disabled:to-slate-700 disabled:text-slate-500 text-white font-semibold rounded-xl transition-all flex items-center justify-center gap-2 text-xs shadow-lg shadow-blue-500/10 disabled:shadow-none"
                 >
@@ -169,9 +243,22 @@ export const AISidebar: React.FC<AISidebarProps> = ({ videoPath }) => {
                     )}
                 </button>
 
+                <button
+                    onClick={() => handleGenerate('translate')}
+                    disabled={loading || !videoPath}
+                    className="w-full py-2 bg-white/5 hover:bg-white/10 disabled:text-slate-600 text-slate-300 font-medium rounded-xl transition-all flex items-center justify-center gap-2 text-xs border border-white/10"
+                >
+                    <Wand2 size={12} /> Translate to English
+                </button>
+
                 {progress && (
-                    <div className="p-2.5 bg-blue-500/10 border border-blue-500/20 rounded-lg">
+                    <div className="p-2.5 bg-blue-500/10 border border-blue-500/20 rounded-lg flex items-center justify-between gap-2">
                         <p className="text-[10px] text-blue-300 font-mono">{progress}</p>
+                        {loading && (
+                            <button onClick={handleCancel} className="text-[10px] text-slate-400 hover:text-red-300 transition-colors">
+                                Cancel
+                            </button>
+                        )}
                     </div>
                 )}
 
@@ -0,0 +1,244 @@
+import { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { open } from '@tauri-apps/plugin-dialog';
+import { X, Clapperboard, FolderOpen, Loader2, Music } from 'lucide-react';
+
+type ClipFormat = 'copy' | 'mp4' | 'gif' | 'webp';
+type ClipPreset = 'small' | 'medium' | 'large';
+
+interface ClipItem {
+    id: string;
+    video: string;
+    start: number;
+    end: number;
+    format: ClipFormat;
+    preset: ClipPreset;
+    status: 'encoding' | 'done' | 'failed' | 'cancelled';
+    progress: number;
+    output: string;
+    error: string | null;
+}
+
+interface ClipSettings {
+    output_dir: string | null;
+    default_format: ClipFormat;
+    default_preset: ClipPreset;
+}
+
+const FORMAT_LABELS: Record<ClipFormat, string> = {
+    copy: 'Lossless cut',
+    mp4: 'MP4',
+    gif: 'GIF',
+    webp: 'WebP',
+};
+
+const PRESET_LABELS: Record<ClipPreset, string> = {
+    small: 'Small',
+    medium: 'Medium',
+    large: 'Large',
+};
+
+interface ClipExportProps {
+    isOpen: boolean;
+    onClose: () => void;
+    path: string | null;
+    currentTime: number;
+}
+
+const formatTime = (secs: number) => {
+    const s = Math.floor(secs);
+    const h = Math.floor(s / 3600);
+    const m = Math.floor((s % 3600) / 60);
+    const pad = (n: number) => n.toString().padStart(2, '0');
+    return h > 0 ? `${h}:${pad(m)}:${pad(s % 60)}` : `${m}:${pad(s % 60)}`;
+};
+
+const fileName = (path: string) => path.split(/[\\/]/).pop() ?? path;
+
+// Stays mounted so exports started earlier keep reporting progress
+export default function ClipExport({ isOpen, onClose, path, currentTime }: ClipExportProps) {
+    const [settings, setSettings] = useState<ClipSettings | null>(null);
+    const [start, setStart] = useState<number | null>(null);
+    const [end, setEnd] = useState<number | null>(null);
+    const [clips, setClips] = useState<ClipItem[]>([]);
+    const [error, setError] = useState('');
+    const [audioFormat, setAudioFormat] = useState<'mp3' | 'flac' | 'aac'>('mp3');
+    const [extracting, setExtracting] = useState<number | null>(null);
+    const [extracted, setExtracted] = useState('');
+
+    useEffect(() => {
+        invoke<ClipSettings>('get_clip_settings').then(setSettings).catch(() => {});
+        invoke<ClipItem[]>('list_clips').then(setClips).catch(() => {});
+        const unlisten = listen<ClipItem>('clip-progress', (event) => {
+            const item = event.payload;
+            setClips(prev => prev.some(c => c.id === item.id) ? prev.map(c => c.id === item.id ? item : c) : [...prev, item]);
+        });
+        const unlistenAudio = listen<{ video: string; progress: number }>('audio-extraction', (event) => setExtracting(event.payload.progress));
+        return () => {
+            unlisten.then(f => f());
+            unlistenAudio.then(f => f());
+        };
+    }, []);
+
+    if (!isOpen || !settings) return null;
+
+    const saveSettings = (patch: Partial<ClipSettings>) => {
+        const next = { ...settings, ...patch };
+        setSettings(next);
+        invoke('save_clip_settings', { settings: next }).catch(e => setError(String(e)));
+    };
+
+    const chooseFolder = async () => {
+        const dir = await open({ directory: true, multiple: false });
+        if (typeof dir === 'string') saveSettings({ output_dir: dir });
+    };
+
+    const exportClip = async () => {
+        if (!path || start === null || end === null) return;
+        setError('');
+        try {
+            await invoke('export_clip', { video: path, start, end, format: settings.default_format, preset: settings.default_preset });
+        } catch (e) {
+            setError(String(e));
+        }
+    };
+
+    // The marked range when both ends are set, otherwise the whole file
+    const extractAudio = async () => {
+        if (!path) return;
+        setError('');
+        setExtracted('');
+        setExtracting(0);
+        try {
+            const ranged = start !== null && end !== null;
+            const output = await invoke<string>('extract_audio', {
+                video: path,
+                format: audioFormat,
+                start: ranged ? Math.min(start, end) : null,
+                end: ranged ? Math.max(start, end) : null,
+            });
+            setExtracted(fileName(output));
+        } catch (e) {
+            setError(String(e));
+        } finally {
+            setExtracting(null);
+        }
+    };
+
+    const clearFinished = async () => {
+        await invoke('clear_finished_clips');
+        setClips(await invoke<ClipItem[]>('list_clips'));
+    };
+
+    const markButton = (label: string, value: number | null, set: (t: number) => void) => (
+        <button
+            onClick={() => set(currentTime)}
+            className="flex-1 py-2 bg-white/5 hover:bg-white/10 border border-white/10 rounded-xl text-sm text-white transition-colors"
+        >
+            {label} <span className="font-mono text-slate-400">{value !== null ? formatTime(value) : '--:--'}</span>
+        </button>
+    );
+
+    const selectClass = "flex-1 px-3 py-2.5 bg-slate-900 border border-white/10 rounded-xl text-sm text-white outline-none focus:border-amber-500/50";
+
+    return (
+        <div className="fixed inset-0 z-[100] flex items-center justify-center bg-black/70 backdrop-blur-sm animate-fade-in" onClick={onClose}>
+            <div
+                className="w-full max-w-md mx-4 flex flex-col max-h-[80vh] bg-gradient-to-br from-slate-900 to-slate-950 border border-white/10 rounded-2xl shadow-2xl overflow-hidden animate-fade-in-up"
+                onClick={e => e.stopPropagation()}
+            >
+                {/* Header */}
+                <div className="flex items-center justify-between px-6 py-5 border-b border-white/10">
+                    <div className="flex items-center gap-3">
+                        <div className="p-2 bg-amber-500/20 rounded-lg">
+                            <Clapperboard size={22} className="text-amber-400" />
+                        </div>
+                        <div>
+                            <h2 className="text-xl font-bold text-white">Export Clip</h2>
+                            <p className="text-xs text-slate-500">Save part of this video as a clip or GIF</p>
+                        </div>
+                    </div>
+                    <button onClick={onClose} className="p-2 text-slate-400 hover:text-white hover:bg-white/10 rounded-lg transition-all">
+                        <X size={20} />
+                    </button>
+                </div>
+
+                <div className="p-6 space-y-3">
+                    <div className="flex gap-2">
+                        {markButton('Start', start, setStart)}
+                        {markButton('End', end, setEnd)}
+                    </div>
+                    <div className="flex gap-2">
+                        <select value={settings.default_format} onChange={(e) => saveSettings({ default_format: e.target.value as ClipFormat })} className={selectClass}>
+                            {Object.entries(FORMAT_LABELS).map(([value, label]) => <option key={value} value={value}>{label}</option>)}
+                        </select>
+                        <select
+                            value={settings.default_preset}
+                            onChange={(e) => saveSettings({ default_preset: e.target.value as ClipPreset })}
+                            disabled={settings.default_format === 'copy'}
+                            className={`${selectClass} disabled:opacity-40`}
+                        >
+                            {Object.entries(PRESET_LABELS).map(([value, label]) => <option key={value} value={value}>{label}</option>)}
+                        </select>
+                    </div>
+                    <button onClick={chooseFolder} className="w-full flex items-center gap-2 px-3 py-2 bg-white/5 hover:bg-white/10 rounded-xl text-xs text-slate-400 transition-colors" title="Clips folder">
+                        <FolderOpen size={14} className="shrink-0" />
+                        <span className="truncate">{settings.output_dir ?? 'Videos/FrameX Clips'}</span>
+                    </button>
+                    <button
+                        onClick={exportClip}
+                        disabled={!path || start === null || end === null}
+                        className="w-full py-2.5 bg-amber-600 hover:bg-amber-500 disabled:bg-slate-700 disabled:text-slate-500 text-white text-sm font-semibold rounded-xl transition-colors"
+                    >
+                        Export
+                    </button>
+                    <div className="flex gap-2 pt-1">
+                        <select value={audioFormat} onChange={(e) => setAudioFormat(e.target.value as 'mp3' | 'flac' | 'aac')} className={selectClass}>
+                            <option value="mp3">MP3</option>
+                            <option value="flac">FLAC</option>
+                            <option value="aac">AAC (.m4a)</option>
+                        </select>
+                        <button
+                            onClick={extractAudio}
+                            disabled={!path || extracting !== null}
+                            className="px-4 py-2.5 bg-white/10 hover:bg-white/15 disabled:opacity-40 text-white text-sm font-semibold rounded-xl transition-colors flex items-center gap-2"
+                            title={start !== null && end !== null ? 'Extract the marked range' : 'Extract the whole soundtrack'}
+                        >
+                            {extracting !== null ? <><Loader2 size={14} className="animate-spin" /> {extracting.toFixed(0)}%</> : <><Music size={14} /> Extract audio</>}
+                        </button>
+                    </div>
+                    {extracted && <p className="text-[11px] text-emerald-400 truncate">Saved {extracted}</p>}
+                    {error && <p className="text-[11px] text-red-400">{error}</p>}
+                </div>
+
+                {clips.length > 0 && (
+                    <div className="flex-1 overflow-y-auto custom-scrollbar px-6 pb-5 space-y-1">
+                        <div className="flex justify-end">
+                            <button onClick={clearFinished} className="text-[11px] text-slate-500 hover:text-white">Clear finished</button>
+                        </div>
+                        {clips.map(c => (
+                            <div key={c.id} className="px-3 py-2 bg-white/5 rounded-xl text-sm">
+                                <div className="flex items-center gap-2">
+                                    {c.status === 'encoding' && <Loader2 size={12} className="animate-spin text-amber-400 shrink-0" />}
+                                    <span className="flex-1 truncate text-white" title={c.output}>{fileName(c.output)}</span>
+                                    {c.status === 'encoding' ? (
+                                        <button onClick={() => invoke('cancel_clip', { id: c.id })} className="text-[11px] text-slate-400 hover:text-red-400">Cancel</button>
+                                    ) : (
+                                        <span className={`text-[11px] ${c.status === 'done' ? 'text-emerald-400' : 'text-slate-500'}`}>{c.status}</span>
+                                    )}
+                                </div>
+                                {c.status === 'encoding' && (
+                                    <div className="mt-1.5 h-1 bg-white/10 rounded-full overflow-hidden">
+                                        <div className="h-full bg-amber-500 transition-all" style={{ width: `${c.progress}%` }} />
+                                    </div>
+                                )}
+                                {c.error && <p className="mt-1 text-[11px] text-red-400">{c.error}</p>}
+                            </div>
+                        ))}
+                    </div>
+                )}
+            </div>
+        </div>
+    );
+}
@@ -0,0 +1,175 @@
+import { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { open } from '@tauri-apps/plugin-dialog';
+import { X, Repeat, FilePlus, FolderOpen, Loader2 } from 'lucide-react';
+
+interface ConversionPreset {
+    id: string;
+    label: string;
+    ext: string;
+}
+
+interface ConversionItem {
+    id: string;
+    input: string;
+    preset: string;
+    output: string;
+    status: 'queued' | 'converting' | 'done' | 'failed' | 'cancelled';
+    progress: number;
+    error: string | null;
+}
+
+interface ConversionSettings {
+    output_dir: string | null;
+}
+
+interface ConversionsProps {
+    isOpen: boolean;
+    onClose: () => void;
+}
+
+const fileName = (path: string) => path.split(/[\\/]/).pop() ?? path;
+
+// Stays mounted so the queue keeps updating while the dialog is closed
+export default function Conversions({ isOpen, onClose }: ConversionsProps) {
+    const [presets, setPresets] = useState<ConversionPreset[]>([]);
+    const [preset, setPreset] = useState('h264_1080p');
+    const [settings, setSettings] = useState<ConversionSettings>({ output_dir: null });
+    const [items, setItems] = useState<ConversionItem[]>([]);
+    const [error, setError] = useState('');
+
+    useEffect(() => {
+        invoke<ConversionPreset[]>('list_conversion_presets').then(setPresets).catch(() => {});
+        invoke<ConversionSettings>('get_conversion_settings').then(setSettings).catch(() => {});
+        invoke<ConversionItem[]>('list_conversions').then(setItems).catch(() => {});
+        const unlisten = listen<ConversionItem>('conversion-progress', (event) => {
+            const item = event.payload;
+            setItems(prev => prev.some(i => i.id === item.id) ? prev.map(i => i.id === item.id ? item : i) : [...prev, item]);
+        });
+        return () => { unlisten.then(f => f()); };
+    }, []);
+
+    if (!isOpen) return null;
+
+    const addFiles = async () => {
+        const selected = await open({
+            multiple: true,
+            filters: [{ name: 'Video', extensions: ['mp4', 'mkv', 'avi', 'mov', 'webm', 'flv', 'wmv'] }]
+        });
+        if (!selected) return;
+        setError('');
+        try {
+            await invoke('queue_conversions', { paths: Array.isArray(selected) ? selected : [selected], preset });
+        } catch (e) {
+            setError(String(e));
+        }
+    };
+
+    const chooseFolder = async () => {
+        const dir = await open({ directory: true, multiple: false });
+        const next = { output_dir: typeof dir === 'string' ? dir : null };
+        setSettings(next);
+        invoke('save_conversion_settings', { settings: next }).catch(e => setError(String(e)));
+    };
+
+    const clearFinished = async () => {
+        await invoke('clear_finished_conversions');
+        setItems(await invoke<ConversionItem[]>('list_conversions'));
+    };
+
+    const presetLabel = (id: string) => presets.find(p => p.id === id)?.label ?? id;
+
+    return (
+        <div className="fixed inset-0 z-[100] flex items-center justify-center bg-black/70 backdrop-blur-sm animate-fade-in" onClick={onClose}>
+            <div
+                className="w-full max-w-lg mx-4 flex flex-col max-h-[80vh] bg-gradient-to-br from-slate-900 to-slate-950 border border-white/10 rounded-2xl shadow-2xl overflow-hidden animate-fade-in-up"
+                onClick={e => e.stopPropagation()}
+            >
+                {/* Header */}
+                <div className="flex items-center justify-between px-6 py-5 border-b border-white/10">
+                    <div className="flex items-center gap-3">
+                        <div className="p-2 bg-sky-500/20 rounded-lg">
+                            <Repeat size={22} className="text-sky-400" />
+                        </div>
+                        <div>
+                            <h2 className="text-xl font-bold text-white">Convert</h2>
+                            <p className="text-xs text-slate-500">Make device-friendly copies of your videos</p>
+                        </div>
+                    </div>
+                    <button onClick={onClose} className="p-2 text-slate-400 hover:text-white hover:bg-white/10 rounded-lg transition-all">
+                        <X size={20} />
+                    </button>
+                </div>
+
+                <div className="p-6 space-y-3">
+                    <div className="flex gap-2">
+                        <select
+                            value={preset}
+                            onChange={(e) => setPreset(e.target.value)}
+                            className="flex-1 px-3 py-2.5 bg-slate-900 border border-white/10 rounded-xl text-sm text-white outline-none focus:border-sky-500/50"
+                        >
+                            {presets.map(p => <option key={p.id} value={p.id}>{p.label} (.{p.ext})</option>)}
+                        </select>
+                        <button
+                            onClick={addFiles}
+                            className="px-4 py-2.5 bg-sky-600 hover:bg-sky-500 text-white text-sm font-semibold rounded-xl transition-colors flex items-center gap-2"
+                        >
+                            <FilePlus size={16} /> Add files
+                        </button>
+                    </div>
+                    <div className="flex items-center gap-2">
+                        <button onClick={chooseFolder} className="flex-1 min-w-0 flex items-center gap-2 px-3 py-2 bg-white/5 hover:bg-white/10 rounded-xl text-xs text-slate-400 transition-colors" title="Output folder">
+                            <FolderOpen size={14} className="shrink-0" />
+                            <span className="truncate">{settings.output_dir ?? 'Next to each source file'}</span>
+                        </button>
+                        {settings.output_dir && (
+                            <button
+                                onClick={() => {
+                                    setSettings({ output_dir: null });
+                                    invoke('save_conversion_settings', { settings: { output_dir: null } }).catch(e => setError(String(e)));
+                                }}
+                                className="text-[11px] text-slate-500 hover:text-white"
+                            >
+                                Reset
+                            </button>
+                        )}
+                    </div>
+                    {error && <p className="text-[11px] text-red-400">{error}</p>}
+                </div>
+
+                <div className="flex-1 overflow-y-auto custom-scrollbar px-6 pb-5 space-y-1">
+                    {items.length === 0 && <p className="text-sm text-slate-500 italic">Nothing queued</p>}
+                    {items.length > 0 && (
+                        <div className="flex justify-end">
+                            <button onClick={clearFinished} className="text-[11px] text-slate-500 hover:text-white">Clear finished</button>
+                        </div>
+                    )}
+                    {items.map(item => (
+                        <div key={item.id} className="px-3 py-2 bg-white/5 rounded-xl text-sm">
+                            <div className="flex items-center gap-2">
+                                {item.status === 'converting' && <Loader2 size={12} className="animate-spin text-sky-400 shrink-0" />}
+                                <span className="flex-1 truncate text-white" title={item.output}>{fileName(item.input)}</span>
+                                <span className="text-[10px] text-slate-500 shrink-0">{presetLabel(item.preset)}</span>
+                                {item.status === 'queued' || item.status === 'converting' ? (
+                                    <button onClick={() => invoke('cancel_conversion', { id: item.id })} className="text-[11px] text-slate-400 hover:text-red-400">Cancel</button>
+                                ) : (
+                                    <span className={`text-[11px] ${item.status === 'done' ? 'text-emerald-400' : 'text-slate-500'}`}>{item.status}</span>
+                                )}
+                            </div>
+                            {item.status === 'converting' && (
+                                <div className="mt-1.5 flex items-center gap-2">
+                                    <div className="flex-1 h-1 bg-white/10 rounded-full overflow-hidden">
+                                        <div className="h-full bg-sky-500 transition-all" style={{ width: `${item.progress}%` }} />
+                                    </div>
+                                    <span className="text-[10px] font-mono text-slate-400">{item.progress.toFixed(0)}%</span>
+                                </div>
+                            )}
+                            {item.error && <p className="mt-1 text-[11px] text-red-400">{item.error}</p>}
+                        </div>
+                    ))}
+                </div>
+            </div>
+        </div>
+    );
+}
@@ -0,0 +1,108 @@
+import { useState, useEffect, useRef } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { save } from '@tauri-apps/plugin-dialog';
+import { ScrollText, RefreshCw, Package } from 'lucide-react';
+
+const LEVELS = ['error', 'warn', 'info', 'debug', 'trace'];
+
+interface CrashReport {
+    file: string;
+    time: number;
+    message: string;
+}
+
+const lineColor = (line: string) => {
+    if (line.includes(' ERROR ')) return 'text-red-400';
+    if (line.includes(' WARN ')) return 'text-amber-300';
+    if (line.includes(' DEBUG ') || line.includes(' TRACE ')) return 'text-slate-500';
+    return 'text-slate-300';
+};
+
+export default function DebugLogs() {
+    const [level, setLevel] = useState('info');
+    const [lines, setLines] = useState<string[]>([]);
+    const [filter, setFilter] = useState('');
+    const [status, setStatus] = useState('');
+    const [crashes, setCrashes] = useState<CrashReport[]>([]);
+    const bottomRef = useRef<HTMLDivElement>(null);
+
+    const refresh = () => invoke<string[]>('get_recent_logs', { limit: 500, filter }).then(setLines).catch(() => {});
+
+    useEffect(() => {
+        invoke<string>('get_log_level').then(setLevel).catch(() => {});
+        invoke<CrashReport[]>('list_crash_reports').then(setCrashes).catch(() => {});
+    }, []);
+
+    useEffect(() => {
+        refresh();
+        const timer = setInterval(refresh, 2000);
+        return () => clearInterval(timer);
+    }, [filter]);
+
+    useEffect(() => {
+        bottomRef.current?.scrollIntoView({ block: 'nearest' });
+    }, [lines.length]);
+
+    const changeLevel = (next: string) => {
+        setLevel(next);
+        invoke('set_log_level', { level: next }).catch(e => setStatus(String(e)));
+    };
+
+    const exportBundle = async () => {
+        const path = await save({ defaultPath: 'framex-diagnostics.zip', filters: [{ name: 'Zip archive', extensions: ['zip'] }] });
+        if (!path) return;
+        try {
+            await invoke('export_diagnostics', { path });
+            setStatus(`Saved ${path}`);
+        } catch (e) {
+            setStatus(String(e));
+        }
+    };
+
+    return (
+        <div className="p-4 bg-white/5 rounded-lg border border-white/5">
+            <div className="flex items-center gap-3 mb-3">
+                <div className="p-2 bg-slate-500/20 rounded-lg">
+                    <ScrollText className="w-5 h-5 text-slate-300" />
+                </div>
+                <div className="flex-1">
+                    <h4 className="text-white font-medium">Logs</h4>
+                    <p className="text-xs text-slate-400">Recent activity, and a diagnostics zip to attach to bug reports</p>
+                </div>
+                <select
+                    value={level}
+                    onChange={e => changeLevel(e.target.value)}
+                    className="px-3 py-1.5 bg-slate-900 border border-white/10 rounded-lg text-white text-xs outline-none focus:border-white/30"
+                >
+                    {LEVELS.map(l => <option key={l} value={l}>{l}</option>)}
+                </select>
+            </div>
+            <div className="flex gap-2 mb-2">
+                <input
+                    value={filter}
+                    onChange={e => setFilter(e.target.value)}
+                    placeholder="Filter"
+                    className="flex-1 px-3 py-1.5 bg-white/10 border border-white/10 rounded-lg text-white text-xs outline-none focus:border-white/30"
+                />
+                <button onClick={refresh} className="p-2 bg-white/10 hover:bg-white/15 rounded-lg text-slate-300" title="Refresh">
+                    <RefreshCw size={14} />
+                </button>
+                <button onClick={exportBundle} className="px-3 py-1.5 bg-white/10 hover:bg-white/15 rounded-lg text-xs text-white flex items-center gap-1.5">
+                    <Package size={14} /> Diagnostics
+                </button>
+            </div>
+            {crashes.length > 0 && (
+                <div className="mb-2 px-3 py-2 bg-red-500/10 border border-red-500/20 rounded-lg text-xs text-red-300">
+                    FrameX crashed {crashes.length === 1 ? 'once' : `${crashes.length} times`} recently, last on {new Date(crashes[0].time * 1000).toLocaleString()}
+                    <p className="mt-1 font-mono text-[10px] text-red-400/80 truncate" title={crashes[0].message}>{crashes[0].message}</p>
+                </div>
+            )}
+            <div className="h-56 overflow-y-auto custom-scrollbar bg-black/40 rounded-lg p-2 font-mono text-[10px] leading-relaxed">
+                {lines.length === 0 && <p className="text-slate-500 italic">Nothing logged yet</p>}
+                {lines.map((line, i) => <div key={i} className={`whitespace-pre-wrap break-all ${lineColor(line)}`}>{line}</div>)}
+                <div ref={bottomRef} />
+            </div>
+            {status && <p className="mt-2 text-[11px] text-slate-400 truncate">{status}</p>}
+        </div>
+    );
+}
@@ -1,19 +1,154 @@
-import { useState, useEffect } from 'react';
+import { useState, useEffect, type KeyboardEvent } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
-import { X, Palette, Play, Layout, Info, Check, Brain, Keyboard, Monitor, Link2 } from 'lucide-react';
+import { X, Palette, Play, Layout, Info, Check, Brain, Keyboard, Monitor, Link2, Server, Trash2, Magnet, Download, Star, Music, SkipForward } from 'lucide-react';
 import KeyboardShortcutsEditor from '../KeyboardShortcutsEditor/KeyboardShortcutsEditor';
 import VideoAdjustments from '../VideoAdjustments/VideoAdjustments';
 import Equalizer from '../Equalizer/Equalizer';
 import ThemeEditor from '../ThemeEditor/ThemeEditor';
+import PluginManager from '../PluginManager/PluginManager';
+import HardwareReport from '../HardwareReport/HardwareReport';
+import DebugLogs from '../DebugLogs/DebugLogs';
+import ParentalControls from '../ParentalControls/ParentalControls';
+import UpdateSettings from '../Updates/UpdateSettings';
 import { useTheme } from '../../context/ThemeContext';
 import { useSettings } from '../../context/SettingsContext';
+import { describeSubtitleError, type SubtitleQuota } from '../../types/playlist';
 
 interface EnhancedSettingsModalProps {
     onClose: () => void;
     isPlayerActive: boolean;
 }
 
+interface PresenceSettings {
+    client_id: string;
+    hide_filenames: boolean;
+    excluded_folders: string[];
+    follow_private_mode: boolean;
+    details_template: string;
+    state_template: string;
+}
+
+interface NetworkSource {
+    id: string;
+    name: string;
+    kind: 'smb' | 'webdav' | 'ftp';
+    address: string;
+    username: string | null;
+    password: string | null;
+    mount_path: string | null;
+}
+
+const EMPTY_SOURCE: NetworkSource = { id: '', name: '', kind: 'smb', address: '', username: null, password: null, mount_path: null };
+
+const ADDRESS_HINTS: Record<NetworkSource['kind'], string> = {
+    smb: 'nas.local/Movies',
+    webdav: 'https://cloud.example.com/remote.php/dav/files/me',
+    ftp: 'nas.local:21/videos',
+};
+
+interface TorrentSettings {
+    cache_dir: string | null;
+    max_cache_gb: number;
+    download_limit_kbps: number;
+    upload_limit_kbps: number;
+    delete_on_close: boolean;
+}
+
+interface AnimeAccount {
+    username: string;
+}
+
+interface AnimeSyncSettings {
+    enabled: boolean;
+    threshold_percent: number;
+    anilist_client_id: string;
+    mal_client_id: string;
+    anilist: AnimeAccount | null;
+    mal: AnimeAccount | null;
+}
+
+interface AnimeMapping {
+    anilist_id: number;
+    title: string;
+    manual: boolean;
+}
+
+interface LastfmSettings {
+    enabled: boolean;
+    api_key: string;
+    api_secret: string;
+}
+
+interface LastfmStatus {
+    username: string | null;
+    queued: number;
+}
+
+type SegmentAction = 'skip' | 'prompt' | 'ignore';
+
+interface SegmentSettings {
+    actions: Record<string, SegmentAction>;
+    sponsorblock: boolean;
+}
+
+const SEGMENT_KINDS: { kind: string; label: string }[] = [
+    { kind: 'intro', label: 'Intros' },
+    { kind: 'recap', label: 'Recaps' },
+    { kind: 'credits', label: 'Credits' },
+    { kind: 'preview', label: 'Previews' },
+    { kind: 'sponsor', label: 'Sponsors' },
+];
+
+interface GlobalHotkeySettings {
+    enabled: boolean;
+    bindings: Record<string, string>;
+}
+
+const GLOBAL_HOTKEY_ACTIONS: [string, string][] = [
+    ['play_pause', 'Play / pause'],
+    ['stop', 'Stop'],
+    ['next', 'Next'],
+    ['previous', 'Previous'],
+    ['seek_forward', 'Seek forward 10s'],
+    ['seek_backward', 'Seek back 10s'],
+    ['volume_up', 'Volume up'],
+    ['volume_down', 'Volume down'],
+    ['mute', 'Mute'],
+    ['boss_key', 'Boss key (pause and hide)'],
+    ['always_on_top', 'Toggle always on top'],
+    ['mini_player', 'Toggle mini player'],
+];
+
+// KeyboardEvent -> accelerator the global shortcut plugin parses ("Alt+Shift+Right")
+const toAccelerator = (e: KeyboardEvent): string | null => {
+    if (['Control', 'Shift', 'Alt', 'Meta'].includes(e.key)) return null;
+    const key = e.code.replace(/^Key|^Digit/, '').replace(/^Arrow/, '');
+    return [e.ctrlKey && 'Ctrl', e.altKey && 'Alt', e.shiftKey && 'Shift', e.metaKey && 'Super', key].filter(Boolean).join('+');
+};
+
+interface DownloadSettings {
+    output_dir: string | null;
+    ytdlp_path: string | null;
+    default_format: string;
+    import_metadata: boolean;
+}
+
+type RpcStatus =
+    | { status: 'connected'; user: string | null }
+    | { status: 'disconnected' }
+    | { status: 'unavailable' }
+    | { status: 'error'; message: string };
+
+function describeRpcStatus(status: RpcStatus): string {
+    switch (status.status) {
+        case 'connected': return status.user ? `Connected as ${status.user}` : 'Connected';
+        case 'disconnected': return 'Off';
+        case 'unavailable': return 'Waiting for Discord to start…';
+        case 'error': return status.message;
+    }
+}
+
 interface Track {
     id: number;
     type: string;
@@ -34,6 +169,239 @@ export default function EnhancedSettingsModal({ onClose, isPlayerActive }: Enhan
     const [speed, setSpeed] = useState(settings.defaultSpeed);
     const [hwDec, setHwDec] = useState(settings.hardwareAcceleration);
 
+    // OpenSubtitles account
+    const [osUsername, setOsUsername] = useState('');
+    const [osPassword, setOsPassword] = useState('');
+    const [osQuota, setOsQuota] = useState<SubtitleQuota | null>(null);
+    const [osError, setOsError] = useState('');
+
+    useEffect(() => {
+        if (activeTab !== 'integrations') return;
+        invoke<SubtitleQuota>('get_subtitle_quota', { apiKey: settings.openSubtitlesApiKey || null })
+            .then(setOsQuota)
+            .catch(e => setOsError(describeSubtitleError(e)));
+    }, [activeTab, settings.openSubtitlesApiKey]);
+
+    // Discord presence privacy
+    const [presence, setPresence] = useState<PresenceSettings | null>(null);
+    const [excludedFolders, setExcludedFolders] = useState('');
+    const [privateMode, setPrivateMode] = useState(false);
+    const [clientId, setClientId] = useState('');
+    const [detailsTemplate, setDetailsTemplate] = useState('');
+    const [stateTemplate, setStateTemplate] = useState('');
+    const [rpcStatus, setRpcStatus] = useState<RpcStatus>({ status: 'disconnected' });
+
+    useEffect(() => {
+        invoke<RpcStatus>('discord_rpc_status').then(setRpcStatus).catch(() => {});
+        const unlisten = listen<RpcStatus>('discord-rpc-status', e => setRpcStatus(e.payload));
+        return () => { unlisten.then(f => f()); };
+    }, []);
+
+    useEffect(() => {
+        if (activeTab !== 'integrations') return;
+        invoke<PresenceSettings>('get_presence_settings').then(p => {
+            setPresence(p);
+            setExcludedFolders(p.excluded_folders.join('\n'));
+            setClientId(p.client_id);
+            setDetailsTemplate(p.details_template);
+            setStateTemplate(p.state_template);
+        }).catch(() => {});
+        invoke<boolean>('get_private_mode').then(setPrivateMode).catch(() => {});
+    }, [activeTab]);
+
+    // SMB / WebDAV / FTP locations browsed from Network Media
+    const [networkSources, setNetworkSources] = useState<NetworkSource[]>([]);
+    const [sourceDraft, setSourceDraft] = useState<NetworkSource>(EMPTY_SOURCE);
+    const [sourceError, setSourceError] = useState('');
+
+    useEffect(() => {
+        if (activeTab !== 'integrations') return;
+        invoke<NetworkSource[]>('list_network_sources').then(setNetworkSources).catch(() => {});
+    }, [activeTab]);
+
+    // Torrent streaming cache and bandwidth
+    const [torrentSettings, setTorrentSettings] = useState<TorrentSettings | null>(null);
+
+    useEffect(() => {
+        if (activeTab !== 'integrations') return;
+        invoke<TorrentSettings>('get_torrent_settings').then(setTorrentSettings).catch(() => {});
+    }, [activeTab]);
+
+    const saveTorrentSettings = (patch: Partial<TorrentSettings>) => {
+        if (!torrentSettings) return;
+        const next = { ...torrentSettings, ...patch };
+        setTorrentSettings(next);
+        invoke('save_torrent_settings', { settings: next }).catch(console.error);
+    };
+
+    // System-wide hotkeys
+    const [globalHotkeys, setGlobalHotkeys] = useState<GlobalHotkeySettings | null>(null);
+    const [capturingHotkey, setCapturingHotkey] = useState<string | null>(null);
+    const [hotkeyError, setHotkeyError] = useState('');
+
+    useEffect(() => {
+        if (activeTab !== 'shortcuts') return;
+        invoke<GlobalHotkeySettings>('get_global_hotkeys').then(setGlobalHotkeys).catch(() => {});
+    }, [activeTab]);
+
+    const saveGlobalHotkeys = async (next: GlobalHotkeySettings) => {
+        setGlobalHotkeys(next);
+        setHotkeyError('');
+        try {
+            const failed = await invoke<string[]>('save_global_hotkeys', { settings: next });
+            if (failed.length > 0) setHotkeyError(`In use by another application: ${failed.join(', ')}`);
+        } catch (e) {
+            setHotkeyError(String(e));
+        }
+    };
+
+    const setGlobalBinding = (action: string, accelerator: string) => {
+        if (!globalHotkeys) return;
+        saveGlobalHotkeys({ ...globalHotkeys, bindings: { ...globalHotkeys.bindings, [action]: accelerator } });
+    };
+
+    // yt-dlp downloads
+    const [downloadSettings, setDownloadSettings] = useState<DownloadSettings | null>(null);
+
+    useEffect(() => {
+        if (activeTab !== 'integrations') return;
+        invoke<DownloadSettings>('get_download_settings').then(setDownloadSettings).catch(() => {});
+    }, [activeTab]);
+
+    const saveDownloadSettings = (patch: Partial<DownloadSettings>) => {
+        if (!downloadSettings) return;
+        const next = { ...downloadSettings, ...patch };
+        setDownloadSettings(next);
+        invoke('save_download_settings', { settings: next }).catch(console.error);
+    };
+
+    // AniList / MyAnimeList progress sync
+    const [animeSync, setAnimeSync] = useState<AnimeSyncSettings | null>(null);
+    const [animeMappings, setAnimeMappings] = useState<Record<string, AnimeMapping>>({});
+    const [anilistToken, setAnilistToken] = useState('');
+    const [animeError, setAnimeError] = useState('');
+
+    const loadAnimeSync = () => {
+        invoke<AnimeSyncSettings>('get_anime_sync_settings').then(setAnimeSync).catch(() => {});
+        invoke<Record<string, AnimeMapping>>('list_anime_mappings').then(setAnimeMappings).catch(() => {});
+    };
+
+    useEffect(() => {
+        if (activeTab !== 'integrations') return;
+        loadAnimeSync();
+    }, [activeTab]);
+
+    const saveAnimeSync = (patch: Partial<AnimeSyncSettings>) => {
+        if (!animeSync) return;
+        const next = { ...animeSync, ...patch };
+        setAnimeSync(next);
+        invoke('save_anime_sync_settings', { settings: next }).catch(console.error);
+    };
+
+    const animeAction = async (action: () => Promise<unknown>) => {
+        setAnimeError('');
+        try {
+            await action();
+            loadAnimeSync();
+        } catch (e) {
+            setAnimeError(String(e));
+        }
+    };
+
+    // Skip segments
+    const [segmentSettings, setSegmentSettings] = useState<SegmentSettings | null>(null);
+
+    useEffect(() => {
+        if (activeTab !== 'playback') return;
+        invoke<SegmentSettings>('get_segment_settings').then(setSegmentSettings).catch(() => {});
+    }, [activeTab]);
+
+    const saveSegmentSettings = (patch: Partial<SegmentSettings>) => {
+        if (!segmentSettings) return;
+        const next = { ...segmentSettings, ...patch };
+        setSegmentSettings(next);
+        invoke('save_segment_settings', { settings: next }).catch(console.error);
+    };
+
+    // Last.fm scrobbling
+    const [lastfm, setLastfm] = useState<LastfmSettings | null>(null);
+    const [lastfmStatus, setLastfmStatus] = useState<LastfmStatus | null>(null);
+    const [lastfmBusy, setLastfmBusy] = useState(false);
+    const [lastfmError, setLastfmError] = useState('');
+
+    useEffect(() => {
+        if (activeTab !== 'integrations') return;
+        invoke<LastfmSettings>('get_lastfm_settings').then(setLastfm).catch(() => {});
+        invoke<LastfmStatus>('get_lastfm_status').then(setLastfmStatus).catch(() => {});
+    }, [activeTab]);
+
+    const saveLastfm = (patch: Partial<LastfmSettings>) => {
+        if (!lastfm) return;
+        const next = { ...lastfm, ...patch };
+        setLastfm(next);
+        invoke('save_lastfm_settings', { settings: next }).catch(console.error);
+    };
+
+    const lastfmAction = async (command: string) => {
+        setLastfmBusy(true);
+        setLastfmError('');
+        try {
+            await invoke(command);
+        } catch (e) {
+            setLastfmError(String(e));
+        } finally {
+            invoke<LastfmStatus>('get_lastfm_status').then(setLastfmStatus).catch(() => {});
+            setLastfmBusy(false);
+        }
+    };
+
+    const saveNetworkSource = async () => {
+        setSourceError('');
+        try {
+            await invoke('save_network_source', { source: sourceDraft });
+            setSourceDraft(EMPTY_SOURCE);
+            setNetworkSources(await invoke<NetworkSource[]>('list_network_sources'));
+        } catch (e) {
+            setSourceError(String(e));
+        }
+    };
+
+    const deleteNetworkSource = async (id: string) => {
+        await invoke('delete_network_source', { id }).catch(console.error);
+        setNetworkSources(sources => sources.filter(s => s.id !== id));
+    };
+
+    const savePresence = (patch: Partial<PresenceSettings>) => {
+        if (!presence) return;
+        const next = { ...presence, ...patch };
+        setPresence(next);
+        invoke('save_presence_settings', { settings: next }).catch(console.error);
+    };
+
+    const togglePrivateMode = () => {
+        setPrivateMode(!privateMode);
+        invoke('set_private_mode', { enabled: !privateMode }).catch(console.error);
+    };
+
+    const handleOsLogin = async () => {
+        setOsError('');
+        try {
+            setOsQuota(await invoke<SubtitleQuota>('opensubtitles_login', {
+                username: osUsername,
+                password: osPassword,
+                apiKey: settings.openSubtitlesApiKey || null,
+            }));
+            setOsPassword('');
+        } catch (e) {
+            setOsError(describeSubtitleError(e));
+        }
+    };
+
+    const handleOsLogout = async () => {
+        await invoke('opensubtitles_logout').catch(() => {});
+        setOsQuota(await invoke<SubtitleQuota>('get_subtitle_quota', { apiKey: settings.openSubtitlesApiKey || null }).catch(() => null));
+    };
+
     useEffect(() => {
         if (!isPlayerActive) return;
 
@@ -427,6 +795,42 @@ export default function EnhancedSettingsModal({ onClose, isPlayerActive }: Enhan
                                             ))}
                                         </select>
                                     </div>
+
+                                    {/* Skip Segments */}
+                                    {segmentSettings && (
+                                        <div className="p-4 bg-white/5 rounded-lg border border-white/5">
+                                            <div className="flex items-center gap-3 mb-3">
+                                                <div className="p-2 bg-violet-500/20 rounded-lg">
+                                                    <SkipForward className="w-5 h-5 text-violet-400" />
+                                                </div>
+                                                <div>
+                                                    <h4 className="text-white font-medium">Skip Segments</h4>
+                                                    <p className="text-xs text-slate-400">Intros, recaps and credits you or the community have marked</p>
+                                                </div>
+                                            </div>
+                                            <div className="space-y-2">
+                                                {SEGMENT_KINDS.map(({ kind, label }) => (
+                                                    <div key={kind} className="flex items-center justify-between text-sm">
+                                                        <span className="text-slate-300">{label}</span>
+                                                        <select
+                                                            value={segmentSettings.actions[kind] ?? 'prompt'}
+                                                            onChange={(e) => saveSegmentSettings({ actions: { ...segmentSettings.actions, [kind]: e.target.value as SegmentAction } })}
+                                                            className="px-3 py-1.5 bg-white/10 border border-white/10 rounded-lg text-white text-xs outline-none focus:border-white/30"
+                                                            style={{ backgroundColor: `${theme.colors.cardBg}`, color: theme.colors.text }}
+                                                        >
+                                                            <option value="skip">Skip automatically</option>
+                                                            <option value="prompt">Show skip button</option>
+                                                            <option value="ignore">Do nothing</option>
+                                                        </select>
+                                                    </div>
+                                                ))}
+                                                <label className="flex items-center justify-between pt-2 text-sm text-slate-300">
+                                                    <span>Look up SponsorBlock segments for YouTube videos</span>
+                                                    <input type="checkbox" checked={segmentSettings.sponsorblock} onChange={e => saveSegmentSettings({ sponsorblock: e.target.checked })} />
+                                                </label>
+                                            </div>
+                                        </div>
+                                    )}
                                 </div>
                             </div>
                         </div>
@@ -513,6 +917,8 @@ export default function EnhancedSettingsModal({ onClose, isPlayerActive }: Enhan
                                     </div>
                                 </div>
                             </div>
+                            <div className="h-px bg-white/5" />
+                            <ParentalControls />
                         </div>
                     )}
 
@@ -523,6 +929,48 @@ export default function EnhancedSettingsModal({ onClose, isPlayerActive }: Enhan
                                 <p className="text-sm text-slate-400 mb-4">Click on a key binding, then press the new key to remap it.</p>
                                 <KeyboardShortcutsEditor />
                             </div>
+
+                            {globalHotkeys && (
+                                <div>
+                                    <div className="flex items-center justify-between mb-2">
+                                        <h3 className="text-xl font-semibold text-white">Global Hotkeys</h3>
+                                        <label className="flex items-center gap-2 text-sm text-slate-300">
+                                            Enabled
+                                            <input type="checkbox" checked={globalHotkeys.enabled} onChange={e => saveGlobalHotkeys({ ...globalHotkeys, enabled: e.target.checked })} />
+                                        </label>
+                                    </div>
+                                    <p className="text-sm text-slate-400 mb-4">Work even when FrameX is minimized or in the background. Click a binding and press the new combination; Backspace clears it.</p>
+                                    <div className={`grid grid-cols-2 gap-2 ${globalHotkeys.enabled ? '' : 'opacity-50 pointer-events-none'}`}>
+                                        {GLOBAL_HOTKEY_ACTIONS.map(([action, label]) => (
+                                            <div key={action} className="flex items-center justify-between gap-2 px-3 py-2 bg-white/5 rounded-lg border border-white/5">
+                                                <span className="text-sm text-slate-300 truncate">{label}</span>
+                                                <button
+                                                    onClick={() => setCapturingHotkey(action)}
+                                                    onBlur={() => setCapturingHotkey(null)}
+                                                    onKeyDown={(e) => {
+                                                        if (capturingHotkey !== action) return;
+                                                        e.preventDefault();
+                                                        e.stopPropagation();
+                                                        if (e.key === 'Escape') return setCapturingHotkey(null);
+                                                        if (e.key === 'Backspace' || e.key === 'Delete') {
+                                                            setCapturingHotkey(null);
+                                                            return setGlobalBinding(action, '');
+                                                        }
+                                                        const accelerator = toAccelerator(e);
+                                                        if (!accelerator) return;
+                                                        setCapturingHotkey(null);
+                                                        setGlobalBinding(action, accelerator);
+                                                    }}
+                                                    className={`px-2 py-1 min-w-[7rem] rounded-md text-xs font-mono border transition-colors ${capturingHotkey === action ? 'border-blue-500 text-blue-300 bg-blue-500/10' : 'border-white/10 text-white bg-white/5 hover:bg-white/10'}`}
+                                                >
+                                                    {capturingHotkey === action ? 'Press keys…' : globalHotkeys.bindings[action] || 'Not set'}
+                                                </button>
+                                            </div>
+                                        ))}
+                                    </div>
+                                    {hotkeyError && <p className="mt-2 text-[11px] text-red-400">{hotkeyError}</p>}
+                                </div>
+                            )}
                         </div>
                     )}
 
@@ -573,6 +1021,10 @@ export default function EnhancedSettingsModal({ onClose, isPlayerActive }: Enhan
                             </div>
                             <div className="h-px bg-white/5" />
                             <Equalizer />
+                            <div className="h-px bg-white/5" />
+                            <HardwareReport />
+                            <div className="h-px bg-white/5" />
+                            <PluginManager />
                         </div>
                     )}
 
@@ -592,15 +1044,14 @@ export default function EnhancedSettingsModal({ onClose, isPlayerActive }: Enhan
                                                 </div>
                                                 <div>
                                                     <h4 className="text-white font-medium">Discord Rich Presence</h4>
-                                                    <p className="text-xs text-slate-400">Show what you're watching</p>
+                                                    <p className="text-xs text-slate-400">
+                                                        {settings.discordRPC ? describeRpcStatus(rpcStatus) : "Show what you're watching"}
+                                                    </p>
                                                 </div>
                                             </div>
                                             <button
                                                 onClick={() => {
-                                                    const newVal = !settings.discordRPC;
-                                                    updateSettings({ discordRPC: newVal });
-                                                    if (newVal) invoke('discord_rpc_connect');
-                                                    else invoke('discord_rpc_disconnect');
+                                                    updateSettings({ discordRPC: !settings.discordRPC });
                                                 }}
                                                 className={`relative w-12 h-6 rounded-full transition-colors ${settings.discordRPC ? 'bg-indigo-500' : 'bg-white/10'}`}
                                             >
@@ -610,6 +1061,59 @@ export default function EnhancedSettingsModal({ onClose, isPlayerActive }: Enhan
                                                 />
                                             </button>
                                         </div>
+                                        {presence && (
+                                            <div className="space-y-2 pt-3 border-t border-white/5">
+                                                <input
+                                                    type="text"
+                                                    value={clientId}
+                                                    onChange={e => setClientId(e.target.value)}
+                                                    onBlur={() => savePresence({ client_id: clientId })}
+                                                    placeholder="Discord application ID"
+                                                    className="w-full px-3 py-2 bg-white/5 border border-white/10 rounded-lg text-sm text-white placeholder-slate-500 outline-none focus:border-indigo-500/50 transition-colors font-mono"
+                                                />
+                                                <div className="grid grid-cols-2 gap-2">
+                                                    <input
+                                                        type="text"
+                                                        value={detailsTemplate}
+                                                        onChange={e => setDetailsTemplate(e.target.value)}
+                                                        onBlur={() => savePresence({ details_template: detailsTemplate })}
+                                                        placeholder="{title} ({year})"
+                                                        className="w-full px-3 py-2 bg-white/5 border border-white/10 rounded-lg text-sm text-white placeholder-slate-500 outline-none focus:border-indigo-500/50 transition-colors font-mono"
+                                                    />
+                                                    <input
+                                                        type="text"
+                                                        value={stateTemplate}
+                                                        onChange={e => setStateTemplate(e.target.value)}
+                                                        onBlur={() => savePresence({ state_template: stateTemplate })}
+                                                        placeholder="S{season}E{episode} · {status}"
+                                                        className="w-full px-3 py-2 bg-white/5 border border-white/10 rounded-lg text-sm text-white placeholder-slate-500 outline-none focus:border-indigo-500/50 transition-colors font-mono"
+                                                    />
+                                                </div>
+                                                <p className="text-[10px] text-slate-500">
+                                                    {'{title} {show} {season} {episode} {year} {filename} {status} {duration}; separate fallbacks with |'}
+                                                </p>
+                                                <label className="flex items-center justify-between text-sm text-slate-300">
+                                                    Hide titles (show "Watching a movie")
+                                                    <input type="checkbox" checked={presence.hide_filenames} onChange={e => savePresence({ hide_filenames: e.target.checked })} />
+                                                </label>
+                                                <label className="flex items-center justify-between text-sm text-slate-300">
+                                                    Hide while private mode is on
+                                                    <input type="checkbox" checked={presence.follow_private_mode} onChange={e => savePresence({ follow_private_mode: e.target.checked })} />
+                                                </label>
+                                                <label className="flex items-center justify-between text-sm text-slate-300">
+                                                    Private mode (no history, no presence)
+                                                    <input type="checkbox" checked={privateMode} onChange={togglePrivateMode} />
+                                                </label>
+                                                <textarea
+                                                    value={excludedFolders}
+                                                    onChange={e => setExcludedFolders(e.target.value)}
+                                                    onBlur={() => savePresence({ excluded_folders: excludedFolders.split('\n') })}
+                                                    placeholder={'Never broadcast from these folders, one per line\n(a full path, or a name like Private)'}
+                                                    rows={2}
+                                                    className="w-full px-3 py-2 bg-white/5 border border-white/10 rounded-lg text-sm text-white placeholder-slate-500 outline-none focus:border-indigo-500/50 transition-colors"
+                                                />
+                                            </div>
+                                        )}
                                     </div>
 
                                     {/* OpenSubtitles API Key */}
@@ -636,8 +1140,464 @@ export default function EnhancedSettingsModal({ onClose, isPlayerActive }: Enhan
                                         <p className="text-[10px] text-slate-500 mt-2">
                                             Get a free key at <span className="text-blue-400">opensubtitles.com/en/consumers</span>
                                         </p>
+
+                                        {/* Account login raises the daily download limit */}
+                                        <div className="mt-3 pt-3 border-t border-white/5">
+                                            {osQuota?.logged_in ? (
+                                                <div className="flex items-center justify-between">
+                                                    <span className="text-xs text-slate-300">
+                                                        Logged in as <span className="text-white">{osQuota.username}</span>
+                                                        {osQuota.vip && <span className="ml-1 text-amber-400">VIP</span>}
+                                                    </span>
+                                                    <button onClick={handleOsLogout} className="text-xs text-slate-400 hover:text-white transition-colors">
+                                                        Log out
+                                                    </button>
+                                                </div>
+                                            ) : (
+                                                <div className="flex gap-2">
+                                                    <input
+                                                        type="text"
+                                                        value={osUsername}
+                                                        onChange={(e) => setOsUsername(e.target.value)}
+                                                        placeholder="Username"
+                                                        className="flex-1 min-w-0 px-3 py-2 bg-white/5 border border-white/10 rounded-lg text-sm text-white placeholder-slate-500 outline-none focus:border-blue-500/50 transition-colors"
+                                                    />
+                                                    <input
+                                                        type="password"
+                                                        value={osPassword}
+                                                        onChange={(e) => setOsPassword(e.target.value)}
+                                                        onKeyDown={(e) => e.key === 'Enter' && handleOsLogin()}
+                                                        placeholder="Password"
+                                                        className="flex-1 min-w-0 px-3 py-2 bg-white/5 border border-white/10 rounded-lg text-sm text-white placeholder-slate-500 outline-none focus:border-blue-500/50 transition-colors"
+                                                    />
+                                                    <button
+                                                        onClick={handleOsLogin}
+                                                        disabled={!osUsername || !osPassword}
+                                                        className="px-3 py-2 bg-blue-500/20 text-blue-300 rounded-lg text-sm hover:bg-blue-500/30 disabled:opacity-40 transition-colors"
+                                                    >
+                                                        Log in
+                                                    </button>
+                                                </div>
+                                            )}
+                                            {osQuota?.remaining_downloads != null && (
+                                                <p className="text-[10px] text-slate-500 mt-2">
+                                                    {osQuota.remaining_downloads}
+                                                    {osQuota.allowed_downloads != null && ` of ${osQuota.allowed_downloads}`} downloads left today
+                                                    {osQuota.reset_time && ` · resets in ${osQuota.reset_time}`}
+                                                </p>
+                                            )}
+                                            {osError && <p className="text-[10px] text-red-400 mt-2">{osError}</p>}
+                                        </div>
                                     </div>
 
+                                    {/* Jimaku API Key */}
+                                    <div className="p-4 bg-white/5 rounded-lg border border-white/5">
+                                        <div className="flex items-center gap-3 mb-3">
+                                            <div className="p-2 bg-pink-500/20 rounded-lg">
+                                                <svg className="w-5 h-5 text-pink-400" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2">
+                                                    <rect x="2" y="5" width="20" height="14" rx="2" />
+                                                    <path d="M7 15h4M13 15h4M7 11h10" />
+                                                </svg>
+                                            </div>
+                                            <div>
+                                                <h4 className="text-white font-medium">Jimaku</h4>
+                                                <p className="text-xs text-slate-400">Japanese subtitles for anime, searched alongside OpenSubtitles</p>
+                                            </div>
+                                        </div>
+                                        <input
+                                            type="password"
+                                            value={settings.jimakuApiKey}
+                                            onChange={(e) => updateSettings({ jimakuApiKey: e.target.value })}
+                                            placeholder="Enter your Jimaku API key..."
+                                            className="w-full px-4 py-2.5 bg-white/5 border border-white/10 rounded-lg text-sm text-white placeholder-slate-500 outline-none focus:border-blue-500/50 transition-colors font-mono"
+                                        />
+                                        <p className="text-[10px] text-slate-500 mt-2">
+                                            Get a free key from your account page at <span className="text-blue-400">jimaku.cc</span>
+                                        </p>
+                                    </div>
+
+                                    {/* Network Sources */}
+                                    <div className="p-4 bg-white/5 rounded-lg border border-white/5">
+                                        <div className="flex items-center gap-3 mb-3">
+                                            <div className="p-2 bg-emerald-500/20 rounded-lg">
+                                                <Server className="w-5 h-5 text-emerald-400" />
+                                            </div>
+                                            <div>
+                                                <h4 className="text-white font-medium">Network Sources</h4>
+                                                <p className="text-xs text-slate-400">SMB shares, WebDAV and FTP servers under Network Media</p>
+                                            </div>
+                                        </div>
+                                        {networkSources.length > 0 && (
+                                            <div className="space-y-1 mb-3">
+                                                {networkSources.map(source => (
+                                                    <div key={source.id} className="flex items-center justify-between px-3 py-2 bg-white/5 rounded-lg">
+                                                        <button onClick={() => setSourceDraft({ ...source, password: null })} className="min-w-0 text-left">
+                                                            <span className="text-sm text-white">{source.name}</span>
+                                                            <span className="ml-2 text-xs text-slate-500 font-mono">{source.kind}://{source.address}</span>
+                                                        </button>
+                                                        <button onClick={() => deleteNetworkSource(source.id)} className="text-slate-400 hover:text-red-400 transition-colors">
+                                                            <Trash2 size={14} />
+                                                        </button>
+                                                    </div>
+                                                ))}
+                                            </div>
+                                        )}
+                                        <div className="space-y-2">
+                                            <div className="flex gap-2">
+                                                <select
+                                                    value={sourceDraft.kind}
+                                                    onChange={(e) => setSourceDraft({ ...sourceDraft, kind: e.target.value as NetworkSource['kind'] })}
+                                                    className="px-3 py-2 bg-white/10 border border-white/10 rounded-lg text-sm text-white appearance-none cursor-pointer outline-none focus:border-white/30 transition-colors"
+                                                    style={{ backgroundColor: theme.colors.cardBg, color: theme.colors.text }}
+                                                >
+                                                    <option value="smb">SMB</option>
+                                                    <option value="webdav">WebDAV</option>
+                                                    <option value="ftp">FTP</option>
+                                                </select>
+                                                <input
+                                                    type="text"
+                                                    value={sourceDraft.name}
+                                                    onChange={(e) => setSourceDraft({ ...sourceDraft, name: e.target.value })}
+                                                    placeholder="Name"
+                                                    className="flex-1 min-w-0 px-3 py-2 bg-white/5 border border-white/10 rounded-lg text-sm text-white placeholder-slate-500 outline-none focus:border-emerald-500/50 transition-colors"
+                                                />
+                                            </div>
+                                            <input
+                                                type="text"
+                                                value={sourceDraft.address}
+                                                onChange={(e) => setSourceDraft({ ...sourceDraft, address: e.target.value })}
+                                                placeholder={ADDRESS_HINTS[sourceDraft.kind]}
+                                                className="w-full px-3 py-2 bg-white/5 border border-white/10 rounded-lg text-sm text-white placeholder-slate-500 outline-none focus:border-emerald-500/50 transition-colors font-mono"
+                                            />
+                                            <div className="flex gap-2">
+                                                <input
+                                                    type="text"
+                                                    value={sourceDraft.username ?? ''}
+                                                    onChange={(e) => setSourceDraft({ ...sourceDraft, username: e.target.value })}
+                                                    placeholder="Username (optional)"
+                                                    className="flex-1 min-w-0 px-3 py-2 bg-white/5 border border-white/10 rounded-lg text-sm text-white placeholder-slate-500 outline-none focus:border-emerald-500/50 transition-colors"
+                                                />
+                                                <input
+                                                    type="password"
+                                                    value={sourceDraft.password ?? ''}
+                                                    onChange={(e) => setSourceDraft({ ...sourceDraft, password: e.target.value })}
+                                                    placeholder={sourceDraft.id ? 'Password (unchanged)' : 'Password'}
+                                                    className="flex-1 min-w-0 px-3 py-2 bg-white/5 border border-white/10 rounded-lg text-sm text-white placeholder-slate-500 outline-none focus:border-emerald-500/50 transition-colors"
+                                                />
+                                            </div>
+                                            {sourceDraft.kind === 'smb' && !navigator.userAgent.includes('Windows') && (
+                                                <input
+                                                    type="text"
+                                                    value={sourceDraft.mount_path ?? ''}
+                                                    onChange={(e) => setSourceDraft({ ...sourceDraft, mount_path: e.target.value })}
+                                                    placeholder="Where the share is mounted, e.g. /Volumes/Movies"
+                                                    className="w-full px-3 py-2 bg-white/5 border border-white/10 rounded-lg text-sm text-white placeholder-slate-500 outline-none focus:border-emerald-500/50 transition-colors font-mono"
+                                                />
+                                            )}
+                                            <div className="flex justify-end gap-2">
+                                                {sourceDraft.id && (
+                                                    <button onClick={() => setSourceDraft(EMPTY_SOURCE)} className="px-3 py-2 text-sm text-slate-400 hover:text-white transition-colors">
+                                                        Cancel
+                                                    </button>
+                                                )}
+                                                <button
+                                                    onClick={saveNetworkSource}
+                                                    disabled={!sourceDraft.address}
+                                                    className="px-3 py-2 bg-emerald-500/20 text-emerald-300 rounded-lg text-sm hover:bg-emerald-500/30 disabled:opacity-40 transition-colors"
+                                                >
+                                                    {sourceDraft.id ? 'Save' : 'Add source'}
+                                                </button>
+                                            </div>
+                                            {sourceError && <p className="text-[10px] text-red-400">{sourceError}</p>}
+                                        </div>
+                                    </div>
+
+                                    {/* Torrents */}
+                                    {torrentSettings && (
+                                        <div className="p-4 bg-white/5 rounded-lg border border-white/5">
+                                            <div className="flex items-center gap-3 mb-3">
+                                                <div className="p-2 bg-orange-500/20 rounded-lg">
+                                                    <Magnet className="w-5 h-5 text-orange-400" />
+                                                </div>
+                                                <div>
+                                                    <h4 className="text-white font-medium">Torrent Streaming</h4>
+                                                    <p className="text-xs text-slate-400">Magnet links and .torrent files from Open Stream</p>
+                                                </div>
+                                            </div>
+                                            <div className="space-y-2">
+                                                <input
+                                                    type="text"
+                                                    value={torrentSettings.cache_dir ?? ''}
+                                                    onChange={(e) => setTorrentSettings({ ...torrentSettings, cache_dir: e.target.value })}
+                                                    onBlur={() => saveTorrentSettings({})}
+                                                    placeholder="Cache folder (FrameX uses a subfolder; default: app data)"
+                                                    className="w-full px-3 py-2 bg-white/5 border border-white/10 rounded-lg text-sm text-white placeholder-slate-500 outline-none focus:border-orange-500/50 transition-colors font-mono"
+                                                />
+                                                <div className="grid grid-cols-3 gap-2">
+                                                    {([
+                                                        ['max_cache_gb', 'Cache limit (GB)'],
+                                                        ['download_limit_kbps', 'Download KiB/s'],
+                                                        ['upload_limit_kbps', 'Upload KiB/s'],
+                                                    ] as const).map(([key, label]) => (
+                                                        <label key={key} className="text-[10px] text-slate-500">
+                                                            {label}
+                                                            <input
+                                                                type="number"
+                                                                min={0}
+                                                                value={torrentSettings[key]}
+                                                                onChange={(e) => saveTorrentSettings({ [key]: Math.max(0, Number(e.target.value) || 0) })}
+                                                                className="w-full mt-1 px-3 py-2 bg-white/5 border border-white/10 rounded-lg text-sm text-white outline-none focus:border-orange-500/50 transition-colors"
+                                                            />
+                                                        </label>
+                                                    ))}
+                                                </div>
+                                                <p className="text-[10px] text-slate-500">0 means unlimited; the cache folder applies after a restart</p>
+                                                <label className="flex items-center justify-between text-sm text-slate-300">
+                                                    Delete downloaded files when a torrent is closed
+                                                    <input type="checkbox" checked={torrentSettings.delete_on_close} onChange={e => saveTorrentSettings({ delete_on_close: e.target.checked })} />
+                                                </label>
+                                            </div>
+                                        </div>
+                                    )}
+
+                                    {/* Downloads */}
+                                    {downloadSettings && (
+                                        <div className="p-4 bg-white/5 rounded-lg border border-white/5">
+                                            <div className="flex items-center gap-3 mb-3">
+                                                <div className="p-2 bg-red-500/20 rounded-lg">
+                                                    <Download className="w-5 h-5 text-red-400" />
+                                                </div>
+                                                <div>
+                                                    <h4 className="text-white font-medium">Video Downloads</h4>
+                                                    <p className="text-xs text-slate-400">Save online videos into the library with yt-dlp</p>
+                                                </div>
+                                            </div>
+                                            <div className="space-y-2">
+                                                <input
+                                                    type="text"
+                                                    value={downloadSettings.output_dir ?? ''}
+                                                    onChange={(e) => setDownloadSettings({ ...downloadSettings, output_dir: e.target.value })}
+                                                    onBlur={() => saveDownloadSettings({})}
+                                                    placeholder="Download folder (default: Videos/FrameX)"
+                                                    className="w-full px-3 py-2 bg-white/5 border border-white/10 rounded-lg text-sm text-white placeholder-slate-500 outline-none focus:border-red-500/50 transition-colors font-mono"
+                                                />
+                                                <div className="grid grid-cols-2 gap-2">
+                                                    <input
+                                                        type="text"
+                                                        value={downloadSettings.ytdlp_path ?? ''}
+                                                        onChange={(e) => setDownloadSettings({ ...downloadSettings, ytdlp_path: e.target.value })}
+                                                        onBlur={() => saveDownloadSettings({})}
+                                                        placeholder="yt-dlp path (default: PATH)"
+                                                        className="w-full px-3 py-2 bg-white/5 border border-white/10 rounded-lg text-sm text-white placeholder-slate-500 outline-none focus:border-red-500/50 transition-colors font-mono"
+                                                    />
+                                                    <input
+                                                        type="text"
+                                                        value={downloadSettings.default_format}
+                                                        onChange={(e) => setDownloadSettings({ ...downloadSettings, default_format: e.target.value })}
+                                                        onBlur={() => saveDownloadSettings({})}
+                                                        placeholder="bv*+ba/b"
+                                                        className="w-full px-3 py-2 bg-white/5 border border-white/10 rounded-lg text-sm text-white placeholder-slate-500 outline-none focus:border-red-500/50 transition-colors font-mono"
+                                                    />
+                                                </div>
+                                                <label className="flex items-center justify-between text-sm text-slate-300">
+                                                    Import thumbnail as poster and details as .nfo
+                                                    <input type="checkbox" checked={downloadSettings.import_metadata} onChange={e => saveDownloadSettings({ import_metadata: e.target.checked })} />
+                                                </label>
+                                            </div>
+                                        </div>
+                                    )}
+
+                                    {/* Anime tracking */}
+                                    {animeSync && (
+                                        <div className="p-4 bg-white/5 rounded-lg border border-white/5">
+                                            <div className="flex items-center justify-between mb-3">
+                                                <div className="flex items-center gap-3">
+                                                    <div className="p-2 bg-sky-500/20 rounded-lg">
+                                                        <Star className="w-5 h-5 text-sky-400" />
+                                                    </div>
+                                                    <div>
+                                                        <h4 className="text-white font-medium">Anime Tracking</h4>
+                                                        <p className="text-xs text-slate-400">Update AniList / MyAnimeList progress as episodes finish</p>
+                                                    </div>
+                                                </div>
+                                                <input type="checkbox" checked={animeSync.enabled} onChange={e => saveAnimeSync({ enabled: e.target.checked })} />
+                                            </div>
+                                            <div className="space-y-3">
+                                                <label className="flex items-center justify-between text-sm text-slate-300">
+                                                    Count an episode as watched at
+                                                    <span className="flex items-center gap-1">
+                                                        <input
+                                                            type="number"
+                                                            min={50}
+                                                            max={100}
+                                                            value={animeSync.threshold_percent}
+                                                            onChange={(e) => saveAnimeSync({ threshold_percent: Math.min(100, Math.max(50, Number(e.target.value) || 85)) })}
+                                                            className="w-16 px-2 py-1 bg-white/5 border border-white/10 rounded-lg text-sm text-white outline-none focus:border-sky-500/50"
+                                                        />
+                                                        %
+                                                    </span>
+                                                </label>
+
+                                                {/* AniList */}
+                                                <div className="space-y-2">
+                                                    <p className="text-xs font-semibold text-slate-400 uppercase tracking-wider">AniList</p>
+                                                    {animeSync.anilist ? (
+                                                        <div className="flex items-center justify-between text-sm text-slate-300">
+                                                            Connected as {animeSync.anilist.username}
+                                                            <button onClick={() => animeAction(() => invoke('anime_sync_logout', { service: 'anilist' }))} className="text-xs text-red-400 hover:text-red-300">Log out</button>
+                                                        </div>
+                                                    ) : (
+                                                        <>
+                                                            <div className="flex gap-2">
+                                                                <input
+                                                                    type="text"
+                                                                    value={animeSync.anilist_client_id}
+                                                                    onChange={(e) => setAnimeSync({ ...animeSync, anilist_client_id: e.target.value })}
+                                                                    onBlur={() => saveAnimeSync({})}
+                                                                    placeholder="AniList client ID"
+                                                                    className="flex-1 min-w-0 px-3 py-2 bg-white/5 border border-white/10 rounded-lg text-sm text-white placeholder-slate-500 outline-none focus:border-sky-500/50 transition-colors font-mono"
+                                                                />
+                                                                <button onClick={() => animeAction(() => invoke('anilist_open_login'))} className="px-3 py-2 bg-white/10 hover:bg-white/15 rounded-lg text-sm text-white">Authorize</button>
+                                                            </div>
+                                                            <div className="flex gap-2">
+                                                                <input
+                                                                    type="password"
+                                                                    value={anilistToken}
+                                                                    onChange={(e) => setAnilistToken(e.target.value)}
+                                                                    placeholder="Paste the token AniList shows"
+                                                                    className="flex-1 min-w-0 px-3 py-2 bg-white/5 border border-white/10 rounded-lg text-sm text-white placeholder-slate-500 outline-none focus:border-sky-500/50 transition-colors font-mono"
+                                                                />
+                                                                <button
+                                                                    onClick={() => animeAction(async () => { await invoke('anilist_login', { token: anilistToken }); setAnilistToken(''); })}
+                                                                    disabled={!anilistToken.trim()}
+                                                                    className="px-3 py-2 bg-sky-600 hover:bg-sky-500 disabled:bg-slate-700 disabled:text-slate-500 rounded-lg text-sm text-white"
+                                                                >
+                                                                    Connect
+                                                                </button>
+                                                            </div>
+                                                            <p className="text-[10px] text-slate-500">Register an API client at anilist.co/settings/developer with the redirect URL https://anilist.co/api/v2/oauth/pin</p>
+                                                        </>
+                                                    )}
+                                                </div>
+
+                                                {/* MyAnimeList */}
+                                                <div className="space-y-2">
+                                                    <p className="text-xs font-semibold text-slate-400 uppercase tracking-wider">MyAnimeList</p>
+                                                    {animeSync.mal ? (
+                                                        <div className="flex items-center justify-between text-sm text-slate-300">
+                                                            Connected as {animeSync.mal.username}
+                                                            <button onClick={() => animeAction(() => invoke('anime_sync_logout', { service: 'mal' }))} className="text-xs text-red-400 hover:text-red-300">Log out</button>
+                                                        </div>
+                                                    ) : (
+                                                        <>
+                                                            <div className="flex gap-2">
+                                                                <input
+                                                                    type="text"
+                                                                    value={animeSync.mal_client_id}
+                                                                    onChange={(e) => setAnimeSync({ ...animeSync, mal_client_id: e.target.value })}
+                                                                    onBlur={() => saveAnimeSync({})}
+                                                                    placeholder="MyAnimeList client ID"
+                                                                    className="flex-1 min-w-0 px-3 py-2 bg-white/5 border border-white/10 rounded-lg text-sm text-white placeholder-slate-500 outline-none focus:border-sky-500/50 transition-colors font-mono"
+                                                                />
+                                                                <button onClick={() => animeAction(() => invoke('mal_login'))} className="px-3 py-2 bg-white/10 hover:bg-white/15 rounded-lg text-sm text-white">Log in</button>
+                                                            </div>
+                                                            <p className="text-[10px] text-slate-500">Register an app at myanimelist.net/apiconfig with the redirect URL http://localhost:53682/callback</p>
+                                                        </>
+                                                    )}
+                                                </div>
+
+                                                {/* Show mappings */}
+                                                {Object.keys(animeMappings).length > 0 && (
+                                                    <div className="space-y-1">
+                                                        <p className="text-xs font-semibold text-slate-400 uppercase tracking-wider">Matched shows</p>
+                                                        {Object.entries(animeMappings).map(([show, mapping]) => (
+                                                            <div key={show} className="flex items-center gap-2 text-xs">
+                                                                <span className="flex-1 min-w-0 truncate text-slate-400" title={show}>{show}</span>
+                                                                <span className="truncate text-white" title={mapping.title}>{mapping.title}</span>
+                                                                <input
+                                                                    type="number"
+                                                                    defaultValue={mapping.anilist_id}
+                                                                    title="AniList ID"
+                                                                    onBlur={(e) => {
+                                                                        const id = Number(e.target.value);
+                                                                        if (id && id !== mapping.anilist_id) animeAction(() => invoke('set_anime_mapping', { show, anilistId: id }));
+                                                                    }}
+                                                                    className="w-20 px-2 py-1 bg-white/5 border border-white/10 rounded-md text-white font-mono outline-none focus:border-sky-500/50"
+                                                                />
+                                                                <button onClick={() => animeAction(() => invoke('delete_anime_mapping', { show }))} title="Forget match" className="p-1 text-slate-500 hover:text-red-400">
+                                                                    <Trash2 size={12} />
+                                                                </button>
+                                                            </div>
+                                                        ))}
+                                                    </div>
+                                                )}
+                                                {animeError && <p className="text-[11px] text-red-400">{animeError}</p>}
+                                            </div>
+                                        </div>
+                                    )}
+
+                                    {/* Last.fm */}
+                                    {lastfm && lastfmStatus && (
+                                        <div className="p-4 bg-white/5 rounded-lg border border-white/5">
+                                            <div className="flex items-center justify-between mb-3">
+                                                <div className="flex items-center gap-3">
+                                                    <div className="p-2 bg-rose-500/20 rounded-lg">
+                                                        <Music className="w-5 h-5 text-rose-400" />
+                                                    </div>
+                                                    <div>
+                                                        <h4 className="text-white font-medium">Last.fm</h4>
+                                                        <p className="text-xs text-slate-400">Scrobble music you play, from each file's tags</p>
+                                                    </div>
+                                                </div>
+                                                <input type="checkbox" checked={lastfm.enabled} onChange={e => saveLastfm({ enabled: e.target.checked })} />
+                                            </div>
+                                            <div className="space-y-2">
+                                                {lastfmStatus.username ? (
+                                                    <div className="flex items-center justify-between text-sm text-slate-300">
+                                                        Scrobbling as {lastfmStatus.username}
+                                                        <button onClick={() => lastfmAction('lastfm_logout')} className="text-xs text-red-400 hover:text-red-300">Log out</button>
+                                                    </div>
+                                                ) : (
+                                                    <>
+                                                        <div className="grid grid-cols-2 gap-2">
+                                                            <input
+                                                                type="text"
+                                                                value={lastfm.api_key}
+                                                                onChange={(e) => setLastfm({ ...lastfm, api_key: e.target.value })}
+                                                                onBlur={() => saveLastfm({})}
+                                                                placeholder="API key"
+                                                                className="w-full px-3 py-2 bg-white/5 border border-white/10 rounded-lg text-sm text-white placeholder-slate-500 outline-none focus:border-rose-500/50 transition-colors font-mono"
+                                                            />
+                                                            <input
+                                                                type="password"
+                                                                value={lastfm.api_secret}
+                                                                onChange={(e) => setLastfm({ ...lastfm, api_secret: e.target.value })}
+                                                                onBlur={() => saveLastfm({})}
+                                                                placeholder="Shared secret"
+                                                                className="w-full px-3 py-2 bg-white/5 border border-white/10 rounded-lg text-sm text-white placeholder-slate-500 outline-none focus:border-rose-500/50 transition-colors font-mono"
+                                                            />
+                                                        </div>
+                                                        <button
+                                                            onClick={() => lastfmAction('lastfm_login')}
+                                                            disabled={lastfmBusy || !lastfm.api_key || !lastfm.api_secret}
+                                                            className="w-full py-2 bg-rose-600 hover:bg-rose-500 disabled:bg-slate-700 disabled:text-slate-500 rounded-lg text-sm text-white"
+                                                        >
+                                                            {lastfmBusy ? 'Waiting for approval on last.fm…' : 'Log in'}
+                                                        </button>
+                                                        <p className="text-[10px] text-slate-500">Create an API account at last.fm/api/account/create</p>
+                                                    </>
+                                                )}
+                                                {lastfmStatus.queued > 0 && (
+                                                    <div className="flex items-center justify-between text-xs text-amber-300">
+                                                        {lastfmStatus.queued} scrobble(s) waiting to be sent
+                                                        <button onClick={() => lastfmAction('flush_lastfm_queue')} disabled={lastfmBusy} className="text-slate-300 hover:text-white">Retry now</button>
+                                                    </div>
+                                                )}
+                                                {lastfmError && <p className="text-[11px] text-red-400">{lastfmError}</p>}
+                                            </div>
+                                        </div>
+                                    )}
+
                                     {/* Default Subtitle Language */}
                                     <div className="p-4 bg-white/5 rounded-lg border border-white/5">
                                         <label className="text-white font-medium mb-2 block">Default Subtitle Language</label>
@@ -686,6 +1646,10 @@ export default function EnhancedSettingsModal({ onClose, isPlayerActive }: Enhan
                                         </div>
                                     </div>
 
+                                    <UpdateSettings />
+
+                                    <DebugLogs />
+
                                     <button
                                         onClick={resetSettings}
                                         className="w-full py-3 px-6 bg-red-500/10 hover:bg-red-500/20 border border-red-500/30 rounded-lg text-red-400 font-medium transition-all"
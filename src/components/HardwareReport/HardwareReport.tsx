@@ -0,0 +1,188 @@
+import { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { Cpu, Loader2, RefreshCw, Gauge, X } from 'lucide-react';
+
+interface HwdecSupport {
+    api: string;
+    available: boolean;
+    hevc: boolean | null;
+    av1: boolean | null;
+}
+
+interface DisplayCaps {
+    name: string;
+    width: number;
+    height: number;
+    scale_factor: number;
+    refresh_hz: number | null;
+    refresh_rates: number[];
+}
+
+export interface HwCapabilities {
+    gpus: string[];
+    hwdec: HwdecSupport[];
+    hevc_decode: boolean;
+    av1_decode: boolean;
+    hdr_display: boolean | null;
+    displays: DisplayCaps[];
+    recommended_hwdec: string;
+}
+
+interface BenchmarkRun {
+    hwdec: string;
+    vo: string;
+    hwdec_active: string;
+    frames: number;
+    dropped_frames: number;
+    cpu_percent: number;
+    gpu_percent: number | null;
+    error: string | null;
+}
+
+interface BenchmarkResult {
+    sample: string;
+    runs: BenchmarkRun[];
+    recommended_hwdec: string | null;
+    recommended_vo: string | null;
+}
+
+const mark = (value: boolean | null) => value === null ? '?' : value ? '✓' : '✗';
+
+export default function HardwareReport() {
+    const [report, setReport] = useState<HwCapabilities | null>(null);
+    const [busy, setBusy] = useState(false);
+    const [error, setError] = useState('');
+    const [benchmark, setBenchmark] = useState<BenchmarkResult | null>(null);
+    const [benchmarking, setBenchmarking] = useState<{ done: number; total: number; hwdec: string; vo: string } | null>(null);
+
+    useEffect(() => {
+        const unlisten = listen<{ done: number; total: number; hwdec: string; vo: string }>('benchmark-progress', e => setBenchmarking(e.payload));
+        return () => { unlisten.then(f => f()); };
+    }, []);
+
+    const runBenchmark = async () => {
+        setError('');
+        setBenchmarking({ done: 0, total: 0, hwdec: '', vo: '' });
+        try {
+            setBenchmark(await invoke<BenchmarkResult>('run_decode_benchmark', {}));
+        } catch (e) {
+            setError(String(e));
+        } finally {
+            setBenchmarking(null);
+        }
+    };
+
+    const load = async (refresh: boolean) => {
+        setBusy(true);
+        setError('');
+        try {
+            setReport(await invoke<HwCapabilities>('get_hw_capabilities', { refresh }));
+        } catch (e) {
+            setError(String(e));
+        } finally {
+            setBusy(false);
+        }
+    };
+
+    return (
+        <div className="p-4 bg-white/5 rounded-lg border border-white/5">
+            <div className="flex items-center gap-3 mb-3">
+                <div className="p-2 bg-cyan-500/20 rounded-lg">
+                    <Cpu className="w-5 h-5 text-cyan-400" />
+                </div>
+                <div className="flex-1">
+                    <h4 className="text-white font-medium">Hardware</h4>
+                    <p className="text-xs text-slate-400">What this machine can decode and display</p>
+                </div>
+                <button onClick={() => load(report !== null)} disabled={busy} className="px-3 py-1.5 bg-white/10 hover:bg-white/15 disabled:opacity-40 rounded-lg text-xs text-white flex items-center gap-1.5">
+                    {busy ? <Loader2 size={14} className="animate-spin" /> : <RefreshCw size={14} />} {report ? 'Re-check' : 'Check'}
+                </button>
+            </div>
+
+            {report && (
+                <div className="space-y-3 text-xs">
+                    <div className="text-slate-300">
+                        {report.gpus.length ? report.gpus.map(gpu => <p key={gpu}>{gpu}</p>) : <p className="italic text-slate-500">No GPU reported</p>}
+                    </div>
+
+                    <table className="w-full text-left">
+                        <thead className="text-slate-500">
+                            <tr><th className="font-normal">Decoder</th><th className="font-normal">Available</th><th className="font-normal">HEVC</th><th className="font-normal">AV1</th></tr>
+                        </thead>
+                        <tbody className="text-slate-300">
+                            {report.hwdec.map(h => (
+                                <tr key={h.api}>
+                                    <td className={h.api === report.recommended_hwdec ? 'text-cyan-300 font-medium' : ''}>{h.api}</td>
+                                    <td>{mark(h.available)}</td>
+                                    <td>{mark(h.hevc)}</td>
+                                    <td>{mark(h.av1)}</td>
+                                </tr>
+                            ))}
+                        </tbody>
+                    </table>
+                    {report.hwdec.length === 0 && <p className="italic text-slate-500">ffmpeg wasn't found, so decoders couldn't be tested</p>}
+
+                    <div className="space-y-1 text-slate-300">
+                        {report.displays.map(d => (
+                            <p key={d.name}>
+                                {d.name}: {d.width}×{d.height}
+                                {d.refresh_hz ? ` @ ${d.refresh_hz.toFixed(2)} Hz` : ''}
+                                {d.refresh_rates.length > 1 && <span className="text-slate-500"> (also {d.refresh_rates.filter(r => r !== d.refresh_hz).map(r => r.toFixed(0)).join(', ')} Hz)</span>}
+                            </p>
+                        ))}
+                        <p>HDR display: {report.hdr_display === null ? 'unknown' : report.hdr_display ? 'yes' : 'no'}</p>
+                    </div>
+
+                    <p className="text-slate-400">
+                        Recommended hardware decoding: <span className="text-white font-mono">{report.recommended_hwdec}</span>
+                    </p>
+                </div>
+            )}
+            <div className="mt-4 pt-3 border-t border-white/5">
+                <div className="flex items-center gap-2">
+                    <p className="flex-1 text-xs text-slate-400">
+                        {benchmarking
+                            ? benchmarking.total ? `Testing vo=${benchmarking.vo} hwdec=${benchmarking.hwdec} (${benchmarking.done + 1}/${benchmarking.total})` : 'Preparing…'
+                            : 'Play a test clip with each decoder and renderer to find settings that don\'t stutter'}
+                    </p>
+                    {benchmarking ? (
+                        <button onClick={() => invoke('cancel_decode_benchmark')} className="px-3 py-1.5 bg-white/10 hover:bg-white/15 rounded-lg text-xs text-white flex items-center gap-1.5">
+                            <X size={14} /> Cancel
+                        </button>
+                    ) : (
+                        <button onClick={runBenchmark} className="px-3 py-1.5 bg-white/10 hover:bg-white/15 rounded-lg text-xs text-white flex items-center gap-1.5">
+                            <Gauge size={14} /> Benchmark
+                        </button>
+                    )}
+                </div>
+                {benchmark && (
+                    <div className="mt-3 space-y-2 text-xs">
+                        <table className="w-full text-left">
+                            <thead className="text-slate-500">
+                                <tr><th className="font-normal">VO</th><th className="font-normal">Decoder</th><th className="font-normal">Dropped</th><th className="font-normal">CPU</th><th className="font-normal">GPU</th></tr>
+                            </thead>
+                            <tbody className="text-slate-300">
+                                {benchmark.runs.map((run, i) => (
+                                    <tr key={`${run.vo}-${run.hwdec}`} className={i === 0 && !run.error ? 'text-cyan-300' : ''} title={run.error ?? undefined}>
+                                        <td>{run.vo}</td>
+                                        <td>{run.hwdec}{run.hwdec !== 'no' && run.hwdec_active === 'no' && <span className="text-amber-400"> (fell back)</span>}</td>
+                                        <td>{run.error ? <span className="text-red-400">failed</span> : `${run.dropped_frames} / ${run.frames}`}</td>
+                                        <td>{run.cpu_percent.toFixed(0)}%</td>
+                                        <td>{run.gpu_percent === null ? '–' : `${run.gpu_percent.toFixed(0)}%`}</td>
+                                    </tr>
+                                ))}
+                            </tbody>
+                        </table>
+                        {benchmark.recommended_hwdec && (
+                            <p className="text-slate-400">
+                                Best: <span className="text-white font-mono">vo={benchmark.recommended_vo} hwdec={benchmark.recommended_hwdec}</span>
+                            </p>
+                        )}
+                    </div>
+                )}
+            </div>
+            {error && <p className="mt-2 text-[11px] text-red-400">{error}</p>}
+        </div>
+    );
+}
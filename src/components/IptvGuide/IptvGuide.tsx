@@ -0,0 +1,309 @@
+import { useState, useEffect, useMemo } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { open } from '@tauri-apps/plugin-dialog';
+import { Tv, X, Plus, RefreshCw, Trash2, Search, FolderOpen, Loader2 } from 'lucide-react';
+
+interface Channel {
+    id: string;
+    name: string;
+    group: string | null;
+    logo: string | null;
+    url: string;
+}
+
+interface IptvPlaylist {
+    id: string;
+    name: string;
+    source: string;
+    epg_url: string | null;
+    channels: Channel[];
+    updated: number;
+}
+
+interface Programme {
+    title: string;
+    description: string | null;
+    start: number;
+    stop: number;
+}
+
+interface ChannelGuide {
+    channel_id: string;
+    now: Programme | null;
+    next: Programme | null;
+}
+
+interface IptvGuideProps {
+    isOpen: boolean;
+    onClose: () => void;
+    onPlay: (playlistId: string, channel: Channel) => void;
+}
+
+const formatTime = (secs: number) => new Date(secs * 1000).toLocaleTimeString([], { hour: '2-digit', minute: '2-digit' });
+
+export default function IptvGuide({ isOpen, onClose, onPlay }: IptvGuideProps) {
+    const [playlists, setPlaylists] = useState<IptvPlaylist[]>([]);
+    const [selectedId, setSelectedId] = useState<string | null>(null);
+    const [guide, setGuide] = useState<Record<string, ChannelGuide>>({});
+    const [group, setGroup] = useState<string>('');
+    const [search, setSearch] = useState('');
+    const [showImport, setShowImport] = useState(false);
+    const [source, setSource] = useState('');
+    const [epgUrl, setEpgUrl] = useState('');
+    const [busy, setBusy] = useState(false);
+    const [error, setError] = useState('');
+    const [, setTick] = useState(0);
+
+    useEffect(() => {
+        if (!isOpen) return;
+        invoke<IptvPlaylist[]>('list_iptv_playlists').then(list => {
+            setPlaylists(list);
+            setSelectedId(prev => prev ?? list[0]?.id ?? null);
+            if (list.length === 0) setShowImport(true);
+        }).catch(e => setError(String(e)));
+        // Now/next moves on as programmes end
+        const timer = setInterval(() => setTick(t => t + 1), 60000);
+        return () => clearInterval(timer);
+    }, [isOpen]);
+
+    useEffect(() => {
+        if (!isOpen || !selectedId) return;
+        setGuide({});
+        setGroup('');
+        loadGuide(selectedId, false);
+    }, [isOpen, selectedId]);
+
+    const selected = playlists.find(p => p.id === selectedId) ?? null;
+
+    const groups = useMemo(() => {
+        const names = new Set<string>();
+        selected?.channels.forEach(c => c.group && names.add(c.group));
+        return [...names].sort();
+    }, [selected]);
+
+    const channels = useMemo(() => {
+        const query = search.trim().toLowerCase();
+        return (selected?.channels ?? []).filter(c =>
+            (!group || c.group === group) && (!query || c.name.toLowerCase().includes(query))
+        );
+    }, [selected, group, search]);
+
+    if (!isOpen) return null;
+
+    async function loadGuide(id: string, refresh: boolean) {
+        try {
+            const entries = await invoke<ChannelGuide[]>('get_iptv_guide', { id, refresh });
+            setGuide(Object.fromEntries(entries.map(e => [e.channel_id, e])));
+        } catch (e) {
+            setError(`Guide unavailable: ${e}`);
+        }
+    }
+
+    const handleImport = async () => {
+        if (!source.trim()) return;
+        setBusy(true);
+        setError('');
+        try {
+            const playlist = await invoke<IptvPlaylist>('import_iptv_playlist', { source, name: null, epgUrl: epgUrl || null });
+            setPlaylists(prev => [...prev, playlist]);
+            setSelectedId(playlist.id);
+            setShowImport(false);
+            setSource('');
+            setEpgUrl('');
+        } catch (e) {
+            setError(String(e));
+        } finally {
+            setBusy(false);
+        }
+    };
+
+    const browseFile = async () => {
+        const file = await open({ multiple: false, filters: [{ name: 'M3U Playlist', extensions: ['m3u', 'm3u8'] }] });
+        if (typeof file === 'string') setSource(file);
+    };
+
+    const handleRefresh = async () => {
+        if (!selected) return;
+        setBusy(true);
+        setError('');
+        try {
+            const updated = await invoke<IptvPlaylist>('refresh_iptv_playlist', { id: selected.id });
+            setPlaylists(prev => prev.map(p => (p.id === updated.id ? updated : p)));
+            await loadGuide(updated.id, false);
+        } catch (e) {
+            setError(String(e));
+        } finally {
+            setBusy(false);
+        }
+    };
+
+    const handleDelete = async () => {
+        if (!selected) return;
+        try {
+            await invoke('delete_iptv_playlist', { id: selected.id });
+            const remaining = playlists.filter(p => p.id !== selected.id);
+            setPlaylists(remaining);
+            setSelectedId(remaining[0]?.id ?? null);
+        } catch (e) {
+            setError(String(e));
+        }
+    };
+
+    const now = Date.now() / 1000;
+
+    return (
+        <div className="fixed inset-0 z-[100] flex items-center justify-center bg-black/70 backdrop-blur-sm animate-fade-in" onClick={onClose}>
+            <div
+                className="w-full max-w-3xl mx-4 h-[80vh] flex flex-col bg-gradient-to-br from-slate-900 to-slate-950 border border-white/10 rounded-2xl shadow-2xl overflow-hidden animate-fade-in-up"
+                onClick={e => e.stopPropagation()}
+            >
+                {/* Header */}
+                <div className="flex items-center justify-between px-6 py-5 border-b border-white/10">
+                    <div className="flex items-center gap-3">
+                        <div className="p-2 bg-rose-500/20 rounded-lg">
+                            <Tv size={22} className="text-rose-400" />
+                        </div>
+                        <div>
+                            <h2 className="text-xl font-bold text-white">Live TV</h2>
+                            <p className="text-xs text-slate-500">IPTV channels and programme guide</p>
+                        </div>
+                    </div>
+                    <div className="flex items-center gap-1">
+                        <button onClick={() => setShowImport(s => !s)} title="Add playlist" className="p-2 text-slate-400 hover:text-white hover:bg-white/10 rounded-lg transition-all">
+                            <Plus size={18} />
+                        </button>
+                        <button onClick={onClose} className="p-2 text-slate-400 hover:text-white hover:bg-white/10 rounded-lg transition-all">
+                            <X size={20} />
+                        </button>
+                    </div>
+                </div>
+
+                {/* Import */}
+                {showImport && (
+                    <div className="px-6 py-4 space-y-2 border-b border-white/10">
+                        <div className="flex gap-2">
+                            <input
+                                value={source}
+                                onChange={(e) => setSource(e.target.value)}
+                                onKeyDown={(e) => e.key === 'Enter' && handleImport()}
+                                placeholder="M3U playlist URL or file"
+                                className="flex-1 min-w-0 px-3 py-2.5 bg-white/5 border border-white/10 rounded-xl text-sm text-white placeholder-slate-500 outline-none focus:border-rose-500/50 transition-colors font-mono"
+                            />
+                            <button onClick={browseFile} title="Choose file" className="px-3 py-2.5 bg-white/5 hover:bg-white/10 border border-white/10 text-slate-300 rounded-xl transition-colors">
+                                <FolderOpen size={16} />
+                            </button>
+                        </div>
+                        <div className="flex gap-2">
+                            <input
+                                value={epgUrl}
+                                onChange={(e) => setEpgUrl(e.target.value)}
+                                placeholder="XMLTV guide URL (optional, read from the playlist when it has one)"
+                                className="flex-1 min-w-0 px-3 py-2.5 bg-white/5 border border-white/10 rounded-xl text-sm text-white placeholder-slate-500 outline-none focus:border-rose-500/50 transition-colors font-mono"
+                            />
+                            <button
+                                onClick={handleImport}
+                                disabled={busy || !source.trim()}
+                                className="px-4 py-2.5 bg-rose-600 hover:bg-rose-500 disabled:bg-slate-700 disabled:text-slate-500 text-white text-sm font-semibold rounded-xl transition-colors flex items-center gap-2"
+                            >
+                                {busy ? <Loader2 size={16} className="animate-spin" /> : <Plus size={16} />} Import
+                            </button>
+                        </div>
+                    </div>
+                )}
+
+                {error && <p className="px-6 pt-3 text-[11px] text-red-400">{error}</p>}
+
+                {/* Playlist + filters */}
+                {selected && (
+                    <div className="px-6 py-3 flex flex-wrap items-center gap-2 border-b border-white/5">
+                        <select
+                            value={selectedId ?? ''}
+                            onChange={(e) => setSelectedId(e.target.value)}
+                            className="px-3 py-2 bg-white/5 border border-white/10 rounded-lg text-sm text-white outline-none"
+                        >
+                            {playlists.map(p => (
+                                <option key={p.id} value={p.id} className="bg-slate-900">{p.name} ({p.channels.length})</option>
+                            ))}
+                        </select>
+                        {groups.length > 0 && (
+                            <select
+                                value={group}
+                                onChange={(e) => setGroup(e.target.value)}
+                                className="px-3 py-2 bg-white/5 border border-white/10 rounded-lg text-sm text-white outline-none"
+                            >
+                                <option value="" className="bg-slate-900">All groups</option>
+                                {groups.map(g => <option key={g} value={g} className="bg-slate-900">{g}</option>)}
+                            </select>
+                        )}
+                        <div className="flex-1 min-w-[8rem] relative">
+                            <Search size={14} className="absolute left-3 top-1/2 -translate-y-1/2 text-slate-500" />
+                            <input
+                                value={search}
+                                onChange={(e) => setSearch(e.target.value)}
+                                placeholder="Search channels"
+                                className="w-full pl-8 pr-3 py-2 bg-white/5 border border-white/10 rounded-lg text-sm text-white placeholder-slate-500 outline-none"
+                            />
+                        </div>
+                        <button onClick={handleRefresh} disabled={busy} title="Reload channels and guide" className="p-2 text-slate-400 hover:text-white hover:bg-white/10 rounded-lg transition-all">
+                            <RefreshCw size={16} className={busy ? 'animate-spin' : ''} />
+                        </button>
+                        <button onClick={handleDelete} title="Remove playlist" className="p-2 text-slate-400 hover:text-red-400 hover:bg-white/10 rounded-lg transition-all">
+                            <Trash2 size={16} />
+                        </button>
+                    </div>
+                )}
+
+                {/* Channels */}
+                <div className="flex-1 overflow-y-auto custom-scrollbar p-3 space-y-1">
+                    {channels.map(channel => {
+                        const entry = guide[channel.id];
+                        const current = entry?.now;
+                        const progress = current ? Math.min(100, ((now - current.start) / (current.stop - current.start)) * 100) : 0;
+                        return (
+                            <button
+                                key={channel.id}
+                                onClick={() => selected && onPlay(selected.id, channel)}
+                                className="w-full flex items-center gap-3 px-3 py-2.5 text-left bg-white/5 hover:bg-white/10 rounded-xl transition-colors"
+                            >
+                                <div className="w-12 h-9 flex-shrink-0 flex items-center justify-center bg-black/30 rounded-md overflow-hidden">
+                                    {channel.logo
+                                        ? <img src={channel.logo} alt="" className="max-w-full max-h-full object-contain" onError={(e) => { e.currentTarget.style.display = 'none'; }} />
+                                        : <Tv size={16} className="text-slate-600" />}
+                                </div>
+                                <div className="flex-1 min-w-0">
+                                    <div className="flex items-center gap-2">
+                                        <span className="text-sm font-medium text-white truncate">{channel.name}</span>
+                                        {channel.group && !group && <span className="text-[10px] text-slate-500 truncate">{channel.group}</span>}
+                                    </div>
+                                    {current ? (
+                                        <>
+                                            <p className="text-xs text-slate-300 truncate" title={current.description ?? undefined}>
+                                                <span className="text-slate-500">{formatTime(current.start)}</span> {current.title}
+                                            </p>
+                                            <div className="mt-1 h-0.5 bg-white/10 rounded-full overflow-hidden">
+                                                <div className="h-full bg-rose-500" style={{ width: `${progress}%` }} />
+                                            </div>
+                                        </>
+                                    ) : (
+                                        <p className="text-xs text-slate-600">No guide data</p>
+                                    )}
+                                    {entry?.next && (
+                                        <p className="mt-0.5 text-[11px] text-slate-500 truncate">
+                                            Next {formatTime(entry.next.start)} · {entry.next.title}
+                                        </p>
+                                    )}
+                                </div>
+                            </button>
+                        );
+                    })}
+                    {selected && channels.length === 0 && (
+                        <p className="text-center text-sm text-slate-500 py-8">No channels match</p>
+                    )}
+                    {!selected && !showImport && (
+                        <p className="text-center text-sm text-slate-500 py-8">Add an M3U playlist to get started</p>
+                    )}
+                </div>
+            </div>
+        </div>
+    );
+}
@@ -0,0 +1,268 @@
+import { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { open } from '@tauri-apps/plugin-dialog';
+import { ShieldCheck, Lock, Unlock, FolderPlus, Trash2 } from 'lucide-react';
+import PinPrompt from './PinPrompt';
+
+interface ParentalLock {
+    target: string;
+    hide: boolean;
+}
+
+interface ParentalRules {
+    locked_folders: ParentalLock[];
+    locked_collections: ParentalLock[];
+    max_age: number | null;
+    protected_areas: string[];
+    unlock_minutes: number;
+}
+
+interface ParentalStatus extends ParentalRules {
+    enabled: boolean;
+    unlocked: boolean;
+    unlocked_secs: number;
+}
+
+interface Collection {
+    id: string;
+    name: string;
+}
+
+const AREAS: [string, string][] = [
+    ['settings', 'Settings'],
+    ['downloads', 'Downloads'],
+    ['torrents', 'Torrents'],
+    ['iptv', 'Live TV'],
+    ['history', 'Watch history'],
+];
+
+// Content rated for viewers older than this is blocked
+const AGES: [number | null, string][] = [
+    [null, 'No rating limit'],
+    [0, 'All ages only (G, TV-Y)'],
+    [7, 'Up to 7 (TV-Y7)'],
+    [10, 'Up to 10 (PG, TV-PG)'],
+    [13, 'Up to 13 (PG-13)'],
+    [14, 'Up to 14 (TV-14)'],
+    [16, 'Up to 16'],
+];
+
+export default function ParentalControls() {
+    const [status, setStatus] = useState<ParentalStatus | null>(null);
+    const [collections, setCollections] = useState<Collection[]>([]);
+    const [currentPin, setCurrentPin] = useState('');
+    const [newPin, setNewPin] = useState('');
+    const [prompt, setPrompt] = useState(false);
+    const [error, setError] = useState('');
+
+    const refresh = () => {
+        invoke<ParentalStatus>('get_parental_controls').then(setStatus).catch(e => setError(String(e)));
+        invoke<Collection[]>('get_collections').then(setCollections).catch(() => {});
+    };
+
+    useEffect(() => {
+        refresh();
+        const unlisten = listen('parental-changed', refresh);
+        return () => { unlisten.then(f => f()); };
+    }, []);
+
+    if (!status) return null;
+    const editable = status.unlocked;
+
+    const save = async (changes: Partial<ParentalRules>) => {
+        setError('');
+        const rules: ParentalRules = {
+            locked_folders: status.locked_folders,
+            locked_collections: status.locked_collections,
+            max_age: status.max_age,
+            protected_areas: status.protected_areas,
+            unlock_minutes: status.unlock_minutes,
+        };
+        try {
+            await invoke('save_parental_controls', { rules: { ...rules, ...changes } });
+        } catch (e) {
+            setError(String(e));
+        }
+        refresh();
+    };
+
+    const setPin = async (pin: string | null) => {
+        setError('');
+        try {
+            await invoke('set_parental_pin', { current: currentPin || null, pin });
+            setCurrentPin('');
+            setNewPin('');
+        } catch (e) {
+            setError(String(e));
+        }
+        refresh();
+    };
+
+    const addFolder = async () => {
+        const selected = await open({ directory: true, multiple: false });
+        if (typeof selected === 'string' && !status.locked_folders.some(l => l.target === selected)) {
+            save({ locked_folders: [...status.locked_folders, { target: selected, hide: false }] });
+        }
+    };
+
+    const toggleArea = (area: string) => save({
+        protected_areas: status.protected_areas.includes(area)
+            ? status.protected_areas.filter(a => a !== area)
+            : [...status.protected_areas, area],
+    });
+
+    const lockList = (key: 'locked_folders' | 'locked_collections', label: (target: string) => string) => (
+        <div className="space-y-1">
+            {status[key].length === 0 && <p className="text-xs text-slate-500 italic">None</p>}
+            {status[key].map(lock => (
+                <div key={lock.target} className="flex items-center gap-3 px-3 py-1.5 bg-white/5 rounded-lg text-sm">
+                    <span className="flex-1 truncate text-white" title={lock.target}>{label(lock.target)}</span>
+                    <label className="flex items-center gap-1.5 text-xs text-slate-400">
+                        <input
+                            type="checkbox"
+                            checked={lock.hide}
+                            disabled={!editable}
+                            onChange={e => save({ [key]: status[key].map(l => l.target === lock.target ? { ...l, hide: e.target.checked } : l) })}
+                        />
+                        Hide
+                    </label>
+                    <button
+                        onClick={() => save({ [key]: status[key].filter(l => l.target !== lock.target) })}
+                        disabled={!editable}
+                        className="p-1 text-slate-400 hover:text-red-400 disabled:opacity-40"
+                        title="Remove"
+                    >
+                        <Trash2 size={14} />
+                    </button>
+                </div>
+            ))}
+        </div>
+    );
+
+    return (
+        <div className="p-4 bg-white/5 rounded-lg border border-white/5">
+            <div className="flex items-center gap-3 mb-3">
+                <div className="p-2 bg-rose-500/20 rounded-lg">
+                    <ShieldCheck className="w-5 h-5 text-rose-400" />
+                </div>
+                <div className="flex-1">
+                    <h4 className="text-white font-medium">Parental Controls</h4>
+                    <p className="text-xs text-slate-400">
+                        {!status.enabled
+                            ? 'Set a PIN to lock folders, collections, ratings and app areas'
+                            : status.unlocked
+                                ? `Unlocked for ${Math.ceil(status.unlocked_secs / 60)} more min`
+                                : 'Locked'}
+                    </p>
+                </div>
+                {status.enabled && (status.unlocked ? (
+                    <button onClick={() => invoke('lock_parental')} className="px-3 py-1.5 bg-white/10 hover:bg-white/15 rounded-lg text-xs text-white flex items-center gap-1.5">
+                        <Lock size={14} /> Lock now
+                    </button>
+                ) : (
+                    <button onClick={() => setPrompt(true)} className="px-3 py-1.5 bg-rose-600 hover:bg-rose-500 rounded-lg text-xs font-semibold text-white flex items-center gap-1.5">
+                        <Unlock size={14} /> Unlock
+                    </button>
+                ))}
+            </div>
+
+            <div className="flex gap-2 mb-4">
+                {status.enabled && (
+                    <input
+                        type="password"
+                        inputMode="numeric"
+                        value={currentPin}
+                        onChange={e => setCurrentPin(e.target.value.replace(/\D/g, '').slice(0, 8))}
+                        placeholder="Current PIN"
+                        className="w-28 px-3 py-1.5 bg-white/10 border border-white/10 rounded-lg text-white text-xs outline-none focus:border-white/30"
+                    />
+                )}
+                <input
+                    type="password"
+                    inputMode="numeric"
+                    value={newPin}
+                    onChange={e => setNewPin(e.target.value.replace(/\D/g, '').slice(0, 8))}
+                    placeholder="New PIN (4-8 digits)"
+                    className="flex-1 px-3 py-1.5 bg-white/10 border border-white/10 rounded-lg text-white text-xs outline-none focus:border-white/30"
+                />
+                <button onClick={() => setPin(newPin)} disabled={newPin.length < 4} className="px-3 py-1.5 bg-white/10 hover:bg-white/15 disabled:opacity-40 rounded-lg text-xs text-white">
+                    {status.enabled ? 'Change PIN' : 'Set PIN'}
+                </button>
+                {status.enabled && (
+                    <button onClick={() => setPin(null)} disabled={!currentPin} className="px-3 py-1.5 bg-white/10 hover:bg-red-500/20 disabled:opacity-40 rounded-lg text-xs text-red-300">
+                        Remove
+                    </button>
+                )}
+            </div>
+
+            <fieldset disabled={!editable} className={`space-y-4 ${editable ? '' : 'opacity-50'}`}>
+                <div className="flex items-center gap-3">
+                    <label className="flex-1 text-sm text-white">Content rating</label>
+                    <select
+                        value={status.max_age ?? ''}
+                        onChange={e => save({ max_age: e.target.value === '' ? null : Number(e.target.value) })}
+                        className="px-3 py-1.5 bg-slate-900 border border-white/10 rounded-lg text-white text-xs outline-none focus:border-white/30"
+                    >
+                        {AGES.map(([age, label]) => <option key={label} value={age ?? ''}>{label}</option>)}
+                    </select>
+                </div>
+
+                <div>
+                    <div className="flex items-center mb-2">
+                        <label className="flex-1 text-sm text-white">Locked folders</label>
+                        <button onClick={addFolder} className="p-1 text-slate-400 hover:text-white" title="Add folder">
+                            <FolderPlus size={16} />
+                        </button>
+                    </div>
+                    {lockList('locked_folders', target => target)}
+                </div>
+
+                <div>
+                    <div className="flex items-center gap-2 mb-2">
+                        <label className="flex-1 text-sm text-white">Locked collections</label>
+                        <select
+                            value=""
+                            onChange={e => e.target.value && save({ locked_collections: [...status.locked_collections, { target: e.target.value, hide: false }] })}
+                            className="px-3 py-1.5 bg-slate-900 border border-white/10 rounded-lg text-white text-xs outline-none focus:border-white/30"
+                        >
+                            <option value="">Add collection…</option>
+                            {collections
+                                .filter(c => !status.locked_collections.some(l => l.target === c.id))
+                                .map(c => <option key={c.id} value={c.id}>{c.name}</option>)}
+                        </select>
+                    </div>
+                    {lockList('locked_collections', target => collections.find(c => c.id === target)?.name ?? target)}
+                </div>
+
+                <div>
+                    <label className="block text-sm text-white mb-2">PIN required for</label>
+                    <div className="flex flex-wrap gap-2">
+                        {AREAS.map(([area, label]) => (
+                            <label key={area} className="flex items-center gap-1.5 px-2.5 py-1 bg-white/5 rounded-lg text-xs text-slate-300">
+                                <input type="checkbox" checked={status.protected_areas.includes(area)} onChange={() => toggleArea(area)} />
+                                {label}
+                            </label>
+                        ))}
+                    </div>
+                </div>
+
+                <div className="flex items-center gap-3">
+                    <label className="flex-1 text-sm text-white">Unlock lasts</label>
+                    <input
+                        type="number"
+                        min={1}
+                        max={240}
+                        value={status.unlock_minutes}
+                        onChange={e => save({ unlock_minutes: Number(e.target.value) })}
+                        className="w-20 px-3 py-1.5 bg-white/10 border border-white/10 rounded-lg text-white text-xs outline-none focus:border-white/30"
+                    />
+                    <span className="text-xs text-slate-400">min</span>
+                </div>
+            </fieldset>
+
+            {error && <p className="mt-3 text-[11px] text-red-400">{error}</p>}
+            {prompt && <PinPrompt onUnlocked={() => { setPrompt(false); refresh(); }} onClose={() => setPrompt(false)} />}
+        </div>
+    );
+}
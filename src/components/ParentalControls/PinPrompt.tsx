@@ -0,0 +1,58 @@
+import { useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { Lock, X } from 'lucide-react';
+
+interface PinPromptProps {
+    message?: string;
+    onUnlocked: () => void;
+    onClose: () => void;
+}
+
+export default function PinPrompt({ message, onUnlocked, onClose }: PinPromptProps) {
+    const [pin, setPin] = useState('');
+    const [error, setError] = useState('');
+
+    const submit = async () => {
+        try {
+            await invoke('unlock_parental', { pin });
+            onUnlocked();
+        } catch (e) {
+            setError(String(e));
+            setPin('');
+        }
+    };
+
+    return (
+        <div className="fixed inset-0 z-[100] flex items-center justify-center bg-black/60 backdrop-blur-sm" onClick={onClose}>
+            <div className="w-80 p-5 bg-slate-900 border border-white/10 rounded-xl shadow-2xl" onClick={e => e.stopPropagation()}>
+                <div className="flex items-center gap-3 mb-4">
+                    <div className="p-2 bg-rose-500/20 rounded-lg">
+                        <Lock className="w-5 h-5 text-rose-400" />
+                    </div>
+                    <h4 className="flex-1 text-white font-medium">Parental controls</h4>
+                    <button onClick={onClose} className="p-1 text-slate-400 hover:text-white">
+                        <X size={16} />
+                    </button>
+                </div>
+                <p className="text-xs text-slate-400 mb-3">{message || 'Enter the PIN to continue'}</p>
+                <input
+                    type="password"
+                    inputMode="numeric"
+                    autoFocus
+                    value={pin}
+                    onChange={e => setPin(e.target.value.replace(/\D/g, '').slice(0, 8))}
+                    onKeyDown={e => e.key === 'Enter' && pin && submit()}
+                    className="w-full px-3 py-2 bg-white/10 border border-white/10 rounded-lg text-white text-center tracking-[0.5em] outline-none focus:border-white/30"
+                />
+                {error && <p className="mt-2 text-[11px] text-red-400">{error}</p>}
+                <button
+                    onClick={submit}
+                    disabled={!pin}
+                    className="mt-4 w-full py-2 bg-rose-600 hover:bg-rose-500 disabled:opacity-40 rounded-lg text-sm font-semibold text-white"
+                >
+                    Unlock
+                </button>
+            </div>
+        </div>
+    );
+}
@@ -4,13 +4,13 @@ import { ListMusic, Plus, Trash2, Play, Edit3, Save, X, GripVertical, FolderOpen
 import type { Playlist } from '../../types/playlist';
 
 interface PlaylistManagerProps {
-    onPlayFile: (path: string) => void;
+    onPlayPlaylist: (id: string, startIndex: number) => void;
     libraryFiles?: string[];
     isOpen: boolean;
     onClose: () => void;
 }
 
-export default function PlaylistManager({ onPlayFile, libraryFiles = [], isOpen, onClose }: PlaylistManagerProps) {
+export default function PlaylistManager({ onPlayPlaylist, libraryFiles = [], isOpen, onClose }: PlaylistManagerProps) {
     const [playlists, setPlaylists] = useState<Playlist[]>([]);
     const [selectedPlaylist, setSelectedPlaylist] = useState<Playlist | null>(null);
     const [editingName, setEditingName] = useState('');
@@ -67,12 +67,10 @@ export default function PlaylistManager({ onPlayFile, libraryFiles = [], isOpen,
 
     const addToPlaylist = async (filePath: string) => {
         if (!selectedPlaylist) return;
-        const newItems = [...selectedPlaylist.items, filePath];
         try {
-            const updated = await invoke<Playlist>('save_playlist', {
-                name: selectedPlaylist.name,
-                items: newItems,
+            const updated = await invoke<Playlist>('playlist_add_items', {
                 id: selectedPlaylist.id,
+                items: [filePath],
             });
             setPlaylists(prev => prev.map(p => p.id === updated.id ? updated : p));
             setSelectedPlaylist(updated);
@@ -81,12 +79,10 @@ export default function PlaylistManager({ onPlayFile, libraryFiles = [], isOpen,
 
     const removeFromPlaylist = async (index: number) => {
         if (!selectedPlaylist) return;
-        const newItems = selectedPlaylist.items.filter((_, i) => i !== index);
         try {
-            const updated = await invoke<Playlist>('save_playlist', {
-                name: selectedPlaylist.name,
-                items: newItems,
+            const updated = await invoke<Playlist>('playlist_remove_item', {
                 id: selectedPlaylist.id,
+                index,
             });
             setPlaylists(prev => prev.map(p => p.id === updated.id ? updated : p));
             setSelectedPlaylist(updated);
@@ -95,7 +91,7 @@ export default function PlaylistManager({ onPlayFile, libraryFiles = [], isOpen,
 
     const playPlaylist = (startIndex: number = 0) => {
         if (!selectedPlaylist || selectedPlaylist.items.length === 0) return;
-        onPlayFile(selectedPlaylist.items[startIndex]);
+        onPlayPlaylist(selectedPlaylist.id, startIndex);
     };
 
     const getFileName = (path: string) => {
@@ -236,7 +232,7 @@ export default function PlaylistManager({ onPlayFile, libraryFiles = [], isOpen,
                                             <GripVertical size={14} className="text-slate-600 cursor-grab" />
                                             <span className="text-xs text-slate-500 font-mono w-5">{i + 1}</span>
                                             <button
-                                                onClick={() => onPlayFile(item)}
+                                                onClick={() => playPlaylist(i)}
                                                 className="flex-1 text-left text-sm text-slate-300 hover:text-white truncate transition-colors"
                                             >
                                                 {getFileName(item)}
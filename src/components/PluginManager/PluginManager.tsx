@@ -0,0 +1,148 @@
+import { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { open } from '@tauri-apps/plugin-dialog';
+import { Puzzle, FilePlus, Link2, Trash2, SlidersHorizontal, Loader2 } from 'lucide-react';
+
+interface Plugin {
+    name: string;
+    file: string;
+    enabled: boolean;
+    size: number;
+    options: Record<string, string>;
+}
+
+// script-opts as editable "key=value" lines
+const toText = (options: Record<string, string>) => Object.entries(options).map(([k, v]) => `${k}=${v}`).join('\n');
+
+const fromText = (text: string) => Object.fromEntries(
+    text.split('\n')
+        .map(line => line.trim())
+        .filter(line => line && !line.startsWith('#') && line.includes('='))
+        .map(line => {
+            const at = line.indexOf('=');
+            return [line.slice(0, at).trim(), line.slice(at + 1).trim()];
+        })
+);
+
+export default function PluginManager() {
+    const [plugins, setPlugins] = useState<Plugin[]>([]);
+    const [url, setUrl] = useState('');
+    const [busy, setBusy] = useState(false);
+    const [editing, setEditing] = useState<string | null>(null);
+    const [optionsText, setOptionsText] = useState('');
+    const [error, setError] = useState('');
+
+    const refresh = () => invoke<Plugin[]>('list_plugins').then(setPlugins).catch(e => setError(String(e)));
+
+    useEffect(() => { refresh(); }, []);
+
+    const run = async (action: () => Promise<unknown>) => {
+        setError('');
+        setBusy(true);
+        try {
+            await action();
+        } catch (e) {
+            setError(String(e));
+        } finally {
+            setBusy(false);
+            refresh();
+        }
+    };
+
+    const installFile = async () => {
+        const selected = await open({ multiple: false, filters: [{ name: 'mpv script', extensions: ['lua', 'js'] }] });
+        if (typeof selected === 'string') run(() => invoke('install_plugin', { source: selected }));
+    };
+
+    const installUrl = () => {
+        if (!url.trim()) return;
+        run(async () => {
+            await invoke('install_plugin', { source: url.trim() });
+            setUrl('');
+        });
+    };
+
+    const startEditing = (plugin: Plugin) => {
+        setEditing(editing === plugin.file ? null : plugin.file);
+        setOptionsText(toText(plugin.options));
+    };
+
+    return (
+        <div className="p-4 bg-white/5 rounded-lg border border-white/5">
+            <div className="flex items-center gap-3 mb-3">
+                <div className="p-2 bg-emerald-500/20 rounded-lg">
+                    <Puzzle className="w-5 h-5 text-emerald-400" />
+                </div>
+                <div className="flex-1">
+                    <h4 className="text-white font-medium">Scripts</h4>
+                    <p className="text-xs text-slate-400">mpv Lua and JavaScript plugins, loaded into the player as you change them</p>
+                </div>
+                {busy && <Loader2 size={16} className="animate-spin text-slate-400" />}
+            </div>
+
+            <div className="flex gap-2 mb-3">
+                <input
+                    value={url}
+                    onChange={e => setUrl(e.target.value)}
+                    onKeyDown={e => e.key === 'Enter' && installUrl()}
+                    placeholder="https://github.com/…/script.lua"
+                    className="flex-1 px-3 py-2 bg-white/10 border border-white/10 rounded-lg text-white text-sm outline-none focus:border-white/30"
+                />
+                <button onClick={installUrl} disabled={busy || !url.trim()} className="px-3 py-2 bg-white/10 hover:bg-white/15 disabled:opacity-40 rounded-lg text-sm text-white flex items-center gap-1.5" title="Install from URL">
+                    <Link2 size={14} /> Install
+                </button>
+                <button onClick={installFile} disabled={busy} className="px-3 py-2 bg-white/10 hover:bg-white/15 disabled:opacity-40 rounded-lg text-sm text-white flex items-center gap-1.5" title="Install from file">
+                    <FilePlus size={14} /> File
+                </button>
+            </div>
+
+            <div className="space-y-1">
+                {plugins.length === 0 && <p className="text-sm text-slate-500 italic">No scripts installed</p>}
+                {plugins.map(plugin => (
+                    <div key={plugin.file} className="px-3 py-2 bg-white/5 rounded-lg">
+                        <div className="flex items-center gap-3 text-sm">
+                            <input
+                                type="checkbox"
+                                checked={plugin.enabled}
+                                disabled={busy}
+                                onChange={e => run(() => invoke('set_plugin_enabled', { file: plugin.file, enabled: e.target.checked }))}
+                            />
+                            <span className={`flex-1 truncate ${plugin.enabled ? 'text-white' : 'text-slate-500'}`} title={plugin.file}>{plugin.name}</span>
+                            <span className="text-[10px] text-slate-500">{(plugin.size / 1024).toFixed(1)} KB</span>
+                            <button onClick={() => startEditing(plugin)} className="p-1 text-slate-400 hover:text-white" title="Script options">
+                                <SlidersHorizontal size={14} />
+                            </button>
+                            <button onClick={() => run(() => invoke('uninstall_plugin', { file: plugin.file }))} disabled={busy} className="p-1 text-slate-400 hover:text-red-400" title="Remove">
+                                <Trash2 size={14} />
+                            </button>
+                        </div>
+                        {editing === plugin.file && (
+                            <div className="mt-2 space-y-2">
+                                <textarea
+                                    value={optionsText}
+                                    onChange={e => setOptionsText(e.target.value)}
+                                    rows={4}
+                                    placeholder="key=value"
+                                    className="w-full px-3 py-2 bg-black/30 border border-white/10 rounded-lg text-white text-xs font-mono outline-none focus:border-white/30"
+                                />
+                                <div className="flex justify-end">
+                                    <button
+                                        onClick={() => run(async () => {
+                                            await invoke('set_plugin_options', { name: plugin.name, options: fromText(optionsText) });
+                                            setEditing(null);
+                                        })}
+                                        className="px-3 py-1.5 bg-emerald-600 hover:bg-emerald-500 rounded-lg text-xs font-semibold text-white"
+                                    >
+                                        Save options
+                                    </button>
+                                </div>
+                            </div>
+                        )}
+                    </div>
+                ))}
+            </div>
+            <p className="mt-2 text-[11px] text-slate-500">Scripts that don't support unloading stay active until the player restarts.</p>
+            {error && <p className="mt-2 text-[11px] text-red-400">{error}</p>}
+        </div>
+    );
+}
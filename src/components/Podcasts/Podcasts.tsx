@@ -0,0 +1,285 @@
+import { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { Mic, X, Plus, RefreshCw, Trash2, Download, Play, CheckCircle2, ChevronLeft, Loader2 } from 'lucide-react';
+
+interface PodcastFeed {
+    id: string;
+    url: string;
+    title: string;
+    author: string | null;
+    description: string | null;
+    image: string | null;
+    last_refreshed: number;
+}
+
+interface EpisodeView {
+    id: string;
+    title: string;
+    description: string | null;
+    published: number | null;
+    duration: number | null;
+    image: string | null;
+    download_path: string | null;
+    path: string;
+    position: number | null;
+    finished: boolean;
+}
+
+interface DownloadProgress {
+    feed_id: string;
+    episode_id: string;
+    downloaded: number;
+    total: number | null;
+    done: boolean;
+    error: string | null;
+}
+
+interface PodcastsProps {
+    isOpen: boolean;
+    onClose: () => void;
+    onPlay: (path: string) => void;
+}
+
+const formatLength = (secs: number) => {
+    const h = Math.floor(secs / 3600);
+    const m = Math.round((secs % 3600) / 60);
+    return h > 0 ? `${h}h ${m}m` : `${m} min`;
+};
+
+export default function Podcasts({ isOpen, onClose, onPlay }: PodcastsProps) {
+    const [feeds, setFeeds] = useState<PodcastFeed[]>([]);
+    const [selected, setSelected] = useState<PodcastFeed | null>(null);
+    const [episodes, setEpisodes] = useState<EpisodeView[]>([]);
+    const [feedUrl, setFeedUrl] = useState('');
+    const [busy, setBusy] = useState(false);
+    const [error, setError] = useState('');
+    const [downloads, setDownloads] = useState<Record<string, DownloadProgress>>({});
+
+    const loadFeeds = () => invoke<PodcastFeed[]>('list_podcasts').then(setFeeds).catch(e => setError(String(e)));
+    const loadEpisodes = (id: string) => invoke<EpisodeView[]>('get_podcast_episodes', { id }).then(setEpisodes).catch(e => setError(String(e)));
+
+    useEffect(() => {
+        if (!isOpen) return;
+        loadFeeds();
+        const unlistenUpdates = listen<number>('podcasts-updated', () => {
+            loadFeeds();
+            setSelected(current => {
+                if (current) loadEpisodes(current.id);
+                return current;
+            });
+        });
+        const unlistenProgress = listen<DownloadProgress>('podcast-download-progress', (event) => {
+            const p = event.payload;
+            setDownloads(prev => ({ ...prev, [p.episode_id]: p }));
+            if (p.done && !p.error) loadEpisodes(p.feed_id);
+        });
+        return () => {
+            unlistenUpdates.then(f => f());
+            unlistenProgress.then(f => f());
+        };
+    }, [isOpen]);
+
+    useEffect(() => {
+        if (isOpen && selected) loadEpisodes(selected.id);
+    }, [isOpen, selected?.id]);
+
+    if (!isOpen) return null;
+
+    const handleSubscribe = async () => {
+        if (!feedUrl.trim()) return;
+        setBusy(true);
+        setError('');
+        try {
+            const feed = await invoke<PodcastFeed>('subscribe_podcast', { url: feedUrl });
+            setFeedUrl('');
+            await loadFeeds();
+            setSelected(feed);
+        } catch (e) {
+            setError(String(e));
+        } finally {
+            setBusy(false);
+        }
+    };
+
+    const handleRefresh = async () => {
+        setBusy(true);
+        setError('');
+        try {
+            await invoke<number>('refresh_podcasts', { id: selected?.id ?? null });
+            await loadFeeds();
+            if (selected) await loadEpisodes(selected.id);
+        } catch (e) {
+            setError(String(e));
+        } finally {
+            setBusy(false);
+        }
+    };
+
+    const handleUnsubscribe = async (feed: PodcastFeed) => {
+        try {
+            await invoke('unsubscribe_podcast', { id: feed.id });
+            setSelected(null);
+            loadFeeds();
+        } catch (e) {
+            setError(String(e));
+        }
+    };
+
+    const handleDownload = (episode: EpisodeView) => {
+        if (!selected) return;
+        setDownloads(prev => ({ ...prev, [episode.id]: { feed_id: selected.id, episode_id: episode.id, downloaded: 0, total: null, done: false, error: null } }));
+        invoke('download_podcast_episode', { feedId: selected.id, episodeId: episode.id }).catch(() => {});
+    };
+
+    const handleDeleteDownload = async (episode: EpisodeView) => {
+        if (!selected) return;
+        try {
+            await invoke('delete_podcast_download', { feedId: selected.id, episodeId: episode.id });
+            loadEpisodes(selected.id);
+        } catch (e) {
+            setError(String(e));
+        }
+    };
+
+    const toggleFinished = async (episode: EpisodeView) => {
+        if (!selected) return;
+        await invoke(episode.finished ? 'mark_unwatched' : 'mark_watched', { path: episode.path }).catch(e => setError(String(e)));
+        loadEpisodes(selected.id);
+    };
+
+    return (
+        <div className="fixed inset-0 z-[100] flex items-center justify-center bg-black/70 backdrop-blur-sm animate-fade-in" onClick={onClose}>
+            <div
+                className="w-full max-w-2xl mx-4 h-[80vh] flex flex-col bg-gradient-to-br from-slate-900 to-slate-950 border border-white/10 rounded-2xl shadow-2xl overflow-hidden animate-fade-in-up"
+                onClick={e => e.stopPropagation()}
+            >
+                {/* Header */}
+                <div className="flex items-center justify-between px-6 py-5 border-b border-white/10">
+                    <div className="flex items-center gap-3 min-w-0">
+                        {selected ? (
+                            <button onClick={() => setSelected(null)} className="p-2 text-slate-400 hover:text-white hover:bg-white/10 rounded-lg transition-all">
+                                <ChevronLeft size={20} />
+                            </button>
+                        ) : (
+                            <div className="p-2 bg-violet-500/20 rounded-lg">
+                                <Mic size={22} className="text-violet-400" />
+                            </div>
+                        )}
+                        <div className="min-w-0">
+                            <h2 className="text-xl font-bold text-white truncate">{selected?.title ?? 'Podcasts'}</h2>
+                            <p className="text-xs text-slate-500 truncate">{selected ? selected.author ?? selected.url : 'Subscriptions'}</p>
+                        </div>
+                    </div>
+                    <div className="flex items-center gap-1">
+                        <button onClick={handleRefresh} disabled={busy} title="Check for new episodes" className="p-2 text-slate-400 hover:text-white hover:bg-white/10 rounded-lg transition-all">
+                            <RefreshCw size={16} className={busy ? 'animate-spin' : ''} />
+                        </button>
+                        {selected && (
+                            <button onClick={() => handleUnsubscribe(selected)} title="Unsubscribe" className="p-2 text-slate-400 hover:text-red-400 hover:bg-white/10 rounded-lg transition-all">
+                                <Trash2 size={16} />
+                            </button>
+                        )}
+                        <button onClick={onClose} className="p-2 text-slate-400 hover:text-white hover:bg-white/10 rounded-lg transition-all">
+                            <X size={20} />
+                        </button>
+                    </div>
+                </div>
+
+                {error && <p className="px-6 pt-3 text-[11px] text-red-400">{error}</p>}
+
+                {!selected ? (
+                    <div className="flex-1 overflow-y-auto custom-scrollbar p-6 space-y-4">
+                        {/* Subscribe */}
+                        <div className="flex gap-2">
+                            <input
+                                value={feedUrl}
+                                onChange={(e) => setFeedUrl(e.target.value)}
+                                onKeyDown={(e) => e.key === 'Enter' && handleSubscribe()}
+                                placeholder="RSS feed URL"
+                                className="flex-1 min-w-0 px-3 py-2.5 bg-white/5 border border-white/10 rounded-xl text-sm text-white placeholder-slate-500 outline-none focus:border-violet-500/50 transition-colors font-mono"
+                            />
+                            <button
+                                onClick={handleSubscribe}
+                                disabled={busy || !feedUrl.trim()}
+                                className="px-4 py-2.5 bg-violet-600 hover:bg-violet-500 disabled:bg-slate-700 disabled:text-slate-500 text-white text-sm font-semibold rounded-xl transition-colors flex items-center gap-2"
+                            >
+                                {busy ? <Loader2 size={16} className="animate-spin" /> : <Plus size={16} />} Subscribe
+                            </button>
+                        </div>
+
+                        {/* Feeds */}
+                        <div className="grid grid-cols-2 sm:grid-cols-3 gap-3">
+                            {feeds.map(feed => (
+                                <button key={feed.id} onClick={() => setSelected(feed)} className="text-left p-2 bg-white/5 hover:bg-white/10 rounded-xl transition-colors">
+                                    <div className="aspect-square rounded-lg overflow-hidden bg-black/30 flex items-center justify-center">
+                                        {feed.image
+                                            ? <img src={feed.image} alt="" className="w-full h-full object-cover" />
+                                            : <Mic size={28} className="text-slate-600" />}
+                                    </div>
+                                    <p className="mt-2 text-sm font-medium text-white truncate">{feed.title}</p>
+                                    {feed.author && <p className="text-[11px] text-slate-500 truncate">{feed.author}</p>}
+                                </button>
+                            ))}
+                        </div>
+                        {feeds.length === 0 && (
+                            <p className="text-center text-sm text-slate-500 py-8">Subscribe to a feed to see its episodes here</p>
+                        )}
+                    </div>
+                ) : (
+                    <div className="flex-1 overflow-y-auto custom-scrollbar p-3 space-y-1">
+                        {episodes.map(episode => {
+                            const download = downloads[episode.id];
+                            const downloading = download && !download.done;
+                            const progress = episode.position && episode.duration ? Math.min(100, episode.position / episode.duration * 100) : 0;
+                            return (
+                                <div key={episode.id} className="flex items-start gap-3 px-3 py-3 bg-white/5 hover:bg-white/10 rounded-xl transition-colors group">
+                                    <button onClick={() => onPlay(episode.path)} title={episode.position ? 'Resume' : 'Play'} className="mt-0.5 p-2 text-violet-400 hover:bg-violet-500/20 rounded-lg transition-colors">
+                                        <Play size={14} fill="currentColor" />
+                                    </button>
+                                    <div className="flex-1 min-w-0">
+                                        <p className={`text-sm font-medium truncate ${episode.finished ? 'text-slate-500' : 'text-white'}`}>{episode.title}</p>
+                                        <p className="text-[11px] text-slate-500">
+                                            {[
+                                                episode.published ? new Date(episode.published * 1000).toLocaleDateString() : null,
+                                                episode.duration ? formatLength(episode.duration) : null,
+                                                episode.position && episode.duration ? `${formatLength(episode.duration - episode.position)} left` : null,
+                                                episode.download_path ? 'Downloaded' : null,
+                                            ].filter(Boolean).join(' · ')}
+                                        </p>
+                                        {episode.description && <p className="mt-1 text-[11px] text-slate-400 line-clamp-2">{episode.description}</p>}
+                                        {progress > 0 && !episode.finished && (
+                                            <div className="mt-1.5 h-0.5 bg-white/10 rounded-full overflow-hidden">
+                                                <div className="h-full bg-violet-500" style={{ width: `${progress}%` }} />
+                                            </div>
+                                        )}
+                                        {downloading && (
+                                            <p className="mt-1 text-[10px] text-violet-300">
+                                                Downloading {download.total ? `${Math.floor(download.downloaded / download.total * 100)}%` : `${(download.downloaded / 1024 / 1024).toFixed(1)} MB`}
+                                            </p>
+                                        )}
+                                        {download?.error && <p className="mt-1 text-[10px] text-red-400 truncate">{download.error}</p>}
+                                    </div>
+                                    <div className="flex items-center gap-1 opacity-0 group-hover:opacity-100 transition-all">
+                                        <button onClick={() => toggleFinished(episode)} title={episode.finished ? 'Mark unplayed' : 'Mark played'} className={`p-1.5 ${episode.finished ? 'text-emerald-400' : 'text-slate-500 hover:text-white'}`}>
+                                            <CheckCircle2 size={14} />
+                                        </button>
+                                        {episode.download_path ? (
+                                            <button onClick={() => handleDeleteDownload(episode)} title="Delete download" className="p-1.5 text-slate-500 hover:text-red-400">
+                                                <Trash2 size={14} />
+                                            </button>
+                                        ) : (
+                                            <button onClick={() => handleDownload(episode)} disabled={downloading} title="Download" className="p-1.5 text-slate-500 hover:text-white disabled:opacity-40">
+                                                <Download size={14} />
+                                            </button>
+                                        )}
+                                    </div>
+                                </div>
+                            );
+                        })}
+                    </div>
+                )}
+            </div>
+        </div>
+    );
+}
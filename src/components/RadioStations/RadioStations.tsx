@@ -0,0 +1,153 @@
+import { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { Radio, X, Plus, Trash2, Pencil, Play } from 'lucide-react';
+
+export interface RadioStation {
+    id: string;
+    name: string;
+    url: string;
+    logo: string | null;
+    genre: string | null;
+}
+
+interface RadioStationsProps {
+    isOpen: boolean;
+    onClose: () => void;
+    onPlay: (station: RadioStation) => void;
+}
+
+const EMPTY: RadioStation = { id: '', name: '', url: '', logo: null, genre: null };
+
+export default function RadioStations({ isOpen, onClose, onPlay }: RadioStationsProps) {
+    const [stations, setStations] = useState<RadioStation[]>([]);
+    const [editing, setEditing] = useState<RadioStation | null>(null);
+    const [error, setError] = useState('');
+
+    useEffect(() => {
+        if (!isOpen) return;
+        invoke<RadioStation[]>('list_radio_stations').then(list => {
+            setStations(list);
+            if (list.length === 0) setEditing(EMPTY);
+        }).catch(e => setError(String(e)));
+    }, [isOpen]);
+
+    if (!isOpen) return null;
+
+    const handleSave = async () => {
+        if (!editing) return;
+        setError('');
+        try {
+            const saved = await invoke<RadioStation>('save_radio_station', { station: editing });
+            setStations(prev => prev.some(s => s.id === saved.id) ? prev.map(s => (s.id === saved.id ? saved : s)) : [...prev, saved]);
+            setEditing(null);
+        } catch (e) {
+            setError(String(e));
+        }
+    };
+
+    const handleDelete = async (id: string) => {
+        try {
+            await invoke('delete_radio_station', { id });
+            setStations(prev => prev.filter(s => s.id !== id));
+        } catch (e) {
+            setError(String(e));
+        }
+    };
+
+    const field = (key: 'name' | 'url' | 'logo' | 'genre', placeholder: string) => (
+        <input
+            value={editing?.[key] ?? ''}
+            onChange={(e) => editing && setEditing({ ...editing, [key]: e.target.value })}
+            onKeyDown={(e) => e.key === 'Enter' && handleSave()}
+            placeholder={placeholder}
+            className="w-full px-3 py-2.5 bg-white/5 border border-white/10 rounded-xl text-sm text-white placeholder-slate-500 outline-none focus:border-amber-500/50 transition-colors"
+        />
+    );
+
+    return (
+        <div className="fixed inset-0 z-[100] flex items-center justify-center bg-black/70 backdrop-blur-sm animate-fade-in" onClick={onClose}>
+            <div
+                className="w-full max-w-lg mx-4 bg-gradient-to-br from-slate-900 to-slate-950 border border-white/10 rounded-2xl shadow-2xl overflow-hidden animate-fade-in-up"
+                onClick={e => e.stopPropagation()}
+            >
+                {/* Header */}
+                <div className="flex items-center justify-between px-6 py-5 border-b border-white/10">
+                    <div className="flex items-center gap-3">
+                        <div className="p-2 bg-amber-500/20 rounded-lg">
+                            <Radio size={22} className="text-amber-400" />
+                        </div>
+                        <div>
+                            <h2 className="text-xl font-bold text-white">Radio</h2>
+                            <p className="text-xs text-slate-500">Internet radio and audio streams</p>
+                        </div>
+                    </div>
+                    <div className="flex items-center gap-1">
+                        <button onClick={() => setEditing(EMPTY)} title="Add station" className="p-2 text-slate-400 hover:text-white hover:bg-white/10 rounded-lg transition-all">
+                            <Plus size={18} />
+                        </button>
+                        <button onClick={onClose} className="p-2 text-slate-400 hover:text-white hover:bg-white/10 rounded-lg transition-all">
+                            <X size={20} />
+                        </button>
+                    </div>
+                </div>
+
+                <div className="p-6 space-y-4">
+                    {/* Add / edit */}
+                    {editing && (
+                        <div className="space-y-2 pb-4 border-b border-white/5">
+                            {field('url', 'Stream URL (http://…, .pls, .m3u)')}
+                            <div className="grid grid-cols-2 gap-2">
+                                {field('name', 'Name')}
+                                {field('genre', 'Genre (optional)')}
+                            </div>
+                            {field('logo', 'Logo URL (optional)')}
+                            <div className="flex justify-end gap-2">
+                                <button onClick={() => setEditing(null)} className="px-4 py-2 text-sm text-slate-400 hover:text-white transition-colors">
+                                    Cancel
+                                </button>
+                                <button
+                                    onClick={handleSave}
+                                    disabled={!editing.url.trim()}
+                                    className="px-4 py-2 bg-amber-600 hover:bg-amber-500 disabled:bg-slate-700 disabled:text-slate-500 text-white text-sm font-semibold rounded-xl transition-colors"
+                                >
+                                    {editing.id ? 'Save' : 'Add Station'}
+                                </button>
+                            </div>
+                        </div>
+                    )}
+
+                    {error && <p className="text-[11px] text-red-400">{error}</p>}
+
+                    {/* Stations */}
+                    <div className="space-y-1 max-h-80 overflow-y-auto custom-scrollbar">
+                        {stations.map(station => (
+                            <div key={station.id} className="flex items-center gap-3 px-3 py-2.5 bg-white/5 hover:bg-white/10 rounded-xl transition-colors group">
+                                <div className="w-10 h-10 flex-shrink-0 flex items-center justify-center bg-black/30 rounded-lg overflow-hidden">
+                                    {station.logo
+                                        ? <img src={station.logo} alt="" className="w-full h-full object-cover" onError={(e) => { e.currentTarget.style.display = 'none'; }} />
+                                        : <Radio size={16} className="text-slate-600" />}
+                                </div>
+                                <button onClick={() => onPlay(station)} className="flex-1 min-w-0 text-left">
+                                    <p className="text-sm font-medium text-white truncate">{station.name}</p>
+                                    <p className="text-[11px] text-slate-500 truncate">{station.genre ?? station.url}</p>
+                                </button>
+                                <button onClick={() => setEditing(station)} title="Edit" className="p-1.5 text-slate-600 hover:text-white opacity-0 group-hover:opacity-100 transition-all">
+                                    <Pencil size={13} />
+                                </button>
+                                <button onClick={() => handleDelete(station.id)} title="Remove" className="p-1.5 text-slate-600 hover:text-red-400 opacity-0 group-hover:opacity-100 transition-all">
+                                    <Trash2 size={13} />
+                                </button>
+                                <button onClick={() => onPlay(station)} title="Play" className="p-2 text-amber-400 hover:bg-amber-500/20 rounded-lg transition-colors">
+                                    <Play size={14} fill="currentColor" />
+                                </button>
+                            </div>
+                        ))}
+                        {stations.length === 0 && !editing && (
+                            <p className="text-center text-sm text-slate-500 py-8">No stations saved yet</p>
+                        )}
+                    </div>
+                </div>
+            </div>
+        </div>
+    );
+}
@@ -0,0 +1,176 @@
+import { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { X, Scissors, Trash2, Wand2, Loader2 } from 'lucide-react';
+
+export interface Segment {
+    id: number;
+    start: number;
+    end: number;
+    kind: string;
+    source: 'manual' | 'sponsorblock' | 'detected';
+}
+
+export const SEGMENT_LABELS: Record<string, string> = {
+    intro: 'Intro',
+    recap: 'Recap',
+    credits: 'Credits',
+    preview: 'Preview',
+    sponsor: 'Sponsor',
+};
+
+const SOURCE_LABELS: Record<Segment['source'], string> = {
+    manual: 'yours',
+    sponsorblock: 'SponsorBlock',
+    detected: 'detected',
+};
+
+interface SegmentEditorProps {
+    isOpen: boolean;
+    onClose: () => void;
+    path: string | null;
+    currentTime: number;
+    segments: Segment[];
+}
+
+const formatTime = (secs: number) => {
+    const s = Math.floor(secs);
+    const h = Math.floor(s / 3600);
+    const m = Math.floor((s % 3600) / 60);
+    const pad = (n: number) => n.toString().padStart(2, '0');
+    return h > 0 ? `${h}:${pad(m)}:${pad(s % 60)}` : `${m}:${pad(s % 60)}`;
+};
+
+// Mark ranges of the playing file to skip. The list refreshes through the `segments` event.
+export default function SegmentEditor({ isOpen, onClose, path, currentTime, segments }: SegmentEditorProps) {
+    const [start, setStart] = useState<number | null>(null);
+    const [end, setEnd] = useState<number | null>(null);
+    const [kind, setKind] = useState('intro');
+    const [error, setError] = useState('');
+    const [detecting, setDetecting] = useState<{ done: number; total: number } | null>(null);
+    const [detectResult, setDetectResult] = useState('');
+
+    useEffect(() => {
+        const unlisten = listen<{ done: number; total: number }>('segment-detection', (event) => setDetecting(event.payload));
+        return () => { unlisten.then(f => f()); };
+    }, []);
+
+    if (!isOpen) return null;
+
+    const detect = async () => {
+        if (!path) return;
+        setError('');
+        setDetectResult('');
+        setDetecting({ done: 0, total: 0 });
+        try {
+            const episodes = await invoke<{ path: string; intro: [number, number] | null }[]>('detect_season_segments', { path });
+            setDetectResult(`Found intros in ${episodes.filter(e => e.intro).length} of ${episodes.length} episodes`);
+        } catch (e) {
+            setError(String(e));
+        } finally {
+            setDetecting(null);
+        }
+    };
+
+    const add = async () => {
+        if (!path || start === null || end === null) return;
+        setError('');
+        try {
+            await invoke('add_segment', { path, start, end, kind });
+            setStart(null);
+            setEnd(null);
+        } catch (e) {
+            setError(String(e));
+        }
+    };
+
+    const remove = (id: number) => {
+        invoke('delete_segment', { id }).catch(e => setError(String(e)));
+    };
+
+    const markButton = (label: string, value: number | null, set: (t: number) => void) => (
+        <button
+            onClick={() => set(currentTime)}
+            className="flex-1 py-2 bg-white/5 hover:bg-white/10 border border-white/10 rounded-xl text-sm text-white transition-colors"
+        >
+            {label} <span className="font-mono text-slate-400">{value !== null ? formatTime(value) : '--:--'}</span>
+        </button>
+    );
+
+    return (
+        <div className="fixed inset-0 z-[100] flex items-center justify-center bg-black/70 backdrop-blur-sm animate-fade-in" onClick={onClose}>
+            <div
+                className="w-full max-w-md mx-4 flex flex-col max-h-[80vh] bg-gradient-to-br from-slate-900 to-slate-950 border border-white/10 rounded-2xl shadow-2xl overflow-hidden animate-fade-in-up"
+                onClick={e => e.stopPropagation()}
+            >
+                {/* Header */}
+                <div className="flex items-center justify-between px-6 py-5 border-b border-white/10">
+                    <div className="flex items-center gap-3">
+                        <div className="p-2 bg-violet-500/20 rounded-lg">
+                            <Scissors size={22} className="text-violet-400" />
+                        </div>
+                        <div>
+                            <h2 className="text-xl font-bold text-white">Skip Segments</h2>
+                            <p className="text-xs text-slate-500">Mark intros, recaps and credits in this video</p>
+                        </div>
+                    </div>
+                    <button onClick={onClose} className="p-2 text-slate-400 hover:text-white hover:bg-white/10 rounded-lg transition-all">
+                        <X size={20} />
+                    </button>
+                </div>
+
+                <div className="p-6 space-y-3">
+                    <div className="flex gap-2">
+                        {markButton('Start', start, setStart)}
+                        {markButton('End', end, setEnd)}
+                    </div>
+                    <div className="flex gap-2">
+                        <select
+                            value={kind}
+                            onChange={(e) => setKind(e.target.value)}
+                            className="flex-1 px-3 py-2.5 bg-slate-900 border border-white/10 rounded-xl text-sm text-white outline-none focus:border-violet-500/50"
+                        >
+                            {Object.entries(SEGMENT_LABELS).map(([value, label]) => (
+                                <option key={value} value={value}>{label}</option>
+                            ))}
+                        </select>
+                        <button
+                            onClick={add}
+                            disabled={!path || start === null || end === null}
+                            className="px-4 py-2.5 bg-violet-600 hover:bg-violet-500 disabled:bg-slate-700 disabled:text-slate-500 text-white text-sm font-semibold rounded-xl transition-colors"
+                        >
+                            Add
+                        </button>
+                    </div>
+                    <button
+                        onClick={detecting ? () => invoke('cancel_segment_detection') : detect}
+                        disabled={!path || (path.includes('://') && !detecting)}
+                        className="w-full py-2 bg-white/5 hover:bg-white/10 disabled:opacity-40 border border-white/10 rounded-xl text-sm text-slate-300 transition-colors flex items-center justify-center gap-2"
+                    >
+                        {detecting ? (
+                            <><Loader2 size={14} className="animate-spin" /> Analysing episodes {detecting.total ? `${detecting.done}/${detecting.total}` : ''} · Cancel</>
+                        ) : (
+                            <><Wand2 size={14} /> Detect intros and credits for this season</>
+                        )}
+                    </button>
+                    {detectResult && <p className="text-[11px] text-slate-400">{detectResult}</p>}
+                    {error && <p className="text-[11px] text-red-400">{error}</p>}
+                </div>
+
+                <div className="flex-1 overflow-y-auto custom-scrollbar px-6 pb-5 space-y-1">
+                    {segments.length === 0 && <p className="text-sm text-slate-500 italic">No segments for this video yet</p>}
+                    {segments.map(s => (
+                        <div key={s.id} className="flex items-center gap-3 px-3 py-2 bg-white/5 rounded-xl text-sm">
+                            <span className="text-white font-medium w-16">{SEGMENT_LABELS[s.kind] ?? s.kind}</span>
+                            <span className="font-mono text-slate-400">{formatTime(s.start)} – {formatTime(s.end)}</span>
+                            <span className="flex-1 text-right text-[10px] text-slate-500">{SOURCE_LABELS[s.source] ?? s.source}</span>
+                            <button onClick={() => remove(s.id)} title="Delete" className="p-1 text-slate-500 hover:text-red-400">
+                                <Trash2 size={14} />
+                            </button>
+                        </div>
+                    ))}
+                </div>
+            </div>
+        </div>
+    );
+}
@@ -1,5 +1,7 @@
 import { useState, useEffect } from 'react';
-import { Globe, X, Clock, Wifi, Play, Trash2 } from 'lucide-react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { Globe, X, Clock, Wifi, Play, Trash2, Download } from 'lucide-react';
 
 interface StreamDialogProps {
     isOpen: boolean;
@@ -7,12 +9,77 @@ interface StreamDialogProps {
     onPlay: (url: string) => void;
 }
 
-const PROTOCOLS = ['http://', 'https://', 'rtsp://', 'rtmp://'];
+const PROTOCOLS = ['http://', 'https://', 'rtsp://', 'rtmp://', 'magnet:'];
+
+interface DownloadFormat {
+    format_id: string;
+    ext: string;
+    resolution: string | null;
+    fps: number | null;
+    vcodec: string | null;
+    acodec: string | null;
+    filesize: number | null;
+    note: string | null;
+}
+
+interface DownloadItem {
+    id: string;
+    url: string;
+    title: string | null;
+    status: 'queued' | 'downloading' | 'processing' | 'done' | 'failed' | 'cancelled';
+    progress: number;
+    speed: string | null;
+    eta: string | null;
+    file_path: string | null;
+    error: string | null;
+}
+
+// yt-dlp selectors; an empty one uses the default from settings
+const FORMAT_PRESETS = [
+    { label: 'Best quality', format: '' },
+    { label: 'Up to 1080p', format: 'bv*[height<=1080]+ba/b[height<=1080]' },
+    { label: 'Up to 720p', format: 'bv*[height<=720]+ba/b[height<=720]' },
+];
+
+function describeFormat(f: DownloadFormat): string {
+    const size = f.filesize ? ` · ${(f.filesize / 1024 / 1024).toFixed(0)} MB` : '';
+    const kind = f.vcodec ? (f.resolution ?? f.note ?? 'video') + (f.fps ? ` ${Math.round(f.fps)}fps` : '') : 'audio only';
+    return `${kind} · ${f.ext}${size} (${f.format_id})`;
+}
+
+// A video-only pick still needs a soundtrack
+const selectorFor = (f: DownloadFormat) => (f.vcodec && !f.acodec ? `${f.format_id}+ba/${f.format_id}` : f.format_id);
 
 export default function StreamDialog({ isOpen, onClose, onPlay }: StreamDialogProps) {
     const [url, setUrl] = useState('');
     const [recentUrls, setRecentUrls] = useState<string[]>([]);
     const [isValid, setIsValid] = useState(false);
+    const [formats, setFormats] = useState<DownloadFormat[] | null>(null);
+    const [format, setFormat] = useState('');
+    const [loadingFormats, setLoadingFormats] = useState(false);
+    const [downloadError, setDownloadError] = useState('');
+    const [downloads, setDownloads] = useState<DownloadItem[]>([]);
+
+    useEffect(() => {
+        if (!isOpen) return;
+        invoke<DownloadItem[]>('list_downloads').then(setDownloads).catch(() => {});
+        const unlisten = listen<DownloadItem>('download-progress', (event) => {
+            setDownloads(prev => {
+                const index = prev.findIndex(d => d.id === event.payload.id);
+                if (index === -1) return [...prev, event.payload];
+                const next = [...prev];
+                next[index] = event.payload;
+                return next;
+            });
+        });
+        return () => { unlisten.then(f => f()); };
+    }, [isOpen]);
+
+    useEffect(() => {
+        setFormats(null);
+        setFormat('');
+        setDownloadError('');
+    }, [url]);
 
     useEffect(() => {
         if (isOpen) {
@@ -30,7 +97,7 @@ export default function StreamDialog({ isOpen, onClose, onPlay }: StreamDialogPr
     }, []);
 
     useEffect(() => {
-        const valid = PROTOCOLS.some(p => url.toLowerCase().startsWith(p)) || url.includes('.m3u8') || url.includes('.m3u');
+        const valid = PROTOCOLS.some(p => url.toLowerCase().startsWith(p)) || url.includes('.m3u8') || url.includes('.m3u') || url.toLowerCase().endsWith('.torrent');
         setIsValid(valid && url.length > 10);
     }, [url]);
 
@@ -46,6 +113,29 @@ export default function StreamDialog({ isOpen, onClose, onPlay }: StreamDialogPr
         onClose();
     };
 
+    const canDownload = url.startsWith('http://') || url.startsWith('https://');
+
+    const loadFormats = async () => {
+        setLoadingFormats(true);
+        setDownloadError('');
+        try {
+            setFormats((await invoke<{ formats: DownloadFormat[] }>('list_download_formats', { url })).formats);
+        } catch (e) {
+            setDownloadError(String(e));
+        } finally {
+            setLoadingFormats(false);
+        }
+    };
+
+    const handleDownload = async () => {
+        setDownloadError('');
+        try {
+            await invoke('queue_download', { url, format: format || null });
+        } catch (e) {
+            setDownloadError(String(e));
+        }
+    };
+
     const removeRecent = (urlToRemove: string) => {
         const updated = recentUrls.filter(u => u !== urlToRemove);
         setRecentUrls(updated);
@@ -53,6 +143,7 @@ export default function StreamDialog({ isOpen, onClose, onPlay }: StreamDialogPr
     };
 
     const detectProtocol = () => {
+        if (url.startsWith('magnet:') || url.toLowerCase().endsWith('.torrent')) return 'Torrent';
         if (url.includes('.m3u8')) return 'HLS Stream';
         if (url.includes('.m3u')) return 'Playlist';
         if (url.startsWith('rtsp://')) return 'RTSP Stream';
@@ -91,7 +182,7 @@ export default function StreamDialog({ isOpen, onClose, onPlay }: StreamDialogPr
                             value={url}
                             onChange={(e) => setUrl(e.target.value)}
                             onKeyDown={(e) => e.key === 'Enter' && handlePlay()}
-                            placeholder="Enter stream URL (http://, rtsp://, .m3u8...)"
+                            placeholder="Enter stream URL (http://, rtsp://, .m3u8, magnet:...)"
                             className="w-full px-4 py-3.5 bg-white/5 border border-white/10 rounded-xl text-white placeholder-slate-500 outline-none focus:border-cyan-500/50 focus:ring-2 focus:ring-cyan-500/20 transition-all font-mono text-sm"
                             autoFocus
                         />
@@ -109,7 +200,7 @@ export default function StreamDialog({ isOpen, onClose, onPlay }: StreamDialogPr
 
                     {/* Supported Protocols */}
                     <div className="flex flex-wrap gap-2">
-                        {['HTTP/HTTPS', 'HLS (.m3u8)', 'RTSP', 'RTMP'].map(proto => (
+                        {['HTTP/HTTPS', 'HLS (.m3u8)', 'RTSP', 'RTMP', 'Magnet / .torrent'].map(proto => (
                             <span key={proto} className="px-2.5 py-1 text-[10px] bg-white/5 text-slate-500 rounded-md border border-white/5 font-medium">
                                 <Wifi size={10} className="inline mr-1" />{proto}
                             </span>
@@ -125,6 +216,80 @@ export default function StreamDialog({ isOpen, onClose, onPlay }: StreamDialogPr
                         <Play size={18} fill="currentColor" /> Play Stream
                     </button>
 
+                    {/* Download to the library with yt-dlp */}
+                    {canDownload && (
+                        <div className="space-y-2">
+                            <div className="flex gap-2">
+                                <select
+                                    value={format}
+                                    onChange={(e) => setFormat(e.target.value)}
+                                    onFocus={() => { if (!formats && !loadingFormats) loadFormats(); }}
+                                    className="flex-1 min-w-0 px-3 py-2.5 bg-white/5 border border-white/10 rounded-xl text-sm text-white outline-none focus:border-cyan-500/50 transition-colors"
+                                >
+                                    {FORMAT_PRESETS.map(p => (
+                                        <option key={p.label} value={p.format} className="bg-slate-900">{p.label}</option>
+                                    ))}
+                                    {loadingFormats && <option disabled className="bg-slate-900">Loading formats…</option>}
+                                    {formats?.map(f => (
+                                        <option key={f.format_id} value={selectorFor(f)} className="bg-slate-900">{describeFormat(f)}</option>
+                                    ))}
+                                </select>
+                                <button
+                                    onClick={handleDownload}
+                                    className="px-4 py-2.5 bg-white/5 hover:bg-white/10 border border-white/10 text-slate-200 rounded-xl text-sm transition-colors flex items-center gap-2"
+                                >
+                                    <Download size={16} /> Download
+                                </button>
+                            </div>
+                            {downloadError && <p className="text-[10px] text-red-400">{downloadError}</p>}
+                        </div>
+                    )}
+
+                    {/* Download queue */}
+                    {downloads.length > 0 && (
+                        <div className="space-y-2 pt-2 border-t border-white/5">
+                            <div className="flex items-center justify-between">
+                                <h4 className="text-xs font-semibold text-slate-500 uppercase tracking-wider flex items-center gap-1.5">
+                                    <Download size={12} /> Downloads
+                                </h4>
+                                <button
+                                    onClick={() => invoke('clear_finished_downloads').then(() => setDownloads(prev => prev.filter(d => ['queued', 'downloading', 'processing'].includes(d.status))))}
+                                    className="text-[10px] text-slate-500 hover:text-white transition-colors"
+                                >
+                                    Clear finished
+                                </button>
+                            </div>
+                            <div className="space-y-1 max-h-40 overflow-y-auto custom-scrollbar">
+                                {downloads.map(d => (
+                                    <div key={d.id} className="px-3 py-2 bg-white/5 rounded-lg group">
+                                        <div className="flex items-center gap-2">
+                                            <span className="flex-1 text-xs text-slate-300 truncate">{d.title ?? d.url}</span>
+                                            <span className="text-[10px] text-slate-500">
+                                                {d.status === 'downloading'
+                                                    ? `${d.progress.toFixed(0)}%${d.speed ? ` · ${d.speed}` : ''}${d.eta ? ` · ${d.eta}` : ''}`
+                                                    : d.status}
+                                            </span>
+                                            {['queued', 'downloading', 'processing'].includes(d.status) && (
+                                                <button
+                                                    onClick={() => invoke('cancel_download', { id: d.id })}
+                                                    className="p-1 text-slate-600 hover:text-red-400 opacity-0 group-hover:opacity-100 transition-all"
+                                                >
+                                                    <X size={12} />
+                                                </button>
+                                            )}
+                                        </div>
+                                        {d.status === 'downloading' && (
+                                            <div className="mt-1.5 h-1 bg-white/10 rounded-full overflow-hidden">
+                                                <div className="h-full bg-cyan-500 transition-all" style={{ width: `${d.progress}%` }} />
+                                            </div>
+                                        )}
+                                        {d.error && <p className="mt-1 text-[10px] text-red-400 truncate">{d.error}</p>}
+                                    </div>
+                                ))}
+                            </div>
+                        </div>
+                    )}
+
                     {/* Recent URLs */}
                     {recentUrls.length > 0 && (
                         <div className="space-y-2 pt-2 border-t border-white/5">
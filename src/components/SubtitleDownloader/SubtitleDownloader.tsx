@@ -2,7 +2,7 @@ import { useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { Search, Download, Languages, X, Loader2, Captions } from 'lucide-react';
 import { useSettings } from '../../context/SettingsContext';
-import type { SubtitleResult } from '../../types/playlist';
+import { describeSubtitleError, type SubtitleResult } from '../../types/playlist';
 
 interface SubtitleDownloaderProps {
     videoPath?: string | null;
@@ -36,9 +36,9 @@ export default function SubtitleDownloader({ videoPath, onSubtitleLoaded, compac
     const [success, setSuccess] = useState('');
 
     const handleSearch = async () => {
-        if (!query.trim()) return;
-        if (!settings.openSubtitlesApiKey) {
-            setError('Please set your OpenSubtitles API key in Settings → Integrations');
+        if (!query.trim() && !videoPath) return;
+        if (!settings.openSubtitlesApiKey && !settings.jimakuApiKey) {
+            setError('Please set an OpenSubtitles or Jimaku API key in Settings → Integrations');
             return;
         }
 
@@ -47,10 +47,19 @@ export default function SubtitleDownloader({ videoPath, onSubtitleLoaded, compac
         setResults([]);
 
         try {
+            // Hash matches are synced to this exact file; fall back to the text query without one
+            const hash = videoPath
+                ? await invoke<{ moviehash: string; file_size: number }>('compute_video_hash', { videoPath }).catch(() => null)
+                : null;
             const data = await invoke<SubtitleResult[]>('search_subtitles', {
                 query: query.trim(),
                 language,
                 apiKey: settings.openSubtitlesApiKey,
+                moviehash: hash?.moviehash,
+                fileSize: hash?.file_size,
+                fileName: videoPath ? videoPath.split(/[\\/]/).pop() : undefined,
+                // Lets episodes be searched by show/season/episode instead of a terse file name
+                videoPath,
             });
             setResults(data);
             if (data.length === 0) setError('No subtitles found. Try a different search term.');
@@ -62,12 +71,15 @@ export default function SubtitleDownloader({ videoPath, onSubtitleLoaded, compac
     };
 
     const handleDownload = async (result: SubtitleResult) => {
-        if (!settings.openSubtitlesApiKey) {
+        if ((result.provider ?? 'opensubtitles') === 'opensubtitles' && !settings.openSubtitlesApiKey) {
             setError('API key required');
             return;
         }
 
-        const saveDir = videoPath ? videoPath.substring(0, videoPath.lastIndexOf('\\')) : '.';
+        if (!videoPath) {
+            setError('Open a video to save subtitles next to it');
+            return;
+        }
         setDownloading(result.id);
         setError('');
         setSuccess('');
@@ -75,14 +87,19 @@ export default function SubtitleDownloader({ videoPath, onSubtitleLoaded, compac
         try {
             const savedPath = await invoke<string>('download_subtitle', {
                 fileId: result.file_id,
-                saveDir,
                 fileName: result.file_name,
                 apiKey: settings.openSubtitlesApiKey,
+                provider: result.provider,
+                downloadUrl: result.download_url,
+                // Saved as `<video>.<lang>.<ext>` so players pick it up; earlier downloads are kept
+                videoPath,
+                language: result.language || language,
+                keepAlternatives: true,
             });
             setSuccess(`Downloaded: ${result.file_name}`);
             onSubtitleLoaded?.(savedPath);
         } catch (e) {
-            setError(`Download failed: ${String(e)}`);
+            setError(`Download failed: ${describeSubtitleError(e)}`);
         } finally {
             setDownloading(null);
         }
@@ -173,7 +190,7 @@ export default function SubtitleDownloader({ videoPath, onSubtitleLoaded, compac
                 </select>
                 <button
                     onClick={handleSearch}
-                    disabled={loading || !query.trim()}
+                    disabled={loading || (!query.trim() && !videoPath)}
                     className="px-4 py-2.5 bg-blue-600 hover:bg-blue-500 disabled:bg-slate-700 text-white rounded-xl transition-colors flex items-center gap-2 font-medium"
                 >
                     {loading ? <Loader2 size={16} className="animate-spin" /> : <Search size={16} />}
@@ -197,6 +214,13 @@ export default function SubtitleDownloader({ videoPath, onSubtitleLoaded, compac
                                     </span>
                                     <span>{r.download_count.toLocaleString()} downloads</span>
                                     {r.hearing_impaired && <span className="text-amber-400">HI</span>}
+                                    {r.hash_match && <span className="text-emerald-400">Synced</span>}
+                                    {!r.hash_match && r.match_confidence != null && r.match_confidence > 0 && (
+                                        <span className={r.match_confidence >= 0.7 ? 'text-emerald-400' : 'text-slate-400'}>
+                                            {Math.round(r.match_confidence * 100)}% match
+                                        </span>
+                                    )}
+                                    {r.provider === 'jimaku' && <span className="text-pink-400">Jimaku</span>}
                                     {r.release && <span className="text-slate-600 truncate">{r.release}</span>}
                                 </div>
                             </div>
@@ -0,0 +1,37 @@
+import { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { openUrl } from '@tauri-apps/plugin-opener';
+import { ArrowUpCircle, X } from 'lucide-react';
+import type { UpdateInfo } from './UpdateSettings';
+
+// Announces the background check's find; details and install live in Settings > About
+export default function UpdateBanner() {
+    const [update, setUpdate] = useState<UpdateInfo | null>(null);
+
+    useEffect(() => {
+        const unlisten = listen<UpdateInfo>('update-available', e => setUpdate(e.payload));
+        return () => { unlisten.then(f => f()); };
+    }, []);
+
+    if (!update) return null;
+
+    return (
+        <div className="fixed bottom-6 right-6 z-[90] flex items-center gap-3 px-4 py-3 bg-slate-900/95 border border-sky-500/30 rounded-xl shadow-2xl backdrop-blur">
+            <ArrowUpCircle className="w-5 h-5 text-sky-400" />
+            <span className="text-sm text-white">FrameX {update.version} is available</span>
+            <button onClick={() => openUrl(update.url)} className="px-3 py-1 bg-sky-600 hover:bg-sky-500 rounded-lg text-xs font-semibold text-white">
+                What's new
+            </button>
+            <button
+                onClick={() => invoke('skip_update', { version: update.version }).finally(() => setUpdate(null))}
+                className="text-xs text-slate-400 hover:text-white"
+            >
+                Skip
+            </button>
+            <button onClick={() => setUpdate(null)} className="p-1 text-slate-400 hover:text-white" title="Dismiss">
+                <X size={14} />
+            </button>
+        </div>
+    );
+}
@@ -0,0 +1,150 @@
+import { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { openUrl } from '@tauri-apps/plugin-opener';
+import { ArrowUpCircle, RefreshCw, Loader2 } from 'lucide-react';
+
+export interface UpdateInfo {
+    version: string;
+    current: string;
+    name: string;
+    notes: string;
+    url: string;
+    published_at: string | null;
+    prerelease: boolean;
+}
+
+interface UpdateSettingsData {
+    check_hours: number;
+    include_prereleases: boolean;
+    skipped_version: string | null;
+    last_check: number;
+}
+
+const INTERVALS: [number, string][] = [
+    [0, 'Never'],
+    [6, 'Every 6 hours'],
+    [24, 'Daily'],
+    [168, 'Weekly'],
+];
+
+export default function UpdateSettings() {
+    const [settings, setSettings] = useState<UpdateSettingsData | null>(null);
+    const [update, setUpdate] = useState<UpdateInfo | null>(null);
+    const [canInstall, setCanInstall] = useState(false);
+    const [busy, setBusy] = useState(false);
+    const [progress, setProgress] = useState<number | null>(null);
+    const [status, setStatus] = useState('');
+
+    const loadSettings = () => invoke<UpdateSettingsData>('get_update_settings').then(setSettings).catch(() => {});
+
+    useEffect(() => {
+        loadSettings();
+        invoke<boolean>('can_install_updates').then(setCanInstall).catch(() => {});
+        const unlistenUpdate = listen<UpdateInfo>('update-available', e => setUpdate(e.payload));
+        const unlistenProgress = listen<[number, number | null]>('update-progress', e => {
+            const [done, total] = e.payload;
+            setProgress(total ? Math.round(done / total * 100) : null);
+        });
+        return () => {
+            unlistenUpdate.then(f => f());
+            unlistenProgress.then(f => f());
+        };
+    }, []);
+
+    const save = (changes: Partial<UpdateSettingsData>) => {
+        if (!settings) return;
+        const next = { ...settings, ...changes };
+        setSettings(next);
+        invoke('save_update_settings', { settings: next }).catch(e => setStatus(String(e)));
+    };
+
+    const checkNow = async () => {
+        setBusy(true);
+        setStatus('');
+        try {
+            const found = await invoke<UpdateInfo | null>('check_for_updates');
+            setUpdate(found);
+            if (!found) setStatus('FrameX is up to date');
+        } catch (e) {
+            setStatus(String(e));
+        } finally {
+            setBusy(false);
+            loadSettings();
+        }
+    };
+
+    const install = async () => {
+        setBusy(true);
+        try {
+            await invoke('install_update');
+        } catch (e) {
+            setStatus(String(e));
+            setBusy(false);
+        }
+    };
+
+    const skip = () => {
+        if (!update) return;
+        invoke('skip_update', { version: update.version }).then(() => { setUpdate(null); loadSettings(); }).catch(e => setStatus(String(e)));
+    };
+
+    if (!settings) return null;
+
+    return (
+        <div className="p-4 bg-white/5 rounded-lg border border-white/5">
+            <div className="flex items-center gap-3 mb-3">
+                <div className="p-2 bg-sky-500/20 rounded-lg">
+                    <ArrowUpCircle className="w-5 h-5 text-sky-400" />
+                </div>
+                <div className="flex-1">
+                    <h4 className="text-white font-medium">Updates</h4>
+                    <p className="text-xs text-slate-400">
+                        {settings.last_check ? `Last checked ${new Date(settings.last_check * 1000).toLocaleString()}` : 'Not checked yet'}
+                    </p>
+                </div>
+                <button onClick={checkNow} disabled={busy} className="px-3 py-1.5 bg-white/10 hover:bg-white/15 disabled:opacity-40 rounded-lg text-xs text-white flex items-center gap-1.5">
+                    {busy ? <Loader2 size={14} className="animate-spin" /> : <RefreshCw size={14} />} Check now
+                </button>
+            </div>
+
+            <div className="flex items-center gap-4 text-sm">
+                <select
+                    value={settings.check_hours}
+                    onChange={e => save({ check_hours: Number(e.target.value) })}
+                    className="px-3 py-1.5 bg-slate-900 border border-white/10 rounded-lg text-white text-xs outline-none focus:border-white/30"
+                >
+                    {INTERVALS.map(([hours, label]) => <option key={hours} value={hours}>{label}</option>)}
+                </select>
+                <label className="flex items-center gap-2 text-xs text-slate-300">
+                    <input type="checkbox" checked={settings.include_prereleases} onChange={e => save({ include_prereleases: e.target.checked })} />
+                    Include pre-releases
+                </label>
+            </div>
+
+            {update && (
+                <div className="mt-3 p-3 bg-sky-500/10 border border-sky-500/20 rounded-lg">
+                    <div className="flex items-center gap-2 mb-2">
+                        <span className="flex-1 text-sm font-medium text-white">
+                            {update.name} <span className="text-xs text-slate-400">({update.current} → {update.version})</span>
+                        </span>
+                        <button onClick={skip} className="px-2 py-1 text-xs text-slate-400 hover:text-white">Skip</button>
+                        {canInstall ? (
+                            <button onClick={install} disabled={busy} className="px-3 py-1 bg-sky-600 hover:bg-sky-500 disabled:opacity-40 rounded-lg text-xs font-semibold text-white">
+                                {progress !== null ? `${progress}%` : 'Install'}
+                            </button>
+                        ) : (
+                            <button onClick={() => openUrl(update.url)} className="px-3 py-1 bg-sky-600 hover:bg-sky-500 rounded-lg text-xs font-semibold text-white">
+                                Download
+                            </button>
+                        )}
+                    </div>
+                    {update.notes && (
+                        <div className="max-h-40 overflow-y-auto custom-scrollbar text-xs text-slate-300 whitespace-pre-wrap">{update.notes}</div>
+                    )}
+                </div>
+            )}
+            {status && <p className="mt-2 text-[11px] text-slate-400">{status}</p>}
+        </div>
+    );
+}
@@ -0,0 +1,256 @@
+import { useState, useEffect, useRef } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { Users, X, Send, LogOut, Copy, AlertTriangle, Loader2 } from 'lucide-react';
+
+interface Participant {
+    name: string;
+    file: string | null;
+}
+
+interface WatchPartyInfo {
+    name: string;
+    room: string;
+    address: string;
+    hosting: boolean;
+    participants: Participant[];
+    latency_ms: number | null;
+}
+
+interface WatchPartySettings {
+    display_name: string;
+    port: number;
+    last_address: string;
+    last_room: string;
+}
+
+interface PlaybackState {
+    paused: boolean;
+    position: number;
+    at: number;
+    by: string;
+    file: string | null;
+}
+
+interface ChatLine {
+    from: string | null; // null for playback notices
+    text: string;
+    at: number;
+}
+
+interface WatchPartyProps {
+    isOpen: boolean;
+    onClose: () => void;
+}
+
+const formatTime = (secs: number) => {
+    const s = Math.floor(secs);
+    const h = Math.floor(s / 3600);
+    const m = Math.floor((s % 3600) / 60);
+    const pad = (n: number) => n.toString().padStart(2, '0');
+    return h > 0 ? `${h}:${pad(m)}:${pad(s % 60)}` : `${m}:${pad(s % 60)}`;
+};
+
+// Stays mounted while closed so chat and notices arriving in the background aren't lost
+export default function WatchParty({ isOpen, onClose }: WatchPartyProps) {
+    const [session, setSession] = useState<WatchPartyInfo | null>(null);
+    const [name, setName] = useState('');
+    const [room, setRoom] = useState('');
+    const [address, setAddress] = useState('');
+    const [passphrase, setPassphrase] = useState('');
+    const [busy, setBusy] = useState(false);
+    const [error, setError] = useState('');
+    const [lines, setLines] = useState<ChatLine[]>([]);
+    const [draft, setDraft] = useState('');
+    const logRef = useRef<HTMLDivElement>(null);
+
+    useEffect(() => {
+        invoke<WatchPartyInfo | null>('get_watch_party').then(setSession).catch(() => {});
+        invoke<WatchPartySettings>('get_watch_party_settings').then(s => {
+            setName(s.display_name);
+            setRoom(s.last_room);
+            setAddress(s.last_address);
+        }).catch(() => {});
+        const unlistenSession = listen<WatchPartyInfo | null>('watch-party', (event) => setSession(event.payload));
+        const unlistenChat = listen<ChatLine>('watch-party-chat', (event) => setLines(prev => [...prev, event.payload]));
+        const unlistenPlayback = listen<PlaybackState>('watch-party-playback', (event) => {
+            const p = event.payload;
+            const text = `${p.by} ${p.paused ? 'paused' : 'played'} at ${formatTime(p.position)}`;
+            setLines(prev => [...prev, { from: null, text, at: p.at }]);
+        });
+        return () => {
+            unlistenSession.then(f => f());
+            unlistenChat.then(f => f());
+            unlistenPlayback.then(f => f());
+        };
+    }, []);
+
+    useEffect(() => {
+        logRef.current?.scrollTo({ top: logRef.current.scrollHeight });
+    }, [lines, isOpen]);
+
+    if (!isOpen) return null;
+
+    const start = async (command: 'host_watch_party' | 'join_watch_party') => {
+        setBusy(true);
+        setError('');
+        try {
+            const info = await invoke<WatchPartyInfo>(command, { name, room, address, passphrase });
+            setSession(info);
+            setLines([]);
+        } catch (e) {
+            setError(String(e));
+        } finally {
+            setBusy(false);
+        }
+    };
+
+    const sendChat = async () => {
+        if (!draft.trim()) return;
+        try {
+            const line = await invoke<ChatLine>('send_watch_party_chat', { text: draft });
+            setLines(prev => [...prev, line]);
+            setDraft('');
+        } catch (e) {
+            setError(String(e));
+        }
+    };
+
+    const ownFile = session?.participants.find(p => p.name === session.name)?.file;
+
+    return (
+        <div className="fixed inset-0 z-[100] flex items-center justify-center bg-black/70 backdrop-blur-sm animate-fade-in" onClick={onClose}>
+            <div
+                className="w-full max-w-md mx-4 flex flex-col max-h-[80vh] bg-gradient-to-br from-slate-900 to-slate-950 border border-white/10 rounded-2xl shadow-2xl overflow-hidden animate-fade-in-up"
+                onClick={e => e.stopPropagation()}
+            >
+                {/* Header */}
+                <div className="flex items-center justify-between px-6 py-5 border-b border-white/10">
+                    <div className="flex items-center gap-3">
+                        <div className="p-2 bg-pink-500/20 rounded-lg">
+                            <Users size={22} className="text-pink-400" />
+                        </div>
+                        <div>
+                            <h2 className="text-xl font-bold text-white">Watch Party</h2>
+                            <p className="text-xs text-slate-500">
+                                {session ? `Room "${session.room}"${session.latency_ms !== null ? ` · ${session.latency_ms} ms` : ''}` : 'Play, pause and seek together'}
+                            </p>
+                        </div>
+                    </div>
+                    <div className="flex items-center gap-1">
+                        {session && (
+                            <button onClick={() => invoke('leave_watch_party')} title="Leave" className="p-2 text-slate-400 hover:text-red-400 hover:bg-white/10 rounded-lg transition-all">
+                                <LogOut size={16} />
+                            </button>
+                        )}
+                        <button onClick={onClose} className="p-2 text-slate-400 hover:text-white hover:bg-white/10 rounded-lg transition-all">
+                            <X size={20} />
+                        </button>
+                    </div>
+                </div>
+
+                {error && <p className="px-6 pt-3 text-[11px] text-red-400">{error}</p>}
+
+                {!session ? (
+                    <div className="p-6 space-y-3">
+                        <input
+                            value={name}
+                            onChange={(e) => setName(e.target.value)}
+                            placeholder="Your name"
+                            className="w-full px-3 py-2.5 bg-white/5 border border-white/10 rounded-xl text-sm text-white placeholder-slate-500 outline-none focus:border-pink-500/50 transition-colors"
+                        />
+                        <input
+                            value={room}
+                            onChange={(e) => setRoom(e.target.value)}
+                            placeholder="Room name"
+                            className="w-full px-3 py-2.5 bg-white/5 border border-white/10 rounded-xl text-sm text-white placeholder-slate-500 outline-none focus:border-pink-500/50 transition-colors"
+                        />
+                        <input
+                            type="password"
+                            value={passphrase}
+                            onChange={(e) => setPassphrase(e.target.value)}
+                            placeholder="Passphrase (optional)"
+                            className="w-full px-3 py-2.5 bg-white/5 border border-white/10 rounded-xl text-sm text-white placeholder-slate-500 outline-none focus:border-pink-500/50 transition-colors"
+                        />
+                        <button
+                            onClick={() => start('host_watch_party')}
+                            disabled={busy}
+                            className="w-full py-2.5 bg-pink-600 hover:bg-pink-500 disabled:bg-slate-700 disabled:text-slate-500 text-white text-sm font-semibold rounded-xl transition-colors flex items-center justify-center gap-2"
+                        >
+                            {busy && <Loader2 size={16} className="animate-spin" />} Host on this computer
+                        </button>
+                        <div className="flex items-center gap-3 text-[10px] text-slate-600 uppercase tracking-wider">
+                            <div className="flex-1 h-px bg-white/10" /> or join <div className="flex-1 h-px bg-white/10" />
+                        </div>
+                        <div className="flex gap-2">
+                            <input
+                                value={address}
+                                onChange={(e) => setAddress(e.target.value)}
+                                onKeyDown={(e) => e.key === 'Enter' && start('join_watch_party')}
+                                placeholder="Host address, e.g. 192.168.1.20:8999"
+                                className="flex-1 min-w-0 px-3 py-2.5 bg-white/5 border border-white/10 rounded-xl text-sm text-white placeholder-slate-500 outline-none focus:border-pink-500/50 transition-colors font-mono"
+                            />
+                            <button
+                                onClick={() => start('join_watch_party')}
+                                disabled={busy || !address.trim()}
+                                className="px-4 py-2.5 bg-white/10 hover:bg-white/15 disabled:opacity-40 text-white text-sm font-semibold rounded-xl transition-colors"
+                            >
+                                Join
+                            </button>
+                        </div>
+                        <p className="text-[10px] text-slate-500">Everyone plays their own copy of the file; only play, pause, seek and chat are shared.</p>
+                    </div>
+                ) : (
+                    <>
+                        {session.hosting && (
+                            <div className="mx-6 mt-4 flex items-center justify-between px-3 py-2 bg-white/5 rounded-xl">
+                                <span className="text-xs text-slate-400">Friends join at <span className="font-mono text-white">{session.address}</span></span>
+                                <button onClick={() => navigator.clipboard.writeText(session.address)} title="Copy" className="p-1 text-slate-400 hover:text-white">
+                                    <Copy size={14} />
+                                </button>
+                            </div>
+                        )}
+
+                        {/* Participants */}
+                        <div className="px-6 pt-4 space-y-1">
+                            {session.participants.map((p, i) => {
+                                const mismatch = ownFile && p.file && p.file !== ownFile;
+                                return (
+                                    <div key={`${p.name}-${i}`} className="flex items-center gap-2 text-sm">
+                                        <span className="w-1.5 h-1.5 rounded-full bg-emerald-400" />
+                                        <span className="text-white">{p.name}{p.name === session.name ? ' (you)' : ''}</span>
+                                        <span className="text-[11px] text-slate-500 truncate">{p.file ?? 'nothing loaded'}</span>
+                                        {mismatch && <span title="Playing a different file"><AlertTriangle size={12} className="text-amber-400 shrink-0" /></span>}
+                                    </div>
+                                );
+                            })}
+                        </div>
+
+                        {/* Chat */}
+                        <div ref={logRef} className="flex-1 min-h-[10rem] overflow-y-auto custom-scrollbar px-6 py-4 space-y-1.5">
+                            {lines.map((line, i) => line.from === null ? (
+                                <p key={i} className="text-[11px] text-slate-500 italic">{line.text}</p>
+                            ) : (
+                                <p key={i} className="text-sm text-slate-300 break-words">
+                                    <span className={`font-semibold ${line.from === session.name ? 'text-pink-300' : 'text-white'}`}>{line.from}</span> {line.text}
+                                </p>
+                            ))}
+                        </div>
+                        <div className="flex gap-2 px-6 pb-5">
+                            <input
+                                value={draft}
+                                onChange={(e) => setDraft(e.target.value)}
+                                onKeyDown={(e) => e.key === 'Enter' && sendChat()}
+                                placeholder="Say something"
+                                className="flex-1 min-w-0 px-3 py-2.5 bg-white/5 border border-white/10 rounded-xl text-sm text-white placeholder-slate-500 outline-none focus:border-pink-500/50 transition-colors"
+                            />
+                            <button onClick={sendChat} disabled={!draft.trim()} className="px-3 py-2.5 bg-pink-600 hover:bg-pink-500 disabled:bg-slate-700 disabled:text-slate-500 text-white rounded-xl transition-colors">
+                                <Send size={16} />
+                            </button>
+                        </div>
+                    </>
+                )}
+            </div>
+        </div>
+    );
+}
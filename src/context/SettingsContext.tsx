@@ -1,4 +1,5 @@
-import { createContext, useContext, useState, ReactNode } from 'react';
+import { createContext, useContext, useEffect, useState, ReactNode } from 'react';
+import { invoke } from '@tauri-apps/api/core';
 import { AppSettings, defaultSettings } from '../types/settings';
 
 interface SettingsContextType {
@@ -31,6 +32,25 @@ export function SettingsProvider({ children }: { children: ReactNode }) {
         });
     };
 
+    // Mirror the subtitle provider keys to the backend, which uses them for hash lookups and auto-fetching
+    useEffect(() => {
+        invoke<Record<string, string | null>>('get_provider_credentials')
+            .then(credentials => invoke('save_provider_credentials', {
+                credentials: {
+                    ...credentials,
+                    opensubtitles_api_key: settings.openSubtitlesApiKey || null,
+                    jimaku_api_key: settings.jimakuApiKey || null,
+                },
+            }))
+            .catch(e => console.debug('Failed to sync subtitle provider keys', e));
+    }, [settings.openSubtitlesApiKey, settings.jimakuApiKey]);
+
+    // Presence reconnects in the backend on its own; this just follows the toggle (and restores it on launch)
+    useEffect(() => {
+        invoke(settings.discordRPC ? 'discord_rpc_connect' : 'discord_rpc_disconnect')
+            .catch(e => console.debug('Failed to toggle Discord presence', e));
+    }, [settings.discordRPC]);
+
     const resetSettings = () => {
         setSettings(defaultSettings);
         localStorage.setItem('framex-settings', JSON.stringify(defaultSettings));
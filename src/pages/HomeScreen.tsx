@@ -1,4 +1,4 @@
-import { FolderOpen, Library, Film, MonitorPlay, Sparkles, Globe } from 'lucide-react';
+import { FolderOpen, Library, Film, MonitorPlay, Sparkles, Globe, Server, Tv, Radio, Mic, Repeat } from 'lucide-react';
 import { useRef, useState } from 'react';
 import { useTheme } from '../context/ThemeContext';
 
@@ -48,9 +48,14 @@ interface HomeScreenProps {
     onOpenFile: () => void;
     onOpenFolder: () => void;
     onOpenStream?: () => void;
+    onOpenNetwork?: () => void;
+    onOpenLiveTv?: () => void;
+    onOpenRadio?: () => void;
+    onOpenPodcasts?: () => void;
+    onOpenConvert?: () => void;
 }
 
-export default function HomeScreen({ onOpenFile, onOpenFolder, onOpenStream }: HomeScreenProps) {
+export default function HomeScreen({ onOpenFile, onOpenFolder, onOpenStream, onOpenNetwork, onOpenLiveTv, onOpenRadio, onOpenPodcasts, onOpenConvert }: HomeScreenProps) {
     const { theme } = useTheme();
     return (
         <div className="h-full overflow-y-auto">
@@ -121,28 +126,140 @@ export default function HomeScreen({ onOpenFile, onOpenFolder, onOpenStream }: H
                         </div>
 
                         {/* Stream CTA */}
-                        {onOpenStream && (
-                            <div className="pt-3 flex justify-center animate-fade-in-up delay-300">
-                                <button
-                                    onClick={onOpenStream}
-                                    className="px-6 py-3 backdrop-blur-xl rounded-xl font-medium text-white text-sm transition-all hover:scale-105 active:scale-95 flex items-center gap-2 shadow-lg"
-                                    style={{
-                                        backgroundColor: `${theme.colors.cardBg}60`,
-                                        borderWidth: '1px',
-                                        borderStyle: 'solid',
-                                        borderColor: `${theme.colors.border}80`,
-                                    }}
-                                    onMouseEnter={(e) => {
-                                        e.currentTarget.style.borderColor = theme.colors.borderHover;
-                                        e.currentTarget.style.backgroundColor = `${theme.colors.cardBg}cc`;
-                                    }}
-                                    onMouseLeave={(e) => {
-                                        e.currentTarget.style.borderColor = `${theme.colors.border}80`;
-                                        e.currentTarget.style.backgroundColor = `${theme.colors.cardBg}60`;
-                                    }}
-                                >
-                                    <Globe size={18} /> Open Stream
-                                </button>
+                        {(onOpenStream || onOpenNetwork || onOpenLiveTv || onOpenRadio || onOpenPodcasts || onOpenConvert) && (
+                            <div className="pt-3 flex flex-wrap justify-center gap-3 animate-fade-in-up delay-300">
+                                {onOpenStream && (
+                                    <button
+                                        onClick={onOpenStream}
+                                        className="px-6 py-3 backdrop-blur-xl rounded-xl font-medium text-white text-sm transition-all hover:scale-105 active:scale-95 flex items-center gap-2 shadow-lg"
+                                        style={{
+                                            backgroundColor: `${theme.colors.cardBg}60`,
+                                            borderWidth: '1px',
+                                            borderStyle: 'solid',
+                                            borderColor: `${theme.colors.border}80`,
+                                        }}
+                                        onMouseEnter={(e) => {
+                                            e.currentTarget.style.borderColor = theme.colors.borderHover;
+                                            e.currentTarget.style.backgroundColor = `${theme.colors.cardBg}cc`;
+                                        }}
+                                        onMouseLeave={(e) => {
+                                            e.currentTarget.style.borderColor = `${theme.colors.border}80`;
+                                            e.currentTarget.style.backgroundColor = `${theme.colors.cardBg}60`;
+                                        }}
+                                    >
+                                        <Globe size={18} /> Open Stream
+                                    </button>
+                                )}
+                                {onOpenNetwork && (
+                                    <button
+                                        onClick={onOpenNetwork}
+                                        className="px-6 py-3 backdrop-blur-xl rounded-xl font-medium text-white text-sm transition-all hover:scale-105 active:scale-95 flex items-center gap-2 shadow-lg"
+                                        style={{
+                                            backgroundColor: `${theme.colors.cardBg}60`,
+                                            borderWidth: '1px',
+                                            borderStyle: 'solid',
+                                            borderColor: `${theme.colors.border}80`,
+                                        }}
+                                        onMouseEnter={(e) => {
+                                            e.currentTarget.style.borderColor = theme.colors.borderHover;
+                                            e.currentTarget.style.backgroundColor = `${theme.colors.cardBg}cc`;
+                                        }}
+                                        onMouseLeave={(e) => {
+                                            e.currentTarget.style.borderColor = `${theme.colors.border}80`;
+                                            e.currentTarget.style.backgroundColor = `${theme.colors.cardBg}60`;
+                                        }}
+                                    >
+                                        <Server size={18} /> Network Media
+                                    </button>
+                                )}
+                                {onOpenLiveTv && (
+                                    <button
+                                        onClick={onOpenLiveTv}
+                                        className="px-6 py-3 backdrop-blur-xl rounded-xl font-medium text-white text-sm transition-all hover:scale-105 active:scale-95 flex items-center gap-2 shadow-lg"
+                                        style={{
+                                            backgroundColor: `${theme.colors.cardBg}60`,
+                                            borderWidth: '1px',
+                                            borderStyle: 'solid',
+                                            borderColor: `${theme.colors.border}80`,
+                                        }}
+                                        onMouseEnter={(e) => {
+                                            e.currentTarget.style.borderColor = theme.colors.borderHover;
+                                            e.currentTarget.style.backgroundColor = `${theme.colors.cardBg}cc`;
+                                        }}
+                                        onMouseLeave={(e) => {
+                                            e.currentTarget.style.borderColor = `${theme.colors.border}80`;
+                                            e.currentTarget.style.backgroundColor = `${theme.colors.cardBg}60`;
+                                        }}
+                                    >
+                                        <Tv size={18} /> Live TV
+                                    </button>
+                                )}
+                                {onOpenRadio && (
+                                    <button
+                                        onClick={onOpenRadio}
+                                        className="px-6 py-3 backdrop-blur-xl rounded-xl font-medium text-white text-sm transition-all hover:scale-105 active:scale-95 flex items-center gap-2 shadow-lg"
+                                        style={{
+                                            backgroundColor: `${theme.colors.cardBg}60`,
+                                            borderWidth: '1px',
+                                            borderStyle: 'solid',
+                                            borderColor: `${theme.colors.border}80`,
+                                        }}
+                                        onMouseEnter={(e) => {
+                                            e.currentTarget.style.borderColor = theme.colors.borderHover;
+                                            e.currentTarget.style.backgroundColor = `${theme.colors.cardBg}cc`;
+                                        }}
+                                        onMouseLeave={(e) => {
+                                            e.currentTarget.style.borderColor = `${theme.colors.border}80`;
+                                            e.currentTarget.style.backgroundColor = `${theme.colors.cardBg}60`;
+                                        }}
+                                    >
+                                        <Radio size={18} /> Radio
+                                    </button>
+                                )}
+                                {onOpenPodcasts && (
+                                    <button
+                                        onClick={onOpenPodcasts}
+                                        className="px-6 py-3 backdrop-blur-xl rounded-xl font-medium text-white text-sm transition-all hover:scale-105 active:scale-95 flex items-center gap-2 shadow-lg"
+                                        style={{
+                                            backgroundColor: `${theme.colors.cardBg}60`,
+                                            borderWidth: '1px',
+                                            borderStyle: 'solid',
+                                            borderColor: `${theme.colors.border}80`,
+                                        }}
+                                        onMouseEnter={(e) => {
+                                            e.currentTarget.style.borderColor = theme.colors.borderHover;
+                                            e.currentTarget.style.backgroundColor = `${theme.colors.cardBg}cc`;
+                                        }}
+                                        onMouseLeave={(e) => {
+                                            e.currentTarget.style.borderColor = `${theme.colors.border}80`;
+                                            e.currentTarget.style.backgroundColor = `${theme.colors.cardBg}60`;
+                                        }}
+                                    >
+                                        <Mic size={18} /> Podcasts
+                                    </button>
+                                )}
+                                {onOpenConvert && (
+                                    <button
+                                        onClick={onOpenConvert}
+                                        className="px-6 py-3 backdrop-blur-xl rounded-xl font-medium text-white text-sm transition-all hover:scale-105 active:scale-95 flex items-center gap-2 shadow-lg"
+                                        style={{
+                                            backgroundColor: `${theme.colors.cardBg}60`,
+                                            borderWidth: '1px',
+                                            borderStyle: 'solid',
+                                            borderColor: `${theme.colors.border}80`,
+                                        }}
+                                        onMouseEnter={(e) => {
+                                            e.currentTarget.style.borderColor = theme.colors.borderHover;
+                                            e.currentTarget.style.backgroundColor = `${theme.colors.cardBg}cc`;
+                                        }}
+                                        onMouseLeave={(e) => {
+                                            e.currentTarget.style.borderColor = `${theme.colors.border}80`;
+                                            e.currentTarget.style.backgroundColor = `${theme.colors.cardBg}60`;
+                                        }}
+                                    >
+                                        <Repeat size={18} /> Convert
+                                    </button>
+                                )}
                             </div>
                         )}
                     </div>
@@ -4,6 +4,49 @@ export interface Playlist {
     items: string[];
     created: number;
     modified: number;
+    source?: FolderSource | null;
+    rules?: SmartRules | null;
+}
+
+export interface SmartRules {
+    tags?: string[];
+    any_tags?: string[];
+    favorite?: boolean | null;
+    min_rating?: number | null;
+    sort?: 'path' | 'rating' | null;
+}
+
+export interface VideoTags {
+    tags: string[];
+    favorite: boolean;
+}
+
+export interface PlaylistEntryInfo {
+    path: string;
+    exists: boolean;
+    duration: number;
+    poster_path?: string | null;
+    watched: boolean;
+    position?: number | null;
+}
+
+export interface PlaylistTotals {
+    item_count: number;
+    missing: number;
+    watched: number;
+    total_runtime: number;
+    remaining_seconds: number;
+}
+
+export interface PlaylistListing extends Playlist {
+    entries?: PlaylistEntryInfo[];
+    totals?: PlaylistTotals;
+}
+
+export interface FolderSource {
+    folder: string;
+    pattern?: string | null;
+    recursive: boolean;
 }
 
 export interface Collection {
@@ -12,6 +55,18 @@ export interface Collection {
     description: string;
     items: string[];
     poster_path?: string;
+    tmdb_collection_id?: number;
+    modified?: number;
+    children?: string[];
+    sort_order?: 'manual' | 'name' | 'year' | null;
+}
+
+export interface CollectionDetails extends Collection {
+    nested: CollectionDetails[];
+    all_items: string[];
+    item_count: number;
+    total_runtime: number;
+    total_size: number;
 }
 
 export interface SubtitleResult {
@@ -22,4 +77,35 @@ export interface SubtitleResult {
     hearing_impaired: boolean;
     file_id: number;
     release: string;
+    hash_match?: boolean;
+    provider?: string; // 'opensubtitles' | 'jimaku'
+    download_url?: string | null;
+    match_confidence?: number; // 0-1, results come sorted by it
+}
+
+// Typed failure from download_subtitle / auto_fetch_subtitle / the OpenSubtitles account commands
+export type SubtitleError =
+    | { kind: 'quota_exceeded'; message: string; remaining: number; reset_time: string | null; logged_in: boolean }
+    | { kind: 'unauthorized'; message: string }
+    | { kind: 'other'; message: string };
+
+export interface SubtitleQuota {
+    logged_in: boolean;
+    username: string | null;
+    level: string | null;
+    vip: boolean;
+    allowed_downloads: number | null;
+    remaining_downloads: number | null;
+    reset_time: string | null;
+}
+
+export function describeSubtitleError(e: unknown): string {
+    if (typeof e !== 'object' || e === null || !('kind' in e)) return String(e);
+    const error = e as SubtitleError;
+    if (error.kind === 'quota_exceeded') {
+        const reset = error.reset_time ? ` It resets in ${error.reset_time}.` : '';
+        const hint = error.logged_in ? '' : ' Log in to OpenSubtitles in Settings → Integrations for a higher limit.';
+        return `Daily OpenSubtitles download limit reached.${reset}${hint}`;
+    }
+    return error.message;
 }
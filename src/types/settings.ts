@@ -55,6 +55,7 @@ export interface AppSettings {
     // Integrations
     discordRPC: boolean;
     openSubtitlesApiKey: string;
+    jimakuApiKey: string;
     defaultSubtitleLanguage: string;
 
     // Video Image Controls (defaults)
@@ -85,6 +86,7 @@ export const defaultSettings: AppSettings = {
     defaultLibraryView: 'grid',
     discordRPC: false,
     openSubtitlesApiKey: '',
+    jimakuApiKey: '',
     defaultSubtitleLanguage: 'en',
     videoBrightness: 0,
     videoContrast: 0,